@@ -0,0 +1,218 @@
+//! Animate a dessin, and export it as an animated GIF.
+//!
+//! An animation is a sequence of frames, either given one by one, or generated from a function of the time.
+//! Every frame is rasterized with [dessin-image](https://docs.rs/dessin-image/), on a canvas big enough for all frames.
+//!
+//! ```no_run
+//! # use dessin::prelude::*;
+//! use dessin_animate::{to_gif_from_fn, GifOptions};
+//! use std::f32::consts::TAU;
+//!
+//! // A dot going around in 2 seconds
+//! let gif = to_gif_from_fn(
+//! 	|t| {
+//! 		dessin2!([
+//! 			Circle!(stroke = (Color::BLACK, 1.), radius = 40.),
+//! 			Circle!(
+//! 				fill = Color::RED,
+//! 				radius = 5.,
+//! 				translate = [40. * (t * TAU / 2.).cos(), 40. * (t * TAU / 2.).sin()],
+//! 			),
+//! 		])
+//! 	},
+//! 	2.,
+//! 	&GifOptions {
+//! 		frame_rate: 25.,
+//! 		..Default::default()
+//! 	},
+//! )
+//! .unwrap();
+//!
+//! std::fs::write("dot.gif", gif).unwrap();
+//! ```
+
+#![allow(clippy::tabs_in_doc_comments)]
+
+use ::image::{
+    codecs::gif::{GifEncoder, Repeat},
+    Delay, Frame,
+};
+use dessin::prelude::*;
+use dessin_image::{ImageError, ToImage};
+use nalgebra::{Point2, Scale2};
+use std::{fmt, io::Write, time::Duration};
+
+#[derive(Debug)]
+pub enum AnimateError {
+    ImageError(ImageError),
+    EncodingError(::image::ImageError),
+    NoFrame,
+    /// Frame rates must be positive and finite
+    InvalidFrameRate(f32),
+}
+impl fmt::Display for AnimateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+impl From<ImageError> for AnimateError {
+    fn from(value: ImageError) -> Self {
+        AnimateError::ImageError(value)
+    }
+}
+impl From<::image::ImageError> for AnimateError {
+    fn from(value: ::image::ImageError) -> Self {
+        AnimateError::EncodingError(value)
+    }
+}
+impl std::error::Error for AnimateError {}
+
+#[derive(Debug, Clone)]
+pub struct GifOptions {
+    /// Frames per second
+    pub frame_rate: f32,
+    /// Number of times the animation is played, forever if `None`
+    pub repeat: Option<u16>,
+    /// Pixels in one unit of the dessin
+    pub scale: f32,
+    /// Color behind every frame, transparent if `None`
+    pub background: Option<Color>,
+}
+impl Default for GifOptions {
+    fn default() -> Self {
+        GifOptions {
+            frame_rate: 25.,
+            repeat: None,
+            scale: 1.,
+            background: None,
+        }
+    }
+}
+
+/// Error if `frame_rate` is not a positive and finite number of frames per second
+fn check_frame_rate(frame_rate: f32) -> Result<(), AnimateError> {
+    if frame_rate.is_finite() && frame_rate > 0. {
+        Ok(())
+    } else {
+        Err(AnimateError::InvalidFrameRate(frame_rate))
+    }
+}
+
+/// Encode frames as an animated GIF, in a writer
+pub fn write_gif<W: Write, I: IntoIterator<Item = Shape>>(
+    writer: W,
+    frames: I,
+    options: &GifOptions,
+) -> Result<(), AnimateError> {
+    check_frame_rate(options.frame_rate)?;
+
+    let frames = frames
+        .into_iter()
+        .map(|frame| frame.with_resize(Scale2::new(options.scale, options.scale)))
+        .collect::<Vec<_>>();
+
+    // Every frame covers the same area, so they are rasterized at the same size and position
+    let area = frames
+        .iter()
+        .map(|frame| frame.local_bounding_box().straigthen())
+        .reduce(BoundingBox::join)
+        .ok_or(AnimateError::NoFrame)?;
+    let canvas = match options.background {
+        Some(color) => dessin2!(
+            Rectangle!(
+                fill = color,
+                width = area.width(),
+                height = area.height(),
+                translate = area.center() - Point2::origin(),
+            ) > ()
+        ),
+        None => dessin2!(
+            Rectangle(
+                width = area.width(),
+                height = area.height(),
+                translate = area.center() - Point2::origin(),
+            ) > ()
+        ),
+    };
+
+    let delay = Delay::from_saturating_duration(Duration::from_secs_f32(1. / options.frame_rate));
+
+    let mut encoder = GifEncoder::new(writer);
+    encoder.set_repeat(match options.repeat {
+        Some(count) => Repeat::Finite(count),
+        None => Repeat::Infinite,
+    })?;
+
+    for frame in frames {
+        let image = dessin2!([{ canvas.clone() }, { frame }]).rasterize()?;
+        encoder.encode_frame(Frame::from_parts(image.to_rgba8(), 0, 0, delay))?;
+    }
+
+    Ok(())
+}
+
+/// Encode frames as an animated GIF.
+///
+/// ```
+/// # use dessin::prelude::*;
+/// use dessin_animate::{to_gif, AnimateError, GifOptions};
+/// use image::{codecs::gif::GifDecoder, AnimationDecoder};
+///
+/// let frames = || (0..3).map(|idx| dessin2!(Circle!(fill = Color::RED, radius = 5. + idx as f32) > ()));
+///
+/// let options = GifOptions {
+/// 	frame_rate: 10.,
+/// 	..Default::default()
+/// };
+/// let gif = to_gif(frames(), &options).unwrap();
+///
+/// let decoded = GifDecoder::new(gif.as_slice())
+/// 	.unwrap()
+/// 	.into_frames()
+/// 	.collect_frames()
+/// 	.unwrap();
+/// assert_eq!(decoded.len(), 3);
+/// assert_eq!(decoded[0].delay().numer_denom_ms(), (100, 1));
+///
+/// let options = GifOptions {
+/// 	frame_rate: 0.,
+/// 	..Default::default()
+/// };
+/// assert!(matches!(
+/// 	to_gif(frames(), &options),
+/// 	Err(AnimateError::InvalidFrameRate(_))
+/// ));
+/// ```
+pub fn to_gif<I: IntoIterator<Item = Shape>>(
+    frames: I,
+    options: &GifOptions,
+) -> Result<Vec<u8>, AnimateError> {
+    let mut gif = vec![];
+    write_gif(&mut gif, frames, options)?;
+
+    Ok(gif)
+}
+
+/// Frames of an animation lasting `duration` seconds, given the time in seconds of each frame
+pub fn frames_from_fn<F: Fn(f32) -> Shape>(
+    animation: F,
+    duration: f32,
+    frame_rate: f32,
+) -> Result<impl Iterator<Item = Shape>, AnimateError> {
+    check_frame_rate(frame_rate)?;
+
+    let count = (duration * frame_rate).ceil().max(1.) as u32;
+    Ok((0..count).map(move |idx| animation(idx as f32 / frame_rate)))
+}
+
+/// Encode an animation lasting `duration` seconds as an animated GIF, given the time in seconds of each frame
+pub fn to_gif_from_fn<F: Fn(f32) -> Shape>(
+    animation: F,
+    duration: f32,
+    options: &GifOptions,
+) -> Result<Vec<u8>, AnimateError> {
+    to_gif(
+        frames_from_fn(animation, duration, options.frame_rate)?,
+        options,
+    )
+}
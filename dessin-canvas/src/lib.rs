@@ -0,0 +1,577 @@
+//! Draw a dessin directly on an HTML canvas, from a WASM application.
+//!
+//! Images are uploaded to the browser as [`ImageBitmap`] and fonts are registered as [`FontFace`] before drawing.
+//! Both are asynchronous in the browser, hence [`draw`] being an `async fn`.
+//!
+//! ```no_run
+//! # use dessin::prelude::*;
+//! # use wasm_bindgen::JsCast;
+//! # async fn run() -> Result<(), dessin_canvas::CanvasError> {
+//! let canvas = web_sys::window()
+//! 	.unwrap()
+//! 	.document()
+//! 	.unwrap()
+//! 	.get_element_by_id("canvas")
+//! 	.unwrap()
+//! 	.dyn_into::<web_sys::HtmlCanvasElement>()
+//! 	.unwrap();
+//! let context = canvas
+//! 	.get_context("2d")
+//! 	.unwrap()
+//! 	.unwrap()
+//! 	.dyn_into::<web_sys::CanvasRenderingContext2d>()
+//! 	.unwrap();
+//!
+//! let dessin = dessin2!(Circle!(fill = Color::RED, radius = 10.) > ());
+//!
+//! dessin_canvas::draw(&dessin, &context).await?;
+//! # Ok(())
+//! # }
+//! ```
+
+#![allow(clippy::tabs_in_doc_comments)]
+
+use dessin::{
+    export::{hint, inherited_style, Export, Exporter, SKIP_HINT},
+    font::FontRef,
+    prelude::*,
+};
+use nalgebra::{Scale2, Transform2, Translation2};
+use std::{cell::RefCell, collections::HashSet, f64::consts::PI, fmt};
+use wasm_bindgen::{Clamped, JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{CanvasRenderingContext2d, CanvasWindingRule, FontFace, ImageBitmap, ImageData};
+
+#[derive(Debug)]
+pub enum CanvasError {
+    JsError(JsValue),
+    NoWindow,
+    NoCanvas,
+    CurveHasNoStartingPoint(CurvePosition),
+    MissingImageBitmap,
+}
+impl fmt::Display for CanvasError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+impl From<JsValue> for CanvasError {
+    fn from(value: JsValue) -> Self {
+        CanvasError::JsError(value)
+    }
+}
+impl std::error::Error for CanvasError {}
+
+#[derive(Default, Clone, Copy, PartialEq)]
+pub enum ViewPort {
+    /// Show the area centered around (0, 0), with size (width, height)
+    ManualCentered { width: f32, height: f32 },
+    /// Show the area centered around (x, y), with size (width, height)
+    ManualViewport {
+        x: f32,
+        y: f32,
+        width: f32,
+        height: f32,
+    },
+    /// Show an area centered around (0, 0), with auto size that include all [Shapes][`dessin::prelude::Shape`]
+    AutoCentered,
+    #[default]
+    /// Show an area centered around the center of the shapes, with auto size that include all [Shapes][`dessin::prelude::Shape`]
+    AutoBoundingBox,
+}
+
+#[derive(Clone)]
+pub struct CanvasOptions {
+    /// Area of the dessin to show, scaled to fit the canvas while keeping its aspect ratio
+    pub viewport: ViewPort,
+    /// Clear the canvas before drawing
+    pub clear: bool,
+}
+impl Default for CanvasOptions {
+    fn default() -> Self {
+        CanvasOptions {
+            viewport: ViewPort::default(),
+            clear: true,
+        }
+    }
+}
+
+/// First pass over the dessin, collecting what needs to be uploaded to the browser before drawing
+#[derive(Default)]
+struct ResourceCollector {
+    images: Vec<(u32, u32, Vec<u8>)>,
+    fonts: HashSet<(FontRef, FontWeight)>,
+    /// Whether the path of a [`Clip`] is being traced
+    in_clip: bool,
+}
+impl Exporter for ResourceCollector {
+    type Error = CanvasError;
+    const CAN_EXPORT_OPACITY: bool = true;
+    const CAN_EXPORT_BLEND_MODE: bool = true;
+    const BACKEND: Option<&'static str> = Some("canvas");
+
+    /// The clipping shape is drawn first, as by [`CanvasExporter`], so images come in the same order
+    fn export_subtree(
+        &mut self,
+        shape: &Shape,
+        parent_transform: &Transform2<f32>,
+    ) -> Result<bool, Self::Error> {
+        let Shape::Group(group) = shape else {
+            return Ok(false);
+        };
+        if hint(&group.metadata, "canvas", SKIP_HINT) == Some("true") {
+            return Ok(false);
+        }
+        let Some((clipped, clipped_by)) = Clip::parts(group).filter(|_| !self.in_clip) else {
+            return Ok(false);
+        };
+        let transform = parent_transform * group.local_transform;
+
+        self.in_clip = true;
+        let written = clipped_by.write_into_exporter(self, &transform);
+        self.in_clip = false;
+        written?;
+        clipped.write_into_exporter(self, &transform)?;
+
+        Ok(true)
+    }
+
+    fn start_style(&mut self, _style: StylePosition) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    fn end_style(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    fn export_image(&mut self, image: ImagePosition) -> Result<(), Self::Error> {
+        let rgba = image.image.to_rgba8();
+        self.images
+            .push((rgba.width(), rgba.height(), rgba.into_raw()));
+        Ok(())
+    }
+
+    fn export_curve(&mut self, _curve: CurvePosition) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    fn export_text(&mut self, text: TextPosition) -> Result<(), Self::Error> {
+        let font = text.font.clone().unwrap_or_default();
+        self.fonts.insert((font, text.font_weight));
+        Ok(())
+    }
+}
+
+pub struct CanvasExporter<'a> {
+    context: &'a CanvasRenderingContext2d,
+    style: Vec<StylePosition>,
+    /// Bitmaps of the images, in the order they are exported
+    bitmaps: std::vec::IntoIter<ImageBitmap>,
+    /// Whether the path of a [`Clip`] is being traced, without filling nor stroking it
+    in_clip: bool,
+}
+
+impl<'a> CanvasExporter<'a> {
+    fn new(context: &'a CanvasRenderingContext2d, bitmaps: Vec<ImageBitmap>) -> Self {
+        CanvasExporter {
+            context,
+            style: vec![],
+            bitmaps: bitmaps.into_iter(),
+            in_clip: false,
+        }
+    }
+
+    /// Set `fill` as the fill style, gradients being drawn by the canvas itself
+    fn set_fill_style(&self, fill: &Fill) -> Result<(), CanvasError> {
+        let (gradient, stops) = match fill {
+            Fill::LinearGradient { start, end, stops } => (
+                self.context.create_linear_gradient(
+                    start.x as f64,
+                    start.y as f64,
+                    end.x as f64,
+                    end.y as f64,
+                ),
+                stops,
+            ),
+            Fill::RadialGradient {
+                center,
+                radius,
+                stops,
+            } => (
+                self.context.create_radial_gradient(
+                    center.x as f64,
+                    center.y as f64,
+                    0.,
+                    center.x as f64,
+                    center.y as f64,
+                    *radius as f64,
+                )?,
+                stops,
+            ),
+            fill => {
+                self.context.set_fill_style_str(&fill.color().to_string());
+                return Ok(());
+            }
+        };
+
+        for (offset, color) in stops.as_slice() {
+            gradient.add_color_stop(*offset, &color.to_string())?;
+        }
+        self.context.set_fill_style_canvas_gradient(&gradient);
+
+        Ok(())
+    }
+
+    /// Fill, with `fill_rule`, and stroke the current path
+    fn paint(&self, fill_rule: FillRule) -> Result<(), CanvasError> {
+        let style = inherited_style(&self.style);
+
+        if let Some(fill) = &style.fill {
+            self.set_fill_style(fill)?;
+            match fill_rule {
+                FillRule::NonZero => self.context.fill(),
+                FillRule::EvenOdd => self
+                    .context
+                    .fill_with_canvas_winding_rule(CanvasWindingRule::Evenodd),
+            }
+        }
+
+        let Some(stroke) = style.stroke else {
+            return Ok(());
+        };
+        let (color, width) = (stroke.color(), stroke.width());
+        let (dash, dash_offset) = match stroke.dashes() {
+            Some((dashes, offset)) => (dashes.as_slice().to_vec(), offset),
+            None => (vec![], 0.),
+        };
+
+        let dash = dash
+            .into_iter()
+            .map(|v| JsValue::from_f64(v as f64))
+            .collect::<js_sys::Array>();
+
+        self.context.set_stroke_style_str(&color.to_string());
+        self.context.set_line_width(width as f64);
+        self.context.set_line_cap(match stroke.cap() {
+            LineCap::Butt => "butt",
+            LineCap::Round => "round",
+            LineCap::Square => "square",
+        });
+        self.context.set_line_join(match stroke.join() {
+            LineJoin::Miter => "miter",
+            LineJoin::Round => "round",
+            LineJoin::Bevel => "bevel",
+        });
+        self.context.set_miter_limit(stroke.miter_limit() as f64);
+        self.context.set_line_dash(&dash)?;
+        self.context.set_line_dash_offset(dash_offset as f64);
+        self.context.stroke();
+
+        Ok(())
+    }
+
+    fn trace_curve(&self, curve: &CurvePosition) -> Result<(), CanvasError> {
+        let mut has_start = false;
+
+        for keypoint in &curve.keypoints {
+            match keypoint {
+                KeypointPosition::Point(p) if has_start => {
+                    self.context.line_to(p.x as f64, p.y as f64)
+                }
+                KeypointPosition::Point(p) => self.context.move_to(p.x as f64, p.y as f64),
+                KeypointPosition::Bezier(b) => {
+                    match (has_start, b.start) {
+                        (false, None) => {
+                            return Err(CanvasError::CurveHasNoStartingPoint(curve.clone()))
+                        }
+                        (false, Some(s)) => self.context.move_to(s.x as f64, s.y as f64),
+                        (true, Some(s)) => self.context.line_to(s.x as f64, s.y as f64),
+                        (true, None) => {}
+                    }
+
+                    self.context.bezier_curve_to(
+                        b.start_control.x as f64,
+                        b.start_control.y as f64,
+                        b.end_control.x as f64,
+                        b.end_control.y as f64,
+                        b.end.x as f64,
+                        b.end.y as f64,
+                    );
+                }
+            }
+
+            has_start = true;
+        }
+
+        if curve.closed {
+            self.context.close_path();
+        }
+
+        Ok(())
+    }
+}
+
+impl Exporter for CanvasExporter<'_> {
+    type Error = CanvasError;
+    const CAN_EXPORT_ELLIPSE: bool = true;
+    const CAN_EXPORT_OPACITY: bool = true;
+    const CAN_EXPORT_BLEND_MODE: bool = true;
+    const BACKEND: Option<&'static str> = Some("canvas");
+
+    /// [Clips][Clip] are drawn inside the path of the clipping shape,
+    /// so pattern and image fills are cut to the shape they fill
+    fn export_subtree(
+        &mut self,
+        shape: &Shape,
+        parent_transform: &Transform2<f32>,
+    ) -> Result<bool, Self::Error> {
+        let Shape::Group(group) = shape else {
+            return Ok(false);
+        };
+        if hint(&group.metadata, "canvas", SKIP_HINT) == Some("true") {
+            return Ok(false);
+        }
+        let Some((clipped, clipped_by)) = Clip::parts(group).filter(|_| !self.in_clip) else {
+            return Ok(false);
+        };
+        let transform = parent_transform * group.local_transform;
+
+        self.context.save();
+        self.context.begin_path();
+        self.in_clip = true;
+        let written = clipped_by.write_into_exporter(self, &transform);
+        self.in_clip = false;
+        written?;
+
+        self.context.clip();
+        let written = clipped.write_into_exporter(self, &transform);
+        self.context.restore();
+        written?;
+
+        Ok(true)
+    }
+
+    /// The opacity and blend mode of a style are set as `globalAlpha` and `globalCompositeOperation` of the context,
+    /// so each shape under it is drawn with them
+    fn start_style(&mut self, style: StylePosition) -> Result<(), Self::Error> {
+        self.context.save();
+        if style.opacity != 1. {
+            self.context
+                .set_global_alpha(self.context.global_alpha() * style.opacity as f64);
+        }
+        if style.blend_mode != BlendMode::Normal {
+            self.context
+                .set_global_composite_operation(style.blend_mode.css_name())?;
+        }
+
+        self.style.push(style);
+        Ok(())
+    }
+
+    fn end_style(&mut self) -> Result<(), Self::Error> {
+        self.style.pop();
+        self.context.restore();
+        Ok(())
+    }
+
+    fn export_image(
+        &mut self,
+        ImagePosition {
+            center,
+            width,
+            height,
+            rotation,
+            ..
+        }: ImagePosition,
+    ) -> Result<(), Self::Error> {
+        let bitmap = self.bitmaps.next().ok_or(CanvasError::MissingImageBitmap)?;
+        if self.in_clip {
+            return Ok(());
+        }
+
+        self.context.save();
+        self.context.translate(center.x as f64, center.y as f64)?;
+        self.context.rotate(-rotation as f64)?;
+        self.context.draw_image_with_image_bitmap_and_dw_and_dh(
+            &bitmap,
+            -width as f64 / 2.,
+            -height as f64 / 2.,
+            width as f64,
+            height as f64,
+        )?;
+        self.context.restore();
+
+        Ok(())
+    }
+
+    fn export_ellipse(
+        &mut self,
+        EllipsePosition {
+            center,
+            semi_major_axis,
+            semi_minor_axis,
+            rotation,
+        }: EllipsePosition,
+    ) -> Result<(), Self::Error> {
+        if self.in_clip {
+            // Start where the ellipse starts, rather than joining it to the end of the previous one
+            self.context.move_to(
+                (center.x + semi_major_axis * rotation.cos()) as f64,
+                (center.y - semi_major_axis * rotation.sin()) as f64,
+            );
+        } else {
+            self.context.begin_path();
+        }
+        self.context.ellipse(
+            center.x as f64,
+            center.y as f64,
+            semi_major_axis as f64,
+            semi_minor_axis as f64,
+            -rotation as f64,
+            0.,
+            2. * PI,
+        )?;
+        if self.in_clip {
+            return Ok(());
+        }
+        self.paint(FillRule::NonZero)
+    }
+
+    fn export_curve(&mut self, curve: CurvePosition) -> Result<(), Self::Error> {
+        if self.in_clip {
+            return self.trace_curve(&curve);
+        }
+        self.context.begin_path();
+        self.trace_curve(&curve)?;
+        self.paint(curve.fill_rule)
+    }
+
+    fn export_text(
+        &mut self,
+        TextPosition {
+            text,
+            align,
+            font_weight,
+            on_curve: _,
+            font_size,
+            reference_start,
+            direction,
+            font,
+        }: TextPosition,
+    ) -> Result<(), Self::Error> {
+        let Some(fill) = inherited_style(&self.style).fill.filter(|_| !self.in_clip) else {
+            return Ok(());
+        };
+
+        let align = match align {
+            TextAlign::Center => "center",
+            TextAlign::Left => "left",
+            TextAlign::Right => "right",
+        };
+
+        let font = font.clone().unwrap_or_default().name(font_weight);
+
+        self.context.save();
+        self.context
+            .translate(reference_start.x as f64, reference_start.y as f64)?;
+        self.context.rotate(direction.y.atan2(direction.x) as f64)?;
+        self.set_fill_style(&fill)?;
+        self.context.set_text_align(align);
+        self.context.set_font(&format!("{font_size}px {font}"));
+        self.context.fill_text(text, 0., 0.)?;
+        self.context.restore();
+
+        Ok(())
+    }
+}
+
+thread_local! {
+    /// Fonts already added to the document, so redrawing doesn't add them again
+    static LOADED_FONTS: RefCell<HashSet<String>> = RefCell::new(HashSet::new());
+}
+
+/// Upload images and fonts used by the dessin to the browser
+async fn load_resources(resources: ResourceCollector) -> Result<Vec<ImageBitmap>, CanvasError> {
+    let window = web_sys::window().ok_or(CanvasError::NoWindow)?;
+
+    if let Some(document) = window.document() {
+        let font_set = document.fonts();
+        for (font_ref, font_weight) in resources.fonts {
+            let name = font_ref.name(font_weight);
+            if LOADED_FONTS.with(|fonts| fonts.borrow().contains(&name)) {
+                continue;
+            }
+
+            let font_group = dessin::font::get(font_ref);
+            let bytes = font_group.get(font_weight).as_bytes();
+
+            let font_face = FontFace::new_with_u8_array(&name, bytes)?;
+            JsFuture::from(font_face.load()?).await?;
+            font_set.add(&font_face)?;
+
+            LOADED_FONTS.with(|fonts| fonts.borrow_mut().insert(name));
+        }
+    }
+
+    let mut bitmaps = Vec::with_capacity(resources.images.len());
+    for (width, height, raw) in resources.images {
+        let data = ImageData::new_with_u8_clamped_array_and_sh(Clamped(&raw), width, height)?;
+        let bitmap = JsFuture::from(window.create_image_bitmap_with_image_data(&data)?).await?;
+        bitmaps.push(bitmap.dyn_into::<ImageBitmap>()?);
+    }
+
+    Ok(bitmaps)
+}
+
+pub async fn draw_with_options(
+    shape: &Shape,
+    context: &CanvasRenderingContext2d,
+    options: CanvasOptions,
+) -> Result<(), CanvasError> {
+    let canvas = context.canvas().ok_or(CanvasError::NoCanvas)?;
+    let (canvas_width, canvas_height) = (canvas.width() as f32, canvas.height() as f32);
+
+    let (center_x, center_y, width, height) = match options.viewport {
+        ViewPort::ManualCentered { width, height } => (0., 0., width, height),
+        ViewPort::ManualViewport {
+            x,
+            y,
+            width,
+            height,
+        } => (x, y, width, height),
+        ViewPort::AutoCentered => {
+            let bb = shape.local_bounding_box().straigthen();
+            let width = 2. * bb.left().abs().max(bb.right().abs());
+            let height = 2. * bb.top().abs().max(bb.bottom().abs());
+            (0., 0., width, height)
+        }
+        ViewPort::AutoBoundingBox => {
+            let bb = shape.local_bounding_box().straigthen();
+            (bb.center().x, bb.center().y, bb.width(), bb.height())
+        }
+    };
+
+    // Fit the viewport in the canvas, flipping the Y axis as the canvas goes down
+    let scale = (canvas_width / width).min(canvas_height / height);
+    let scale = if scale.is_finite() { scale } else { 1. };
+    let parent_transform = nalgebra::convert::<_, Transform2<f32>>(Translation2::new(
+        canvas_width / 2.,
+        canvas_height / 2.,
+    )) * nalgebra::convert::<_, Transform2<f32>>(Scale2::new(scale, -scale))
+        * nalgebra::convert::<_, Transform2<f32>>(Translation2::new(-center_x, -center_y));
+
+    let mut resources = ResourceCollector::default();
+    shape.write_into_exporter(&mut resources, &parent_transform)?;
+    let bitmaps = load_resources(resources).await?;
+
+    if options.clear {
+        context.clear_rect(0., 0., canvas_width as f64, canvas_height as f64);
+    }
+
+    let mut exporter = CanvasExporter::new(context, bitmaps);
+    shape.write_into_exporter(&mut exporter, &parent_transform)
+}
+
+pub async fn draw(shape: &Shape, context: &CanvasRenderingContext2d) -> Result<(), CanvasError> {
+    draw_with_options(shape, context, CanvasOptions::default()).await
+}
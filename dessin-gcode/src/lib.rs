@@ -0,0 +1,487 @@
+//! Export a dessin to G-code, for CNC and laser engravers.
+//!
+//! Every curve is flattened into straight segments, then engraved with `G1` moves, each dash of dashed strokes on its own.
+//! Travels between curves are done with `G0` moves, optionaly reordered to be as short as possible.
+//!
+//! ```
+//! # use dessin::prelude::*;
+//! use dessin_gcode::{to_string_with_options, GCodeOptions, PowerMapping};
+//!
+//! let dessin = dessin2!([
+//! 	Circle!(stroke = (Color::BLACK, 0.2), radius = 20.),
+//! 	Rectangle!(stroke = (Color::GRAY, 0.2), width = 10., height = 10.),
+//! ]);
+//!
+//! let gcode = to_string_with_options(
+//! 	&dessin,
+//! 	GCodeOptions {
+//! 		power: PowerMapping::Darkness,
+//! 		..Default::default()
+//! 	},
+//! )
+//! .unwrap();
+//!
+//! assert!(gcode.contains("G1"));
+//! ```
+
+#![allow(clippy::tabs_in_doc_comments)]
+
+use dessin::{
+    export::{Export, Exporter},
+    outline::split_dashes,
+    prelude::*,
+};
+use nalgebra::{Point2, Transform2, Translation2};
+use std::fmt::{self, Write};
+
+#[derive(Debug)]
+pub enum GCodeError {
+    WriteError(fmt::Error),
+    /// [`PowerMapping::StrokeWidth`] with a `full_power_width` that isn't positive
+    InvalidFullPowerWidth(f32),
+}
+impl fmt::Display for GCodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+impl From<fmt::Error> for GCodeError {
+    fn from(value: fmt::Error) -> Self {
+        GCodeError::WriteError(value)
+    }
+}
+impl std::error::Error for GCodeError {}
+
+/// How the power of the laser (or spindle speed) is chosen for a curve.
+///
+/// Only stroked curves are engraved, whatever the mapping.
+///
+/// ```
+/// # use dessin::prelude::*;
+/// let dessin = dessin2!(Rectangle!(fill = Color::BLACK, width = 10., height = 10.) > ());
+///
+/// let gcode = dessin_gcode::to_string(&dessin).unwrap();
+/// assert!(!gcode.contains("G1"));
+/// ```
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub enum PowerMapping {
+    /// Every stroked curve is engraved at [`GCodeOptions::max_power`]
+    #[default]
+    Constant,
+    /// Power is proportional to the stroke width, reaching [`GCodeOptions::max_power`] at `full_power_width`, which must be positive
+    StrokeWidth { full_power_width: f32 },
+    /// Darker stroke colors are engraved with more power, black being [`GCodeOptions::max_power`]
+    Darkness,
+}
+
+#[derive(Debug, Clone)]
+pub struct GCodeOptions {
+    /// Maximum distance between a curve and its engraved segments
+    pub tolerance: f32,
+    /// Speed while engraving, in units per minute
+    pub feed_rate: f32,
+    /// Speed of travels between curves, in units per minute
+    pub travel_rate: f32,
+    /// Maximum `S` value of the machine
+    pub max_power: f32,
+    /// How the power is chosen for each curve
+    pub power: PowerMapping,
+    /// Reorder and reverse curves to shorten travels
+    pub optimize_travel: bool,
+    /// Move the drawing so its bottom left corner is at the origin of the machine
+    pub align_to_origin: bool,
+}
+impl Default for GCodeOptions {
+    fn default() -> Self {
+        GCodeOptions {
+            tolerance: 0.05,
+            feed_rate: 1000.,
+            travel_rate: 3000.,
+            max_power: 1000.,
+            power: PowerMapping::Constant,
+            optimize_travel: true,
+            align_to_origin: true,
+        }
+    }
+}
+
+/// Polyline to engrave, with its power
+struct Toolpath {
+    points: Vec<Point2<f32>>,
+    power: f32,
+    closed: bool,
+}
+
+pub struct GCodeExporter {
+    options: GCodeOptions,
+    stroke_stack: Vec<Option<Stroke>>,
+    toolpaths: Vec<Toolpath>,
+}
+
+impl GCodeExporter {
+    fn new(options: GCodeOptions) -> Self {
+        GCodeExporter {
+            options,
+            stroke_stack: vec![],
+            toolpaths: vec![],
+        }
+    }
+
+    fn power(&self) -> f32 {
+        let stroke = self.stroke_stack.last().cloned().flatten();
+
+        let ratio = match (self.options.power, stroke) {
+            (PowerMapping::Constant, Some(_)) => 1.,
+            (PowerMapping::StrokeWidth { full_power_width }, Some(stroke)) => {
+                stroke.width() / full_power_width
+            }
+            (PowerMapping::Darkness, Some(stroke)) => {
+                let (r, g, b) = stroke.color().as_rgb_f32();
+                1. - (0.2126 * r + 0.7152 * g + 0.0722 * b)
+            }
+            (_, None) => 0.,
+        };
+
+        ratio.clamp(0., 1.) * self.options.max_power
+    }
+
+    /// Greedy nearest neighbour ordering, starting from the origin.
+    ///
+    /// Open toolpaths may be reversed, closed toolpaths start where they are.
+    fn optimize_travel(toolpaths: Vec<Toolpath>) -> Vec<Toolpath> {
+        let mut remaining = toolpaths;
+        let mut ordered = Vec::with_capacity(remaining.len());
+        let mut position = Point2::origin();
+
+        while !remaining.is_empty() {
+            let (idx, reverse, _) = remaining
+                .iter()
+                .enumerate()
+                .flat_map(|(idx, toolpath)| {
+                    let start = (idx, false, (toolpath.points[0] - position).norm_squared());
+                    let end = (
+                        idx,
+                        true,
+                        (toolpath.points[toolpath.points.len() - 1] - position).norm_squared(),
+                    );
+
+                    if toolpath.closed {
+                        vec![start]
+                    } else {
+                        vec![start, end]
+                    }
+                })
+                .min_by(|(_, _, a), (_, _, b)| a.total_cmp(b))
+                .unwrap();
+
+            let mut toolpath = remaining.swap_remove(idx);
+            if reverse {
+                toolpath.points.reverse();
+            }
+
+            position = toolpath.points[toolpath.points.len() - 1];
+            ordered.push(toolpath);
+        }
+
+        ordered
+    }
+
+    fn finish(self) -> Result<String, GCodeError> {
+        let GCodeOptions {
+            feed_rate,
+            travel_rate,
+            optimize_travel,
+            ..
+        } = self.options;
+
+        let toolpaths = if optimize_travel {
+            GCodeExporter::optimize_travel(self.toolpaths)
+        } else {
+            self.toolpaths
+        };
+
+        let mut acc = String::new();
+        writeln!(acc, "G21")?; // Millimeters
+        writeln!(acc, "G90")?; // Absolute positioning
+        writeln!(acc, "M4 S0")?; // Laser in dynamic power mode
+
+        for Toolpath { points, power, .. } in toolpaths {
+            let (first, rest) = points.split_first().unwrap();
+
+            writeln!(acc, "G0 X{:.3} Y{:.3} F{travel_rate}", first.x, first.y)?;
+            for (idx, p) in rest.iter().enumerate() {
+                if idx == 0 {
+                    writeln!(acc, "G1 X{:.3} Y{:.3} S{power:.0} F{feed_rate}", p.x, p.y)?;
+                } else {
+                    writeln!(acc, "G1 X{:.3} Y{:.3}", p.x, p.y)?;
+                }
+            }
+        }
+
+        writeln!(acc, "M5")?; // Laser off
+        writeln!(acc, "G0 X0 Y0")?;
+
+        Ok(acc)
+    }
+}
+
+impl Exporter for GCodeExporter {
+    type Error = GCodeError;
+    const CAN_EXPORT_ELLIPSE: bool = false;
+    const BACKEND: Option<&'static str> = Some("gcode");
+
+    fn start_style(&mut self, style: StylePosition) -> Result<(), Self::Error> {
+        let stroke = style
+            .stroke
+            .or_else(|| self.stroke_stack.last().cloned().flatten());
+        self.stroke_stack.push(stroke);
+
+        Ok(())
+    }
+
+    fn end_style(&mut self) -> Result<(), Self::Error> {
+        self.stroke_stack.pop();
+        Ok(())
+    }
+
+    /// Images can't be engraved as toolpaths, they are skipped
+    fn export_image(&mut self, _image: ImagePosition) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    fn export_curve(&mut self, curve: CurvePosition) -> Result<(), Self::Error> {
+        let power = self.power();
+        if power <= 0. {
+            return Ok(());
+        }
+
+        let mut points = curve.flatten(self.options.tolerance);
+        points.dedup();
+
+        let dashes = self
+            .stroke_stack
+            .last()
+            .cloned()
+            .flatten()
+            .and_then(|stroke| stroke.dashes());
+        let (paths, closed) = match dashes {
+            Some((dashes, offset)) => (split_dashes(&points, &dashes, offset), false),
+            None => (vec![points], curve.closed),
+        };
+
+        self.toolpaths.extend(
+            paths
+                .into_iter()
+                .filter(|points| points.len() > 1)
+                .map(|points| Toolpath {
+                    points,
+                    power,
+                    closed,
+                }),
+        );
+
+        Ok(())
+    }
+
+    /// Texts have no outlines yet, they are skipped
+    fn export_text(&mut self, _text: TextPosition) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+pub fn to_string_with_options(shape: &Shape, options: GCodeOptions) -> Result<String, GCodeError> {
+    if let PowerMapping::StrokeWidth { full_power_width } = options.power {
+        if full_power_width <= 0. || full_power_width.is_nan() {
+            return Err(GCodeError::InvalidFullPowerWidth(full_power_width));
+        }
+    }
+
+    let parent_transform = if options.align_to_origin {
+        let bb = shape.local_bounding_box().straigthen();
+        nalgebra::convert(Translation2::new(-bb.left(), -bb.bottom()))
+    } else {
+        Transform2::default()
+    };
+
+    let mut exporter = GCodeExporter::new(options);
+    shape.write_into_exporter(&mut exporter, &parent_transform)?;
+
+    exporter.finish()
+}
+
+pub fn to_string(shape: &Shape) -> Result<String, GCodeError> {
+    to_string_with_options(shape, GCodeOptions::default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Toolpaths of `gcode`, as the points of each `G0` move and the `G1` moves following it
+    fn toolpaths(gcode: &str) -> Vec<Vec<(f32, f32)>> {
+        let point = |line: &str| {
+            let mut words = line.split(' ').skip(1);
+            let x = words.next().unwrap()[1..].parse().unwrap();
+            let y = words.next().unwrap()[1..].parse().unwrap();
+            (x, y)
+        };
+
+        let mut toolpaths: Vec<Vec<(f32, f32)>> = vec![];
+        for line in gcode.lines() {
+            if line.starts_with("G0 ") && line.contains('F') {
+                toolpaths.push(vec![point(line)]);
+            } else if line.starts_with("G1 ") {
+                toolpaths.last_mut().unwrap().push(point(line));
+            }
+        }
+
+        toolpaths
+    }
+
+    fn powers(gcode: &str) -> Vec<&str> {
+        gcode
+            .lines()
+            .filter_map(|line| line.split(' ').find(|word| word.starts_with('S')))
+            .filter(|power| *power != "S0")
+            .collect()
+    }
+
+    fn options(power: PowerMapping) -> GCodeOptions {
+        GCodeOptions {
+            power,
+            align_to_origin: false,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn nearest_toolpath_first() {
+        let dessin = dessin2!([
+            Line!(
+                stroke = (Color::BLACK, 0.2),
+                from = [50., 0.],
+                to = [60., 0.]
+            ),
+            Line!(
+                stroke = (Color::BLACK, 0.2),
+                from = [20., 0.],
+                to = [10., 0.]
+            ),
+            Line!(
+                stroke = (Color::BLACK, 0.2),
+                from = [30., 0.],
+                to = [40., 0.]
+            ),
+        ]);
+
+        let gcode = to_string_with_options(&dessin, options(PowerMapping::Constant)).unwrap();
+        assert_eq!(
+            toolpaths(&gcode),
+            vec![
+                vec![(10., 0.), (20., 0.)],
+                vec![(30., 0.), (40., 0.)],
+                vec![(50., 0.), (60., 0.)],
+            ]
+        );
+    }
+
+    #[test]
+    fn closed_toolpaths_in_one_piece() {
+        let dessin = dessin2!([
+            Rectangle!(
+                stroke = (Color::BLACK, 0.2),
+                width = 10.,
+                height = 10.,
+                translate = [20., 20.]
+            ),
+            Line!(
+                stroke = (Color::BLACK, 0.2),
+                from = [0., 0.],
+                to = [15., 15.]
+            ),
+        ]);
+
+        let gcode = to_string_with_options(&dessin, options(PowerMapping::Constant)).unwrap();
+        let toolpaths = toolpaths(&gcode);
+        assert_eq!(toolpaths.len(), 2);
+        assert_eq!(toolpaths[0], vec![(0., 0.), (15., 15.)]);
+
+        // Every corner of the rectangle, back to where it started
+        let rectangle = &toolpaths[1];
+        assert_eq!(rectangle.len(), 5);
+        assert_eq!(rectangle.first(), rectangle.last());
+    }
+
+    #[test]
+    fn dashed_strokes() {
+        let dessin = dessin2!(
+            Line!(
+                stroke = Stroke::dashed(Color::BLACK, 0.2, 2., 1.),
+                from = [0., 0.],
+                to = [10., 0.],
+            ) > ()
+        );
+
+        let gcode = to_string_with_options(&dessin, options(PowerMapping::Constant)).unwrap();
+        assert_eq!(
+            toolpaths(&gcode),
+            vec![
+                vec![(0., 0.), (2., 0.)],
+                vec![(3., 0.), (5., 0.)],
+                vec![(6., 0.), (8., 0.)],
+                vec![(9., 0.), (10., 0.)],
+            ]
+        );
+    }
+
+    #[test]
+    fn power_mappings() {
+        let dessin = dessin2!([
+            Line!(
+                stroke = (Color::BLACK, 0.5),
+                from = [0., 0.],
+                to = [10., 0.]
+            ),
+            Line!(stroke = (Color::WHITE, 2.), from = [0., 5.], to = [10., 5.]),
+        ]);
+        let gcode = |power| {
+            let options = GCodeOptions {
+                optimize_travel: false,
+                ..options(power)
+            };
+            to_string_with_options(&dessin, options).unwrap()
+        };
+
+        assert_eq!(
+            powers(&gcode(PowerMapping::Constant)),
+            vec!["S1000", "S1000"]
+        );
+        assert_eq!(
+            powers(&gcode(PowerMapping::StrokeWidth {
+                full_power_width: 1.
+            })),
+            vec!["S500", "S1000"]
+        );
+        // White isn't engraved at all
+        assert_eq!(powers(&gcode(PowerMapping::Darkness)), vec!["S1000"]);
+    }
+
+    #[test]
+    fn invalid_full_power_width() {
+        let dessin = dessin2!(
+            Line!(
+                stroke = (Color::BLACK, 0.5),
+                from = [0., 0.],
+                to = [10., 0.]
+            ) > ()
+        );
+
+        for full_power_width in [0., -1., f32::NAN] {
+            let options = options(PowerMapping::StrokeWidth { full_power_width });
+            assert!(matches!(
+                to_string_with_options(&dessin, options),
+                Err(GCodeError::InvalidFullPowerWidth(_))
+            ));
+        }
+    }
+}
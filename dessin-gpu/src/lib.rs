@@ -0,0 +1,232 @@
+//! Draw a dessin on the GPU.
+//!
+//! Shapes are tessellated into triangles with [lyon](https://docs.rs/lyon/), in a [`Mesh`] ready to be uploaded to the GPU.
+//! A [`Renderer`] then draws a [`Mesh`] in any [wgpu](https://docs.rs/wgpu/) render pass,
+//! so a dessin with thousands of shapes can be redrawn at interactive frame rates.
+//!
+//! Only fills and strokes of curves and ellipses are tessellated, texts and images are skipped.
+//! Dashed strokes are drawn as full strokes.
+//!
+//! ```
+//! # use dessin::prelude::*;
+//! let dessin = dessin2!([
+//! 	Circle!(fill = Color::RED, radius = 10.),
+//! 	Rectangle!(stroke = (Color::BLACK, 0.5), width = 30., height = 20.),
+//! ]);
+//!
+//! let mesh = dessin_gpu::tessellate(&dessin, 0.01).unwrap();
+//! assert!(!mesh.indices.is_empty());
+//! ```
+
+#![allow(clippy::tabs_in_doc_comments)]
+
+mod renderer;
+
+pub use renderer::*;
+
+use bytemuck::{Pod, Zeroable};
+use dessin::{
+    export::{inherited_style, Export, Exporter},
+    prelude::*,
+};
+use lyon::{
+    math::point,
+    path::Path,
+    tessellation::{
+        BuffersBuilder, FillOptions, FillTessellator, FillVertex, StrokeOptions, StrokeTessellator,
+        StrokeVertex, TessellationError, VertexBuffers,
+    },
+};
+use std::fmt;
+
+#[derive(Debug)]
+pub enum GPUError {
+    TessellationError(TessellationError),
+    CurveHasNoStartingPoint(CurvePosition),
+}
+impl fmt::Display for GPUError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+impl From<TessellationError> for GPUError {
+    fn from(value: TessellationError) -> Self {
+        GPUError::TessellationError(value)
+    }
+}
+impl std::error::Error for GPUError {}
+
+/// Vertex of a [`Mesh`], as expected by the [`Renderer`]
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Pod, Zeroable)]
+pub struct Vertex {
+    /// Position, in the coordinates of the dessin
+    pub position: [f32; 2],
+    /// RGBA color, in sRGB
+    pub color: [f32; 4],
+}
+
+/// Triangles of a tessellated dessin
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Mesh {
+    /// Vertices
+    pub vertices: Vec<Vertex>,
+    /// Indices of the vertices, three by triangle
+    pub indices: Vec<u32>,
+}
+
+pub struct TessellationExporter {
+    tolerance: f32,
+    style: Vec<StylePosition>,
+    buffers: VertexBuffers<Vertex, u32>,
+    fill_tessellator: FillTessellator,
+    stroke_tessellator: StrokeTessellator,
+}
+
+impl TessellationExporter {
+    fn new(tolerance: f32) -> Self {
+        TessellationExporter {
+            tolerance,
+            style: vec![],
+            buffers: VertexBuffers::new(),
+            fill_tessellator: FillTessellator::new(),
+            stroke_tessellator: StrokeTessellator::new(),
+        }
+    }
+
+    fn path(curve: CurvePosition) -> Result<Path, GPUError> {
+        let mut builder = Path::builder();
+        let mut has_start = false;
+
+        for keypoint in &curve.keypoints {
+            match keypoint {
+                KeypointPosition::Point(p) if has_start => {
+                    builder.line_to(point(p.x, p.y));
+                }
+                KeypointPosition::Point(p) => {
+                    builder.begin(point(p.x, p.y));
+                }
+                KeypointPosition::Bezier(b) => {
+                    match (has_start, b.start) {
+                        (false, None) => return Err(GPUError::CurveHasNoStartingPoint(curve)),
+                        (false, Some(s)) => {
+                            builder.begin(point(s.x, s.y));
+                        }
+                        (true, Some(s)) => {
+                            builder.line_to(point(s.x, s.y));
+                        }
+                        (true, None) => {}
+                    }
+
+                    builder.cubic_bezier_to(
+                        point(b.start_control.x, b.start_control.y),
+                        point(b.end_control.x, b.end_control.y),
+                        point(b.end.x, b.end.y),
+                    );
+                }
+            }
+
+            has_start = true;
+        }
+
+        if has_start {
+            builder.end(curve.closed);
+        }
+
+        Ok(builder.build())
+    }
+
+    fn finish(self) -> Mesh {
+        Mesh {
+            vertices: self.buffers.vertices,
+            indices: self.buffers.indices,
+        }
+    }
+}
+
+impl Exporter for TessellationExporter {
+    type Error = GPUError;
+    const CAN_EXPORT_ELLIPSE: bool = false;
+    const BACKEND: Option<&'static str> = Some("gpu");
+
+    fn start_style(&mut self, style: StylePosition) -> Result<(), Self::Error> {
+        self.style.push(style);
+        Ok(())
+    }
+
+    fn end_style(&mut self) -> Result<(), Self::Error> {
+        self.style.pop();
+        Ok(())
+    }
+
+    fn export_image(&mut self, _image: ImagePosition) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    fn export_curve(&mut self, curve: CurvePosition) -> Result<(), Self::Error> {
+        let style = inherited_style(&self.style);
+        let fill_rule = match curve.fill_rule {
+            FillRule::NonZero => lyon::path::FillRule::NonZero,
+            FillRule::EvenOdd => lyon::path::FillRule::EvenOdd,
+        };
+        let path = TessellationExporter::path(curve)?;
+
+        if let Some(color) = style.fill.map(|fill| fill.color()) {
+            let (r, g, b, a) = color.as_rgba_f32();
+            let color = [r, g, b, a];
+
+            self.fill_tessellator.tessellate_path(
+                &path,
+                &FillOptions::tolerance(self.tolerance).with_fill_rule(fill_rule),
+                &mut BuffersBuilder::new(&mut self.buffers, |v: FillVertex| Vertex {
+                    position: v.position().to_array(),
+                    color,
+                }),
+            )?;
+        }
+
+        if let Some(stroke) = style.stroke {
+            let (r, g, b, a) = stroke.color().as_rgba_f32();
+            let color = [r, g, b, a];
+
+            let cap = match stroke.cap() {
+                LineCap::Butt => lyon::path::LineCap::Butt,
+                LineCap::Round => lyon::path::LineCap::Round,
+                LineCap::Square => lyon::path::LineCap::Square,
+            };
+            let join = match stroke.join() {
+                LineJoin::Miter => lyon::path::LineJoin::Miter,
+                LineJoin::Round => lyon::path::LineJoin::Round,
+                LineJoin::Bevel => lyon::path::LineJoin::Bevel,
+            };
+
+            self.stroke_tessellator.tessellate_path(
+                &path,
+                &StrokeOptions::tolerance(self.tolerance)
+                    .with_line_width(stroke.width())
+                    .with_line_cap(cap)
+                    .with_line_join(join)
+                    // Limits below the minimum of lyon, set without `Stroke::with_miter_limit`, bevel every corner anyway
+                    .with_miter_limit(stroke.miter_limit().max(StrokeOptions::MINIMUM_MITER_LIMIT)),
+                &mut BuffersBuilder::new(&mut self.buffers, |v: StrokeVertex| Vertex {
+                    position: v.position().to_array(),
+                    color,
+                }),
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn export_text(&mut self, _text: TextPosition) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+/// Tessellate a dessin into triangles, no further than `tolerance` from the real curves
+pub fn tessellate(shape: &Shape, tolerance: f32) -> Result<Mesh, GPUError> {
+    let mut exporter = TessellationExporter::new(tolerance);
+    shape.write_into_exporter(&mut exporter, &Default::default())?;
+
+    Ok(exporter.finish())
+}
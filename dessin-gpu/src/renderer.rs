@@ -0,0 +1,238 @@
+use crate::{Mesh, Vertex};
+use bytemuck::{Pod, Zeroable};
+use dessin::prelude::*;
+use nalgebra::{Point2, Scale2, Transform2, Translation2};
+use wgpu::util::DeviceExt;
+
+const SHADER: &str = r#"
+struct Globals {
+    transform: mat3x3<f32>,
+    // x: 1 if the target expects linear colors
+    flags: vec4<f32>,
+};
+
+@group(0) @binding(0) var<uniform> globals: Globals;
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) color: vec4<f32>,
+};
+
+@vertex
+fn vs_main(@location(0) position: vec2<f32>, @location(1) color: vec4<f32>) -> VertexOutput {
+    var out: VertexOutput;
+    let p = globals.transform * vec3<f32>(position, 1.0);
+    out.position = vec4<f32>(p.xy, 0.0, 1.0);
+    out.color = color;
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    if globals.flags.x > 0.5 {
+        return vec4<f32>(pow(in.color.rgb, vec3<f32>(2.2)), in.color.a);
+    }
+    return in.color;
+}
+"#;
+
+/// Uniforms of the shader, `mat3x3` columns are aligned on 16 bytes
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod, Zeroable)]
+struct Globals {
+    transform: [[f32; 4]; 3],
+    flags: [f32; 4],
+}
+
+/// [`Mesh`] uploaded to the GPU
+pub struct GpuMesh {
+    vertices: wgpu::Buffer,
+    indices: wgpu::Buffer,
+    index_count: u32,
+}
+
+/// Draws [`Mesh`]es in a wgpu render pass.
+///
+/// ```no_run
+/// # use dessin::prelude::*;
+/// # fn frame(device: &wgpu::Device, queue: &wgpu::Queue, pass: &mut wgpu::RenderPass, format: wgpu::TextureFormat) {
+/// let renderer = dessin_gpu::Renderer::new(device, format);
+///
+/// let dessin = dessin2!(Circle!(fill = Color::RED, radius = 10.) > ());
+/// let mesh = renderer.upload(device, &dessin_gpu::tessellate(&dessin, 0.01).unwrap());
+///
+/// renderer.set_viewport(queue, [0., 0.], 50., 50.);
+/// renderer.render(pass, &mesh);
+/// # }
+/// ```
+pub struct Renderer {
+    pipeline: wgpu::RenderPipeline,
+    globals: wgpu::Buffer,
+    bind_group: wgpu::BindGroup,
+    linear_target: bool,
+}
+
+impl Renderer {
+    /// Create the render pipeline for a given target format
+    pub fn new(device: &wgpu::Device, format: wgpu::TextureFormat) -> Self {
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("dessin"),
+            source: wgpu::ShaderSource::Wgsl(SHADER.into()),
+        });
+
+        let linear_target = format.is_srgb();
+        let globals = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("dessin globals"),
+            contents: bytemuck::bytes_of(&Renderer::globals(
+                &Transform2::identity(),
+                linear_target,
+            )),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("dessin globals"),
+            entries: &[wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::VERTEX_FRAGMENT,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            }],
+        });
+
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("dessin globals"),
+            layout: &bind_group_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: globals.as_entire_binding(),
+            }],
+        });
+
+        let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("dessin"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("dessin"),
+            layout: Some(&layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: Some("vs_main"),
+                compilation_options: Default::default(),
+                buffers: &[wgpu::VertexBufferLayout {
+                    array_stride: std::mem::size_of::<Vertex>() as wgpu::BufferAddress,
+                    step_mode: wgpu::VertexStepMode::Vertex,
+                    attributes: &wgpu::vertex_attr_array![0 => Float32x2, 1 => Float32x4],
+                }],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: Some("fs_main"),
+                compilation_options: Default::default(),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format,
+                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+            }),
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+            cache: None,
+        });
+
+        Renderer {
+            pipeline,
+            globals,
+            bind_group,
+            linear_target,
+        }
+    }
+
+    fn globals(transform: &Transform2<f32>, linear_target: bool) -> Globals {
+        let m = transform.matrix();
+        let column = |c: usize| [m[(0, c)], m[(1, c)], m[(2, c)], 0.];
+
+        Globals {
+            transform: [column(0), column(1), column(2)],
+            flags: [if linear_target { 1. } else { 0. }, 0., 0., 0.],
+        }
+    }
+
+    /// Set the transform from the coordinates of the dessin to clip space, where the target spans from -1 to 1
+    pub fn set_transform(&self, queue: &wgpu::Queue, transform: &Transform2<f32>) {
+        queue.write_buffer(
+            &self.globals,
+            0,
+            bytemuck::bytes_of(&Renderer::globals(transform, self.linear_target)),
+        );
+    }
+
+    /// Show the area centered around `center`, with size (width, height), on the whole target
+    pub fn set_viewport<P: Into<Point2<f32>>>(
+        &self,
+        queue: &wgpu::Queue,
+        center: P,
+        width: f32,
+        height: f32,
+    ) {
+        let center = center.into();
+        let transform =
+            nalgebra::convert::<_, Transform2<f32>>(Scale2::new(2. / width, 2. / height))
+                * nalgebra::convert::<_, Transform2<f32>>(Translation2::new(-center.x, -center.y));
+
+        self.set_transform(queue, &transform);
+    }
+
+    /// Show the whole dessin on the target, keeping its aspect ratio
+    pub fn fit(&self, queue: &wgpu::Queue, shape: &Shape, target_width: u32, target_height: u32) {
+        let bb = shape.local_bounding_box().straigthen();
+        let ratio = target_width as f32 / target_height as f32;
+
+        let (width, height) = if bb.width() / bb.height() > ratio {
+            (bb.width(), bb.width() / ratio)
+        } else {
+            (bb.height() * ratio, bb.height())
+        };
+
+        self.set_viewport(queue, bb.center(), width, height);
+    }
+
+    /// Upload a [`Mesh`] to the GPU
+    pub fn upload(&self, device: &wgpu::Device, mesh: &Mesh) -> GpuMesh {
+        GpuMesh {
+            vertices: device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("dessin vertices"),
+                contents: bytemuck::cast_slice(&mesh.vertices),
+                usage: wgpu::BufferUsages::VERTEX,
+            }),
+            indices: device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("dessin indices"),
+                contents: bytemuck::cast_slice(&mesh.indices),
+                usage: wgpu::BufferUsages::INDEX,
+            }),
+            index_count: mesh.indices.len() as u32,
+        }
+    }
+
+    /// Draw a [`GpuMesh`] in a render pass
+    pub fn render(&self, pass: &mut wgpu::RenderPass<'_>, mesh: &GpuMesh) {
+        if mesh.index_count == 0 {
+            return;
+        }
+
+        pass.set_pipeline(&self.pipeline);
+        pass.set_bind_group(0, &self.bind_group, &[]);
+        pass.set_vertex_buffer(0, mesh.vertices.slice(..));
+        pass.set_index_buffer(mesh.indices.slice(..), wgpu::IndexFormat::Uint32);
+        pass.draw_indexed(0..mesh.index_count, 0, 0..1);
+    }
+}
@@ -0,0 +1,308 @@
+//! Export a dessin to HPGL, the language of pen plotters.
+//!
+//! Every stroked curve is flattened into straight segments and plotted in order, with `PU` (pen up) and `PD` (pen down) commands.
+//! Each stroke color is mapped to the closest pen of [`HPGLOptions::pens`], and curves without stroke are skipped.
+//!
+//! ```
+//! # use dessin::prelude::*;
+//! let dessin = dessin2!([
+//! 	Circle!(stroke = (Color::BLACK, 0.3), radius = 20.),
+//! 	Line!(stroke = (Color::RED, 0.3), from = [-20., 0.], to = [20., 0.]),
+//! ]);
+//!
+//! let hpgl = dessin_hpgl::to_string(&dessin).unwrap();
+//! assert!(hpgl.starts_with("IN;"));
+//! ```
+
+#![allow(clippy::tabs_in_doc_comments)]
+
+use dessin::{
+    export::{Export, Exporter},
+    prelude::*,
+};
+use nalgebra::{Point2, Transform2, Translation2};
+use std::fmt::{self, Write};
+
+/// Plotter units in a millimeter, as defined by HPGL
+const PLOTTER_UNITS_PER_MM: f32 = 40.;
+
+#[derive(Debug)]
+pub enum HPGLError {
+    WriteError(fmt::Error),
+}
+impl fmt::Display for HPGLError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+impl From<fmt::Error> for HPGLError {
+    fn from(value: fmt::Error) -> Self {
+        HPGLError::WriteError(value)
+    }
+}
+impl std::error::Error for HPGLError {}
+
+#[derive(Clone)]
+pub struct HPGLOptions {
+    /// Colors of the pens loaded in the plotter, pen 1 being the first.
+    ///
+    /// A stroke is drawn with the pen of the closest color.
+    pub pens: Vec<Color>,
+    /// Plotter units in one unit of the dessin. HPGL plotters use 40 units per millimeter.
+    pub units_per_mm: f32,
+    /// Maximum distance, in the unit of the dessin, between a curve and its plotted segments
+    pub tolerance: f32,
+    /// Move the drawing so its bottom left corner is at the origin of the plotter
+    pub align_to_origin: bool,
+}
+impl Default for HPGLOptions {
+    fn default() -> Self {
+        HPGLOptions {
+            pens: vec![Color::BLACK],
+            units_per_mm: PLOTTER_UNITS_PER_MM,
+            tolerance: 0.1,
+            align_to_origin: true,
+        }
+    }
+}
+
+pub struct HPGLExporter {
+    acc: String,
+    options: HPGLOptions,
+    /// Pen of the stroke of each style, if any
+    pen_stack: Vec<Option<usize>>,
+    selected_pen: Option<usize>,
+}
+
+impl HPGLExporter {
+    fn new(options: HPGLOptions) -> Self {
+        HPGLExporter {
+            acc: "IN;".to_string(),
+            options,
+            pen_stack: vec![],
+            selected_pen: None,
+        }
+    }
+
+    /// Pen, starting from 1, with the closest color
+    fn pen_of(&self, color: Color) -> usize {
+        let (r, g, b) = color.as_rgb_f32();
+
+        self.options
+            .pens
+            .iter()
+            .enumerate()
+            .map(|(idx, pen)| {
+                let (pr, pg, pb) = pen.as_rgb_f32();
+                let distance = (r - pr).powi(2) + (g - pg).powi(2) + (b - pb).powi(2);
+                (idx + 1, distance)
+            })
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(pen, _)| pen)
+            .unwrap_or(1)
+    }
+
+    /// Pen of the innermost stroke
+    fn pen(&self) -> Option<usize> {
+        self.pen_stack.last().copied().flatten()
+    }
+
+    fn select_pen(&mut self, pen: usize) -> Result<(), HPGLError> {
+        if self.selected_pen != Some(pen) {
+            write!(self.acc, "SP{pen};")?;
+            self.selected_pen = Some(pen);
+        }
+
+        Ok(())
+    }
+
+    fn write_point(&mut self, p: Point2<f32>) -> Result<(), HPGLError> {
+        write!(
+            self.acc,
+            "{},{}",
+            (p.x * self.options.units_per_mm).round() as i32,
+            (p.y * self.options.units_per_mm).round() as i32,
+        )?;
+
+        Ok(())
+    }
+
+    fn write_polyline(&mut self, pen: usize, points: &[Point2<f32>]) -> Result<(), HPGLError> {
+        let Some((first, rest)) = points.split_first() else {
+            return Ok(());
+        };
+
+        self.select_pen(pen)?;
+
+        write!(self.acc, "PU")?;
+        self.write_point(*first)?;
+        write!(self.acc, ";")?;
+
+        if !rest.is_empty() {
+            write!(self.acc, "PD")?;
+            for (idx, p) in rest.iter().enumerate() {
+                if idx > 0 {
+                    write!(self.acc, ",")?;
+                }
+                self.write_point(*p)?;
+            }
+            write!(self.acc, ";")?;
+        }
+
+        Ok(())
+    }
+
+    fn finish(mut self) -> String {
+        self.acc.push_str("PU;SP0;");
+        self.acc
+    }
+}
+
+impl Exporter for HPGLExporter {
+    type Error = HPGLError;
+    const CAN_EXPORT_ELLIPSE: bool = false;
+    const BACKEND: Option<&'static str> = Some("hpgl");
+
+    fn start_style(&mut self, style: StylePosition) -> Result<(), Self::Error> {
+        let pen = match style.stroke {
+            Some(stroke) => Some(self.pen_of(stroke.color())),
+            None => self.pen(),
+        };
+        self.pen_stack.push(pen);
+
+        Ok(())
+    }
+
+    fn end_style(&mut self) -> Result<(), Self::Error> {
+        self.pen_stack.pop();
+        Ok(())
+    }
+
+    /// Pen plotters can't draw images, they are skipped
+    fn export_image(&mut self, _image: ImagePosition) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    /// Curves without stroke are skipped, as the plotter can only draw outlines
+    fn export_curve(&mut self, curve: CurvePosition) -> Result<(), Self::Error> {
+        let Some(pen) = self.pen() else {
+            return Ok(());
+        };
+
+        let points = curve.flatten(self.options.tolerance);
+        self.write_polyline(pen, &points)
+    }
+
+    fn export_text(
+        &mut self,
+        TextPosition {
+            text,
+            font_size,
+            reference_start,
+            direction,
+            ..
+        }: TextPosition,
+    ) -> Result<(), Self::Error> {
+        // Texts are labels, drawn even without stroke
+        self.select_pen(self.pen().unwrap_or(1))?;
+
+        write!(self.acc, "PU")?;
+        self.write_point(reference_start)?;
+        write!(self.acc, ";")?;
+
+        // Character size is in centimeters, whatever the scale of the plotter units
+        let height = font_size * self.options.units_per_mm / PLOTTER_UNITS_PER_MM / 10.;
+        write!(
+            self.acc,
+            "DI{},{};SI{},{};LB{text}\u{3};",
+            direction.x,
+            direction.y,
+            height * 0.5,
+            height,
+        )?;
+
+        Ok(())
+    }
+}
+
+pub fn to_string_with_options(shape: &Shape, options: HPGLOptions) -> Result<String, HPGLError> {
+    let parent_transform = if options.align_to_origin {
+        let bb = shape.local_bounding_box().straigthen();
+        nalgebra::convert(Translation2::new(-bb.left(), -bb.bottom()))
+    } else {
+        Transform2::default()
+    };
+
+    let mut exporter = HPGLExporter::new(options);
+    shape.write_into_exporter(&mut exporter, &parent_transform)?;
+
+    Ok(exporter.finish())
+}
+
+pub fn to_string(shape: &Shape) -> Result<String, HPGLError> {
+    to_string_with_options(shape, HPGLOptions::default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn curves_without_stroke_are_skipped() {
+        let dessin = dessin2!([
+            Rectangle!(fill = Color::BLACK, width = 10., height = 10.),
+            Circle!(radius = 5.),
+        ]);
+
+        let hpgl = to_string(&dessin).unwrap();
+        assert_eq!(hpgl, "IN;PU;SP0;");
+    }
+
+    #[test]
+    fn inherited_stroke() {
+        let shapes = dessin2!([
+            Rectangle!(fill = Color::RED, width = 10., height = 10.),
+            Circle!(radius = 5.),
+        ]);
+        let dessin: Shape = Style::new(shapes).with_stroke((Color::BLACK, 0.3)).into();
+
+        let hpgl = to_string(&dessin).unwrap();
+        assert_eq!(hpgl.matches("PD").count(), 2);
+    }
+
+    #[test]
+    fn closest_pen() {
+        let dessin = dessin2!([
+            Line!(stroke = (Color::RED, 0.3), from = [0., 0.], to = [10., 0.]),
+            Line!(
+                stroke = (Color::BLACK, 0.3),
+                from = [0., 5.],
+                to = [10., 5.]
+            ),
+        ]);
+        let options = HPGLOptions {
+            pens: vec![Color::BLACK, rgb(200, 0, 0)],
+            ..Default::default()
+        };
+
+        let hpgl = to_string_with_options(&dessin, options).unwrap();
+        let red = hpgl.find("SP2;").unwrap();
+        let black = hpgl.find("SP1;").unwrap();
+        assert!(red < black);
+    }
+
+    #[test]
+    fn character_size_follows_units_per_mm() {
+        let label = dessin2!(Text!(fill = Color::BLACK, text = "A", font_size = 10.) > ());
+
+        let hpgl = to_string(&label).unwrap();
+        assert!(hpgl.contains("SI0.5,1;"), "{hpgl}");
+
+        let options = HPGLOptions {
+            units_per_mm: 80.,
+            ..Default::default()
+        };
+        let hpgl = to_string_with_options(&label, options).unwrap();
+        assert!(hpgl.contains("SI1,2;"), "{hpgl}");
+    }
+}
@@ -0,0 +1,217 @@
+//! Golden-image testing of dessins.
+//!
+//! A dessin is rasterized and compared, pixel by pixel, against a reference PNG stored alongside the tests.
+//! Antialiasing may differ between platforms, so a [`Tolerance`] allows small differences.
+//!
+//! References are only written when the `DESSIN_UPDATE_GOLDEN` environment variable is set, see [`dessin::golden`].
+//! A missing reference fails the test.
+//! On failure, an image highlighting the differences is written next to the reference, with a `.diff.png` extension.
+//!
+//! ```no_run
+//! # use dessin::prelude::*;
+//! use dessin_image::golden::{assert_golden, Tolerance};
+//!
+//! let dessin = dessin2!(Circle!(fill = Color::RED, radius = 10.) > ());
+//!
+//! assert_golden(&dessin, "tests/golden/circle.png", Tolerance::default());
+//! ```
+
+use crate::{ImageError, ToImage};
+use ::image::{DynamicImage, Rgba, RgbaImage};
+pub use dessin::golden::UPDATE_GOLDEN_ENV;
+use dessin::{
+    golden::{assert_reference, check_reference, MissingReference},
+    prelude::*,
+};
+use std::{
+    fmt, io,
+    path::{Path, PathBuf},
+};
+
+/// Allowed differences between a rasterized dessin and its reference
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tolerance {
+    /// Maximum difference on a channel for two pixels to be considered the same
+    pub channel: u8,
+    /// Ratio, between 0 and 1, of pixels allowed to be different
+    pub pixels: f32,
+}
+impl Default for Tolerance {
+    fn default() -> Self {
+        Tolerance {
+            channel: 8,
+            pixels: 0.001,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum GoldenError {
+    ImageError(ImageError),
+    IOError(io::Error),
+    MissingReference(MissingReference),
+    DecodeError(::image::ImageError),
+    SizeMismatch {
+        expected: (u32, u32),
+        actual: (u32, u32),
+    },
+    TooManyDifferences {
+        different_pixels: usize,
+        total_pixels: usize,
+        /// Image highlighting the differences, if it could be written
+        diff: Option<PathBuf>,
+    },
+}
+impl fmt::Display for GoldenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GoldenError::SizeMismatch { expected, actual } => write!(
+                f,
+                "Size differs from reference: expected {}x{}, got {}x{}",
+                expected.0, expected.1, actual.0, actual.1
+            ),
+            GoldenError::TooManyDifferences {
+                different_pixels,
+                total_pixels,
+                diff,
+            } => {
+                write!(
+                    f,
+                    "{different_pixels} out of {total_pixels} pixels differ from reference"
+                )?;
+                if let Some(diff) = diff {
+                    write!(f, ", see {}", diff.display())?;
+                }
+                Ok(())
+            }
+            GoldenError::MissingReference(missing) => write!(f, "{missing}"),
+            _ => write!(f, "{self:?}"),
+        }
+    }
+}
+impl From<MissingReference> for GoldenError {
+    fn from(value: MissingReference) -> Self {
+        GoldenError::MissingReference(value)
+    }
+}
+impl From<ImageError> for GoldenError {
+    fn from(value: ImageError) -> Self {
+        GoldenError::ImageError(value)
+    }
+}
+impl From<io::Error> for GoldenError {
+    fn from(value: io::Error) -> Self {
+        GoldenError::IOError(value)
+    }
+}
+impl From<::image::ImageError> for GoldenError {
+    fn from(value: ::image::ImageError) -> Self {
+        GoldenError::DecodeError(value)
+    }
+}
+impl std::error::Error for GoldenError {}
+
+/// Number of different pixels, and an image where they are painted in red over the faded reference
+fn diff(expected: &RgbaImage, actual: &RgbaImage, tolerance: Tolerance) -> (usize, RgbaImage) {
+    let (width, height) = actual.dimensions();
+    let mut diff = RgbaImage::new(width, height);
+    let mut different_pixels = 0;
+
+    for ((e, a), d) in expected
+        .pixels()
+        .zip(actual.pixels())
+        .zip(diff.pixels_mut())
+    {
+        let is_same =
+            e.0.iter()
+                .zip(a.0.iter())
+                .all(|(e, a)| e.abs_diff(*a) <= tolerance.channel);
+
+        *d = if is_same {
+            let [r, g, b, _] = e.0;
+            Rgba([r / 4 + 191, g / 4 + 191, b / 4 + 191, 255])
+        } else {
+            different_pixels += 1;
+            Rgba([255, 0, 0, 255])
+        };
+    }
+
+    (different_pixels, diff)
+}
+
+/// Compare two images, within a [`Tolerance`]
+pub fn compare(
+    expected: &DynamicImage,
+    actual: &DynamicImage,
+    tolerance: Tolerance,
+) -> Result<(), GoldenError> {
+    compare_or_write_diff(expected, actual, tolerance, None)
+}
+
+fn compare_or_write_diff(
+    expected: &DynamicImage,
+    actual: &DynamicImage,
+    tolerance: Tolerance,
+    diff_path: Option<PathBuf>,
+) -> Result<(), GoldenError> {
+    let expected = expected.to_rgba8();
+    let actual = actual.to_rgba8();
+
+    if expected.dimensions() != actual.dimensions() {
+        return Err(GoldenError::SizeMismatch {
+            expected: expected.dimensions(),
+            actual: actual.dimensions(),
+        });
+    }
+
+    let (different_pixels, diff_image) = diff(&expected, &actual, tolerance);
+    let total_pixels = expected.width() as usize * expected.height() as usize;
+
+    if different_pixels as f32 <= tolerance.pixels * total_pixels as f32 {
+        return Ok(());
+    }
+
+    let diff = diff_path.and_then(|path| diff_image.save(&path).ok().map(|_| path));
+
+    Err(GoldenError::TooManyDifferences {
+        different_pixels,
+        total_pixels,
+        diff,
+    })
+}
+
+/// Rasterize a dessin and compare it to a reference PNG.
+///
+/// The reference is written instead if [`UPDATE_GOLDEN_ENV`] is set, and is an error if it does not exist otherwise.
+pub fn check_golden<P: AsRef<Path>>(
+    shape: &Shape,
+    reference: P,
+    tolerance: Tolerance,
+) -> Result<(), GoldenError> {
+    let actual = shape.rasterize()?;
+
+    check_reference(
+        reference.as_ref(),
+        |reference| Ok(actual.save(reference)?),
+        |reference| {
+            let expected = ::image::open(reference)?;
+            compare_or_write_diff(
+                &expected,
+                &actual,
+                tolerance,
+                Some(reference.with_extension("diff.png")),
+            )
+        },
+    )
+}
+
+/// Same as [`check_golden`], but panics with a readable message
+#[track_caller]
+pub fn assert_golden<P: AsRef<Path>>(shape: &Shape, reference: P, tolerance: Tolerance) {
+    let reference = reference.as_ref();
+    assert_reference(
+        "image",
+        reference,
+        check_golden(shape, reference, tolerance),
+    );
+}
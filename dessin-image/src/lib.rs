@@ -1,12 +1,14 @@
+pub mod golden;
+
 use ::image::{DynamicImage, RgbaImage};
 use dessin::{
-    export::{Export, Exporter},
+    export::{hint, inherited_style, Export, Exporter, SKIP_HINT},
     prelude::*,
 };
 use nalgebra::{Point2, Transform2, Translation2, Vector2};
 use raqote::{
-    DrawOptions, DrawTarget, LineCap, LineJoin, PathBuilder, Point, SolidSource, Source,
-    StrokeStyle,
+    DrawOptions, DrawTarget, Gradient, GradientStop, Path, PathBuilder, PathOp, Point, SolidSource,
+    Source, Spread, StrokeStyle, Winding,
 };
 use std::fmt;
 
@@ -16,6 +18,7 @@ pub enum ImageError {
     CurveHasNoStartingPoint(CurvePosition),
     FontLoadingError(font_kit::error::FontLoadingError),
     ImageError,
+    PngError(png::EncodingError),
 }
 impl fmt::Display for ImageError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -27,6 +30,11 @@ impl From<fmt::Error> for ImageError {
         ImageError::WriteError(value)
     }
 }
+impl From<png::EncodingError> for ImageError {
+    fn from(value: png::EncodingError) -> Self {
+        ImageError::PngError(value)
+    }
+}
 impl std::error::Error for ImageError {}
 
 #[derive(Default)]
@@ -37,6 +45,8 @@ pub struct ImageOptions {
 pub struct ImageExporter {
     buffer: DrawTarget,
     style: Vec<StylePosition>,
+    /// Path of the [`Clip`] being written, which is neither filled nor stroked
+    clip: Option<Vec<PathOp>>,
 }
 
 impl ImageExporter {
@@ -44,6 +54,7 @@ impl ImageExporter {
         ImageExporter {
             buffer: DrawTarget::new(width as i32, height as i32),
             style: vec![],
+            clip: None,
         }
     }
 
@@ -51,35 +62,140 @@ impl ImageExporter {
         self.buffer
     }
 
-    fn style(&self) -> StylePosition {
-        let mut acc = StylePosition {
-            stroke: None,
-            fill: None,
-        };
+    /// `shape` drawn alone on a transparent buffer of the same size, with the current styles
+    fn render_apart(
+        &mut self,
+        shape: &Shape,
+        transform: &Transform2<f32>,
+    ) -> Result<DrawTarget, ImageError> {
+        let blank = DrawTarget::new(self.buffer.width(), self.buffer.height());
+        let buffer = std::mem::replace(&mut self.buffer, blank);
+        let written = shape.write_into_exporter(self, transform);
+        let rendered = std::mem::replace(&mut self.buffer, buffer);
+        written.map(|_| rendered)
+    }
+}
 
-        for style in self.style.iter().rev() {
-            match (acc.fill, style.fill) {
-                (None, Some(s)) => acc.fill = Some(s),
-                _ => {}
-            }
+/// Luminance of a pixel, from 0 to 255, as the opacity given by a mask
+fn luminance(pixel: u32) -> u32 {
+    // Red and blue are swapped in the buffer, and colors are premultiplied by their alpha
+    let (b, g, r) = ((pixel >> 16) & 0xFF, (pixel >> 8) & 0xFF, pixel & 0xFF);
+    (2126 * r + 7152 * g + 722 * b) / 10000
+}
 
-            match (acc.stroke, style.stroke) {
-                (None, Some(s)) => acc.stroke = Some(s),
-                _ => {}
-            }
+/// `source` faded by `opacity`, from 0 to 255, and painted over `destination`, both premultiplied
+fn composite(source: u32, opacity: u32, destination: u32) -> u32 {
+    let alpha = (source >> 24) * opacity / 255;
+    (0..4).map(|channel| channel * 8).fold(0, |acc, shift| {
+        let source = ((source >> shift) & 0xFF) * opacity / 255;
+        let destination = ((destination >> shift) & 0xFF) * (255 - alpha) / 255;
+        acc | ((source + destination).min(255) << shift)
+    })
+}
 
-            if acc.fill.is_some() && acc.fill.is_some() {
-                break;
-            }
-        }
+/// Source painting `color`, whose red and blue are swapped as the buffer is read as RGBA
+fn solid(color: Color) -> Source<'static> {
+    let (r, g, b, a) = color.rgba();
+    Source::Solid(SolidSource { r: b, g, b: r, a })
+}
 
-        acc
+/// Source painting `fill`, with gradients padded past their ends like in SVG
+fn source(fill: &Fill) -> Source<'static> {
+    let gradient = |stops: &GradientStops| Gradient {
+        stops: stops
+            .as_slice()
+            .iter()
+            .map(|(position, color)| {
+                let (r, g, b, a) = color.rgba();
+                GradientStop {
+                    position: *position,
+                    color: raqote::Color::new(a, b, g, r),
+                }
+            })
+            .collect(),
+    };
+
+    match fill {
+        Fill::Color(_) | Fill::Pattern { .. } | Fill::Image { .. } => solid(fill.color()),
+        Fill::LinearGradient { start, end, stops } => Source::new_linear_gradient(
+            gradient(stops),
+            Point::new(start.x, start.y),
+            Point::new(end.x, end.y),
+            Spread::Pad,
+        ),
+        Fill::RadialGradient {
+            center,
+            radius,
+            stops,
+        } => Source::new_radial_gradient(
+            gradient(stops),
+            Point::new(center.x, center.y),
+            *radius,
+            Spread::Pad,
+        ),
     }
 }
 
 impl Exporter for ImageExporter {
     type Error = ImageError;
     const CAN_EXPORT_ELLIPSE: bool = false;
+    const BACKEND: Option<&'static str> = Some("image");
+
+    /// [Clips][Clip] are drawn inside the path of the clipping shape,
+    /// and [masks][Mask] are drawn apart, and composited with the luminance of their mask
+    fn export_subtree(
+        &mut self,
+        shape: &Shape,
+        parent_transform: &Transform2<f32>,
+    ) -> Result<bool, Self::Error> {
+        let Shape::Group(group) = shape else {
+            return Ok(false);
+        };
+        if hint(&group.metadata, "image", SKIP_HINT) == Some("true") {
+            return Ok(false);
+        }
+        let transform = parent_transform * group.local_transform;
+
+        if let Some((clipped, clipped_by)) = Clip::parts(group).filter(|_| self.clip.is_none()) {
+            self.clip = Some(vec![]);
+            let written = clipped_by.write_into_exporter(self, &transform);
+            let ops = self.clip.take().unwrap_or_default();
+            written?;
+
+            self.buffer.push_clip(&Path {
+                ops,
+                winding: Winding::NonZero,
+            });
+            let written = clipped.write_into_exporter(self, &transform);
+            self.buffer.pop_clip();
+            written?;
+
+            return Ok(true);
+        }
+
+        let Some((masked, mask)) = Mask::parts(group) else {
+            return Ok(false);
+        };
+        let mode = Mask::mode_of(group);
+        let mask = self.render_apart(mask, &transform)?;
+        let masked = self.render_apart(masked, &transform)?;
+
+        for ((pixel, masked), mask) in self
+            .buffer
+            .get_data_mut()
+            .iter_mut()
+            .zip(masked.get_data())
+            .zip(mask.get_data())
+        {
+            let opacity = match mode {
+                MaskMode::Luminance => luminance(*mask),
+                MaskMode::Alpha => *mask >> 24,
+            };
+            *pixel = composite(*masked, opacity, *pixel);
+        }
+
+        Ok(true)
+    }
 
     fn start_style(&mut self, style: StylePosition) -> Result<(), Self::Error> {
         self.style.push(style);
@@ -163,58 +279,47 @@ impl Exporter for ImageExporter {
             path.close()
         }
 
-        let path = path.finish();
+        let mut path = path.finish();
+        if curve.fill_rule == FillRule::EvenOdd {
+            path.winding = Winding::EvenOdd;
+        }
+        if let Some(clip) = &mut self.clip {
+            clip.extend(path.ops);
+            return Ok(());
+        }
 
-        let style = self.style();
+        let style = inherited_style(&self.style);
 
-        if let Some(Fill::Color(c)) = style.fill {
-            let (r, g, b, a) = c.rgba();
-            self.buffer.fill(
-                &path,
-                &Source::Solid(SolidSource { r: b, g, b: r, a }),
-                &DrawOptions::new(),
-            )
+        if let Some(fill) = style.fill {
+            self.buffer.fill(&path, &source(&fill), &DrawOptions::new())
         }
 
-        match style.stroke {
-            Some(Stroke::Full { color, width }) => {
-                let (r, g, b, a) = color.rgba();
-                self.buffer.stroke(
-                    &path,
-                    &Source::Solid(SolidSource { r: b, g, b: r, a }),
-                    &StrokeStyle {
-                        cap: LineCap::Butt,
-                        join: LineJoin::Miter,
-                        width,
-                        miter_limit: 2.,
-                        dash_array: vec![],
-                        dash_offset: 0.,
+        if let Some(stroke) = style.stroke {
+            let (dash_array, dash_offset) = match stroke.dashes() {
+                Some((dashes, offset)) => (dashes.as_slice().to_vec(), offset),
+                None => (vec![], 0.),
+            };
+            self.buffer.stroke(
+                &path,
+                &solid(stroke.color()),
+                &StrokeStyle {
+                    cap: match stroke.cap() {
+                        LineCap::Butt => raqote::LineCap::Butt,
+                        LineCap::Round => raqote::LineCap::Round,
+                        LineCap::Square => raqote::LineCap::Square,
                     },
-                    &DrawOptions::new(),
-                );
-            }
-            Some(Stroke::Dashed {
-                color,
-                width,
-                on,
-                off,
-            }) => {
-                let (r, g, b, a) = color.rgba();
-                self.buffer.stroke(
-                    &path,
-                    &Source::Solid(SolidSource { r: b, g, b: r, a }),
-                    &StrokeStyle {
-                        cap: LineCap::Butt,
-                        join: LineJoin::Miter,
-                        width,
-                        miter_limit: 2.,
-                        dash_array: vec![on, off],
-                        dash_offset: 0.,
+                    join: match stroke.join() {
+                        LineJoin::Miter => raqote::LineJoin::Miter,
+                        LineJoin::Round => raqote::LineJoin::Round,
+                        LineJoin::Bevel => raqote::LineJoin::Bevel,
                     },
-                    &DrawOptions::new(),
-                );
-            }
-            None => {}
+                    width: stroke.width(),
+                    miter_limit: stroke.miter_limit(),
+                    dash_array,
+                    dash_offset,
+                },
+                &DrawOptions::new(),
+            );
         }
 
         Ok(())
@@ -233,6 +338,10 @@ impl Exporter for ImageExporter {
             font,
         }: TextPosition,
     ) -> Result<(), Self::Error> {
+        if self.clip.is_some() {
+            return Ok(());
+        }
+
         let font = font.clone().unwrap_or_default();
         let fg = dessin::font::get(font);
         let font = fg.get(font_weight).as_bytes();
@@ -240,11 +349,9 @@ impl Exporter for ImageExporter {
         //dt.set_transform(&Transform::create_translation(50.0, 0.0));
         // dt.set_transform(&Transform::rotation(euclid::Angle::degrees(15.0)));
 
-        let color = match self.style().fill {
-            Some(Fill::Color(c)) => c,
-            None => return Ok(()),
+        let Some(fill) = inherited_style(&self.style).fill else {
+            return Ok(());
         };
-        let (r, g, b, a) = color.rgba();
 
         let font = font_kit::loader::Loader::from_bytes(std::sync::Arc::new(font.to_vec()), 0)
             .map_err(|e| ImageError::FontLoadingError(e))?;
@@ -253,7 +360,7 @@ impl Exporter for ImageExporter {
             font_size,
             text,
             Point::new(reference_start.x, reference_start.y),
-            &Source::Solid(SolidSource { r: b, g, b: r, a }),
+            &source(&fill),
             &DrawOptions::new(),
         );
 
@@ -261,8 +368,93 @@ impl Exporter for ImageExporter {
     }
 }
 
+/// Rasterize a dessin, one pixel per unit.
+///
+/// Fills, gradients included, and dashed strokes are painted like in SVG.
+///
+/// ```
+/// # use dessin::prelude::*;
+/// use dessin_image::ToImage;
+///
+/// let bar = dessin2!(Rectangle!(
+///     fill = Fill::LinearGradient {
+///         start: [-50., 0.].into(),
+///         end: [50., 0.].into(),
+///         stops: [(0., Color::BLACK), (1., Color::WHITE)].into(),
+///     },
+///     width = 100.,
+///     height = 10.,
+/// ) > ());
+///
+/// let image = bar.rasterize().unwrap().into_rgba8();
+/// assert!(image.get_pixel(1, 5)[0] < 20);
+/// assert!(image.get_pixel(98, 5)[0] > 235);
+/// ```
 pub trait ToImage {
     fn rasterize(&self) -> Result<DynamicImage, ImageError>;
+
+    /// Rasterize a dessin for printing, one unit being one millimeter.
+    ///
+    /// The origin of the dessin is at the center of the image, like on a PDF page,
+    /// and everything drawn, stroke widths and font sizes included, is scaled to the resolution of `size`.
+    ///
+    /// ```
+    /// # use dessin::prelude::*;
+    /// use dessin_image::{PrintSize, ToImage};
+    ///
+    /// let label = dessin2!(Rectangle!(
+    ///     stroke = (Color::BLACK, 1.),
+    ///     width = 98.,
+    ///     height = 78.,
+    /// ) > ());
+    ///
+    /// // 100×80 mm at 600 dpi
+    /// let size = PrintSize::new(100., 80., 600.);
+    /// let image = label.rasterize_for_print(size).unwrap().into_rgba8();
+    /// assert_eq!(image.dimensions(), (2362, 1890));
+    ///
+    /// // The 1 mm wide border is about 24 pixels wide, half a millimeter from the edges
+    /// assert_eq!(image.get_pixel(1181, 2)[3], 0);
+    /// assert_eq!(image.get_pixel(1181, 20)[3], 255);
+    /// assert_eq!(image.get_pixel(1181, 40)[3], 0);
+    /// ```
+    fn rasterize_for_print(&self, size: PrintSize) -> Result<DynamicImage, ImageError>;
+}
+
+/// Physical size and resolution of an image meant to be printed
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PrintSize {
+    /// Width, in millimeters
+    pub width: f32,
+    /// Height, in millimeters
+    pub height: f32,
+    /// Dots per inch
+    pub dpi: f32,
+}
+impl PrintSize {
+    /// Millimeters in an inch
+    pub const MM_PER_INCH: f32 = 25.4;
+
+    #[inline]
+    pub fn new(width: f32, height: f32, dpi: f32) -> Self {
+        PrintSize { width, height, dpi }
+    }
+
+    /// Pixels in a millimeter
+    #[inline]
+    pub fn pixels_per_mm(&self) -> f32 {
+        self.dpi / Self::MM_PER_INCH
+    }
+
+    /// Width and height of the image, in pixels
+    #[inline]
+    pub fn pixels(&self) -> (u32, u32) {
+        let pixels_per_mm = self.pixels_per_mm();
+        (
+            (self.width * pixels_per_mm).round().max(1.) as u32,
+            (self.height * pixels_per_mm).round().max(1.) as u32,
+        )
+    }
 }
 
 impl ToImage for Shape {
@@ -278,23 +470,92 @@ impl ToImage for Shape {
 
         let width = bb.width().ceil() as u32;
         let height = bb.height().ceil() as u32;
-        let mut exporter = ImageExporter::new(width, height);
 
-        self.write_into_exporter(&mut exporter, &transform)?;
+        render(self, &transform, width, height)
+    }
 
-        let raw: Vec<u32> = exporter.finalize().into_vec();
-        let raw: Vec<u8> = unsafe {
-            let cap = raw.capacity();
-            let len = raw.len();
-            let ptr = Box::into_raw(raw.into_boxed_slice());
+    fn rasterize_for_print(&self, size: PrintSize) -> Result<DynamicImage, ImageError> {
+        let (width, height) = size.pixels();
 
-            Vec::from_raw_parts(ptr.cast(), len * 4, cap * 4)
-        };
+        let translation = Translation2::new(width as f32 / 2., height as f32 / 2.);
+        let pixels_per_mm = size.pixels_per_mm();
+        let scale = nalgebra::Scale2::new(pixels_per_mm, -pixels_per_mm);
+        let transform = nalgebra::convert::<_, Transform2<f32>>(translation)
+            * nalgebra::convert::<_, Transform2<f32>>(scale);
 
-        let img = DynamicImage::ImageRgba8(
-            RgbaImage::from_raw(width, height, raw).ok_or(ImageError::ImageError)?,
-        );
+        render(self, &transform, width, height)
+    }
+}
+
+/// `shape` drawn with `transform` on a transparent image of `width` by `height` pixels
+fn render(
+    shape: &Shape,
+    transform: &Transform2<f32>,
+    width: u32,
+    height: u32,
+) -> Result<DynamicImage, ImageError> {
+    let mut exporter = ImageExporter::new(width, height);
+
+    shape.write_into_exporter(&mut exporter, transform)?;
+
+    let raw: Vec<u32> = exporter.finalize().into_vec();
+    let raw: Vec<u8> = unsafe {
+        let cap = raw.capacity();
+        let len = raw.len();
+        let ptr = Box::into_raw(raw.into_boxed_slice());
 
-        Ok(img)
+        Vec::from_raw_parts(ptr.cast(), len * 4, cap * 4)
+    };
+
+    let img = DynamicImage::ImageRgba8(
+        RgbaImage::from_raw(width, height, raw).ok_or(ImageError::ImageError)?,
+    );
+
+    Ok(img)
+}
+
+/// Options of [`to_png`]
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PngOptions {
+    /// Embed the ICC profile of sRGB, the color space of dessin colors.
+    ///
+    /// Calibrated viewers then show the PNG like the PDF exported with the sRGB color space,
+    /// instead of guessing what its untagged colors are.
+    pub srgb_profile: bool,
+}
+
+/// `image` encoded as a PNG
+///
+/// ```
+/// # use dessin::prelude::*;
+/// use dessin_image::{PngOptions, ToImage};
+///
+/// let image = dessin2!(Circle!(fill = Color::RED, radius = 10.) > ())
+///     .rasterize()
+///     .unwrap();
+/// let png = dessin_image::to_png(&image, PngOptions { srgb_profile: true }).unwrap();
+///
+/// let decoder = png::Decoder::new(png.as_slice());
+/// let reader = decoder.read_info().unwrap();
+/// assert_eq!(
+///     reader.info().icc_profile.as_deref(),
+///     Some(dessin::color_profile::srgb().as_slice()),
+/// );
+/// ```
+pub fn to_png(image: &DynamicImage, options: PngOptions) -> Result<Vec<u8>, ImageError> {
+    let image = image.to_rgba8();
+
+    let mut info = png::Info::with_size(image.width(), image.height());
+    info.color_type = png::ColorType::Rgba;
+    info.bit_depth = png::BitDepth::Eight;
+    if options.srgb_profile {
+        info.icc_profile = Some(dessin::color_profile::srgb().into());
     }
+
+    let mut bytes = vec![];
+    let mut writer = png::Encoder::with_info(&mut bytes, info)?.write_header()?;
+    writer.write_image_data(&image)?;
+    writer.finish()?;
+
+    Ok(bytes)
 }
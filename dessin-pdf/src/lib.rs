@@ -1,14 +1,26 @@
-use dessin::font::FontRef;
+use ::image::DynamicImage;
+use dessin::font::{EmbeddingPermission, FontRef};
 use dessin::{
-    export::{Export, Exporter},
+    coordinates::CoordinateSystem,
+    debug::DebugOverlay,
+    export::{
+        hint, with_backdrop, with_scale_statement, with_stamps, Background, Export, Exporter,
+        RestrictedFontPolicy, ScaleStatement, Stamp, SKIP_HINT,
+    },
     prelude::*,
+    template::{generate_batch, BatchError, TemplateError, TemplateValues},
 };
-use nalgebra::Translation2;
+use nalgebra::{Isometry2, Point2, Transform2, Translation2, Unit, Vector2};
 use printpdf::{
-    BuiltinFont, IndirectFontRef, Line, Mm, PdfDocument, PdfDocumentReference, PdfLayerReference,
-    Point,
+    Actions, BorderArray, CustomPdfConformance, IndirectFontRef, Line, LinkAnnotation, Mm,
+    PdfConformance, PdfDocument, PdfDocumentReference, PdfLayerReference, Point,
+};
+use std::{
+    cell::Cell,
+    collections::HashMap,
+    fmt,
+    io::{BufWriter, Write},
 };
-use std::{collections::HashMap, fmt};
 
 #[derive(Debug)]
 pub enum PDFError {
@@ -16,7 +28,23 @@ pub enum PDFError {
     WriteError(fmt::Error),
     CurveHasNoStartingPoint(Curve),
     UnknownBuiltinFont(String),
+    InvalidFont(String),
+    /// The license of the font doesn't allow embedding it, see [`PDFOptions::restricted_fonts`]
+    RestrictedFont {
+        font: FontRef,
+        font_weight: FontWeight,
+    },
     OrphelinLayer,
+    EmptyDocument,
+    Outline(printpdf::lopdf::Error),
+    /// Completing the saved document, with its transparency or output intent, failed
+    Save(printpdf::lopdf::Error),
+    /// Re-encoding an image as JPEG failed
+    ImageEncoding(::image::ImageError),
+    Template {
+        record: usize,
+        error: TemplateError,
+    },
 }
 impl From<fmt::Error> for PDFError {
     fn from(e: fmt::Error) -> Self {
@@ -28,19 +56,505 @@ impl From<printpdf::Error> for PDFError {
         PDFError::PrintPDF(e)
     }
 }
+impl From<BatchError<PDFError>> for PDFError {
+    fn from(e: BatchError<PDFError>) -> Self {
+        match e {
+            BatchError::Template { record, error } => PDFError::Template { record, error },
+            BatchError::Export { error, .. } => error,
+        }
+    }
+}
 
-type PDFFontHolder = HashMap<(FontRef, FontWeight), IndirectFontRef>;
+/// Fonts embedded in a PDF document, each font and weight only once.
+///
+/// Pass the holder returned by an export to the next page of the same document, through [`PDFOptions::used_font`],
+/// so pages share their fonts.
+/// Embedded fonts are subset to the glyphs used in the document when it is saved.
+///
+/// ```
+/// # use dessin::prelude::*;
+/// use dessin_pdf::PDFDocument;
+///
+/// let page = |text: &str| dessin2!(Text!(fill = Color::BLACK, text = text) > ());
+///
+/// let pdf = PDFDocument::new()
+///     .with_page_sized(page("Hello"), (100., 100.))
+///     .with_page_sized(page("World"), (100., 100.))
+///     .to_bytes()
+///     .unwrap();
+///
+/// let embedded = pdf.windows(10).filter(|w| w == b"/FontFile2").count();
+/// assert_eq!(embedded, 1);
+/// ```
+#[derive(Default, Clone)]
+pub struct PDFFontHolder {
+    fonts: HashMap<(FontRef, FontWeight), EmbeddedFont>,
+}
+impl PDFFontHolder {
+    pub fn new() -> Self {
+        PDFFontHolder::default()
+    }
 
-#[derive(Default)]
+    /// Reference to `font` in `doc`, embedding it on first use
+    pub fn get_or_insert(
+        &mut self,
+        doc: &PdfDocumentReference,
+        font: FontRef,
+        font_weight: FontWeight,
+    ) -> Result<IndirectFontRef, PDFError> {
+        Ok(self
+            .embed(doc, font, font_weight, RestrictedFontPolicy::Warn)?
+            .reference
+            .clone())
+    }
+
+    fn embed(
+        &mut self,
+        doc: &PdfDocumentReference,
+        font: FontRef,
+        font_weight: FontWeight,
+        restricted_fonts: RestrictedFontPolicy,
+    ) -> Result<&EmbeddedFont, PDFError> {
+        let key = (font, font_weight);
+        if !self.fonts.contains_key(&key) {
+            let font_group = font::get(key.0.clone());
+            let embedded = font_group.get(font_weight);
+            if restricted_fonts == RestrictedFontPolicy::Deny
+                && embedded.embedding_permission() == Some(EmbeddingPermission::Restricted)
+            {
+                return Err(PDFError::RestrictedFont {
+                    font: key.0,
+                    font_weight,
+                });
+            }
+            let bytes = embedded.as_bytes();
+
+            let metrics = fontdue::Font::from_bytes(bytes, fontdue::FontSettings::default())
+                .map_err(|e| PDFError::InvalidFont(e.to_string()))?;
+            let reference = doc.add_external_font(bytes)?;
+            self.fonts
+                .insert(key.clone(), EmbeddedFont { reference, metrics });
+        }
+
+        Ok(&self.fonts[&key])
+    }
+
+    /// Number of embedded fonts
+    pub fn len(&self) -> usize {
+        self.fonts.len()
+    }
+
+    /// Whether no font is embedded yet
+    pub fn is_empty(&self) -> bool {
+        self.fonts.is_empty()
+    }
+}
+
+/// Font embedded in a document, with its metrics to measure texts
+#[derive(Clone)]
+struct EmbeddedFont {
+    reference: IndirectFontRef,
+    metrics: fontdue::Font,
+}
+impl EmbeddedFont {
+    /// Width of `text`, as written by a PDF viewer which doesn't kern
+    fn width(&self, text: &str, font_size: f32) -> f32 {
+        text.chars()
+            .map(|c| self.metrics.metrics(c, font_size).advance_width)
+            .sum()
+    }
+}
+
+/// Color space of the fills and strokes of a document
+///
+/// ```
+/// # use dessin::prelude::*;
+/// use dessin_pdf::{ColorSpace, PDFDocument, PDFOptions};
+///
+/// let pdf = PDFDocument::new()
+///     .with_options(PDFOptions {
+///         color_space: ColorSpace::Cmyk { icc_profile: None },
+///         ..Default::default()
+///     })
+///     .with_page_sized(dessin2!(Circle!(fill = Color::RED, radius = 10.) > ()), (30., 30.))
+///     .to_bytes()
+///     .unwrap();
+///
+/// let pdf = printpdf::lopdf::Document::load_mem(&pdf).unwrap();
+/// assert!(pdf.catalog().unwrap().has(b"OutputIntents"));
+///
+/// let page = pdf.get_pages()[&1];
+/// let content = pdf.get_and_decode_page_content(page).unwrap();
+/// assert!(content.operations.iter().any(|op| op.operator == "k"));
+/// assert!(!content.operations.iter().any(|op| op.operator == "rg"));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum ColorSpace {
+    /// Colors as they are, in RGB
+    #[default]
+    Rgb,
+    /// Colors as they are, in RGB, tagged as sRGB.
+    ///
+    /// The document gets an sRGB output intent, so calibrated viewers show its colors,
+    /// images included, like those of the PNGs and SVGs of the same dessin,
+    /// instead of guessing what its untagged RGB is.
+    ///
+    /// ```
+    /// # use dessin::prelude::*;
+    /// use dessin_pdf::{ColorSpace, PDFDocument, PDFOptions};
+    ///
+    /// let pdf = PDFDocument::new()
+    ///     .with_options(PDFOptions {
+    ///         color_space: ColorSpace::Srgb,
+    ///         ..Default::default()
+    ///     })
+    ///     .with_page_sized(dessin2!(Circle!(fill = Color::RED, radius = 10.) > ()), (30., 30.))
+    ///     .to_bytes()
+    ///     .unwrap();
+    ///
+    /// let pdf = printpdf::lopdf::Document::load_mem(&pdf).unwrap();
+    /// let intents = pdf.catalog().unwrap().get(b"OutputIntents").unwrap().as_array().unwrap();
+    /// let intent = intents[0].as_dict().unwrap();
+    /// assert_eq!(intent.get(b"S").unwrap().as_name_str().unwrap(), "GTS_PDFA1");
+    ///
+    /// let profile = intent.get(b"DestOutputProfile").unwrap().as_reference().unwrap();
+    /// let profile = pdf.get_object(profile).unwrap().as_stream().unwrap();
+    /// assert_eq!(profile.dict.get(b"N").unwrap().as_i64().unwrap(), 3);
+    /// ```
+    Srgb,
+    /// Colors converted to CMYK, as asked by print providers.
+    ///
+    /// The document gets an output intent with `icc_profile`, describing the printing condition,
+    /// or the Coated FOGRA39 profile if `None`.
+    /// A custom profile is only embedded by [`PDFDocument::to_bytes`], as the document is saved.
+    ///
+    /// Images are left in RGB.
+    ///
+    /// ```
+    /// # use dessin::prelude::*;
+    /// use dessin_pdf::{ColorSpace, PDFDocument, PDFOptions};
+    ///
+    /// // Not a real profile, only recognizable bytes
+    /// let icc_profile = b"custom printing condition".to_vec();
+    ///
+    /// let pdf = PDFDocument::new()
+    ///     .with_options(PDFOptions {
+    ///         color_space: ColorSpace::Cmyk {
+    ///             icc_profile: Some(icc_profile.clone()),
+    ///         },
+    ///         ..Default::default()
+    ///     })
+    ///     .with_page_sized(dessin2!(Circle!(fill = Color::RED, radius = 10.) > ()), (30., 30.))
+    ///     .to_bytes()
+    ///     .unwrap();
+    ///
+    /// let pdf = printpdf::lopdf::Document::load_mem(&pdf).unwrap();
+    /// let intents = pdf.catalog().unwrap().get(b"OutputIntents").unwrap().as_array().unwrap();
+    /// let intent = intents[0].as_dict().unwrap();
+    /// assert!(!intent.has(b"DestinationOutputProfile"));
+    ///
+    /// let profile = intent.get(b"DestOutputProfile").unwrap().as_reference().unwrap();
+    /// let profile = pdf.get_object(profile).unwrap().as_stream().unwrap();
+    /// assert_eq!(profile.content, icc_profile);
+    /// ```
+    Cmyk {
+        /// ICC profile of the printing condition
+        icc_profile: Option<Vec<u8>>,
+    },
+}
+impl ColorSpace {
+    /// `color` in this color space, its alpha put aside.
+    ///
+    /// RGB is converted to CMYK without any color management, by extracting the black first.
+    ///
+    /// ```
+    /// # use dessin::prelude::*;
+    /// use dessin_pdf::ColorSpace;
+    ///
+    /// let cmyk = ColorSpace::Cmyk { icc_profile: None };
+    /// assert!(matches!(
+    ///     cmyk.convert(Color::RED),
+    ///     printpdf::Color::Cmyk(printpdf::Cmyk { c: 0., m: 1., y: 1., k: 0., .. })
+    /// ));
+    /// ```
+    pub fn convert(&self, color: Color) -> printpdf::Color {
+        let (r, g, b) = color.as_rgb_f32();
+        match self {
+            ColorSpace::Rgb | ColorSpace::Srgb => printpdf::Color::Rgb(printpdf::Rgb {
+                r,
+                g,
+                b,
+                icc_profile: None,
+            }),
+            ColorSpace::Cmyk { .. } => {
+                let k = 1. - r.max(g).max(b);
+                let ink = |v: f32| if k < 1. { (1. - v - k) / (1. - k) } else { 0. };
+                printpdf::Color::Cmyk(printpdf::Cmyk {
+                    c: ink(r),
+                    m: ink(g),
+                    y: ink(b),
+                    k,
+                    icc_profile: None,
+                })
+            }
+        }
+    }
+}
+
+/// Options of a PDF export
+///
+/// Photos are usually much larger than needed on a page,
+/// downsampling and re-encoding them keeps documents small:
+///
+/// ```
+/// # use dessin::prelude::*;
+/// use dessin::image::{DynamicImage, Rgb, RgbImage};
+/// use dessin_pdf::{PDFDocument, PDFOptions};
+///
+/// let photo = RgbImage::from_fn(2000, 2000, |x, y| Rgb([(x % 256) as u8, (y % 256) as u8, 128]));
+/// let page = dessin2!(Image(image = DynamicImage::ImageRgb8(photo), scale = [50., 50.]) > ());
+///
+/// let export = |options: PDFOptions| {
+///     PDFDocument::new()
+///         .with_options(options)
+///         .with_page(page.clone())
+///         .to_bytes()
+///         .unwrap()
+///         .len()
+/// };
+///
+/// let original = export(PDFOptions::default());
+/// let compressed = export(PDFOptions {
+///     compress: true,
+///     image_max_dpi: Some(150.),
+///     jpeg_quality: Some(80),
+///     ..Default::default()
+/// });
+/// assert!(compressed * 10 < original);
+/// ```
+#[derive(Default, Clone)]
 pub struct PDFOptions {
     pub size: Option<(f32, f32)>,
+    /// Fonts already embedded in the document
     pub used_font: PDFFontHolder,
+    /// Draw layout information on top of the dessin
+    pub debug: Option<DebugOverlay>,
+    /// Drawn behind the dessin, on the whole page
+    pub background: Option<Background>,
+    /// Frame around the page
+    pub border: Option<Stroke>,
+    /// Scale and unit of the dessin, stamped in the bottom right corner of the page
+    pub scale_statement: Option<ScaleStatement>,
+    /// Stamped on every page, such as a DRAFT watermark.
+    ///
+    /// ```
+    /// # use dessin::prelude::*;
+    /// use dessin::export::Stamp;
+    /// use dessin_pdf::{PDFDocument, PDFOptions};
+    ///
+    /// let cover = dessin2!(Text!(fill = Color::BLACK, text = "Report", font_size = 20.) > ());
+    /// let chapter = dessin2!(Text!(fill = Color::BLACK, text = "Chapter 1") > ());
+    ///
+    /// let pdf = PDFDocument::new()
+    ///     .with_options(PDFOptions {
+    ///         stamps: vec![
+    ///             Stamp::watermark("DRAFT").with_below(true),
+    ///             Stamp::new(dessin2!(Text!(fill = Color::RED, text = "CONFIDENTIAL", font_size = 5.) > ()))
+    ///                 .with_position(BoxAnchor::Top)
+    ///                 .with_margin(5.),
+    ///         ],
+    ///         ..Default::default()
+    ///     })
+    ///     .with_page_sized(cover, (210., 297.))
+    ///     .with_page_sized(chapter, (210., 297.))
+    ///     .build()
+    ///     .unwrap()
+    ///     .save_to_bytes()
+    ///     .unwrap();
+    ///
+    /// let pdf = printpdf::lopdf::Document::load_mem(&pdf).unwrap();
+    /// assert_eq!(pdf.get_pages().len(), 2);
+    /// ```
+    pub stamps: Vec<Stamp>,
+    /// Whether fonts whose license doesn't allow embedding them fail the export.
+    ///
+    /// ```
+    /// # use dessin::prelude::*;
+    /// use dessin::{export::RestrictedFontPolicy, font::{self, Font, FontGroup}};
+    /// use dessin_pdf::{PDFDocument, PDFError, PDFOptions};
+    ///
+    /// // Font whose OS/2 table restricts embedding
+    /// let mut bytes = vec![0, 1, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0];
+    /// bytes.extend(b"OS/2\0\0\0\0");
+    /// bytes.extend(28_u32.to_be_bytes());
+    /// bytes.extend(10_u32.to_be_bytes());
+    /// bytes.extend([0, 4, 0, 0, 0, 0, 0, 0, 0, 2]);
+    /// let restricted = font::add_font("Restricted", FontGroup {
+    ///     regular: Font::TTF(bytes),
+    ///     bold: None,
+    ///     italic: None,
+    ///     bold_italic: None,
+    /// });
+    ///
+    /// let pdf = PDFDocument::new()
+    ///     .with_options(PDFOptions {
+    ///         restricted_fonts: RestrictedFontPolicy::Deny,
+    ///         ..Default::default()
+    ///     })
+    ///     .with_page_sized(dessin2!(Text!(text = "Hello", font = restricted) > ()), (100., 100.))
+    ///     .to_bytes();
+    /// assert!(matches!(pdf, Err(PDFError::RestrictedFont { .. })));
+    /// ```
+    pub restricted_fonts: RestrictedFontPolicy,
+    /// Color space of the fills and strokes, shared by every page of a document
+    pub color_space: ColorSpace,
+    /// Orientation of the Y axis the dessins are drawn in
+    pub coordinates: CoordinateSystem,
+    /// Compress every stream of the saved document.
+    ///
+    /// printpdf only compresses documents in release builds.
+    pub compress: bool,
+    /// Resolution above which images are downsampled, in dots per inch of their size on the page
+    pub image_max_dpi: Option<f32>,
+    /// Re-encode opaque images as JPEG of this quality, from 1 to 100.
+    ///
+    /// Images with transparency are kept lossless.
+    pub jpeg_quality: Option<u8>,
 }
 
-pub struct PDFExporter<'a> {
+/// Metadata key putting a [`Group`] in a PDF layer (optional content group), named by its value.
+///
+/// Viewers can then hide or show each layer.
+/// Groups of the same layer on a page share it, and nested layers are not nested in the viewer.
+///
+/// The content of a layer is drawn above the content outside of any layer,
+/// and above the layers of the page which first appear before it.
+///
+/// ```
+/// # use dessin::prelude::*;
+/// use dessin_pdf::LAYER_METADATA_KEY;
+///
+/// let mut grid = dessin2!(Rectangle!(stroke = (Color::GRAY, 0.1), width = 100., height = 100.) > ());
+/// grid.add_metadata((LAYER_METADATA_KEY, "Grid"));
+///
+/// let mut dimensions = dessin2!(Text!(fill = Color::BLACK, text = "100 mm", translate = [0., -55.]) > ());
+/// dimensions.add_metadata((LAYER_METADATA_KEY, "Dimensions"));
+///
+/// let pdf = dessin_pdf::to_pdf_bytes(&dessin2!([{ grid }, { dimensions }])).unwrap();
+/// ```
+pub const LAYER_METADATA_KEY: &str = dessin::layers::LAYER_METADATA_KEY;
+
+/// Metadata key making the area of a [`Group`] a link to the URL in its value.
+///
+/// The link covers the bounding box of the group.
+///
+/// ```
+/// # use dessin::prelude::*;
+/// use dessin_pdf::LINK_METADATA_KEY;
+///
+/// let mut product = dessin2!([
+///     Rectangle!(stroke = (Color::BLACK, 0.2), width = 40., height = 20.),
+///     Text!(fill = Color::BLACK, text = "Product"),
+/// ]);
+/// product.add_metadata((LINK_METADATA_KEY, "https://example.com/product"));
+///
+/// let pdf = dessin_pdf::to_pdf_bytes(&product).unwrap();
+/// assert!(pdf.windows(4).any(|w| w == b"/URI"));
+/// ```
+pub const LINK_METADATA_KEY: &str = "url";
+
+/// Metadata key adding a [`Group`] to the outline of the document, with its value as title.
+///
+/// The bookmark points at the top left corner of the group.
+/// Bookmarks of nested groups are nested in the outline.
+///
+/// Documents get the complete outline when saved through [`ExportedPDF`],
+/// printpdf alone only knows of the first bookmark of each page, pointing at the page itself.
+///
+/// ```
+/// # use dessin::prelude::*;
+/// use dessin_pdf::{PDFDocument, BOOKMARK_METADATA_KEY};
+///
+/// let chapter = |title: &str| {
+///     let mut chapter = dessin2!([Text!(fill = Color::BLACK, text = title)]);
+///     chapter.add_metadata((BOOKMARK_METADATA_KEY, title));
+///     chapter
+/// };
+///
+/// let pdf = PDFDocument::new()
+///     .with_page_sized(chapter("Introduction"), (210., 297.))
+///     .with_page_sized(chapter("Results"), (210., 297.))
+///     .to_bytes()
+///     .unwrap();
+/// ```
+pub const BOOKMARK_METADATA_KEY: &str = "bookmark";
+
+/// Graphics states setting the alpha of fills and strokes are named after their alpha, from 0 to 255.
+/// They are only referenced during the export, and registered in the page resources when the document is saved.
+const FILL_ALPHA_STATE: &str = "DessinFillAlpha";
+const STROKE_ALPHA_STATE: &str = "DessinStrokeAlpha";
+
+/// Graphics states setting the blend mode are named after the PDF name of the mode, and registered like the alpha ones
+const BLEND_STATE: &str = "DessinBlend";
+
+/// Graphics states applying a soft mask, numbered on each page.
+/// The content of the mask is written in a marked content of the same name, moved to its own form when the document is saved through [`ExportedPDF`].
+const MASK_STATE: &str = "DessinMask";
+/// Graphics states applying an alpha soft mask, like [`MASK_STATE`]
+const ALPHA_MASK_STATE: &str = "DessinAlphaMask";
+
+/// Transparency groups of styles with an opacity, numbered on each page.
+/// Their content is written in a marked content of the same name, moved to its own form when the document is saved,
+/// and the form is painted in its place with the alpha of the opacity.
+const GROUP_FORM: &str = "DessinGroup";
+
+/// Shading patterns painting gradient fills, numbered on each page.
+/// Their shading is written inline with a `DP` operation of the same name, moved to a pattern in the page resources when the document is saved through [`ExportedPDF`].
+const GRADIENT_PATTERN: &str = "DessinGradient";
+
+/// Default miter limit of PDF
+const PDF_MITER_LIMIT: f32 = 10.;
+
+fn fill_alpha(style: &StylePosition) -> u8 {
+    match &style.fill {
+        Some(fill) => fill.color().rgba().3,
+        None => 255,
+    }
+}
+
+fn stroke_alpha(style: &StylePosition) -> u8 {
+    match style.stroke {
+        Some(
+            Stroke::Full { color, .. }
+            | Stroke::Dashed { color, .. }
+            | Stroke::DashPattern { color, .. },
+        ) => color.rgba().3,
+        None => 255,
+    }
+}
+
+/// Writer of shapes on a page, whose transparency, gradients and masks are only complete once saved through [`ExportedPDF`]
+pub(crate) struct PDFExporter<'a> {
     layer: PdfLayerReference,
     doc: &'a PdfDocumentReference,
     used_font: PDFFontHolder,
+    color_space: ColorSpace,
+    image_max_dpi: Option<f32>,
+    jpeg_quality: Option<u8>,
+    restricted_fonts: RestrictedFontPolicy,
+    /// Number of soft masks written on the page
+    masks: usize,
+    /// Number of transparency groups written on the page
+    groups: usize,
+    /// Name and alpha of the transparency group of each style, if it has an opacity
+    open_groups: Vec<Option<(String, u8)>>,
+    /// Number of gradient patterns written on the page
+    gradients: Cell<usize>,
+    /// Writing the path of a [`Clip`], which is neither filled nor stroked
+    clipping: bool,
+    style: Vec<StylePosition>,
+    layers: HashMap<String, PdfLayerReference>,
+    parent_layers: Vec<PdfLayerReference>,
 }
 impl<'a> PDFExporter<'a> {
     pub fn new_with_font(
@@ -52,86 +566,668 @@ impl<'a> PDFExporter<'a> {
             layer,
             doc,
             used_font,
+            color_space: ColorSpace::default(),
+            image_max_dpi: None,
+            jpeg_quality: None,
+            restricted_fonts: RestrictedFontPolicy::default(),
+            masks: 0,
+            groups: 0,
+            open_groups: vec![],
+            gradients: Cell::new(0),
+            clipping: false,
+            style: vec![],
+            layers: HashMap::default(),
+            parent_layers: vec![],
         }
     }
-    pub fn new(layer: PdfLayerReference, doc: &'a PdfDocumentReference) -> Self {
-        let stock: PDFFontHolder = HashMap::default();
-        PDFExporter {
-            layer,
-            doc,
-            used_font: stock,
+
+    /// Write colors in `color_space`, RGB by default
+    pub fn with_color_space(mut self, color_space: ColorSpace) -> Self {
+        self.color_space = color_space;
+        self
+    }
+
+    /// Downsample images above `max_dpi`, in dots per inch of their size on the page
+    pub fn with_image_max_dpi(mut self, max_dpi: Option<f32>) -> Self {
+        self.image_max_dpi = max_dpi;
+        self
+    }
+
+    /// Re-encode opaque images as JPEG of `quality`, from 1 to 100
+    pub fn with_jpeg_quality(mut self, quality: Option<u8>) -> Self {
+        self.jpeg_quality = quality;
+        self
+    }
+
+    /// Fail on fonts whose license doesn't allow embedding them, if `policy` denies them
+    pub fn with_restricted_fonts(mut self, policy: RestrictedFontPolicy) -> Self {
+        self.restricted_fonts = policy;
+        self
+    }
+
+    /// `image`, downsampled and re-encoded as set
+    fn compress_image(
+        &self,
+        image: &DynamicImage,
+        width: f32,
+        height: f32,
+    ) -> Result<printpdf::Image, PDFError> {
+        use ::image::{codecs::jpeg::JpegEncoder, imageops::FilterType};
+        use printpdf::{ColorBits, ImageFilter, ImageXObject, Px};
+
+        let downsampled;
+        let image = match self.image_max_dpi {
+            Some(max_dpi) => {
+                let max_px = |mm: f32| ((mm / 25.4 * max_dpi).ceil() as u32).max(1);
+                let width_px = image.width().min(max_px(width));
+                let height_px = image.height().min(max_px(height));
+                if (width_px, height_px) != (image.width(), image.height()) {
+                    downsampled = image.resize_exact(width_px, height_px, FilterType::Triangle);
+                    &downsampled
+                } else {
+                    image
+                }
+            }
+            None => image,
+        };
+
+        let quality = match self.jpeg_quality {
+            Some(quality) if !image.color().has_alpha() => quality.clamp(1, 100),
+            _ => return Ok(printpdf::Image::from_dynamic_image(image)),
+        };
+
+        let (image, color_space) = match image.color().channel_count() {
+            1 => (
+                DynamicImage::ImageLuma8(image.to_luma8()),
+                printpdf::ColorSpace::Greyscale,
+            ),
+            _ => (
+                DynamicImage::ImageRgb8(image.to_rgb8()),
+                printpdf::ColorSpace::Rgb,
+            ),
+        };
+        let mut jpeg = vec![];
+        JpegEncoder::new_with_quality(&mut jpeg, quality)
+            .encode_image(&image)
+            .map_err(PDFError::ImageEncoding)?;
+
+        Ok(printpdf::Image::from(ImageXObject {
+            width: Px(image.width() as usize),
+            height: Px(image.height() as usize),
+            color_space,
+            bits_per_component: ColorBits::Bit8,
+            interpolate: true,
+            image_data: jpeg,
+            image_filter: Some(ImageFilter::DCT),
+            clipping_bbox: None,
+        }))
+    }
+
+    /// Set the alpha of fills, or strokes, through the graphics state named `state`
+    fn set_alpha(&self, state: &str, alpha: u8) {
+        use printpdf::lopdf::{content::Operation, Object};
+
+        self.layer.add_operation(Operation::new(
+            "gs",
+            vec![Object::Name(format!("{state}{alpha}").into_bytes())],
+        ));
+    }
+
+    /// Bevel mitered corners whose miter is longer than `miter_limit` widths of the stroke
+    fn set_miter_limit(&self, miter_limit: f32) {
+        use printpdf::lopdf::{content::Operation, Object};
+
+        self.layer
+            .add_operation(Operation::new("M", vec![Object::Real(miter_limit)]));
+    }
+
+    /// Blend what is drawn next with `blend_mode`, through its graphics state
+    fn set_blend_mode(&self, blend_mode: BlendMode) {
+        use printpdf::lopdf::{content::Operation, Object};
+
+        self.layer.add_operation(Operation::new(
+            "gs",
+            vec![Object::Name(
+                format!("{BLEND_STATE}{}", blend_mode.pdf_name()).into_bytes(),
+            )],
+        ));
+    }
+
+    /// Fill with `gradient`, through a shading pattern
+    fn set_fill_gradient(&self, gradient: &Fill) {
+        use printpdf::lopdf::{content::Operation, Dictionary, Object};
+
+        let pt = |mm: f32| Object::Real(Mm(mm).into_pt().0);
+        let (shading_type, coords, stops) = match gradient {
+            Fill::Color(_) | Fill::Pattern { .. } | Fill::Image { .. } => return,
+            Fill::LinearGradient { start, end, stops } => (
+                2,
+                vec![pt(start.x), pt(start.y), pt(end.x), pt(end.y)],
+                stops,
+            ),
+            Fill::RadialGradient {
+                center,
+                radius,
+                stops,
+            } => (
+                3,
+                vec![
+                    pt(center.x),
+                    pt(center.y),
+                    pt(0.),
+                    pt(center.x),
+                    pt(center.y),
+                    pt(*radius),
+                ],
+                stops,
+            ),
+        };
+        let color_space = match self.color_space {
+            ColorSpace::Rgb | ColorSpace::Srgb => "DeviceRGB",
+            ColorSpace::Cmyk { .. } => "DeviceCMYK",
+        };
+
+        let shading = Dictionary::from_iter(vec![
+            ("ShadingType", Object::Integer(shading_type)),
+            ("ColorSpace", Object::Name(color_space.as_bytes().to_vec())),
+            ("Coords", Object::Array(coords)),
+            ("Function", self.gradient_function(stops)),
+            (
+                "Extend",
+                Object::Array(vec![Object::Boolean(true), Object::Boolean(true)]),
+            ),
+        ]);
+
+        let name = format!("{GRADIENT_PATTERN}{}", self.gradients.get());
+        self.gradients.set(self.gradients.get() + 1);
+
+        self.layer.add_operation(Operation::new(
+            "DP",
+            vec![
+                Object::Name(name.clone().into_bytes()),
+                Object::Dictionary(shading),
+            ],
+        ));
+        self.layer.add_operation(Operation::new(
+            "cs",
+            vec![Object::Name(b"Pattern".to_vec())],
+        ));
+        self.layer
+            .add_operation(Operation::new("scn", vec![Object::Name(name.into_bytes())]));
+    }
+
+    /// Colors of `stops` along a gradient, from 0 to 1, interpolated between each stop.
+    ///
+    /// Alpha is not part of a shading and is ignored.
+    fn gradient_function(&self, stops: &GradientStops) -> printpdf::lopdf::Object {
+        use printpdf::lopdf::{Dictionary, Object};
+
+        let components = |color: Color| {
+            let components = match self.color_space.convert(color) {
+                printpdf::Color::Rgb(rgb) => vec![rgb.r, rgb.g, rgb.b],
+                printpdf::Color::Cmyk(cmyk) => vec![cmyk.c, cmyk.m, cmyk.y, cmyk.k],
+                printpdf::Color::Greyscale(grey) => vec![grey.percent],
+                printpdf::Color::SpotColor(spot) => vec![spot.c, spot.m, spot.y, spot.k],
+            };
+            Object::Array(components.into_iter().map(Object::Real).collect())
+        };
+
+        // Colors before the first stop and after the last one are the ones of these stops
+        let mut stops = stops.as_slice().to_vec();
+        match (stops.first().copied(), stops.last().copied()) {
+            (Some(first), Some(last)) => {
+                if first.0 > 0. {
+                    stops.insert(0, (0., first.1));
+                }
+                if last.0 < 1. {
+                    stops.push((1., last.1));
+                }
+            }
+            _ => stops = vec![(0., Color::BLACK), (1., Color::BLACK)],
+        }
+
+        let interpolations = stops
+            .windows(2)
+            .map(|pair| {
+                Object::Dictionary(Dictionary::from_iter(vec![
+                    ("FunctionType", Object::Integer(2)),
+                    ("Domain", Object::Array(vec![0.into(), 1.into()])),
+                    ("C0", components(pair[0].1)),
+                    ("C1", components(pair[1].1)),
+                    ("N", Object::Integer(1)),
+                ]))
+            })
+            .collect::<Vec<_>>();
+        let bounds = stops[1..stops.len() - 1]
+            .iter()
+            .map(|(offset, _)| Object::Real(*offset))
+            .collect();
+        let encode = interpolations
+            .iter()
+            .flat_map(|_| [0.into(), 1.into()])
+            .collect();
+
+        Object::Dictionary(Dictionary::from_iter(vec![
+            ("FunctionType", Object::Integer(3)),
+            ("Domain", Object::Array(vec![0.into(), 1.into()])),
+            ("Functions", Object::Array(interpolations)),
+            ("Bounds", Object::Array(bounds)),
+            ("Encode", Object::Array(encode)),
+        ]))
+    }
+
+    fn set_style(&self, style: &StylePosition) {
+        let StylePosition { fill, stroke, .. } = style;
+
+        // Opaque colors only need a graphics state to cover a transparent parent
+        if fill.is_some() && self.style.iter().any(|s| fill_alpha(s) < 255) {
+            self.set_alpha(FILL_ALPHA_STATE, fill_alpha(style));
+        }
+        if stroke.is_some() && self.style.iter().any(|s| stroke_alpha(s) < 255) {
+            self.set_alpha(STROKE_ALPHA_STATE, stroke_alpha(style));
+        }
+        if style.blend_mode != BlendMode::Normal {
+            self.set_blend_mode(style.blend_mode);
+        }
+
+        match fill {
+            Some(Fill::Color(color)) => self.layer.set_fill_color(self.color_space.convert(*color)),
+            Some(gradient) => self.set_fill_gradient(gradient),
+            None => {}
+        }
+
+        if let Some(stroke) = stroke {
+            if let Some((dashes, offset)) = stroke.dashes() {
+                // Whole points, at least 1 so no dash vanishes
+                let pt = |mm: f32| (Mm(mm).into_pt().0.round() as i64).max(1);
+                let length = |idx: usize| dashes.as_slice().get(idx).map(|mm| pt(*mm));
+
+                self.layer.set_line_dash_pattern(printpdf::LineDashPattern {
+                    offset: Mm(offset).into_pt().0.round() as i64,
+                    dash_1: length(0),
+                    gap_1: length(1),
+                    dash_2: length(2),
+                    gap_2: length(3),
+                    dash_3: length(4),
+                    gap_3: length(5),
+                });
+            }
+
+            self.layer
+                .set_outline_color(self.color_space.convert(stroke.color()));
+
+            self.layer
+                .set_outline_thickness(printpdf::Mm(stroke.width()).into_pt().0);
+
+            // Butt and miter are the defaults of PDF
+            match stroke.cap() {
+                LineCap::Butt => {}
+                LineCap::Round => self.layer.set_line_cap_style(printpdf::LineCapStyle::Round),
+                LineCap::Square => self
+                    .layer
+                    .set_line_cap_style(printpdf::LineCapStyle::ProjectingSquare),
+            }
+            match stroke.join() {
+                LineJoin::Miter => {}
+                LineJoin::Round => self
+                    .layer
+                    .set_line_join_style(printpdf::LineJoinStyle::Round),
+                // Line join 2 of PDF, named after the miter limit by printpdf
+                LineJoin::Bevel => self
+                    .layer
+                    .set_line_join_style(printpdf::LineJoinStyle::Limit),
+            }
+            if stroke.join() == LineJoin::Miter && stroke.miter_limit() != PDF_MITER_LIMIT {
+                self.set_miter_limit(stroke.miter_limit());
+            }
         }
     }
 }
 
 impl Exporter for PDFExporter<'_> {
     type Error = PDFError;
-    const CAN_EXPORT_ELLIPSE: bool = false;
+    const CAN_EXPORT_ELLIPSE: bool = true;
+    const CAN_EXPORT_OPACITY: bool = true;
+    const CAN_EXPORT_BLEND_MODE: bool = true;
+    const BACKEND: Option<&'static str> = Some("pdf");
 
-    fn start_style(
+    /// [Clips][Clip] are written as clipping paths, and [masks][Mask] as soft masks, from the luminosity of the mask
+    ///
+    /// ```
+    /// # use dessin::prelude::*;
+    /// let faded = dessin2!(Mask(
+    ///     shape = dessin2!(Rectangle!(fill = Color::RED, width = 40., height = 20.)),
+    ///     mask = dessin2!(Rectangle!(fill = rgb(128, 128, 128), width = 40., height = 20.)),
+    /// ));
+    ///
+    /// let mut pdf = vec![];
+    /// dessin_pdf::to_pdf(&faded.into())
+    ///     .unwrap()
+    ///     .save(&mut std::io::BufWriter::new(&mut pdf))
+    ///     .unwrap();
+    ///
+    /// let pdf = printpdf::lopdf::Document::load_mem(&pdf).unwrap();
+    /// let masks = pdf
+    ///     .objects
+    ///     .values()
+    ///     .filter_map(|object| object.as_dict().ok()?.get(b"SMask").ok())
+    ///     .count();
+    /// assert_eq!(masks, 1);
+    ///
+    /// // The mask is only drawn in its form, not on the page
+    /// let page = pdf.get_pages()[&1];
+    /// let content = pdf.get_and_decode_page_content(page).unwrap();
+    /// assert!(!content.operations.iter().any(|op| op.operator == "BMC"));
+    /// ```
+    ///
+    /// Alpha masks are soft masks from the alpha of the mask
+    ///
+    /// ```
+    /// # use dessin::prelude::*;
+    /// let faded = dessin2!(Mask(
+    ///     shape = dessin2!(Rectangle!(fill = Color::RED, width = 40., height = 20.)),
+    ///     mask = dessin2!(Rectangle!(fill = rgba(0, 0, 0, 128), width = 40., height = 20.)),
+    ///     mode = MaskMode::Alpha,
+    /// ));
+    ///
+    /// let pdf = dessin_pdf::to_pdf(&faded.into()).unwrap().save_to_bytes().unwrap();
+    ///
+    /// let pdf = printpdf::lopdf::Document::load_mem(&pdf).unwrap();
+    /// let subtypes = pdf
+    ///     .objects
+    ///     .values()
+    ///     .filter_map(|object| object.as_dict().ok()?.get(b"SMask").ok()?.as_dict().ok())
+    ///     .map(|mask| mask.get(b"S").unwrap().as_name_str().unwrap())
+    ///     .collect::<Vec<_>>();
+    /// assert_eq!(subtypes, ["Alpha"]);
+    ///
+    /// let page = pdf.get_pages()[&1];
+    /// let content = pdf.get_and_decode_page_content(page).unwrap();
+    /// assert!(!content.operations.iter().any(|op| op.operator == "BMC"));
+    /// ```
+    ///
+    /// Pattern fills are drawn as copies of their tile, clipped by the filled shape
+    ///
+    /// ```
+    /// # use dessin::prelude::*;
+    /// let dotted = dessin2!(Circle!(fill = Fill::dots(Color::BLACK, 0.5, 2.), radius = 10.) > ());
+    ///
+    /// let pdf = dessin_pdf::to_pdf_bytes(&dotted).unwrap();
+    ///
+    /// let pdf = printpdf::lopdf::Document::load_mem(&pdf).unwrap();
+    /// let page = pdf.get_pages()[&1];
+    /// let content = pdf.get_and_decode_page_content(page).unwrap();
+    /// assert!(content.operations.iter().any(|op| op.operator == "W"));
+    /// ```
+    fn export_subtree(
         &mut self,
-        StylePosition { fill, stroke }: StylePosition,
-    ) -> Result<(), Self::Error> {
-        if let Some(fill) = fill {
-            let (r, g, b) = match fill {
-                Fill::Color(c) => c.as_rgb_f32(),
-            };
+        shape: &Shape,
+        parent_transform: &Transform2<f32>,
+    ) -> Result<bool, Self::Error> {
+        use printpdf::lopdf::{content::Operation, Object};
 
-            self.layer
-                .set_fill_color(printpdf::Color::Rgb(printpdf::Rgb {
-                    r,
-                    g,
-                    b,
-                    icc_profile: None,
-                }));
+        let Shape::Group(group) = shape else {
+            return Ok(false);
+        };
+        if hint(&group.metadata, "pdf", SKIP_HINT) == Some("true") {
+            return Ok(false);
         }
+        let transform = parent_transform * group.local_transform;
 
-        if let Some(stroke) = stroke {
-            let ((r, g, b), w) = match stroke {
-                Stroke::Full { color, width } => (color.as_rgb_f32(), width),
-                Stroke::Dashed {
-                    color,
-                    width,
-                    on,
-                    off,
-                } => {
-                    self.layer.set_line_dash_pattern(printpdf::LineDashPattern {
-                        offset: 0,
-                        dash_1: Some(on as i64),
-                        gap_1: Some(off as i64),
-                        dash_2: None,
-                        gap_2: None,
-                        dash_3: None,
-                        gap_3: None,
-                    });
-
-                    (color.as_rgb_f32(), width)
-                }
-            };
+        if let Some((clipped, clipped_by)) = Clip::parts(group).filter(|_| !self.clipping) {
+            self.layer.add_operation(Operation::new("q", vec![]));
+            self.clipping = true;
+            let written = clipped_by.write_into_exporter(self, &transform);
+            self.clipping = false;
+            written?;
+            self.layer.add_operation(Operation::new("W", vec![]));
+            self.layer.add_operation(Operation::new("n", vec![]));
 
-            self.layer
-                .set_outline_color(printpdf::Color::Rgb(printpdf::Rgb {
-                    r,
-                    g,
-                    b,
-                    icc_profile: None,
-                }));
+            clipped.write_into_exporter(self, &transform)?;
+            self.layer.add_operation(Operation::new("Q", vec![]));
 
-            self.layer
-                .set_outline_thickness(printpdf::Mm(w).into_pt().0);
+            return Ok(true);
+        }
+
+        let Some((masked, mask)) = Mask::parts(group) else {
+            return Ok(false);
+        };
+
+        let state = match Mask::mode_of(group) {
+            MaskMode::Luminance => MASK_STATE,
+            MaskMode::Alpha => ALPHA_MASK_STATE,
+        };
+        let name = format!("{state}{}", self.masks);
+        self.masks += 1;
+
+        self.layer.add_operation(Operation::new("q", vec![]));
+        self.layer.add_operation(Operation::new(
+            "BMC",
+            vec![Object::Name(name.clone().into_bytes())],
+        ));
+        mask.write_into_exporter(self, &transform)?;
+        self.layer.add_operation(Operation::new("EMC", vec![]));
+        self.layer
+            .add_operation(Operation::new("gs", vec![Object::Name(name.into_bytes())]));
+
+        masked.write_into_exporter(self, &transform)?;
+        self.layer.add_operation(Operation::new("Q", vec![]));
+
+        Ok(true)
+    }
+
+    /// [Gradient fills][Fill::LinearGradient] are painted with shading patterns
+    ///
+    /// ```
+    /// # use dessin::prelude::*;
+    /// let sky = dessin2!(Rectangle!(
+    ///     fill = Fill::LinearGradient {
+    ///         start: [0., -10.].into(),
+    ///         end: [0., 10.].into(),
+    ///         stops: GradientStops::new(&[(0., Color::WHITE), (1., Color::BLUE)]),
+    ///     },
+    ///     width = 40.,
+    ///     height = 20.,
+    /// ));
+    ///
+    /// let pdf = dessin_pdf::to_pdf(&sky.into()).unwrap().save_to_bytes().unwrap();
+    ///
+    /// let pdf = printpdf::lopdf::Document::load_mem(&pdf).unwrap();
+    /// let patterns = pdf
+    ///     .objects
+    ///     .values()
+    ///     .filter_map(|object| object.as_dict().ok()?.get(b"Pattern").ok()?.as_dict().ok())
+    ///     .find(|patterns| patterns.has(b"DessinGradient0"))
+    ///     .unwrap();
+    /// let pattern = patterns.get(b"DessinGradient0").unwrap().as_reference().unwrap();
+    /// let pattern = pdf.get_dictionary(pattern).unwrap();
+    /// assert_eq!(pattern.get(b"PatternType").unwrap().as_i64().unwrap(), 2);
+    /// assert!(pattern.has(b"Shading"));
+    ///
+    /// let page = pdf.get_pages()[&1];
+    /// let content = pdf.get_and_decode_page_content(page).unwrap();
+    /// assert!(!content.operations.iter().any(|op| op.operator == "DP"));
+    /// ```
+    ///
+    /// Caps and joins of strokes other than butt and miter are set in the graphics state
+    ///
+    /// ```
+    /// # use dessin::prelude::*;
+    /// let route = dessin2!(Rectangle!(
+    ///     stroke = Stroke::from((Color::BLACK, 2.)).with_join(LineJoin::Round),
+    ///     width = 40.,
+    ///     height = 20.,
+    /// ) > ());
+    ///
+    /// let pdf = dessin_pdf::to_pdf_bytes(&route).unwrap();
+    ///
+    /// let pdf = printpdf::lopdf::Document::load_mem(&pdf).unwrap();
+    /// let page = pdf.get_pages()[&1];
+    /// let content = pdf.get_and_decode_page_content(page).unwrap();
+    /// assert!(content.operations.iter().any(|op| op.operator == "j"));
+    /// assert!(!content.operations.iter().any(|op| op.operator == "J"));
+    /// ```
+    ///
+    /// The [miter limit][Stroke::miter_limit] of mitered corners is set too, since the default of PDF is longer than the one of SVG
+    ///
+    /// ```
+    /// # use dessin::prelude::*;
+    /// use nalgebra::Point2;
+    ///
+    /// let spike = dessin2!(Curve!(
+    ///     stroke = Stroke::from((Color::BLACK, 2.)).with_miter_limit(2.),
+    ///     then = Point2::new(-5., 0.),
+    ///     then = Point2::new(0., 40.),
+    ///     then = Point2::new(5., 0.),
+    ///     closed,
+    /// ) > ());
+    ///
+    /// let pdf = dessin_pdf::to_pdf_bytes(&spike).unwrap();
+    ///
+    /// let pdf = printpdf::lopdf::Document::load_mem(&pdf).unwrap();
+    /// let page = pdf.get_pages()[&1];
+    /// let content = pdf.get_and_decode_page_content(page).unwrap();
+    /// let limits = content
+    ///     .operations
+    ///     .iter()
+    ///     .filter(|op| op.operator == "M")
+    ///     .filter_map(|op| op.operands[0].as_float().ok())
+    ///     .collect::<Vec<_>>();
+    /// assert_eq!(limits, [2., 10.]);
+    /// ```
+    ///
+    /// The [opacity of a style][Style::opacity] is applied to everything inside it at once,
+    /// drawn in a transparency group painted with the alpha of the opacity,
+    /// so overlapping shapes don't show through each other
+    ///
+    /// ```
+    /// # use dessin::prelude::*;
+    /// let layer = dessin2!([
+    ///     Circle!(fill = Color::RED, radius = 10.),
+    ///     Rectangle!(fill = rgba(0, 0, 255, 128), width = 10., height = 10.),
+    /// ]);
+    /// let faded: Shape = Style::new(layer).with_opacity(0.5).into();
+    ///
+    /// let pdf = dessin_pdf::to_pdf_bytes(&faded).unwrap();
+    ///
+    /// let pdf = printpdf::lopdf::Document::load_mem(&pdf).unwrap();
+    /// let groups = pdf
+    ///     .objects
+    ///     .values()
+    ///     .filter_map(|object| object.as_stream().ok())
+    ///     .filter(|form| form.dict.get(b"Group").is_ok())
+    ///     .count();
+    /// assert_eq!(groups, 1);
+    ///
+    /// // The group, then the rectangle in it, have their own alpha
+    /// let fill_alphas = pdf
+    ///     .objects
+    ///     .values()
+    ///     .filter_map(|object| object.as_dict().ok()?.get(b"ca").ok()?.as_float().ok())
+    ///     .collect::<Vec<_>>();
+    /// assert!(fill_alphas.iter().any(|alpha| (alpha - 0.5).abs() < 10e-3));
+    /// assert!(!fill_alphas.iter().any(|alpha| (alpha - 0.25).abs() < 10e-3));
+    ///
+    /// let page = pdf.get_pages()[&1];
+    /// let content = pdf.get_and_decode_page_content(page).unwrap();
+    /// assert!(content.operations.iter().any(|op| op.operator == "Do"));
+    /// assert!(!content.operations.iter().any(|op| op.operator == "BMC"));
+    /// ```
+    ///
+    /// The [blend mode of a style][Style::blend_mode] is set through the graphics state, for every shape inside it
+    ///
+    /// ```
+    /// # use dessin::prelude::*;
+    /// let ink = dessin2!(Circle!(fill = Color::CYAN, radius = 10.) > ());
+    /// let printed: Shape = Style::new(ink).with_blend_mode(BlendMode::Multiply).into();
+    ///
+    /// let pdf = dessin_pdf::to_pdf_bytes(&printed).unwrap();
+    ///
+    /// let pdf = printpdf::lopdf::Document::load_mem(&pdf).unwrap();
+    /// assert!(pdf.objects.values().any(|object| {
+    ///     object.as_dict().ok().and_then(|dict| dict.get(b"BM").ok()?.as_name().ok()) == Some(b"Multiply".as_slice())
+    /// }));
+    /// ```
+    fn start_style(&mut self, style: StylePosition) -> Result<(), Self::Error> {
+        use printpdf::lopdf::{content::Operation, Object};
+
+        let group = (!self.clipping && style.opacity < 1.).then(|| {
+            let name = format!("{GROUP_FORM}{}", self.groups);
+            self.groups += 1;
+
+            self.layer.add_operation(Operation::new("q", vec![]));
+            self.layer.add_operation(Operation::new(
+                "BMC",
+                vec![Object::Name(name.clone().into_bytes())],
+            ));
+
+            // Alpha is reset in a group, unlike the colors inherited from outside of it
+            let inherited_fill = self.style.iter().rev().find(|s| s.fill.is_some());
+            if let Some(alpha) = inherited_fill.map(fill_alpha).filter(|alpha| *alpha < 255) {
+                self.set_alpha(FILL_ALPHA_STATE, alpha);
+            }
+            let inherited_stroke = self.style.iter().rev().find(|s| s.stroke.is_some());
+            if let Some(alpha) = inherited_stroke
+                .map(stroke_alpha)
+                .filter(|alpha| *alpha < 255)
+            {
+                self.set_alpha(STROKE_ALPHA_STATE, alpha);
+            }
+
+            (name, (style.opacity.max(0.) * 255.).round() as u8)
+        });
+        self.open_groups.push(group);
+
+        self.style.push(style);
+        if !self.clipping {
+            self.set_style(self.style.last().unwrap());
         }
 
         Ok(())
     }
 
     fn end_style(&mut self) -> Result<(), Self::Error> {
+        use printpdf::lopdf::{content::Operation, Object};
+
+        // Only the geometry of a clipping shape matters
+        if self.clipping {
+            self.style.pop();
+            self.open_groups.pop();
+            return Ok(());
+        }
+
+        if let Some(style) = self.style.pop() {
+            if fill_alpha(&style) < 255 {
+                self.set_alpha(FILL_ALPHA_STATE, 255);
+            }
+            if stroke_alpha(&style) < 255 {
+                self.set_alpha(STROKE_ALPHA_STATE, 255);
+            }
+            if style.blend_mode != BlendMode::Normal {
+                let parent = self
+                    .style
+                    .iter()
+                    .rev()
+                    .map(|style| style.blend_mode)
+                    .find(|blend_mode| *blend_mode != BlendMode::Normal);
+                self.set_blend_mode(parent.unwrap_or(BlendMode::Normal));
+            }
+            if let Some(stroke) = style.stroke {
+                if stroke.cap() != LineCap::Butt {
+                    self.layer.set_line_cap_style(printpdf::LineCapStyle::Butt);
+                }
+                if stroke.join() != LineJoin::Miter {
+                    self.layer
+                        .set_line_join_style(printpdf::LineJoinStyle::Miter);
+                }
+                if stroke.join() == LineJoin::Miter && stroke.miter_limit() != PDF_MITER_LIMIT {
+                    self.set_miter_limit(PDF_MITER_LIMIT);
+                }
+            }
+        }
+
         self.layer
-            .set_outline_color(printpdf::Color::Rgb(printpdf::Rgb {
-                r: 0.,
-                g: 0.,
-                b: 0.,
-                icc_profile: None,
-            }));
+            .set_outline_color(self.color_space.convert(Color::BLACK));
         self.layer.set_outline_thickness(0.);
         self.layer.set_line_dash_pattern(printpdf::LineDashPattern {
             offset: 0,
@@ -144,12 +1240,50 @@ impl Exporter for PDFExporter<'_> {
         });
 
         self.layer
-            .set_fill_color(printpdf::Color::Rgb(printpdf::Rgb {
-                r: 0.,
-                g: 0.,
-                b: 0.,
-                icc_profile: None,
-            }));
+            .set_fill_color(self.color_space.convert(Color::BLACK));
+
+        if let Some(Some((name, alpha))) = self.open_groups.pop() {
+            self.layer.add_operation(Operation::new("EMC", vec![]));
+            self.set_alpha(FILL_ALPHA_STATE, alpha);
+            self.set_alpha(STROKE_ALPHA_STATE, alpha);
+            self.layer
+                .add_operation(Operation::new("Do", vec![Object::Name(name.into_bytes())]));
+            self.layer.add_operation(Operation::new("Q", vec![]));
+        }
+
+        Ok(())
+    }
+
+    fn start_block(&mut self, metadata: &[(String, String)]) -> Result<(), Self::Error> {
+        let Some((_, name)) = metadata.iter().find(|(k, _)| k == LAYER_METADATA_KEY) else {
+            return Ok(());
+        };
+        if self.clipping {
+            return Ok(());
+        }
+
+        let layer = self
+            .layers
+            .entry(name.clone())
+            .or_insert_with(|| self.doc.get_page(self.layer.page).add_layer(name.as_str()))
+            .clone();
+        self.parent_layers
+            .push(std::mem::replace(&mut self.layer, layer));
+
+        // Each layer has its own graphic state
+        if let Some(style) = self.style.last() {
+            self.set_style(style);
+        }
+
+        Ok(())
+    }
+
+    fn end_block(&mut self, metadata: &[(String, String)]) -> Result<(), Self::Error> {
+        if !self.clipping && metadata.iter().any(|(k, _)| k == LAYER_METADATA_KEY) {
+            if let Some(parent) = self.parent_layers.pop() {
+                self.layer = parent;
+            }
+        }
 
         Ok(())
     }
@@ -168,8 +1302,13 @@ impl Exporter for PDFExporter<'_> {
             image,
         }: ImagePosition,
     ) -> Result<(), Self::Error> {
-        let width_px = image.width();
-        let height_px = image.height();
+        if self.clipping {
+            return Ok(());
+        }
+
+        let image = self.compress_image(image, width, height)?;
+        let width_px = image.image.width.0 as u32;
+        let height_px = image.image.height.0 as u32;
 
         let dpi = 300.;
         let raw_width = width_px as f32 * 25.4 / dpi;
@@ -178,7 +1317,7 @@ impl Exporter for PDFExporter<'_> {
         let scale_width = width / raw_width;
         let scale_height = height / raw_height;
 
-        printpdf::Image::from_dynamic_image(image).add_to_layer(
+        image.add_to_layer(
             self.layer.clone(),
             printpdf::ImageTransform {
                 translate_x: Some(Mm(bottom_left.x)),
@@ -197,6 +1336,45 @@ impl Exporter for PDFExporter<'_> {
         Ok(())
     }
 
+    /// Four bezier quarters, smooth at any zoom
+    fn export_ellipse(
+        &mut self,
+        EllipsePosition {
+            center,
+            semi_major_axis,
+            semi_minor_axis,
+            rotation,
+        }: EllipsePosition,
+    ) -> Result<(), Self::Error> {
+        // Distance of the controls of a quarter, relative to the axis
+        const KAPPA: f32 = 0.552_284_8;
+
+        let transform = Isometry2::new(center.coords, rotation);
+        let point =
+            |x: f32, y: f32| transform * Point2::new(x * semi_major_axis, y * semi_minor_axis);
+
+        let quarters = [(1., 0.), (0., 1.), (-1., 0.), (0., -1.), (1., 0.)];
+        let keypoints = quarters
+            .windows(2)
+            .enumerate()
+            .map(|(idx, w)| {
+                let ((x1, y1), (x2, y2)) = (w[0], w[1]);
+                KeypointPosition::Bezier(Bezier {
+                    start: (idx == 0).then(|| point(x1, y1)),
+                    start_control: point(x1 + KAPPA * x2, y1 + KAPPA * y2),
+                    end_control: point(x2 + KAPPA * x1, y2 + KAPPA * y1),
+                    end: point(x2, y2),
+                })
+            })
+            .collect();
+
+        self.export_curve(CurvePosition {
+            keypoints,
+            closed: true,
+            fill_rule: FillRule::NonZero,
+        })
+    }
+
     fn export_curve(&mut self, curve: CurvePosition) -> Result<(), Self::Error> {
         let points1 = curve
             .keypoints
@@ -231,7 +1409,31 @@ impl Exporter for PDFExporter<'_> {
             points: points1,
             is_closed: curve.closed,
         };
-        self.layer.add_line(line);
+        // The path without the stroking operator of printpdf
+        let mut operations = line.into_stream_op();
+        operations.pop();
+
+        let filled = self.style.iter().any(|style| style.fill.is_some());
+        let stroked = self.style.iter().any(|style| style.stroke.is_some());
+        let even_odd = curve.fill_rule == FillRule::EvenOdd;
+        let paint = match (filled, stroked, curve.closed) {
+            // The path is only closed for the clip
+            _ if self.clipping => curve.closed.then_some("h"),
+            (true, true, true) if even_odd => Some("b*"),
+            (true, true, true) => Some("b"),
+            (true, true, false) if even_odd => Some("B*"),
+            (true, true, false) => Some("B"),
+            (true, false, _) if even_odd => Some("f*"),
+            (true, false, _) => Some("f"),
+            (false, _, true) => Some("s"),
+            (false, _, false) => Some("S"),
+        };
+        operations
+            .extend(paint.map(|paint| printpdf::lopdf::content::Operation::new(paint, vec![])));
+
+        for operation in operations {
+            self.layer.add_operation(operation);
+        }
         Ok(())
     }
 
@@ -239,44 +1441,51 @@ impl Exporter for PDFExporter<'_> {
         &mut self,
         TextPosition {
             text,
-            align: _,
+            align,
             font_weight,
-            on_curve: _,
+            on_curve,
             font_size,
             reference_start,
             direction,
             font,
         }: TextPosition,
     ) -> Result<(), Self::Error> {
-        let font = font.clone().unwrap_or(FontRef::default());
-
-        // search if (font_ref, font_weight) is stocked in used_font
-        let font = self
-            .used_font
-            .entry((font.clone(), font_weight))
-            .or_insert_with(|| match font::get(font.clone()).get(font_weight) {
-                dessin::font::Font::OTF(b) | dessin::font::Font::TTF(b) => {
-                    if let Err(err) = self.doc.add_external_font(b.as_slice()) {
-                        panic!("Failed to add external font : {}", err)
-                    } else {
-                        self.doc.add_external_font(b.as_slice()).unwrap()
-                    }
-                }
-            });
+        if self.clipping {
+            return Ok(());
+        }
+
+        let font = self.used_font.embed(
+            self.doc,
+            font.clone().unwrap_or_default(),
+            font_weight,
+            self.restricted_fonts,
+        )?;
+
+        // Same units as the geometry, like other exporters
+        let start = match align {
+            TextAlign::Left => reference_start,
+            TextAlign::Center => {
+                reference_start - direction.into_inner() * font.width(text, font_size) / 2.
+            }
+            TextAlign::Right => {
+                reference_start - direction.into_inner() * font.width(text, font_size)
+            }
+        };
+        if let Some(curve) = on_curve {
+            write_text_on_curve(&self.layer, text, align, font_size, &curve, font);
+            return Ok(());
+        }
+        let font = font.reference.clone();
 
         self.layer.begin_text_section();
-        self.layer.set_font(&font, font_size);
-        // if let Some(te) = text.on_curve {
-        //     self.layer.add_polygon()
-        //     todo!()
-        // }
+        self.layer.set_font(&font, Mm(font_size).into_pt().0);
         let rotation = direction.y.atan2(direction.x).to_degrees();
         self.layer
             .set_text_rendering_mode(printpdf::TextRenderingMode::Fill);
         self.layer
             .set_text_matrix(printpdf::TextMatrix::TranslateRotate(
-                Mm(reference_start.x).into_pt(),
-                Mm(reference_start.y).into_pt(),
+                Mm(start.x).into_pt(),
+                Mm(start.y).into_pt(),
                 rotation,
             ));
 
@@ -287,52 +1496,1114 @@ impl Exporter for PDFExporter<'_> {
     }
 }
 
-pub fn write_to_pdf_with_options(
+/// Write each glyph of `text` on its own, along `curve`, like SVG text paths:
+/// the middle of each glyph is on the curve, and glyphs past its end are dropped.
+///
+/// Left aligned texts start at the start of the curve, centered ones are centered on its middle,
+/// and right aligned ones end at its end.
+fn write_text_on_curve(
+    layer: &PdfLayerReference,
+    text: &str,
+    align: TextAlign,
+    font_size: f32,
+    curve: &CurvePosition,
+    font: &EmbeddedFont,
+) {
+    let path = ArcLength::new(&curve.flatten(font_size / 100.));
+    let mut distance = match align {
+        TextAlign::Left => 0.,
+        TextAlign::Center => (path.length() - font.width(text, font_size)) / 2.,
+        TextAlign::Right => path.length() - font.width(text, font_size),
+    };
+
+    layer.begin_text_section();
+    layer.set_font(&font.reference, Mm(font_size).into_pt().0);
+    layer.set_text_rendering_mode(printpdf::TextRenderingMode::Fill);
+
+    for c in text.chars() {
+        let advance = font.metrics.metrics(c, font_size).advance_width;
+        let middle = distance + advance / 2.;
+        distance += advance;
+
+        let Some((point, direction)) = path.at(middle) else {
+            continue;
+        };
+        let start = point - direction.into_inner() * advance / 2.;
+        layer.set_text_matrix(printpdf::TextMatrix::TranslateRotate(
+            Mm(start.x).into_pt(),
+            Mm(start.y).into_pt(),
+            direction.y.atan2(direction.x).to_degrees(),
+        ));
+        layer.write_text(c.to_string(), &font.reference);
+    }
+
+    layer.end_text_section();
+}
+
+/// Polyline parametrized by the distance along it
+struct ArcLength {
+    points: Vec<Point2<f32>>,
+    /// Distance from the first point to each point
+    distances: Vec<f32>,
+}
+impl ArcLength {
+    fn new(points: &[Point2<f32>]) -> Self {
+        let mut points = points.to_vec();
+        points.dedup();
+
+        let mut total = 0.;
+        let mut distances = Vec::with_capacity(points.len());
+        distances.push(0.);
+        for w in points.windows(2) {
+            total += (w[1] - w[0]).magnitude();
+            distances.push(total);
+        }
+
+        ArcLength { points, distances }
+    }
+
+    fn length(&self) -> f32 {
+        self.distances.last().copied().unwrap_or_default()
+    }
+
+    /// Point `distance` away from the start, and the direction of the polyline there.
+    ///
+    /// `None` outside of the polyline.
+    fn at(&self, distance: f32) -> Option<(Point2<f32>, Unit<Vector2<f32>>)> {
+        if self.points.len() < 2 || distance < 0. || distance > self.length() {
+            return None;
+        }
+
+        let segment = self
+            .distances
+            .partition_point(|d| *d <= distance)
+            .clamp(1, self.points.len() - 1);
+        let (from, to) = (self.points[segment - 1], self.points[segment]);
+        let direction = Unit::new_normalize(to - from);
+
+        Some((
+            from + direction.into_inner() * (distance - self.distances[segment - 1]),
+            direction,
+        ))
+    }
+}
+
+fn write_page(
     shape: &Shape,
     layer: PdfLayerReference,
     options: PDFOptions,
     doc: &PdfDocumentReference,
-) -> Result<(), PDFError> {
+    outline: &mut Vec<Bookmark>,
+    page_number: usize,
+) -> Result<PDFFontHolder, PDFError> {
+    let shape = options.coordinates.to_y_up(shape);
+    let shape = shape.as_ref();
+
+    let debug;
+    let shape = match &options.debug {
+        Some(overlay) => {
+            debug = shape.with_debug_overlay(overlay);
+            &debug
+        }
+        None => shape,
+    };
+
     let (width, height) = options.size.unwrap_or_else(|| {
         let bb = shape.local_bounding_box();
         (bb.width(), bb.height())
     });
-    let mut exporter = PDFExporter::new_with_font(layer, doc, options.used_font);
+
+    let stamped;
+    let shape = match &options.scale_statement {
+        Some(statement) => {
+            stamped =
+                with_scale_statement(shape, &BoundingBox::centered([width, height]), statement);
+            &stamped
+        }
+        None => shape,
+    };
+
+    let stamped_on_page;
+    let shape = if options.stamps.is_empty() {
+        shape
+    } else {
+        stamped_on_page = with_stamps(
+            shape,
+            &BoundingBox::centered([width, height]),
+            &options.stamps,
+        );
+        &stamped_on_page
+    };
+
+    let backdrop;
+    let shape = if options.background.is_some() || options.border.is_some() {
+        backdrop = with_backdrop(
+            shape,
+            &BoundingBox::centered([width, height]),
+            options.background.as_ref(),
+            options.border,
+        );
+        &backdrop
+    } else {
+        shape
+    };
+
     let translation = Translation2::new(width / 2., height / 2.);
     let parent_transform = nalgebra::convert(translation);
 
-    shape.write_into_exporter(&mut exporter, &parent_transform)
+    let mut marks = Marks::default();
+    marks.collect(shape, &parent_transform, 0);
+    for (url, bb) in marks.links {
+        layer.add_link_annotation(LinkAnnotation::new(
+            printpdf::Rect::new(Mm(bb.left()), Mm(bb.bottom()), Mm(bb.right()), Mm(bb.top())),
+            Some(BorderArray::Solid([0., 0., 0.])),
+            None,
+            Actions::uri(url),
+            None,
+        ));
+    }
+    // printpdf only knows of one bookmark per page, the whole outline is written on save
+    if let Some(bookmark) = marks.bookmarks.first() {
+        doc.add_bookmark(bookmark.title.clone(), layer.page);
+    }
+    outline.extend(marks.bookmarks.into_iter().map(|bookmark| Bookmark {
+        page: page_number,
+        ..bookmark
+    }));
+
+    let mut exporter = PDFExporter::new_with_font(layer, doc, options.used_font)
+        .with_color_space(options.color_space)
+        .with_image_max_dpi(options.image_max_dpi)
+        .with_jpeg_quality(options.jpeg_quality)
+        .with_restricted_fonts(options.restricted_fonts);
+    shape.write_into_exporter(&mut exporter, &parent_transform)?;
+
+    Ok(exporter.used_font)
+}
+
+/// Entry of the outline of a document
+struct Bookmark {
+    title: String,
+    /// Number of bookmarks above this one
+    depth: usize,
+    page: usize,
+    /// Top left corner of the group, in page coordinates
+    at: Point2<f32>,
+}
+
+/// Links and bookmarks of a page, in page coordinates
+#[derive(Default)]
+struct Marks {
+    links: Vec<(String, BoundingBox<Straight>)>,
+    bookmarks: Vec<Bookmark>,
+}
+impl Marks {
+    fn collect(&mut self, shape: &Shape, parent_transform: &Transform2<f32>, depth: usize) {
+        match shape {
+            Shape::Group(Group {
+                local_transform,
+                shapes,
+                metadata,
+            }) => {
+                let value = |key: &str| {
+                    metadata
+                        .iter()
+                        .find(|(k, _)| k == key)
+                        .map(|(_, value)| value.clone())
+                };
+
+                let url = value(LINK_METADATA_KEY);
+                let title = value(BOOKMARK_METADATA_KEY);
+                let mut depth = depth;
+                if url.is_some() || title.is_some() {
+                    let bb = shape.global_bounding_box(parent_transform).straigthen();
+
+                    if let Some(url) = url {
+                        self.links.push((url, bb));
+                    }
+                    if let Some(title) = title {
+                        self.bookmarks.push(Bookmark {
+                            title,
+                            depth,
+                            page: 0,
+                            at: bb.top_left(),
+                        });
+                        depth += 1;
+                    }
+                }
+
+                let transform = parent_transform * local_transform;
+                for shape in shapes {
+                    self.collect(shape, &transform, depth);
+                }
+            }
+            Shape::Style { shape, .. } => self.collect(shape, parent_transform, depth),
+            Shape::LevelOfDetail(lod) => self.collect(
+                lod.pick(parent_transform),
+                &lod.global_transform(parent_transform),
+                depth,
+            ),
+            Shape::Dynamic {
+                local_transform,
+                shaper,
+            } => self.collect(&shaper(), &(parent_transform * local_transform), depth),
+            _ => {}
+        }
+    }
+}
+
+/// Register the graphics states setting the alpha of fills and strokes, and the blend mode, in the resources of the pages using them.
+///
+/// Returns whether the document uses any.
+fn add_alpha_states(pdf: &mut printpdf::lopdf::Document) -> Result<bool, printpdf::lopdf::Error> {
+    use printpdf::lopdf::{Dictionary, Object};
+
+    let mut states = HashMap::new();
+    for page in pdf.get_pages().into_values() {
+        let content = pdf.get_and_decode_page_content(page)?;
+        let mut names = content
+            .operations
+            .iter()
+            .filter(|op| op.operator == "gs")
+            .filter_map(|op| op.operands.first()?.as_name_str().ok())
+            .map(str::to_string)
+            .collect::<Vec<_>>();
+        names.sort();
+        names.dedup();
+
+        for name in names {
+            let (key, value) = match (
+                name.strip_prefix(FILL_ALPHA_STATE),
+                name.strip_prefix(STROKE_ALPHA_STATE),
+                name.strip_prefix(BLEND_STATE),
+            ) {
+                (Some(alpha), _, _) => match alpha.parse::<u8>() {
+                    Ok(alpha) => ("ca", Object::Real(alpha as f32 / 255.)),
+                    Err(_) => continue,
+                },
+                (_, Some(alpha), _) => match alpha.parse::<u8>() {
+                    Ok(alpha) => ("CA", Object::Real(alpha as f32 / 255.)),
+                    Err(_) => continue,
+                },
+                (_, _, Some(blend_mode)) => ("BM", Object::Name(blend_mode.as_bytes().to_vec())),
+                _ => continue,
+            };
+
+            let id = *states.entry(name.clone()).or_insert_with(|| {
+                pdf.add_object(Dictionary::from_iter(vec![
+                    ("Type", Object::Name(b"ExtGState".to_vec())),
+                    (key, value),
+                ]))
+            });
+            pdf.add_graphics_state(page, name, id)?;
+        }
+    }
+
+    Ok(!states.is_empty())
+}
+
+/// Register the shading patterns of gradient fills, in the resources of the pages using them.
+///
+/// Returns whether the document uses any.
+fn add_gradient_patterns(
+    pdf: &mut printpdf::lopdf::Document,
+) -> Result<bool, printpdf::lopdf::Error> {
+    use printpdf::lopdf::{Dictionary, Object};
+
+    let mut any = false;
+    for page in pdf.get_pages().into_values() {
+        let mut content = pdf.get_and_decode_page_content(page)?;
+        let mut shadings = vec![];
+        content
+            .operations
+            .retain(|op| match op.operands.as_slice() {
+                [Object::Name(name), Object::Dictionary(shading)]
+                    if op.operator == "DP" && name.starts_with(GRADIENT_PATTERN.as_bytes()) =>
+                {
+                    shadings.push((name.clone(), shading.clone()));
+                    false
+                }
+                _ => true,
+            });
+        if shadings.is_empty() {
+            continue;
+        }
+        any = true;
+
+        for (name, shading) in shadings {
+            let pattern = pdf.add_object(Dictionary::from_iter(vec![
+                ("Type", Object::Name(b"Pattern".to_vec())),
+                ("PatternType", Object::Integer(2)),
+                ("Shading", Object::Dictionary(shading)),
+            ]));
+
+            let resources = pdf.get_or_create_resources(page)?.as_dict_mut()?;
+            if !resources.has(b"Pattern") {
+                resources.set("Pattern", Dictionary::new());
+            }
+            resources
+                .get_mut(b"Pattern")?
+                .as_dict_mut()?
+                .set(name, Object::Reference(pattern));
+        }
+
+        pdf.change_page_content(page, content.encode()?)?;
+    }
+
+    Ok(any)
+}
+
+/// Move the content of the soft masks and transparency groups of each page to its own form,
+/// referenced by the graphics state applying the mask, or painted in place of the group.
+///
+/// Returns whether the document has any.
+fn add_forms(pdf: &mut printpdf::lopdf::Document) -> Result<bool, printpdf::lopdf::Error> {
+    use printpdf::lopdf::{
+        content::{Content, Operation},
+        Dictionary, Object, Stream,
+    };
+
+    let form_name = |op: &Operation| {
+        let name = op.operands.first()?.as_name_str().ok()?;
+        let form = [MASK_STATE, ALPHA_MASK_STATE, GROUP_FORM]
+            .iter()
+            .any(|prefix| name.starts_with(prefix));
+        (op.operator == "BMC" && form).then(|| name.to_string())
+    };
+
+    let mut any = false;
+    for page in pdf.get_pages().into_values() {
+        let content = pdf.get_and_decode_page_content(page)?;
+        if !content.operations.iter().any(|op| form_name(op).is_some()) {
+            continue;
+        }
+        any = true;
+
+        // Operations of the page, then of each form being read, with the depth of the marked contents opened in it
+        let mut frames = vec![(None, vec![], 0_usize)];
+        let mut forms = vec![];
+        for op in content.operations {
+            if let Some(name) = form_name(&op) {
+                frames.push((Some(name), vec![], 0));
+                continue;
+            }
+
+            let (name, operations, depth) = frames.last_mut().unwrap();
+            match op.operator.as_str() {
+                "BMC" | "BDC" => *depth += 1,
+                "EMC" if *depth == 0 && name.is_some() => {
+                    if let Some((Some(name), operations, _)) = frames.pop() {
+                        forms.push((name, operations));
+                    }
+                    continue;
+                }
+                "EMC" => *depth = depth.saturating_sub(1),
+                _ => {}
+            }
+            operations.push(op);
+        }
+        let (_, operations, _) = frames.swap_remove(0);
+
+        let ids = forms
+            .iter()
+            .map(|_| pdf.new_object_id())
+            .collect::<Vec<_>>();
+        for ((name, _), form) in forms.iter().zip(&ids) {
+            if name.starts_with(GROUP_FORM) {
+                pdf.add_xobject(page, name.as_str(), *form)?;
+                continue;
+            }
+
+            let subtype = if name.starts_with(ALPHA_MASK_STATE) {
+                "Alpha"
+            } else {
+                "Luminosity"
+            };
+            let state = pdf.add_object(Dictionary::from_iter(vec![
+                ("Type", Object::Name(b"ExtGState".to_vec())),
+                (
+                    "SMask",
+                    Object::Dictionary(Dictionary::from_iter(vec![
+                        ("Type", Object::Name(b"Mask".to_vec())),
+                        ("S", Object::Name(subtype.as_bytes().to_vec())),
+                        ("G", Object::Reference(*form)),
+                    ])),
+                ),
+            ]));
+            pdf.add_graphics_state(page, name.as_str(), state)?;
+        }
+
+        // Forms were written with the resources of the page, nested masks and groups included
+        let page_dictionary = pdf.get_dictionary(page)?;
+        let media_box = page_dictionary.get(b"MediaBox")?.clone();
+        let resources = page_dictionary.get(b"Resources")?.clone();
+        for ((name, operations), form) in forms.into_iter().zip(ids) {
+            // Masks are read in RGB, groups are blended in the color space of the page
+            let mut group =
+                Dictionary::from_iter(vec![("S", Object::Name(b"Transparency".to_vec()))]);
+            if !name.starts_with(GROUP_FORM) {
+                group.set("CS", Object::Name(b"DeviceRGB".to_vec()));
+            }
+            let dictionary = Dictionary::from_iter(vec![
+                ("Type", Object::Name(b"XObject".to_vec())),
+                ("Subtype", Object::Name(b"Form".to_vec())),
+                ("BBox", media_box.clone()),
+                ("Group", Object::Dictionary(group)),
+                ("Resources", resources.clone()),
+            ]);
+            let content = Content { operations }.encode()?;
+            pdf.objects
+                .insert(form, Object::Stream(Stream::new(dictionary, content)));
+        }
+
+        pdf.change_page_content(page, Content { operations }.encode()?)?;
+    }
+
+    Ok(any)
+}
+
+/// Put the profile of the output intents of the document under `DestOutputProfile`,
+/// where printpdf writes it as `DestinationOutputProfile`, and replace it by `icc_profile` if any
+fn set_output_profile(
+    pdf: &mut printpdf::lopdf::Document,
+    icc_profile: Option<&[u8]>,
+) -> Result<(), printpdf::lopdf::Error> {
+    use printpdf::lopdf::{Object, StringFormat};
+
+    let mut intents = pdf.catalog()?.get(b"OutputIntents")?.as_array()?.clone();
+    for intent in &mut intents {
+        let intent = intent.as_dict_mut()?;
+        if let Some(profile) = intent.remove(b"DestinationOutputProfile") {
+            intent.set("DestOutputProfile", profile);
+        }
+
+        let Some(icc_profile) = icc_profile else {
+            continue;
+        };
+        let profile = intent.get(b"DestOutputProfile")?.as_reference()?;
+        pdf.get_object_mut(profile)?
+            .as_stream_mut()?
+            .set_plain_content(icc_profile.to_vec());
+
+        // The printing condition is only known by its profile
+        intent.remove(b"OutputCondition");
+        for key in ["OutputConditionIdentifier", "Info"] {
+            intent.set(
+                key,
+                Object::String(b"Custom".to_vec(), StringFormat::Literal),
+            );
+        }
+    }
+    pdf.catalog_mut()?
+        .set("OutputIntents", Object::Array(intents));
+
+    Ok(())
+}
+
+/// Tag the `DeviceRGB` colors of the document as sRGB, with an output intent
+fn add_srgb_output_intent(
+    pdf: &mut printpdf::lopdf::Document,
+) -> Result<(), printpdf::lopdf::Error> {
+    use printpdf::lopdf::{Dictionary, Object, Stream, StringFormat};
+
+    let profile = Stream::new(
+        Dictionary::from_iter(vec![
+            ("N", Object::Integer(3)),
+            ("Alternate", Object::Name(b"DeviceRGB".to_vec())),
+        ]),
+        dessin::color_profile::srgb(),
+    );
+    let profile = pdf.add_object(profile);
+
+    let name = || {
+        Object::String(
+            dessin::color_profile::SRGB_NAME.as_bytes().to_vec(),
+            StringFormat::Literal,
+        )
+    };
+    let intent = Dictionary::from_iter(vec![
+        ("Type", Object::Name(b"OutputIntent".to_vec())),
+        ("S", Object::Name(b"GTS_PDFA1".to_vec())),
+        ("OutputConditionIdentifier", name()),
+        ("Info", name()),
+        (
+            "RegistryName",
+            Object::String(b"http://www.color.org".to_vec(), StringFormat::Literal),
+        ),
+        ("DestOutputProfile", Object::Reference(profile)),
+    ]);
+    pdf.catalog_mut()?.set(
+        "OutputIntents",
+        Object::Array(vec![Object::Dictionary(intent)]),
+    );
+
+    Ok(())
+}
+
+/// New document whose first page is `size`, in millimeters, with the output intent of `color_space`
+fn new_document(
+    title: &str,
+    size: (f32, f32),
+    color_space: &ColorSpace,
+) -> (PdfDocumentReference, PdfLayerReference) {
+    let (doc, page, layer) = PdfDocument::new(title, Mm(size.0), Mm(size.1), "Layer 1");
+    let doc = match color_space {
+        ColorSpace::Rgb | ColorSpace::Srgb => doc,
+        ColorSpace::Cmyk { .. } => {
+            doc.with_conformance(PdfConformance::Custom(CustomPdfConformance {
+                requires_icc_profile: true,
+                ..Default::default()
+            }))
+        }
+    };
+    let layer = doc.get_page(page).get_layer(layer);
+
+    (doc, layer)
+}
+
+/// Save the document, registering its transparency and output profile, and replacing its outline by `outline`
+fn save_with_outline(
+    doc: PdfDocumentReference,
+    outline: &[Bookmark],
+    color_space: &ColorSpace,
+    compress: bool,
+) -> Result<Vec<u8>, PDFError> {
+    use printpdf::lopdf::{Dictionary, Document, Object, StringFormat};
+
+    let bytes = doc.save_to_bytes()?;
+    let mut pdf = Document::load_mem(&bytes).map_err(PDFError::Save)?;
+    let gradients = add_gradient_patterns(&mut pdf).map_err(PDFError::Save)?;
+    let transparent = add_alpha_states(&mut pdf).map_err(PDFError::Save)?;
+    let transparent = add_forms(&mut pdf).map_err(PDFError::Save)? || transparent;
+    let cmyk = matches!(color_space, ColorSpace::Cmyk { .. });
+    if let ColorSpace::Cmyk { icc_profile } = color_space {
+        set_output_profile(&mut pdf, icc_profile.as_deref()).map_err(PDFError::Save)?;
+    }
+    let srgb = *color_space == ColorSpace::Srgb;
+    if srgb {
+        add_srgb_output_intent(&mut pdf).map_err(PDFError::Save)?;
+    }
+    if compress {
+        pdf.compress();
+    }
+    if outline.is_empty() {
+        if !transparent && !gradients && !cmyk && !srgb && !compress {
+            return Ok(bytes);
+        }
+
+        let mut bytes = vec![];
+        pdf.save_to(&mut bytes)
+            .map_err(|e| PDFError::Save(e.into()))?;
+        return Ok(bytes);
+    }
+
+    let pages = pdf.get_pages().into_values().collect::<Vec<_>>();
+
+    // Children of each bookmark, the last entry being the root of the outline
+    let mut children = vec![vec![]; outline.len() + 1];
+    let mut parents: Vec<usize> = vec![];
+    for (idx, bookmark) in outline.iter().enumerate() {
+        parents.truncate(bookmark.depth);
+        children[parents.last().copied().unwrap_or(outline.len())].push(idx);
+        parents.push(idx);
+    }
+
+    let ids = (0..=outline.len())
+        .map(|_| pdf.new_object_id())
+        .collect::<Vec<_>>();
+    let descendants = |idx: usize| {
+        let mut count = 0;
+        let mut stack = children[idx].clone();
+        while let Some(child) = stack.pop() {
+            count += 1;
+            stack.extend(&children[child]);
+        }
+        count
+    };
+    let title = |title: &str| {
+        // Text strings are either PDFDocEncoding, compatible with ASCII, or UTF-16BE
+        let bytes = if title.is_ascii() {
+            title.as_bytes().to_vec()
+        } else {
+            [0xFE, 0xFF]
+                .into_iter()
+                .chain(title.encode_utf16().flat_map(u16::to_be_bytes))
+                .collect()
+        };
+        Object::String(bytes, StringFormat::Literal)
+    };
+
+    // Parent, previous and next sibling of each bookmark
+    let mut links = vec![(None, None, None); outline.len() + 1];
+    for (parent, kids) in children.iter().enumerate() {
+        for (pos, kid) in kids.iter().enumerate() {
+            links[*kid] = (
+                Some(ids[parent]),
+                pos.checked_sub(1).map(|prev| ids[kids[prev]]),
+                kids.get(pos + 1).map(|next| ids[*next]),
+            );
+        }
+    }
+
+    for (idx, kids) in children.iter().enumerate() {
+        let mut dict = match outline.get(idx) {
+            None => Dictionary::from_iter(vec![("Type", Object::Name(b"Outlines".to_vec()))]),
+            Some(bookmark) => Dictionary::from_iter(vec![
+                ("Title", title(&bookmark.title)),
+                (
+                    "Dest",
+                    Object::Array(vec![
+                        Object::Reference(pages[bookmark.page.min(pages.len() - 1)]),
+                        Object::Name(b"XYZ".to_vec()),
+                        Object::Real(Mm(bookmark.at.x).into_pt().0),
+                        Object::Real(Mm(bookmark.at.y).into_pt().0),
+                        Object::Null,
+                    ]),
+                ),
+            ]),
+        };
+
+        let (parent, prev, next) = links[idx];
+        for (key, id) in [("Parent", parent), ("Prev", prev), ("Next", next)] {
+            if let Some(id) = id {
+                dict.set(key, Object::Reference(id));
+            }
+        }
+        if let (Some(first), Some(last)) = (kids.first(), kids.last()) {
+            dict.set("First", Object::Reference(ids[*first]));
+            dict.set("Last", Object::Reference(ids[*last]));
+            dict.set("Count", Object::Integer(descendants(idx) as i64));
+        }
+
+        pdf.objects.insert(ids[idx], Object::Dictionary(dict));
+    }
+
+    let catalog = pdf.catalog_mut().map_err(PDFError::Outline)?;
+    catalog.set("Outlines", Object::Reference(ids[outline.len()]));
+    catalog.set("PageMode", Object::Name(b"UseOutlines".to_vec()));
+
+    pdf.prune_objects();
+    let mut bytes = vec![];
+    pdf.save_to(&mut bytes)
+        .map_err(|e| PDFError::Outline(e.into()))?;
+
+    Ok(bytes)
+}
+
+/// Document exported by dessin, to complete with printpdf before saving it.
+///
+/// Transparency, blend modes, gradients and masks are only referenced while exporting,
+/// and registered in the resources of the pages when the document is saved through [`ExportedPDF::save`] or [`ExportedPDF::save_to_bytes`].
+/// Saving the [`document`][ExportedPDF::document] with printpdf alone would leave them undefined.
+///
+/// ```
+/// # use dessin::prelude::*;
+/// use std::io::BufWriter;
+///
+/// let pdf = dessin_pdf::to_pdf(&dessin2!(Circle!(fill = rgba(255, 0, 0, 51), radius = 10.) > ())).unwrap();
+///
+/// let mut bytes = vec![];
+/// pdf.save(&mut BufWriter::new(&mut bytes)).unwrap();
+///
+/// let pdf = printpdf::lopdf::Document::load_mem(&bytes).unwrap();
+/// let registered = pdf
+///     .objects
+///     .values()
+///     .filter_map(|object| object.as_dict().ok()?.get(b"ExtGState").ok()?.as_dict().ok())
+///     .any(|states| states.has(b"DessinFillAlpha51"));
+/// assert!(registered);
+/// ```
+pub struct ExportedPDF {
+    doc: PdfDocumentReference,
+    outline: Vec<Bookmark>,
+    color_space: ColorSpace,
+    compress: bool,
+}
+impl ExportedPDF {
+    /// printpdf document, to add pages or content to before saving it
+    pub fn document(&self) -> &PdfDocumentReference {
+        &self.doc
+    }
+
+    pub fn save_to_bytes(self) -> Result<Vec<u8>, PDFError> {
+        save_with_outline(self.doc, &self.outline, &self.color_space, self.compress)
+    }
+
+    pub fn save<W: Write>(self, target: &mut BufWriter<W>) -> Result<(), PDFError> {
+        let bytes = self.save_to_bytes()?;
+        target.write_all(&bytes).map_err(printpdf::Error::from)?;
+        Ok(())
+    }
 }
 
-pub fn to_pdf_with_options(
+pub fn to_pdf_with_options(shape: &Shape, options: PDFOptions) -> Result<ExportedPDF, PDFError> {
+    let color_space = options.color_space.clone();
+    let compress = options.compress;
+    let (doc, outline) = to_pdf_with_outline(shape, options)?;
+    Ok(ExportedPDF {
+        doc,
+        outline,
+        color_space,
+        compress,
+    })
+}
+
+fn to_pdf_with_outline(
     shape: &Shape,
     mut options: PDFOptions,
-) -> Result<PdfDocumentReference, PDFError> {
+) -> Result<(PdfDocumentReference, Vec<Bookmark>), PDFError> {
     let size = options.size.get_or_insert_with(|| {
-        let bb = shape.local_bounding_box();
+        let bb = match &options.debug {
+            Some(overlay) => shape.with_debug_overlay(overlay).local_bounding_box(),
+            None => shape.local_bounding_box(),
+        };
         (bb.width(), bb.height())
     });
-    let (doc, page, layer) = PdfDocument::new("", Mm(size.0), Mm(size.1), "Layer 1");
+    let (doc, layer) = new_document("", *size, &options.color_space);
 
-    let layer = doc.get_page(page).get_layer(layer);
+    let mut outline = vec![];
+    write_page(shape, layer, options, &doc, &mut outline, 0)?;
+
+    Ok((doc, outline))
+}
 
-    write_to_pdf_with_options(shape, layer, options, &doc)?;
+/// Export each [`Tile`] of a [`Tiling`] on its own page, row by row.
+///
+/// All pages share the size of a tile, and fonts are only embedded once.
+pub fn tiles_to_pdf(tiling: &Tiling) -> Result<ExportedPDF, PDFError> {
+    let size = (tiling.page_width, tiling.page_height);
 
-    Ok(doc)
+    tiling
+        .tiles()
+        .into_iter()
+        .fold(PDFDocument::new(), |doc, tile| {
+            doc.with_page_sized(tile.shape, size)
+        })
+        .build()
 }
 
-pub fn write_to_pdf(
-    shape: &Shape,
-    layer: PdfLayerReference,
-    doc: &PdfDocumentReference,
-) -> Result<(), PDFError> {
-    write_to_pdf_with_options(shape, layer, PDFOptions::default(), doc)
+/// Export each page of a [`Pagination`] on its own page, from the top of the drawing.
+///
+/// All pages share the same size, and fonts are only embedded once.
+///
+/// ```
+/// # use dessin::prelude::*;
+/// let timeline = dessin2!(for year in 0..40 {
+///     dessin2!(Text!(fill = Color::BLACK, text = format!("{}", 1990 + year), translate = [0., year as f32 * -10.]))
+/// });
+///
+/// let pdf = dessin_pdf::pages_to_pdf(&Pagination::new(timeline).with_page_height(148.))
+///     .unwrap()
+///     .save_to_bytes()
+///     .unwrap();
+///
+/// let pdf = printpdf::lopdf::Document::load_mem(&pdf).unwrap();
+/// assert_eq!(pdf.get_pages().len(), 3);
+/// ```
+pub fn pages_to_pdf(pagination: &Pagination) -> Result<ExportedPDF, PDFError> {
+    let size = (pagination.page_width, pagination.page_height);
+
+    pagination
+        .pages()
+        .into_iter()
+        .fold(PDFDocument::new(), |doc, page| {
+            doc.with_page_sized(page.shape, size)
+        })
+        .build()
+}
+
+/// Page of a [`PDFDocument`]
+struct PDFPage {
+    shape: Shape,
+    size: Option<(f32, f32)>,
 }
 
-pub fn to_pdf(shape: &Shape) -> Result<PdfDocumentReference, PDFError> {
+/// PDF document of several pages, each with its own dessin and size.
+///
+/// Fonts are only embedded once for the whole document.
+///
+/// ```
+/// # use dessin::prelude::*;
+/// use dessin_pdf::PDFDocument;
+///
+/// let cover = dessin2!(Text!(fill = Color::BLACK, text = "Report", font_size = 20.) > ());
+/// let chapter = dessin2!(Text!(fill = Color::BLACK, text = "Chapter 1") > ());
+///
+/// let pdf = PDFDocument::new()
+///     .with_title("Report")
+///     .with_page_sized(cover, (210., 297.))
+///     .with_page_sized(chapter, (297., 210.))
+///     .to_bytes()
+///     .unwrap();
+/// ```
+#[derive(Default)]
+pub struct PDFDocument {
+    title: String,
+    pages: Vec<PDFPage>,
+    options: PDFOptions,
+}
+impl PDFDocument {
+    pub fn new() -> Self {
+        PDFDocument::default()
+    }
+
+    /// Title of the document
+    pub fn title<S: Into<String>>(&mut self, title: S) -> &mut Self {
+        self.title = title.into();
+        self
+    }
+    pub fn with_title<S: Into<String>>(mut self, title: S) -> Self {
+        self.title(title);
+        self
+    }
+
+    /// Options of every page.
+    ///
+    /// Their size is ignored, as each page has its own.
+    pub fn options(&mut self, options: PDFOptions) -> &mut Self {
+        self.options = options;
+        self
+    }
+    pub fn with_options(mut self, options: PDFOptions) -> Self {
+        self.options(options);
+        self
+    }
+
+    /// Add a page, the size of the bounding box of its dessin
+    pub fn page(&mut self, shape: Shape) -> &mut Self {
+        self.pages.push(PDFPage { shape, size: None });
+        self
+    }
+    pub fn with_page(mut self, shape: Shape) -> Self {
+        self.page(shape);
+        self
+    }
+
+    /// Add a page of `(width, height)`, in millimeters
+    pub fn page_sized(&mut self, shape: Shape, size: (f32, f32)) -> &mut Self {
+        self.pages.push(PDFPage {
+            shape,
+            size: Some(size),
+        });
+        self
+    }
+    pub fn with_page_sized(mut self, shape: Shape, size: (f32, f32)) -> Self {
+        self.page_sized(shape, size);
+        self
+    }
+
+    /// One page per layer of `shape`, as split by [`Shape::split_layers`], each bookmarked with the name of its layer.
+    ///
+    /// Every page has the size of the whole dessin, or [`PDFOptions::size`], so layers stay aligned from one page to the other.
+    ///
+    /// ```
+    /// # use dessin::prelude::*;
+    /// use dessin_pdf::{PDFDocument, PDFOptions, LAYER_METADATA_KEY};
+    ///
+    /// let mut cut = dessin2!(Circle!(stroke = (Color::RED, 0.1), radius = 20.) > ());
+    /// cut.add_metadata((LAYER_METADATA_KEY, "Cut"));
+    /// let mut engrave = dessin2!(Text!(fill = Color::BLACK, text = "Coaster") > ());
+    /// engrave.add_metadata((LAYER_METADATA_KEY, "Engrave"));
+    ///
+    /// let pdf = PDFDocument::from_layers(&dessin2!([{ cut }, { engrave }]), PDFOptions::default());
+    /// assert_eq!(pdf.len(), 2);
+    ///
+    /// let pdf = pdf.to_bytes().unwrap();
+    /// ```
+    pub fn from_layers(shape: &Shape, options: PDFOptions) -> Self {
+        let size = options.size.unwrap_or_else(|| {
+            let bb = match &options.debug {
+                Some(overlay) => shape.with_debug_overlay(overlay).local_bounding_box(),
+                None => shape.local_bounding_box(),
+            };
+            (bb.width(), bb.height())
+        });
+
+        shape.split_layers().into_iter().fold(
+            PDFDocument::new().with_options(options),
+            |doc, (name, layer)| {
+                let mut page = dessin2!([{ layer }]);
+                if let Some(name) = name {
+                    page.add_metadata((BOOKMARK_METADATA_KEY, name));
+                }
+                doc.with_page_sized(page, size)
+            },
+        )
+    }
+
+    /// Number of pages
+    pub fn len(&self) -> usize {
+        self.pages.len()
+    }
+
+    /// Whether the document has no page yet
+    pub fn is_empty(&self) -> bool {
+        self.pages.is_empty()
+    }
+
+    pub fn build(self) -> Result<ExportedPDF, PDFError> {
+        let color_space = self.options.color_space.clone();
+        let compress = self.options.compress;
+        let (doc, outline) = self.build_with_outline()?;
+        Ok(ExportedPDF {
+            doc,
+            outline,
+            color_space,
+            compress,
+        })
+    }
+
+    fn build_with_outline(self) -> Result<(PdfDocumentReference, Vec<Bookmark>), PDFError> {
+        let PDFDocument {
+            title,
+            pages,
+            mut options,
+        } = self;
+
+        let mut pages = pages.into_iter().map(|PDFPage { shape, size }| {
+            let size = size.unwrap_or_else(|| {
+                let bb = match &options.debug {
+                    Some(overlay) => shape.with_debug_overlay(overlay).local_bounding_box(),
+                    None => shape.local_bounding_box(),
+                };
+                (bb.width(), bb.height())
+            });
+            (shape, size)
+        });
+        let first_font = std::mem::take(&mut options.used_font);
+        let page_options = |size: (f32, f32), used_font: PDFFontHolder| PDFOptions {
+            size: Some(size),
+            used_font,
+            ..options.clone()
+        };
+
+        let Some((shape, size)) = pages.next() else {
+            return Err(PDFError::EmptyDocument);
+        };
+        let (doc, layer) = new_document(&title, size, &options.color_space);
+        let mut outline = vec![];
+        let mut used_font = write_page(
+            &shape,
+            layer,
+            page_options(size, first_font),
+            &doc,
+            &mut outline,
+            0,
+        )?;
+
+        for (page_number, (shape, size)) in pages.enumerate() {
+            let (page, layer) = doc.add_page(Mm(size.0), Mm(size.1), "Layer 1");
+            let layer = doc.get_page(page).get_layer(layer);
+            used_font = write_page(
+                &shape,
+                layer,
+                page_options(size, used_font),
+                &doc,
+                &mut outline,
+                page_number + 1,
+            )?;
+        }
+
+        Ok((doc, outline))
+    }
+
+    /// Save the document, with its complete outline
+    pub fn to_bytes(self) -> Result<Vec<u8>, PDFError> {
+        self.build()?.save_to_bytes()
+    }
+}
+
+/// Fill a template with each record, on its own page.
+///
+/// All pages share the size of the template, unless one is given in the options, and fonts are only embedded once.
+/// See [`dessin::template`] for the syntax of the template.
+pub fn batch_to_pdf<V: TemplateValues, I: IntoIterator<Item = V>>(
+    template: &Shape,
+    records: I,
+    mut options: PDFOptions,
+) -> Result<ExportedPDF, PDFError> {
+    let size = options.size.unwrap_or_else(|| {
+        let bb = template.local_bounding_box();
+        (bb.width(), bb.height())
+    });
+    let (doc, layer) = new_document("", size, &options.color_space);
+
+    let mut first_layer = Some(layer);
+    let mut used_font = std::mem::take(&mut options.used_font);
+    let mut outline = vec![];
+    let mut page_number = 0;
+    for page in generate_batch(template, records, |shape| {
+        let layer = first_layer.take().unwrap_or_else(|| {
+            let (page, layer) = doc.add_page(Mm(size.0), Mm(size.1), "Layer 1");
+            doc.get_page(page).get_layer(layer)
+        });
+
+        let options = PDFOptions {
+            size: Some(size),
+            used_font: std::mem::take(&mut used_font),
+            ..options.clone()
+        };
+        used_font = write_page(shape, layer, options, &doc, &mut outline, page_number)?;
+        page_number += 1;
+
+        Ok(())
+    }) {
+        page?;
+    }
+
+    Ok(ExportedPDF {
+        doc,
+        outline,
+        color_space: options.color_space,
+        compress: options.compress,
+    })
+}
+
+/// Export `shape` to a document, to complete before saving it
+pub fn to_pdf(shape: &Shape) -> Result<ExportedPDF, PDFError> {
     to_pdf_with_options(shape, PDFOptions::default())
 }
 
+/// Export `shape` to a saved document.
+///
+/// Transparent fills and strokes are drawn with the alpha of their color.
+///
+/// ```
+/// # use dessin::prelude::*;
+/// use dessin_pdf::to_pdf_bytes;
+///
+/// let pdf = to_pdf_bytes(&dessin2!(Circle!(fill = rgba(255, 0, 0, 51), radius = 10.) > ())).unwrap();
+///
+/// let pdf = printpdf::lopdf::Document::load_mem(&pdf).unwrap();
+/// let fill_alphas = pdf
+///     .objects
+///     .values()
+///     .filter_map(|object| object.as_dict().ok()?.get(b"ca").ok()?.as_float().ok())
+///     .collect::<Vec<_>>();
+/// assert!(fill_alphas.iter().any(|alpha| (alpha - 0.2).abs() < 10e-3));
+/// ```
 pub fn to_pdf_bytes(shape: &Shape) -> Result<Vec<u8>, PDFError> {
-    Ok(to_pdf(shape)?.save_to_bytes()?)
+    to_pdf(shape)?.save_to_bytes()
+}
+
+/// Export `shape` to a saved document, with `options`.
+///
+/// ```
+/// # use dessin::prelude::*;
+/// use dessin_pdf::{to_pdf_bytes_with_options, ColorSpace, PDFOptions};
+///
+/// let pdf = to_pdf_bytes_with_options(
+///     &dessin2!(Circle!(fill = Color::RED, radius = 10.) > ()),
+///     PDFOptions {
+///         color_space: ColorSpace::Cmyk { icc_profile: None },
+///         ..Default::default()
+///     },
+/// )
+/// .unwrap();
+///
+/// let pdf = printpdf::lopdf::Document::load_mem(&pdf).unwrap();
+/// assert!(pdf.catalog().unwrap().has(b"OutputIntents"));
+/// ```
+pub fn to_pdf_bytes_with_options(shape: &Shape, options: PDFOptions) -> Result<Vec<u8>, PDFError> {
+    to_pdf_with_options(shape, options)?.save_to_bytes()
 }
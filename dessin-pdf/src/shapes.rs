@@ -32,11 +32,7 @@ fn color(c: dessin::style::Color) -> Color {
 
 fn setup_style(style: &Option<Style>, _: &IndirectFontRef, layer: &PdfLayerReference) {
     if let Some(fill) = style.as_ref().map(|v| v.fill).flatten() {
-        match fill {
-            Fill::Color(c) => {
-                layer.set_fill_color(color(c));
-            }
-        }
+        layer.set_fill_color(color(fill.color()));
     } else {
         layer.set_fill_color(Color::Rgb(Rgb {
             r: 0.0,
@@ -48,7 +44,7 @@ fn setup_style(style: &Option<Style>, _: &IndirectFontRef, layer: &PdfLayerRefer
 
     if let Some(stroke) = style.as_ref().map(|v| v.stroke).flatten() {
         match stroke {
-            Stroke::Full { color: c, width } => {
+            Stroke::Full { color: c, width, .. } => {
                 layer.set_outline_color(color(c));
                 layer.set_outline_thickness(width as f64);
                 layer.set_line_dash_pattern(LineDashPattern::default());
@@ -59,6 +55,7 @@ fn setup_style(style: &Option<Style>, _: &IndirectFontRef, layer: &PdfLayerRefer
                 width,
                 on,
                 off,
+                ..
             } => {
                 layer.set_outline_color(color(c));
                 layer.set_outline_thickness(width as f64);
@@ -0,0 +1,470 @@
+//! Draw [plotters](https://docs.rs/plotters/) charts into a dessin.
+//!
+//! [`DessinBackend`] is a plotters backend collecting everything drawn into a [`Group`].
+//! The chart can then be composed with other shapes, and exported with any dessin exporter.
+//!
+//! One pixel of the backend is one unit of the dessin, and the chart is centered around the origin.
+//!
+//! ```
+//! # use dessin::prelude::*;
+//! use dessin_plotters::DessinBackend;
+//! use plotters::prelude::{ChartBuilder, IntoDrawingArea, LineSeries, RED, WHITE};
+//!
+//! let mut chart = Group::default();
+//! {
+//! 	let area = DessinBackend::new(&mut chart, (400, 300)).into_drawing_area();
+//! 	area.fill(&WHITE).unwrap();
+//!
+//! 	let mut chart = ChartBuilder::on(&area)
+//! 		.margin(10)
+//! 		.build_cartesian_2d(0f32..10f32, 0f32..100f32)
+//! 		.unwrap();
+//! 	chart
+//! 		.draw_series(LineSeries::new((0..10).map(|x| (x as f32, (x * x) as f32)), &RED))
+//! 		.unwrap();
+//! }
+//!
+//! let report = dessin2!([
+//! 	Text!(text = "Squares", font_size = 20., translate = [0., 170.]),
+//! 	{ Shape::Group(chart) },
+//! ]);
+//! ```
+//!
+//! ## Error bars, reference lines and annotations
+//!
+//! Error bars, and reference lines or bands, are plotters elements and series like any other.
+//! Annotations are dessin shapes, such as a [`Leader`] pointing at a point of the chart,
+//! placed with [`backend_point`] from the position of the point in the backend.
+//!
+//! ```
+//! # use dessin::prelude::*;
+//! use dessin_plotters::{backend_point, DessinBackend};
+//! use plotters::prelude::{
+//! 	ChartBuilder, ErrorBar, IntoDrawingArea, LineSeries, Rectangle as Band, BLUE, RED,
+//! };
+//! // `mix` and `filled` of plotters colors, from a trait named like the `Color` of dessin
+//! use plotters::style::Color as _;
+//!
+//! let measures = [(1., 2.1, 0.3), (2., 3.9, 0.5), (3., 6.2, 0.4), (4., 7.8, 0.6)];
+//!
+//! let size = (400, 300);
+//! let mut chart = Group::default();
+//! let outlier = {
+//! 	let area = DessinBackend::new(&mut chart, size).into_drawing_area();
+//! 	let mut chart = ChartBuilder::on(&area)
+//! 		.margin(10)
+//! 		.build_cartesian_2d(0f32..5f32, 0f32..10f32)
+//! 		.unwrap();
+//!
+//! 	// Tolerance band, and its nominal value as a reference line
+//! 	chart
+//! 		.draw_series([Band::new([(0., 4.), (5., 6.)], BLUE.mix(0.2).filled())])
+//! 		.unwrap();
+//! 	chart
+//! 		.draw_series(LineSeries::new([(0., 5.), (5., 5.)], &BLUE))
+//! 		.unwrap();
+//!
+//! 	chart
+//! 		.draw_series(measures.iter().map(|&(x, y, error)| {
+//! 			ErrorBar::new_vertical(x, y - error, y, y + error, RED.filled(), 6)
+//! 		}))
+//! 		.unwrap();
+//!
+//! 	backend_point(chart.backend_coord(&(4., 7.8)), size)
+//! };
+//!
+//! let figure = dessin2!([
+//! 	{ Shape::Group(chart) },
+//! 	Leader(
+//! 		target = outlier,
+//! 		elbow = [outlier.x - 40., outlier.y + 30.],
+//! 		text = "Out of tolerance",
+//! 		font_size = 12.,
+//! 	),
+//! ]);
+//! ```
+//!
+//! ## Log, date and categorical axes
+//!
+//! Axis scales, their ticks and the format of their labels are the ones of plotters:
+//! a range turned into a log scale by `log_scale`, categories as the segments of `into_segmented`,
+//! named by a label formatter, or dates with the `datetime` feature of plotters.
+//! The backend draws every tick and label as dessin lines and texts.
+//!
+//! ```
+//! # use dessin::prelude::*;
+//! use dessin_plotters::DessinBackend;
+//! use plotters::prelude::{
+//! 	ChartBuilder, IntoDrawingArea, IntoLogRange, IntoSegmentedCoord, Rectangle as Bar,
+//! 	SegmentValue, BLUE,
+//! };
+//! use plotters::style::Color as _;
+//!
+//! let parts = ["Wing", "Fuselage", "Tail"];
+//! let masses = [1200f32, 3400., 150.];
+//!
+//! let mut chart = Group::default();
+//! {
+//! 	let area = DessinBackend::new(&mut chart, (400, 300)).into_drawing_area();
+//! 	let mut chart = ChartBuilder::on(&area)
+//! 		.margin(10)
+//! 		.x_label_area_size(30)
+//! 		.y_label_area_size(50)
+//! 		.build_cartesian_2d(
+//! 			(0..parts.len()).into_segmented(),
+//! 			(10f32..10_000f32).log_scale(),
+//! 		)
+//! 		.unwrap();
+//!
+//! 	chart
+//! 		.configure_mesh()
+//! 		.x_label_formatter(&|segment| match segment {
+//! 			SegmentValue::CenterOf(idx) => parts.get(*idx).unwrap_or(&"").to_string(),
+//! 			_ => String::new(),
+//! 		})
+//! 		.y_desc("Mass (kg)")
+//! 		.draw()
+//! 		.unwrap();
+//!
+//! 	chart
+//! 		.draw_series(masses.iter().enumerate().map(|(idx, mass)| {
+//! 			Bar::new(
+//! 				[(SegmentValue::Exact(idx), 10.), (SegmentValue::Exact(idx + 1), *mass)],
+//! 				BLUE.filled(),
+//! 			)
+//! 		}))
+//! 		.unwrap();
+//! }
+//!
+//! let labels = chart
+//! 	.shapes
+//! 	.iter()
+//! 	.filter_map(|shape| match shape {
+//! 		Shape::Style { shape, .. } => match shape.as_ref() {
+//! 			Shape::Text(text) => Some(text.text.as_str()),
+//! 			_ => None,
+//! 		},
+//! 		_ => None,
+//! 	})
+//! 	.collect::<Vec<_>>();
+//! assert!(parts.iter().all(|part| labels.contains(part)));
+//! ```
+//!
+//! With the `datetime` feature of plotters, a range of `chrono` dates is an axis of its own,
+//! whose ticks fall on days, weeks or months:
+//!
+//! ```
+//! # use dessin::prelude::*;
+//! use chrono::NaiveDate;
+//! use dessin_plotters::DessinBackend;
+//! use plotters::prelude::{ChartBuilder, IntoDrawingArea, LineSeries, RED};
+//!
+//! let first_flight = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+//! let flight_hours = (0..60u64).map(|day| {
+//! 	let date = first_flight + chrono::Days::new(day);
+//! 	(date, (day % 7) as f32)
+//! });
+//!
+//! let mut chart = Group::default();
+//! {
+//! 	let area = DessinBackend::new(&mut chart, (400, 300)).into_drawing_area();
+//! 	let mut chart = ChartBuilder::on(&area)
+//! 		.margin(10)
+//! 		.x_label_area_size(30)
+//! 		.y_label_area_size(30)
+//! 		.build_cartesian_2d(
+//! 			first_flight..NaiveDate::from_ymd_opt(2024, 3, 1).unwrap(),
+//! 			0f32..7f32,
+//! 		)
+//! 		.unwrap();
+//!
+//! 	chart
+//! 		.configure_mesh()
+//! 		.x_label_formatter(&|date| date.format("%Y-%m-%d").to_string())
+//! 		.y_desc("Flight hours")
+//! 		.draw()
+//! 		.unwrap();
+//!
+//! 	chart.draw_series(LineSeries::new(flight_hours, &RED)).unwrap();
+//! }
+//!
+//! let labels = chart
+//! 	.shapes
+//! 	.iter()
+//! 	.filter_map(|shape| match shape {
+//! 		Shape::Style { shape, .. } => match shape.as_ref() {
+//! 			Shape::Text(text) => Some(text.text.as_str()),
+//! 			_ => None,
+//! 		},
+//! 		_ => None,
+//! 	})
+//! 	.collect::<Vec<_>>();
+//! assert!(labels.iter().any(|label| label.starts_with("2024-01-")));
+//! assert!(labels.iter().any(|label| label.starts_with("2024-02-")));
+//! ```
+
+#![allow(clippy::tabs_in_doc_comments)]
+
+use dessin::{font::FontRef, prelude::*};
+use nalgebra::{Point2, Rotation2, Vector2};
+use plotters_backend::{
+    text_anchor::{HPos, VPos},
+    BackendColor, BackendCoord, BackendStyle, BackendTextStyle, DrawingBackend, DrawingErrorKind,
+    FontStyle, FontTransform,
+};
+use std::{convert::Infallible, f32::consts::FRAC_PI_2};
+
+/// Plotters backend drawing into a dessin [`Group`]
+pub struct DessinBackend<'a> {
+    group: &'a mut Group,
+    width: u32,
+    height: u32,
+    font: Option<FontRef>,
+}
+
+impl<'a> DessinBackend<'a> {
+    /// Backend of `width` x `height` pixels, adding its shapes to `group`
+    pub fn new(group: &'a mut Group, (width, height): (u32, u32)) -> Self {
+        DessinBackend {
+            group,
+            width,
+            height,
+            font: None,
+        }
+    }
+
+    /// Font of every text, instead of the default font
+    pub fn with_font(mut self, font: FontRef) -> Self {
+        self.font = Some(font);
+        self
+    }
+
+    fn point(&self, coord: BackendCoord) -> Point2<f32> {
+        backend_point(coord, (self.width, self.height))
+    }
+
+    fn curve<I: IntoIterator<Item = BackendCoord>>(&self, points: I, closed: bool) -> Curve {
+        Curve {
+            local_transform: Default::default(),
+            keypoints: points
+                .into_iter()
+                .map(|p| Keypoint::Point(self.point(p)))
+                .collect(),
+            closed,
+            start_marker: None,
+            end_marker: None,
+            fill_rule: FillRule::NonZero,
+        }
+    }
+
+    fn text(&self, text: &str, style: &impl BackendTextStyle) -> Text {
+        let font_weight = match style.style() {
+            FontStyle::Bold => FontWeight::Bold,
+            FontStyle::Italic | FontStyle::Oblique => FontWeight::Italic,
+            FontStyle::Normal => FontWeight::Regular,
+        };
+
+        Text {
+            text: text.to_string(),
+            font_size: style.size() as f32,
+            font_weight,
+            font: self.font.clone(),
+            ..Default::default()
+        }
+    }
+
+    fn push<S: Into<Shape>>(&mut self, shape: S) {
+        self.group.shapes.push(shape.into());
+    }
+}
+
+/// Point of the dessin drawn by a [`DessinBackend`] of `width` x `height` pixels at `coord` of the backend.
+///
+/// Places dessin shapes, such as annotations, on a chart,
+/// from the backend coordinates of a point of the chart, given by `ChartContext::backend_coord`.
+///
+/// ```
+/// use dessin_plotters::backend_point;
+///
+/// assert_eq!(backend_point((0, 0), (400, 300)), [-200., 150.].into());
+/// assert_eq!(backend_point((200, 300), (400, 300)), [0., -150.].into());
+/// ```
+pub fn backend_point((x, y): BackendCoord, (width, height): (u32, u32)) -> Point2<f32> {
+    Point2::new(x as f32 - width as f32 / 2., height as f32 / 2. - y as f32)
+}
+
+fn color(color: BackendColor) -> Color {
+    let (r, g, b) = color.rgb;
+    rgba(r, g, b, (color.alpha.clamp(0., 1.) * 255.).round() as u8)
+}
+
+fn stroke<S: BackendStyle>(style: &S) -> Stroke {
+    Stroke::from((color(style.color()), style.stroke_width() as f32))
+}
+
+impl DrawingBackend for DessinBackend<'_> {
+    type ErrorType = Infallible;
+
+    fn get_size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    fn ensure_prepared(&mut self) -> Result<(), DrawingErrorKind<Self::ErrorType>> {
+        Ok(())
+    }
+
+    fn present(&mut self) -> Result<(), DrawingErrorKind<Self::ErrorType>> {
+        Ok(())
+    }
+
+    fn draw_pixel(
+        &mut self,
+        point: BackendCoord,
+        pixel: BackendColor,
+    ) -> Result<(), DrawingErrorKind<Self::ErrorType>> {
+        let (x, y) = point;
+        let pixel_square = self.curve([(x, y), (x + 1, y), (x + 1, y + 1), (x, y + 1)], true);
+        self.push(Style::new(pixel_square).with_fill(color(pixel)));
+        Ok(())
+    }
+
+    fn draw_line<S: BackendStyle>(
+        &mut self,
+        from: BackendCoord,
+        to: BackendCoord,
+        style: &S,
+    ) -> Result<(), DrawingErrorKind<Self::ErrorType>> {
+        let line = self.curve([from, to], false);
+        self.push(Style::new(line).with_stroke(stroke(style)));
+        Ok(())
+    }
+
+    fn draw_rect<S: BackendStyle>(
+        &mut self,
+        upper_left: BackendCoord,
+        bottom_right: BackendCoord,
+        style: &S,
+        fill: bool,
+    ) -> Result<(), DrawingErrorKind<Self::ErrorType>> {
+        let (left, top) = upper_left;
+        let (right, bottom) = bottom_right;
+        let rect = self.curve(
+            [(left, top), (right, top), (right, bottom), (left, bottom)],
+            true,
+        );
+
+        if fill {
+            self.push(Style::new(rect).with_fill(color(style.color())));
+        } else {
+            self.push(Style::new(rect).with_stroke(stroke(style)));
+        }
+        Ok(())
+    }
+
+    fn draw_path<S: BackendStyle, I: IntoIterator<Item = BackendCoord>>(
+        &mut self,
+        path: I,
+        style: &S,
+    ) -> Result<(), DrawingErrorKind<Self::ErrorType>> {
+        let path = self.curve(path, false);
+        self.push(Style::new(path).with_stroke(stroke(style)));
+        Ok(())
+    }
+
+    fn draw_circle<S: BackendStyle>(
+        &mut self,
+        center: BackendCoord,
+        radius: u32,
+        style: &S,
+        fill: bool,
+    ) -> Result<(), DrawingErrorKind<Self::ErrorType>> {
+        let circle = Circle::default()
+            .with_radius(radius as f32)
+            .with_translate(self.point(center) - Point2::origin());
+
+        if fill {
+            self.push(Style::new(circle).with_fill(color(style.color())));
+        } else {
+            self.push(Style::new(circle).with_stroke(stroke(style)));
+        }
+        Ok(())
+    }
+
+    fn fill_polygon<S: BackendStyle, I: IntoIterator<Item = BackendCoord>>(
+        &mut self,
+        vert: I,
+        style: &S,
+    ) -> Result<(), DrawingErrorKind<Self::ErrorType>> {
+        let polygon = self.curve(vert, true);
+        self.push(Style::new(polygon).with_fill(color(style.color())));
+        Ok(())
+    }
+
+    fn draw_text<TStyle: BackendTextStyle>(
+        &mut self,
+        text: &str,
+        style: &TStyle,
+        pos: BackendCoord,
+    ) -> Result<(), DrawingErrorKind<Self::ErrorType>> {
+        let anchor = style.anchor();
+        let align = match anchor.h_pos {
+            HPos::Left => TextAlign::Left,
+            HPos::Center => TextAlign::Center,
+            HPos::Right => TextAlign::Right,
+        };
+        let vertical_align = match anchor.v_pos {
+            VPos::Top => TextVerticalAlign::Top,
+            VPos::Center => TextVerticalAlign::Center,
+            VPos::Bottom => TextVerticalAlign::Bottom,
+        };
+        // Plotters rotates clockwise, with the Y axis going down
+        let rotation = match style.transform() {
+            FontTransform::None => 0.,
+            FontTransform::Rotate90 => -FRAC_PI_2,
+            FontTransform::Rotate180 => 2. * -FRAC_PI_2,
+            FontTransform::Rotate270 => 3. * -FRAC_PI_2,
+        };
+
+        let text = Text {
+            align,
+            vertical_align,
+            ..self.text(text, style)
+        }
+        .with_rotate(Rotation2::new(rotation))
+        .with_translate(self.point(pos) - Point2::origin());
+
+        self.push(Style::new(text).with_fill(color(style.color())));
+        Ok(())
+    }
+
+    fn estimate_text_size<TStyle: BackendTextStyle>(
+        &self,
+        text: &str,
+        style: &TStyle,
+    ) -> Result<(u32, u32), DrawingErrorKind<Self::ErrorType>> {
+        let bb = self.text(text, style).local_bounding_box().straigthen();
+        Ok((bb.width().ceil() as u32, bb.height().ceil() as u32))
+    }
+
+    fn blit_bitmap(
+        &mut self,
+        pos: BackendCoord,
+        (width, height): (u32, u32),
+        src: &[u8],
+    ) -> Result<(), DrawingErrorKind<Self::ErrorType>> {
+        let Some(pixels) = dessin::image::RgbImage::from_raw(width, height, src.to_vec()) else {
+            return Ok(());
+        };
+
+        let top_left = self.point(pos);
+        let image = Image::default()
+            .with_image(dessin::image::DynamicImage::ImageRgb8(pixels))
+            .with_resize(nalgebra::Scale2::new(width as f32, height as f32))
+            .with_translate(
+                top_left - Point2::origin() + Vector2::new(width as f32, -(height as f32)) / 2.,
+            );
+
+        self.push(image);
+        Ok(())
+    }
+}
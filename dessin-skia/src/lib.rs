@@ -0,0 +1,439 @@
+//! Draw a dessin into a [Skia](https://skia.org/) canvas.
+//!
+//! The canvas can come from any Skia surface: a raster surface, a GPU surface of a GUI application, a PDF document...
+//! Anti-aliasing and blending are done by Skia, so the output looks the same as the rest of the application.
+//!
+//! ```no_run
+//! # use dessin::prelude::*;
+//! use dessin_skia::{draw_with_options, SkiaOptions};
+//!
+//! let dessin = dessin2!(Circle!(fill = Color::RED, radius = 40.));
+//!
+//! let mut surface = skia_safe::surfaces::raster_n32_premul((100, 100)).unwrap();
+//! let canvas = surface.canvas();
+//!
+//! // The dessin is drawn around the origin of the canvas, with the Y axis going up
+//! canvas.translate((50., 50.));
+//! draw_with_options(&dessin, canvas, &SkiaOptions::default()).unwrap();
+//! ```
+
+#![allow(clippy::tabs_in_doc_comments)]
+
+use dessin::{
+    export::{inherited_style, Export, Exporter},
+    font::FontRef,
+    prelude::*,
+};
+use nalgebra::{Scale2, Transform2};
+use skia_safe::{
+    images, AlphaType, BlendMode, Canvas, Color as SkColor, ColorType, Data, Font, FontMgr,
+    ImageInfo, Paint, PaintCap, PaintJoin, PaintStyle, Path, PathEffect, PathFillType, Rect,
+    Typeface,
+};
+use std::{collections::HashMap, fmt};
+
+#[derive(Debug)]
+pub enum SkiaError {
+    CurveHasNoStartingPoint(CurvePosition),
+    ImageError,
+    FontLoadingError(FontRef, FontWeight),
+}
+impl fmt::Display for SkiaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+impl std::error::Error for SkiaError {}
+
+#[derive(Debug, Clone)]
+pub struct SkiaOptions {
+    /// Smooth the edges of shapes and texts
+    pub anti_alias: bool,
+    /// How the dessin is blended with what is already on the canvas, unless a [style][Style::blend_mode] says otherwise
+    pub blend_mode: BlendMode,
+}
+impl Default for SkiaOptions {
+    fn default() -> Self {
+        SkiaOptions {
+            anti_alias: true,
+            blend_mode: BlendMode::SrcOver,
+        }
+    }
+}
+
+pub struct SkiaExporter<'a> {
+    canvas: &'a Canvas,
+    options: SkiaOptions,
+    style: Vec<StylePosition>,
+    font_manager: FontMgr,
+    typefaces: HashMap<(FontRef, FontWeight), Typeface>,
+}
+
+impl<'a> SkiaExporter<'a> {
+    pub fn new(canvas: &'a Canvas, options: SkiaOptions) -> Self {
+        SkiaExporter {
+            canvas,
+            options,
+            style: vec![],
+            font_manager: FontMgr::new(),
+            typefaces: HashMap::default(),
+        }
+    }
+
+    /// Blend mode of the innermost style which has one, or the one of the options
+    fn blend_mode(&self) -> BlendMode {
+        match inherited_style(&self.style).blend_mode {
+            dessin::style::BlendMode::Normal => self.options.blend_mode,
+            blend_mode => skia_blend_mode(blend_mode),
+        }
+    }
+
+    fn paint(&self, color: Color) -> Paint {
+        let (r, g, b, a) = color.rgba();
+
+        let mut paint = Paint::default();
+        paint
+            .set_anti_alias(self.options.anti_alias)
+            .set_blend_mode(self.blend_mode())
+            .set_color(SkColor::from_argb(a, r, g, b));
+        paint
+    }
+
+    fn fill_paint(&self) -> Option<Paint> {
+        match inherited_style(&self.style).fill {
+            Some(fill) => {
+                let mut paint = self.paint(fill.color());
+                paint.set_style(PaintStyle::Fill);
+                Some(paint)
+            }
+            None => None,
+        }
+    }
+
+    fn stroke_paint(&self) -> Option<Paint> {
+        let stroke = inherited_style(&self.style).stroke?;
+        let (color, width) = (stroke.color(), stroke.width());
+        let dash = stroke
+            .dashes()
+            .and_then(|(dashes, offset)| PathEffect::dash(dashes.as_slice(), offset));
+
+        let mut paint = self.paint(color);
+        paint
+            .set_style(PaintStyle::Stroke)
+            .set_stroke_width(width)
+            .set_stroke_cap(match stroke.cap() {
+                LineCap::Butt => PaintCap::Butt,
+                LineCap::Round => PaintCap::Round,
+                LineCap::Square => PaintCap::Square,
+            })
+            .set_stroke_join(match stroke.join() {
+                LineJoin::Miter => PaintJoin::Miter,
+                LineJoin::Round => PaintJoin::Round,
+                LineJoin::Bevel => PaintJoin::Bevel,
+            })
+            .set_stroke_miter(stroke.miter_limit())
+            .set_path_effect(dash);
+        Some(paint)
+    }
+
+    fn draw_path(&mut self, path: &Path) {
+        if let Some(paint) = self.fill_paint() {
+            self.canvas.draw_path(path, &paint);
+        }
+        if let Some(paint) = self.stroke_paint() {
+            self.canvas.draw_path(path, &paint);
+        }
+    }
+
+    fn typeface(&mut self, font: FontRef, font_weight: FontWeight) -> Result<Typeface, SkiaError> {
+        if let Some(typeface) = self.typefaces.get(&(font.clone(), font_weight)) {
+            return Ok(typeface.clone());
+        }
+
+        let font_group = dessin::font::get(font.clone());
+        let typeface = self
+            .font_manager
+            .new_from_data(font_group.get(font_weight).as_bytes(), None)
+            .ok_or_else(|| SkiaError::FontLoadingError(font.clone(), font_weight))?;
+
+        self.typefaces.insert((font, font_weight), typeface.clone());
+
+        Ok(typeface)
+    }
+}
+
+fn skia_blend_mode(blend_mode: dessin::style::BlendMode) -> BlendMode {
+    use dessin::style::BlendMode as B;
+
+    match blend_mode {
+        B::Normal => BlendMode::SrcOver,
+        B::Multiply => BlendMode::Multiply,
+        B::Screen => BlendMode::Screen,
+        B::Overlay => BlendMode::Overlay,
+        B::Darken => BlendMode::Darken,
+        B::Lighten => BlendMode::Lighten,
+        B::ColorDodge => BlendMode::ColorDodge,
+        B::ColorBurn => BlendMode::ColorBurn,
+        B::HardLight => BlendMode::HardLight,
+        B::SoftLight => BlendMode::SoftLight,
+        B::Difference => BlendMode::Difference,
+        B::Exclusion => BlendMode::Exclusion,
+        B::Hue => BlendMode::Hue,
+        B::Saturation => BlendMode::Saturation,
+        B::Color => BlendMode::Color,
+        B::Luminosity => BlendMode::Luminosity,
+    }
+}
+
+impl Exporter for SkiaExporter<'_> {
+    type Error = SkiaError;
+    const BACKEND: Option<&'static str> = Some("skia");
+    const CAN_EXPORT_BLEND_MODE: bool = true;
+
+    fn start_style(&mut self, style: StylePosition) -> Result<(), Self::Error> {
+        self.style.push(style);
+        Ok(())
+    }
+
+    fn end_style(&mut self) -> Result<(), Self::Error> {
+        self.style.pop();
+        Ok(())
+    }
+
+    fn export_image(
+        &mut self,
+        ImagePosition {
+            center,
+            width,
+            height,
+            rotation,
+            image,
+            ..
+        }: ImagePosition,
+    ) -> Result<(), Self::Error> {
+        let rgba = image.to_rgba8();
+        let info = ImageInfo::new(
+            (rgba.width() as i32, rgba.height() as i32),
+            ColorType::RGBA8888,
+            AlphaType::Unpremul,
+            None,
+        );
+        let row_bytes = rgba.width() as usize * 4;
+        let image = images::raster_from_data(&info, Data::new_copy(rgba.as_raw()), row_bytes)
+            .ok_or(SkiaError::ImageError)?;
+
+        let mut paint = Paint::default();
+        paint
+            .set_anti_alias(self.options.anti_alias)
+            .set_blend_mode(self.blend_mode());
+
+        self.canvas.save();
+        self.canvas.translate((center.x, center.y));
+        self.canvas.rotate(-rotation.to_degrees(), None);
+        self.canvas.draw_image_rect(
+            image,
+            None,
+            Rect::from_xywh(-width / 2., -height / 2., width, height),
+            &paint,
+        );
+        self.canvas.restore();
+
+        Ok(())
+    }
+
+    fn export_ellipse(
+        &mut self,
+        EllipsePosition {
+            center,
+            semi_major_axis,
+            semi_minor_axis,
+            rotation,
+        }: EllipsePosition,
+    ) -> Result<(), Self::Error> {
+        let oval = Rect::new(
+            -semi_major_axis,
+            -semi_minor_axis,
+            semi_major_axis,
+            semi_minor_axis,
+        );
+
+        self.canvas.save();
+        self.canvas.translate((center.x, center.y));
+        self.canvas.rotate(-rotation.to_degrees(), None);
+        if let Some(paint) = self.fill_paint() {
+            self.canvas.draw_oval(oval, &paint);
+        }
+        if let Some(paint) = self.stroke_paint() {
+            self.canvas.draw_oval(oval, &paint);
+        }
+        self.canvas.restore();
+
+        Ok(())
+    }
+
+    fn export_curve(&mut self, curve: CurvePosition) -> Result<(), Self::Error> {
+        let mut path = Path::new();
+
+        for (idx, k) in curve.keypoints.iter().enumerate() {
+            let is_first = idx == 0;
+
+            match k {
+                KeypointPosition::Point(p) if is_first => {
+                    path.move_to((p.x, p.y));
+                }
+                KeypointPosition::Point(p) => {
+                    path.line_to((p.x, p.y));
+                }
+                KeypointPosition::Bezier(b) => {
+                    match (is_first, b.start) {
+                        (true, None) => return Err(SkiaError::CurveHasNoStartingPoint(curve)),
+                        (true, Some(s)) => {
+                            path.move_to((s.x, s.y));
+                        }
+                        (false, None) => {}
+                        (false, Some(s)) => {
+                            path.line_to((s.x, s.y));
+                        }
+                    }
+
+                    path.cubic_to(
+                        (b.start_control.x, b.start_control.y),
+                        (b.end_control.x, b.end_control.y),
+                        (b.end.x, b.end.y),
+                    );
+                }
+            }
+        }
+
+        if curve.closed {
+            path.close();
+        }
+        if curve.fill_rule == FillRule::EvenOdd {
+            path.set_fill_type(PathFillType::EvenOdd);
+        }
+
+        self.draw_path(&path);
+
+        Ok(())
+    }
+
+    fn export_text(
+        &mut self,
+        TextPosition {
+            text,
+            align,
+            font_weight,
+            font_size,
+            reference_start,
+            direction,
+            font,
+            ..
+        }: TextPosition,
+    ) -> Result<(), Self::Error> {
+        let Some(paint) = self.fill_paint() else {
+            return Ok(());
+        };
+
+        let typeface = self.typeface(font.clone().unwrap_or_default(), font_weight)?;
+        let mut font = Font::from_typeface(typeface, font_size);
+        font.set_subpixel(self.options.anti_alias);
+
+        let (width, _) = font.measure_str(text, Some(&paint));
+        let start = match align {
+            TextAlign::Left => 0.,
+            TextAlign::Center => -width / 2.,
+            TextAlign::Right => -width,
+        };
+
+        self.canvas.save();
+        self.canvas
+            .translate((reference_start.x, reference_start.y));
+        self.canvas
+            .rotate(direction.y.atan2(direction.x).to_degrees(), None);
+        self.canvas.draw_str(text, (start, 0.), &font, &paint);
+        self.canvas.restore();
+
+        Ok(())
+    }
+}
+
+/// Draw a dessin around the origin of a canvas.
+///
+/// The Y axis of a dessin goes up, so it is mirrored to match the canvas.
+/// Images and ellipses are rotated the same way as by the SVG and canvas exporters.
+///
+/// ```
+/// # use dessin::prelude::*;
+/// use dessin_skia::{draw_with_options, SkiaOptions};
+/// use skia_safe::ImageInfo;
+///
+/// let dessin = dessin2!(Ellipse!(
+/// 	fill = Color::RED,
+/// 	semi_major_axis = 40.,
+/// 	semi_minor_axis = 5.,
+/// 	rotate = Angle::degrees(45.)
+/// ) > ());
+///
+/// let mut surface = skia_safe::surfaces::raster_n32_premul((100, 100)).unwrap();
+/// {
+/// 	let canvas = surface.canvas();
+/// 	canvas.translate((50., 50.));
+/// 	draw_with_options(&dessin, canvas, &SkiaOptions::default()).unwrap();
+/// }
+///
+/// let mut alpha_at = |x: i32, y: i32| {
+/// 	let mut pixel = [0_u8; 4];
+/// 	assert!(surface.read_pixels(&ImageInfo::new_n32_premul((1, 1), None), &mut pixel, 4, (x, y)));
+/// 	pixel[3]
+/// };
+///
+/// // Along the major axis of the ellipse
+/// assert_eq!(alpha_at(50, 50), 255);
+/// assert_eq!(alpha_at(70, 70), 255);
+/// assert_eq!(alpha_at(30, 30), 255);
+/// // Along the other diagonal
+/// assert_eq!(alpha_at(70, 30), 0);
+/// assert_eq!(alpha_at(30, 70), 0);
+/// ```
+///
+/// The [blend mode of a style][Style::blend_mode] is set on the paint of each shape it contains
+///
+/// ```
+/// # use dessin::prelude::*;
+/// use dessin_skia::{draw_with_options, SkiaOptions};
+/// use skia_safe::{AlphaType, ColorType, ImageInfo};
+///
+/// let ink = dessin2!(Rectangle!(fill = Color::RED, width = 20., height = 20.) > ());
+/// let dessin = dessin2!([
+/// 	Rectangle!(fill = Color::BLUE, width = 20., height = 20.),
+/// 	{ Style::new(ink).with_blend_mode(BlendMode::Multiply) },
+/// ] > ());
+///
+/// let mut surface = skia_safe::surfaces::raster_n32_premul((20, 20)).unwrap();
+/// {
+/// 	let canvas = surface.canvas();
+/// 	canvas.translate((10., 10.));
+/// 	draw_with_options(&dessin, canvas, &SkiaOptions::default()).unwrap();
+/// }
+///
+/// let mut pixel = [0_u8; 4];
+/// let info = ImageInfo::new((1, 1), ColorType::RGBA8888, AlphaType::Premul, None);
+/// assert!(surface.read_pixels(&info, &mut pixel, 4, (10, 10)));
+/// // Red multiplied by blue is black, where a normal red square would hide the blue one
+/// assert_eq!(pixel, [0, 0, 0, 255]);
+/// ```
+pub fn draw_with_options(
+    shape: &Shape,
+    canvas: &Canvas,
+    options: &SkiaOptions,
+) -> Result<(), SkiaError> {
+    let mut exporter = SkiaExporter::new(canvas, options.clone());
+
+    let parent_transform = nalgebra::convert::<_, Transform2<f32>>(Scale2::new(1., -1.));
+    shape.write_into_exporter(&mut exporter, &parent_transform)
+}
+
+/// Draw a dessin around the origin of a canvas, with the default [`SkiaOptions`]
+pub fn draw(shape: &Shape, canvas: &Canvas) -> Result<(), SkiaError> {
+    draw_with_options(shape, canvas, &SkiaOptions::default())
+}
@@ -0,0 +1,528 @@
+//! Looping animations, written as SMIL `<animate>` and `<animateTransform>` elements.
+//!
+//! An [`Animation`] changes a property of a group over time, going through a value at each keyframe.
+//! It is attached to the group as the `svg:animate` hint, either with the [`Animated`] wrapper,
+//! or directly in its metadata as text, such as `("svg:animate", "opacity 1;0.2;1 2s")`.
+//! [`dessin_animate!`][crate::dessin_animate] declares the keyframes of an [`Animated`] shape next to it.
+//! Other backends ignore it, and draw the group as it is.
+//!
+//! ```
+//! # use dessin::prelude::*;
+//! use dessin_svg::animation::{Animated, Animation, Keyframes};
+//!
+//! // A status light blinking red, and a spinner turning every second
+//! let light = Animated::new(dessin2!(Circle!(fill = Color::GREEN, radius = 2.)))
+//!     .with_animation(Animation::new(
+//!         Keyframes::Fill(vec![Color::GREEN, Color::RED, Color::GREEN]),
+//!         1.,
+//!     ));
+//! let spinner = Animated::new(dessin2!(Line!(
+//!     stroke = (Color::BLACK, 0.5),
+//!     from = [0., 0.],
+//!     to = [4., 0.],
+//! )))
+//! .with_animation(Animation::new(
+//!     Keyframes::Rotate(vec![Angle::ZERO, Angle::FULL_TURN]),
+//!     1.,
+//! ))
+//! .with_translate([10., 0.]);
+//!
+//! let svg = dessin_svg::to_string(&dessin2!([{ light }, { spinner }])).unwrap();
+//!
+//! assert!(svg.contains(r##"<animate attributeName="fill" values="#00FF00;#FF0000;#00FF00" dur="1s" repeatCount="indefinite"/>"##));
+//! // Turning around its own origin
+//! assert!(svg.contains(r#"type="rotate" values="0 10 0;-360 10 0""#));
+//! ```
+
+use crate::SVGError;
+use dessin::{export::split_hint, prelude::*};
+use nalgebra::{Point2, Transform2, Vector2};
+use std::{
+    f32::consts::FRAC_1_SQRT_2,
+    fmt::{self, Write},
+    str::FromStr,
+};
+
+/// Hint holding an [`Animation`], as text
+pub const ANIMATE_HINT: &str = "animate";
+
+/// Animated property, and its value at each keyframe.
+///
+/// Lengths and positions are in the coordinates of the animated group, and follow its transform.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Keyframes {
+    /// Opacity of the whole group, between 0 and 1
+    Opacity(Vec<f32>),
+    /// Color of the filled shapes of the group
+    Fill(Vec<Color>),
+    /// Color of the stroked shapes of the group
+    Stroke(Vec<Color>),
+    /// Width of the strokes of the group
+    StrokeWidth(Vec<f32>),
+    /// Offset of the group
+    Translate(Vec<Vector2<f32>>),
+    /// Rotation of the group around its origin
+    Rotate(Vec<Angle>),
+    /// Scale of the group around its origin
+    Scale(Vec<f32>),
+}
+impl Keyframes {
+    /// Name of the property, as written in the `svg:animate` hint
+    pub fn name(&self) -> &'static str {
+        match self {
+            Keyframes::Opacity(_) => "opacity",
+            Keyframes::Fill(_) => "fill",
+            Keyframes::Stroke(_) => "stroke",
+            Keyframes::StrokeWidth(_) => "stroke-width",
+            Keyframes::Translate(_) => "translate",
+            Keyframes::Rotate(_) => "rotate",
+            Keyframes::Scale(_) => "scale",
+        }
+    }
+}
+
+/// Change of a property of a group, looping over `duration` seconds
+#[derive(Debug, Clone, PartialEq)]
+pub struct Animation {
+    /// Animated property, and its value at each keyframe
+    pub keyframes: Keyframes,
+    /// Time of each keyframe, from 0 at the start of a loop to 1 at its end, evenly spread if empty
+    pub key_times: Vec<f32>,
+    /// Duration of a loop, in seconds
+    pub duration: f32,
+    /// Number of loops, forever if `None`
+    pub repeat: Option<u32>,
+}
+impl Animation {
+    /// Animation going through `keyframes` every `duration` seconds, forever
+    pub fn new(keyframes: Keyframes, duration: f32) -> Self {
+        Animation {
+            keyframes,
+            key_times: vec![],
+            duration,
+            repeat: None,
+        }
+    }
+
+    /// Time of each keyframe, from 0 at the start of a loop to 1 at its end
+    #[inline]
+    pub fn with_key_times(mut self, key_times: Vec<f32>) -> Self {
+        self.key_times = key_times;
+        self
+    }
+
+    /// Play `repeat` loops, then stop
+    #[inline]
+    pub fn with_repeat(mut self, repeat: u32) -> Self {
+        self.repeat = Some(repeat);
+        self
+    }
+
+    /// Whether the animation applies to styles under the group, instead of the group itself
+    pub(crate) fn is_style(&self) -> bool {
+        matches!(
+            self.keyframes,
+            Keyframes::Fill(_) | Keyframes::Stroke(_) | Keyframes::StrokeWidth(_)
+        )
+    }
+
+    /// Whether the animation applies to a style with `fill` and `stroke`
+    pub(crate) fn applies_to(&self, fill: bool, stroke: bool) -> bool {
+        match self.keyframes {
+            Keyframes::Fill(_) => fill,
+            Keyframes::Stroke(_) | Keyframes::StrokeWidth(_) => stroke,
+            _ => false,
+        }
+    }
+
+    /// SMIL elements of the animation, for a group drawn with `transform`
+    pub(crate) fn to_elements(&self, transform: &Transform2<f32>) -> Result<String, SVGError> {
+        let mut timing = format!(r#"dur="{}s" "#, self.duration);
+        match self.repeat {
+            Some(repeat) => write!(timing, r#"repeatCount="{repeat}""#)?,
+            None => write!(timing, r#"repeatCount="indefinite""#)?,
+        }
+        if !self.key_times.is_empty() {
+            write!(timing, r#" keyTimes="{}""#, join(&self.key_times))?;
+        }
+
+        // Transforms are baked into the coordinates of the SVG, so animations are converted to them
+        let origin = transform * Point2::origin();
+        let factor = (transform * Vector2::new(FRAC_1_SQRT_2, FRAC_1_SQRT_2)).magnitude();
+        let mirrored = transform.matrix().fixed_view::<2, 2>(0, 0).determinant() < 0.;
+
+        let animate = |attribute: &str, values: String| {
+            format!(r#"<animate attributeName="{attribute}" values="{values}" {timing}/>"#)
+        };
+        let animate_transform = |kind: &str, values: String| {
+            format!(
+                r#"<animateTransform attributeName="transform" type="{kind}" values="{values}" additive="sum" {timing}/>"#
+            )
+        };
+
+        let elements = match &self.keyframes {
+            Keyframes::Opacity(values) => animate("opacity", join(values)),
+            Keyframes::Fill(colors) => animate("fill", join(colors)),
+            Keyframes::Stroke(colors) => animate("stroke", join(colors)),
+            Keyframes::StrokeWidth(widths) => animate(
+                "stroke-width",
+                join(&widths.iter().map(|w| w * factor).collect::<Vec<_>>()),
+            ),
+            Keyframes::Translate(offsets) => animate_transform(
+                "translate",
+                offsets
+                    .iter()
+                    .map(|offset| {
+                        let offset = transform * offset;
+                        format!("{} {}", offset.x, offset.y)
+                    })
+                    .collect::<Vec<_>>()
+                    .join(";"),
+            ),
+            Keyframes::Rotate(angles) => animate_transform(
+                "rotate",
+                angles
+                    .iter()
+                    .map(|angle| {
+                        // SVG angles are clockwise in a Y axis pointing down, subtracted to avoid `-0`
+                        let degrees = if mirrored {
+                            0. - angle.as_degrees()
+                        } else {
+                            angle.as_degrees()
+                        };
+                        format!("{degrees} {} {}", origin.x, origin.y)
+                    })
+                    .collect::<Vec<_>>()
+                    .join(";"),
+            ),
+            // SVG scales around the origin of the document, so the group is moved there and back
+            Keyframes::Scale(scales) => [
+                animate_transform("translate", format!("{} {}", origin.x, origin.y)),
+                animate_transform(
+                    "scale",
+                    scales
+                        .iter()
+                        .map(ToString::to_string)
+                        .collect::<Vec<_>>()
+                        .join(";"),
+                ),
+                animate_transform("translate", format!("{} {}", -origin.x, -origin.y)),
+            ]
+            .concat(),
+        };
+
+        Ok(elements)
+    }
+}
+
+fn join<T: fmt::Display>(values: &[T]) -> String {
+    values
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(";")
+}
+
+/// Written as `<property> <values> <duration>s`, followed by `x<repeat>` for a limited number of loops,
+/// and `@<key times>` for keyframes unevenly spread.
+///
+/// Values and key times are separated by `;`, offsets are written `x,y`, and angles in degrees.
+///
+/// ```
+/// # use dessin::prelude::*;
+/// use dessin_svg::animation::{Animation, Keyframes};
+///
+/// let pulse: Animation = "scale 1;1.5;1 0.8s x3 @0;0.2;1".parse().unwrap();
+/// assert_eq!(pulse.keyframes, Keyframes::Scale(vec![1., 1.5, 1.]));
+/// assert_eq!(pulse.repeat, Some(3));
+/// assert_eq!(pulse.to_string(), "scale 1;1.5;1 0.8s x3 @0;0.2;1");
+///
+/// let blink: Animation = "fill #FF0000;#00FF0080 2s".parse().unwrap();
+/// assert_eq!(blink.keyframes, Keyframes::Fill(vec![Color::RED, rgba(0, 255, 0, 128)]));
+///
+/// assert!("fill red 2s".parse::<Animation>().is_err());
+/// assert!("opacity 1;0".parse::<Animation>().is_err());
+/// ```
+impl fmt::Display for Animation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let values = match &self.keyframes {
+            Keyframes::Opacity(values)
+            | Keyframes::StrokeWidth(values)
+            | Keyframes::Scale(values) => join(values),
+            Keyframes::Fill(colors) | Keyframes::Stroke(colors) => join(colors),
+            Keyframes::Translate(offsets) => offsets
+                .iter()
+                .map(|offset| format!("{},{}", offset.x, offset.y))
+                .collect::<Vec<_>>()
+                .join(";"),
+            Keyframes::Rotate(angles) => join(
+                &angles
+                    .iter()
+                    .map(|angle| angle.as_degrees())
+                    .collect::<Vec<_>>(),
+            ),
+        };
+
+        write!(f, "{} {values} {}s", self.keyframes.name(), self.duration)?;
+        if let Some(repeat) = self.repeat {
+            write!(f, " x{repeat}")?;
+        }
+        if !self.key_times.is_empty() {
+            write!(f, " @{}", join(&self.key_times))?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Animation {
+    type Err = SVGError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || SVGError::InvalidAnimation(s.to_string());
+
+        let mut tokens = s.split_whitespace();
+        let (Some(name), Some(values), Some(duration)) =
+            (tokens.next(), tokens.next(), tokens.next())
+        else {
+            return Err(invalid());
+        };
+
+        let numbers = || -> Result<Vec<f32>, SVGError> {
+            values
+                .split(';')
+                .map(|v| v.trim().parse::<f32>().map_err(|_| invalid()))
+                .collect()
+        };
+        let colors = || -> Result<Vec<Color>, SVGError> {
+            values
+                .split(';')
+                .map(|v| parse_color(v.trim()).ok_or_else(invalid))
+                .collect()
+        };
+
+        let keyframes = match name {
+            "opacity" => Keyframes::Opacity(numbers()?),
+            "fill" => Keyframes::Fill(colors()?),
+            "stroke" => Keyframes::Stroke(colors()?),
+            "stroke-width" => Keyframes::StrokeWidth(numbers()?),
+            "translate" => Keyframes::Translate(
+                values
+                    .split(';')
+                    .map(|v| {
+                        let (x, y) = v.split_once(',').ok_or_else(invalid)?;
+                        match (x.trim().parse(), y.trim().parse()) {
+                            (Ok(x), Ok(y)) => Ok(Vector2::new(x, y)),
+                            _ => Err(invalid()),
+                        }
+                    })
+                    .collect::<Result<_, _>>()?,
+            ),
+            "rotate" => Keyframes::Rotate(numbers()?.into_iter().map(Angle::degrees).collect()),
+            "scale" => Keyframes::Scale(numbers()?),
+            _ => return Err(invalid()),
+        };
+
+        let duration = duration
+            .strip_suffix('s')
+            .and_then(|d| d.parse().ok())
+            .ok_or_else(invalid)?;
+
+        let mut animation = Animation::new(keyframes, duration);
+        for token in tokens {
+            if let Some(repeat) = token.strip_prefix('x') {
+                animation.repeat = Some(repeat.parse().map_err(|_| invalid())?);
+            } else if let Some(key_times) = token.strip_prefix('@') {
+                animation.key_times = key_times
+                    .split(';')
+                    .map(|t| t.parse().map_err(|_| invalid()))
+                    .collect::<Result<_, _>>()?;
+            } else {
+                return Err(invalid());
+            }
+        }
+
+        Ok(animation)
+    }
+}
+
+/// Color written `#RRGGBB` or `#RRGGBBAA`
+fn parse_color(color: &str) -> Option<Color> {
+    let hex = color.strip_prefix('#')?;
+    if !hex.is_ascii() || (hex.len() != 6 && hex.len() != 8) {
+        return None;
+    }
+
+    let channel = |idx: usize| u8::from_str_radix(&hex[idx..idx + 2], 16).ok();
+    if hex.len() == 8 {
+        Some(rgba(channel(0)?, channel(2)?, channel(4)?, channel(6)?))
+    } else {
+        Some(rgb(channel(0)?, channel(2)?, channel(4)?))
+    }
+}
+
+/// Whether `metadata` holds an `svg:animate` hint
+pub(crate) fn is_animated(metadata: &[(String, String)]) -> bool {
+    metadata.iter().any(|(key, _)| is_animate_hint(key))
+}
+
+pub(crate) fn is_animate_hint(key: &str) -> bool {
+    split_hint(key) == Some((crate::BACKEND, ANIMATE_HINT))
+}
+
+/// Animations of the `svg:animate` hints of `metadata`
+pub(crate) fn animations_of(metadata: &[(String, String)]) -> Result<Vec<Animation>, SVGError> {
+    metadata
+        .iter()
+        .filter(|(key, _)| is_animate_hint(key))
+        .map(|(_, value)| value.parse())
+        .collect()
+}
+
+/// Shape with [animations][Animation], played when exported to SVG
+#[derive(Debug, Clone)]
+pub struct Animated<T> {
+    shape: T,
+    animations: Vec<Animation>,
+    local_transform: Transform2<f32>,
+}
+impl<T> Animated<T> {
+    /// `shape`, not animated yet
+    pub fn new(shape: T) -> Self {
+        Animated {
+            shape,
+            animations: vec![],
+            local_transform: Transform2::identity(),
+        }
+    }
+
+    /// Add an animation, played along the others
+    #[inline]
+    pub fn animation(&mut self, animation: Animation) -> &mut Self {
+        self.animations.push(animation);
+        self
+    }
+    /// Add an animation, played along the others
+    #[inline]
+    pub fn with_animation(mut self, animation: Animation) -> Self {
+        self.animation(animation);
+        self
+    }
+}
+
+impl<T> ShapeOp for Animated<T> {
+    #[inline]
+    fn transform(&mut self, transform_matrix: Transform2<f32>) -> &mut Self {
+        self.local_transform = transform_matrix * self.local_transform;
+        self
+    }
+
+    #[inline]
+    fn local_transform(&self) -> &Transform2<f32> {
+        &self.local_transform
+    }
+}
+
+impl<T: Into<Shape>> From<Animated<T>> for Shape {
+    fn from(
+        Animated {
+            shape,
+            animations,
+            local_transform,
+        }: Animated<T>,
+    ) -> Self {
+        let key = format!("{}:{ANIMATE_HINT}", crate::BACKEND);
+        Shape::Group(Group {
+            local_transform,
+            shapes: vec![shape.into()],
+            metadata: animations
+                .iter()
+                .map(|animation| (key.clone(), animation.to_string()))
+                .collect(),
+        })
+    }
+}
+
+/// [`Animated`] shape, with its keyframes declared next to it.
+///
+/// Each track names the animated property, as in [`Keyframes`] (`opacity`, `fill`, `stroke`, `stroke_width`, `translate`, `rotate` or `scale`),
+/// then gives its value at each keyframe, the time of each keyframe after `at` if they are not evenly spread,
+/// the duration of a loop in seconds after `in`, and the number of loops after `repeat` if it doesn't loop forever.
+///
+/// ```
+/// # use dessin::prelude::*;
+/// use dessin_svg::{
+///     animation::{Animated, Animation, Keyframes},
+///     dessin_animate,
+/// };
+///
+/// let light = dessin_animate!(dessin2!(Circle!(fill = Color::GREEN, radius = 2.)) => {
+///     fill: [Color::GREEN, Color::RED, Color::GREEN] in 1.;
+///     translate: [[0., 0.], [0., 5.], [0., 0.]] at [0., 0.2, 1.] in 2., repeat 3;
+/// });
+///
+/// let built = Animated::new(dessin2!(Circle!(fill = Color::GREEN, radius = 2.)))
+///     .with_animation(Animation::new(
+///         Keyframes::Fill(vec![Color::GREEN, Color::RED, Color::GREEN]),
+///         1.,
+///     ))
+///     .with_animation(
+///         Animation::new(
+///             Keyframes::Translate(vec![[0., 0.].into(), [0., 5.].into(), [0., 0.].into()]),
+///             2.,
+///         )
+///         .with_key_times(vec![0., 0.2, 1.])
+///         .with_repeat(3),
+///     );
+///
+/// assert_eq!(
+///     dessin_svg::to_string(&light.into()).unwrap(),
+///     dessin_svg::to_string(&built.into()).unwrap(),
+/// );
+/// ```
+#[macro_export]
+macro_rules! dessin_animate {
+    (@keyframes opacity $($value:expr),*) => {
+        $crate::animation::Keyframes::Opacity(vec![$($value),*])
+    };
+    (@keyframes fill $($value:expr),*) => {
+        $crate::animation::Keyframes::Fill(vec![$($value),*])
+    };
+    (@keyframes stroke $($value:expr),*) => {
+        $crate::animation::Keyframes::Stroke(vec![$($value),*])
+    };
+    (@keyframes stroke_width $($value:expr),*) => {
+        $crate::animation::Keyframes::StrokeWidth(vec![$($value),*])
+    };
+    (@keyframes translate $($value:expr),*) => {
+        $crate::animation::Keyframes::Translate(vec![$(::core::convert::Into::into($value)),*])
+    };
+    (@keyframes rotate $($value:expr),*) => {
+        $crate::animation::Keyframes::Rotate(vec![$($value),*])
+    };
+    (@keyframes scale $($value:expr),*) => {
+        $crate::animation::Keyframes::Scale(vec![$($value),*])
+    };
+    (@keyframes $property:ident $($value:expr),*) => {
+        compile_error!(concat!("`", stringify!($property), "` can't be animated"))
+    };
+    ($shape:expr => {
+        $(
+            $property:ident: [$($value:expr),* $(,)?]
+            $(at [$($key_time:expr),* $(,)?])?
+            in $duration:expr
+            $(, repeat $repeat:expr)?;
+        )*
+    }) => {{
+        let animated = $crate::animation::Animated::new($shape);
+        $(
+            let animation = $crate::animation::Animation::new(
+                $crate::dessin_animate!(@keyframes $property $($value),*),
+                $duration,
+            );
+            $(let animation = animation.with_key_times(vec![$($key_time),*]);)?
+            $(let animation = animation.with_repeat($repeat);)?
+            let animated = animated.with_animation(animation);
+        )*
+        animated
+    }};
+}
@@ -0,0 +1,147 @@
+//! Several [`Artboard`]s side by side in a single SVG, each in its own nested `<svg>` element.
+
+use crate::{
+    decorated, format, Decimal, InstanceCounter, SVGError, SVGExporter, SVGOptions, ViewPort,
+};
+use dessin::{export::Export, prelude::*};
+use nalgebra::{Scale2, Transform2};
+use std::fmt::Write;
+
+/// Layout of the artboards of a contact sheet, in rows from the top left corner
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ContactSheet {
+    /// Artboards in each row, all of them in a single row if `None`
+    pub columns: Option<usize>,
+    /// Space between the artboards, and around them
+    pub gap: f32,
+}
+impl Default for ContactSheet {
+    fn default() -> Self {
+        ContactSheet {
+            columns: None,
+            gap: 10.,
+        }
+    }
+}
+impl ContactSheet {
+    /// Top left corner of each artboard in the sheet, and the size of the sheet
+    fn layout(&self, artboards: &[Artboard]) -> (Vec<(f32, f32)>, f32, f32) {
+        let columns = self.columns.unwrap_or(artboards.len()).max(1);
+
+        let mut positions = Vec::with_capacity(artboards.len());
+        let mut width = self.gap;
+        let mut top = self.gap;
+        for row in artboards.chunks(columns) {
+            let mut left = self.gap;
+            for artboard in row {
+                positions.push((left, top));
+                left += artboard.width + self.gap;
+            }
+            width = width.max(left);
+            top += row
+                .iter()
+                .map(|artboard| artboard.height)
+                .fold(0., f32::max)
+                + self.gap;
+        }
+
+        (positions, width, top)
+    }
+}
+
+/// SVG of `artboards` laid out as a contact sheet, each in a nested `<svg>` with its own `viewBox`, clipping its content.
+///
+/// The viewport of `options` is replaced by the size of each artboard,
+/// its background, border and stamps are drawn on each artboard.
+/// Definitions, such as gradients and embedded fonts, are shared by all the artboards.
+///
+/// ```
+/// # use dessin::prelude::*;
+/// use dessin_svg::{ContactSheet, SVGOptions};
+///
+/// let cover = dessin2!(Artboard(
+///     shape = dessin2!(Circle!(fill = Color::RED, radius = 20.) > ()),
+///     width = 100.,
+///     height = 50.,
+/// ));
+/// let back = dessin2!(Artboard(
+///     shape = dessin2!(Rectangle!(fill = Color::BLUE, width = 20., height = 20.) > ()),
+///     width = 50.,
+///     height = 50.,
+/// ));
+///
+/// let svg = dessin_svg::to_contact_sheet_string(
+///     &[cover, back],
+///     SVGOptions::default(),
+///     ContactSheet::default(),
+/// )
+/// .unwrap();
+///
+/// assert!(svg.starts_with(r#"<svg viewBox="0 0 180 70""#));
+/// assert!(svg.contains(r#"<svg x="10" y="10" width="100" height="50" viewBox="-50 -25 100 50">"#));
+/// assert!(svg.contains(r#"<svg x="120" y="10" width="50" height="50" viewBox="-25 -25 50 50">"#));
+/// ```
+pub fn to_contact_sheet_string(
+    artboards: &[Artboard],
+    options: SVGOptions,
+    sheet: ContactSheet,
+) -> Result<String, SVGError> {
+    let islands = artboards
+        .iter()
+        .map(|artboard| {
+            let options = SVGOptions {
+                viewport: ViewPort::ManualCentered {
+                    width: artboard.width,
+                    height: artboard.height,
+                },
+                ..options.clone()
+            };
+            let shape = Shape::from(artboard.clone());
+            let (shape, view_box) = decorated(&shape, &options);
+            (shape.into_owned(), view_box)
+        })
+        .collect::<Vec<_>>();
+
+    let (positions, width, height) = sheet.layout(artboards);
+    let mut exporter = SVGExporter::new(0., 0., width, height, &options);
+
+    let parent_transform: Transform2<f32> = nalgebra::convert(Scale2::new(1., -1.));
+    if options.reuse_subtrees {
+        let mut counter = InstanceCounter::default();
+        for (shape, _) in &islands {
+            shape.write_into_exporter(&mut counter, &parent_transform)?;
+        }
+        exporter.instance_counts = Some(counter.counts);
+    }
+
+    let d = |value| Decimal {
+        value,
+        precision: options.precision,
+    };
+    for ((shape, (min_x, min_y, span_x, span_y)), (x, y)) in islands.iter().zip(positions) {
+        write!(
+            exporter.acc,
+            r#"<svg x="{}" y="{}" width="{}" height="{}" viewBox="{} {} {} {}">"#,
+            d(x),
+            d(y),
+            d(*span_x),
+            d(*span_y),
+            d(*min_x),
+            d(*min_y),
+            d(*span_x),
+            d(*span_y),
+        )?;
+        shape.write_into_exporter(&mut exporter, &parent_transform)?;
+        write!(exporter.acc, "</svg>")?;
+    }
+
+    let mut svg = exporter.finish();
+    if options.minify {
+        svg = format::minify(&svg);
+    }
+    if let Some(indent) = options.indent {
+        svg = format::indent(&svg, indent);
+    }
+
+    Ok(svg)
+}
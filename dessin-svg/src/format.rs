@@ -0,0 +1,177 @@
+//! Layout of the written SVG: rounding of numbers, minification and indentation.
+
+use std::fmt;
+
+/// Number written with at most `precision` decimal places, without trailing zeros
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Decimal {
+    pub value: f32,
+    pub precision: Option<usize>,
+}
+impl fmt::Display for Decimal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Some(precision) = self.precision else {
+            return write!(f, "{}", self.value);
+        };
+
+        let rounded = format!("{:.precision$}", self.value);
+        let rounded = if rounded.contains('.') {
+            rounded.trim_end_matches('0').trim_end_matches('.')
+        } else {
+            &rounded
+        };
+        // Small negative numbers round to `-0`
+        if rounded == "-0" {
+            write!(f, "0")
+        } else {
+            write!(f, "{rounded}")
+        }
+    }
+}
+
+/// Index of the `>` closing the tag `svg` starts with, ignoring the ones in attribute values
+fn tag_end(svg: &str) -> usize {
+    let mut quote = None;
+    for (idx, c) in svg.char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(q), _) if q == c => quote = None,
+            (None, '>') => return idx,
+            _ => {}
+        }
+    }
+    svg.len() - 1
+}
+
+/// Name of the element of `tag`
+fn tag_name(tag: &str) -> &str {
+    let name = tag.trim_start_matches(['<', '/']);
+    let end = name
+        .find(|c: char| c.is_whitespace() || c == '>' || c == '/')
+        .unwrap_or(name.len());
+    &name[..end]
+}
+
+/// `svg` without unneeded whitespaces in its tags and path data
+pub(crate) fn minify(svg: &str) -> String {
+    let mut minified = String::with_capacity(svg.len());
+    let mut rest = svg;
+    while let Some(start) = rest.find('<') {
+        minified.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let end = tag_end(rest) + 1;
+        minify_tag(&rest[..end], &mut minified);
+        rest = &rest[end..];
+    }
+    minified.push_str(rest);
+    minified
+}
+
+fn minify_tag(tag: &str, minified: &mut String) {
+    let mut rest = tag;
+    let mut space = false;
+    while let Some(c) = rest.chars().next() {
+        if c.is_whitespace() {
+            space = true;
+            rest = &rest[c.len_utf8()..];
+            continue;
+        }
+        if space && c != '>' && c != '/' {
+            minified.push(' ');
+        }
+        space = false;
+
+        if c != '"' && c != '\'' {
+            minified.push(c);
+            rest = &rest[c.len_utf8()..];
+            continue;
+        }
+
+        let is_path = minified
+            .strip_suffix('=')
+            .and_then(|before| before.rsplit(char::is_whitespace).next())
+            == Some("d");
+        let end = rest[1..].find(c).map_or(rest.len(), |end| end + 1);
+        let value = rest[1..end].split_whitespace();
+
+        let value = if is_path {
+            // Commands are single letters, which need no space around them
+            let mut compact = String::new();
+            for token in value {
+                let after_number = compact.ends_with(|c: char| !c.is_alphabetic());
+                if after_number && !token.starts_with(char::is_alphabetic) {
+                    compact.push(' ');
+                }
+                compact.push_str(token);
+            }
+            compact
+        } else {
+            value.collect::<Vec<_>>().join(" ")
+        };
+
+        minified.push(c);
+        minified.push_str(&value);
+        minified.push(c);
+        rest = rest.get(end + 1..).unwrap_or_default();
+    }
+}
+
+/// `svg` with one element per line, indented by `width` spaces for each level of nesting.
+///
+/// Texts are kept on a single line with their content, where whitespaces matter.
+pub(crate) fn indent(svg: &str, width: usize) -> String {
+    let mut indented = String::with_capacity(svg.len() * 2);
+    let mut depth = 0_usize;
+    // Closing tags following text content stay on its line
+    let mut after_text = false;
+
+    let new_line = |indented: &mut String, depth: usize| {
+        if !indented.is_empty() {
+            indented.push('\n');
+        }
+        indented.push_str(&" ".repeat(depth * width));
+    };
+
+    let mut rest = svg;
+    while !rest.is_empty() {
+        if !rest.starts_with('<') {
+            let end = rest.find('<').unwrap_or(rest.len());
+            indented.push_str(&rest[..end]);
+            rest = &rest[end..];
+            after_text = true;
+            continue;
+        }
+
+        let end = tag_end(rest) + 1;
+        let tag = &rest[..end];
+        let name = tag_name(tag);
+
+        if tag.starts_with("</") {
+            depth = depth.saturating_sub(1);
+            if !after_text {
+                new_line(&mut indented, depth);
+            }
+            indented.push_str(tag);
+            rest = &rest[end..];
+        } else if name == "text" || name == "style" {
+            let closing = format!("</{name}>");
+            let end = rest
+                .find(&closing)
+                .map_or(rest.len(), |idx| idx + closing.len());
+            new_line(&mut indented, depth);
+            indented.push_str(&rest[..end]);
+            rest = &rest[end..];
+        } else {
+            new_line(&mut indented, depth);
+            indented.push_str(tag);
+            if !tag.ends_with("/>") {
+                depth += 1;
+            }
+            rest = &rest[end..];
+        }
+        after_text = false;
+    }
+
+    indented
+}
@@ -0,0 +1,222 @@
+//! Golden-file testing of dessins.
+//!
+//! A dessin is exported to SVG, normalized, and compared against a reference file stored alongside the tests.
+//! Numbers are compared with a tolerance, so small floating point differences between platforms don't fail the test.
+//!
+//! References are only written when the `DESSIN_UPDATE_GOLDEN` environment variable is set, see [`dessin::golden`].
+//! A missing reference fails the test.
+//!
+//! ```
+//! # use dessin::prelude::*;
+//! use dessin_svg::golden::{assert_golden, check_golden, GoldenError, UPDATE_GOLDEN_ENV};
+//!
+//! let reference = std::env::temp_dir().join("dessin-svg-golden-doc.svg");
+//! # let _ = std::fs::remove_file(&reference);
+//!
+//! let dessin = dessin2!(Circle!(fill = Color::RED, radius = 10.) > ());
+//! assert!(matches!(
+//!     check_golden(&dessin, &reference, 10e-4),
+//!     Err(GoldenError::MissingReference(_))
+//! ));
+//!
+//! std::env::set_var(UPDATE_GOLDEN_ENV, "1");
+//! assert_golden(&dessin, &reference, 10e-4); // Writes the reference
+//! std::env::remove_var(UPDATE_GOLDEN_ENV);
+//! assert_golden(&dessin, &reference, 10e-4); // Compares with it
+//! ```
+
+use crate::{to_string, SVGError};
+pub use dessin::golden::UPDATE_GOLDEN_ENV;
+use dessin::{
+    golden::{assert_reference, check_reference, MissingReference},
+    prelude::*,
+};
+use std::{collections::HashMap, fmt, io, path::Path};
+
+#[derive(Debug)]
+pub enum GoldenError {
+    SVGError(SVGError),
+    IOError(io::Error),
+    MissingReference(MissingReference),
+    /// First difference between the reference and the export
+    Mismatch {
+        /// Position in the normalized SVG
+        position: usize,
+        expected: String,
+        actual: String,
+    },
+}
+impl fmt::Display for GoldenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GoldenError::Mismatch {
+                position,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "SVG differs from reference at {position}: expected `{expected}`, got `{actual}`"
+            ),
+            GoldenError::MissingReference(missing) => write!(f, "{missing}"),
+            _ => write!(f, "{self:?}"),
+        }
+    }
+}
+impl From<MissingReference> for GoldenError {
+    fn from(value: MissingReference) -> Self {
+        GoldenError::MissingReference(value)
+    }
+}
+impl From<SVGError> for GoldenError {
+    fn from(value: SVGError) -> Self {
+        GoldenError::SVGError(value)
+    }
+}
+impl From<io::Error> for GoldenError {
+    fn from(value: io::Error) -> Self {
+        GoldenError::IOError(value)
+    }
+}
+impl std::error::Error for GoldenError {}
+
+/// Normalize an SVG, so two exports of the same dessin are identical.
+///
+/// Random ids, such as the ones of text paths, are replaced by sequential ids.
+pub fn normalize(svg: &str) -> String {
+    let mut ids: HashMap<String, String> = HashMap::new();
+    let mut acc = String::with_capacity(svg.len());
+    let mut rest = svg;
+
+    loop {
+        let idx = match (rest.find(r#"id=""#), rest.find(r##"href="#"##)) {
+            (Some(id), Some(href)) => id.min(href),
+            (Some(idx), None) | (None, Some(idx)) => idx,
+            (None, None) => break,
+        };
+
+        let (before, after) = rest.split_at(idx);
+        acc.push_str(before);
+
+        let prefix_len = if after.starts_with("id=") { 4 } else { 7 };
+        let (prefix, after) = after.split_at(prefix_len);
+        acc.push_str(prefix);
+
+        let end = after.find('"').unwrap_or(after.len());
+        let (id, after) = after.split_at(end);
+
+        let next = ids.len();
+        let normalized = ids
+            .entry(id.to_string())
+            .or_insert_with(|| format!("id{next}"));
+        acc.push_str(normalized);
+
+        rest = after;
+    }
+    acc.push_str(rest);
+
+    acc
+}
+
+#[derive(Debug, PartialEq)]
+enum Token<'a> {
+    Number(f32),
+    Text(&'a str),
+}
+
+fn tokenize(svg: &str) -> Vec<(usize, Token<'_>)> {
+    let is_number_char = |c: char| c.is_ascii_digit() || c == '.' || c == '-' || c == 'e';
+
+    let mut tokens = vec![];
+    let mut start = 0;
+    let mut in_number = false;
+
+    for (idx, c) in svg.char_indices().chain([(svg.len(), ' ')]) {
+        let starts_number = !in_number && (c.is_ascii_digit() || c == '-' || c == '.');
+        let ends_number = in_number && !is_number_char(c);
+
+        if starts_number || ends_number || idx == svg.len() {
+            if start < idx {
+                let s = &svg[start..idx];
+                let token = match s.parse::<f32>() {
+                    Ok(n) if in_number => Token::Number(n),
+                    _ => Token::Text(s),
+                };
+                tokens.push((start, token));
+            }
+            start = idx;
+            in_number = starts_number;
+        }
+    }
+
+    tokens
+}
+
+/// Compare two SVGs, numbers being equal within `tolerance`
+pub fn compare(expected: &str, actual: &str, tolerance: f32) -> Result<(), GoldenError> {
+    let expected = normalize(expected);
+    let actual = normalize(actual);
+
+    let expected_tokens = tokenize(&expected);
+    let actual_tokens = tokenize(&actual);
+
+    let mismatch = |position: usize, e: Option<&Token>, a: Option<&Token>| {
+        let show = |t: Option<&Token>| match t {
+            Some(Token::Number(n)) => n.to_string(),
+            Some(Token::Text(s)) => s.chars().take(40).collect(),
+            None => "<end of file>".to_string(),
+        };
+
+        GoldenError::Mismatch {
+            position,
+            expected: show(e),
+            actual: show(a),
+        }
+    };
+
+    for idx in 0..expected_tokens.len().max(actual_tokens.len()) {
+        let e = expected_tokens.get(idx);
+        let a = actual_tokens.get(idx);
+
+        let is_same = match (e, a) {
+            (Some((_, Token::Number(e))), Some((_, Token::Number(a)))) => {
+                (e - a).abs() <= tolerance
+            }
+            (Some((_, e)), Some((_, a))) => e == a,
+            _ => false,
+        };
+
+        if !is_same {
+            let position = a.or(e).map(|(p, _)| *p).unwrap_or(0);
+            return Err(mismatch(position, e.map(|(_, t)| t), a.map(|(_, t)| t)));
+        }
+    }
+
+    Ok(())
+}
+
+/// Export a dessin and compare it to a reference file.
+///
+/// The reference is written instead if [`UPDATE_GOLDEN_ENV`] is set, and is an error if it does not exist otherwise.
+pub fn check_golden<P: AsRef<Path>>(
+    shape: &Shape,
+    reference: P,
+    tolerance: f32,
+) -> Result<(), GoldenError> {
+    let actual = normalize(&to_string(shape)?);
+
+    check_reference(
+        reference.as_ref(),
+        |reference| Ok(std::fs::write(reference, &actual)?),
+        |reference| {
+            let expected = std::fs::read_to_string(reference)?;
+            compare(&expected, &actual, tolerance)
+        },
+    )
+}
+
+/// Same as [`check_golden`], but panics with a readable message
+#[track_caller]
+pub fn assert_golden<P: AsRef<Path>>(shape: &Shape, reference: P, tolerance: f32) {
+    let reference = reference.as_ref();
+    assert_reference("SVG", reference, check_golden(shape, reference, tolerance));
+}
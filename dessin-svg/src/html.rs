@@ -0,0 +1,163 @@
+//! Standalone HTML page showing the SVG of a dessin, with optional pan and zoom, and tooltips.
+
+use crate::{to_string_with_options, write_file, SVGError, SVGOptions};
+use dessin::prelude::*;
+
+/// Metadata key of the tooltip of a group, shown by [`to_html_string_with_options`] when the pointer is over the group.
+///
+/// Written on the `<g>` of the group, as its `data-tooltip` attribute.
+pub const TOOLTIP_METADATA_KEY: &str = "svg:data-tooltip";
+
+const STYLE: &str = "html,body{margin:0;height:100%;overflow:hidden}\
+body>svg{display:block;width:100%;height:100%}\
+#dessin-tooltip{position:fixed;pointer-events:none;padding:4px 8px;border-radius:4px;\
+background:rgba(0,0,0,0.8);color:#fff;font:13px sans-serif;white-space:pre-line}";
+
+/// Zoom around the pointer with the wheel, pan by dragging, reset with a double click
+const PAN_ZOOM_SCRIPT: &str = r#"(() => {
+const svg = document.querySelector("body>svg");
+const initial = svg.getAttribute("viewBox");
+const box = svg.viewBox.baseVal;
+const at = (event) => new DOMPoint(event.clientX, event.clientY).matrixTransform(svg.getScreenCTM().inverse());
+svg.addEventListener("wheel", (event) => {
+	event.preventDefault();
+	const p = at(event);
+	const factor = Math.exp(event.deltaY * 0.001);
+	box.x = p.x - (p.x - box.x) * factor;
+	box.y = p.y - (p.y - box.y) * factor;
+	box.width *= factor;
+	box.height *= factor;
+}, { passive: false });
+let grabbed = null;
+svg.addEventListener("pointerdown", (event) => {
+	grabbed = at(event);
+	svg.setPointerCapture(event.pointerId);
+});
+svg.addEventListener("pointermove", (event) => {
+	if (!grabbed) return;
+	const p = at(event);
+	box.x -= p.x - grabbed.x;
+	box.y -= p.y - grabbed.y;
+});
+svg.addEventListener("pointerup", () => grabbed = null);
+svg.addEventListener("pointercancel", () => grabbed = null);
+svg.addEventListener("dblclick", () => svg.setAttribute("viewBox", initial));
+})();"#;
+
+/// Show the tooltip of the innermost group under the pointer, next to it
+const TOOLTIP_SCRIPT: &str = r#"(() => {
+const tooltip = document.getElementById("dessin-tooltip");
+document.addEventListener("pointermove", (event) => {
+	const target = event.target.closest ? event.target.closest("[data-tooltip]") : null;
+	tooltip.hidden = !target;
+	if (!target) return;
+	tooltip.textContent = target.getAttribute("data-tooltip");
+	tooltip.style.left = `${event.clientX + 12}px`;
+	tooltip.style.top = `${event.clientY + 12}px`;
+});
+document.documentElement.addEventListener("pointerleave", () => tooltip.hidden = true);
+})();"#;
+
+#[derive(Clone)]
+pub struct HTMLOptions {
+    /// Title of the page
+    pub title: String,
+    /// Zoom with the mouse wheel, pan by dragging, and go back to the whole dessin with a double click
+    pub pan_zoom: bool,
+    /// Show the [tooltips][TOOLTIP_METADATA_KEY] of the groups under the pointer
+    pub tooltips: bool,
+    /// Options of the SVG embedded in the page
+    pub svg: SVGOptions,
+}
+impl Default for HTMLOptions {
+    fn default() -> Self {
+        HTMLOptions {
+            title: "dessin".to_string(),
+            pan_zoom: true,
+            tooltips: true,
+            svg: SVGOptions::default(),
+        }
+    }
+}
+
+/// Single HTML file showing the SVG of `shape`, filling the window, with its scripts inline so it can be shared as is.
+///
+/// ```
+/// # use dessin::prelude::*;
+/// use dessin_svg::{HTMLOptions, TOOLTIP_METADATA_KEY};
+///
+/// let mut tank = dessin2!(Rectangle!(fill = Color::BLUE, width = 40., height = 20.) > ());
+/// tank.add_metadata((TOOLTIP_METADATA_KEY, "Fuel tank: 1200 L"));
+///
+/// let html = dessin_svg::to_html_string_with_options(
+///     &tank,
+///     HTMLOptions {
+///         title: "Fuel system".to_string(),
+///         pan_zoom: false,
+///         ..Default::default()
+///     },
+/// )
+/// .unwrap();
+///
+/// assert!(html.starts_with("<!DOCTYPE html>"));
+/// assert!(html.contains("<title>Fuel system</title>"));
+/// assert!(html.contains(r#"data-tooltip="Fuel tank: 1200 L""#));
+/// assert!(html.contains(r#"id="dessin-tooltip""#));
+/// assert!(!html.contains("wheel"));
+/// ```
+pub fn to_html_string_with_options(
+    shape: &Shape,
+    options: HTMLOptions,
+) -> Result<String, SVGError> {
+    let svg = to_string_with_options(shape, options.svg)?;
+    let title = options
+        .title
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;");
+
+    let mut html = format!(
+        r#"<!DOCTYPE html><html><head><meta charset="utf-8"><meta name="viewport" content="width=device-width, initial-scale=1"><title>{title}</title><style>{STYLE}</style></head><body>"#
+    );
+    html.push_str(&svg);
+    if options.tooltips {
+        html.push_str(r#"<div id="dessin-tooltip" hidden></div>"#);
+        html.push_str(&format!("<script>{TOOLTIP_SCRIPT}</script>"));
+    }
+    if options.pan_zoom {
+        html.push_str(&format!("<script>{PAN_ZOOM_SCRIPT}</script>"));
+    }
+    html.push_str("</body></html>");
+
+    Ok(html)
+}
+
+pub fn to_html_string(shape: &Shape) -> Result<String, SVGError> {
+    to_html_string_with_options(shape, HTMLOptions::default())
+}
+
+/// Write the HTML page of `shape` to the file at `path`, like [`to_file`][crate::to_file] writes its SVG.
+///
+/// ```
+/// # use dessin::prelude::*;
+/// use dessin_svg::HTMLOptions;
+///
+/// let dessin = dessin2!(Circle!(fill = Color::RED, radius = 10.) > ());
+///
+/// let path = std::env::temp_dir().join("dessin_svg_to_html_file/circle.html");
+/// dessin_svg::to_html_file(&dessin, &path, HTMLOptions::default()).unwrap();
+///
+/// let html = std::fs::read_to_string(&path).unwrap();
+/// assert!(html.contains("<svg"));
+/// assert!(html.contains("wheel"));
+/// ```
+pub fn to_html_file<P: AsRef<std::path::Path>>(
+    shape: &Shape,
+    path: P,
+    options: HTMLOptions,
+) -> Result<(), SVGError> {
+    let atomic_write = options.svg.atomic_write;
+    let html = to_html_string_with_options(shape, options)?;
+
+    write_file(path.as_ref(), &html, atomic_write)
+}
@@ -1,11 +1,30 @@
+pub mod animation;
+mod contact_sheet;
+mod format;
+pub mod golden;
+mod html;
+mod reuse;
+
 use ::image::ImageFormat;
+use animation::Animation;
+pub use contact_sheet::*;
 use dessin::{
-    export::{Export, Exporter},
-    font::FontRef,
+    coordinates::CoordinateSystem,
+    debug::DebugOverlay,
+    export::{
+        hint, split_hint, with_backdrop, with_scale_statement, with_stamps, Background, Export,
+        Exporter, RestrictedFontPolicy, ScaleStatement, Stamp, SKIP_HINT,
+    },
+    font::{EmbeddingPermission, FontRef},
+    layers::LAYER_METADATA_KEY,
     prelude::*,
 };
-use nalgebra::{Scale2, Transform2};
-use std::collections::HashSet;
+use format::Decimal;
+pub use html::*;
+use nalgebra::{Scale2, Transform2, Translation2};
+use reuse::{Instance, InstanceCounter, MIN_SHARED_LENGTH};
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
 use std::{
     fmt::{self, Write},
     io::Cursor,
@@ -15,6 +34,14 @@ use std::{
 pub enum SVGError {
     WriteError(fmt::Error),
     CurveHasNoStartingPoint(CurvePosition),
+    InvalidAnimation(String),
+    /// The license of the font doesn't allow embedding it, see [`SVGOptions::restricted_fonts`]
+    RestrictedFont {
+        font: FontRef,
+        font_weight: FontWeight,
+    },
+    /// Writing the SVG to a writer or a file failed
+    Io(std::io::Error),
 }
 impl fmt::Display for SVGError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -26,8 +53,16 @@ impl From<fmt::Error> for SVGError {
         SVGError::WriteError(value)
     }
 }
+impl From<std::io::Error> for SVGError {
+    fn from(value: std::io::Error) -> Self {
+        SVGError::Io(value)
+    }
+}
 impl std::error::Error for SVGError {}
 
+/// Metadata key making the shapes of a [`Group`] a hyperlink to its value, in an `<a>` element
+pub const LINK_METADATA_KEY: &str = "href";
+
 #[derive(Default, Clone, Copy, PartialEq)]
 pub enum ViewPort {
     /// Create a viewport centered around (0, 0), with size (width, height)
@@ -44,26 +79,367 @@ pub enum ViewPort {
     #[default]
     /// Create a Viewport centered around the centered of the shapes, with auto size that include all [Shapes][`dessin::prelude::Shape`]
     AutoBoundingBox,
+    /// Create a Viewport around the bounding box of the shapes, with `padding` on each side
+    ///
+    /// ```
+    /// # use dessin::prelude::*;
+    /// use dessin_svg::{AspectRatio, SVGOptions, ViewPort};
+    ///
+    /// let badge = dessin2!(Circle!(fill = Color::RED, radius = 10.) > ());
+    ///
+    /// let svg = dessin_svg::to_string_with_options(
+    ///     &badge,
+    ///     SVGOptions {
+    ///         viewport: ViewPort::AutoPadded { padding: 2. },
+    ///         aspect_ratio: AspectRatio::Slice,
+    ///         ..Default::default()
+    ///     },
+    /// )
+    /// .unwrap();
+    ///
+    /// assert!(svg.starts_with(r#"<svg viewBox="-12 -12 24 24" preserveAspectRatio="xMidYMid slice""#));
+    /// ```
+    AutoPadded { padding: f32 },
+}
+
+/// How the viewport is fitted in the area the SVG is displayed in, when their aspect ratios differ.
+///
+/// The viewport is always centered in that area.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AspectRatio {
+    /// The whole viewport is visible, with empty margins on two sides
+    #[default]
+    Meet,
+    /// The viewport covers the whole area, and is cropped on two sides
+    Slice,
+    /// The viewport is stretched to the area, distorting the dessin
+    None,
+}
+impl AspectRatio {
+    /// Value of the `preserveAspectRatio` attribute
+    fn attribute(&self) -> &'static str {
+        match self {
+            AspectRatio::Meet => "xMidYMid meet",
+            AspectRatio::Slice => "xMidYMid slice",
+            AspectRatio::None => "none",
+        }
+    }
 }
 
-#[derive(Default, Clone)]
+#[derive(Clone)]
 pub struct SVGOptions {
     pub viewport: ViewPort,
+    /// How the viewport fits where the SVG is displayed
+    pub aspect_ratio: AspectRatio,
+    /// Draw layout information on top of the dessin
+    pub debug: Option<DebugOverlay>,
+    /// Drawn behind the dessin, the SVG is transparent otherwise
+    pub background: Option<Background>,
+    /// Frame around the viewport
+    pub border: Option<Stroke>,
+    /// Scale and unit of the dessin, stamped in the bottom right corner of the viewport
+    ///
+    /// ```
+    /// # use dessin::prelude::*;
+    /// use dessin::export::{LengthUnit, ScaleStatement};
+    /// use dessin_svg::{SVGOptions, ViewPort};
+    ///
+    /// let plan = dessin2!(Rectangle!(stroke = (Color::BLACK, 0.1), width = 100., height = 60.) > ());
+    ///
+    /// let svg = dessin_svg::to_string_with_options(
+    ///     &plan,
+    ///     SVGOptions {
+    ///         viewport: ViewPort::AutoPadded { padding: 10. },
+    ///         scale_statement: Some(ScaleStatement::new(50., LengthUnit::Meter)),
+    ///         ..Default::default()
+    ///     },
+    /// )
+    /// .unwrap();
+    ///
+    /// assert!(svg.contains(">1:50 (m)</text>"));
+    /// ```
+    pub scale_statement: Option<ScaleStatement>,
+    /// Stamped on the viewport, such as a DRAFT watermark.
+    ///
+    /// ```
+    /// # use dessin::prelude::*;
+    /// use dessin::export::Stamp;
+    /// use dessin_svg::{SVGOptions, ViewPort};
+    ///
+    /// let plan = dessin2!(Rectangle!(stroke = (Color::BLACK, 0.1), width = 100., height = 60.) > ());
+    ///
+    /// let svg = dessin_svg::to_string_with_options(
+    ///     &plan,
+    ///     SVGOptions {
+    ///         viewport: ViewPort::ManualCentered { width: 120., height: 80. },
+    ///         stamps: vec![Stamp::watermark("DRAFT").with_below(true)],
+    ///         ..Default::default()
+    ///     },
+    /// )
+    /// .unwrap();
+    ///
+    /// // Under the plan
+    /// assert!(svg.find(">DRAFT</text>").unwrap() < svg.find("<path").unwrap());
+    /// ```
+    pub stamps: Vec<Stamp>,
+    /// Orientation of the Y axis the dessin is drawn in
+    pub coordinates: CoordinateSystem,
+    /// How fills and strokes are written
+    pub style_mode: StyleMode,
+    /// Write identical subtrees once, in `<defs>`, and reference them with `<use>`.
+    ///
+    /// Subtrees with texts or images, and subtrees too small to be worth it, are always written inline.
+    ///
+    /// ```
+    /// # use dessin::prelude::*;
+    /// use dessin_svg::SVGOptions;
+    ///
+    /// let clock = dessin2!(for tick in 0..160 {
+    ///     dessin2!(Line!(
+    ///         stroke = (Color::BLACK, 0.2),
+    ///         from = [0., 45.],
+    ///         to = [0., 50.],
+    ///         rotate = Angle::degrees(tick as f32 * 360. / 160.),
+    ///     ))
+    /// });
+    ///
+    /// let svg = dessin_svg::to_string_with_options(
+    ///     &clock,
+    ///     SVGOptions {
+    ///         reuse_subtrees: true,
+    ///         ..Default::default()
+    ///     },
+    /// )
+    /// .unwrap();
+    ///
+    /// assert_eq!(svg.matches("<path").count(), 1);
+    /// assert_eq!(svg.matches("<use").count(), 160);
+    /// ```
+    pub reuse_subtrees: bool,
+    /// Decimal places of coordinates and lengths, all the digits of `f32` if `None`
+    ///
+    /// ```
+    /// # use dessin::prelude::*;
+    /// use dessin_svg::SVGOptions;
+    ///
+    /// let dessin = dessin2!(Line!(stroke = (Color::BLACK, 0.1), from = [0., 0.], to = [1. / 3., 2. / 3.]) > ());
+    ///
+    /// let svg = dessin_svg::to_string_with_options(
+    ///     &dessin,
+    ///     SVGOptions {
+    ///         precision: Some(2),
+    ///         minify: true,
+    ///         indent: Some(2),
+    ///         ..Default::default()
+    ///     },
+    /// )
+    /// .unwrap();
+    ///
+    /// assert!(svg.contains(r#"<path d="M0 0L0.33 -0.67"/>"#));
+    /// // One element per line
+    /// assert_eq!(svg.lines().nth(1), Some(r#"  <g fill='none' stroke='#000000' stroke-width='0.1'>"#));
+    /// ```
+    pub precision: Option<usize>,
+    /// Write no more whitespaces than needed in tags, and in the data of paths
+    pub minify: bool,
+    /// Write each element on its own line, indented by this number of spaces for each level of nesting.
+    ///
+    /// Texts are written on a single line, as whitespaces in them are rendered.
+    pub indent: Option<usize>,
+    /// Embed the fonts of the texts, as `@font-face` rules with their data, on by default.
+    ///
+    /// Without them, texts are drawn with the fonts of the same name installed where the SVG is opened,
+    /// or a fallback, but the SVG is much smaller.
+    ///
+    /// ```
+    /// # use dessin::prelude::*;
+    /// use dessin_svg::SVGOptions;
+    ///
+    /// let label = dessin2!(Text!(fill = Color::BLACK, text = "Label") > ());
+    ///
+    /// let embedded = dessin_svg::to_string(&label).unwrap();
+    /// assert!(embedded.contains("@font-face{font-family:"));
+    ///
+    /// let light = dessin_svg::to_string_with_options(&label, SVGOptions::default().embed_fonts(false)).unwrap();
+    /// assert!(!light.contains("@font-face"));
+    /// ```
+    pub embed_fonts: bool,
+    /// Whether embedding fonts whose license doesn't allow it fails the export.
+    ///
+    /// ```
+    /// # use dessin::prelude::*;
+    /// use dessin::{export::RestrictedFontPolicy, font::{self, Font, FontGroup}};
+    /// use dessin_svg::{SVGError, SVGOptions, ViewPort};
+    ///
+    /// // Font whose OS/2 table restricts embedding
+    /// let mut bytes = vec![0, 1, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0];
+    /// bytes.extend(b"OS/2\0\0\0\0");
+    /// bytes.extend(28_u32.to_be_bytes());
+    /// bytes.extend(10_u32.to_be_bytes());
+    /// bytes.extend([0, 4, 0, 0, 0, 0, 0, 0, 0, 2]);
+    /// let restricted = font::add_font("Restricted", FontGroup {
+    ///     regular: Font::TTF(bytes),
+    ///     bold: None,
+    ///     italic: None,
+    ///     bold_italic: None,
+    /// });
+    ///
+    /// let label = dessin2!(Text!(fill = Color::BLACK, text = "Label", font = restricted) > ());
+    /// let options = SVGOptions {
+    ///     restricted_fonts: RestrictedFontPolicy::Deny,
+    ///     // The font can't be measured
+    ///     viewport: ViewPort::ManualCentered { width: 100., height: 100. },
+    ///     ..Default::default()
+    /// };
+    ///
+    /// let svg = dessin_svg::to_string_with_options(&label, options);
+    /// assert!(matches!(svg, Err(SVGError::RestrictedFont { .. })));
+    /// ```
+    pub restricted_fonts: RestrictedFontPolicy,
+    /// Whether [`to_file`] writes a temporary file next to the target, then renames it over the target,
+    /// so other processes never read a partially written SVG
+    pub atomic_write: bool,
+}
+impl Default for SVGOptions {
+    fn default() -> Self {
+        SVGOptions {
+            viewport: ViewPort::default(),
+            aspect_ratio: AspectRatio::default(),
+            debug: None,
+            background: None,
+            border: None,
+            scale_statement: None,
+            stamps: vec![],
+            coordinates: CoordinateSystem::default(),
+            style_mode: StyleMode::default(),
+            reuse_subtrees: false,
+            precision: None,
+            minify: false,
+            indent: None,
+            embed_fonts: true,
+            restricted_fonts: RestrictedFontPolicy::default(),
+            atomic_write: false,
+        }
+    }
+}
+impl SVGOptions {
+    /// Draw `background` behind the dessin, over the whole viewport, however large the dessin gets
+    ///
+    /// ```
+    /// # use dessin::prelude::*;
+    /// use dessin_svg::SVGOptions;
+    ///
+    /// let dessin = dessin2!(Circle!(fill = Color::WHITE, radius = 10.) > ());
+    ///
+    /// let svg = dessin_svg::to_string_with_options(&dessin, SVGOptions::default().background(Color::BLACK)).unwrap();
+    /// assert!(svg.contains("fill='#000000'"));
+    /// ```
+    #[inline]
+    pub fn background<B: Into<Background>>(mut self, background: B) -> Self {
+        self.background = Some(background.into());
+        self
+    }
+
+    /// Whether to [embed the fonts][SVGOptions::embed_fonts] of the texts
+    #[inline]
+    pub fn embed_fonts(mut self, embed_fonts: bool) -> Self {
+        self.embed_fonts = embed_fonts;
+        self
+    }
+}
+
+/// How fills and strokes are written in the SVG
+///
+/// ```
+/// # use dessin::prelude::*;
+/// use dessin_svg::{SVGOptions, StyleMode};
+///
+/// let dessin = dessin2!(for x in 0..100 {
+///     dessin2!(Circle!(fill = Color::RED, stroke = (Color::BLACK, 0.1), radius = 1., translate = [x as f32 * 3., 0.]))
+/// });
+///
+/// let svg = dessin_svg::to_string_with_options(
+///     &dessin,
+///     SVGOptions {
+///         style_mode: StyleMode::Classes,
+///         ..Default::default()
+///     },
+/// )
+/// .unwrap();
+///
+/// // A single rule shared by every circle
+/// assert_eq!(svg.matches("{fill:").count(), 1);
+/// assert_eq!(svg.matches(r#"class="s0""#).count(), 100);
+/// ```
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StyleMode {
+    /// Presentation attributes on every styled group
+    #[default]
+    Inline,
+    /// Each distinct combination of fill and stroke is a CSS class, `s0`, `s1` and so on,
+    /// defined once in a `<style>` block.
+    ///
+    /// Styles in a `<style>` block apply to the whole document the SVG is embedded in,
+    /// so SVGs written this way should not be inlined together in the same HTML page.
+    Classes,
 }
 
 pub struct SVGExporter {
     start: String,
     acc: String,
     used_font: HashSet<(FontRef, FontWeight)>,
+    style_mode: StyleMode,
+    /// CSS declarations of each class, by index
+    classes: Vec<String>,
+    class_of: HashMap<String, usize>,
+    /// Instances of each subtree, when identical ones are reused
+    instance_counts: Option<HashMap<String, usize>>,
+    /// Id of the definition of each subtree, `None` if too small to be referenced
+    definitions: HashMap<String, Option<usize>>,
+    defs: String,
+    in_definition: bool,
+    /// Id of the `<filter>` of each filter
+    filters: HashMap<String, usize>,
+    /// Id of the `<linearGradient>` or `<radialGradient>` of each gradient fill
+    gradients: HashMap<String, usize>,
+    /// Id of the `<pattern>` of each pattern fill
+    patterns: HashMap<String, usize>,
+    /// Number of `<clipPath>` written
+    clip_paths: usize,
+    /// Number of `<mask>` written
+    masks: usize,
+    /// Writing the content of a `<clipPath>`, where only shapes are allowed
+    in_clip_path: bool,
+    /// Elements of the fill and stroke animations of the groups being written
+    style_animations: Vec<(Animation, String)>,
+    /// Decimal places of numbers, all of them if `None`
+    precision: Option<usize>,
+    embed_fonts: bool,
+    restricted_fonts: RestrictedFontPolicy,
+    /// Whether a group was written as an Inkscape layer, which needs its namespace
+    inkscape_layers: bool,
 }
 
 impl SVGExporter {
     // fn new(min_x: f32, min_y: f32, span_x: f32, span_y: f32) -> Self {
-    fn new(min_x: f32, min_y: f32, span_x: f32, span_y: f32) -> Self {
+    fn new(min_x: f32, min_y: f32, span_x: f32, span_y: f32, options: &SVGOptions) -> Self {
         const SCHEME: &str =
             r#"xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink""#;
 
-        let start = format!(r#"<svg viewBox="{min_x} {min_y} {span_x} {span_y}" {SCHEME}>"#,);
+        let precision = options.precision;
+        let d = |value| Decimal { value, precision };
+        // Meet is the default of SVG, it isn't written
+        let aspect_ratio = match options.aspect_ratio {
+            AspectRatio::Meet => String::new(),
+            aspect_ratio => format!(r#"preserveAspectRatio="{}" "#, aspect_ratio.attribute()),
+        };
+        let start = format!(
+            r#"<svg viewBox="{} {} {} {}" {aspect_ratio}{SCHEME}>"#,
+            d(min_x),
+            d(min_y),
+            d(span_x),
+            d(span_y)
+        );
         let acc = String::new();
         let stock: HashSet<(FontRef, FontWeight)> = HashSet::default();
 
@@ -71,30 +447,403 @@ impl SVGExporter {
             start,
             acc,
             used_font: stock,
+            style_mode: options.style_mode,
+            classes: vec![],
+            class_of: HashMap::new(),
+            instance_counts: None,
+            definitions: HashMap::new(),
+            defs: String::new(),
+            in_definition: false,
+            filters: HashMap::new(),
+            gradients: HashMap::new(),
+            patterns: HashMap::new(),
+            clip_paths: 0,
+            masks: 0,
+            in_clip_path: false,
+            style_animations: vec![],
+            precision,
+            embed_fonts: options.embed_fonts,
+            restricted_fonts: options.restricted_fonts,
+            inkscape_layers: false,
         }
     }
 
-    fn write_style(&mut self, style: StylePosition) -> Result<(), SVGError> {
-        match style.fill {
-            Some(Fill::Color(color)) => write!(self.acc, "fill='{color}' ")?,
-            None => write!(self.acc, "fill='none' ")?,
+    /// Write `shape` in `<defs>`, returning its id, unless it is too small to be worth it
+    fn write_definition(
+        &mut self,
+        shape: &Shape,
+        instance: &Instance,
+    ) -> Result<Option<usize>, SVGError> {
+        let acc = std::mem::take(&mut self.acc);
+        self.in_definition = true;
+        let written = shape.write_into_exporter(self, &instance.definition_transform);
+        self.in_definition = false;
+        let definition = std::mem::replace(&mut self.acc, acc);
+        written?;
+
+        if definition.len() < MIN_SHARED_LENGTH {
+            return Ok(None);
         }
 
-        match style.stroke {
-            Some(Stroke::Dashed {
-                color,
-                width,
-                on,
-                off,
-            }) => write!(
+        let id = self.definitions.values().flatten().count();
+        write!(self.defs, r#"<g id="u{id}">{definition}</g>"#)?;
+        Ok(Some(id))
+    }
+
+    /// Attributes of a group, from its metadata
+    /// Open an `<a>` element if the group links somewhere
+    fn start_link(&mut self, metadata: &[(String, String)]) -> Result<(), SVGError> {
+        if let Some(url) = link(metadata) {
+            let url = url.replace('&', "&amp;").replace('"', "&quot;");
+            write!(self.acc, r#"<a href="{url}">"#)?;
+        }
+        Ok(())
+    }
+
+    fn end_link(&mut self, metadata: &[(String, String)]) -> Result<(), SVGError> {
+        if link(metadata).is_some() {
+            write!(self.acc, "</a>")?;
+        }
+        Ok(())
+    }
+
+    fn write_block_attributes(&mut self, metadata: &[(String, String)]) -> Result<(), SVGError> {
+        if let Some(name) = layer(metadata) {
+            let name = name.replace('&', "&amp;").replace('"', "&quot;");
+            write!(
                 self.acc,
-                "stroke='{color}' stroke-width='{width}' stroke-dasharray='{on},{off}' "
+                r#"inkscape:groupmode="layer" inkscape:label="{name}" "#
+            )?;
+            self.inkscape_layers = true;
+        }
+        for (key, value) in block_attributes(metadata) {
+            match split_hint(key) {
+                Some((_, attribute)) => {
+                    let value = value.replace('&', "&amp;").replace('"', "&quot;");
+                    write!(self.acc, r#"{attribute}="{value}" "#)?;
+                }
+                None => write!(self.acc, r#"{key}={value} "#)?,
+            }
+        }
+        Ok(())
+    }
+
+    /// Write a group with its animations, which need its transform
+    fn write_animated(
+        &mut self,
+        Group {
+            local_transform,
+            shapes,
+            metadata,
+        }: &Group,
+        parent_transform: &Transform2<f32>,
+    ) -> Result<(), SVGError> {
+        let transform = parent_transform * local_transform;
+        let animations = animation::animations_of(metadata)?;
+
+        self.start_link(metadata)?;
+        write!(self.acc, "<g ")?;
+        self.write_block_attributes(metadata)?;
+        write!(self.acc, ">")?;
+
+        // Fills and strokes are set by the styles under the group, so they are animated there
+        let inherited = self.style_animations.len();
+        for animation in animations {
+            let elements = animation.to_elements(&transform)?;
+            if animation.is_style() {
+                self.style_animations.push((animation, elements));
+            } else {
+                self.acc.push_str(&elements);
+            }
+        }
+
+        let written = shapes
+            .iter()
+            .try_for_each(|shape| shape.write_into_exporter(self, &transform));
+        self.style_animations.truncate(inherited);
+        written?;
+
+        write!(self.acc, "</g>")?;
+        self.end_link(metadata)?;
+        Ok(())
+    }
+
+    /// Write the shape of a [`Clip`] in a group clipped by a `<clipPath>`, written in `<defs>`
+    fn write_clipped(
+        &mut self,
+        group: &Group,
+        shape: &Shape,
+        clipped_by: &Shape,
+        parent_transform: &Transform2<f32>,
+    ) -> Result<(), SVGError> {
+        let transform = parent_transform * group.local_transform;
+
+        let acc = std::mem::take(&mut self.acc);
+        self.in_clip_path = true;
+        let written = clipped_by.write_into_exporter(self, &transform);
+        self.in_clip_path = false;
+        let clip_path = std::mem::replace(&mut self.acc, acc);
+        written?;
+
+        let id = self.clip_paths;
+        self.clip_paths += 1;
+        write!(self.defs, r#"<clipPath id="c{id}">{clip_path}</clipPath>"#)?;
+
+        self.start_link(&group.metadata)?;
+        write!(self.acc, r##"<g clip-path="url(#c{id})" "##)?;
+        self.write_block_attributes(&group.metadata)?;
+        write!(self.acc, ">")?;
+        shape.write_into_exporter(self, &transform)?;
+        write!(self.acc, "</g>")?;
+        self.end_link(&group.metadata)?;
+
+        Ok(())
+    }
+
+    /// Write the shape of a [`Mask`] in a group masked by a `<mask>`, written in `<defs>`
+    fn write_masked(
+        &mut self,
+        group: &Group,
+        shape: &Shape,
+        mask: &Shape,
+        parent_transform: &Transform2<f32>,
+    ) -> Result<(), SVGError> {
+        let transform = parent_transform * group.local_transform;
+
+        let acc = std::mem::take(&mut self.acc);
+        let written = mask.write_into_exporter(self, &transform);
+        let content = std::mem::replace(&mut self.acc, acc);
+        written?;
+
+        let id = self.masks;
+        self.masks += 1;
+        let mode = match Mask::mode_of(group) {
+            MaskMode::Luminance => "",
+            MaskMode::Alpha => r#" mask-type="alpha""#,
+        };
+        write!(self.defs, r#"<mask id="m{id}"{mode}>{content}</mask>"#)?;
+
+        self.start_link(&group.metadata)?;
+        write!(self.acc, r##"<g mask="url(#m{id})" "##)?;
+        self.write_block_attributes(&group.metadata)?;
+        write!(self.acc, ">")?;
+        shape.write_into_exporter(self, &transform)?;
+        write!(self.acc, "</g>")?;
+        self.end_link(&group.metadata)?;
+
+        Ok(())
+    }
+
+    /// Id of the `<filter>` of `filter`, written in `<defs>` the first time
+    fn filter_id(&mut self, filter: Filter) -> Result<usize, SVGError> {
+        let key = format!("{filter:?}");
+        if let Some(id) = self.filters.get(&key) {
+            return Ok(*id);
+        }
+
+        let id = self.filters.len();
+        // Blurs and shadows spread outside of the bounding box of the shape
+        write!(
+            self.defs,
+            r#"<filter id="f{id}" x="-50%" y="-50%" width="200%" height="200%">"#
+        )?;
+        match filter {
+            Filter::GaussianBlur { std_deviation } => write!(
+                self.defs,
+                r#"<feGaussianBlur in="SourceGraphic" stdDeviation="{std_deviation}"/>"#
+            )?,
+            Filter::DropShadow {
+                dx,
+                dy,
+                std_deviation,
+                color,
+            } => {
+                let (r, g, b, a) = color.rgba();
+                write!(
+                    self.defs,
+                    concat!(
+                        r#"<feGaussianBlur in="SourceAlpha" stdDeviation="{std_deviation}"/>"#,
+                        r#"<feOffset dx="{dx}" dy="{dy}" result="offset"/>"#,
+                        r##"<feFlood flood-color="#{r:02X?}{g:02X?}{b:02X?}" flood-opacity="{opacity}"/>"##,
+                        r#"<feComposite in2="offset" operator="in"/>"#,
+                        r#"<feMerge><feMergeNode/><feMergeNode in="SourceGraphic"/></feMerge>"#,
+                    ),
+                    std_deviation = std_deviation,
+                    dx = dx,
+                    dy = dy,
+                    r = r,
+                    g = g,
+                    b = b,
+                    opacity = a as f32 / 255.,
+                )?
+            }
+            Filter::Grayscale => write!(
+                self.defs,
+                r#"<feColorMatrix in="SourceGraphic" type="saturate" values="0"/>"#
             )?,
-            Some(Stroke::Full { color, width }) => {
-                write!(self.acc, "stroke='{color}' stroke-width='{width}' ")?
+        }
+        write!(self.defs, "</filter>")?;
+
+        self.filters.insert(key, id);
+        Ok(id)
+    }
+
+    /// Id of the gradient of `fill`, written in `<defs>` the first time, `None` for other fills
+    fn gradient_id(&mut self, fill: &Fill) -> Result<Option<usize>, SVGError> {
+        if let Fill::Color(_) | Fill::Pattern { .. } | Fill::Image { .. } = fill {
+            return Ok(None);
+        }
+
+        let key = format!("{fill:?}");
+        if let Some(id) = self.gradients.get(&key) {
+            return Ok(Some(*id));
+        }
+
+        let id = self.gradients.len();
+        let precision = self.precision;
+        let d = |value| Decimal { value, precision };
+        // Positions are in the coordinates of the SVG, not relative to the bounding box of the shape
+        let stops = match fill {
+            Fill::Color(_) | Fill::Pattern { .. } | Fill::Image { .. } => unreachable!(),
+            Fill::LinearGradient { start, end, stops } => {
+                write!(
+                    self.defs,
+                    r#"<linearGradient id="g{id}" gradientUnits="userSpaceOnUse" x1="{}" y1="{}" x2="{}" y2="{}">"#,
+                    d(start.x),
+                    d(start.y),
+                    d(end.x),
+                    d(end.y),
+                )?;
+                stops
+            }
+            Fill::RadialGradient {
+                center,
+                radius,
+                stops,
+            } => {
+                write!(
+                    self.defs,
+                    r#"<radialGradient id="g{id}" gradientUnits="userSpaceOnUse" cx="{}" cy="{}" r="{}">"#,
+                    d(center.x),
+                    d(center.y),
+                    d(*radius),
+                )?;
+                stops
+            }
+        };
+        for (offset, color) in stops.as_slice() {
+            write!(
+                self.defs,
+                r#"<stop offset="{}" stop-color="{color}"/>"#,
+                d(*offset)
+            )?;
+        }
+        match fill {
+            Fill::LinearGradient { .. } => write!(self.defs, "</linearGradient>")?,
+            _ => write!(self.defs, "</radialGradient>")?,
+        }
+
+        self.gradients.insert(key, id);
+        Ok(Some(id))
+    }
+
+    /// Id of the `<pattern>` of `fill`, written in `<defs>` the first time, `None` for other fills
+    fn pattern_id(&mut self, fill: &Fill) -> Result<Option<usize>, SVGError> {
+        let Fill::Pattern {
+            pattern,
+            spacing,
+            transform,
+        } = fill
+        else {
+            return Ok(None);
+        };
+
+        let key = format!("{fill:?}");
+        if let Some(id) = self.patterns.get(&key) {
+            return Ok(Some(*id));
+        }
+
+        // The tile is centered on the first cell of the pattern, which is centered on the origin of the grid
+        let center = Translation2::new(spacing.x / 2., spacing.y / 2.);
+        let acc = std::mem::take(&mut self.acc);
+        let written = pattern
+            .tile()
+            .write_into_exporter(self, &nalgebra::convert(center));
+        let content = std::mem::replace(&mut self.acc, acc);
+        written?;
+
+        let id = self.patterns.len();
+        let placement = transform * center.inverse();
+        let m = placement.matrix();
+        let precision = self.precision;
+        let n = |value| Decimal { value, precision };
+        write!(
+            self.defs,
+            r#"<pattern id="p{id}" patternUnits="userSpaceOnUse" width="{w}" height="{h}" patternTransform="matrix({a} {b} {c} {d} {e} {f})">{content}</pattern>"#,
+            w = n(spacing.x),
+            h = n(spacing.y),
+            a = n(m[(0, 0)]),
+            b = n(m[(1, 0)]),
+            c = n(m[(0, 1)]),
+            d = n(m[(1, 1)]),
+            e = n(m[(0, 2)]),
+            f = n(m[(1, 2)]),
+        )?;
+
+        self.patterns.insert(key, id);
+        Ok(Some(id))
+    }
+
+    fn write_style(&mut self, style: StylePosition) -> Result<(), SVGError> {
+        let filter = style.filter;
+        let paint = match &style.fill {
+            Some(fill) => match self.pattern_id(fill)? {
+                Some(id) => Some(format!("url(#p{id})")),
+                None => self.gradient_id(fill)?.map(|id| format!("url(#g{id})")),
+            },
+            None => None,
+        };
+        let mut properties = style_properties(style, self.precision);
+        if let Some(paint) = paint {
+            for (property, value) in &mut properties {
+                if *property == "fill" {
+                    *value = paint.clone();
+                }
             }
+        }
+        if let Some(filter) = filter {
+            let id = self.filter_id(filter)?;
+            properties.push(("filter", format!("url(#f{id})")));
+        }
 
-            None => {}
+        match self.style_mode {
+            StyleMode::Inline => {
+                for (property, value) in properties {
+                    // Not a presentation attribute, only read from CSS
+                    if property == BLEND_MODE_PROPERTY {
+                        write!(self.acc, "style='{property}:{value}' ")?;
+                    } else {
+                        write!(self.acc, "{property}='{value}' ")?;
+                    }
+                }
+            }
+            StyleMode::Classes => {
+                let declarations = properties
+                    .iter()
+                    .map(|(property, value)| format!("{property}:{value};"))
+                    .collect::<String>();
+
+                let class = match self.class_of.get(&declarations) {
+                    Some(class) => *class,
+                    None => {
+                        let class = self.classes.len();
+                        self.classes.push(declarations.clone());
+                        self.class_of.insert(declarations, class);
+                        class
+                    }
+                };
+                write!(self.acc, r#"class="s{class}" "#)?;
+            }
         }
 
         Ok(())
@@ -102,6 +851,8 @@ impl SVGExporter {
 
     #[allow(unused)]
     fn write_curve(&mut self, curve: CurvePosition) -> Result<(), SVGError> {
+        let precision = self.precision;
+        let d = |value| Decimal { value, precision };
         let mut has_start = false;
 
         for keypoint in &curve.keypoints {
@@ -113,16 +864,16 @@ impl SVGExporter {
                         write!(self.acc, "M ")?;
                         has_start = true;
                     }
-                    write!(self.acc, "{} {} ", p.x, p.y)?;
+                    write!(self.acc, "{} {} ", d(p.x), d(p.y))?;
                 }
                 KeypointPosition::Bezier(b) => {
                     if has_start {
                         if let Some(v) = b.start {
-                            write!(self.acc, "L {} {} ", v.x, v.y)?;
+                            write!(self.acc, "L {} {} ", d(v.x), d(v.y))?;
                         }
                     } else {
                         if let Some(v) = b.start {
-                            write!(self.acc, "M {} {} ", v.x, v.y)?;
+                            write!(self.acc, "M {} {} ", d(v.x), d(v.y))?;
                             has_start = true;
                         } else {
                             return Err(SVGError::CurveHasNoStartingPoint(curve));
@@ -132,12 +883,12 @@ impl SVGExporter {
                     write!(
                             self.acc,
                             "C {start_ctrl_x} {start_ctrl_y} {end_ctrl_x} {end_ctrl_y} {end_x} {end_y} ",
-                            start_ctrl_x = b.start_control.x,
-                            start_ctrl_y = b.start_control.y,
-                            end_ctrl_x = b.end_control.x,
-                            end_ctrl_y = b.end_control.y,
-                            end_x = b.end.x,
-                            end_y = b.end.y,
+                            start_ctrl_x = d(b.start_control.x),
+                            start_ctrl_y = d(b.start_control.y),
+                            end_ctrl_x = d(b.end_control.x),
+                            end_ctrl_y = d(b.end_control.y),
+                            end_x = d(b.end.x),
+                            end_y = d(b.end.y),
                         )?;
                 }
             }
@@ -153,8 +904,12 @@ impl SVGExporter {
     }
 
     fn finish(self) -> String {
-        let return_fonts = self
-            .used_font
+        let used_font = if self.embed_fonts {
+            self.used_font
+        } else {
+            HashSet::new()
+        };
+        let return_fonts = used_font
             .into_iter()
             .map(move |(font_ref, font_weight)| {
                 let font_name = font_ref.name(font_weight);
@@ -170,50 +925,452 @@ impl SVGExporter {
             })
             .collect::<String>();
 
-        if return_fonts.is_empty() {
-            format!("{}{}</svg>", self.start, self.acc)
+        let classes = self
+            .classes
+            .iter()
+            .enumerate()
+            .map(|(class, declarations)| format!(".s{class}{{{declarations}}}"))
+            .collect::<String>();
+
+        let start = if self.inkscape_layers {
+            self.start
+                .replacen("<svg ", &format!("<svg {INKSCAPE_NAMESPACE} "), 1)
         } else {
-            format!(
-                "{}<defs><style>{return_fonts}</style></defs>{}</svg>",
-                self.start, self.acc
-            )
+            self.start
+        };
+
+        let style = if return_fonts.is_empty() && classes.is_empty() {
+            String::new()
+        } else {
+            format!("<style>{return_fonts}{classes}</style>")
+        };
+
+        if style.is_empty() && self.defs.is_empty() {
+            format!("{start}{}</svg>", self.acc)
+        } else {
+            format!("{start}<defs>{style}{}</defs>{}</svg>", self.defs, self.acc)
         }
     }
 }
 
+/// CSS property of the blend mode of a style
+const BLEND_MODE_PROPERTY: &str = "mix-blend-mode";
+
+/// SVG properties of a style, and their values
+fn style_properties(style: StylePosition, precision: Option<usize>) -> Vec<(&'static str, String)> {
+    let d = |value| Decimal { value, precision };
+    let mut properties = vec![];
+
+    match style.fill {
+        // Gradients are referenced by the exporter
+        Some(fill) => properties.push(("fill", fill.color().to_string())),
+        None => properties.push(("fill", "none".to_string())),
+    }
+
+    if let Some(stroke) = style.stroke {
+        properties.push(("stroke", stroke.color().to_string()));
+        properties.push(("stroke-width", d(stroke.width()).to_string()));
+
+        if let Some((dashes, offset)) = stroke.dashes() {
+            let dashes = dashes
+                .as_slice()
+                .iter()
+                .map(|length| d(*length).to_string())
+                .collect::<Vec<_>>()
+                .join(",");
+            properties.push(("stroke-dasharray", dashes));
+            if offset != 0. {
+                properties.push(("stroke-dashoffset", d(offset).to_string()));
+            }
+        }
+
+        // Butt and miter are the defaults of SVG
+        match stroke.cap() {
+            LineCap::Butt => {}
+            LineCap::Round => properties.push(("stroke-linecap", "round".to_string())),
+            LineCap::Square => properties.push(("stroke-linecap", "square".to_string())),
+        }
+        match stroke.join() {
+            LineJoin::Miter if stroke.miter_limit() != Stroke::DEFAULT_MITER_LIMIT => {
+                properties.push(("stroke-miterlimit", d(stroke.miter_limit()).to_string()))
+            }
+            LineJoin::Miter => {}
+            LineJoin::Round => properties.push(("stroke-linejoin", "round".to_string())),
+            LineJoin::Bevel => properties.push(("stroke-linejoin", "bevel".to_string())),
+        }
+    }
+
+    if style.opacity != 1. {
+        properties.push(("opacity", d(style.opacity).to_string()));
+    }
+    if style.blend_mode != BlendMode::Normal {
+        properties.push((BLEND_MODE_PROPERTY, style.blend_mode.css_name().to_string()));
+    }
+
+    properties
+}
+
+/// Metadata written on a group: plain metadata, and hints for `svg` other than animations
+fn block_attributes(metadata: &[(String, String)]) -> impl Iterator<Item = (&str, &str)> {
+    metadata
+        .iter()
+        .filter(|(key, _)| split_hint(key).is_none_or(|(backend, _)| backend == BACKEND))
+        .filter(|(key, _)| !animation::is_animate_hint(key) && key != CLIP_METADATA_KEY)
+        .filter(|(key, _)| key != MASK_METADATA_KEY && key != MASK_MODE_METADATA_KEY)
+        .filter(|(key, _)| key != LINK_METADATA_KEY && key != LAYER_METADATA_KEY)
+        .map(|(key, value)| (key.as_str(), value.as_str()))
+}
+
+/// URL the shapes of a group link to, in an `<a>` element
+fn link(metadata: &[(String, String)]) -> Option<&str> {
+    metadata
+        .iter()
+        .find(|(key, _)| key == LINK_METADATA_KEY)
+        .map(|(_, url)| url.as_str())
+}
+
+/// Name of the layer of a group, written as an Inkscape layer
+fn layer(metadata: &[(String, String)]) -> Option<&str> {
+    metadata
+        .iter()
+        .find(|(key, _)| key == LAYER_METADATA_KEY)
+        .map(|(_, name)| name.as_str())
+}
+
+/// Whether a group is written as a `<g>` element
+fn has_block(metadata: &[(String, String)]) -> bool {
+    block_attributes(metadata).next().is_some() || layer(metadata).is_some()
+}
+
+const BACKEND: &str = "svg";
+
+const INKSCAPE_NAMESPACE: &str = r#"xmlns:inkscape="http://www.inkscape.org/namespaces/inkscape""#;
+
 impl Exporter for SVGExporter {
     type Error = SVGError;
     const CAN_EXPORT_ELLIPSE: bool = true;
+    const CAN_EXPORT_FILTER: bool = true;
+    const CAN_EXPORT_PATTERN: bool = true;
+    const CAN_EXPORT_OPACITY: bool = true;
+    const CAN_EXPORT_BLEND_MODE: bool = true;
+    const BACKEND: Option<&'static str> = Some(BACKEND);
+
+    /// Identical subtrees are written once, when [`SVGOptions::reuse_subtrees`] is set.
+    ///
+    /// [Clips][Clip] are written as a group referencing a `<clipPath>`.
+    ///
+    /// ```
+    /// # use dessin::prelude::*;
+    /// let badge = dessin2!(Clip(
+    ///     shape = dessin2!(Rectangle!(fill = Color::RED, width = 40., height = 20.)),
+    ///     clipped_by = dessin2!(Circle!(fill = Color::BLUE, radius = 8.)),
+    /// ));
+    ///
+    /// let svg = dessin_svg::to_string(&badge.into()).unwrap();
+    /// assert!(svg.contains(r#"<clipPath id="c0"><ellipse rx="8" ry="8""#));
+    /// assert!(svg.contains(r##"<g clip-path="url(#c0)" ><g fill='#FF0000'"##));
+    /// ```
+    ///
+    /// [Masks][Mask] are written as a group referencing a `<mask>`.
+    ///
+    /// ```
+    /// # use dessin::prelude::*;
+    /// let faded = dessin2!(Mask(
+    ///     shape = dessin2!(Rectangle!(fill = Color::RED, width = 40., height = 20.)),
+    ///     mask = dessin2!(Rectangle!(fill = rgb(128, 128, 128), width = 40., height = 20.)),
+    /// ));
+    ///
+    /// let svg = dessin_svg::to_string(&faded.into()).unwrap();
+    /// assert!(svg.contains(r##"<mask id="m0"><g fill='#808080'"##));
+    /// assert!(svg.contains(r##"<g mask="url(#m0)" ><g fill='#FF0000'"##));
+    /// ```
+    ///
+    /// Alpha masks are marked by their `mask-type`.
+    ///
+    /// ```
+    /// # use dessin::prelude::*;
+    /// let faded = dessin2!(Mask(
+    ///     shape = dessin2!(Rectangle!(fill = Color::RED, width = 40., height = 20.)),
+    ///     mask = dessin2!(Rectangle!(fill = rgba(0, 0, 0, 128), width = 40., height = 20.)),
+    ///     mode = MaskMode::Alpha,
+    /// ));
+    ///
+    /// let svg = dessin_svg::to_string(&faded.into()).unwrap();
+    /// assert!(svg.contains(r#"<mask id="m0" mask-type="alpha">"#));
+    /// assert!(!svg.contains("mask-mode"));
+    /// ```
+    fn export_subtree(
+        &mut self,
+        shape: &Shape,
+        parent_transform: &Transform2<f32>,
+    ) -> Result<bool, Self::Error> {
+        if self.in_clip_path {
+            return Ok(false);
+        }
+
+        if let Shape::Group(group) = shape {
+            let skipped = hint(&group.metadata, BACKEND, SKIP_HINT) == Some("true");
+            if let Some((clipped, clipped_by)) = Clip::parts(group).filter(|_| !skipped) {
+                self.write_clipped(group, clipped, clipped_by, parent_transform)?;
+                return Ok(true);
+            }
+            if let Some((masked, mask)) = Mask::parts(group).filter(|_| !skipped) {
+                self.write_masked(group, masked, mask, parent_transform)?;
+                return Ok(true);
+            }
+
+            if animation::is_animated(&group.metadata)
+                && hint(&group.metadata, BACKEND, SKIP_HINT) != Some("true")
+            {
+                self.write_animated(group, parent_transform)?;
+                return Ok(true);
+            }
+        }
+
+        if self.in_definition {
+            return Ok(false);
+        }
+        let Some(instance_counts) = &self.instance_counts else {
+            return Ok(false);
+        };
+        let Some(instance) = Instance::of(shape, parent_transform) else {
+            return Ok(false);
+        };
+        if instance_counts
+            .get(&instance.key)
+            .is_none_or(|count| *count < 2)
+        {
+            return Ok(false);
+        }
+
+        let id = match self.definitions.get(&instance.key) {
+            Some(id) => *id,
+            None => {
+                let id = self.write_definition(shape, &instance)?;
+                self.definitions.insert(instance.key.clone(), id);
+                id
+            }
+        };
+        let Some(id) = id else {
+            return Ok(false);
+        };
+
+        let m = instance.placement.matrix();
+        let precision = self.precision;
+        let n = |value| Decimal { value, precision };
+        write!(
+            self.acc,
+            r##"<use href="#u{id}" transform="matrix({a} {b} {c} {d} {e} {f})"/>"##,
+            a = n(m[(0, 0)]),
+            b = n(m[(1, 0)]),
+            c = n(m[(0, 1)]),
+            d = n(m[(1, 1)]),
+            e = n(m[(0, 2)]),
+            f = n(m[(1, 2)]),
+        )?;
+
+        Ok(true)
+    }
 
+    /// Filters are written once in `<defs>`, and referenced by every style applying them
+    ///
+    /// ```
+    /// # use dessin::prelude::*;
+    /// let cards = dessin2!(for x in 0..3 {
+    ///     dessin2!(Rectangle!(
+    ///         fill = Color::WHITE,
+    ///         filter = Filter::DropShadow {
+    ///             dx: 0.5,
+    ///             dy: -0.5,
+    ///             std_deviation: 0.3,
+    ///             color: Color::BLACK,
+    ///         },
+    ///         width = 4.,
+    ///         height = 3.,
+    ///         translate = [x as f32 * 5., 0.],
+    ///     ))
+    /// });
+    ///
+    /// let svg = dessin_svg::to_string(&cards).unwrap();
+    /// assert_eq!(svg.matches("<filter").count(), 1);
+    /// assert_eq!(svg.matches("filter='url(#f0)'").count(), 3);
+    /// ```
+    ///
+    /// Gradients are written in `<defs>` the same way, and referenced by the fills using them
+    ///
+    /// ```
+    /// # use dessin::prelude::*;
+    /// // Gradients are placed in the coordinates of their style, translated with the group
+    /// let badge = dessin2!([Circle!(
+    ///     fill = Fill::RadialGradient {
+    ///         center: [0., 0.].into(),
+    ///         radius: 10.,
+    ///         stops: [(0., Color::WHITE), (1., Color::BLUE)].into(),
+    ///     },
+    ///     radius = 10.,
+    /// )] > (translate = [5., 5.]));
+    ///
+    /// let svg = dessin_svg::to_string(&badge).unwrap();
+    /// assert!(svg.contains(r#"<radialGradient id="g0" gradientUnits="userSpaceOnUse" cx="5" cy="-5""#));
+    /// assert!(svg.contains(r##"<stop offset="1" stop-color="#0000FF"/>"##));
+    /// assert!(svg.contains("fill='url(#g0)'"));
+    /// ```
+    ///
+    /// Patterns as well, with their tile drawn once in the `<pattern>`
+    ///
+    /// ```
+    /// # use dessin::prelude::*;
+    /// let field = dessin2!(Rectangle!(
+    ///     fill = Fill::hatching(Color::BLACK, 0.2, 2., Angle::degrees(45.)),
+    ///     width = 20.,
+    ///     height = 10.,
+    /// ) > ());
+    ///
+    /// let svg = dessin_svg::to_string(&field).unwrap();
+    /// assert!(svg.contains(r#"<pattern id="p0" patternUnits="userSpaceOnUse" width="2" height="2""#));
+    /// assert!(svg.contains("fill='url(#p0)'"));
+    /// ```
+    ///
+    /// Caps and joins of strokes are only written when they are not butt and miter, the defaults of SVG
+    ///
+    /// ```
+    /// # use dessin::prelude::*;
+    /// let route = dessin2!(Line!(
+    ///     stroke = Stroke::from((Color::BLACK, 2.)).with_cap(LineCap::Round),
+    ///     from = [0., 0.],
+    ///     to = [10., 0.],
+    /// ) > ());
+    ///
+    /// let svg = dessin_svg::to_string(&route).unwrap();
+    /// assert!(svg.contains("stroke-linecap='round'"));
+    /// assert!(!svg.contains("stroke-linejoin"));
+    /// ```
+    ///
+    /// Likewise, the [miter limit][Stroke::miter_limit] is only written when it is not 4
+    ///
+    /// ```
+    /// # use dessin::prelude::*;
+    /// use nalgebra::Point2;
+    ///
+    /// let spike = dessin2!(Curve!(
+    ///     stroke = Stroke::from((Color::BLACK, 2.)).with_miter_limit(2.),
+    ///     then = Point2::new(-5., 0.),
+    ///     then = Point2::new(0., 40.),
+    ///     then = Point2::new(5., 0.),
+    ///     closed,
+    /// ) > ());
+    ///
+    /// let svg = dessin_svg::to_string(&spike).unwrap();
+    /// assert!(svg.contains("stroke-miterlimit='2'"));
+    /// ```
+    ///
+    /// The [opacity of a style][Style::opacity] applies to the whole group
+    ///
+    /// ```
+    /// # use dessin::prelude::*;
+    /// let layer = dessin2!([
+    ///     Circle!(fill = Color::RED, radius = 10.),
+    ///     Rectangle!(fill = Color::BLUE, width = 10., height = 10.),
+    /// ]);
+    /// let faded: Shape = Style::new(layer).with_opacity(0.5).into();
+    ///
+    /// let svg = dessin_svg::to_string(&faded).unwrap();
+    /// assert!(svg.contains("opacity='0.5'"));
+    /// assert!(svg.contains("fill='#FF0000'"));
+    /// ```
+    ///
+    /// The [blend mode of a style][Style::blend_mode] is written as the CSS property `mix-blend-mode`
+    ///
+    /// ```
+    /// # use dessin::prelude::*;
+    /// let ink = dessin2!(Circle!(fill = Color::CYAN, radius = 10.) > ());
+    /// let printed: Shape = Style::new(ink).with_blend_mode(BlendMode::ColorBurn).into();
+    ///
+    /// let svg = dessin_svg::to_string(&printed).unwrap();
+    /// assert!(svg.contains("style='mix-blend-mode:color-burn'"));
+    /// ```
     fn start_style(&mut self, style: StylePosition) -> Result<(), Self::Error> {
+        // Only the geometry of a clipping shape matters
+        if self.in_clip_path {
+            return Ok(());
+        }
+
+        let (fill, stroke) = (style.fill.is_some(), style.stroke.is_some());
+
         write!(self.acc, "<g ")?;
         self.write_style(style)?;
         write!(self.acc, ">")?;
 
+        for (animation, elements) in &self.style_animations {
+            if animation.applies_to(fill, stroke) {
+                self.acc.push_str(elements);
+            }
+        }
+
         Ok(())
     }
 
     fn end_style(&mut self) -> Result<(), Self::Error> {
+        if self.in_clip_path {
+            return Ok(());
+        }
         write!(self.acc, "</g>")?;
         Ok(())
     }
 
-    fn start_block(&mut self, _metadata: &[(String, String)]) -> Result<(), Self::Error> {
-        if !_metadata.is_empty() {
+    /// Hints for `svg` become attributes of the group, such as `("svg:filter", "url(#blur)")`
+    ///
+    /// Groups with a [link][LINK_METADATA_KEY] are wrapped in an `<a>` element
+    ///
+    /// Groups in a [layer][LAYER_METADATA_KEY] are written as Inkscape layers, named after it
+    ///
+    /// ```
+    /// # use dessin::prelude::*;
+    /// use dessin::layers::LAYER_METADATA_KEY;
+    ///
+    /// let mut cut = dessin2!(Circle!(stroke = (Color::RED, 0.1), radius = 20.) > ());
+    /// cut.add_metadata((LAYER_METADATA_KEY, "Cut"));
+    ///
+    /// let svg = dessin_svg::to_string(&dessin2!([{ cut }])).unwrap();
+    /// assert!(svg.starts_with(r#"<svg xmlns:inkscape="http://www.inkscape.org/namespaces/inkscape" "#));
+    /// assert!(svg.contains(r#"<g inkscape:groupmode="layer" inkscape:label="Cut" >"#));
+    /// ```
+    ///
+    /// ```
+    /// # use dessin::prelude::*;
+    /// use dessin_svg::LINK_METADATA_KEY;
+    ///
+    /// let mut card = dessin2!(Rectangle!(fill = Color::WHITE, width = 30., height = 10.) > ());
+    /// card.add_metadata((LINK_METADATA_KEY, "https://example.com/people?id=4&tab=team"));
+    ///
+    /// let svg = dessin_svg::to_string(&dessin2!([{ card }])).unwrap();
+    /// assert!(svg.contains(r#"<a href="https://example.com/people?id=4&amp;tab=team"><g fill="#));
+    /// assert!(svg.contains("</a>"));
+    /// ```
+    fn start_block(&mut self, metadata: &[(String, String)]) -> Result<(), Self::Error> {
+        if self.in_clip_path {
+            return Ok(());
+        }
+
+        self.start_link(metadata)?;
+        if has_block(metadata) {
             write!(self.acc, "<g ")?;
-            for (key, value) in _metadata {
-                write!(self.acc, r#"{key}={value} "#)?;
-            }
+            self.write_block_attributes(metadata)?;
             write!(self.acc, ">")?;
         }
 
         Ok(())
     }
 
-    fn end_block(&mut self, _metadata: &[(String, String)]) -> Result<(), Self::Error> {
-        if !_metadata.is_empty() {
+    fn end_block(&mut self, metadata: &[(String, String)]) -> Result<(), Self::Error> {
+        if self.in_clip_path {
+            return Ok(());
+        }
+
+        if has_block(metadata) {
             write!(self.acc, "</g>")?;
         }
+        self.end_link(metadata)?;
         Ok(())
     }
 
@@ -231,23 +1388,32 @@ impl Exporter for SVGExporter {
             image,
         }: ImagePosition,
     ) -> Result<(), Self::Error> {
+        // Images can't clip
+        if self.in_clip_path {
+            return Ok(());
+        }
+
         let mut raw_image = Cursor::new(vec![]);
         image.write_to(&mut raw_image, ImageFormat::Png).unwrap();
 
         let data = data_encoding::BASE64.encode(&raw_image.into_inner());
 
+        let precision = self.precision;
+        let d = |value| Decimal { value, precision };
         write!(
             self.acc,
             r#"<image width="{width}" height="{height}" x="{x}" y="{y}" "#,
-            x = center.x - width / 2.,
-            y = center.y - height / 2.,
+            width = d(width),
+            height = d(height),
+            x = d(center.x - width / 2.),
+            y = d(center.y - height / 2.),
         )?;
 
         if rotation.abs() > 10e-6 {
             write!(
                 self.acc,
                 r#" transform="rotate({rot})" "#,
-                rot = (-rotation.to_degrees() + 360.) % 360.
+                rot = d((-rotation.to_degrees() + 360.) % 360.)
             )?;
         }
 
@@ -265,20 +1431,28 @@ impl Exporter for SVGExporter {
             rotation,
         }: EllipsePosition,
     ) -> Result<(), Self::Error> {
+        let precision = self.precision;
+        let d = |value| Decimal { value, precision };
         write!(
             self.acc,
-            r#"<ellipse rx="{semi_major_axis}" ry="{semi_minor_axis}" transform=""#,
+            r#"<ellipse rx="{rx}" ry="{ry}" transform=""#,
+            rx = d(semi_major_axis),
+            ry = d(semi_minor_axis),
         )?;
 
         write!(
             self.acc,
             r#"translate({cx} {cy}) "#,
-            cx = center.x,
-            cy = center.y
+            cx = d(center.x),
+            cy = d(center.y)
         )?;
 
         if rotation.abs() > 10e-6 {
-            write!(self.acc, r#"rotate({rot}) "#, rot = -rotation.to_degrees())?;
+            write!(
+                self.acc,
+                r#"rotate({rot}) "#,
+                rot = d(-rotation.to_degrees())
+            )?;
         }
 
         write!(self.acc, r#""/>"#)?;
@@ -286,10 +1460,40 @@ impl Exporter for SVGExporter {
         Ok(())
     }
 
+    /// Curves filled with the even-odd rule carry it, as the `clip-rule` of a clipping path
+    ///
+    /// ```
+    /// # use dessin::prelude::*;
+    /// use nalgebra::Point2;
+    ///
+    /// let star = dessin2!(Curve!(
+    ///     fill = Color::BLACK,
+    ///     then = Point2::new(0., 10.),
+    ///     then = Point2::new(6., -8.),
+    ///     then = Point2::new(-9.5, 3.),
+    ///     then = Point2::new(9.5, 3.),
+    ///     then = Point2::new(-6., -8.),
+    ///     closed,
+    ///     fill_rule = FillRule::EvenOdd,
+    /// ) > ());
+    ///
+    /// let svg = dessin_svg::to_string(&star).unwrap();
+    /// assert!(svg.contains(r#"Z" fill-rule="evenodd"/>"#));
+    /// ```
     fn export_curve(&mut self, curve: CurvePosition) -> Result<(), Self::Error> {
+        let fill_rule = curve.fill_rule;
         write!(self.acc, r#"<path d=""#)?;
         self.write_curve(curve)?;
-        write!(self.acc, r#""/>"#)?;
+        write!(self.acc, r#"""#)?;
+        if fill_rule == FillRule::EvenOdd {
+            let property = if self.in_clip_path {
+                "clip-rule"
+            } else {
+                "fill-rule"
+            };
+            write!(self.acc, r#" {property}="evenodd""#)?;
+        }
+        write!(self.acc, "/>")?;
 
         Ok(())
     }
@@ -317,17 +1521,28 @@ impl Exporter for SVGExporter {
             FontWeight::Italic | FontWeight::BoldItalic => "italic",
             _ => "normal",
         };
-        let align = match align {
-            TextAlign::Center => "middle",
-            TextAlign::Left => "start",
-            TextAlign::Right => "end",
+        // Offset of the anchor along the curve, if any
+        let (align, start_offset) = match align {
+            TextAlign::Center => ("middle", "50%"),
+            TextAlign::Left => ("start", "0%"),
+            TextAlign::Right => ("end", "100%"),
         };
 
         let text = text.replace("<", "&lt;").replace(">", "&gt;");
 
         let font = font.clone().unwrap_or(FontRef::default());
 
-        self.used_font.insert((font.clone(), font_weight));
+        let first_use = self.used_font.insert((font.clone(), font_weight));
+        if first_use
+            && self.embed_fonts
+            && self.restricted_fonts == RestrictedFontPolicy::Deny
+            && font::get(font.clone())
+                .get(font_weight)
+                .embedding_permission()
+                == Some(EmbeddingPermission::Restricted)
+        {
+            return Err(SVGError::RestrictedFont { font, font_weight });
+        }
 
         // let font_group = font::get(font.clone());
 
@@ -354,31 +1569,44 @@ impl Exporter for SVGExporter {
 
         write!(
             self.acc,
-            r#"<text font-family="{font}" text-anchor="{align}" font-size="{font_size}px" font-weight="{weight}" text-style="{text_style}" transform=""#,
+            r#"<text font-family="{font}" text-anchor="{align}" font-size="{font_size}px" font-weight="{weight}" text-style="{text_style}""#,
+            font_size = Decimal {
+                value: font_size,
+                precision: self.precision
+            },
         )?;
 
-        write!(
-            self.acc,
-            r#"translate({cx} {cy}) "#,
-            cx = reference_start.x,
-            cy = reference_start.y
-        )?;
-
-        let rotation = direction.y.atan2(direction.x);
-        if rotation.abs() > 10e-6 {
-            write!(self.acc, r#"rotate({rot}) "#, rot = rotation.to_degrees())?;
-        }
-
-        write!(self.acc, r#"">"#)?;
-
         if let Some(curve) = on_curve {
+            // The curve is already in global coordinates, so the text isn't transformed
+            write!(self.acc, r#">"#)?;
             write!(self.acc, r#"<path id="{id}" d=""#)?;
             self.write_curve(curve)?;
             write!(self.acc, r#""/>"#)?;
 
-            write!(self.acc, r##"<textPath href="#{id}">{text}</textPath>"##)?;
+            write!(
+                self.acc,
+                r##"<textPath href="#{id}" startOffset="{start_offset}">{text}</textPath>"##
+            )?;
         } else {
-            write!(self.acc, "{text}")?;
+            let precision = self.precision;
+            let d = |value| Decimal { value, precision };
+            write!(
+                self.acc,
+                r#" transform="translate({cx} {cy}) "#,
+                cx = d(reference_start.x),
+                cy = d(reference_start.y)
+            )?;
+
+            let rotation = direction.y.atan2(direction.x);
+            if rotation.abs() > 10e-6 {
+                write!(
+                    self.acc,
+                    r#"rotate({rot}) "#,
+                    rot = d(rotation.to_degrees())
+                )?;
+            }
+
+            write!(self.acc, r#"">{text}"#)?;
         }
         write!(self.acc, r#"</text>"#)?;
 
@@ -386,8 +1614,9 @@ impl Exporter for SVGExporter {
     }
 }
 
-pub fn to_string_with_options(shape: &Shape, options: SVGOptions) -> Result<String, SVGError> {
-    let (min_x, min_y, span_x, span_y) = match options.viewport {
+/// Top left corner and size of the viewport of `shape`, in SVG coordinates
+fn viewport_of(shape: &Shape, viewport: ViewPort) -> (f32, f32, f32, f32) {
+    match viewport {
         ViewPort::ManualCentered { width, height } => (-width / 2., -height / 2., width, height),
         ViewPort::ManualViewport {
             x,
@@ -418,16 +1647,304 @@ pub fn to_string_with_options(shape: &Shape, options: SVGOptions) -> Result<Stri
 
             (bb.top_left().x, -bb.top_left().y, bb.width(), bb.height())
         }
-    };
+        ViewPort::AutoPadded { padding } => {
+            let bb = shape.local_bounding_box().straigthen();
 
-    let mut exporter = SVGExporter::new(min_x, min_y, span_x, span_y);
+            (
+                bb.top_left().x - padding,
+                -bb.top_left().y - padding,
+                bb.width() + 2. * padding,
+                bb.height() + 2. * padding,
+            )
+        }
+    }
+}
+
+/// Group with an id, and where it is drawn
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExportedElement {
+    /// Id of the element, from the `svg:id` hint of the group
+    pub id: String,
+    /// Bounding box of the group, in the coordinates of the `viewBox`, where Y points down
+    pub bounding_box: BoundingBox<Straight>,
+}
+
+/// Geometry of an exported SVG, to map positions on the image back to the dessin, such as for hit maps.
+///
+/// Positions are in the coordinates of the `viewBox`, scaled to the size the SVG is displayed at.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SVGExportReport {
+    /// Top left corner and size of the `viewBox`
+    pub view_box: (f32, f32, f32, f32),
+    /// Groups with an `svg:id` hint, in the order they are written
+    pub elements: Vec<ExportedElement>,
+}
+
+/// Groups of `shape` with an id, with their bounding box
+fn exported_elements(
+    shape: &Shape,
+    parent_transform: &Transform2<f32>,
+    elements: &mut Vec<ExportedElement>,
+) {
+    match shape {
+        Shape::Group(group) => {
+            if hint(&group.metadata, BACKEND, SKIP_HINT) == Some("true") {
+                return;
+            }
+            if let Some(id) = hint(&group.metadata, BACKEND, "id") {
+                elements.push(ExportedElement {
+                    id: id.to_string(),
+                    bounding_box: shape.global_bounding_box(parent_transform).straigthen(),
+                });
+            }
+
+            let transform = parent_transform * group.local_transform;
+            for shape in &group.shapes {
+                exported_elements(shape, &transform, elements);
+            }
+        }
+        Shape::Style { shape, .. } => exported_elements(shape, parent_transform, elements),
+        Shape::Dynamic {
+            local_transform,
+            shaper,
+        } => exported_elements(&shaper(), &(parent_transform * local_transform), elements),
+        _ => {}
+    }
+}
+
+/// `shape` in Y up coordinates, with the debug overlay, stamps and backdrop of `options`,
+/// and the top left corner and size of its viewport
+fn decorated<'a>(shape: &'a Shape, options: &SVGOptions) -> (Cow<'a, Shape>, (f32, f32, f32, f32)) {
+    let mut shape = options.coordinates.to_y_up(shape);
+    if let Some(overlay) = &options.debug {
+        shape = Cow::Owned(shape.with_debug_overlay(overlay));
+    }
+
+    let (min_x, min_y, span_x, span_y) = viewport_of(&shape, options.viewport);
+    let viewport = BoundingBox::mins_maxs(min_x, -(min_y + span_y), min_x + span_x, -min_y);
+
+    if let Some(statement) = &options.scale_statement {
+        shape = Cow::Owned(with_scale_statement(&shape, &viewport, statement));
+    }
+    if !options.stamps.is_empty() {
+        shape = Cow::Owned(with_stamps(&shape, &viewport, &options.stamps));
+    }
+    if options.background.is_some() || options.border.is_some() {
+        shape = Cow::Owned(with_backdrop(
+            &shape,
+            &viewport,
+            options.background.as_ref(),
+            options.border,
+        ));
+    }
+
+    (shape, (min_x, min_y, span_x, span_y))
+}
+
+pub fn to_string_with_options(shape: &Shape, options: SVGOptions) -> Result<String, SVGError> {
+    to_string_with_report(shape, options).map(|(svg, _)| svg)
+}
+
+/// SVG of `shape`, with the final `viewBox` and where each group with an `svg:id` hint is drawn
+///
+/// ```
+/// # use dessin::prelude::*;
+/// use dessin_svg::SVGOptions;
+///
+/// let mut ceo = dessin2!(Rectangle!(fill = Color::WHITE, width = 30., height = 10., translate = [0., 20.]) > ());
+/// ceo.add_metadata(("svg:id", "ceo"));
+/// let mut cto = dessin2!(Rectangle!(fill = Color::WHITE, width = 30., height = 10.) > ());
+/// cto.add_metadata(("svg:id", "cto"));
+///
+/// let chart = dessin2!([{ ceo }, { cto }]);
+/// let (svg, report) = dessin_svg::to_string_with_report(&chart, SVGOptions::default()).unwrap();
+/// assert!(svg.contains(r#"id="ceo""#));
+///
+/// assert_eq!(report.view_box, (-15., -25., 30., 30.));
+/// assert_eq!(report.elements[0].id, "ceo");
+/// // Y points down in the SVG, the CEO is above
+/// assert_eq!(report.elements[0].bounding_box.top(), -15.);
+/// assert_eq!(report.elements[1].bounding_box.bottom(), -5.);
+/// ```
+pub fn to_string_with_report(
+    shape: &Shape,
+    options: SVGOptions,
+) -> Result<(String, SVGExportReport), SVGError> {
+    let (shape, (min_x, min_y, span_x, span_y)) = decorated(shape, &options);
+    let shape = shape.as_ref();
+
+    let mut exporter = SVGExporter::new(min_x, min_y, span_x, span_y, &options);
 
     let parent_transform = nalgebra::convert(Scale2::new(1., -1.));
+    if options.reuse_subtrees {
+        let mut counter = InstanceCounter::default();
+        shape.write_into_exporter(&mut counter, &parent_transform)?;
+        exporter.instance_counts = Some(counter.counts);
+    }
     shape.write_into_exporter(&mut exporter, &parent_transform)?;
 
-    Ok(exporter.finish())
+    let mut svg = exporter.finish();
+    if options.minify {
+        svg = format::minify(&svg);
+    }
+    if let Some(indent) = options.indent {
+        svg = format::indent(&svg, indent);
+    }
+
+    let mut elements = vec![];
+    exported_elements(shape, &parent_transform, &mut elements);
+    let report = SVGExportReport {
+        view_box: (min_x, min_y, span_x, span_y),
+        elements,
+    };
+
+    Ok((svg, report))
 }
 
 pub fn to_string(shape: &Shape) -> Result<String, SVGError> {
     to_string_with_options(shape, SVGOptions::default())
 }
+
+/// Write the SVG of `shape` to `writer`
+///
+/// ```
+/// # use dessin::prelude::*;
+/// use dessin_svg::SVGOptions;
+///
+/// let dessin = dessin2!(Circle!(fill = Color::RED, radius = 10.) > ());
+///
+/// let mut svg = vec![];
+/// dessin_svg::to_writer(&dessin, &mut svg, SVGOptions::default()).unwrap();
+/// assert!(svg.starts_with(b"<svg"));
+/// ```
+pub fn to_writer<W: std::io::Write>(
+    shape: &Shape,
+    mut writer: W,
+    options: SVGOptions,
+) -> Result<(), SVGError> {
+    let svg = to_string_with_options(shape, options)?;
+    writer.write_all(svg.as_bytes())?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Write the SVG of `shape` to the file at `path`, creating its missing parent directories.
+///
+/// With [`atomic_write`][SVGOptions::atomic_write], the SVG is written to a temporary file in the same directory,
+/// then renamed over `path`.
+///
+/// ```
+/// # use dessin::prelude::*;
+/// use dessin_svg::SVGOptions;
+///
+/// let dessin = dessin2!(Circle!(fill = Color::RED, radius = 10.) > ());
+///
+/// let path = std::env::temp_dir().join("dessin_svg_to_file/nested/circle.svg");
+/// # let _ = std::fs::remove_dir_all(path.parent().unwrap().parent().unwrap());
+/// dessin_svg::to_file(
+///     &dessin,
+///     &path,
+///     SVGOptions {
+///         atomic_write: true,
+///         ..Default::default()
+///     },
+/// )
+/// .unwrap();
+///
+/// assert!(std::fs::read_to_string(&path).unwrap().starts_with("<svg"));
+/// ```
+pub fn to_file<P: AsRef<std::path::Path>>(
+    shape: &Shape,
+    path: P,
+    options: SVGOptions,
+) -> Result<(), SVGError> {
+    let atomic_write = options.atomic_write;
+    let svg = to_string_with_options(shape, options)?;
+
+    write_file(path.as_ref(), &svg, atomic_write)
+}
+
+/// Write `contents` to the file at `path`, creating its missing parent directories,
+/// through a temporary file renamed over `path` if `atomic_write`
+fn write_file(path: &std::path::Path, contents: &str, atomic_write: bool) -> Result<(), SVGError> {
+    if let Some(parent) = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+    {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    if !atomic_write {
+        std::fs::write(path, contents)?;
+        return Ok(());
+    }
+
+    let mut temporary = path.as_os_str().to_owned();
+    temporary.push(format!(".{}.tmp", std::process::id()));
+    let temporary = std::path::PathBuf::from(temporary);
+
+    let written =
+        std::fs::write(&temporary, contents).and_then(|_| std::fs::rename(&temporary, path));
+    if written.is_err() {
+        let _ = std::fs::remove_file(&temporary);
+    }
+    written?;
+
+    Ok(())
+}
+
+/// One SVG per layer of `shape`, as split by [`Shape::split_layers`], with the name of its layer.
+///
+/// Every SVG has the viewport of the whole dessin, so layers stay aligned when they are stacked back,
+/// or cut and engraved one after the other.
+///
+/// ```
+/// # use dessin::prelude::*;
+/// use dessin::layers::LAYER_METADATA_KEY;
+/// use dessin_svg::{to_layer_strings_with_options, SVGOptions};
+///
+/// let mut cut = dessin2!(Circle!(stroke = (Color::RED, 0.1), radius = 20.) > ());
+/// cut.add_metadata((LAYER_METADATA_KEY, "Cut"));
+/// let mut engrave = dessin2!(Text!(fill = Color::BLACK, text = "Coaster") > ());
+/// engrave.add_metadata((LAYER_METADATA_KEY, "Engrave"));
+///
+/// let layers = to_layer_strings_with_options(&dessin2!([{ cut }, { engrave }]), SVGOptions::default()).unwrap();
+/// assert_eq!(layers.len(), 2);
+/// assert_eq!(layers[0].0.as_deref(), Some("Cut"));
+///
+/// // Both layers share the viewport of the circle
+/// let view_box = |svg: &str| svg.split('"').nth(1).unwrap().to_string();
+/// assert_eq!(view_box(&layers[0].1), view_box(&layers[1].1));
+/// ```
+pub fn to_layer_strings_with_options(
+    shape: &Shape,
+    options: SVGOptions,
+) -> Result<Vec<(Option<String>, String)>, SVGError> {
+    let shape = options.coordinates.to_y_up(shape);
+    let shape = shape.as_ref();
+
+    let (min_x, min_y, width, height) = match &options.debug {
+        Some(overlay) => viewport_of(&shape.with_debug_overlay(overlay), options.viewport),
+        None => viewport_of(shape, options.viewport),
+    };
+    let options = SVGOptions {
+        viewport: ViewPort::ManualViewport {
+            x: min_x + width / 2.,
+            y: min_y + height / 2.,
+            width,
+            height,
+        },
+        coordinates: CoordinateSystem::YUp,
+        ..options
+    };
+
+    shape
+        .split_layers()
+        .into_iter()
+        .map(|(name, layer)| Ok((name, to_string_with_options(&layer, options.clone())?)))
+        .collect()
+}
+
+pub fn to_layer_strings(shape: &Shape) -> Result<Vec<(Option<String>, String)>, SVGError> {
+    to_layer_strings_with_options(shape, SVGOptions::default())
+}
@@ -0,0 +1,175 @@
+//! Identical subtrees, written once in `<defs>` and referenced by `<use>`.
+//!
+//! Two subtrees are identical when their content is, and their transforms only differ by a rotation,
+//! a reflection or a translation, which a `<use>` applies without changing the width of strokes.
+//! The rest of the transform, its scale and skew, is baked into the definition.
+
+use crate::{animation::is_animated, SVGError};
+use dessin::{export::Exporter, prelude::*};
+use nalgebra::{Matrix2, Matrix3, Transform2};
+use std::collections::HashMap;
+
+/// Definitions shorter than this are cheaper to write inline than to reference
+pub(crate) const MIN_SHARED_LENGTH: usize = 80;
+
+/// A subtree, as a `<use>` of a definition shared by every identical subtree
+pub(crate) struct Instance {
+    /// Content of the subtree, with the scale and skew of its transform
+    pub key: String,
+    /// Transform the definition is exported with
+    pub definition_transform: Transform2<f32>,
+    /// Rotation, reflection and translation applied by the `<use>`
+    pub placement: Transform2<f32>,
+}
+impl Instance {
+    /// `None` if the subtree can't be shared
+    pub fn of(shape: &Shape, parent_transform: &Transform2<f32>) -> Option<Instance> {
+        let (content, local_transform) = fingerprint(shape, parent_transform)?;
+
+        let global = parent_transform.matrix() * local_transform.matrix();
+        let linear = global.fixed_view::<2, 2>(0, 0).into_owned();
+
+        // Polar decomposition, linear = rotation * stretch, with stretch symmetric positive
+        let squared = linear.transpose() * linear;
+        let det = squared.determinant();
+        if !det.is_finite() || det <= 10e-12 {
+            return None;
+        }
+        let sqrt_det = det.sqrt();
+        let stretch =
+            (squared + Matrix2::identity() * sqrt_det) / (squared.trace() + 2. * sqrt_det).sqrt();
+        let rotation = linear * stretch.try_inverse()?;
+
+        let mut placement = Matrix3::identity();
+        placement.fixed_view_mut::<2, 2>(0, 0).copy_from(&rotation);
+        placement
+            .fixed_view_mut::<2, 1>(0, 2)
+            .copy_from(&global.fixed_view::<2, 1>(0, 2));
+
+        let definition_transform = Transform2::from_matrix_unchecked(stretch.to_homogeneous())
+            * local_transform.try_inverse()?;
+
+        Some(Instance {
+            key: format!(
+                "{content}{:.4?}",
+                [stretch[(0, 0)], stretch[(0, 1)], stretch[(1, 1)]]
+            ),
+            definition_transform,
+            placement: Transform2::from_matrix_unchecked(placement),
+        })
+    }
+}
+
+/// Content of a subtree that can be shared, and its own transform
+fn fingerprint(
+    shape: &Shape,
+    parent_transform: &Transform2<f32>,
+) -> Option<(String, Transform2<f32>)> {
+    match shape {
+        Shape::Group(Group {
+            local_transform,
+            shapes,
+            metadata,
+        }) if shapes.iter().all(is_shareable) => {
+            Some((format!("group{shapes:?}{metadata:?}"), *local_transform))
+        }
+        Shape::Style {
+            fill: fill @ (None | Some(Fill::Color(_))),
+            stroke,
+            stroke_alignment,
+            filter: None,
+            opacity,
+            blend_mode,
+            shape,
+        } => {
+            let (content, local_transform) = fingerprint(shape, parent_transform)?;
+            // The width of the stroke depends on the transform of the style, not the one of its content
+            let stroke = stroke.map(|stroke| *parent_transform * stroke);
+            Some((
+                format!(
+                    "style{fill:?}{stroke:?}{stroke_alignment:?}{opacity}{blend_mode:?}{content}"
+                ),
+                local_transform,
+            ))
+        }
+        Shape::Curve(Curve {
+            local_transform,
+            keypoints,
+            closed,
+            start_marker: None,
+            end_marker: None,
+            fill_rule,
+        }) => Some((
+            format!("curve{keypoints:?}{closed}{fill_rule:?}"),
+            *local_transform,
+        )),
+        _ => None,
+    }
+}
+
+/// Whether `shape` is drawn the same under any rotation, reflection and translation.
+///
+/// Texts and images are written upright, markers are drawn after their style, shadows are offset in a fixed direction, gradients are placed by the style,
+/// animations depend on the transform of their group, and other shapes may change with the transform.
+fn is_shareable(shape: &Shape) -> bool {
+    match shape {
+        Shape::Group(Group {
+            shapes, metadata, ..
+        }) => !is_animated(metadata) && shapes.iter().all(is_shareable),
+        Shape::Style {
+            fill: None | Some(Fill::Color(_)),
+            filter: None,
+            shape,
+            ..
+        } => is_shareable(shape),
+        Shape::Style { .. } => false,
+        Shape::Curve(Curve {
+            start_marker: None,
+            end_marker: None,
+            ..
+        })
+        | Shape::Ellipse(_) => true,
+        Shape::Curve(_) => false,
+        Shape::Text(_) | Shape::Image(_) | Shape::LevelOfDetail(_) | Shape::Dynamic { .. } => false,
+    }
+}
+
+/// First pass, counting the instances of each subtree
+#[derive(Default)]
+pub(crate) struct InstanceCounter {
+    pub counts: HashMap<String, usize>,
+}
+impl Exporter for InstanceCounter {
+    type Error = SVGError;
+    const CAN_EXPORT_OPACITY: bool = true;
+    const CAN_EXPORT_BLEND_MODE: bool = true;
+
+    fn export_subtree(
+        &mut self,
+        shape: &Shape,
+        parent_transform: &Transform2<f32>,
+    ) -> Result<bool, Self::Error> {
+        if let Some(instance) = Instance::of(shape, parent_transform) {
+            *self.counts.entry(instance.key).or_default() += 1;
+        }
+
+        // Nothing to count in texts and images, which may be costly to load
+        Ok(matches!(shape, Shape::Text(_) | Shape::Image(_)))
+    }
+
+    fn start_style(&mut self, _style: StylePosition) -> Result<(), Self::Error> {
+        Ok(())
+    }
+    fn end_style(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+    fn export_image(&mut self, _image: ImagePosition) -> Result<(), Self::Error> {
+        Ok(())
+    }
+    fn export_curve(&mut self, _curve: CurvePosition) -> Result<(), Self::Error> {
+        Ok(())
+    }
+    fn export_text(&mut self, _text: TextPosition) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
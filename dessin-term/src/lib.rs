@@ -0,0 +1,462 @@
+//! Preview a dessin in the terminal.
+//!
+//! The dessin is rasterized at a low resolution, and printed with Unicode braille (2x4 dots per character)
+//! or half-block (2 pixels per character) characters, colored with ANSI true color escape codes.
+//! Quick enough to check generated drawings over SSH or in CLI tools.
+//!
+//! Texts are skipped, as they would be unreadable at this resolution.
+//!
+//! ```
+//! # use dessin::prelude::*;
+//! use dessin_term::{to_string_with_options, Charset, TermOptions};
+//!
+//! let dessin = dessin2!([
+//! 	Circle!(fill = Color::RED, radius = 10.),
+//! 	Rectangle!(stroke = (Color::BLUE, 1.), width = 30., height = 20.),
+//! ]);
+//!
+//! let preview = to_string_with_options(
+//! 	&dessin,
+//! 	TermOptions {
+//! 		columns: 40,
+//! 		charset: Charset::Braille,
+//! 		..Default::default()
+//! 	},
+//! )
+//! .unwrap();
+//!
+//! println!("{preview}");
+//! ```
+
+#![allow(clippy::tabs_in_doc_comments)]
+
+use dessin::{
+    export::{inherited_style, Export, Exporter},
+    image::GenericImageView,
+    prelude::*,
+};
+use nalgebra::{Point2, Scale2, Transform2, Translation2};
+use std::fmt::{self, Write};
+
+#[derive(Debug)]
+pub enum TermError {
+    WriteError(fmt::Error),
+}
+impl fmt::Display for TermError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+impl From<fmt::Error> for TermError {
+    fn from(value: fmt::Error) -> Self {
+        TermError::WriteError(value)
+    }
+}
+impl std::error::Error for TermError {}
+
+/// Rows deduced from the aspect ratio of a dessin are capped at this many times the columns,
+/// so a very thin and tall dessin doesn't allocate a huge preview
+pub const MAX_DEDUCED_ROWS_PER_COLUMN: usize = 4;
+
+/// Characters used to draw the pixels
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub enum Charset {
+    /// 2x4 dots per character, with a single color per character
+    #[default]
+    Braille,
+    /// 1x2 pixels per character, each with its own color
+    HalfBlock,
+}
+impl Charset {
+    /// Pixels in one character, horizontally and vertically
+    fn cell_size(&self) -> (usize, usize) {
+        match self {
+            Charset::Braille => (2, 4),
+            Charset::HalfBlock => (1, 2),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TermOptions {
+    /// Width of the preview, in characters
+    pub columns: usize,
+    /// Height of the preview, in characters.
+    ///
+    /// Deduced from the aspect ratio of the dessin if `None`, up to [`MAX_DEDUCED_ROWS_PER_COLUMN`] times `columns`.
+    pub rows: Option<usize>,
+    /// Characters used to draw the pixels
+    pub charset: Charset,
+    /// Color the characters with ANSI true color escape codes
+    pub colors: bool,
+}
+impl Default for TermOptions {
+    fn default() -> Self {
+        TermOptions {
+            columns: 80,
+            rows: None,
+            charset: Charset::default(),
+            colors: true,
+        }
+    }
+}
+
+pub struct TermExporter {
+    width: usize,
+    height: usize,
+    pixels: Vec<Option<Color>>,
+    style: Vec<StylePosition>,
+}
+
+impl TermExporter {
+    fn new(width: usize, height: usize) -> Self {
+        TermExporter {
+            width,
+            height,
+            pixels: vec![None; width * height],
+            style: vec![],
+        }
+    }
+
+    fn paint(&mut self, x: usize, y: usize, color: Color) {
+        if x < self.width && y < self.height && color.rgba().3 >= 128 {
+            self.pixels[y * self.width + x] = Some(color);
+        }
+    }
+
+    /// Rows and columns of pixels whose center may be in a given area
+    fn pixel_range(&self, min: f32, max: f32, size: usize) -> std::ops::Range<usize> {
+        let start = (min - 0.5).ceil().max(0.) as usize;
+        let end = ((max - 0.5).floor() + 1.).clamp(0., size as f32) as usize;
+        start..end.max(start)
+    }
+
+    /// Scanline fill, with `fill_rule`, sampling the center of each pixel
+    fn fill_polygon(&mut self, points: &[Point2<f32>], fill_rule: FillRule, color: Color) {
+        let (min_y, max_y) = points.iter().fold((f32::MAX, f32::MIN), |(min, max), p| {
+            (min.min(p.y), max.max(p.y))
+        });
+
+        for y in self.pixel_range(min_y, max_y, self.height) {
+            let cy = y as f32 + 0.5;
+
+            let mut crossings = points
+                .windows(2)
+                .filter_map(|w| {
+                    let (a, b) = (w[0], w[1]);
+                    if (a.y <= cy) != (b.y <= cy) {
+                        let x = a.x + (cy - a.y) / (b.y - a.y) * (b.x - a.x);
+                        Some((x, if b.y > a.y { 1 } else { -1 }))
+                    } else {
+                        None
+                    }
+                })
+                .collect::<Vec<_>>();
+            crossings.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+
+            let mut winding = 0;
+            for span in crossings.windows(2) {
+                winding += span[0].1;
+                if !fill_rule.is_inside(winding) {
+                    continue;
+                }
+                for x in self.pixel_range(span[0].0, span[1].0, self.width) {
+                    self.paint(x, y, color);
+                }
+            }
+        }
+    }
+
+    /// Paint pixels whose center is within half the width of the polyline
+    fn stroke_polyline(&mut self, points: &[Point2<f32>], width: f32, color: Color) {
+        // Always visible, even if thinner than a pixel
+        let half_width = (width / 2.).max(0.5);
+
+        for w in points.windows(2) {
+            let (a, b) = (w[0], w[1]);
+            let ab = b - a;
+            let length_squared = ab.norm_squared();
+
+            let xs = self.pixel_range(
+                a.x.min(b.x) - half_width,
+                a.x.max(b.x) + half_width,
+                self.width,
+            );
+            let ys = self.pixel_range(
+                a.y.min(b.y) - half_width,
+                a.y.max(b.y) + half_width,
+                self.height,
+            );
+
+            for y in ys {
+                for x in xs.clone() {
+                    let p = Point2::new(x as f32 + 0.5, y as f32 + 0.5);
+                    let t = if length_squared > 0. {
+                        ((p - a).dot(&ab) / length_squared).clamp(0., 1.)
+                    } else {
+                        0.
+                    };
+
+                    if (p - (a + ab * t)).magnitude() <= half_width {
+                        self.paint(x, y, color);
+                    }
+                }
+            }
+        }
+    }
+
+    fn pixel(&self, x: usize, y: usize) -> Option<Color> {
+        if x < self.width && y < self.height {
+            self.pixels[y * self.width + x]
+        } else {
+            None
+        }
+    }
+
+    fn finish(self, charset: Charset, colors: bool) -> Result<String, TermError> {
+        let (cell_width, cell_height) = charset.cell_size();
+        let columns = self.width / cell_width;
+        let rows = self.height / cell_height;
+
+        let mut acc = String::new();
+        for row in 0..rows {
+            let mut current = (None, None);
+
+            for column in 0..columns {
+                let (x, y) = (column * cell_width, row * cell_height);
+
+                let (c, foreground, background) = match charset {
+                    Charset::Braille => {
+                        // Dots are numbered column by column, the last row coming last
+                        const DOTS: [(usize, usize, u32); 8] = [
+                            (0, 0, 0x01),
+                            (0, 1, 0x02),
+                            (0, 2, 0x04),
+                            (1, 0, 0x08),
+                            (1, 1, 0x10),
+                            (1, 2, 0x20),
+                            (0, 3, 0x40),
+                            (1, 3, 0x80),
+                        ];
+
+                        let mut bits = 0;
+                        let mut counts: Vec<(Color, usize)> = vec![];
+                        for (dx, dy, bit) in DOTS {
+                            if let Some(color) = self.pixel(x + dx, y + dy) {
+                                bits |= bit;
+                                match counts.iter_mut().find(|(c, _)| *c == color) {
+                                    Some((_, count)) => *count += 1,
+                                    None => counts.push((color, 1)),
+                                }
+                            }
+                        }
+
+                        let color = counts
+                            .into_iter()
+                            .max_by_key(|(_, count)| *count)
+                            .map(|(c, _)| c);
+
+                        match char::from_u32(0x2800 + bits) {
+                            Some(c) if bits != 0 => (c, color, None),
+                            _ => (' ', None, None),
+                        }
+                    }
+                    Charset::HalfBlock => match (self.pixel(x, y), self.pixel(x, y + 1)) {
+                        (None, None) => (' ', None, None),
+                        (Some(top), None) => ('▀', Some(top), None),
+                        (None, Some(bottom)) => ('▄', Some(bottom), None),
+                        (Some(top), Some(bottom)) if top == bottom || !colors => {
+                            ('█', Some(top), None)
+                        }
+                        (Some(top), Some(bottom)) => ('▀', Some(top), Some(bottom)),
+                    },
+                };
+
+                if colors && (foreground, background) != current && c != ' ' {
+                    acc.push_str("\x1b[0m");
+                    if let Some(color) = foreground {
+                        let (r, g, b) = color.as_rgb();
+                        write!(acc, "\x1b[38;2;{r};{g};{b}m")?;
+                    }
+                    if let Some(color) = background {
+                        let (r, g, b) = color.as_rgb();
+                        write!(acc, "\x1b[48;2;{r};{g};{b}m")?;
+                    }
+                    current = (foreground, background);
+                } else if colors && c == ' ' && current.1.is_some() {
+                    acc.push_str("\x1b[0m");
+                    current = (None, None);
+                }
+
+                acc.push(c);
+            }
+
+            if colors && current != (None, None) {
+                acc.push_str("\x1b[0m");
+            }
+            acc.push('\n');
+        }
+
+        Ok(acc)
+    }
+}
+
+impl Exporter for TermExporter {
+    type Error = TermError;
+    const CAN_EXPORT_ELLIPSE: bool = false;
+    const BACKEND: Option<&'static str> = Some("term");
+
+    fn start_style(&mut self, style: StylePosition) -> Result<(), Self::Error> {
+        self.style.push(style);
+        Ok(())
+    }
+
+    fn end_style(&mut self) -> Result<(), Self::Error> {
+        self.style.pop();
+        Ok(())
+    }
+
+    fn export_image(
+        &mut self,
+        ImagePosition {
+            top_left,
+            top_right,
+            bottom_left,
+            bottom_right,
+            image,
+            ..
+        }: ImagePosition,
+    ) -> Result<(), Self::Error> {
+        let (image_width, image_height) = image.dimensions();
+        if image_width == 0 || image_height == 0 {
+            return Ok(());
+        }
+
+        let u = top_right - top_left;
+        let v = bottom_left - top_left;
+        let determinant = u.x * v.y - u.y * v.x;
+        if determinant.abs() < f32::EPSILON {
+            return Ok(());
+        }
+
+        let corners = [top_left, top_right, bottom_right, bottom_left];
+        let (min, max) = corners.iter().fold(
+            (
+                Point2::new(f32::MAX, f32::MAX),
+                Point2::new(f32::MIN, f32::MIN),
+            ),
+            |(min, max), p| (min.inf(p), max.sup(p)),
+        );
+
+        for y in self.pixel_range(min.y, max.y, self.height) {
+            for x in self.pixel_range(min.x, max.x, self.width) {
+                // Position of the pixel center in the image, between 0 and 1
+                let p = Point2::new(x as f32 + 0.5, y as f32 + 0.5) - top_left;
+                let s = (p.x * v.y - p.y * v.x) / determinant;
+                let t = (u.x * p.y - u.y * p.x) / determinant;
+
+                if (0. ..1.).contains(&s) && (0. ..1.).contains(&t) {
+                    let px = ((s * image_width as f32) as u32).min(image_width - 1);
+                    let py = ((t * image_height as f32) as u32).min(image_height - 1);
+                    let [r, g, b, a] = image.get_pixel(px, py).0;
+
+                    self.paint(x, y, rgba(r, g, b, a));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn export_curve(&mut self, curve: CurvePosition) -> Result<(), Self::Error> {
+        let style = inherited_style(&self.style);
+        // Pixels are the unit, a fourth of a pixel is invisible
+        let points = curve.flatten(0.25);
+
+        if let Some(color) = style.fill.map(|fill| fill.color()) {
+            let mut polygon = points.clone();
+            if let (Some(first), Some(last)) = (polygon.first().copied(), polygon.last()) {
+                if first != *last {
+                    polygon.push(first);
+                }
+            }
+            self.fill_polygon(&polygon, curve.fill_rule, color);
+        }
+
+        if let Some(
+            Stroke::Full { color, width, .. }
+            | Stroke::Dashed { color, width, .. }
+            | Stroke::DashPattern { color, width, .. },
+        ) = style.stroke
+        {
+            self.stroke_polyline(&points, width, color);
+        }
+
+        Ok(())
+    }
+
+    /// Texts are unreadable at this resolution, they are skipped
+    fn export_text(&mut self, _text: TextPosition) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+/// Preview a dessin, scaled to fit the [`TermOptions::columns`] and [`TermOptions::rows`] while keeping its aspect ratio.
+///
+/// Each line ends with a new line, and resets the colors if [`TermOptions::colors`] is set.
+///
+/// ```
+/// # use dessin::prelude::*;
+/// use dessin_term::{to_string_with_options, Charset, TermOptions, MAX_DEDUCED_ROWS_PER_COLUMN};
+///
+/// let options = TermOptions {
+/// 	columns: 10,
+/// 	charset: Charset::HalfBlock,
+/// 	colors: false,
+/// 	..Default::default()
+/// };
+///
+/// let square = dessin2!(Rectangle!(fill = Color::RED, width = 10., height = 10.) > ());
+/// let preview = to_string_with_options(&square, options.clone()).unwrap();
+/// // Characters are twice as high as they are wide
+/// assert_eq!(preview.lines().count(), 5);
+///
+/// // The rows of very thin dessins are capped
+/// let pole = dessin2!(Rectangle!(fill = Color::RED, width = 0.001, height = 100.) > ());
+/// let preview = to_string_with_options(&pole, options).unwrap();
+/// assert_eq!(preview.lines().count(), 10 * MAX_DEDUCED_ROWS_PER_COLUMN);
+/// ```
+pub fn to_string_with_options(shape: &Shape, options: TermOptions) -> Result<String, TermError> {
+    let (cell_width, cell_height) = options.charset.cell_size();
+    let bb = shape.local_bounding_box().straigthen();
+
+    let width = options.columns * cell_width;
+    let height = match options.rows {
+        Some(rows) => rows * cell_height,
+        None if bb.width() > 0. => {
+            let rows = (width as f32 * bb.height() / bb.width() / cell_height as f32).ceil();
+            let max_rows = options.columns * MAX_DEDUCED_ROWS_PER_COLUMN;
+            (rows.max(1.) as usize).min(max_rows.max(1)) * cell_height
+        }
+        None => cell_height,
+    };
+
+    // Pixels are square, with the Y axis pointing down
+    let scale = (width as f32 / bb.width()).min(height as f32 / bb.height());
+    let scale = if scale.is_finite() { scale } else { 1. };
+    let parent_transform = nalgebra::convert::<_, Transform2<f32>>(Translation2::new(
+        (width as f32 - bb.width() * scale) / 2.,
+        (height as f32 - bb.height() * scale) / 2.,
+    )) * nalgebra::convert::<_, Transform2<f32>>(Scale2::new(scale, -scale))
+        * nalgebra::convert::<_, Transform2<f32>>(Translation2::new(-bb.left(), -bb.top()));
+
+    let mut exporter = TermExporter::new(width, height);
+    shape.write_into_exporter(&mut exporter, &parent_transform)?;
+
+    exporter.finish(options.charset, options.colors)
+}
+
+/// Preview a dessin with the default [`TermOptions`]: 80 colored columns of braille characters
+pub fn to_string(shape: &Shape) -> Result<String, TermError> {
+    to_string_with_options(shape, TermOptions::default())
+}
@@ -0,0 +1,249 @@
+//! Shapes built once, and reused as long as their key doesn't change.
+//!
+//! Costly components, such as a detailed map or a generated pattern, rarely change between two frames
+//! of an animation or two reloads of a scene.
+//! A [`Cached`] shape only calls its builder the first time its key is seen, and clones the built shape afterwards.
+//!
+//! Each thread has its own cache, which grows until it is [cleared][clear].
+//!
+//! ```
+//! # use dessin::prelude::*;
+//! use dessin::cache::{self, Cached};
+//! use std::cell::Cell;
+//!
+//! let builds = &Cell::new(0);
+//! let terrain = |seed: u64| {
+//! 	Cached::new(("terrain", seed), move || {
+//! 		builds.set(builds.get() + 1);
+//! 		dessin2!(Circle!(fill = Color::GREEN, radius = seed as f32) > ())
+//! 	})
+//! };
+//!
+//! for frame in 0..10 {
+//! 	let _scene = dessin2!([{ terrain(3) }, Circle(radius = 1., translate = [frame as f32, 0.])]);
+//! }
+//! assert_eq!(builds.get(), 1);
+//!
+//! // Translated like any shape
+//! let moved = Shape::from(terrain(3).with_translate([10., 0.]));
+//! assert!((moved.local_bounding_box().center().x - 10.).abs() < 10e-5);
+//!
+//! // Built again for another key
+//! let _ = Shape::from(terrain(4));
+//! assert_eq!(builds.get(), 2);
+//!
+//! cache::clear();
+//! ```
+
+use crate::prelude::*;
+use nalgebra::Transform2;
+use std::{
+    any::{Any, TypeId},
+    cell::RefCell,
+    collections::{hash_map::DefaultHasher, HashMap},
+    hash::{Hash, Hasher},
+};
+
+/// Shapes of the keys hashing alike, each with its key
+type Bucket = Vec<(Box<dyn Any>, Shape)>;
+
+thread_local! {
+    static CACHE: RefCell<HashMap<u64, Bucket>> = RefCell::new(HashMap::new());
+}
+
+/// Keys of different types never collide, even if they hash alike
+fn hash_of<K: Hash + 'static>(key: &K) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    TypeId::of::<K>().hash(&mut hasher);
+    key.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Position of `key` in `bucket`, compared to the keys of the same type
+fn position_in<K: Eq + 'static>(bucket: &Bucket, key: &K) -> Option<usize> {
+    bucket
+        .iter()
+        .position(|(cached, _)| cached.downcast_ref::<K>() == Some(key))
+}
+
+/// Shape built by `build` the first time `key` is seen, and cloned from the cache afterwards.
+///
+/// The key must hold every parameter the shape depends on, as the builder isn't called again while it is cached.
+/// Keys are compared, not only hashed, so two keys hashing alike get their own shapes.
+#[derive(Debug, Clone)]
+pub struct Cached<K, F> {
+    key: K,
+    build: F,
+    local_transform: Transform2<f32>,
+}
+impl<K, F> Cached<K, F>
+where
+    K: Hash + Eq + 'static,
+    F: FnOnce() -> Shape,
+{
+    /// Shape of `key`, built by `build` if not cached yet
+    #[inline]
+    pub fn new(key: K, build: F) -> Self {
+        Cached {
+            key,
+            build,
+            local_transform: Transform2::identity(),
+        }
+    }
+}
+
+impl<K, F> ShapeOp for Cached<K, F> {
+    #[inline]
+    fn transform(&mut self, transform_matrix: Transform2<f32>) -> &mut Self {
+        self.local_transform = transform_matrix * self.local_transform;
+        self
+    }
+
+    #[inline]
+    fn local_transform(&self) -> &Transform2<f32> {
+        &self.local_transform
+    }
+}
+
+impl<K, F> From<Cached<K, F>> for Shape
+where
+    K: Hash + Eq + 'static,
+    F: FnOnce() -> Shape,
+{
+    fn from(
+        Cached {
+            key,
+            build,
+            local_transform,
+        }: Cached<K, F>,
+    ) -> Self {
+        let hash = hash_of(&key);
+
+        let cached = CACHE.with(|cache| {
+            let cache = cache.borrow();
+            let bucket = cache.get(&hash)?;
+            position_in(bucket, &key).map(|idx| bucket[idx].1.clone())
+        });
+        let shape = match cached {
+            Some(shape) => shape,
+            None => {
+                // Not borrowed while building, so cached shapes can be nested
+                let shape = build();
+                CACHE.with(|cache| {
+                    let mut cache = cache.borrow_mut();
+                    cache
+                        .entry(hash)
+                        .or_default()
+                        .push((Box::new(key), shape.clone()));
+                });
+                shape
+            }
+        };
+
+        if local_transform == Transform2::identity() {
+            return shape;
+        }
+
+        Shape::Group(Group {
+            local_transform,
+            shapes: vec![shape],
+            metadata: vec![],
+        })
+    }
+}
+
+/// Remove the shape of `key` from the cache of this thread, returning whether it was cached
+pub fn invalidate<K: Hash + Eq + 'static>(key: &K) -> bool {
+    let hash = hash_of(key);
+    CACHE.with(|cache| {
+        let mut cache = cache.borrow_mut();
+        let Some(bucket) = cache.get_mut(&hash) else {
+            return false;
+        };
+        let Some(idx) = position_in(bucket, key) else {
+            return false;
+        };
+        bucket.remove(idx);
+        if bucket.is_empty() {
+            cache.remove(&hash);
+        }
+        true
+    })
+}
+
+/// Remove every shape from the cache of this thread
+pub fn clear() {
+    CACHE.with(|cache| cache.borrow_mut().clear());
+}
+
+/// Number of shapes in the cache of this thread
+pub fn len() -> usize {
+    CACHE.with(|cache| cache.borrow().values().map(Vec::len).sum())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    #[test]
+    fn nested_and_invalidated() {
+        let builds = Cell::new(0);
+        let dot = || {
+            Cached::new("tests::dot", || {
+                builds.set(builds.get() + 1);
+                dessin2!(Circle(radius = 1.) > ())
+            })
+        };
+        let dots = || {
+            Cached::new(("tests::dots", 2), || {
+                builds.set(builds.get() + 1);
+                dessin2!([{ dot() }, { dot().with_translate([3., 0.]) }])
+            })
+        };
+
+        let _ = Shape::from(dots());
+        let _ = Shape::from(dots());
+        assert_eq!(builds.get(), 2);
+
+        assert!(invalidate(&("tests::dots", 2)));
+        assert!(!invalidate(&("tests::dots", 2)));
+        let shape = Shape::from(dots());
+        assert_eq!(builds.get(), 3);
+        assert!((shape.local_bounding_box().straigthen().width() - 5.).abs() < 10e-5);
+
+        // Same hash, other type
+        assert!(!invalidate(&("tests::dots", 2_u8)));
+
+        clear();
+        assert_eq!(len(), 0);
+    }
+
+    #[test]
+    fn keys_hashing_alike() {
+        /// Every key has the same hash
+        #[derive(PartialEq, Eq)]
+        struct Key(u32);
+        impl Hash for Key {
+            fn hash<H: Hasher>(&self, _: &mut H) {}
+        }
+
+        let radius = |key: u32| {
+            let shape = Shape::from(Cached::new(Key(key), move || {
+                dessin2!(Circle(radius = key as f32) > ())
+            }));
+            shape.local_bounding_box().straigthen().width() / 2.
+        };
+
+        assert!((radius(1) - 1.).abs() < 10e-5);
+        assert!((radius(2) - 2.).abs() < 10e-5);
+        assert!((radius(1) - 1.).abs() < 10e-5);
+        assert_eq!(len(), 2);
+
+        assert!(invalidate(&Key(1)));
+        assert!(!invalidate(&Key(1)));
+        assert_eq!(len(), 1);
+
+        clear();
+    }
+}
@@ -0,0 +1,159 @@
+//! Color profile of the colors of a dessin.
+//!
+//! Colors of a dessin are sRGB, as in CSS.
+//! Formats leaving colors untagged, such as PNG or the `DeviceRGB` of PDF, let each viewer guess their color space.
+//! Calibrated viewers guess differently, tagging exports with [`srgb`] makes them all agree.
+
+/// Entries of the table of the tone response curve
+const TRC_ENTRIES: usize = 1024;
+
+/// Name of the sRGB color space, as registered by the ICC
+pub const SRGB_NAME: &str = "sRGB IEC61966-2.1";
+
+/// ICC profile (version 2.1) of the sRGB color space, the color space of every [`Color`][crate::style::Color].
+///
+/// ```
+/// let profile = dessin::color_profile::srgb();
+///
+/// assert_eq!(&profile[36..40], b"acsp");
+/// assert_eq!(&profile[16..20], b"RGB ");
+/// ```
+pub fn srgb() -> Vec<u8> {
+    let tags = [
+        (*b"desc", description(SRGB_NAME)),
+        (*b"cprt", text("No copyright, use freely")),
+        (*b"wtpt", xyz(0.9505, 1., 1.089)),
+        // Primaries adapted to the D50 illuminant of the profile connection space
+        (*b"rXYZ", xyz(0.436_074_7, 0.222_504_5, 0.013_932_2)),
+        (*b"gXYZ", xyz(0.385_064_9, 0.716_878_6, 0.097_104_5)),
+        (*b"bXYZ", xyz(0.143_080_4, 0.060_616_9, 0.714_173_3)),
+        (*b"rTRC", srgb_curve()),
+        (*b"gTRC", srgb_curve()),
+        (*b"bTRC", srgb_curve()),
+    ];
+
+    let table_size = 4 + 12 * tags.len();
+    let mut data: Vec<u8> = vec![];
+    let mut table = (tags.len() as u32).to_be_bytes().to_vec();
+    for (signature, tag) in &tags {
+        table.extend(signature);
+        table.extend(((128 + table_size + data.len()) as u32).to_be_bytes());
+        table.extend((tag.len() as u32).to_be_bytes());
+
+        data.extend(tag);
+        // Tags start on 4 bytes boundaries
+        data.resize(data.len().next_multiple_of(4), 0);
+    }
+
+    let size = 128 + table_size + data.len();
+    let mut profile = Vec::with_capacity(size);
+    profile.extend((size as u32).to_be_bytes());
+    profile.extend([0; 4]); // preferred CMM
+    profile.extend([2, 0x10, 0, 0]); // version 2.1
+    profile.extend(b"mntrRGB XYZ ");
+    for date in [2024_u16, 1, 1, 0, 0, 0] {
+        profile.extend(date.to_be_bytes());
+    }
+    profile.extend(b"acsp");
+    profile.extend([0; 24]); // platform, flags, device manufacturer, model and attributes
+    profile.extend([0; 4]); // perceptual rendering intent
+    profile.extend(s15_fixed16(0.9642));
+    profile.extend(s15_fixed16(1.));
+    profile.extend(s15_fixed16(0.8249));
+    profile.resize(128, 0);
+    profile.extend(table);
+    profile.extend(data);
+
+    profile
+}
+
+/// Fraction of the light of an sRGB component, both in [0, 1]
+fn srgb_to_linear(component: f32) -> f32 {
+    if component <= 0.04045 {
+        component / 12.92
+    } else {
+        ((component + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// Signed fixed point number, with 16 fractional bits
+fn s15_fixed16(value: f32) -> [u8; 4] {
+    ((value * 65536.).round() as i32).to_be_bytes()
+}
+
+fn xyz(x: f32, y: f32, z: f32) -> Vec<u8> {
+    [
+        *b"XYZ ",
+        [0; 4],
+        s15_fixed16(x),
+        s15_fixed16(y),
+        s15_fixed16(z),
+    ]
+    .concat()
+}
+
+fn text(text: &str) -> Vec<u8> {
+    [b"text", &[0; 4][..], text.as_bytes(), &[0]].concat()
+}
+
+fn description(description: &str) -> Vec<u8> {
+    let mut tag = b"desc".to_vec();
+    tag.extend([0; 4]);
+    tag.extend((description.len() as u32 + 1).to_be_bytes());
+    tag.extend(description.as_bytes());
+    tag.push(0);
+    // Neither Unicode nor ScriptCode descriptions
+    tag.extend([0; 8]);
+    tag.extend([0; 3]);
+    tag.extend([0; 67]);
+    tag
+}
+
+fn srgb_curve() -> Vec<u8> {
+    let mut tag = b"curv".to_vec();
+    tag.extend([0; 4]);
+    tag.extend((TRC_ENTRIES as u32).to_be_bytes());
+    for entry in 0..TRC_ENTRIES {
+        let component = entry as f32 / (TRC_ENTRIES - 1) as f32;
+        let light = (srgb_to_linear(component) * u16::MAX as f32).round() as u16;
+        tag.extend(light.to_be_bytes());
+    }
+    tag
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn u32_at(profile: &[u8], offset: usize) -> usize {
+        u32::from_be_bytes(profile[offset..offset + 4].try_into().unwrap()) as usize
+    }
+
+    #[test]
+    fn tags_are_in_the_profile() {
+        let profile = srgb();
+        assert_eq!(u32_at(&profile, 0), profile.len());
+
+        let count = u32_at(&profile, 128);
+        assert_eq!(count, 9);
+        for tag in 0..count {
+            let entry = 132 + 12 * tag;
+            let (offset, size) = (u32_at(&profile, entry + 4), u32_at(&profile, entry + 8));
+
+            assert_eq!(offset % 4, 0);
+            assert!(offset + size <= profile.len());
+        }
+    }
+
+    #[test]
+    fn curve_of_srgb() {
+        let curve = srgb_curve();
+        let entry = |idx: usize| u16::from_be_bytes([curve[12 + 2 * idx], curve[13 + 2 * idx]]);
+
+        assert_eq!(entry(0), 0);
+        assert_eq!(entry(TRC_ENTRIES - 1), u16::MAX);
+        // Mid gray reflects about a fifth of the light
+        let mid = entry(TRC_ENTRIES / 2) as f32 / u16::MAX as f32;
+        assert!((0.2..0.22).contains(&mid));
+    }
+}
@@ -10,15 +10,30 @@ macro_rules! auto_import {
 auto_import! {
     anchor,
     arc,
+    artboard,
     circle,
+    clip,
     fit,
+    gradient_stroke,
+    hyphenation,
+    flowchart,
+    isometric,
     layout,
+    leader,
     line,
+    mask,
+    packing,
     padding,
+    pagination,
     polygone,
+    print_marks,
     rectangle,
+    ribbon_text,
+    rich_text_on_curve,
     textbox,
+    tiling,
     thick_arc,
     triangle,
+    viewport,
     diamond,
 }
@@ -38,6 +38,64 @@ impl<T> Anchor<T> {
     }
 }
 
+/// A corner, the middle of an edge, or the center of a box.
+///
+/// Converts to the [`anchor`][Anchor::anchor] placing this point of the shape on the origin.
+///
+/// ```
+/// # use dessin::prelude::*;
+/// // Top left corner on the origin
+/// let image = dessin2!(Anchor::<Image>(anchor = BoxAnchor::TopLeft, scale = [4., 2.]) > ());
+///
+/// let bb = image.local_bounding_box().straigthen();
+/// assert!(bb.top_left().coords.magnitude() < 10e-5);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum BoxAnchor {
+    /// Top left corner
+    TopLeft,
+    /// Middle of the top edge
+    Top,
+    /// Top right corner
+    TopRight,
+    /// Middle of the left edge
+    Left,
+    /// Center
+    #[default]
+    Center,
+    /// Middle of the right edge
+    Right,
+    /// Bottom left corner
+    BottomLeft,
+    /// Middle of the bottom edge
+    Bottom,
+    /// Bottom right corner
+    BottomRight,
+}
+impl BoxAnchor {
+    /// Position of this point on a box going from -1 to 1 on both axis
+    pub fn direction(self) -> Vector2<f32> {
+        let x = match self {
+            BoxAnchor::TopLeft | BoxAnchor::Left | BoxAnchor::BottomLeft => -1.,
+            BoxAnchor::Top | BoxAnchor::Center | BoxAnchor::Bottom => 0.,
+            BoxAnchor::TopRight | BoxAnchor::Right | BoxAnchor::BottomRight => 1.,
+        };
+        let y = match self {
+            BoxAnchor::TopLeft | BoxAnchor::Top | BoxAnchor::TopRight => 1.,
+            BoxAnchor::Left | BoxAnchor::Center | BoxAnchor::Right => 0.,
+            BoxAnchor::BottomLeft | BoxAnchor::Bottom | BoxAnchor::BottomRight => -1.,
+        };
+
+        Vector2::new(x, y)
+    }
+}
+impl From<BoxAnchor> for Vector2<f32> {
+    #[inline]
+    fn from(anchor: BoxAnchor) -> Self {
+        anchor.direction()
+    }
+}
+
 impl<T> Deref for Anchor<T> {
     type Target = T;
 
@@ -1,17 +1,28 @@
 use crate::prelude::*;
 use nalgebra::{Point2, Rotation2, Scale2, Transform2};
-use std::f32::consts::{FRAC_PI_2, PI};
+use std::f32::consts::{FRAC_PI_2, TAU};
 
 /// Arc between a start_angle and a end_angle, with a radius.
+///
+/// The arc goes counterclockwise from its start angle to its end angle.
+/// Angles a whole number of turns apart make a full circle.
+///
+/// ```
+/// # use dessin::prelude::*;
+/// // Top half of a circle
+/// let arc = dessin2!(Arc(radius = 10., start_angle = Angle::ZERO, end_angle = Angle::degrees(180.)));
+/// ```
 #[derive(Default, Debug, Clone, PartialEq, Shape)]
 pub struct Arc {
     /// [`ShapeOp`]
     #[local_transform]
     pub local_transform: Transform2<f32>,
-    /// start angle in radian
-    pub start_angle: f32,
-    /// end angle in radian
-    pub end_angle: f32,
+    /// Start angle, in radians if given as a `f32`
+    #[shape(into)]
+    pub start_angle: Angle,
+    /// End angle, in radians if given as a `f32`
+    #[shape(into)]
+    pub end_angle: Angle,
 }
 impl Arc {
     /// Radius
@@ -37,9 +48,9 @@ impl From<Arc> for Curve {
             end_angle,
         }: Arc,
     ) -> Self {
-        let span = (end_angle + 2. * PI - start_angle) % (2. * PI);
+        let span = start_angle.span_to(end_angle).as_radians();
 
-        if (span - 2. * PI).abs() < 1e-6 {
+        if (span - TAU).abs() < 1e-6 {
             Curve::from(Circle { local_transform })
         } else {
             // From https://ecridge.com/bezier.pdf
@@ -48,7 +59,7 @@ impl From<Arc> for Curve {
 
             let mut arcs = vec![];
             for c in 0..(curves as u32) {
-                let start = (start_angle + (c as f32) * span_per_curve) % 360.;
+                let start = start_angle.as_radians() + (c as f32) * span_per_curve;
 
                 let alpha = span_per_curve / 2.;
 
@@ -85,6 +96,9 @@ impl From<Arc> for Curve {
                 closed: false,
                 keypoints: arcs,
                 local_transform,
+                start_marker: None,
+                end_marker: None,
+                fill_rule: FillRule::NonZero,
             }
             .into()
         }
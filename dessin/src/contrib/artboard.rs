@@ -0,0 +1,320 @@
+use crate::{
+    prelude::*,
+    validate::{ValidationOptions, ValidationWarning},
+};
+use nalgebra::Transform2;
+
+/// Metadata key letting the content of a [`Group`] cross the safe areas of an [`Artboard`], when set to `true`.
+///
+/// Meant for backgrounds and other full bleed content.
+pub const FULL_BLEED_METADATA_KEY: &str = "full_bleed";
+
+/// Area of an [`Artboard`] where content is expected to stay,
+/// such as the title safe area of a video frame or the margins of a printed page
+#[derive(Debug, Clone, PartialEq)]
+pub struct SafeArea {
+    /// Name of the area, reported by warnings
+    pub name: String,
+    /// Distance from the top side of the artboard
+    pub top: f32,
+    /// Distance from the right side of the artboard
+    pub right: f32,
+    /// Distance from the bottom side of the artboard
+    pub bottom: f32,
+    /// Distance from the left side of the artboard
+    pub left: f32,
+}
+impl SafeArea {
+    /// Safe area `margin` away from every side of the artboard
+    pub fn new<S: Into<String>>(name: S, margin: f32) -> Self {
+        SafeArea {
+            name: name.into(),
+            top: margin,
+            right: margin,
+            bottom: margin,
+            left: margin,
+        }
+    }
+
+    /// Centered safe area covering `ratio` of the width and height of an artboard of `width` x `height`.
+    ///
+    /// Video frames usually keep 93% for the action safe area, and 90% for the title safe area.
+    pub fn scaled<S: Into<String>>(name: S, ratio: f32, width: f32, height: f32) -> Self {
+        let margin_x = width * (1. - ratio) / 2.;
+        let margin_y = height * (1. - ratio) / 2.;
+        SafeArea {
+            name: name.into(),
+            top: margin_y,
+            right: margin_x,
+            bottom: margin_y,
+            left: margin_x,
+        }
+    }
+
+    /// Bounding box of the area, in an artboard of `width` x `height` centered around (0, 0)
+    pub fn bounding_box(&self, width: f32, height: f32) -> BoundingBox<Straight> {
+        BoundingBox::mins_maxs(
+            -width / 2. + self.left,
+            -height / 2. + self.bottom,
+            width / 2. - self.right,
+            height / 2. - self.top,
+        )
+    }
+}
+
+/// Drawing area of a fixed size, centered around (0, 0), with safe areas.
+///
+/// [`validate`][Artboard::validate] reports the content crossing a safe area,
+/// and the guides of each safe area can be drawn for review.
+///
+/// ```
+/// # use dessin::prelude::*;
+/// use dessin::validate::ValidationWarning;
+///
+/// let mut background = dessin2!(Rectangle!(fill = Color::BLUE, width = 1920., height = 1080.) > ());
+/// background.add_metadata((FULL_BLEED_METADATA_KEY, "true"));
+///
+/// let frame = dessin2!(Artboard(
+/// 	shape = dessin2!([
+/// 		{ background },
+/// 		Text!(fill = Color::WHITE, text = "Breaking news", font_size = 80., translate = [-900., -500.]),
+/// 	]),
+/// 	width = 1920.,
+/// 	height = 1080.,
+/// 	safe_area = SafeArea::scaled("Title safe", 0.9, 1920., 1080.),
+/// 	guides,
+/// ));
+///
+/// let warnings = frame.validate();
+/// assert!(matches!(warnings[..], [ValidationWarning::CrossesSafeArea { .. }]));
+/// ```
+#[derive(Debug, Clone, Shape)]
+pub struct Artboard {
+    /// Content of the artboard
+    #[shape(skip)]
+    pub shape: Shape,
+
+    /// Width of the artboard
+    pub width: f32,
+
+    /// Height of the artboard
+    pub height: f32,
+
+    /// Areas where content is expected to stay
+    #[shape(skip)]
+    pub safe_areas: Vec<SafeArea>,
+
+    /// Draw a dashed outline around each safe area
+    #[shape(bool)]
+    pub guides: bool,
+
+    /// Color of the guides
+    pub guide_color: Color,
+
+    /// Stroke width of the guides
+    pub guide_thickness: f32,
+}
+impl Default for Artboard {
+    fn default() -> Self {
+        Artboard {
+            shape: Shape::default(),
+            width: 210.,
+            height: 297.,
+            safe_areas: vec![],
+            guides: false,
+            guide_color: Color::CYAN,
+            guide_thickness: 0.2,
+        }
+    }
+}
+impl Artboard {
+    /// Content of the artboard
+    #[inline]
+    pub fn shape<T: Into<Shape>>(&mut self, shape: T) -> &mut Self {
+        self.shape = shape.into();
+        self
+    }
+    /// Content of the artboard
+    #[inline]
+    pub fn with_shape<T: Into<Shape>>(mut self, shape: T) -> Self {
+        self.shape(shape);
+        self
+    }
+
+    /// Add a safe area
+    #[inline]
+    pub fn safe_area(&mut self, safe_area: SafeArea) -> &mut Self {
+        self.safe_areas.push(safe_area);
+        self
+    }
+    /// Add a safe area
+    #[inline]
+    pub fn with_safe_area(mut self, safe_area: SafeArea) -> Self {
+        self.safe_area(safe_area);
+        self
+    }
+
+    /// Report the content crossing a safe area, and everything [`Shape::validate`] reports,
+    /// with the default [`ValidationOptions`]
+    #[inline]
+    pub fn validate(&self) -> Vec<ValidationWarning> {
+        self.validate_with_options(ValidationOptions::default())
+    }
+
+    /// Report the content crossing a safe area, and everything [`Shape::validate_with_options`] reports
+    pub fn validate_with_options(&self, options: ValidationOptions) -> Vec<ValidationWarning> {
+        let mut warnings = self.shape.validate_with_options(options);
+
+        let mut boxes = vec![];
+        collect_bounding_boxes(&self.shape, &Transform2::identity(), &mut boxes);
+
+        for safe_area in &self.safe_areas {
+            let area = safe_area.bounding_box(self.width, self.height);
+            warnings.extend(
+                boxes
+                    .iter()
+                    .filter(|bb| {
+                        bb.left() < area.left() - options.epsilon
+                            || bb.right() > area.right() + options.epsilon
+                            || bb.bottom() < area.bottom() - options.epsilon
+                            || bb.top() > area.top() + options.epsilon
+                    })
+                    .map(|bb| ValidationWarning::CrossesSafeArea {
+                        area: safe_area.name.clone(),
+                        bounding_box: *bb,
+                    }),
+            );
+        }
+
+        warnings
+    }
+}
+
+/// Global bounding box of every basic shape, full bleed groups excepted
+fn collect_bounding_boxes(
+    shape: &Shape,
+    parent_transform: &Transform2<f32>,
+    boxes: &mut Vec<BoundingBox<Straight>>,
+) {
+    match shape {
+        Shape::Group(Group {
+            local_transform,
+            shapes,
+            metadata,
+        }) => {
+            if metadata
+                .iter()
+                .any(|(key, value)| key == FULL_BLEED_METADATA_KEY && value == "true")
+            {
+                return;
+            }
+
+            let transform = parent_transform * local_transform;
+            for shape in shapes {
+                collect_bounding_boxes(shape, &transform, boxes);
+            }
+        }
+        Shape::Style { shape, .. } => collect_bounding_boxes(shape, parent_transform, boxes),
+        Shape::Dynamic {
+            local_transform,
+            shaper,
+        } => collect_bounding_boxes(&shaper(), &(parent_transform * local_transform), boxes),
+        Shape::LevelOfDetail(lod) => collect_bounding_boxes(
+            lod.pick(parent_transform),
+            &lod.global_transform(parent_transform),
+            boxes,
+        ),
+        leaf => {
+            let bb = leaf.global_bounding_box(parent_transform).straigthen();
+            // Non finite positions are already reported by the validation
+            if [bb.left(), bb.right(), bb.bottom(), bb.top()]
+                .iter()
+                .all(|v| v.is_finite())
+            {
+                boxes.push(bb);
+            }
+        }
+    }
+}
+
+impl From<Artboard> for Shape {
+    fn from(
+        Artboard {
+            shape,
+            width,
+            height,
+            safe_areas,
+            guides,
+            guide_color,
+            guide_thickness,
+        }: Artboard,
+    ) -> Self {
+        if !guides || safe_areas.is_empty() {
+            return shape;
+        }
+
+        let guides = Style::new(dessin2!(for safe_area in safe_areas {
+            let bb = safe_area.bounding_box(width, height);
+            dessin2!(Rectangle(
+                width = bb.width(),
+                height = bb.height(),
+                translate = [(bb.left() + bb.right()) / 2., (bb.bottom() + bb.top()) / 2.],
+            ))
+        }))
+        .with_stroke(Stroke::dashed(
+            guide_color,
+            guide_thickness,
+            10. * guide_thickness,
+            5. * guide_thickness,
+        ));
+
+        dessin2!([{ shape }, { guides }])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{prelude::*, validate::ValidationWarning};
+
+    #[test]
+    fn content_crossing_safe_area() {
+        let artboard = |translate: [f32; 2]| {
+            dessin2!(Artboard(
+                shape = dessin2!(Rectangle(width = 10., height = 10., translate = translate) > ()),
+                width = 100.,
+                height = 100.,
+                safe_area = SafeArea::new("Margin", 10.),
+            ))
+        };
+
+        assert_eq!(artboard([0., 0.]).validate(), vec![]);
+        assert_eq!(artboard([35., -35.]).validate(), vec![]);
+
+        let warnings = artboard([40., 0.]).validate();
+        assert!(matches!(
+            &warnings[..],
+            [ValidationWarning::CrossesSafeArea { area, .. }] if area == "Margin"
+        ));
+    }
+
+    #[test]
+    fn full_bleed() {
+        let mut background = dessin2!(Rectangle(width = 100., height = 100.) > ());
+        background.add_metadata((FULL_BLEED_METADATA_KEY, "true"));
+
+        let artboard = dessin2!(Artboard(
+            shape = background,
+            width = 100.,
+            height = 100.,
+            safe_area = SafeArea::scaled("Title safe", 0.9, 100., 100.),
+            guides,
+        ));
+
+        assert_eq!(artboard.validate(), vec![]);
+        // Background, and the guide of the safe area
+        assert!(matches!(
+            Shape::from(artboard),
+            Shape::Group(Group { shapes, .. }) if shapes.len() == 2
+        ));
+    }
+}
@@ -1,5 +1,5 @@
 use crate::{
-    prelude::{Ellipse, Shape, ShapeOp},
+    prelude::{Ellipse, FillRule, Shape, ShapeOp},
     shapes::{Bezier, Curve, Keypoint},
 };
 use nalgebra::{self as na, Point2, Rotation2, Scale2, Transform2};
@@ -60,6 +60,9 @@ impl From<Circle> for Curve {
             ],
             local_transform,
             closed: true,
+            start_marker: None,
+            end_marker: None,
+            fill_rule: FillRule::NonZero,
         }
     }
 }
@@ -0,0 +1,114 @@
+use crate::prelude::*;
+use nalgebra::Transform2;
+
+/// Metadata key marking a [`Group`] made by a [`Clip`]
+pub const CLIP_METADATA_KEY: &str = "clip";
+
+/// Shape only visible inside another one, such as an image cropped to a circular badge.
+///
+/// Exporters not supporting clipping draw the shape whole, and the clipping shape without fill nor stroke.
+/// The bounding box of a clip is the part of the bounding box of the shape inside the one of the clipping shape,
+/// so cropped drawings, photos and patterns are laid out by what remains visible.
+///
+/// ```
+/// # use dessin::prelude::*;
+/// let badge = dessin2!(Clip(
+/// 	shape = dessin2!(Rectangle!(fill = Color::RED, width = 40., height = 20.)),
+/// 	clipped_by = dessin2!(Circle(radius = 8.)),
+/// 	translate = [10., 0.],
+/// ));
+///
+/// let Shape::Group(group) = Shape::from(badge) else {
+/// 	unreachable!()
+/// };
+/// assert!(Clip::parts(&group).is_some());
+///
+/// let bb = Shape::Group(group).local_bounding_box().straigthen();
+/// assert_eq!((bb.width(), bb.height()), (16., 16.));
+/// ```
+#[derive(Default, Debug, Clone, Shape)]
+pub struct Clip {
+    /// [`ShapeOp`]
+    #[local_transform]
+    pub local_transform: Transform2<f32>,
+
+    /// Shape being clipped
+    #[shape(skip)]
+    pub shape: Shape,
+
+    /// Shape outside of which nothing is visible, its style is ignored
+    #[shape(skip)]
+    pub clipped_by: Shape,
+}
+impl Clip {
+    /// Shape being clipped
+    #[inline]
+    pub fn shape<T: Into<Shape>>(&mut self, shape: T) -> &mut Self {
+        self.shape = shape.into();
+        self
+    }
+    /// Shape being clipped
+    #[inline]
+    pub fn with_shape<T: Into<Shape>>(mut self, shape: T) -> Self {
+        self.shape(shape);
+        self
+    }
+
+    /// Shape outside of which nothing is visible
+    #[inline]
+    pub fn clipped_by<T: Into<Shape>>(&mut self, clipped_by: T) -> &mut Self {
+        self.clipped_by = clipped_by.into();
+        self
+    }
+    /// Shape outside of which nothing is visible
+    #[inline]
+    pub fn with_clipped_by<T: Into<Shape>>(mut self, clipped_by: T) -> Self {
+        self.clipped_by(clipped_by);
+        self
+    }
+
+    /// Shape being clipped and shape clipping it, if `group` was made by a [`Clip`]
+    pub fn parts(group: &Group) -> Option<(&Shape, &Shape)> {
+        if !group
+            .metadata
+            .iter()
+            .any(|(key, value)| key == CLIP_METADATA_KEY && value == "true")
+        {
+            return None;
+        }
+
+        match &group.shapes[..] {
+            [shape, Shape::Style {
+                shape: clipped_by, ..
+            }] => Some((shape, clipped_by)),
+            _ => None,
+        }
+    }
+}
+
+impl From<Clip> for Shape {
+    fn from(
+        Clip {
+            local_transform,
+            shape,
+            clipped_by,
+        }: Clip,
+    ) -> Self {
+        Shape::Group(Group {
+            local_transform,
+            shapes: vec![
+                shape,
+                Shape::Style {
+                    fill: None,
+                    stroke: None,
+                    stroke_alignment: StrokeAlignment::Center,
+                    filter: None,
+                    opacity: 1.,
+                    blend_mode: BlendMode::Normal,
+                    shape: Box::new(clipped_by),
+                },
+            ],
+            metadata: vec![(CLIP_METADATA_KEY.to_string(), "true".to_string())],
+        })
+    }
+}
@@ -0,0 +1,598 @@
+use crate::{font::FontRef, prelude::*};
+use nalgebra::{Point2, Transform2, Vector2};
+
+/// Box of a [`Flowchart`], once laid out
+#[derive(Debug, Clone, PartialEq)]
+pub struct FlowchartNode {
+    /// The text
+    pub label: String,
+    /// Center of the box
+    pub center: Point2<f32>,
+    /// Dimension on the x-axis
+    pub width: f32,
+    /// Dimension on the y-axis
+    pub height: f32,
+}
+
+/// Connector of a [`Flowchart`], once routed
+#[derive(Debug, Clone, PartialEq)]
+pub struct FlowchartEdge {
+    /// Index of the node the connector starts from
+    pub from: usize,
+    /// Index of the node the connector points to
+    pub to: usize,
+    /// Polyline of the connector, from the border of `from` to the border of `to`
+    pub points: Vec<Point2<f32>>,
+}
+
+/// Positions of the nodes and connectors of a [`Flowchart`], centered around (0, 0)
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct FlowchartLayout {
+    /// Nodes, in the order they were added
+    pub nodes: Vec<FlowchartNode>,
+    /// Connectors, in the order they were added
+    pub edges: Vec<FlowchartEdge>,
+}
+
+/// Boxes with labels, connected by arrows, laid out automatically from top to bottom.
+///
+/// Nodes are identified by their label, and created by the first edge using them.
+/// The layout is layered (Sugiyama-style):
+/// every node is placed one layer below its lowest predecessor,
+/// connectors spanning several layers are routed between the nodes of the layers they cross,
+/// and nodes are reordered within their layer to reduce crossings.
+/// Cycles are broken by drawing some connectors upward.
+///
+/// ```
+/// # use dessin::prelude::*;
+/// let flowchart = dessin2!(Flowchart(
+/// 	edge = ("Start", "Read input"),
+/// 	edge = ("Read input", "Valid?"),
+/// 	edge = ("Valid?", "Process"),
+/// 	edge = ("Valid?", "Read input"),
+/// 	edge = ("Process", "End"),
+/// ));
+///
+/// let layout = flowchart.layout();
+/// assert_eq!(layout.nodes.len(), 5);
+/// assert!(layout.nodes[0].center.y > layout.nodes[4].center.y);
+///
+/// let dessin: Shape = flowchart.into();
+/// ```
+#[derive(Debug, Clone, PartialEq, Shape)]
+pub struct Flowchart {
+    /// [`ShapeOp`]
+    #[local_transform]
+    pub local_transform: Transform2<f32>,
+
+    /// Labels of the nodes
+    #[shape(skip)]
+    pub nodes: Vec<String>,
+
+    /// Connectors, as indices in [`nodes`][Flowchart::nodes]
+    #[shape(skip)]
+    pub edges: Vec<(usize, usize)>,
+
+    /// Font size
+    pub font_size: f32,
+
+    /// Font weight
+    pub font_weight: FontWeight,
+
+    /// Font
+    #[shape(into_some)]
+    pub font: Option<FontRef>,
+
+    /// Space between the label and the border of its box
+    pub padding: f32,
+
+    /// Horizontal space between two boxes of the same layer
+    pub node_gap: f32,
+
+    /// Vertical space between two layers
+    pub layer_gap: f32,
+
+    /// Length of the arrowheads
+    pub arrow_length: f32,
+
+    /// Width of the base of the arrowheads
+    pub arrow_width: f32,
+
+    /// Color of boxes, connectors and labels
+    pub color: Color,
+
+    /// Stroke width of boxes and connectors
+    pub thickness: f32,
+}
+impl Default for Flowchart {
+    fn default() -> Self {
+        Flowchart {
+            local_transform: Default::default(),
+            nodes: vec![],
+            edges: vec![],
+            font_size: 5.,
+            font_weight: Default::default(),
+            font: None,
+            padding: 2.,
+            node_gap: 10.,
+            layer_gap: 10.,
+            arrow_length: 2.,
+            arrow_width: 1.,
+            color: Color::BLACK,
+            thickness: 0.2,
+        }
+    }
+}
+impl Flowchart {
+    /// Index of the node labelled `label`, created if needed
+    fn index_of(&mut self, label: String) -> usize {
+        match self.nodes.iter().position(|n| *n == label) {
+            Some(idx) => idx,
+            None => {
+                self.nodes.push(label);
+                self.nodes.len() - 1
+            }
+        }
+    }
+
+    /// Add a node without connectors, unless it already exists
+    #[inline]
+    pub fn node<S: Into<String>>(&mut self, label: S) -> &mut Self {
+        self.index_of(label.into());
+        self
+    }
+    /// Chained version of [`Flowchart::node`]
+    #[inline]
+    pub fn with_node<S: Into<String>>(mut self, label: S) -> Self {
+        self.node(label);
+        self
+    }
+
+    /// Connect two nodes, by label
+    #[inline]
+    pub fn edge<A: Into<String>, B: Into<String>>(&mut self, (from, to): (A, B)) -> &mut Self {
+        let from = self.index_of(from.into());
+        let to = self.index_of(to.into());
+        self.edges.push((from, to));
+        self
+    }
+    /// Chained version of [`Flowchart::edge`]
+    #[inline]
+    pub fn with_edge<A: Into<String>, B: Into<String>>(mut self, edge: (A, B)) -> Self {
+        self.edge(edge);
+        self
+    }
+
+    /// Iterator version of [`Flowchart::edge`]
+    #[inline]
+    pub fn extend_edges<A, B, I>(&mut self, edges: I) -> &mut Self
+    where
+        A: Into<String>,
+        B: Into<String>,
+        I: IntoIterator<Item = (A, B)>,
+    {
+        for edge in edges {
+            self.edge(edge);
+        }
+        self
+    }
+
+    fn node_size(&self, label: &str) -> (f32, f32) {
+        let text_width = if label.is_empty() {
+            0.
+        } else {
+            Text {
+                text: label.to_string(),
+                font_size: self.font_size,
+                font_weight: self.font_weight,
+                font: self.font.clone(),
+                ..Default::default()
+            }
+            .local_bounding_box()
+            .straigthen()
+            .width()
+        };
+
+        (
+            text_width + 2. * self.padding,
+            self.font_size + 2. * self.padding,
+        )
+    }
+
+    /// Position every node and route every connector
+    pub fn layout(&self) -> FlowchartLayout {
+        let node_count = self.nodes.len();
+        // Self loops can't be drawn between layers
+        let edges = self
+            .edges
+            .iter()
+            .copied()
+            .filter(|(from, to)| from != to && *from < node_count && *to < node_count)
+            .collect::<Vec<_>>();
+
+        // 1. Break cycles, by reversing the edges going back in a depth first search
+        let reversed = back_edges(node_count, &edges);
+        let acyclic = edges
+            .iter()
+            .zip(&reversed)
+            .map(|(&(from, to), &reversed)| if reversed { (to, from) } else { (from, to) })
+            .collect::<Vec<_>>();
+
+        // 2. Layers, by longest path from the sources
+        let mut layer_of = longest_path_layers(node_count, &acyclic);
+
+        // 3. Split edges spanning several layers with dummy vertices
+        let mut sizes = self
+            .nodes
+            .iter()
+            .map(|label| self.node_size(label))
+            .collect::<Vec<_>>();
+        let mut chains = Vec::with_capacity(acyclic.len());
+        let mut links = vec![];
+        for &(from, to) in &acyclic {
+            let mut chain = vec![from];
+            for layer in layer_of[from] + 1..layer_of[to] {
+                layer_of.push(layer);
+                sizes.push((0., 0.));
+                chain.push(layer_of.len() - 1);
+            }
+            chain.push(to);
+
+            links.extend(chain.windows(2).map(|w| (w[0], w[1])));
+            chains.push(chain);
+        }
+
+        let layer_count = layer_of.iter().max().map_or(0, |l| l + 1);
+        let mut layers = vec![vec![]; layer_count];
+        for (vertex, layer) in layer_of.iter().enumerate() {
+            layers[*layer].push(vertex);
+        }
+
+        // 4. Reduce crossings
+        order_layers(&mut layers, &links);
+
+        // 5. Coordinates, layers from top to bottom, each centered horizontally
+        let mut centers = vec![Point2::origin(); sizes.len()];
+        let mut top = 0.;
+        for layer in &layers {
+            let height = layer.iter().map(|v| sizes[*v].1).fold(0., f32::max);
+            let width = layer.iter().map(|v| sizes[*v].0).sum::<f32>()
+                + self.node_gap * layer.len().saturating_sub(1) as f32;
+
+            let mut left = -width / 2.;
+            for v in layer {
+                centers[*v] = Point2::new(left + sizes[*v].0 / 2., top - height / 2.);
+                left += sizes[*v].0 + self.node_gap;
+            }
+
+            top -= height + self.layer_gap;
+        }
+        let total_height = -(top + self.layer_gap).min(0.);
+        let recenter = Vector2::new(0., total_height / 2.);
+        for center in &mut centers {
+            *center += recenter;
+        }
+
+        // 6. Route connectors through their dummy vertices
+        let nodes = self
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(idx, label)| FlowchartNode {
+                label: label.clone(),
+                center: centers[idx],
+                width: sizes[idx].0,
+                height: sizes[idx].1,
+            })
+            .collect();
+
+        let edges = edges
+            .iter()
+            .zip(&reversed)
+            .zip(chains)
+            .map(|((&(from, to), &reversed), chain)| {
+                let first = chain[0];
+                let last = chain[chain.len() - 1];
+
+                let mut points = Vec::with_capacity(chain.len());
+                points.push(centers[first] - Vector2::new(0., sizes[first].1 / 2.));
+                points.extend(chain[1..chain.len() - 1].iter().map(|v| centers[*v]));
+                points.push(centers[last] + Vector2::new(0., sizes[last].1 / 2.));
+                if reversed {
+                    points.reverse();
+                }
+
+                FlowchartEdge { from, to, points }
+            })
+            .collect();
+
+        FlowchartLayout { nodes, edges }
+    }
+}
+
+/// Edges going back to an ancestor in a depth first search, which break cycles once reversed
+fn back_edges(node_count: usize, edges: &[(usize, usize)]) -> Vec<bool> {
+    #[derive(Clone, Copy, PartialEq)]
+    enum State {
+        New,
+        Visiting,
+        Done,
+    }
+
+    let mut outgoing = vec![vec![]; node_count];
+    for (idx, (from, to)) in edges.iter().enumerate() {
+        outgoing[*from].push((idx, *to));
+    }
+
+    let mut state = vec![State::New; node_count];
+    let mut reversed = vec![false; edges.len()];
+    for root in 0..node_count {
+        if state[root] != State::New {
+            continue;
+        }
+
+        // Explicit stack of (vertex, next outgoing edge), as graphs can be deep
+        state[root] = State::Visiting;
+        let mut stack = vec![(root, 0)];
+        while let Some((vertex, next)) = stack.last_mut() {
+            let Some(&(edge, to)) = outgoing[*vertex].get(*next) else {
+                state[*vertex] = State::Done;
+                stack.pop();
+                continue;
+            };
+            *next += 1;
+
+            match state[to] {
+                State::New => {
+                    state[to] = State::Visiting;
+                    stack.push((to, 0));
+                }
+                State::Visiting => reversed[edge] = true,
+                State::Done => {}
+            }
+        }
+    }
+
+    reversed
+}
+
+/// Layer of each node of an acyclic graph, one below its lowest predecessor
+fn longest_path_layers(node_count: usize, edges: &[(usize, usize)]) -> Vec<usize> {
+    let mut incoming = vec![0; node_count];
+    let mut outgoing = vec![vec![]; node_count];
+    for (from, to) in edges {
+        incoming[*to] += 1;
+        outgoing[*from].push(*to);
+    }
+
+    let mut layer_of = vec![0; node_count];
+    let mut ready = (0..node_count)
+        .filter(|v| incoming[*v] == 0)
+        .collect::<Vec<_>>();
+    while let Some(vertex) = ready.pop() {
+        for &to in &outgoing[vertex] {
+            layer_of[to] = layer_of[to].max(layer_of[vertex] + 1);
+            incoming[to] -= 1;
+            if incoming[to] == 0 {
+                ready.push(to);
+            }
+        }
+    }
+
+    layer_of
+}
+
+/// Reorder vertices in their layer by the barycenter of their neighbours, sweeping down then up
+fn order_layers(layers: &mut [Vec<usize>], links: &[(usize, usize)]) {
+    const SWEEPS: usize = 4;
+
+    let vertex_count = layers.iter().map(Vec::len).sum::<usize>();
+    let mut above = vec![vec![]; vertex_count];
+    let mut below = vec![vec![]; vertex_count];
+    for (from, to) in links {
+        below[*from].push(*to);
+        above[*to].push(*from);
+    }
+
+    let mut position = vec![0.; vertex_count];
+    for layer in layers.iter() {
+        for (idx, v) in layer.iter().enumerate() {
+            position[*v] = idx as f32;
+        }
+    }
+
+    let reorder = |layer: &mut Vec<usize>, neighbours: &[Vec<usize>], position: &mut Vec<f32>| {
+        let barycenter = |v: usize| {
+            let n = &neighbours[v];
+            if n.is_empty() {
+                position[v]
+            } else {
+                n.iter().map(|n| position[*n]).sum::<f32>() / n.len() as f32
+            }
+        };
+
+        let mut keyed = layer
+            .iter()
+            .map(|v| (barycenter(*v), *v))
+            .collect::<Vec<_>>();
+        keyed.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+
+        for (idx, (_, v)) in keyed.into_iter().enumerate() {
+            layer[idx] = v;
+            position[v] = idx as f32;
+        }
+    };
+
+    for _ in 0..SWEEPS {
+        for layer in layers.iter_mut().skip(1) {
+            reorder(layer, &above, &mut position);
+        }
+        for layer in layers.iter_mut().rev().skip(1) {
+            reorder(layer, &below, &mut position);
+        }
+    }
+}
+
+impl From<Flowchart> for Shape {
+    fn from(flowchart: Flowchart) -> Self {
+        let layout = flowchart.layout();
+        let Flowchart {
+            local_transform,
+            font_size,
+            font_weight,
+            font,
+            arrow_length,
+            arrow_width,
+            color,
+            thickness,
+            ..
+        } = flowchart;
+
+        let mut outlines = vec![];
+        let mut fills = vec![];
+        for node in layout.nodes {
+            outlines.push(
+                Rectangle::default()
+                    .with_width(node.width)
+                    .with_height(node.height)
+                    .with_translate(node.center - Point2::origin())
+                    .into(),
+            );
+            fills.push(
+                Text {
+                    local_transform: Default::default(),
+                    text: node.label,
+                    align: TextAlign::Center,
+                    vertical_align: TextVerticalAlign::Center,
+                    font_weight,
+                    on_curve: None,
+                    font_size,
+                    font: font.clone(),
+                }
+                .with_translate(node.center - Point2::origin())
+                .into(),
+            );
+        }
+
+        for FlowchartEdge { points, .. } in layout.edges {
+            let [.., before, end] = points[..] else {
+                continue;
+            };
+
+            let towards = before - end;
+            if towards.magnitude() > 0. {
+                let towards = towards.normalize();
+                let normal = Vector2::new(-towards.y, towards.x) * arrow_width / 2.;
+                let base = end + towards * arrow_length;
+
+                fills.push(Shape::Curve(Curve {
+                    local_transform: Default::default(),
+                    keypoints: vec![
+                        Keypoint::Point(end),
+                        Keypoint::Point(base + normal),
+                        Keypoint::Point(base - normal),
+                    ],
+                    closed: true,
+                    start_marker: None,
+                    end_marker: None,
+                    fill_rule: FillRule::NonZero,
+                }));
+            }
+
+            outlines.push(Shape::Curve(Curve {
+                local_transform: Default::default(),
+                keypoints: points.into_iter().map(Keypoint::Point).collect(),
+                closed: false,
+                start_marker: None,
+                end_marker: None,
+                fill_rule: FillRule::NonZero,
+            }));
+        }
+
+        let outlines = Style::new(Shape::Group(Group {
+            shapes: outlines,
+            ..Default::default()
+        }))
+        .with_stroke((color, thickness));
+        let fills = Style::new(Shape::Group(Group {
+            shapes: fills,
+            ..Default::default()
+        }))
+        .with_fill(color);
+
+        dessin2!([{ outlines }, { fills }] > (transform = local_transform))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    fn crossings(layout: &FlowchartLayout) -> usize {
+        let segments = layout
+            .edges
+            .iter()
+            .flat_map(|e| e.points.windows(2).map(|w| (w[0], w[1])))
+            .collect::<Vec<_>>();
+
+        let mut count = 0;
+        for (idx, (a, b)) in segments.iter().enumerate() {
+            for (c, d) in &segments[idx + 1..] {
+                let side =
+                    |p: &nalgebra::Point2<f32>,
+                     q: &nalgebra::Point2<f32>,
+                     r: &nalgebra::Point2<f32>| { (q - p).perp(&(r - p)) };
+                if side(a, b, c) * side(a, b, d) < 0. && side(c, d, a) * side(c, d, b) < 0. {
+                    count += 1;
+                }
+            }
+        }
+        count
+    }
+
+    #[test]
+    fn layers_follow_edges() {
+        let flowchart = dessin2!(Flowchart(
+            edge = ("A", "B"),
+            edge = ("B", "C"),
+            edge = ("A", "C"),
+        ));
+        let layout = flowchart.layout();
+
+        let y = |idx: usize| layout.nodes[idx].center.y;
+        assert!(y(0) > y(1));
+        assert!(y(1) > y(2));
+
+        // A to C goes around B, through a dummy vertex
+        assert_eq!(layout.edges[2].points.len(), 3);
+    }
+
+    #[test]
+    fn cycles_are_broken() {
+        let flowchart = dessin2!(Flowchart(
+            edge = ("A", "B"),
+            edge = ("B", "C"),
+            edge = ("C", "A"),
+        ));
+        let layout = flowchart.layout();
+
+        // The back edge still points at A, from below
+        let back = &layout.edges[2];
+        assert_eq!((back.from, back.to), (2, 0));
+        assert!(back.points[0].y < back.points[back.points.len() - 1].y);
+    }
+
+    #[test]
+    fn crossings_are_reduced() {
+        let flowchart = dessin2!(Flowchart(
+            edge = ("A", "D"),
+            edge = ("B", "C"),
+            edge = ("A", "C"),
+            edge = ("B", "E"),
+            edge = ("C", "F"),
+            edge = ("D", "G"),
+        ));
+
+        assert_eq!(crossings(&flowchart.layout()), 0);
+    }
+}
@@ -0,0 +1,253 @@
+use crate::prelude::*;
+use nalgebra::{Point2, Transform2};
+
+/// Stroke of a curve whose color changes along its length.
+///
+/// No backend draws such a gradient natively, so the curve is [flattened][CurvePosition::flatten]
+/// and cut in pieces no longer than [`step`][GradientStroke::step], each stroked with the color at its middle.
+/// Colors are interpolated between the [stops][GradientStroke::stop],
+/// placed by their offset along the curve, 0 being its start and 1 its end.
+///
+/// ```
+/// # use dessin::prelude::*;
+/// let flow = dessin2!(GradientStroke(
+/// 	curve = Arc::default().with_radius(10.).with_end_angle(Angle::HALF_TURN),
+/// 	stop = (0., Color::BLUE),
+/// 	stop = (0.5, Color::GREEN),
+/// 	stop = (1., Color::RED),
+/// 	width = 0.5,
+/// 	step = 0.5,
+/// ) > ());
+///
+/// let Shape::Group(Group { shapes, .. }) = flow else {
+/// 	unreachable!()
+/// };
+/// // Half a circle of radius 10, in pieces of 0.5
+/// assert_eq!(shapes.len(), 63);
+/// ```
+#[derive(Debug, Clone, Shape)]
+pub struct GradientStroke {
+    /// [`ShapeOp`]
+    #[local_transform]
+    pub local_transform: Transform2<f32>,
+
+    /// Stroked curve
+    #[shape(skip)]
+    pub curve: Curve,
+
+    /// Offset along the curve, between 0 and 1, and color of each stop of the gradient
+    #[shape(skip)]
+    pub stops: Vec<(f32, Color)>,
+
+    /// Width of the stroke
+    pub width: f32,
+
+    /// Longest length of a piece of a single color
+    pub step: f32,
+
+    /// Largest distance between the curve and its flattening
+    pub tolerance: f32,
+}
+impl Default for GradientStroke {
+    fn default() -> Self {
+        GradientStroke {
+            local_transform: Transform2::default(),
+            curve: Curve::default(),
+            stops: vec![],
+            width: 1.,
+            step: 1.,
+            tolerance: 0.01,
+        }
+    }
+}
+impl GradientStroke {
+    /// Stroked curve
+    #[inline]
+    pub fn curve<C: Into<Curve>>(&mut self, curve: C) -> &mut Self {
+        self.curve = curve.into();
+        self
+    }
+    /// Stroked curve
+    #[inline]
+    pub fn with_curve<C: Into<Curve>>(mut self, curve: C) -> Self {
+        self.curve(curve);
+        self
+    }
+
+    /// Add a stop of the gradient, at `offset` along the curve, between 0 and 1
+    #[inline]
+    pub fn stop(&mut self, (offset, color): (f32, Color)) -> &mut Self {
+        self.stops.push((offset, color));
+        self
+    }
+    /// Add a stop of the gradient, at `offset` along the curve, between 0 and 1
+    #[inline]
+    pub fn with_stop(mut self, stop: (f32, Color)) -> Self {
+        self.stop(stop);
+        self
+    }
+
+    /// Color of the gradient at `offset` along the curve, `None` without stops
+    pub fn color_at(&self, offset: f32) -> Option<Color> {
+        let mut stops = self.stops.clone();
+        stops.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+        color_at(&stops, offset)
+    }
+}
+
+/// Color at `offset` of stops sorted by offset
+fn color_at(stops: &[(f32, Color)], offset: f32) -> Option<Color> {
+    let (first, last) = (stops.first()?, stops.last()?);
+    if offset <= first.0 {
+        return Some(first.1);
+    }
+
+    Some(
+        stops
+            .windows(2)
+            .find(|stops| offset <= stops[1].0)
+            .map(|stops| {
+                let ((start, from), (end, to)) = (stops[0], stops[1]);
+                let span = end - start;
+                if span <= 0. {
+                    to
+                } else {
+                    from.mix(to, (offset - start) / span)
+                }
+            })
+            .unwrap_or(last.1),
+    )
+}
+
+impl From<GradientStroke> for Shape {
+    fn from(
+        GradientStroke {
+            local_transform,
+            curve,
+            mut stops,
+            width,
+            step,
+            tolerance,
+        }: GradientStroke,
+    ) -> Self {
+        stops.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+
+        let closed = curve.closed;
+        let mut points = curve.position(&Transform2::identity()).flatten(tolerance);
+        points.dedup();
+        if closed {
+            if let Some(&start) = points.first() {
+                points.push(start);
+            }
+        }
+
+        // Distance of each point from the start
+        let mut distances = Vec::with_capacity(points.len());
+        let mut length = 0.;
+        for (idx, point) in points.iter().enumerate() {
+            if idx > 0 {
+                length += (point - points[idx - 1]).magnitude();
+            }
+            distances.push(length);
+        }
+
+        let mut shapes = vec![];
+        if length > 0. && !stops.is_empty() {
+            let count = if step > 0. {
+                (length / step).ceil().max(1.) as usize
+            } else {
+                1
+            };
+
+            let at = |distance: f32| -> Point2<f32> {
+                let idx = distances
+                    .partition_point(|d| *d < distance)
+                    .clamp(1, points.len() - 1);
+                let (start, end) = (distances[idx - 1], distances[idx]);
+                let t = if end > start {
+                    (distance - start) / (end - start)
+                } else {
+                    0.
+                };
+                points[idx - 1] + (points[idx] - points[idx - 1]) * t
+            };
+
+            for piece in 0..count {
+                let start = length * piece as f32 / count as f32;
+                let end = length * (piece + 1) as f32 / count as f32;
+
+                let keypoints = std::iter::once(at(start))
+                    .chain(
+                        points
+                            .iter()
+                            .zip(&distances)
+                            .filter(|(_, d)| **d > start && **d < end)
+                            .map(|(p, _)| *p),
+                    )
+                    .chain(std::iter::once(at(end)))
+                    .map(Keypoint::Point)
+                    .collect();
+
+                let color = color_at(&stops, (start + end) / 2. / length).unwrap_or(Color::BLACK);
+                shapes.push(Shape::Style {
+                    fill: None,
+                    stroke: Some(Stroke::from((color, width))),
+                    stroke_alignment: StrokeAlignment::Center,
+                    filter: None,
+                    opacity: 1.,
+                    blend_mode: BlendMode::Normal,
+                    shape: Box::new(Shape::Curve(Curve {
+                        local_transform: Transform2::identity(),
+                        keypoints,
+                        closed: false,
+                        start_marker: None,
+                        end_marker: None,
+                        fill_rule: FillRule::NonZero,
+                    })),
+                });
+            }
+        }
+
+        Shape::Group(Group {
+            local_transform,
+            shapes,
+            metadata: vec![],
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn colors_along_the_curve() {
+        let stroke = dessin2!(GradientStroke(
+            curve = Line::default().with_from([0., 0.]).with_to([10., 0.]),
+            stop = (1., Color::WHITE),
+            stop = (0., Color::BLACK),
+            step = 2.,
+        ));
+
+        assert_eq!(stroke.color_at(-1.), Some(Color::BLACK));
+        assert_eq!(stroke.color_at(0.5), Some(rgba(128, 128, 128, 255)));
+        assert_eq!(stroke.color_at(2.), Some(Color::WHITE));
+
+        let Shape::Group(Group { shapes, .. }) = stroke.into() else {
+            unreachable!()
+        };
+        assert_eq!(shapes.len(), 5);
+
+        let Shape::Style {
+            stroke: Some(stroke),
+            shape,
+            ..
+        } = &shapes[0]
+        else {
+            unreachable!()
+        };
+        // Color at the middle of the first piece
+        assert_eq!(stroke.color(), rgba(26, 26, 26, 255));
+        assert!((shape.local_bounding_box().straigthen().width() - 2.).abs() < 10e-5);
+    }
+}
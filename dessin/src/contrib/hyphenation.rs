@@ -0,0 +1,174 @@
+use std::collections::HashMap;
+
+/// Hyphenation of words of a language, with Liang's patterns, as used by TeX.
+///
+/// Patterns aren't bundled, as each language has its own.
+/// They are published by the [hyph-utf8](https://github.com/hyphenation/tex-hyphen) project,
+/// for instance `hyph-en-us.pat.txt` and `hyph-en-us.hyp.txt` for american english.
+///
+/// ```
+/// # use dessin::prelude::*;
+/// let hyphenation = Hyphenation::new("hy3ph he2n hena4 hen5at 1na n2at 1tio 2io o2n")
+/// 	.with_exceptions("ta-ble");
+///
+/// assert_eq!(hyphenation.hyphenate("hyphenation"), vec!["hy", "phen", "ation"]);
+/// assert_eq!(hyphenation.hyphenate("Table"), vec!["Ta", "ble"]);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hyphenation {
+    /// Letters of each pattern, and the values between them
+    patterns: HashMap<String, Vec<u8>>,
+    /// Positions of the breaks of words hyphenated by hand
+    exceptions: HashMap<String, Vec<usize>>,
+    /// Length of the longest pattern, in letters
+    longest: usize,
+    /// Fewest letters before the first break
+    pub left_min: usize,
+    /// Fewest letters after the last break
+    pub right_min: usize,
+}
+impl Hyphenation {
+    /// Hyphenation with `patterns`, separated by whitespaces, such as `.ach4 4ab 1ba`
+    pub fn new(patterns: &str) -> Self {
+        let mut longest = 0;
+        let patterns = patterns
+            .split_whitespace()
+            .map(|pattern| {
+                let mut letters = String::new();
+                let mut values = vec![0];
+                for c in pattern.chars() {
+                    match c.to_digit(10) {
+                        Some(value) => *values.last_mut().unwrap() = value as u8,
+                        None => {
+                            letters.push(c);
+                            values.push(0);
+                        }
+                    }
+                }
+                longest = longest.max(letters.chars().count());
+                (letters, values)
+            })
+            .collect();
+
+        Hyphenation {
+            patterns,
+            exceptions: HashMap::new(),
+            longest,
+            left_min: 2,
+            right_min: 3,
+        }
+    }
+
+    /// Words hyphenated by hand, separated by whitespaces, such as `as-so-ciate ta-ble`
+    pub fn exceptions(&mut self, exceptions: &str) -> &mut Self {
+        for exception in exceptions.split_whitespace() {
+            let mut breaks = vec![];
+            let mut word = String::new();
+            for c in exception.chars() {
+                if c == '-' {
+                    breaks.push(word.chars().count());
+                } else {
+                    word.push(c);
+                }
+            }
+            self.exceptions.insert(word.to_lowercase(), breaks);
+        }
+        self
+    }
+    /// Words hyphenated by hand, separated by whitespaces, such as `as-so-ciate ta-ble`
+    #[inline]
+    pub fn with_exceptions(mut self, exceptions: &str) -> Self {
+        self.exceptions(exceptions);
+        self
+    }
+
+    /// Fewest letters before the first break, and after the last one
+    #[inline]
+    pub fn min_lengths(&mut self, (left_min, right_min): (usize, usize)) -> &mut Self {
+        self.left_min = left_min;
+        self.right_min = right_min;
+        self
+    }
+    /// Fewest letters before the first break, and after the last one
+    #[inline]
+    pub fn with_min_lengths(mut self, min_lengths: (usize, usize)) -> Self {
+        self.min_lengths(min_lengths);
+        self
+    }
+
+    /// Positions, in chars, where `word` can be broken.
+    ///
+    /// Only the letters are hyphenated, leading and trailing punctuation is kept with them.
+    pub fn breaks(&self, word: &str) -> Vec<usize> {
+        let chars = word.chars().collect::<Vec<_>>();
+        let start = chars.iter().take_while(|c| !c.is_alphabetic()).count();
+        let end = chars.len()
+            - chars
+                .iter()
+                .rev()
+                .take_while(|c| !c.is_alphabetic())
+                .count();
+        if start >= end {
+            return vec![];
+        }
+
+        let letters = chars[start..end]
+            .iter()
+            .flat_map(|c| c.to_lowercase())
+            .collect::<Vec<_>>();
+        // Lowercasing can change the number of chars, whose positions would be lost
+        if letters.len() != end - start {
+            return vec![];
+        }
+
+        let len = letters.len();
+        let breaks = match self.exceptions.get(&letters.iter().collect::<String>()) {
+            Some(breaks) => breaks.clone(),
+            None => {
+                let dotted = std::iter::once('.')
+                    .chain(letters.iter().copied())
+                    .chain(std::iter::once('.'))
+                    .collect::<Vec<_>>();
+
+                // Value between each letter of the dotted word, breaks are at odd ones
+                let mut values = vec![0; dotted.len() + 1];
+                for from in 0..dotted.len() {
+                    for to in from + 1..=dotted.len().min(from + self.longest) {
+                        let part = dotted[from..to].iter().collect::<String>();
+                        if let Some(pattern) = self.patterns.get(&part) {
+                            for (idx, value) in pattern.iter().enumerate() {
+                                values[from + idx] = values[from + idx].max(*value);
+                            }
+                        }
+                    }
+                }
+
+                // Between letters `idx - 1` and `idx` of the word, which is after the dot
+                (1..len).filter(|idx| values[idx + 1] % 2 == 1).collect()
+            }
+        };
+
+        breaks
+            .into_iter()
+            .filter(|idx| *idx >= self.left_min && *idx + self.right_min <= len)
+            .map(|idx| idx + start)
+            .collect()
+    }
+
+    /// Parts of `word`, between its breaks
+    pub fn hyphenate<'a>(&self, word: &'a str) -> Vec<&'a str> {
+        let offsets = word
+            .char_indices()
+            .map(|(offset, _)| offset)
+            .collect::<Vec<_>>();
+
+        let mut parts = vec![];
+        let mut last = 0;
+        for idx in self.breaks(word) {
+            parts.push(&word[last..offsets[idx]]);
+            last = offsets[idx];
+        }
+        parts.push(&word[last..]);
+        parts
+    }
+}
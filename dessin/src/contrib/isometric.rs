@@ -0,0 +1,220 @@
+use crate::prelude::*;
+use nalgebra::{Matrix3, Point2, Point3, Transform2, Vector2};
+use std::f32::consts::FRAC_PI_6;
+
+/// Face of a box seen through an [`Axonometric`] projection
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub enum ProjectionPlane {
+    /// Plane of the X and Y axes, facing up
+    #[default]
+    Top,
+    /// Plane of the Y and Z axes, facing left.
+    ///
+    /// The drawing is not mirrored, so its X axis runs along -Y.
+    Left,
+    /// Plane of the X and Z axes, facing right
+    Right,
+}
+
+/// Projection of a 3D space on the page, for pseudo-3D technical sketches.
+///
+/// Each field is where the unit vector of an axis lands on the page.
+/// The Z axis points up, X goes to the right and Y to the left.
+///
+/// Flat shapes are drawn on a face of a box with [`plane`][Axonometric::plane]:
+/// ```
+/// # use dessin::prelude::*;
+/// let iso = Axonometric::isometric();
+///
+/// let cube = dessin2!([
+/// 	Rectangle(
+/// 		width = 10.,
+/// 		height = 10.,
+/// 		translate = [5., 5.],
+/// 		transform = iso.plane(ProjectionPlane::Top),
+/// 		translate = iso.project([0., 0., 10.]) - nalgebra::Point2::origin(),
+/// 	),
+/// 	Rectangle(
+/// 		width = 10.,
+/// 		height = 10.,
+/// 		translate = [5., 5.],
+/// 		transform = iso.plane(ProjectionPlane::Right),
+/// 	),
+/// ]);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Axonometric {
+    /// Projection of the X axis
+    pub x: Vector2<f32>,
+    /// Projection of the Y axis
+    pub y: Vector2<f32>,
+    /// Projection of the Z axis
+    pub z: Vector2<f32>,
+}
+impl Default for Axonometric {
+    fn default() -> Self {
+        Axonometric::isometric()
+    }
+}
+impl Axonometric {
+    /// Isometric drawing: X and Y rise by 30° from the horizontal, and the three axes keep their length
+    pub fn isometric() -> Self {
+        Axonometric::dimetric(FRAC_PI_6, 1.)
+    }
+
+    /// Dimetric drawing: X and Y rise by `angle` from the horizontal, and Z is scaled by `vertical_scale`.
+    ///
+    /// The 2:1 projection of pixel art uses an angle of `0.5f32.atan()`.
+    pub fn dimetric(angle: f32, vertical_scale: f32) -> Self {
+        Axonometric {
+            x: Vector2::new(angle.cos(), angle.sin()),
+            y: Vector2::new(-angle.cos(), angle.sin()),
+            z: Vector2::new(0., vertical_scale),
+        }
+    }
+
+    /// Position of a 3D point on the page
+    pub fn project<P: Into<Point3<f32>>>(&self, point: P) -> Point2<f32> {
+        let p = point.into();
+        Point2::origin() + self.x * p.x + self.y * p.y + self.z * p.z
+    }
+
+    /// Transform drawing flat shapes on a plane going through the origin
+    pub fn plane(&self, plane: ProjectionPlane) -> Transform2<f32> {
+        let (u, v) = match plane {
+            ProjectionPlane::Top => (self.x, self.y),
+            ProjectionPlane::Left => (-self.y, self.z),
+            ProjectionPlane::Right => (self.x, self.z),
+        };
+
+        Transform2::from_matrix_unchecked(Matrix3::new(
+            u.x, v.x, 0., //
+            u.y, v.y, 0., //
+            0., 0., 1.,
+        ))
+    }
+}
+
+/// Grid of the top plane of an [`Axonometric`] projection, starting at the origin.
+///
+/// ```
+/// # use dessin::prelude::*;
+/// let grid = dessin2!(AxonometricGrid!(
+/// 	stroke = (Color::GRAY, 0.1),
+/// 	columns = 10,
+/// 	rows = 10,
+/// 	spacing = 5.,
+/// 	verticals,
+/// ));
+/// ```
+#[derive(Debug, Clone, PartialEq, Shape)]
+pub struct AxonometricGrid {
+    /// [`ShapeOp`]
+    #[local_transform]
+    pub local_transform: Transform2<f32>,
+
+    /// Projection of the grid
+    pub projection: Axonometric,
+
+    /// Number of cells along X
+    pub columns: u32,
+
+    /// Number of cells along Y
+    pub rows: u32,
+
+    /// Size of a cell
+    pub spacing: f32,
+
+    /// Split each cell along its vertical diagonal, giving the triangular grid of isometric paper
+    #[shape(bool)]
+    pub verticals: bool,
+}
+impl Default for AxonometricGrid {
+    fn default() -> Self {
+        AxonometricGrid {
+            local_transform: Default::default(),
+            projection: Axonometric::isometric(),
+            columns: 10,
+            rows: 10,
+            spacing: 1.,
+            verticals: false,
+        }
+    }
+}
+
+impl From<AxonometricGrid> for Shape {
+    fn from(
+        AxonometricGrid {
+            local_transform,
+            projection,
+            columns,
+            rows,
+            spacing,
+            verticals,
+        }: AxonometricGrid,
+    ) -> Self {
+        let at = |i: u32, j: u32| projection.project([i as f32 * spacing, j as f32 * spacing, 0.]);
+
+        let along_x = (0..=rows).map(|j| (at(0, j), at(columns, j)));
+        let along_y = (0..=columns).map(|i| (at(i, 0), at(i, rows)));
+        let diagonals = (0..columns)
+            .flat_map(|i| (0..rows).map(move |j| (i, j)))
+            .filter(|_| verticals)
+            .map(|(i, j)| (at(i, j), at(i + 1, j + 1)));
+
+        dessin2!(
+            for (from, to) in (along_x.chain(along_y).chain(diagonals).collect::<Vec<_>>()) {
+                dessin2!(Line(from = from, to = to))
+            } > (transform = local_transform)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use assert_float_eq::*;
+    use nalgebra::Point2;
+
+    #[test]
+    fn isometric_axes() {
+        let iso = Axonometric::isometric();
+
+        assert_float_absolute_eq!(iso.x.angle(&iso.y).to_degrees(), 120., 10e-4);
+        assert_float_absolute_eq!(iso.x.angle(&iso.z).to_degrees(), 60., 10e-4);
+        assert_float_absolute_eq!(iso.y.angle(&iso.z).to_degrees(), 60., 10e-4);
+
+        for axis in [iso.x, iso.y, iso.z] {
+            assert_float_absolute_eq!(axis.magnitude(), 1., 10e-6);
+        }
+    }
+
+    #[test]
+    fn plane_matches_projection() {
+        let iso = Axonometric::isometric();
+
+        let top = iso.plane(ProjectionPlane::Top) * Point2::new(2., 3.);
+        let projected = iso.project([2., 3., 0.]);
+        assert_float_absolute_eq!(top.x, projected.x, 10e-6);
+        assert_float_absolute_eq!(top.y, projected.y, 10e-6);
+
+        let right = iso.plane(ProjectionPlane::Right) * Point2::new(2., 3.);
+        let projected = iso.project([2., 0., 3.]);
+        assert_float_absolute_eq!(right.x, projected.x, 10e-6);
+        assert_float_absolute_eq!(right.y, projected.y, 10e-6);
+    }
+
+    #[test]
+    fn grid_lines() {
+        let Shape::Group(grid) = dessin2!(AxonometricGrid(columns = 3, rows = 2)).into() else {
+            panic!("Expected a group");
+        };
+        assert_eq!(grid.shapes.len(), 3 + 4);
+
+        let Shape::Group(grid) = dessin2!(AxonometricGrid(columns = 3, rows = 2, verticals)).into()
+        else {
+            panic!("Expected a group");
+        };
+        assert_eq!(grid.shapes.len(), 3 + 4 + 6);
+    }
+}
@@ -0,0 +1,333 @@
+use crate::{font::FontRef, prelude::*, presets::ArrowSize};
+use nalgebra::{Point2, Transform2, Vector2};
+
+/// Side of the elbow of a [`Leader`] where the text goes
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub enum LeaderSide {
+    /// Away from the target, unless the text does not fit in the available space
+    #[default]
+    Auto,
+    /// Left of the elbow
+    Left,
+    /// Right of the elbow
+    Right,
+}
+
+/// Callout pointing at a target.
+///
+/// An arrow starts at the `target`, goes to the `elbow`, then continues horizontally for `landing`, where the text sits.
+/// With [`LeaderSide::Auto`], the text goes on the side away from the target,
+/// unless it doesn't fit in the `available_space` and there is more room on the other side.
+///
+/// ```
+/// # use dessin::prelude::*;
+/// let part = dessin2!(Circle!(stroke = (Color::BLACK, 0.2), radius = 10.));
+///
+/// let callout = dessin2!(Leader(
+/// 	target = [7., 7.],
+/// 	elbow = [15., 15.],
+/// 	text = "Bearing",
+/// 	available_space = BoundingBox::centered([40., 40.]),
+/// ));
+///
+/// let dessin = dessin2!([{ part }, { callout }]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Shape)]
+pub struct Leader {
+    /// [`ShapeOp`]
+    #[local_transform]
+    pub local_transform: Transform2<f32>,
+
+    /// Point the arrow points at
+    #[shape(into)]
+    pub target: Point2<f32>,
+
+    /// Point where the leader turns horizontal
+    #[shape(into)]
+    pub elbow: Point2<f32>,
+
+    /// The text
+    #[shape(into)]
+    pub text: String,
+
+    /// Font size
+    pub font_size: f32,
+
+    /// Font weight
+    pub font_weight: FontWeight,
+
+    /// Font
+    #[shape(into_some)]
+    pub font: Option<FontRef>,
+
+    /// Length of the horizontal segment after the elbow
+    pub landing: f32,
+
+    /// Space between the end of the landing and the text
+    pub gap: f32,
+
+    /// Length of the arrowhead
+    pub arrow_length: f32,
+
+    /// Width of the base of the arrowhead
+    pub arrow_width: f32,
+
+    /// Color of the leader, arrowhead and text
+    pub color: Color,
+
+    /// Stroke width of the leader
+    pub thickness: f32,
+
+    /// Side of the elbow where the text goes
+    pub side: LeaderSide,
+
+    /// Area the text should fit in, used by [`LeaderSide::Auto`]
+    #[shape(some)]
+    pub available_space: Option<BoundingBox<Straight>>,
+
+    /// Bend the leader between the target and the elbow, instead of a straight line
+    #[shape(bool)]
+    pub curved: bool,
+}
+impl Default for Leader {
+    fn default() -> Self {
+        Leader {
+            local_transform: Default::default(),
+            target: Point2::origin(),
+            elbow: Point2::new(10., 10.),
+            text: Default::default(),
+            font_size: 5.,
+            font_weight: Default::default(),
+            font: None,
+            landing: 5.,
+            gap: 1.,
+            arrow_length: 2.,
+            arrow_width: 1.,
+            color: Color::BLACK,
+            thickness: 0.2,
+            side: LeaderSide::Auto,
+            available_space: None,
+            curved: false,
+        }
+    }
+}
+impl Leader {
+    /// Length and width of the arrowhead, such as one [sized for a line][crate::presets::ArrowSize::for_width]
+    #[inline]
+    pub fn arrow<A: Into<ArrowSize>>(&mut self, arrow: A) -> &mut Self {
+        let ArrowSize { length, width } = arrow.into();
+        self.arrow_length = length;
+        self.arrow_width = width;
+        self
+    }
+    /// Length and width of the arrowhead, such as one [sized for a line][crate::presets::ArrowSize::for_width]
+    #[inline]
+    pub fn with_arrow<A: Into<ArrowSize>>(mut self, arrow: A) -> Self {
+        self.arrow(arrow);
+        self
+    }
+
+    /// Width of the text, once rendered
+    fn text_width(&self) -> f32 {
+        if self.text.is_empty() {
+            return 0.;
+        }
+
+        Text {
+            text: self.text.clone(),
+            font_size: self.font_size,
+            font_weight: self.font_weight,
+            font: self.font.clone(),
+            ..Default::default()
+        }
+        .local_bounding_box()
+        .straigthen()
+        .width()
+    }
+
+    /// Side where the text goes, either [`LeaderSide::Left`] or [`LeaderSide::Right`]
+    pub fn resolved_side(&self) -> LeaderSide {
+        match self.side {
+            LeaderSide::Auto => {}
+            side => return side,
+        }
+
+        let preferred = if self.elbow.x >= self.target.x {
+            LeaderSide::Right
+        } else {
+            LeaderSide::Left
+        };
+
+        let Some(available_space) = self.available_space else {
+            return preferred;
+        };
+
+        let needed = self.landing + self.gap + self.text_width();
+        let room_right = available_space.right() - self.elbow.x;
+        let room_left = self.elbow.x - available_space.left();
+
+        match preferred {
+            LeaderSide::Right if room_right < needed && room_left > room_right => LeaderSide::Left,
+            LeaderSide::Left if room_left < needed && room_right > room_left => LeaderSide::Right,
+            side => side,
+        }
+    }
+}
+
+impl From<Leader> for Shape {
+    fn from(leader: Leader) -> Self {
+        let side = leader.resolved_side();
+        let Leader {
+            local_transform,
+            target,
+            elbow,
+            text,
+            font_size,
+            font_weight,
+            font,
+            landing,
+            gap,
+            arrow_length,
+            arrow_width,
+            color,
+            thickness,
+            curved,
+            ..
+        } = leader;
+
+        let (direction, align) = match side {
+            LeaderSide::Left => (-1., TextAlign::Right),
+            _ => (1., TextAlign::Left),
+        };
+        let landing_end = elbow + Vector2::new(direction * landing, 0.);
+
+        let leader_line = if curved {
+            let bend = (elbow - target).magnitude() / 2.;
+            Curve {
+                local_transform: Default::default(),
+                keypoints: vec![
+                    Keypoint::Bezier(Bezier {
+                        start: Some(target),
+                        start_control: target + (elbow - target) / 2.,
+                        end_control: elbow - Vector2::new(direction * bend, 0.),
+                        end: elbow,
+                    }),
+                    Keypoint::Point(landing_end),
+                ],
+                closed: false,
+                start_marker: None,
+                end_marker: None,
+                fill_rule: FillRule::NonZero,
+            }
+        } else {
+            Curve {
+                local_transform: Default::default(),
+                keypoints: vec![
+                    Keypoint::Point(target),
+                    Keypoint::Point(elbow),
+                    Keypoint::Point(landing_end),
+                ],
+                closed: false,
+                start_marker: None,
+                end_marker: None,
+                fill_rule: FillRule::NonZero,
+            }
+        };
+
+        // The arrowhead follows the leader as it leaves the target
+        let towards = match &leader_line.keypoints[0] {
+            Keypoint::Bezier(b) => b.start_control - target,
+            _ => elbow - target,
+        };
+        let towards = if towards.magnitude() > 0. {
+            towards.normalize()
+        } else {
+            Vector2::new(-direction, 0.)
+        };
+        let normal = Vector2::new(-towards.y, towards.x) * arrow_width / 2.;
+        let base = target + towards * arrow_length;
+
+        let arrow = Curve {
+            local_transform: Default::default(),
+            keypoints: vec![
+                Keypoint::Point(target),
+                Keypoint::Point(base + normal),
+                Keypoint::Point(base - normal),
+            ],
+            closed: true,
+            start_marker: None,
+            end_marker: None,
+            fill_rule: FillRule::NonZero,
+        };
+
+        let text = Text {
+            local_transform: Default::default(),
+            text,
+            align,
+            vertical_align: TextVerticalAlign::Center,
+            font_weight,
+            on_curve: None,
+            font_size,
+            font,
+        }
+        .with_translate(landing_end + Vector2::new(direction * gap, 0.) - Point2::origin());
+
+        let leader_line = Style::new(leader_line).with_stroke((color, thickness));
+        let arrow = Style::new(arrow).with_fill(color);
+        let text = Style::new(text).with_fill(color);
+
+        dessin2!([{ leader_line }, { arrow }, { text }] > (transform = local_transform))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    #[test]
+    fn text_goes_away_from_target() {
+        let leader = dessin2!(Leader(target = [0., 0.], elbow = [-10., 10.], text = "A"));
+        assert_eq!(leader.resolved_side(), LeaderSide::Left);
+
+        let leader = dessin2!(Leader(target = [0., 0.], elbow = [10., 10.], text = "A"));
+        assert_eq!(leader.resolved_side(), LeaderSide::Right);
+    }
+
+    #[test]
+    fn text_flips_when_out_of_space() {
+        let leader = dessin2!(Leader(
+            target = [0., 0.],
+            elbow = [15., 10.],
+            text = "A rather long label",
+            available_space = BoundingBox::centered([40., 40.]),
+        ));
+        assert_eq!(leader.resolved_side(), LeaderSide::Left);
+
+        let leader = dessin2!(Leader(
+            target = [0., 0.],
+            elbow = [15., 10.],
+            text = "A rather long label",
+            side = LeaderSide::Right,
+            available_space = BoundingBox::centered([40., 40.]),
+        ));
+        assert_eq!(leader.resolved_side(), LeaderSide::Right);
+    }
+
+    #[test]
+    fn arrow_points_at_target() {
+        let leader: Shape =
+            dessin2!(Leader(target = [1., 2.], elbow = [5., 6.], text = "A")).into();
+
+        let Shape::Group(Group { shapes, .. }) = leader else {
+            panic!("Expected a group");
+        };
+        let Shape::Style { shape, .. } = &shapes[1] else {
+            panic!("Expected a style");
+        };
+        let Shape::Curve(arrow) = shape.as_ref() else {
+            panic!("Expected a curve");
+        };
+
+        assert_eq!(arrow.keypoints[0], Keypoint::Point([1., 2.].into()));
+    }
+}
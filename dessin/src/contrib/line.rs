@@ -18,6 +18,14 @@ pub struct Line {
     /// Ending point
     #[shape(into)]
     to: Point2<f32>,
+
+    /// Head drawn at the starting point
+    #[shape(some)]
+    start_marker: Option<Marker>,
+
+    /// Head drawn at the ending point
+    #[shape(some)]
+    end_marker: Option<Marker>,
 }
 
 impl From<Line> for Shape {
@@ -34,12 +42,17 @@ impl From<Line> for Curve {
             local_transform,
             from,
             to,
+            start_marker,
+            end_marker,
         }: Line,
     ) -> Self {
         Curve {
             local_transform,
             closed: false,
             keypoints: vec![Keypoint::Point(from), Keypoint::Point(to)],
+            start_marker,
+            end_marker,
+            fill_rule: FillRule::NonZero,
         }
     }
 }
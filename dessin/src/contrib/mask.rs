@@ -0,0 +1,182 @@
+use crate::prelude::*;
+use nalgebra::Transform2;
+
+/// Metadata key marking a [`Group`] made by a [`Mask`]
+pub const MASK_METADATA_KEY: &str = "mask";
+
+/// Metadata key of the [`MaskMode`] of a [`Mask`], absent for luminance masks
+pub const MASK_MODE_METADATA_KEY: &str = "mask-mode";
+
+/// What of the mask of a [`Mask`] makes the masked shape opaque
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum MaskMode {
+    /// Opaque where the mask is white, transparent where it is black or absent
+    #[default]
+    Luminance,
+    /// Opaque where the mask is opaque, whatever its color, transparent where it is transparent or absent
+    Alpha,
+}
+
+/// Shape faded by the luminance of another one: opaque where the mask is white, transparent where it is black or absent.
+///
+/// A rectangle filled with a gradient from white to black gives a shape fading out along it.
+/// With [`MaskMode::Alpha`], the alpha of the mask is the opacity instead, so a gradient to a transparent color fades it out.
+///
+/// Exporters not supporting masks draw the shape unmasked, and never draw the mask.
+///
+/// ```
+/// # use dessin::prelude::*;
+/// let faded = dessin2!(Mask(
+/// 	shape = dessin2!(Rectangle!(fill = Color::RED, width = 40., height = 20.)),
+/// 	mask = dessin2!(Rectangle!(
+/// 		fill = Fill::LinearGradient {
+/// 			start: [-20., 0.].into(),
+/// 			end: [20., 0.].into(),
+/// 			stops: [(0., Color::WHITE), (1., Color::BLACK)].into(),
+/// 		},
+/// 		width = 40.,
+/// 		height = 20.,
+/// 	)),
+/// ));
+///
+/// let Shape::Group(group) = Shape::from(faded) else {
+/// 	unreachable!()
+/// };
+/// assert!(Mask::parts(&group).is_some());
+/// assert_eq!(Mask::mode_of(&group), MaskMode::Luminance);
+/// ```
+///
+/// ```
+/// # use dessin::prelude::*;
+/// let edge = dessin2!(Mask(
+/// 	shape = dessin2!(Rectangle!(fill = Color::BLUE, width = 40., height = 20.)),
+/// 	mask = dessin2!(Rectangle!(
+/// 		fill = Fill::LinearGradient {
+/// 			start: [10., 0.].into(),
+/// 			end: [20., 0.].into(),
+/// 			stops: [(0., Color::BLACK), (1., Color::TRANSPARENT)].into(),
+/// 		},
+/// 		width = 40.,
+/// 		height = 20.,
+/// 	)),
+/// 	mode = MaskMode::Alpha,
+/// ));
+///
+/// let Shape::Group(group) = Shape::from(edge) else {
+/// 	unreachable!()
+/// };
+/// assert_eq!(Mask::mode_of(&group), MaskMode::Alpha);
+/// ```
+#[derive(Default, Debug, Clone, Shape)]
+pub struct Mask {
+    /// [`ShapeOp`]
+    #[local_transform]
+    pub local_transform: Transform2<f32>,
+
+    /// Shape being masked
+    #[shape(skip)]
+    pub shape: Shape,
+
+    /// Shape whose luminance, or alpha, is the opacity of the masked shape
+    #[shape(skip)]
+    pub mask: Shape,
+
+    /// Whether the luminance or the alpha of the mask is the opacity of the masked shape
+    pub mode: MaskMode,
+}
+impl Mask {
+    /// Shape being masked
+    #[inline]
+    pub fn shape<T: Into<Shape>>(&mut self, shape: T) -> &mut Self {
+        self.shape = shape.into();
+        self
+    }
+    /// Shape being masked
+    #[inline]
+    pub fn with_shape<T: Into<Shape>>(mut self, shape: T) -> Self {
+        self.shape(shape);
+        self
+    }
+
+    /// Shape whose luminance, or alpha, is the opacity of the masked shape
+    #[inline]
+    pub fn mask<T: Into<Shape>>(&mut self, mask: T) -> &mut Self {
+        self.mask = mask.into();
+        self
+    }
+    /// Shape whose luminance, or alpha, is the opacity of the masked shape
+    #[inline]
+    pub fn with_mask<T: Into<Shape>>(mut self, mask: T) -> Self {
+        self.mask(mask);
+        self
+    }
+
+    /// Whether a group with `metadata` was made by a [`Mask`]
+    pub fn is_mask(metadata: &[(String, String)]) -> bool {
+        metadata
+            .iter()
+            .any(|(key, value)| key == MASK_METADATA_KEY && value == "true")
+    }
+
+    /// Shape being masked and its mask, if `group` was made by a [`Mask`]
+    pub fn parts(group: &Group) -> Option<(&Shape, &Shape)> {
+        if !Mask::is_mask(&group.metadata) {
+            return None;
+        }
+
+        match &group.shapes[..] {
+            [shape, mask] => Some((shape, mask)),
+            _ => None,
+        }
+    }
+
+    /// Whether the luminance or the alpha of the mask of `group` is the opacity of its shape
+    pub fn mode_of(group: &Group) -> MaskMode {
+        let alpha = group
+            .metadata
+            .iter()
+            .any(|(key, value)| key == MASK_MODE_METADATA_KEY && value == "alpha");
+        if alpha {
+            MaskMode::Alpha
+        } else {
+            MaskMode::Luminance
+        }
+    }
+}
+
+impl From<Mask> for Shape {
+    fn from(
+        Mask {
+            local_transform,
+            shape,
+            mask,
+            mode,
+        }: Mask,
+    ) -> Self {
+        let mut metadata = vec![(MASK_METADATA_KEY.to_string(), "true".to_string())];
+        if mode == MaskMode::Alpha {
+            metadata.push((MASK_MODE_METADATA_KEY.to_string(), "alpha".to_string()));
+        }
+
+        Shape::Group(Group {
+            local_transform,
+            shapes: vec![shape, mask],
+            metadata,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{display_list::DisplayList, prelude::*};
+
+    #[test]
+    fn mask_not_drawn_without_support() {
+        let masked = dessin2!(Mask(
+            shape = dessin2!(Circle!(fill = Color::RED, radius = 10.)),
+            mask = dessin2!(Rectangle!(fill = Color::WHITE, width = 10., height = 10.)),
+        ));
+
+        assert_eq!(DisplayList::new(&masked.into()).len(), 1);
+    }
+}
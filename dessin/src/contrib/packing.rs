@@ -0,0 +1,452 @@
+use crate::prelude::*;
+use nalgebra::Rotation2;
+use std::f32::consts::FRAC_PI_2;
+
+/// Where a shape of a [`Packing`] lands
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Placement {
+    /// Index of the shape, in the order they were added
+    pub index: usize,
+    /// Index of the sheet
+    pub sheet: usize,
+    /// Area covered by the bounding box of the shape, in the coordinates of the sheet, centered around (0, 0)
+    pub area: BoundingBox<Straight>,
+    /// Whether the shape is rotated by a quarter turn, counterclockwise
+    pub rotated: bool,
+}
+
+/// One sheet of a [`Packing`]
+#[derive(Debug, Clone)]
+pub struct Sheet {
+    /// Shapes placed on this sheet
+    pub placements: Vec<Placement>,
+    /// Content of the sheet, centered around (0, 0).
+    ///
+    /// Export it with a size of [`Packing::sheet_width`] x [`Packing::sheet_height`]
+    pub shape: Shape,
+}
+
+/// Result of a [`Packing`]
+#[derive(Debug, Clone)]
+pub struct Packed {
+    /// Sheets, in the order they were filled
+    pub sheets: Vec<Sheet>,
+    /// Index of the shapes too large to fit on a sheet, even alone
+    pub oversized: Vec<usize>,
+}
+
+/// Pack shapes, by their bounding box, on as few sheets as possible.
+///
+/// Useful to nest parts for laser cutting, or to generate sprite sheets.
+/// Larger shapes are placed first, each in the free area that fits it the most tightly.
+///
+/// ```
+/// # use dessin::prelude::*;
+/// let parts = (1..=10).map(|idx| {
+/// 	dessin2!(Rectangle!(
+/// 		stroke = (Color::RED, 0.1),
+/// 		width = 10. * idx as f32,
+/// 		height = 20.,
+/// 	) > ())
+/// });
+///
+/// let packed = Packing::default()
+/// 	.with_sheet_width(100.)
+/// 	.with_sheet_height(60.)
+/// 	.with_spacing(2.)
+/// 	.with_rotations()
+/// 	.with_shapes(parts)
+/// 	.pack();
+///
+/// assert!(packed.oversized.is_empty());
+/// for sheet in packed.sheets {
+/// 	let page = sheet.shape;
+/// }
+/// ```
+#[derive(Debug, Clone, Shape)]
+pub struct Packing {
+    /// Shapes to pack
+    #[shape(skip)]
+    pub shapes: Vec<Shape>,
+
+    /// Width of a sheet
+    pub sheet_width: f32,
+
+    /// Height of a sheet
+    pub sheet_height: f32,
+
+    /// Minimum distance between two shapes
+    pub spacing: f32,
+
+    /// Minimum distance between a shape and the border of its sheet
+    pub margin: f32,
+
+    /// Allow shapes to be rotated by a quarter turn, if it fits them better
+    #[shape(bool)]
+    pub rotations: bool,
+}
+impl Default for Packing {
+    fn default() -> Self {
+        Packing {
+            shapes: vec![],
+            sheet_width: 210.,
+            sheet_height: 297.,
+            spacing: 0.,
+            margin: 0.,
+            rotations: false,
+        }
+    }
+}
+impl Packing {
+    /// Add a shape to pack
+    #[inline]
+    pub fn shape<T: Into<Shape>>(&mut self, shape: T) -> &mut Self {
+        self.shapes.push(shape.into());
+        self
+    }
+    /// Add a shape to pack
+    #[inline]
+    pub fn with_shape<T: Into<Shape>>(mut self, shape: T) -> Self {
+        self.shape(shape);
+        self
+    }
+
+    /// Add shapes to pack
+    #[inline]
+    pub fn extend_shapes<T: Into<Shape>, I: IntoIterator<Item = T>>(
+        &mut self,
+        shapes: I,
+    ) -> &mut Self {
+        self.shapes.extend(shapes.into_iter().map(Into::into));
+        self
+    }
+    /// Add shapes to pack
+    #[inline]
+    pub fn with_shapes<T: Into<Shape>, I: IntoIterator<Item = T>>(mut self, shapes: I) -> Self {
+        self.extend_shapes(shapes);
+        self
+    }
+
+    /// Where each shape lands, sheet by sheet, and the shapes which can't fit at all
+    pub fn placements(&self) -> (Vec<Placement>, Vec<usize>) {
+        // Spacing is added to the right and bottom of each shape, so the usable area grows by one spacing
+        let bin_width = self.sheet_width - 2. * self.margin + self.spacing;
+        let bin_height = self.sheet_height - 2. * self.margin + self.spacing;
+
+        let mut sizes = self
+            .shapes
+            .iter()
+            .map(|shape| {
+                let bb = shape.local_bounding_box().straigthen();
+                (bb.width(), bb.height())
+            })
+            .enumerate()
+            .collect::<Vec<_>>();
+        sizes.sort_by(|(_, (w1, h1)), (_, (w2, h2))| {
+            (w2 * h2)
+                .total_cmp(&(w1 * h1))
+                .then(w2.max(*h2).total_cmp(&w1.max(*h1)))
+        });
+
+        let mut bins: Vec<MaxRects> = vec![];
+        let mut placements = vec![];
+        let mut oversized = vec![];
+        for (index, (width, height)) in sizes {
+            let (width, height) = (width + self.spacing, height + self.spacing);
+
+            let placed = bins
+                .iter_mut()
+                .enumerate()
+                .find_map(|(sheet, bin)| {
+                    bin.insert(width, height, self.rotations)
+                        .map(|place| (sheet, place))
+                })
+                .or_else(|| {
+                    let mut bin = MaxRects::new(bin_width, bin_height);
+                    let place = bin.insert(width, height, self.rotations)?;
+                    bins.push(bin);
+                    Some((bins.len() - 1, place))
+                });
+
+            let Some((sheet, (x, y, rotated))) = placed else {
+                oversized.push(index);
+                continue;
+            };
+
+            let (width, height) = if rotated {
+                (height, width)
+            } else {
+                (width, height)
+            };
+            let min_x = -self.sheet_width / 2. + self.margin + x;
+            let max_y = self.sheet_height / 2. - self.margin - y;
+            placements.push(Placement {
+                index,
+                sheet,
+                area: BoundingBox::mins_maxs(
+                    min_x,
+                    max_y - (height - self.spacing),
+                    min_x + (width - self.spacing),
+                    max_y,
+                ),
+                rotated,
+            });
+        }
+
+        placements.sort_by_key(|placement| (placement.sheet, placement.index));
+        oversized.sort();
+
+        (placements, oversized)
+    }
+
+    /// Pack the shapes on sheets
+    pub fn pack(&self) -> Packed {
+        let (placements, oversized) = self.placements();
+
+        let mut sheets: Vec<(Vec<Placement>, Vec<Shape>)> = vec![];
+        for placement in placements {
+            if placement.sheet == sheets.len() {
+                sheets.push((vec![], vec![]));
+            }
+
+            let shape = if placement.rotated {
+                self.shapes[placement.index]
+                    .clone()
+                    .with_rotate(Rotation2::new(FRAC_PI_2))
+            } else {
+                self.shapes[placement.index].clone()
+            };
+            let center = shape.local_bounding_box().center();
+            let shape = shape.with_translate(placement.area.as_unparticular().center() - center);
+
+            let (placements, shapes) = &mut sheets[placement.sheet];
+            placements.push(placement);
+            shapes.push(shape);
+        }
+
+        Packed {
+            sheets: sheets
+                .into_iter()
+                .map(|(placements, shapes)| Sheet {
+                    placements,
+                    shape: Shape::Group(Group {
+                        shapes,
+                        ..Default::default()
+                    }),
+                })
+                .collect(),
+            oversized,
+        }
+    }
+}
+
+/// Free area of a bin, from its top left corner, Y going down
+#[derive(Debug, Clone, Copy)]
+struct Free {
+    x: f32,
+    y: f32,
+    width: f32,
+    height: f32,
+}
+impl Free {
+    fn contains(&self, other: &Free) -> bool {
+        other.x >= self.x
+            && other.y >= self.y
+            && other.x + other.width <= self.x + self.width
+            && other.y + other.height <= self.y + self.height
+    }
+}
+
+/// Maximal rectangles bin packing, with the best short side fit heuristic
+struct MaxRects {
+    free: Vec<Free>,
+}
+impl MaxRects {
+    fn new(width: f32, height: f32) -> Self {
+        MaxRects {
+            free: if width > 0. && height > 0. {
+                vec![Free {
+                    x: 0.,
+                    y: 0.,
+                    width,
+                    height,
+                }]
+            } else {
+                vec![]
+            },
+        }
+    }
+
+    /// Top left corner of the area given to a `width` x `height` rectangle, and whether it is rotated
+    fn insert(&mut self, width: f32, height: f32, rotations: bool) -> Option<(f32, f32, bool)> {
+        let orientations: &[(f32, f32, bool)] = if rotations {
+            &[(width, height, false), (height, width, true)]
+        } else {
+            &[(width, height, false)]
+        };
+
+        let (free, width, height, rotated) = self
+            .free
+            .iter()
+            .flat_map(|free| {
+                orientations
+                    .iter()
+                    .filter(|(w, h, _)| *w <= free.width && *h <= free.height)
+                    .map(move |(w, h, rotated)| (free, *w, *h, *rotated))
+            })
+            .min_by(|(f1, w1, h1, _), (f2, w2, h2, _)| {
+                let score = |f: &Free, w: f32, h: f32| {
+                    let (dw, dh) = (f.width - w, f.height - h);
+                    (dw.min(dh), dw.max(dh))
+                };
+                let (short1, long1) = score(f1, *w1, *h1);
+                let (short2, long2) = score(f2, *w2, *h2);
+                short1.total_cmp(&short2).then(long1.total_cmp(&long2))
+            })
+            .map(|(free, w, h, rotated)| (*free, w, h, rotated))?;
+
+        let used = Free {
+            x: free.x,
+            y: free.y,
+            width,
+            height,
+        };
+        self.split(&used);
+
+        Some((used.x, used.y, rotated))
+    }
+
+    /// Remove `used` from the free areas, keeping the largest free rectangles around it
+    fn split(&mut self, used: &Free) {
+        let mut free = Vec::with_capacity(self.free.len() + 4);
+        for f in &self.free {
+            let overlaps = used.x < f.x + f.width
+                && used.x + used.width > f.x
+                && used.y < f.y + f.height
+                && used.y + used.height > f.y;
+            if !overlaps {
+                free.push(*f);
+                continue;
+            }
+
+            if used.x > f.x {
+                free.push(Free {
+                    width: used.x - f.x,
+                    ..*f
+                });
+            }
+            if used.x + used.width < f.x + f.width {
+                free.push(Free {
+                    x: used.x + used.width,
+                    width: f.x + f.width - used.x - used.width,
+                    ..*f
+                });
+            }
+            if used.y > f.y {
+                free.push(Free {
+                    height: used.y - f.y,
+                    ..*f
+                });
+            }
+            if used.y + used.height < f.y + f.height {
+                free.push(Free {
+                    y: used.y + used.height,
+                    height: f.y + f.height - used.y - used.height,
+                    ..*f
+                });
+            }
+        }
+
+        // Only keep maximal rectangles
+        let mut idx = 0;
+        while idx < free.len() {
+            let contained = free.iter().enumerate().any(|(other, f)| {
+                other != idx && f.contains(&free[idx]) && (other < idx || !free[idx].contains(f))
+            });
+            if contained {
+                free.remove(idx);
+            } else {
+                idx += 1;
+            }
+        }
+
+        self.free = free;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    fn overlaps(a: &BoundingBox<Straight>, b: &BoundingBox<Straight>, spacing: f32) -> bool {
+        a.left() < b.right() + spacing - 10e-4
+            && b.left() < a.right() + spacing - 10e-4
+            && a.bottom() < b.top() + spacing - 10e-4
+            && b.bottom() < a.top() + spacing - 10e-4
+    }
+
+    #[test]
+    fn no_overlap() {
+        let packing = Packing::default()
+            .with_sheet_width(100.)
+            .with_sheet_height(100.)
+            .with_spacing(2.)
+            .with_margin(5.)
+            .with_shapes((0..30).map(|idx| {
+                dessin2!(Rectangle(
+                    width = 5. + (idx * 7 % 20) as f32,
+                    height = 5. + (idx * 11 % 15) as f32,
+                ))
+            }));
+
+        let (placements, oversized) = packing.placements();
+        assert!(oversized.is_empty());
+        assert_eq!(placements.len(), 30);
+
+        let sheet = BoundingBox::centered([90., 90.]);
+        for (idx, a) in placements.iter().enumerate() {
+            assert!(a.area.left() >= sheet.left() - 10e-4);
+            assert!(a.area.right() <= sheet.right() + 10e-4);
+            assert!(a.area.bottom() >= sheet.bottom() - 10e-4);
+            assert!(a.area.top() <= sheet.top() + 10e-4);
+
+            for b in &placements[idx + 1..] {
+                assert!(a.sheet != b.sheet || !overlaps(&a.area, &b.area, 2.));
+            }
+        }
+    }
+
+    #[test]
+    fn several_sheets() {
+        let packed = Packing::default()
+            .with_sheet_width(20.)
+            .with_sheet_height(20.)
+            .with_shapes((0..5).map(|_| dessin2!(Rectangle(width = 10., height = 10.))))
+            .with_shape(dessin2!(Rectangle(width = 30., height = 10.)))
+            .pack();
+
+        assert_eq!(packed.sheets.len(), 2);
+        assert_eq!(packed.sheets[0].placements.len(), 4);
+        assert_eq!(packed.sheets[1].placements.len(), 1);
+        assert_eq!(packed.oversized, vec![5]);
+    }
+
+    #[test]
+    fn rotations() {
+        let tall = || dessin2!(Rectangle(width = 10., height = 30.));
+        let packing = Packing::default()
+            .with_sheet_width(30.)
+            .with_sheet_height(10.)
+            .with_shape(tall());
+
+        assert_eq!(packing.pack().oversized, vec![0]);
+
+        let packed = packing.with_rotations().pack();
+        assert!(packed.oversized.is_empty());
+        assert!(packed.sheets[0].placements[0].rotated);
+
+        let bb = packed.sheets[0].shape.local_bounding_box().straigthen();
+        assert!((bb.width() - 30.).abs() < 10e-4);
+        assert!((bb.height() - 10.).abs() < 10e-4);
+    }
+}
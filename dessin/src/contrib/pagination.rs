@@ -0,0 +1,304 @@
+use crate::prelude::*;
+use nalgebra::{Point2, Transform2};
+
+const EPSILON: f32 = 10e-4;
+
+/// Split a drawing taller than a page into consecutive pages, from top to bottom.
+///
+/// Pages break on horizontal lines crossing no shape, as low as possible on the page,
+/// so rows of a table or events of a timeline are never cut in half.
+/// With [`break_at_groups`][Pagination::break_at_groups], pages only break between the shapes of the top level group.
+///
+/// A shape taller than a page is cut at the bottom of the page, and continues on the next one.
+///
+/// ```
+/// # use dessin::prelude::*;
+/// let rows = dessin2!(for row in 0..10 {
+/// 	dessin2!(Rectangle!(stroke = (Color::BLACK, 0.1), width = 100., height = 40., translate = [0., row as f32 * -50.]))
+/// });
+///
+/// let pages = Pagination::new(rows)
+/// 	.with_page_width(210.)
+/// 	.with_page_height(297.)
+/// 	.pages();
+///
+/// // 6 rows fit in a page, the seventh one starts the next page
+/// assert_eq!(pages.len(), 2);
+/// ```
+#[derive(Debug, Clone, Shape)]
+pub struct Pagination {
+    /// Drawing to split
+    #[shape(skip)]
+    pub shape: Shape,
+
+    /// Width of a page
+    pub page_width: f32,
+
+    /// Height of a page
+    pub page_height: f32,
+
+    /// Only break pages between the shapes of the top level group
+    #[shape(bool)]
+    pub break_at_groups: bool,
+}
+impl Default for Pagination {
+    fn default() -> Self {
+        Pagination {
+            shape: Shape::default(),
+            page_width: 210.,
+            page_height: 297.,
+            break_at_groups: false,
+        }
+    }
+}
+impl Pagination {
+    /// Paginate a drawing
+    #[inline]
+    pub fn new<T: Into<Shape>>(shape: T) -> Self {
+        Pagination {
+            shape: shape.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Drawing to split
+    #[inline]
+    pub fn shape<T: Into<Shape>>(&mut self, shape: T) -> &mut Self {
+        self.shape = shape.into();
+        self
+    }
+    /// Drawing to split
+    #[inline]
+    pub fn with_shape<T: Into<Shape>>(mut self, shape: T) -> Self {
+        self.shape(shape);
+        self
+    }
+
+    /// Vertical extent, as (bottom, top), of each shape pages can't break through
+    fn spans(&self) -> Vec<(f32, f32)> {
+        let mut spans = vec![];
+        if self.break_at_groups {
+            let mut shape = &self.shape;
+            while let Shape::Style { shape: styled, .. } = shape {
+                shape = styled;
+            }
+
+            match shape {
+                Shape::Group(group) => spans.extend(group.shapes.iter().map(|shape| {
+                    let bb = shape
+                        .global_bounding_box(&group.local_transform)
+                        .straigthen();
+                    (bb.bottom(), bb.top())
+                })),
+                shape => {
+                    let bb = shape.local_bounding_box().straigthen();
+                    spans.push((bb.bottom(), bb.top()));
+                }
+            }
+        } else {
+            leaf_spans(&self.shape, &Transform2::identity(), &mut spans);
+        }
+
+        spans
+    }
+
+    /// Height of the top of each page, in the coordinates of the drawing
+    pub fn breaks(&self) -> Vec<f32> {
+        let bb = self.shape.local_bounding_box().straigthen();
+        let spans = self.spans();
+        let crosses = |y: f32| {
+            spans
+                .iter()
+                .any(|(bottom, top)| *bottom < y - EPSILON && y + EPSILON < *top)
+        };
+
+        let mut tops = vec![bb.top()];
+        let mut top = bb.top();
+        while self.page_height > 0. && top - self.page_height > bb.bottom() + EPSILON {
+            let lowest = top - self.page_height;
+            let cut = spans
+                .iter()
+                .flat_map(|(bottom, top)| [*bottom, *top])
+                .chain([lowest])
+                .filter(|y| *y >= lowest && *y < top - EPSILON && !crosses(*y))
+                .min_by(f32::total_cmp)
+                .unwrap_or(lowest);
+
+            tops.push(cut);
+            top = cut;
+        }
+
+        tops
+    }
+
+    /// Split the drawing, from the top.
+    ///
+    /// Each page is a [`Tile`] of the only column, its content at the top of the page.
+    pub fn pages(&self) -> Vec<Tile> {
+        let bb = self.shape.local_bounding_box().straigthen();
+        let tops = self.breaks();
+
+        tops.iter()
+            .enumerate()
+            .map(|(row, top)| {
+                let bottom = tops.get(row + 1).copied().unwrap_or(bb.bottom());
+                let area = BoundingBox::mins_maxs(
+                    bb.center().x - self.page_width / 2.,
+                    top - self.page_height,
+                    bb.center().x + self.page_width / 2.,
+                    *top,
+                );
+
+                let content = between(&self.shape, &Transform2::identity(), bottom, *top)
+                    .unwrap_or_default()
+                    .with_translate(Point2::origin() - area.center());
+
+                Tile {
+                    row,
+                    column: 0,
+                    area,
+                    shape: content,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Vertical extent of each shape of `shape` which is not a group nor a style
+fn leaf_spans(shape: &Shape, parent_transform: &Transform2<f32>, spans: &mut Vec<(f32, f32)>) {
+    match shape {
+        Shape::Group(Group {
+            local_transform,
+            shapes,
+            ..
+        }) => {
+            let transform = parent_transform * local_transform;
+            for shape in shapes {
+                leaf_spans(shape, &transform, spans);
+            }
+        }
+        Shape::Style { shape, .. } => leaf_spans(shape, parent_transform, spans),
+        shape => {
+            let bb = shape.global_bounding_box(parent_transform).straigthen();
+            spans.push((bb.bottom(), bb.top()));
+        }
+    }
+}
+
+/// Part of `shape` drawn between the heights `bottom` and `top`.
+///
+/// Shapes crossing these heights are kept whole.
+fn between(
+    shape: &Shape,
+    parent_transform: &Transform2<f32>,
+    bottom: f32,
+    top: f32,
+) -> Option<Shape> {
+    let bb = shape.global_bounding_box(parent_transform).straigthen();
+    if bb.bottom() >= bottom - EPSILON && bb.top() <= top + EPSILON {
+        return Some(shape.clone());
+    }
+    if bb.top() <= bottom + EPSILON || bb.bottom() >= top - EPSILON {
+        return None;
+    }
+
+    match shape {
+        Shape::Group(Group {
+            local_transform,
+            shapes,
+            metadata,
+        }) => {
+            let transform = parent_transform * local_transform;
+            Some(Shape::Group(Group {
+                local_transform: *local_transform,
+                shapes: shapes
+                    .iter()
+                    .filter_map(|shape| between(shape, &transform, bottom, top))
+                    .collect(),
+                metadata: metadata.clone(),
+            }))
+        }
+        Shape::Style {
+            fill,
+            stroke,
+            stroke_alignment,
+            filter,
+            opacity,
+            blend_mode,
+            shape,
+        } => Some(Shape::Style {
+            fill: fill.clone(),
+            stroke: *stroke,
+            stroke_alignment: *stroke_alignment,
+            filter: *filter,
+            opacity: *opacity,
+            blend_mode: *blend_mode,
+            shape: Box::new(between(shape, parent_transform, bottom, top)?),
+        }),
+        shape => Some(shape.clone()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use assert_float_eq::*;
+
+    #[test]
+    fn single_page() {
+        let pagination = Pagination::new(dessin2!(Rectangle(width = 100., height = 100.)));
+
+        assert_eq!(pagination.pages().len(), 1);
+    }
+
+    #[test]
+    fn break_between_rows() {
+        // Rows of 30 mm, 10 mm apart
+        let rows = dessin2!(for row in 0..4 {
+            dessin2!(Rectangle(
+                width = 100.,
+                height = 30.,
+                translate = [0., row as f32 * -40.]
+            ))
+        });
+        let pagination = Pagination::new(rows).with_page_height(100.);
+
+        // Two rows fit, the third one starts on the next page
+        let breaks = pagination.breaks();
+        assert_eq!(breaks.len(), 2);
+        assert_float_absolute_eq!(breaks[0] - breaks[1], 80., 10e-4);
+
+        let pages = pagination.pages();
+        let Shape::Group(Group { shapes, .. }) = &pages[1].shape else {
+            unreachable!()
+        };
+        assert_eq!(shapes.len(), 2);
+    }
+
+    #[test]
+    fn break_at_groups() {
+        // Rows of two lines, 20 mm apart
+        let row = |y: f32| {
+            dessin2!([
+                Line(from = [0., y], to = [100., y]),
+                Line(from = [0., y - 20.], to = [100., y - 20.]),
+            ])
+        };
+        let rows = dessin2!([{ row(0.) }, { row(-30.) }, { row(-60.) }]);
+
+        // Lines alone break in the middle of the second row
+        let pagination = Pagination::new(rows).with_page_height(40.);
+        assert_float_absolute_eq!(pagination.breaks()[1], -40., 10e-4);
+
+        let pagination = pagination.with_break_at_groups();
+        assert_float_absolute_eq!(pagination.breaks()[1], -30., 10e-4);
+    }
+
+    #[test]
+    fn taller_than_a_page() {
+        let pagination =
+            Pagination::new(dessin2!(Rectangle(width = 10., height = 250.))).with_page_height(100.);
+
+        assert_eq!(pagination.pages().len(), 3);
+    }
+}
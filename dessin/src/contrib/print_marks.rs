@@ -0,0 +1,265 @@
+use crate::prelude::*;
+use nalgebra::Vector2;
+
+/// Inks and tints printed in the color bars, from left to right
+const COLOR_BARS: [Color; 11] = [
+    rgb(0, 255, 255),   // Cyan
+    rgb(255, 0, 255),   // Magenta
+    rgb(255, 255, 0),   // Yellow
+    rgb(0, 0, 0),       // Black
+    rgb(255, 0, 0),     // Magenta + Yellow
+    rgb(0, 255, 0),     // Cyan + Yellow
+    rgb(0, 0, 255),     // Cyan + Magenta
+    rgb(64, 64, 64),    // 75% tint
+    rgb(128, 128, 128), // 50% tint
+    rgb(191, 191, 191), // 25% tint
+    rgb(255, 255, 255), // Paper
+];
+
+/// Crop marks around a trim box, drawn outside of it.
+///
+/// Each corner gets two lines of `length`, starting `offset` away from the trim box.
+pub(crate) fn crop_marks(trim: BoundingBox<Straight>, offset: f32, length: f32) -> Shape {
+    dessin2!(
+        for (x, y, dx, dy) in [
+            (trim.left(), trim.top(), -1., 1.),
+            (trim.right(), trim.top(), 1., 1.),
+            (trim.right(), trim.bottom(), 1., -1.),
+            (trim.left(), trim.bottom(), -1., -1.),
+        ] {
+            dessin2!([
+                Line(
+                    from = [x, y + dy * offset],
+                    to = [x, y + dy * (offset + length)]
+                ),
+                Line(
+                    from = [x + dx * offset, y],
+                    to = [x + dx * (offset + length), y]
+                ),
+            ])
+        }
+    )
+}
+
+/// Print finishing for professional print handoff.
+///
+/// Decorates a page, centered around (0, 0), with crop marks and optionaly registration marks and color bars.
+/// All marks are drawn outside of the trim box, use [`sheet_size`][PrintMarks::sheet_size] to know the size of the sheet to export.
+///
+/// ```
+/// # use dessin::prelude::*;
+/// let page = dessin2!(Rectangle!(fill = Color::RED, width = 210., height = 297.));
+///
+/// let marks = dessin2!(PrintMarks(
+/// 	shape = page,
+/// 	trim_width = 210.,
+/// 	trim_height = 297.,
+/// 	registration_marks,
+/// 	color_bars,
+/// ));
+///
+/// let (width, height) = marks.sheet_size();
+/// let sheet = Shape::from(marks);
+/// ```
+#[derive(Debug, Clone, Shape)]
+pub struct PrintMarks {
+    /// Content of the page
+    #[shape(skip)]
+    pub shape: Shape,
+
+    /// Width of the page once trimmed
+    pub trim_width: f32,
+
+    /// Height of the page once trimmed
+    pub trim_height: f32,
+
+    /// Distance between the trim box and the marks.
+    /// Content bleeding out of the trim box should not go further.
+    pub bleed: f32,
+
+    /// Length of the crop marks, and size of the registration marks and color bars
+    pub mark_length: f32,
+
+    /// Stroke width of the marks
+    pub mark_thickness: f32,
+
+    /// Add a registration mark in the middle of each side
+    #[shape(bool)]
+    pub registration_marks: bool,
+
+    /// Add color calibration bars under the bottom left corner.
+    ///
+    /// They stand between the crop marks of the corner and the bottom registration mark, and shrink to fit narrow pages.
+    #[shape(bool)]
+    pub color_bars: bool,
+}
+impl Default for PrintMarks {
+    fn default() -> Self {
+        PrintMarks {
+            shape: Shape::default(),
+            trim_width: 210.,
+            trim_height: 297.,
+            bleed: 3.,
+            mark_length: 5.,
+            mark_thickness: 0.1,
+            registration_marks: false,
+            color_bars: false,
+        }
+    }
+}
+impl PrintMarks {
+    /// Content of the page
+    #[inline]
+    pub fn shape<T: Into<Shape>>(&mut self, shape: T) -> &mut Self {
+        self.shape = shape.into();
+        self
+    }
+    /// Content of the page
+    #[inline]
+    pub fn with_shape<T: Into<Shape>>(mut self, shape: T) -> Self {
+        self.shape(shape);
+        self
+    }
+
+    /// Size of the sheet needed to print the page with all its marks
+    pub fn sheet_size(&self) -> (f32, f32) {
+        let margin = 2. * (self.bleed + self.mark_length);
+        (self.trim_width + margin, self.trim_height + margin)
+    }
+}
+
+impl From<PrintMarks> for Shape {
+    fn from(
+        PrintMarks {
+            shape,
+            trim_width,
+            trim_height,
+            bleed,
+            mark_length,
+            mark_thickness,
+            registration_marks,
+            color_bars,
+        }: PrintMarks,
+    ) -> Self {
+        let trim = BoundingBox::centered([trim_width, trim_height]);
+        let distance = bleed + mark_length / 2.;
+
+        let crop_marks = crop_marks(trim, bleed, mark_length);
+
+        // Clear of the crop mark on the left, and of the bottom registration mark or crop mark on the right
+        let gap = mark_length / 2.;
+        let start = trim.left() + gap;
+        let end = if registration_marks {
+            -mark_length / 2. - gap
+        } else {
+            trim.right() - gap
+        };
+        let bar_width = ((end - start) / COLOR_BARS.len() as f32).min(mark_length);
+
+        let registration_marks = dessin2!(
+            for center in (if registration_marks {
+                vec![
+                    Vector2::new(0., trim.top() + distance),
+                    Vector2::new(trim.right() + distance, 0.),
+                    Vector2::new(0., trim.bottom() - distance),
+                    Vector2::new(trim.left() - distance, 0.),
+                ]
+            } else {
+                vec![]
+            }) {
+                let radius = mark_length / 2.;
+                dessin2!([
+                    Circle(radius = radius * 0.6),
+                    Line(from = [-radius, 0.], to = [radius, 0.]),
+                    Line(from = [0., -radius], to = [0., radius]),
+                ] > (translate = center))
+            }
+        );
+
+        let color_bars = dessin2!(
+            for (idx, color) in (if color_bars && bar_width > 0. {
+                COLOR_BARS.iter().enumerate().collect::<Vec<_>>()
+            } else {
+                vec![]
+            }) {
+                dessin2!(Rectangle!(
+                    fill = *color,
+                    stroke = (Color::BLACK, mark_thickness),
+                    width = bar_width,
+                    height = mark_length,
+                    translate = [
+                        start + (idx as f32 + 0.5) * bar_width,
+                        trim.bottom() - distance,
+                    ],
+                ))
+            }
+        );
+
+        let marks = Style::new(dessin2!([{ crop_marks }, { registration_marks }]))
+            .with_stroke((Color::BLACK, mark_thickness));
+
+        dessin2!([{ shape }, { marks }, { color_bars }])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{display_list::DisplayList, prelude::*};
+    use assert_float_eq::*;
+
+    fn overlaps(a: &BoundingBox<Straight>, b: &BoundingBox<Straight>) -> bool {
+        a.left() < b.right() && b.left() < a.right() && a.bottom() < b.top() && b.bottom() < a.top()
+    }
+
+    /// Boxes of the color bars, the only filled items, and of the other marks
+    fn bars_and_marks(marks: PrintMarks) -> (Vec<BoundingBox<Straight>>, Vec<BoundingBox<Straight>>) {
+        let sheet = Shape::from(marks.with_shape(Shape::default()));
+        let (bars, marks): (Vec<_>, Vec<_>) = DisplayList::new(&sheet)
+            .items
+            .into_iter()
+            .partition(|item| item.fill.is_some());
+
+        (
+            bars.iter().map(|item| item.bounding_box()).collect(),
+            marks.iter().map(|item| item.bounding_box()).collect(),
+        )
+    }
+
+    #[test]
+    fn sheet_size() {
+        let marks = PrintMarks::default()
+            .with_trim_width(100.)
+            .with_trim_height(50.)
+            .with_bleed(3.)
+            .with_mark_length(5.)
+            .with_registration_marks()
+            .with_color_bars();
+
+        let (width, height) = marks.sheet_size();
+        let bb = Shape::from(marks).local_bounding_box().straigthen();
+
+        assert_float_absolute_eq!(bb.width(), width, 10e-4);
+        assert_float_absolute_eq!(bb.height(), height, 10e-4);
+        assert_float_absolute_eq!(width, 116., 10e-4);
+        assert_float_absolute_eq!(height, 66., 10e-4);
+    }
+
+    #[test]
+    fn marks_do_not_overlap() {
+        for (trim_width, registration_marks) in [(100., true), (210., true), (40., false)] {
+            let mut marks = PrintMarks::default()
+                .with_trim_width(trim_width)
+                .with_trim_height(50.)
+                .with_color_bars();
+            marks.registration_marks = registration_marks;
+            let trim = BoundingBox::centered([trim_width, 50.]);
+
+            let (bars, marks) = bars_and_marks(marks);
+            assert_eq!(bars.len(), 11);
+            for bar in &bars {
+                assert!(bar.top() < trim.bottom());
+                assert!(marks.iter().all(|mark| !overlaps(bar, mark)));
+            }
+        }
+    }
+}
@@ -2,11 +2,33 @@ use crate::prelude::*;
 use nalgebra::{Point2, Scale2, Transform2};
 
 /// Rectangle
+///
+/// Centered on its origin, unless an [`anchor`][Rectangle::anchor] puts a corner or an edge there,
+/// so translating it places that point, and rotating it turns around that point.
+///
+/// ```
+/// # use dessin::prelude::*;
+/// # use nalgebra::Point2;
+/// let rectangle = dessin2!(Rectangle(
+/// 	anchor = BoxAnchor::BottomLeft,
+/// 	width = 10.,
+/// 	height = 5.,
+/// 	translate = [2., 3.],
+/// ));
+///
+/// let bb = Shape::from(rectangle).local_bounding_box().straigthen();
+/// assert!((bb.bottom_left() - Point2::new(2., 3.)).magnitude() < 10e-5);
+/// assert!((bb.top_right() - Point2::new(12., 8.)).magnitude() < 10e-5);
+/// ```
 #[derive(Default, Debug, Clone, PartialEq, Shape)]
 pub struct Rectangle {
     /// [`ShapeOp`]
     #[local_transform]
     pub local_transform: Transform2<f32>,
+
+    /// Point of the rectangle on its origin
+    #[shape(into)]
+    pub anchor: BoxAnchor,
 }
 impl Rectangle {
     /// Width (x axis)
@@ -37,11 +59,17 @@ impl Rectangle {
 }
 
 impl From<Rectangle> for Curve {
-    fn from(Rectangle { local_transform }: Rectangle) -> Self {
-        let top_left = Point2::new(-0.5, 0.5);
-        let top_right = Point2::new(0.5, 0.5);
-        let bottom_right = Point2::new(0.5, -0.5);
-        let bottom_left = Point2::new(-0.5, -0.5);
+    fn from(
+        Rectangle {
+            local_transform,
+            anchor,
+        }: Rectangle,
+    ) -> Self {
+        let offset = anchor.direction() / 2.;
+        let top_left = Point2::new(-0.5, 0.5) - offset;
+        let top_right = Point2::new(0.5, 0.5) - offset;
+        let bottom_right = Point2::new(0.5, -0.5) - offset;
+        let bottom_left = Point2::new(-0.5, -0.5) - offset;
 
         dessin2!(Curve(
             transform = local_transform,
@@ -78,6 +106,34 @@ mod tests {
         assert_eq!(base, base_3);
     }
 
+    #[test]
+    fn anchor() {
+        let corner = |anchor: BoxAnchor| {
+            let Shape::Curve(curve) = dessin2!(
+                Rectangle(
+                    anchor = anchor,
+                    width = 4.,
+                    height = 2.,
+                    rotate = Rotation2::new(FRAC_PI_2),
+                    translate = [1., 1.],
+                ) > ()
+            ) else {
+                unreachable!()
+            };
+            let KeypointPosition::Point(top_left) =
+                curve.position(&Transform2::default()).keypoints[0]
+            else {
+                unreachable!()
+            };
+            top_left
+        };
+
+        // Rotated around the anchor, which stays on the translation
+        assert!((corner(BoxAnchor::TopLeft) - Point2::new(1., 1.)).magnitude() < EPS);
+        assert!((corner(BoxAnchor::Center) - Point2::new(0., -1.)).magnitude() < EPS);
+        assert!((corner(BoxAnchor::Right) - Point2::new(0., -3.)).magnitude() < EPS);
+    }
+
     #[test]
     fn parent_rotate_text_scale() {
         let base = dessin2!(Rectangle(width = 2., height = 3., translate = [1., 2.],));
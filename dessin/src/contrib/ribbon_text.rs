@@ -0,0 +1,238 @@
+use crate::{font::FontRef, prelude::*};
+use fontdue::{Font, FontSettings};
+use nalgebra::{Point2, Transform2, Vector2};
+
+/// Text too long for a curve, continued on successive offsets of the same curve.
+///
+/// Each line of text follows a copy of the curve, offset by `line_spacing` from the previous one:
+/// outward for closed curves, or inward with a negative spacing.
+/// Open curves have no outside, so their copies are moved down instead.
+///
+/// The text is split where it no longer fits the length of a line, on a whitespace if there is one.
+///
+/// ```
+/// # use dessin::prelude::*;
+/// let ring = dessin2!(RibbonText!(
+/// 	fill = Color::BLACK,
+/// 	text = "1000101110100101100010111000101001010111010011101001010111001010100111001010",
+/// 	curve = Circle::default().with_radius(10.),
+/// 	font_size = 1.,
+/// 	line_spacing = 1.5,
+/// ));
+/// ```
+#[derive(Debug, Clone, PartialEq, Shape)]
+pub struct RibbonText {
+    /// [`ShapeOp`]
+    #[local_transform]
+    pub local_transform: Transform2<f32>,
+
+    /// The text
+    #[shape(into)]
+    pub text: String,
+
+    /// Curve followed by the first line
+    #[shape(into)]
+    pub curve: Curve,
+
+    /// Font size
+    pub font_size: f32,
+
+    /// Font weight
+    pub font_weight: FontWeight,
+
+    /// Font
+    #[shape(into_some)]
+    pub font: Option<FontRef>,
+
+    /// Distance between two successive lines
+    pub line_spacing: f32,
+
+    /// Stop after this number of lines, even if the text is not over
+    #[shape(some)]
+    pub max_lines: Option<usize>,
+}
+impl Default for RibbonText {
+    fn default() -> Self {
+        RibbonText {
+            local_transform: Default::default(),
+            text: Default::default(),
+            curve: Default::default(),
+            font_size: 10.,
+            font_weight: Default::default(),
+            font: None,
+            line_spacing: 12.,
+            max_lines: None,
+        }
+    }
+}
+impl RibbonText {
+    /// Curve of the line at `index`, starting from 0
+    pub fn line_curve(&self, index: usize) -> Curve {
+        let distance = self.line_spacing * index as f32;
+        if index == 0 || !self.curve.closed {
+            return self
+                .curve
+                .clone()
+                .with_translate(Vector2::new(0., -distance));
+        }
+
+        let offset = self
+            .curve
+            .position(&Transform2::identity())
+            .offset(
+                distance,
+                LineJoin::Miter,
+                Stroke::DEFAULT_MITER_LIMIT,
+                self.tolerance(),
+            );
+
+        Curve {
+            local_transform: Default::default(),
+            keypoints: offset
+                .keypoints
+                .into_iter()
+                .map(|k| match k {
+                    KeypointPosition::Point(p) => Keypoint::Point(p),
+                    KeypointPosition::Bezier(b) => Keypoint::Bezier(b),
+                })
+                .collect(),
+            closed: true,
+            start_marker: None,
+            end_marker: None,
+            fill_rule: FillRule::NonZero,
+        }
+    }
+
+    /// Text of each line, with the curve it follows
+    pub fn lines(&self) -> Vec<(String, Curve)> {
+        let fonts = crate::font::get(self.font.clone().unwrap_or_default());
+        let raw_font = match fonts.get(self.font_weight) {
+            crate::font::Font::OTF(bytes) => bytes,
+            crate::font::Font::TTF(bytes) => bytes,
+        };
+        let font = Font::from_bytes(raw_font.as_slice(), FontSettings::default()).unwrap();
+
+        let mut lines = vec![];
+        let mut rest = self.text.trim_start();
+        while !rest.is_empty() && self.max_lines.is_none_or(|max| lines.len() < max) {
+            let curve = self.line_curve(lines.len());
+            let length = polyline_length(
+                &curve
+                    .position(&Transform2::identity())
+                    .flatten(self.tolerance()),
+            );
+
+            // Longest prefix fitting the line, at least one character so the text always progresses
+            let ends = rest
+                .char_indices()
+                .map(|(idx, c)| idx + c.len_utf8())
+                .collect::<Vec<_>>();
+            let fitting =
+                ends.partition_point(|end| size_of(&font, &rest[..*end], self.font_size) <= length);
+            let fit = ends[fitting.saturating_sub(1)];
+
+            // Prefer breaking between words
+            let line = match rest[..fit].rfind(char::is_whitespace) {
+                Some(space) if fit < rest.len() && space > 0 => &rest[..space],
+                _ => &rest[..fit],
+            };
+
+            rest = rest[line.len()..].trim_start();
+            lines.push((line.trim_end().to_string(), curve));
+        }
+
+        lines
+    }
+
+    fn tolerance(&self) -> f32 {
+        (self.font_size / 50.).max(10e-4)
+    }
+}
+
+fn polyline_length(points: &[Point2<f32>]) -> f32 {
+    points.windows(2).map(|w| (w[1] - w[0]).magnitude()).sum()
+}
+
+impl From<RibbonText> for Shape {
+    fn from(ribbon: RibbonText) -> Self {
+        let lines = ribbon
+            .lines()
+            .into_iter()
+            .map(|(text, curve)| {
+                Shape::Text(Text {
+                    local_transform: Default::default(),
+                    text,
+                    align: TextAlign::Left,
+                    vertical_align: TextVerticalAlign::Bottom,
+                    font_weight: ribbon.font_weight,
+                    on_curve: Some(curve),
+                    font_size: ribbon.font_size,
+                    font: ribbon.font.clone(),
+                })
+            })
+            .collect();
+
+        Shape::Group(Group {
+            local_transform: ribbon.local_transform,
+            shapes: lines,
+            metadata: vec![],
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    #[test]
+    fn text_continues_on_next_ring() {
+        let text = "0".repeat(200);
+        let ribbon = dessin2!(RibbonText(
+            text = text.clone(),
+            curve = Circle::default().with_radius(5.),
+            font_size = 1.,
+            line_spacing = 1.5,
+        ));
+
+        let lines = ribbon.lines();
+        assert!(lines.len() > 1);
+        assert_eq!(
+            lines
+                .iter()
+                .map(|(line, _)| line.as_str())
+                .collect::<String>(),
+            text
+        );
+
+        // Outer rings are longer, so fit more text
+        assert!(lines[1].0.len() > lines[0].0.len());
+    }
+
+    #[test]
+    fn split_on_whitespace() {
+        let ribbon = dessin2!(RibbonText(
+            text = "aaaa bbbb cccc dddd eeee ffff",
+            curve = Line::default().with_from([0., 0.]).with_to([12., 0.]),
+            font_size = 2.,
+            line_spacing = 3.,
+        ));
+
+        for (line, _) in ribbon.lines() {
+            assert!(!line.starts_with(' ') && !line.ends_with(' '));
+            assert!(line.split(' ').all(|word| word.len() == 4));
+        }
+    }
+
+    #[test]
+    fn max_lines() {
+        let ribbon = dessin2!(RibbonText(
+            text = "0".repeat(200),
+            curve = Circle::default().with_radius(5.),
+            font_size = 1.,
+            line_spacing = 1.5,
+            max_lines = 2,
+        ));
+
+        assert_eq!(ribbon.lines().len(), 2);
+    }
+}
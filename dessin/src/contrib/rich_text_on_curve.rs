@@ -0,0 +1,258 @@
+use crate::{font::FontRef, prelude::*};
+use fontdue::{Font, FontSettings};
+use nalgebra::{Point2, Transform2};
+
+/// Run of text with its own color and weight, in a [`RichTextOnCurve`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextSpan {
+    /// The text
+    pub text: String,
+    /// Color of the text
+    pub color: Color,
+    /// Font weight
+    pub font_weight: FontWeight,
+}
+impl<T: Into<String>> From<(T, Color)> for TextSpan {
+    fn from((text, color): (T, Color)) -> Self {
+        TextSpan {
+            text: text.into(),
+            color,
+            font_weight: FontWeight::Regular,
+        }
+    }
+}
+impl<T: Into<String>> From<(T, Color, FontWeight)> for TextSpan {
+    fn from((text, color, font_weight): (T, Color, FontWeight)) -> Self {
+        TextSpan {
+            text: text.into(),
+            color,
+            font_weight,
+        }
+    }
+}
+
+/// Spans of text following a curve one after the other, each with its own color and weight.
+///
+/// The spans are placed along the curve as a single text aligned by `align`,
+/// from the start of the curve, around its middle or up to its end.
+/// Text past the end of the curve is not drawn.
+///
+/// ```
+/// # use dessin::prelude::*;
+/// let seal = dessin2!(RichTextOnCurve(
+/// 	curve = Arc::default().with_radius(20.).with_start_angle(Angle::degrees(170.)).with_end_angle(Angle::degrees(10.)),
+/// 	span = ("CERTIFIED ", Color::BLACK),
+/// 	span = ("ORGANIC", rgb(0, 128, 0), FontWeight::Bold),
+/// 	span = (" 2024", Color::BLACK),
+/// 	font_size = 4.,
+/// 	align = TextAlign::Center,
+/// ));
+///
+/// assert_eq!(seal.span_offsets().len(), 3);
+/// ```
+#[derive(Debug, Clone, PartialEq, Shape)]
+pub struct RichTextOnCurve {
+    /// [`ShapeOp`]
+    #[local_transform]
+    pub local_transform: Transform2<f32>,
+
+    /// Spans of text, in order
+    #[shape(skip)]
+    pub spans: Vec<TextSpan>,
+
+    /// Curve followed by the text
+    #[shape(into)]
+    pub curve: Curve,
+
+    /// Font size
+    pub font_size: f32,
+
+    /// Font
+    #[shape(into_some)]
+    pub font: Option<FontRef>,
+
+    /// Placement of the text along the curve
+    pub align: TextAlign,
+}
+impl Default for RichTextOnCurve {
+    fn default() -> Self {
+        RichTextOnCurve {
+            local_transform: Default::default(),
+            spans: vec![],
+            curve: Default::default(),
+            font_size: 10.,
+            font: None,
+            align: TextAlign::Left,
+        }
+    }
+}
+impl RichTextOnCurve {
+    /// Add a span after the others
+    #[inline]
+    pub fn span<S: Into<TextSpan>>(&mut self, span: S) -> &mut Self {
+        self.spans.push(span.into());
+        self
+    }
+    /// Add a span after the others
+    #[inline]
+    pub fn with_span<S: Into<TextSpan>>(mut self, span: S) -> Self {
+        self.span(span);
+        self
+    }
+
+    /// Distance along the curve where each span starts
+    pub fn span_offsets(&self) -> Vec<f32> {
+        let widths = self
+            .spans
+            .iter()
+            .map(|span| {
+                let fonts = crate::font::get(self.font.clone().unwrap_or_default());
+                let raw_font = match fonts.get(span.font_weight) {
+                    crate::font::Font::OTF(bytes) => bytes,
+                    crate::font::Font::TTF(bytes) => bytes,
+                };
+                let font = Font::from_bytes(raw_font.as_slice(), FontSettings::default()).unwrap();
+                size_of(&font, &span.text, self.font_size)
+            })
+            .collect::<Vec<_>>();
+
+        let length = polyline_length(&self.points());
+        let total = widths.iter().sum::<f32>();
+        let mut offset = match self.align {
+            TextAlign::Left => 0.,
+            TextAlign::Center => (length - total) / 2.,
+            TextAlign::Right => length - total,
+        };
+
+        widths
+            .into_iter()
+            .map(|width| {
+                let start = offset;
+                offset += width;
+                start
+            })
+            .collect()
+    }
+
+    fn points(&self) -> Vec<Point2<f32>> {
+        let tolerance = (self.font_size / 50.).max(10e-4);
+        self.curve
+            .position(&Transform2::identity())
+            .flatten(tolerance)
+    }
+}
+
+fn polyline_length(points: &[Point2<f32>]) -> f32 {
+    points.windows(2).map(|w| (w[1] - w[0]).magnitude()).sum()
+}
+
+/// Part of the polyline `points` from `distance` along it to its end, `None` if it is shorter
+fn polyline_from(points: &[Point2<f32>], distance: f32) -> Option<Vec<Point2<f32>>> {
+    let mut walked = 0.;
+    for (idx, segment) in points.windows(2).enumerate() {
+        let length = (segment[1] - segment[0]).magnitude();
+        if walked + length > distance {
+            let t = ((distance - walked) / length).max(0.);
+            let start = segment[0] + (segment[1] - segment[0]) * t;
+            return Some(
+                std::iter::once(start)
+                    .chain(points[idx + 1..].iter().copied())
+                    .collect(),
+            );
+        }
+        walked += length;
+    }
+    None
+}
+
+impl From<RichTextOnCurve> for Shape {
+    fn from(text: RichTextOnCurve) -> Self {
+        let points = text.points();
+        let spans = text
+            .span_offsets()
+            .into_iter()
+            .zip(&text.spans)
+            .filter_map(|(offset, span)| {
+                let points = polyline_from(&points, offset)?;
+                let curve = Curve {
+                    local_transform: Default::default(),
+                    keypoints: points.into_iter().map(Keypoint::Point).collect(),
+                    closed: false,
+                    start_marker: None,
+                    end_marker: None,
+                    fill_rule: FillRule::NonZero,
+                };
+
+                Some(Shape::Style {
+                    fill: Some(Fill::Color(span.color)),
+                    stroke: None,
+                    stroke_alignment: StrokeAlignment::Center,
+                    filter: None,
+                    opacity: 1.,
+                    blend_mode: BlendMode::Normal,
+                    shape: Box::new(Shape::Text(Text {
+                        local_transform: Default::default(),
+                        text: span.text.clone(),
+                        align: TextAlign::Left,
+                        vertical_align: TextVerticalAlign::Bottom,
+                        font_weight: span.font_weight,
+                        on_curve: Some(curve),
+                        font_size: text.font_size,
+                        font: text.font.clone(),
+                    })),
+                })
+            })
+            .collect();
+
+        Shape::Group(Group {
+            local_transform: text.local_transform,
+            shapes: spans,
+            metadata: vec![],
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    #[test]
+    fn spans_follow_each_other() {
+        let text = dessin2!(RichTextOnCurve(
+            curve = Line::default().with_from([0., 0.]).with_to([100., 0.]),
+            span = ("ab", Color::RED),
+            span = ("cd", Color::BLUE, FontWeight::Bold),
+            font_size = 5.,
+            align = TextAlign::Right,
+        ));
+
+        let offsets = text.span_offsets();
+        assert!(offsets[0] < offsets[1] && offsets[1] < 100.);
+
+        let Shape::Group(Group { shapes, .. }) = text.into() else {
+            unreachable!()
+        };
+        let Shape::Style {
+            fill: Some(Fill::Color(color)),
+            shape,
+            ..
+        } = &shapes[1]
+        else {
+            unreachable!()
+        };
+        assert_eq!(*color, Color::BLUE);
+
+        // The second span starts where the first one ends, on the curve
+        let Shape::Text(Text {
+            on_curve: Some(curve),
+            ..
+        }) = shape.as_ref()
+        else {
+            unreachable!()
+        };
+        let Keypoint::Point(start) = curve.keypoints[0] else {
+            unreachable!()
+        };
+        assert!((start.x - offsets[1]).abs() < 10e-4);
+    }
+}
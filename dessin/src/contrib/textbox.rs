@@ -38,6 +38,19 @@ pub struct TextBox {
     /// Font
     #[shape(into_some)]
     pub font: Option<FontRef>,
+
+    /// Stretch the spaces between words so lines span the whole width, except the last line of each paragraph
+    #[shape(bool)]
+    pub justify: bool,
+
+    /// Widest space between the words of a justified line, in normal spaces.
+    ///
+    /// Lines which would need wider spaces are left as they are.
+    pub max_word_spacing: f32,
+
+    /// Break words which don't fit at the end of a line
+    #[shape(some)]
+    pub hyphenation: Option<Hyphenation>,
 }
 impl Default for TextBox {
     fn default() -> Self {
@@ -52,6 +65,9 @@ impl Default for TextBox {
             width: f32::MAX,
             height: Default::default(),
             font: Default::default(),
+            justify: false,
+            max_word_spacing: 2.,
+            hyphenation: None,
         }
     }
 }
@@ -70,6 +86,13 @@ impl TextBox {
     }
 }
 
+/// Line of a paragraph
+struct Line {
+    words: Vec<String>,
+    /// Last line of its paragraph, which is never justified
+    last: bool,
+}
+
 impl From<TextBox> for Shape {
     fn from(
         TextBox {
@@ -83,6 +106,9 @@ impl From<TextBox> for Shape {
             vertical_align,
             font_weight,
             font,
+            justify,
+            max_word_spacing,
+            hyphenation,
         }: TextBox,
     ) -> Self {
         let font_ref = font.clone();
@@ -93,45 +119,71 @@ impl From<TextBox> for Shape {
         };
 
         let font = Font::from_bytes(raw_font.as_slice(), FontSettings::default()).unwrap();
+        let space = size_of(&font, " ", font_size);
 
         let mut lines = vec![];
         let mut height = height.unwrap_or(f32::MAX);
 
-        for line in text.lines() {
+        for paragraph in text.lines() {
+            let mut words: Vec<String> = vec![];
             let mut len = 0.;
-            let mut acc = String::new();
 
             if height - font_size < 0. {
                 break;
             }
 
-            for word in line.split_whitespace() {
-                if word.is_empty() {
-                    continue;
-                }
-
-                let word_size = size_of(&font, word, font_size);
+            for word in paragraph.split_whitespace() {
+                let mut word = word.to_owned();
+
+                loop {
+                    let word_size = size_of(&font, &word, font_size);
+                    let room = if words.is_empty() {
+                        width
+                    } else {
+                        width - len - space
+                    };
+
+                    if word_size <= room {
+                        len = if words.is_empty() {
+                            word_size
+                        } else {
+                            len + space + word_size
+                        };
+                        words.push(word);
+                        break;
+                    }
 
-                if len + word_size > width {
-                    lines.push(std::mem::take(&mut acc));
+                    // Longest start of the word fitting in the rest of the line
+                    let split = hyphenation.as_ref().and_then(|hyphenation| {
+                        let parts = hyphenation.hyphenate(&word);
+                        (1..parts.len())
+                            .rev()
+                            .map(|count| (parts[..count].concat() + "-", parts[count..].concat()))
+                            .find(|(head, _)| size_of(&font, head, font_size) <= room)
+                    });
+
+                    if let Some((head, tail)) = split {
+                        words.push(head);
+                        word = tail;
+                    } else if words.is_empty() {
+                        // Too wide for any line
+                        len = word_size;
+                        words.push(word);
+                        break;
+                    }
 
-                    acc = word.to_owned();
-                    len = word_size;
+                    lines.push(Line {
+                        words: std::mem::take(&mut words),
+                        last: false,
+                    });
+                    len = 0.;
 
                     height -= font_size + line_spacing;
-                } else {
-                    len += word_size;
-
-                    if !acc.is_empty() {
-                        acc.push(' ');
-                    }
-
-                    acc.push_str(word)
                 }
             }
 
-            if !acc.is_empty() {
-                lines.push(acc)
+            if !words.is_empty() {
+                lines.push(Line { words, last: true })
             }
         }
 
@@ -141,18 +193,51 @@ impl From<TextBox> for Shape {
             TextVerticalAlign::Top => (TextVerticalAlign::Bottom, -1.),
         };
 
+        let text = |text: String, align: TextAlign| -> Shape {
+            dessin2!(Text(
+                { text },
+                { align },
+                { vertical_align },
+                { font_weight },
+                { font_size },
+                maybe_font = font_ref.clone(),
+            ))
+            .into()
+        };
+
         dessin2!(
             VerticalLayout(
-                extend = lines.into_iter().map(|text| {
-                    dessin2!(Text(
-                        { text },
-                        { align },
-                        { vertical_align },
-                        { font_weight },
-                        { font_size },
-                        maybe_font = font_ref.clone(),
-                    ))
-                    .into()
+                extend = lines.into_iter().map(|Line { words, last }| {
+                    let sizes = words
+                        .iter()
+                        .map(|word| size_of(&font, word, font_size))
+                        .collect::<Vec<_>>();
+                    let spaces = (words.len() - 1) as f32;
+                    let spacing = (width - sizes.iter().sum::<f32>()) / spaces;
+
+                    if !justify || last || spaces == 0. || spacing > max_word_spacing * space {
+                        return text(words.join(" "), align);
+                    }
+
+                    // Justified lines span the box, wherever the text is aligned
+                    let mut x = match align {
+                        TextAlign::Left => 0.,
+                        TextAlign::Center => -width / 2.,
+                        TextAlign::Right => -width,
+                    };
+                    let words = words
+                        .into_iter()
+                        .zip(sizes)
+                        .map(|(word, size)| {
+                            let word = text(word, TextAlign::Left).with_translate([x, 0.]);
+                            x += size + spacing;
+                            word
+                        })
+                        .collect();
+                    Shape::Group(Group {
+                        shapes: words,
+                        ..Default::default()
+                    })
                 }),
                 gap = line_spacing,
                 transform = local_transform,
@@ -267,3 +352,55 @@ fn should_break() {
     let bb = shape.local_bounding_box();
     assert_float_absolute_eq!(bb.height(), 10., 0.001);
 }
+
+#[test]
+fn hyphenated_and_justified() {
+    use assert_float_eq::*;
+
+    let width_of = |text: &str| {
+        dessin2!(Text(font_size = 5., { text }) > ())
+            .local_bounding_box()
+            .straigthen()
+            .width()
+    };
+    let width = width_of("a hyphen-") + 1.;
+
+    let mut shape: Shape = dessin2!(
+        TextBox(
+            text = "a hyphenation",
+            font_size = 5.,
+            { width },
+            hyphenation = Hyphenation::new("hy3ph he2n hena4 hen5at 1na n2at 1tio 2io o2n"),
+            justify,
+        ) > ()
+    );
+
+    let lines = shape.get_or_mutate_as_group().shapes.clone();
+    assert_eq!(lines.len(), 2);
+
+    // Spread over the whole width
+    let Shape::Group(Group { shapes: words, .. }) = &lines[0] else {
+        unreachable!()
+    };
+    let [Shape::Text(a), Shape::Text(b)] = &words[..] else {
+        unreachable!()
+    };
+    assert_eq!((a.text.as_str(), b.text.as_str()), ("a", "hyphen-"));
+    let end = (b.local_transform * nalgebra::Point2::origin()).x + width_of("hyphen-");
+    assert_float_absolute_eq!(end, width, 0.01);
+
+    // The last line isn't justified
+    assert!(matches!(&lines[1], Shape::Text(text) if text.text == "ation"));
+
+    // Too few words to stay under the spacing limit
+    let mut shape: Shape = dessin2!(
+        TextBox(
+            text = "a hyphenation",
+            font_size = 5.,
+            width = width * 2.,
+            justify,
+        ) > ()
+    );
+    let lines = shape.get_or_mutate_as_group().shapes.clone();
+    assert!(matches!(&lines[..], [Shape::Text(text)] if text.text == "a hyphenation"));
+}
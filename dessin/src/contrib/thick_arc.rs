@@ -1,5 +1,4 @@
 use crate::prelude::*;
-use core::f32::consts::PI;
 use nalgebra::Transform2;
 
 /// An arc with a thickness
@@ -9,11 +8,13 @@ pub struct ThickArc {
     #[local_transform]
     pub local_transform: Transform2<f32>,
 
-    /// start angle in radian
-    pub start_angle: f32,
+    /// Start angle, in radians if given as a `f32`
+    #[shape(into)]
+    pub start_angle: Angle,
 
-    /// end angle in radian
-    pub end_angle: f32,
+    /// End angle, in radians if given as a `f32`
+    #[shape(into)]
+    pub end_angle: Angle,
 
     /// Width of the inner radius
     pub inner_radius: f32,
@@ -22,14 +23,14 @@ pub struct ThickArc {
     pub outer_radius: f32,
 }
 impl ThickArc {
-    /// End angle from a span
-    pub fn span_angle(&mut self, span_angle: f32) -> &mut Self {
-        self.end_angle = (self.start_angle + span_angle) % (2. * PI);
+    /// End angle from a counterclockwise span
+    pub fn span_angle<A: Into<Angle>>(&mut self, span_angle: A) -> &mut Self {
+        self.end_angle = self.start_angle + span_angle.into();
         self
     }
-    /// End angle from a span
-    pub fn with_span_angle(mut self, span_angle: f32) -> Self {
-        self.end_angle((self.start_angle + span_angle) % (2. * PI));
+    /// End angle from a counterclockwise span
+    pub fn with_span_angle<A: Into<Angle>>(mut self, span_angle: A) -> Self {
+        self.span_angle(span_angle);
         self
     }
 }
@@ -0,0 +1,301 @@
+use crate::prelude::*;
+use nalgebra::{Point2, Vector2};
+
+/// One page of a [`Tiling`] or a [`Pagination`]
+#[derive(Debug, Clone)]
+pub struct Tile {
+    /// Row of the tile, starting from the top
+    pub row: usize,
+    /// Column of the tile, starting from the left
+    pub column: usize,
+    /// Area covered by this tile, in the coordinates of the original drawing
+    pub area: BoundingBox<Straight>,
+    /// Content of the page, centered around (0, 0).
+    ///
+    /// Export it with a page size of [`Tiling::page_width`] x [`Tiling::page_height`]
+    pub shape: Shape,
+}
+
+/// Split a drawing bigger than a page into a grid of pages.
+///
+/// Consecutive pages share an overlap of [`overlap`][Tiling::overlap] to glue them together.
+/// Crop marks are drawn in the middle of the overlap, where the pages should be trimmed,
+/// and [`crop_mark_length`][Tiling::crop_mark_length] inside the outer edges of the poster, so they stay on the page.
+/// Each page only shows the part of the drawing on it.
+///
+/// ```
+/// # use dessin::prelude::*;
+/// let poster = dessin2!(Rectangle(width = 500., height = 300.));
+///
+/// let tiles = Tiling::new(poster)
+/// 	.with_page_width(210.)
+/// 	.with_page_height(297.)
+/// 	.with_overlap(10.)
+/// 	.tiles();
+///
+/// assert_eq!(tiles.len(), 3 * 2);
+/// ```
+#[derive(Debug, Clone, Shape)]
+pub struct Tiling {
+    /// Drawing to split
+    #[shape(skip)]
+    pub shape: Shape,
+
+    /// Width of a page
+    pub page_width: f32,
+
+    /// Height of a page
+    pub page_height: f32,
+
+    /// Area shared by two consecutive pages
+    pub overlap: f32,
+
+    /// Length of the crop marks
+    pub crop_mark_length: f32,
+
+    /// Draw crop marks on each page
+    #[shape(bool)]
+    pub crop_marks: bool,
+}
+impl Default for Tiling {
+    fn default() -> Self {
+        Tiling {
+            shape: Shape::default(),
+            page_width: 210.,
+            page_height: 297.,
+            overlap: 10.,
+            crop_mark_length: 5.,
+            crop_marks: true,
+        }
+    }
+}
+impl Tiling {
+    /// Tile a drawing
+    #[inline]
+    pub fn new<T: Into<Shape>>(shape: T) -> Self {
+        Tiling {
+            shape: shape.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Drawing to split
+    #[inline]
+    pub fn shape<T: Into<Shape>>(&mut self, shape: T) -> &mut Self {
+        self.shape = shape.into();
+        self
+    }
+    /// Drawing to split
+    #[inline]
+    pub fn with_shape<T: Into<Shape>>(mut self, shape: T) -> Self {
+        self.shape(shape);
+        self
+    }
+
+    /// Do not draw crop marks
+    #[inline]
+    pub fn no_crop_marks(&mut self) -> &mut Self {
+        self.crop_marks = false;
+        self
+    }
+    /// Do not draw crop marks
+    #[inline]
+    pub fn without_crop_marks(mut self) -> Self {
+        self.no_crop_marks();
+        self
+    }
+
+    /// Number of (columns, rows) needed to cover the whole drawing
+    pub fn grid(&self) -> (usize, usize) {
+        let bb = self.shape.local_bounding_box().straigthen();
+
+        let count = |size: f32, page: f32| {
+            let step = page - self.overlap;
+            if size <= page || step <= 0. {
+                1
+            } else {
+                ((size - self.overlap) / step).ceil() as usize
+            }
+        };
+
+        (
+            count(bb.width(), self.page_width),
+            count(bb.height(), self.page_height),
+        )
+    }
+
+    /// Split the drawing, row by row, from the top left corner.
+    pub fn tiles(&self) -> Vec<Tile> {
+        let bb = self.shape.local_bounding_box().straigthen();
+        let (columns, rows) = self.grid();
+
+        let step_x = self.page_width - self.overlap;
+        let step_y = self.page_height - self.overlap;
+
+        // Center the grid on the drawing, so the excess is evenly split on each side
+        let grid_width = columns as f32 * step_x + self.overlap;
+        let grid_height = rows as f32 * step_y + self.overlap;
+        let left = bb.center().x - grid_width / 2.;
+        let top = bb.center().y + grid_height / 2.;
+
+        let mut tiles = Vec::with_capacity(columns * rows);
+        for row in 0..rows {
+            for column in 0..columns {
+                let min_x = left + column as f32 * step_x;
+                let max_y = top - row as f32 * step_y;
+                let area = BoundingBox::mins_maxs(
+                    min_x,
+                    max_y - self.page_height,
+                    min_x + self.page_width,
+                    max_y,
+                );
+
+                let content: Shape = Clip::default()
+                    .with_shape(
+                        self.shape
+                            .clone()
+                            .with_translate(Point2::origin() - area.center()),
+                    )
+                    .with_clipped_by(
+                        Rectangle::default()
+                            .with_width(self.page_width)
+                            .with_height(self.page_height),
+                    )
+                    .into();
+
+                let shape = if self.crop_marks {
+                    let marks = self.crop_marks_of(column, row, columns, rows);
+                    dessin2!([{ content }, { marks }])
+                } else {
+                    content
+                };
+
+                tiles.push(Tile {
+                    row,
+                    column,
+                    area,
+                    shape,
+                });
+            }
+        }
+
+        tiles
+    }
+
+    /// Crop marks of a page, centered around (0, 0)
+    fn crop_marks_of(&self, column: usize, row: usize, columns: usize, rows: usize) -> Shape {
+        let half = Vector2::new(self.page_width / 2., self.page_height / 2.);
+        // Trim lines are in the middle of the overlap.
+        // On outer edges, marks are drawn outward from the trim line, which is inset so they end on the border of the page.
+        let inset = |inner: bool| {
+            if inner {
+                self.overlap / 2.
+            } else {
+                self.crop_mark_length
+            }
+        };
+
+        let left = -half.x + inset(column > 0);
+        let right = half.x - inset(column + 1 < columns);
+        let top = half.y - inset(row > 0);
+        let bottom = -half.y + inset(row + 1 < rows);
+
+        let trim = BoundingBox::mins_maxs(left, bottom, right, top);
+        let marks = super::print_marks::crop_marks(trim, 0., self.crop_mark_length);
+
+        Style::new(marks).with_stroke((Color::BLACK, 0.1)).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        display_list::{DisplayList, Primitive},
+        prelude::*,
+    };
+    use assert_float_eq::*;
+    use nalgebra::Point2;
+
+    /// Ends of the crop marks of a tile
+    fn mark_points(tile: &Tile) -> Vec<Point2<f32>> {
+        let Shape::Group(Group { shapes, .. }) = &tile.shape else {
+            unreachable!()
+        };
+
+        DisplayList::new(&shapes[1])
+            .items
+            .iter()
+            .flat_map(|item| match &item.primitive {
+                Primitive::Curve(curve) => curve.flatten(0.1),
+                _ => vec![],
+            })
+            .collect()
+    }
+
+    #[test]
+    fn single_page() {
+        let tiling = Tiling::new(dessin2!(Rectangle(width = 100., height = 100.)))
+            .with_page_width(210.)
+            .with_page_height(297.);
+
+        assert_eq!(tiling.grid(), (1, 1));
+        assert_eq!(tiling.tiles().len(), 1);
+    }
+
+    #[test]
+    fn grid_with_overlap() {
+        let tiling = Tiling::new(dessin2!(Rectangle(width = 500., height = 300.)))
+            .with_page_width(210.)
+            .with_page_height(297.)
+            .with_overlap(10.);
+
+        assert_eq!(tiling.grid(), (3, 2));
+
+        let tiles = tiling.tiles();
+        let first = &tiles[0];
+        let second = &tiles[1];
+
+        assert_eq!((first.row, first.column), (0, 0));
+        assert_eq!((second.row, second.column), (0, 1));
+        assert_float_absolute_eq!(first.area.right() - second.area.left(), 10., 10e-4);
+        assert_float_absolute_eq!(first.area.width(), 210., 10e-4);
+        assert_float_absolute_eq!(first.area.height(), 297., 10e-4);
+    }
+
+    #[test]
+    fn crop_marks_are_on_the_page() {
+        let tiling = Tiling::new(dessin2!(Rectangle(width = 300., height = 200.)))
+            .with_page_width(210.)
+            .with_page_height(297.)
+            .with_overlap(10.)
+            .with_crop_mark_length(5.);
+        assert_eq!(tiling.grid(), (2, 1));
+
+        let tiles = tiling.tiles();
+        let points = mark_points(&tiles[0]);
+        assert!(!points.is_empty());
+        for p in &points {
+            assert!(p.x.abs() <= 105. + 10e-4 && p.y.abs() <= 148.5 + 10e-4);
+        }
+
+        let xs = |x: f32| points.iter().filter(|p| (p.x - x).abs() < 10e-4).count();
+        // Outer left edge: trim line inset by the length of the marks
+        assert!(xs(-100.) > 0);
+        // Inner right edge: trim line in the middle of the overlap
+        assert!(xs(100.) > 0);
+    }
+
+    #[test]
+    fn content_is_clipped_to_the_page() {
+        let tiling = Tiling::new(dessin2!(Rectangle(width = 500., height = 300.)))
+            .with_page_width(210.)
+            .with_page_height(297.)
+            .without_crop_marks();
+
+        for tile in tiling.tiles() {
+            let bb = tile.shape.local_bounding_box().straigthen();
+            assert!(bb.width() <= 210. + 10e-4);
+            assert!(bb.height() <= 297. + 10e-4);
+        }
+    }
+}
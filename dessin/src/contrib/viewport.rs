@@ -0,0 +1,580 @@
+use crate::prelude::*;
+use nalgebra::{Point2, Scale2, Transform2, Translation2, Vector2};
+
+/// Region of a dessin, drawn at its own scale into a rectangle, and clipped to it.
+///
+/// The [`view`][Viewport::view] of the content, its whole bounding box by default,
+/// is stretched over a rectangle of [`width`][Viewport::width] x [`height`][Viewport::height] centered around (0, 0),
+/// which can then be placed like any shape, for instance as a zoomed detail next to the full view.
+///
+/// Curves and ellipses crossing the sides of the rectangle are [flattened][CurvePosition::flatten]
+/// within [`tolerance`][Viewport::tolerance] and cut along them,
+/// while texts and images are kept whole as long as they overlap it.
+/// [`Dynamic`][Shape::Dynamic] shapes are evaluated once and for all.
+///
+/// ```
+/// # use dessin::prelude::*;
+/// let drawing = dessin2!([
+/// 	Circle!(stroke = (Color::BLACK, 0.5), radius = 40.),
+/// 	Rectangle!(fill = Color::RED, width = 4., height = 4., translate = [40., 0.]),
+/// ]);
+///
+/// // Around the rectangle, 2.5 times bigger
+/// let detail = dessin2!(Viewport(
+/// 	shape = drawing.clone(),
+/// 	view = BoundingBox::mins_maxs(36., -4., 44., 4.),
+/// 	width = 20.,
+/// 	height = 20.,
+/// 	translate = [70., 0.],
+/// ));
+///
+/// let dessin = dessin2!([{ drawing }, { detail }]);
+///
+/// let bb = dessin.local_bounding_box().straigthen();
+/// assert!((bb.right() - 75.).abs() < 10e-3);
+/// ```
+#[derive(Debug, Clone, Shape)]
+pub struct Viewport {
+    /// [`ShapeOp`]
+    #[local_transform]
+    pub local_transform: Transform2<f32>,
+
+    /// Content of the viewport
+    #[shape(skip)]
+    pub shape: Shape,
+
+    /// Region of the content to show, its whole bounding box if `None`
+    #[shape(skip)]
+    pub view: Option<BoundingBox<Straight>>,
+
+    /// Width of the rectangle the view is drawn into
+    pub width: f32,
+
+    /// Height of the rectangle the view is drawn into
+    pub height: f32,
+
+    /// Scale the view by the same factor on both axis, centered in the rectangle, instead of stretching it
+    #[shape(bool)]
+    pub keep_ratio: bool,
+
+    /// Largest distance between a cut curve and its flattening
+    pub tolerance: f32,
+}
+impl Default for Viewport {
+    fn default() -> Self {
+        Viewport {
+            local_transform: Transform2::default(),
+            shape: Shape::default(),
+            view: None,
+            width: 1.,
+            height: 1.,
+            keep_ratio: false,
+            tolerance: 0.01,
+        }
+    }
+}
+impl Viewport {
+    /// Content of the viewport
+    #[inline]
+    pub fn shape<T: Into<Shape>>(&mut self, shape: T) -> &mut Self {
+        self.shape = shape.into();
+        self
+    }
+    /// Content of the viewport
+    #[inline]
+    pub fn with_shape<T: Into<Shape>>(mut self, shape: T) -> Self {
+        self.shape(shape);
+        self
+    }
+
+    /// Region of the content to show
+    #[inline]
+    pub fn view(&mut self, view: BoundingBox<Straight>) -> &mut Self {
+        self.view = Some(view);
+        self
+    }
+    /// Region of the content to show
+    #[inline]
+    pub fn with_view(mut self, view: BoundingBox<Straight>) -> Self {
+        self.view(view);
+        self
+    }
+
+    /// From the coordinates of the content to the ones of the rectangle
+    fn content_transform(&self) -> Transform2<f32> {
+        let view = self
+            .view
+            .unwrap_or_else(|| self.shape.local_bounding_box().straigthen());
+
+        let mut scale = Vector2::new(self.width / view.width(), self.height / view.height());
+        if self.keep_ratio {
+            let factor = scale.x.min(scale.y);
+            scale = Vector2::new(factor, factor);
+        }
+        // Empty views are only centered
+        scale.apply(|v| {
+            if !v.is_finite() {
+                *v = 1.
+            }
+        });
+
+        nalgebra::convert::<_, Transform2<f32>>(Scale2::new(scale.x, scale.y))
+            * nalgebra::convert::<_, Transform2<f32>>(Translation2::from(-view.center().coords))
+    }
+}
+
+impl From<Viewport> for Shape {
+    fn from(viewport: Viewport) -> Self {
+        let clip = Clip {
+            rect: BoundingBox::centered([viewport.width, viewport.height]),
+            transform: viewport.content_transform(),
+            fill: None,
+            stroke: None,
+            stroke_alignment: StrokeAlignment::Center,
+            tolerance: viewport.tolerance,
+        };
+
+        Shape::Group(Group {
+            local_transform: viewport.local_transform,
+            shapes: clip.clip(&viewport.shape).into_iter().collect(),
+            metadata: vec![],
+        })
+    }
+}
+
+/// Content being clipped, with the style inherited from its parents
+#[derive(Clone)]
+struct Clip {
+    rect: BoundingBox<Straight>,
+    /// From the current coordinates to the ones of the rectangle
+    transform: Transform2<f32>,
+    fill: Option<Fill>,
+    /// In the coordinates of the rectangle
+    stroke: Option<Stroke>,
+    stroke_alignment: StrokeAlignment,
+    tolerance: f32,
+}
+impl Clip {
+    fn transformed(&self, local_transform: &Transform2<f32>) -> Clip {
+        Clip {
+            transform: self.transform * local_transform,
+            ..self.clone()
+        }
+    }
+
+    /// `shape` in the coordinates of the rectangle, each basic shape carrying its own style.
+    ///
+    /// `None` when nothing is left in the rectangle.
+    fn clip(&self, shape: &Shape) -> Option<Shape> {
+        match shape {
+            Shape::Group(Group {
+                local_transform,
+                shapes,
+                metadata,
+            }) => {
+                let clip = self.transformed(local_transform);
+                let shapes = shapes
+                    .iter()
+                    .filter_map(|s| clip.clip(s))
+                    .collect::<Vec<_>>();
+                if shapes.is_empty() {
+                    return None;
+                }
+
+                Some(Shape::Group(Group {
+                    local_transform: Transform2::identity(),
+                    shapes,
+                    metadata: metadata.clone(),
+                }))
+            }
+            Shape::Style {
+                fill,
+                stroke,
+                stroke_alignment,
+                filter,
+                opacity,
+                blend_mode,
+                shape,
+            } => {
+                let clip = match stroke {
+                    Some(stroke) => Clip {
+                        fill: fill.clone().or_else(|| self.fill.clone()),
+                        stroke: Some(self.transform * *stroke),
+                        stroke_alignment: *stroke_alignment,
+                        ..self.clone()
+                    },
+                    None => Clip {
+                        fill: fill.clone().or_else(|| self.fill.clone()),
+                        ..self.clone()
+                    },
+                };
+
+                let clipped = clip.clip(shape)?;
+                // Effects, opacity and blending apply to the clipped content as a whole
+                Some(if filter.is_some() || *opacity != 1. || *blend_mode != BlendMode::Normal {
+                    Shape::Style {
+                        fill: None,
+                        stroke: None,
+                        stroke_alignment: StrokeAlignment::Center,
+                        filter: filter.map(|filter| self.transform * filter),
+                        opacity: *opacity,
+                        blend_mode: *blend_mode,
+                        shape: Box::new(clipped),
+                    }
+                } else {
+                    clipped
+                })
+            }
+            Shape::Curve(curve) => self.clip_curve(shape, curve),
+            Shape::Ellipse(ellipse) => self.clip_curve(shape, &ellipse.as_curve()),
+            Shape::Text(_) | Shape::Image(_) => {
+                let bb = shape.global_bounding_box(&self.transform).straigthen();
+                self.overlaps(&bb, 0.)
+                    .then(|| self.styled(self.placed(shape.clone())))
+            }
+            Shape::LevelOfDetail(lod) => self
+                .transformed(&lod.local_transform)
+                .clip(lod.pick(&self.transform)),
+            Shape::Dynamic {
+                local_transform,
+                shaper,
+            } => self.transformed(local_transform).clip(&shaper()),
+        }
+    }
+
+    /// `shape` kept as is if inside the rectangle, or `curve`, its outline, cut along the sides of the rectangle
+    fn clip_curve(&self, shape: &Shape, curve: &Curve) -> Option<Shape> {
+        let margin = self.stroke.map(|s| s.width() / 2.).unwrap_or(0.);
+        let bb = shape.global_bounding_box(&self.transform).straigthen();
+        if !self.overlaps(&bb, margin) {
+            return None;
+        }
+        if self.contains(&bb, margin) {
+            return Some(self.styled(self.placed(shape.clone())));
+        }
+
+        let mut points = curve.position(&self.transform).flatten(self.tolerance);
+        points.dedup();
+
+        let mut shapes = vec![];
+        if let Some(fill) = &self.fill {
+            let area = clip_polygon(&points, &self.rect);
+            if area.len() > 2 {
+                shapes.push(Shape::Style {
+                    fill: Some(fill.clone()),
+                    stroke: None,
+                    stroke_alignment: StrokeAlignment::Center,
+                    filter: None,
+                    opacity: 1.,
+                    blend_mode: BlendMode::Normal,
+                    shape: Box::new(polyline(area, true)),
+                });
+            }
+        }
+        if let Some(stroke) = self.stroke {
+            if curve.closed {
+                if let Some(&start) = points.first() {
+                    points.push(start);
+                }
+            }
+
+            let pieces = clip_polyline(&points, &self.rect)
+                .into_iter()
+                .map(|piece| polyline(piece, false))
+                .collect::<Vec<_>>();
+            if !pieces.is_empty() {
+                shapes.push(Shape::Style {
+                    fill: None,
+                    stroke: Some(stroke),
+                    stroke_alignment: StrokeAlignment::Center,
+                    filter: None,
+                    opacity: 1.,
+                    blend_mode: BlendMode::Normal,
+                    shape: Box::new(Shape::Group(Group {
+                        shapes: pieces,
+                        ..Default::default()
+                    })),
+                });
+            }
+        }
+
+        match shapes.len() {
+            0 => None,
+            1 => shapes.pop(),
+            _ => Some(Shape::Group(Group {
+                shapes,
+                ..Default::default()
+            })),
+        }
+    }
+
+    /// Basic `shape` moved in the coordinates of the rectangle
+    fn placed(&self, shape: Shape) -> Shape {
+        Shape::Group(Group {
+            local_transform: self.transform,
+            shapes: vec![shape],
+            metadata: vec![],
+        })
+    }
+
+    /// `shape` with the inherited style
+    fn styled(&self, shape: Shape) -> Shape {
+        if self.fill.is_none() && self.stroke.is_none() {
+            return shape;
+        }
+
+        Shape::Style {
+            fill: self.fill.clone(),
+            stroke: self.stroke,
+            stroke_alignment: self.stroke_alignment,
+            filter: None,
+            opacity: 1.,
+            blend_mode: BlendMode::Normal,
+            shape: Box::new(shape),
+        }
+    }
+
+    fn overlaps(&self, bb: &BoundingBox<Straight>, margin: f32) -> bool {
+        bb.right() + margin >= self.rect.left()
+            && bb.left() - margin <= self.rect.right()
+            && bb.top() + margin >= self.rect.bottom()
+            && bb.bottom() - margin <= self.rect.top()
+    }
+
+    fn contains(&self, bb: &BoundingBox<Straight>, margin: f32) -> bool {
+        bb.left() - margin >= self.rect.left()
+            && bb.right() + margin <= self.rect.right()
+            && bb.bottom() - margin >= self.rect.bottom()
+            && bb.top() + margin <= self.rect.top()
+    }
+}
+
+fn polyline(points: Vec<Point2<f32>>, closed: bool) -> Shape {
+    Shape::Curve(Curve {
+        local_transform: Transform2::identity(),
+        keypoints: points.into_iter().map(Keypoint::Point).collect(),
+        closed,
+        start_marker: None,
+        end_marker: None,
+        fill_rule: FillRule::NonZero,
+    })
+}
+
+/// Part of the polygon inside `rect`, by Sutherland-Hodgman.
+///
+/// Parts joined only along the sides of `rect` stay in a single polygon.
+fn clip_polygon(points: &[Point2<f32>], rect: &BoundingBox<Straight>) -> Vec<Point2<f32>> {
+    // Axis, bound, and whether the inside is above the bound
+    let sides = [
+        (0, rect.left(), true),
+        (0, rect.right(), false),
+        (1, rect.bottom(), true),
+        (1, rect.top(), false),
+    ];
+
+    let mut points = points.to_vec();
+    for (axis, bound, above) in sides {
+        let inside = |p: &Point2<f32>| {
+            if above {
+                p[axis] >= bound
+            } else {
+                p[axis] <= bound
+            }
+        };
+        let cross = |a: Point2<f32>, b: Point2<f32>| {
+            a + (b - a) * ((bound - a[axis]) / (b[axis] - a[axis]))
+        };
+
+        let mut clipped = Vec::with_capacity(points.len());
+        for (idx, &current) in points.iter().enumerate() {
+            let previous = points[(idx + points.len() - 1) % points.len()];
+            match (inside(&previous), inside(&current)) {
+                (true, true) => clipped.push(current),
+                (true, false) => clipped.push(cross(previous, current)),
+                (false, true) => {
+                    clipped.push(cross(previous, current));
+                    clipped.push(current);
+                }
+                (false, false) => {}
+            }
+        }
+        points = clipped;
+    }
+
+    points
+}
+
+/// Segment from `a` to `b` inside `rect`, by Liang-Barsky
+fn clip_segment(
+    a: Point2<f32>,
+    b: Point2<f32>,
+    rect: &BoundingBox<Straight>,
+) -> Option<(Point2<f32>, Point2<f32>)> {
+    let d = b - a;
+    let (mut t0, mut t1) = (0_f32, 1_f32);
+    for (p, q) in [
+        (-d.x, a.x - rect.left()),
+        (d.x, rect.right() - a.x),
+        (-d.y, a.y - rect.bottom()),
+        (d.y, rect.top() - a.y),
+    ] {
+        if p == 0. {
+            if q < 0. {
+                return None;
+            }
+        } else if p < 0. {
+            t0 = t0.max(q / p);
+        } else {
+            t1 = t1.min(q / p);
+        }
+    }
+    if t0 > t1 {
+        return None;
+    }
+
+    // Ends kept exact, so consecutive segments still join
+    let start = if t0 == 0. { a } else { a + d * t0 };
+    let end = if t1 == 1. { b } else { a + d * t1 };
+    Some((start, end))
+}
+
+/// Pieces of the polyline inside `rect`.
+///
+/// The first and last pieces are joined when the polyline is closed by its last point.
+fn clip_polyline(points: &[Point2<f32>], rect: &BoundingBox<Straight>) -> Vec<Vec<Point2<f32>>> {
+    let mut pieces: Vec<Vec<Point2<f32>>> = vec![];
+    let mut current: Vec<Point2<f32>> = vec![];
+    for segment in points.windows(2) {
+        match clip_segment(segment[0], segment[1], rect) {
+            Some((start, end)) => {
+                if current.last() != Some(&start) {
+                    if current.len() > 1 {
+                        pieces.push(std::mem::take(&mut current));
+                    }
+                    current = vec![start];
+                }
+                current.push(end);
+            }
+            None => {
+                if current.len() > 1 {
+                    pieces.push(std::mem::take(&mut current));
+                }
+                current.clear();
+            }
+        }
+    }
+    if current.len() > 1 {
+        pieces.push(current);
+    }
+
+    let closed = points.len() > 2 && points.first() == points.last();
+    if closed && pieces.len() > 1 && pieces[0].first() == points.first() {
+        let first = pieces.remove(0);
+        let last = pieces.last_mut().unwrap();
+        if last.last() == points.last() {
+            last.extend(first.into_iter().skip(1));
+        } else {
+            pieces.insert(0, first);
+        }
+    }
+
+    pieces
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clipped_to_rectangle() {
+        let content = dessin2!([
+            Rectangle!(fill = Color::RED, width = 10., height = 10.),
+            Text!(
+                fill = Color::BLACK,
+                text = "Far away",
+                translate = [100., 100.]
+            ),
+        ]);
+
+        // Scaled 4 times around (2.5, 2.5)
+        let viewport = dessin2!(
+            Viewport(
+                shape = content,
+                view = BoundingBox::mins_maxs(0., 0., 5., 5.),
+                width = 20.,
+                height = 20.,
+            ) > ()
+        );
+
+        let Shape::Group(Group { shapes, .. }) = &viewport else {
+            panic!("Viewport is a group");
+        };
+        // The text is dropped
+        assert!(matches!(&shapes[..], [Shape::Group(Group { shapes, .. })] if shapes.len() == 1));
+
+        let bb = viewport.local_bounding_box().straigthen();
+        assert!((bb.width() - 20.).abs() < 10e-5);
+        assert!((bb.height() - 20.).abs() < 10e-5);
+        assert!(bb.center().coords.magnitude() < 10e-5);
+    }
+
+    #[test]
+    fn shapes_inside_are_kept() {
+        let viewport = dessin2!(
+            Viewport(
+                shape = dessin2!(Circle!(stroke = (Color::BLACK, 0.1), radius = 1.) > ()),
+                view = BoundingBox::centered([10., 10.]),
+                width = 20.,
+                height = 20.,
+            ) > ()
+        );
+
+        let bb = viewport.local_bounding_box().straigthen();
+        assert!((bb.width() - 4.).abs() < 10e-5);
+
+        let mut ellipses = 0;
+        let mut stack = vec![&viewport];
+        while let Some(shape) = stack.pop() {
+            match shape {
+                Shape::Group(Group { shapes, .. }) => stack.extend(shapes),
+                Shape::Style { shape, stroke, .. } => {
+                    // Stroke scaled with the content
+                    assert!((stroke.unwrap().width() - 0.2).abs() < 10e-5);
+                    stack.push(shape);
+                }
+                Shape::Ellipse(_) => ellipses += 1,
+                _ => {}
+            }
+        }
+        assert_eq!(ellipses, 1);
+    }
+
+    #[test]
+    fn polyline_pieces() {
+        let rect = BoundingBox::centered([2., 2.]);
+        // Square crossing the right side of the rectangle, starting inside
+        let points = [
+            Point2::new(0., 0.),
+            Point2::new(2., 0.),
+            Point2::new(2., 0.5),
+            Point2::new(0., 0.5),
+            Point2::new(0., 0.),
+        ];
+
+        let pieces = clip_polyline(&points, &rect);
+        assert_eq!(pieces.len(), 1);
+        assert_eq!(
+            pieces[0],
+            vec![
+                Point2::new(1., 0.5),
+                Point2::new(0., 0.5),
+                Point2::new(0., 0.),
+                Point2::new(1., 0.),
+            ]
+        );
+
+        let area = clip_polygon(&points[..4], &rect);
+        let bb = BoundingBox::new(area[0], area[1], area[2], area[3]).straigthen();
+        assert!((bb.width() - 1.).abs() < 10e-5);
+    }
+}
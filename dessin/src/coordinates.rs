@@ -0,0 +1,163 @@
+//! Orientation of the Y axis a dessin is drawn in.
+//!
+//! Dessins are y-up, like in math: positive Y goes to the top of the page.
+//! Code ported from canvas or the web is usually y-down, like a screen.
+//! Exporters taking a [`CoordinateSystem`] flip y-down dessins for them,
+//! keeping texts and images upright.
+//!
+//! ```
+//! # use dessin::prelude::*;
+//! use dessin::coordinates::CoordinateSystem;
+//!
+//! // 10 units below the origin, as on a screen
+//! let dessin = dessin2!(Text!(fill = Color::BLACK, text = "Title", translate = [0., 10.]) > ());
+//!
+//! let y_up = CoordinateSystem::YDown.to_y_up(&dessin);
+//! assert!(y_up.local_bounding_box().center().y < 0.);
+//! ```
+
+use crate::prelude::*;
+use nalgebra::{Scale2, Transform2};
+use std::borrow::Cow;
+
+/// Orientation of the Y axis
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum CoordinateSystem {
+    /// Positive Y goes up, like in math
+    #[default]
+    YUp,
+    /// Positive Y goes down, like on a screen
+    YDown,
+}
+impl CoordinateSystem {
+    /// `shape`, drawn in this coordinate system, in the y-up coordinates of dessin
+    pub fn to_y_up<'a>(&self, shape: &'a Shape) -> Cow<'a, Shape> {
+        match self {
+            CoordinateSystem::YUp => Cow::Borrowed(shape),
+            CoordinateSystem::YDown => Cow::Owned(shape.from_y_down()),
+        }
+    }
+}
+
+fn flip() -> Transform2<f32> {
+    nalgebra::convert(Scale2::new(1., -1.))
+}
+
+impl Shape {
+    /// This dessin, drawn in y-down coordinates, in the y-up coordinates of dessin.
+    ///
+    /// The whole dessin is mirrored along the X axis, then each text and image is mirrored back
+    /// around its own origin, so it reads upright at its mirrored position.
+    /// Texts on a curve follow the mirrored curve.
+    /// [`Dynamic`][Shape::Dynamic] shapes are evaluated once and for all.
+    pub fn from_y_down(&self) -> Shape {
+        Shape::Group(Group {
+            local_transform: flip(),
+            shapes: vec![self.unflip_content()],
+            metadata: vec![],
+        })
+    }
+
+    /// Mirror texts and images back, so they are upright once the dessin is flipped
+    fn unflip_content(&self) -> Shape {
+        match self {
+            Shape::Group(Group {
+                local_transform,
+                shapes,
+                metadata,
+            }) => Shape::Group(Group {
+                local_transform: *local_transform,
+                shapes: shapes.iter().map(Shape::unflip_content).collect(),
+                metadata: metadata.clone(),
+            }),
+            Shape::Style {
+                fill,
+                stroke,
+                stroke_alignment,
+                filter,
+                opacity,
+                blend_mode,
+                shape,
+            } => Shape::Style {
+                fill: fill.clone(),
+                stroke: *stroke,
+                stroke_alignment: *stroke_alignment,
+                filter: *filter,
+                opacity: *opacity,
+                blend_mode: *blend_mode,
+                shape: Box::new(shape.unflip_content()),
+            },
+            Shape::Text(text) if text.on_curve.is_none() => Shape::Text(Text {
+                local_transform: text.local_transform * flip(),
+                ..text.clone()
+            }),
+            Shape::Image(image) => Shape::Image(Image {
+                local_transform: image.local_transform * flip(),
+                ..image.clone()
+            }),
+            Shape::Text(_) | Shape::Ellipse(_) | Shape::Curve(_) => self.clone(),
+            Shape::LevelOfDetail(lod) => Shape::LevelOfDetail(LevelOfDetail {
+                local_transform: lod.local_transform,
+                detailed: Box::new(lod.detailed.unflip_content()),
+                simplified: lod
+                    .simplified
+                    .iter()
+                    .map(|(size, shape)| (*size, shape.unflip_content()))
+                    .collect(),
+            }),
+            Shape::Dynamic {
+                local_transform,
+                shaper,
+            } => Shape::Group(Group {
+                local_transform: *local_transform,
+                shapes: vec![shaper().unflip_content()],
+                metadata: vec![],
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn geometry_is_flipped() {
+        let dessin = dessin2!(Circle(radius = 1., translate = [2., 5.]) > ());
+
+        let center = CoordinateSystem::YDown
+            .to_y_up(&dessin)
+            .local_bounding_box()
+            .center();
+        assert!((center.x - 2.).abs() < 10e-5);
+        assert!((center.y + 5.).abs() < 10e-5);
+
+        assert!(matches!(
+            CoordinateSystem::YUp.to_y_up(&dessin),
+            Cow::Borrowed(_)
+        ));
+    }
+
+    #[test]
+    fn texts_stay_upright() {
+        let dessin = dessin2!(Text!(text = "Upright", translate = [0., 10.]) > ());
+        let y_up = dessin.from_y_down();
+
+        let Shape::Group(Group {
+            local_transform,
+            shapes,
+            ..
+        }) = &y_up
+        else {
+            panic!("Flipped in a group");
+        };
+        let Shape::Text(text) = &shapes[0] else {
+            panic!("Text is kept");
+        };
+
+        // Flipped twice, so not mirrored
+        let transform = (local_transform * text.local_transform).to_homogeneous();
+        assert!(transform.fixed_view::<2, 2>(0, 0).determinant() > 0.);
+        assert!((transform[(1, 2)] + 10.).abs() < 10e-5);
+    }
+}
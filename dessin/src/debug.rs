@@ -0,0 +1,389 @@
+//! Draw layout information on top of a dessin.
+//!
+//! [`Shape::with_debug_overlay`] decorates a dessin with the bounding box of every basic shape,
+//! the anchor points of curves, the origin and axes of every transform, and rulers along the dessin.
+//! Exporters can also enable it from their options.
+//!
+//! ```
+//! # use dessin::prelude::*;
+//! use dessin::debug::DebugOverlay;
+//!
+//! let dessin = dessin2!([
+//! 	Circle!(stroke = (Color::BLACK, 0.2), radius = 10.),
+//! 	Text!(text = "Hello", translate = [0., 15.]),
+//! ]);
+//!
+//! let debug = dessin.with_debug_overlay(&DebugOverlay::default());
+//! ```
+
+use crate::prelude::*;
+use nalgebra::{Point2, Transform2, Vector2};
+
+/// What to draw over a dessin
+#[derive(Debug, Clone, PartialEq)]
+pub struct DebugOverlay {
+    /// Outline the bounding box of every basic shape
+    pub bounding_boxes: bool,
+    /// Mark the points of curves, and the control points of beziers
+    pub anchors: bool,
+    /// Draw the origin and axes of every transform, X in red and Y in green
+    pub origins: bool,
+    /// Graduate the bottom and left sides of the dessin
+    pub rulers: bool,
+    /// Color of bounding boxes, anchors and rulers
+    pub color: Color,
+    /// Size of markers and labels.
+    ///
+    /// Default to 1/50 of the largest side of the dessin.
+    pub size: Option<f32>,
+}
+impl Default for DebugOverlay {
+    fn default() -> Self {
+        DebugOverlay {
+            bounding_boxes: true,
+            anchors: true,
+            origins: true,
+            rulers: true,
+            color: Color::MAGENTA,
+            size: None,
+        }
+    }
+}
+
+impl Shape {
+    /// This dessin, with the layout information of `overlay` drawn on top
+    pub fn with_debug_overlay(&self, overlay: &DebugOverlay) -> Shape {
+        let bb = self.local_bounding_box().straigthen();
+        let size = overlay
+            .size
+            .unwrap_or_else(|| bb.width().max(bb.height()) / 50.);
+        if size.is_nan() || size <= 0. {
+            return self.clone();
+        }
+
+        let mut decorations = Decorations {
+            overlay,
+            size,
+            lines: vec![],
+            fills: vec![],
+            origins: vec![],
+        };
+        decorations.collect(self, &Transform2::identity());
+        if overlay.rulers {
+            decorations.rulers(&bb);
+        }
+
+        let Decorations {
+            lines,
+            fills,
+            origins,
+            ..
+        } = decorations;
+
+        let lines = Style::new(Shape::Group(Group {
+            shapes: lines,
+            ..Default::default()
+        }))
+        .with_stroke(Stroke::from((overlay.color, size / 10.)));
+        let fills = Style::new(Shape::Group(Group {
+            shapes: fills,
+            ..Default::default()
+        }))
+        .with_fill(overlay.color);
+        let origins = Shape::Group(Group {
+            shapes: origins,
+            ..Default::default()
+        });
+
+        let mut debug = Shape::Group(Group {
+            shapes: vec![self.clone(), lines.into(), fills.into(), origins],
+            ..Default::default()
+        });
+        debug.add_metadata(("debug", "overlay"));
+        debug
+    }
+}
+
+/// Decorations collected while walking the dessin, in global coordinates
+struct Decorations<'a> {
+    overlay: &'a DebugOverlay,
+    size: f32,
+    lines: Vec<Shape>,
+    fills: Vec<Shape>,
+    origins: Vec<Shape>,
+}
+impl Decorations<'_> {
+    fn collect(&mut self, shape: &Shape, parent_transform: &Transform2<f32>) {
+        match shape {
+            Shape::Group(Group {
+                local_transform,
+                shapes,
+                ..
+            }) => {
+                let transform = parent_transform * local_transform;
+                if *local_transform != Transform2::identity() {
+                    self.origin(&transform);
+                }
+
+                for shape in shapes {
+                    self.collect(shape, &transform);
+                }
+            }
+            Shape::Style { shape, .. } => self.collect(shape, parent_transform),
+            Shape::Dynamic {
+                local_transform,
+                shaper,
+            } => self.collect(&shaper(), &(parent_transform * local_transform)),
+            Shape::LevelOfDetail(lod) => self.collect(
+                lod.pick(parent_transform),
+                &lod.global_transform(parent_transform),
+            ),
+            leaf => {
+                self.origin(&leaf.global_transform(parent_transform));
+
+                if self.overlay.bounding_boxes {
+                    let bb = leaf.global_bounding_box(parent_transform).straigthen();
+                    self.lines.push(polyline(
+                        &[
+                            bb.bottom_left(),
+                            bb.bottom_right(),
+                            bb.top_right(),
+                            bb.top_left(),
+                        ],
+                        true,
+                    ));
+                }
+
+                if let (true, Shape::Curve(curve)) = (self.overlay.anchors, leaf) {
+                    self.anchors(&curve.position(parent_transform));
+                }
+            }
+        }
+    }
+
+    fn origin(&mut self, transform: &Transform2<f32>) {
+        if !self.overlay.origins {
+            return;
+        }
+
+        let origin = transform * Point2::origin();
+        let axis = |v: Vector2<f32>| {
+            let v = transform * v;
+            if v.magnitude() > f32::EPSILON {
+                origin + v.normalize() * self.size
+            } else {
+                origin
+            }
+        };
+
+        for (color, to) in [
+            (Color::RED, axis(Vector2::x())),
+            (Color::GREEN, axis(Vector2::y())),
+        ] {
+            self.origins.push(
+                Style::new(polyline(&[origin, to], false))
+                    .with_stroke(Stroke::from((color, self.size / 10.)))
+                    .into(),
+            );
+        }
+    }
+
+    fn anchors(&mut self, curve: &CurvePosition) {
+        let half = self.size / 4.;
+
+        let mut square = |p: Point2<f32>| {
+            self.fills.push(polyline(
+                &[
+                    p + Vector2::new(-half, -half),
+                    p + Vector2::new(half, -half),
+                    p + Vector2::new(half, half),
+                    p + Vector2::new(-half, half),
+                ],
+                true,
+            ))
+        };
+
+        let mut controls = vec![];
+        for keypoint in &curve.keypoints {
+            match keypoint {
+                KeypointPosition::Point(p) => square(*p),
+                KeypointPosition::Bezier(b) => {
+                    if let Some(start) = b.start {
+                        square(start);
+                        controls.push(polyline(&[start, b.start_control], false));
+                    }
+                    square(b.end);
+                    controls.push(polyline(&[b.end_control, b.end], false));
+                }
+            }
+        }
+
+        self.lines.extend(controls);
+    }
+
+    fn rulers(&mut self, bb: &BoundingBox<Straight>) {
+        let extent = bb.width().max(bb.height());
+        if extent.is_nan() || extent <= 0. {
+            return;
+        }
+
+        // 1, 2 or 5 times a power of ten, for about 10 graduations
+        let magnitude = 10f32.powf((extent / 10.).log10().floor());
+        let step = [1., 2., 5., 10.]
+            .into_iter()
+            .map(|m| m * magnitude)
+            .find(|step| extent / step <= 10.)
+            .unwrap_or(10. * magnitude);
+
+        let offset = self.size;
+        let graduations = |from: f32, to: f32| {
+            let first = (from / step).ceil() as i64;
+            let last = (to / step).floor() as i64;
+            (first..=last).map(move |idx| idx as f32 * step)
+        };
+
+        // Bottom ruler
+        let y = bb.bottom() - offset;
+        self.lines.push(polyline(
+            &[Point2::new(bb.left(), y), Point2::new(bb.right(), y)],
+            false,
+        ));
+        for x in graduations(bb.left(), bb.right()) {
+            self.lines.push(polyline(
+                &[Point2::new(x, y), Point2::new(x, y - offset / 2.)],
+                false,
+            ));
+            self.fills.push(label(
+                x,
+                Point2::new(x, y - offset),
+                offset,
+                0.,
+                TextVerticalAlign::Top,
+            ));
+        }
+
+        // Left ruler
+        let x = bb.left() - offset;
+        self.lines.push(polyline(
+            &[Point2::new(x, bb.bottom()), Point2::new(x, bb.top())],
+            false,
+        ));
+        for y in graduations(bb.bottom(), bb.top()) {
+            self.lines.push(polyline(
+                &[Point2::new(x, y), Point2::new(x - offset / 2., y)],
+                false,
+            ));
+            self.fills.push(label(
+                y,
+                Point2::new(x - offset, y),
+                offset,
+                std::f32::consts::FRAC_PI_2,
+                TextVerticalAlign::Bottom,
+            ));
+        }
+    }
+}
+
+fn polyline(points: &[Point2<f32>], closed: bool) -> Shape {
+    Shape::Curve(Curve {
+        local_transform: Default::default(),
+        keypoints: points.iter().copied().map(Keypoint::Point).collect(),
+        closed,
+        start_marker: None,
+        end_marker: None,
+        fill_rule: FillRule::NonZero,
+    })
+}
+
+fn label(
+    value: f32,
+    at: Point2<f32>,
+    font_size: f32,
+    rotation: f32,
+    vertical_align: TextVerticalAlign,
+) -> Shape {
+    // Remove the noise of floating point numbers
+    let value = (value * 1000.).round() / 1000.;
+
+    dessin2!(
+        Text(
+            text = format!("{value}"),
+            font_size = font_size,
+            align = TextAlign::Center,
+            vertical_align = vertical_align,
+            rotate = nalgebra::Rotation2::new(rotation),
+            translate = at - Point2::origin(),
+        ) > ()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn count_curves(shape: &Shape) -> usize {
+        match shape {
+            Shape::Group(Group { shapes, .. }) => shapes.iter().map(count_curves).sum(),
+            Shape::Style { shape, .. } => count_curves(shape),
+            Shape::Curve(_) => 1,
+            _ => 0,
+        }
+    }
+
+    #[test]
+    fn toggles() {
+        let dessin = dessin2!([Rectangle(width = 10., height = 10., translate = [5., 5.])]);
+
+        let nothing = dessin.with_debug_overlay(&DebugOverlay {
+            bounding_boxes: false,
+            anchors: false,
+            origins: false,
+            rulers: false,
+            ..Default::default()
+        });
+        assert_eq!(count_curves(&nothing), 1);
+
+        let bounding_boxes = dessin.with_debug_overlay(&DebugOverlay {
+            bounding_boxes: true,
+            anchors: false,
+            origins: false,
+            rulers: false,
+            ..Default::default()
+        });
+        assert_eq!(count_curves(&bounding_boxes), 2);
+
+        let anchors = dessin.with_debug_overlay(&DebugOverlay {
+            bounding_boxes: false,
+            anchors: true,
+            origins: false,
+            rulers: false,
+            ..Default::default()
+        });
+        assert_eq!(count_curves(&anchors), 1 + 4);
+
+        let origins = dessin.with_debug_overlay(&DebugOverlay {
+            bounding_boxes: false,
+            anchors: false,
+            origins: true,
+            rulers: false,
+            ..Default::default()
+        });
+        assert_eq!(count_curves(&origins), 1 + 2);
+    }
+
+    #[test]
+    fn rulers_graduations() {
+        let dessin = dessin2!(Rectangle(width = 100., height = 50., translate = [50., 25.]) > ());
+
+        let rulers = dessin.with_debug_overlay(&DebugOverlay {
+            bounding_boxes: false,
+            anchors: false,
+            origins: false,
+            rulers: true,
+            ..Default::default()
+        });
+
+        // Dessin, then 2 rulers, graduated every 10 from 0 to 100 and from 0 to 50
+        assert_eq!(count_curves(&rulers), 1 + 2 + 11 + 6);
+    }
+}
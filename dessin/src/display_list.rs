@@ -0,0 +1,404 @@
+//! Flat list of everything a dessin draws.
+//!
+//! A [`DisplayList`] is what an [`Exporter`] sees, collected once instead of called back:
+//! every drawable in painting order, positioned in the global coordinates,
+//! with the style it is drawn with.
+//! Renderers and analysis tools can iterate over it without implementing [`Exporter`].
+//!
+//! ```
+//! # use dessin::prelude::*;
+//! use dessin::display_list::{DisplayList, Primitive};
+//!
+//! let dessin = dessin2!([
+//! 	Circle!(fill = rgba(255, 0, 0, 128), radius = 10.),
+//! 	[
+//! 		Line(from = [0., 0.], to = [10., 0.], translate = [5., 5.]),
+//! 	] > !(stroke = (Color::BLACK, 1.)),
+//! ]);
+//!
+//! let list = DisplayList::new(&dessin);
+//! assert_eq!(list.len(), 2);
+//!
+//! // Colors keep their alpha
+//! assert_eq!(list.items[0].fill, Some(Fill::Color(rgba(255, 0, 0, 128))));
+//!
+//! // The line inherits the stroke of its parent, and is translated
+//! let line = &list.items[1];
+//! assert!(line.stroke.is_some());
+//! assert!(matches!(
+//! 	&line.primitive,
+//! 	Primitive::Curve(curve) if curve.keypoints[0] == KeypointPosition::Point([5., 5.].into())
+//! ));
+//! ```
+
+use crate::{
+    export::{Export, Exporter},
+    font::FontRef,
+    prelude::*,
+};
+use ::image::DynamicImage;
+use nalgebra::{Point2, Transform2, Unit, Vector2};
+use std::convert::Infallible;
+
+/// Owned [`TextPosition`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct DisplayText {
+    /// The text
+    pub text: String,
+    /// Horizontal alignment
+    pub align: TextAlign,
+    /// Font weight
+    pub font_weight: FontWeight,
+    /// Curve followed by the text
+    pub on_curve: Option<CurvePosition>,
+    /// Font size
+    pub font_size: f32,
+    /// Start of the baseline
+    pub reference_start: Point2<f32>,
+    /// Direction of the baseline
+    pub direction: Unit<Vector2<f32>>,
+    /// Font, the default one if `None`
+    pub font: Option<FontRef>,
+}
+impl From<TextPosition<'_>> for DisplayText {
+    fn from(text: TextPosition<'_>) -> Self {
+        DisplayText {
+            text: text.text.to_string(),
+            align: text.align,
+            font_weight: text.font_weight,
+            on_curve: text.on_curve,
+            font_size: text.font_size,
+            reference_start: text.reference_start,
+            direction: text.direction,
+            font: text.font.clone(),
+        }
+    }
+}
+
+/// Owned [`ImagePosition`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct DisplayImage {
+    /// Top left corner
+    pub top_left: Point2<f32>,
+    /// Top right corner
+    pub top_right: Point2<f32>,
+    /// Bottom right corner
+    pub bottom_right: Point2<f32>,
+    /// Bottom left corner
+    pub bottom_left: Point2<f32>,
+    /// Center
+    pub center: Point2<f32>,
+    /// Width
+    pub width: f32,
+    /// Height
+    pub height: f32,
+    /// Rotation, in radians
+    pub rotation: f32,
+    /// Pixels, with lazy images already decoded
+    pub image: DynamicImage,
+}
+impl From<ImagePosition<'_>> for DisplayImage {
+    fn from(image: ImagePosition<'_>) -> Self {
+        DisplayImage {
+            top_left: image.top_left,
+            top_right: image.top_right,
+            bottom_right: image.bottom_right,
+            bottom_left: image.bottom_left,
+            center: image.center,
+            width: image.width,
+            height: image.height,
+            rotation: image.rotation,
+            image: image.image.clone(),
+        }
+    }
+}
+
+/// Geometry of a [`DisplayItem`], in global coordinates
+#[derive(Debug, Clone, PartialEq)]
+pub enum Primitive {
+    /// Curve, already offset to honor the [`StrokeAlignment`] of its stroke
+    Curve(CurvePosition),
+    /// Ellipse
+    Ellipse(EllipsePosition),
+    /// Text
+    Text(DisplayText),
+    /// Image
+    Image(DisplayImage),
+}
+
+/// A single drawable, with its resolved style
+#[derive(Debug, Clone, PartialEq)]
+pub struct DisplayItem {
+    /// What is drawn
+    pub primitive: Primitive,
+    /// Fill of the closest [`Style`] defining one
+    pub fill: Option<Fill>,
+    /// Stroke of the closest [`Style`] defining one, its width scaled to the global coordinates
+    pub stroke: Option<Stroke>,
+    /// Metadata of every enclosing [`Group`], from the outermost to the innermost
+    pub metadata: Vec<(String, String)>,
+}
+impl DisplayItem {
+    /// Box containing everything drawn by the item, its stroke included.
+    ///
+    /// Curves are bounded by their control points, and texts by their whole font size above and below the baseline,
+    /// so the box may be larger than what is drawn, never smaller.
+    pub fn bounding_box(&self) -> BoundingBox<Straight> {
+        let mut points = vec![];
+        let mut margin = 0.;
+        match &self.primitive {
+            Primitive::Curve(curve) => curve_points(curve, &mut points),
+            Primitive::Ellipse(EllipsePosition {
+                center,
+                semi_major_axis: a,
+                semi_minor_axis: b,
+                rotation,
+            }) => {
+                let (sin, cos) = rotation.sin_cos();
+                let half = Vector2::new(
+                    ((a * cos).powi(2) + (b * sin).powi(2)).sqrt(),
+                    ((a * sin).powi(2) + (b * cos).powi(2)).sqrt(),
+                );
+                points.extend([center - half, center + half]);
+            }
+            Primitive::Image(image) => points.extend([
+                image.top_left,
+                image.top_right,
+                image.bottom_right,
+                image.bottom_left,
+            ]),
+            Primitive::Text(text) => {
+                margin = text.font_size;
+                match &text.on_curve {
+                    Some(curve) => curve_points(curve, &mut points),
+                    None => {
+                        let fonts = crate::font::get(text.font.clone().unwrap_or_default());
+                        let raw_font = match fonts.get(text.font_weight) {
+                            crate::font::Font::OTF(bytes) => bytes,
+                            crate::font::Font::TTF(bytes) => bytes,
+                        };
+                        let width = fontdue::Font::from_bytes(
+                            raw_font.as_slice(),
+                            fontdue::FontSettings::default(),
+                        )
+                        .map_or(0., |font| size_of(&font, &text.text, text.font_size));
+
+                        points.extend([
+                            text.reference_start,
+                            text.reference_start + text.direction.into_inner() * width,
+                        ]);
+                    }
+                }
+            }
+        }
+
+        if let Some(stroke) = &self.stroke {
+            // Miters stick out further than half the width
+            margin = f32::max(margin, stroke.width());
+        }
+
+        let Some(first) = points.first() else {
+            return BoundingBox::zero();
+        };
+        let (min, max) = points
+            .iter()
+            .fold((*first, *first), |(min, max), p| (min.inf(p), max.sup(p)));
+        BoundingBox::mins_maxs(
+            min.x - margin,
+            min.y - margin,
+            max.x + margin,
+            max.y + margin,
+        )
+    }
+}
+
+/// Points of `curve`, with the control points of its beziers, which contain the whole curve
+fn curve_points(curve: &CurvePosition, points: &mut Vec<Point2<f32>>) {
+    for keypoint in &curve.keypoints {
+        match keypoint {
+            KeypointPosition::Point(p) => points.push(*p),
+            KeypointPosition::Bezier(b) => {
+                points.extend(b.start);
+                points.extend([b.start_control, b.end_control, b.end]);
+            }
+        }
+    }
+}
+
+/// Everything a dessin draws, in painting order: later items are drawn on top of earlier ones
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DisplayList {
+    /// Items, from the bottom to the top
+    pub items: Vec<DisplayItem>,
+}
+impl DisplayList {
+    /// Flatten `shape` into a display list
+    pub fn new(shape: &Shape) -> Self {
+        let mut collector = Collector::default();
+        shape
+            .write_into_exporter(&mut collector, &Transform2::identity())
+            .unwrap_or_else(|e| match e {});
+
+        collector.list
+    }
+
+    /// Number of items
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Whether nothing is drawn
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Items, from the bottom to the top
+    #[inline]
+    pub fn iter(&self) -> std::slice::Iter<'_, DisplayItem> {
+        self.items.iter()
+    }
+}
+impl IntoIterator for DisplayList {
+    type Item = DisplayItem;
+    type IntoIter = std::vec::IntoIter<DisplayItem>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.items.into_iter()
+    }
+}
+impl<'a> IntoIterator for &'a DisplayList {
+    type Item = &'a DisplayItem;
+    type IntoIter = std::slice::Iter<'a, DisplayItem>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.items.iter()
+    }
+}
+impl From<&Shape> for DisplayList {
+    fn from(shape: &Shape) -> Self {
+        DisplayList::new(shape)
+    }
+}
+
+#[derive(Default)]
+struct Collector {
+    list: DisplayList,
+    styles: Vec<StylePosition>,
+    /// Number of metadata entries added by each open block
+    blocks: Vec<usize>,
+    metadata: Vec<(String, String)>,
+}
+impl Collector {
+    fn push(&mut self, primitive: Primitive) {
+        self.list.items.push(DisplayItem {
+            primitive,
+            fill: self.styles.iter().rev().find_map(|s| s.fill.clone()),
+            stroke: self.styles.iter().rev().find_map(|s| s.stroke),
+            metadata: self.metadata.clone(),
+        });
+    }
+}
+impl Exporter for Collector {
+    type Error = Infallible;
+
+    fn start_style(&mut self, style: StylePosition) -> Result<(), Self::Error> {
+        self.styles.push(style);
+        Ok(())
+    }
+    fn end_style(&mut self) -> Result<(), Self::Error> {
+        self.styles.pop();
+        Ok(())
+    }
+
+    fn start_block(&mut self, metadata: &[(String, String)]) -> Result<(), Self::Error> {
+        self.blocks.push(metadata.len());
+        self.metadata.extend_from_slice(metadata);
+        Ok(())
+    }
+    fn end_block(&mut self, _metadata: &[(String, String)]) -> Result<(), Self::Error> {
+        let added = self.blocks.pop().unwrap_or_default();
+        self.metadata.truncate(self.metadata.len() - added);
+        Ok(())
+    }
+
+    fn export_image(&mut self, image: ImagePosition) -> Result<(), Self::Error> {
+        self.push(Primitive::Image(image.into()));
+        Ok(())
+    }
+    fn export_ellipse(&mut self, ellipse: EllipsePosition) -> Result<(), Self::Error> {
+        self.push(Primitive::Ellipse(ellipse));
+        Ok(())
+    }
+    fn export_curve(&mut self, curve: CurvePosition) -> Result<(), Self::Error> {
+        self.push(Primitive::Curve(curve));
+        Ok(())
+    }
+    fn export_text(&mut self, text: TextPosition) -> Result<(), Self::Error> {
+        self.push(Primitive::Text(text.into()));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn painting_order() {
+        let dessin = dessin2!([
+            Line!(stroke = (Color::RED, 1.), from = [0., 0.], to = [1., 0.]),
+            Text!(fill = Color::BLACK, text = "Above"),
+            Circle!(fill = Color::BLUE, radius = 1.),
+        ]);
+
+        let list = DisplayList::new(&dessin);
+        assert!(matches!(
+            &list.items[..],
+            [
+                DisplayItem { primitive: Primitive::Curve(_), .. },
+                DisplayItem { primitive: Primitive::Text(text), .. },
+                DisplayItem { primitive: Primitive::Ellipse(_), .. },
+            ] if text.text == "Above"
+        ));
+    }
+
+    #[test]
+    fn inherited_style() {
+        let dessin = dessin2!([[
+            Circle!(fill = Color::BLUE, radius = 1.),
+            Circle(radius = 1.),
+        ] > !(fill = Color::RED, stroke = (Color::BLACK, 1.)),]);
+
+        let list = DisplayList::new(&dessin);
+        assert_eq!(list.len(), 2);
+        assert_eq!(list.items[0].fill, Some(Fill::Color(Color::BLUE)));
+        assert_eq!(list.items[1].fill, Some(Fill::Color(Color::RED)));
+        for item in &list {
+            assert!(matches!(
+                item.stroke,
+                Some(Stroke::Full { color: Color::BLACK, width, .. }) if (width - 1.).abs() < 10e-5
+            ));
+        }
+    }
+
+    #[test]
+    fn metadata() {
+        let mut inner = dessin2!([Circle(radius = 1.)]);
+        inner.add_metadata(("name", "inner"));
+        let mut outer = dessin2!([{ inner }, Circle(radius = 1.)]);
+        outer.add_metadata(("name", "outer"));
+
+        let list = DisplayList::new(&outer);
+        let metadata = |idx: usize| {
+            list.items[idx]
+                .metadata
+                .iter()
+                .map(|(_, v)| v.as_str())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(metadata(0), vec!["outer", "inner"]);
+        assert_eq!(metadata(1), vec!["outer"]);
+    }
+}
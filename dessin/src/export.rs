@@ -5,8 +5,11 @@
 //!
 //! ## Examples
 //! Examples can be found for [PDF](https://docs.rs/dessin-pdf/) or [SVG](https://docs.rs/dessin-svg/)
-use crate::prelude::*;
-use nalgebra::Transform2;
+mod shadow;
+
+use crate::{prelude::*, validate::ValidationWarning};
+use nalgebra::{Transform2, Vector2};
+use std::fmt;
 
 /// Orchestrator of the export
 ///
@@ -61,56 +64,580 @@ where
         exporter: &mut E,
         parent_transform: &Transform2<f32>,
     ) -> Result<(), <E as Exporter>::Error> {
-        match self {
-            Shape::Group(Group {
-                local_transform,
-                shapes,
-                metadata,
-            }) => {
-                exporter.start_block(metadata.as_slice())?;
-
-                let parent_transform = parent_transform * local_transform;
-                for shape in shapes {
-                    shape.write_into_exporter(exporter, &parent_transform)?;
-                }
+        write_shape(self, exporter, parent_transform, Offset::NONE)
+    }
+}
+
+/// Hint skipping a group, and everything in it, when set to `true`
+pub const SKIP_HINT: &str = "skip";
+
+/// Value of the hint `key` for `backend`, from the metadata of a group.
+///
+/// Hints are metadata whose key is prefixed by the name of a backend, such as `("pdf:skip", "true")`.
+/// They let a dessin ask for something specific to a single export format,
+/// and are ignored by the exporters of other backends.
+///
+/// ```
+/// # use dessin::prelude::*;
+/// use dessin::export::hint;
+///
+/// let metadata = vec![
+/// 	("svg:filter".to_string(), "url(#blur)".to_string()),
+/// 	("pdf:skip".to_string(), "true".to_string()),
+/// ];
+///
+/// assert_eq!(hint(&metadata, "svg", "filter"), Some("url(#blur)"));
+/// assert_eq!(hint(&metadata, "pdf", "skip"), Some("true"));
+/// assert_eq!(hint(&metadata, "svg", "skip"), None);
+/// ```
+pub fn hint<'a>(metadata: &'a [(String, String)], backend: &str, key: &str) -> Option<&'a str> {
+    metadata.iter().find_map(|(k, v)| match split_hint(k) {
+        Some((b, k)) if b == backend && k == key => Some(v.as_str()),
+        _ => None,
+    })
+}
+
+/// Backend and key of a hint, or `None` if `key` is plain metadata
+pub fn split_hint(key: &str) -> Option<(&str, &str)> {
+    key.split_once(':')
+}
+
+/// Style of the shapes inside a stack of styles given to [`start_style`][Exporter::start_style], the innermost being last.
+///
+/// Fills and strokes come from the innermost style which has one, and blend modes from the innermost one which isn't normal.
+/// Opacities are multiplied, and filters, which apply to a style as a whole, are left out.
+///
+/// ```
+/// # use dessin::{prelude::*, export::inherited_style};
+/// let outer = StylePosition {
+/// 	stroke: Some(Stroke::from((Color::BLACK, 1.))),
+/// 	fill: Some(Fill::Color(Color::RED)),
+/// 	filter: None,
+/// 	opacity: 0.5,
+/// 	blend_mode: BlendMode::Multiply,
+/// };
+/// let inner = StylePosition {
+/// 	stroke: None,
+/// 	fill: Some(Fill::Color(Color::BLUE)),
+/// 	filter: None,
+/// 	opacity: 0.5,
+/// 	blend_mode: BlendMode::Normal,
+/// };
+///
+/// let style = inherited_style(&[outer, inner]);
+/// assert_eq!(style.fill, Some(Fill::Color(Color::BLUE)));
+/// assert_eq!(style.stroke, Some(Stroke::from((Color::BLACK, 1.))));
+/// assert_eq!(style.opacity, 0.25);
+/// assert_eq!(style.blend_mode, BlendMode::Multiply);
+/// ```
+pub fn inherited_style(styles: &[StylePosition]) -> StylePosition {
+    let mut acc = StylePosition {
+        stroke: None,
+        fill: None,
+        filter: None,
+        opacity: 1.,
+        blend_mode: BlendMode::Normal,
+    };
+
+    for style in styles.iter().rev() {
+        if acc.fill.is_none() {
+            acc.fill = style.fill.clone();
+        }
+        if acc.stroke.is_none() {
+            acc.stroke = style.stroke;
+        }
+        if acc.blend_mode == BlendMode::Normal {
+            acc.blend_mode = style.blend_mode;
+        }
+        acc.opacity *= style.opacity;
+    }
+
+    acc
+}
+
+/// Offset of closed outlines, honoring the [`StrokeAlignment`] of the current stroke
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Offset {
+    distance: f32,
+    /// Join of the stroke, closing the gaps at outer corners
+    join: LineJoin,
+    miter_limit: f32,
+}
+impl Offset {
+    const NONE: Offset = Offset {
+        distance: 0.,
+        join: LineJoin::Miter,
+        miter_limit: Stroke::DEFAULT_MITER_LIMIT,
+    };
+}
 
-                exporter.end_block(metadata.as_slice())?;
+/// Export a shape, offsetting closed outlines by `offset`
+fn write_shape<E: Exporter>(
+    shape: &Shape,
+    exporter: &mut E,
+    parent_transform: &Transform2<f32>,
+    offset: Offset,
+) -> Result<(), E::Error> {
+    // Offset outlines can't be written any other way
+    if offset.distance == 0. && exporter.export_subtree(shape, parent_transform)? {
+        return Ok(());
+    }
 
-                Ok(())
+    match shape {
+        Shape::Group(Group {
+            local_transform,
+            shapes,
+            metadata,
+        }) => {
+            if E::BACKEND.is_some_and(|backend| hint(metadata, backend, SKIP_HINT) == Some("true"))
+            {
+                return Ok(());
             }
-            Shape::Style {
-                fill,
-                stroke,
-                shape,
-            } => {
-                let style = StylePosition {
-                    fill: fill.clone(),
-                    stroke: stroke.clone().map(|v| *parent_transform * v),
-                };
 
-                exporter.start_style(style)?;
-                shape.write_into_exporter(exporter, parent_transform)?;
-                exporter.end_style()
+            // The mask is only drawn by exporters supporting masks, through `export_subtree`
+            let shapes = match shapes.as_slice() {
+                [shape, _] if Mask::is_mask(metadata) => std::slice::from_ref(shape),
+                shapes => shapes,
+            };
+
+            exporter.start_block(metadata.as_slice())?;
+
+            let parent_transform = parent_transform * local_transform;
+            for shape in shapes {
+                write_shape(shape, exporter, &parent_transform, offset)?;
             }
-            Shape::Image(image) => exporter.export_image(image.position(parent_transform)),
-            Shape::Ellipse(ellipse) => {
-                if E::CAN_EXPORT_ELLIPSE {
-                    exporter.export_ellipse(ellipse.position(parent_transform))
-                } else {
-                    exporter.export_curve(ellipse.as_curve().position(parent_transform))
+
+            exporter.end_block(metadata.as_slice())?;
+
+            Ok(())
+        }
+        // Colors made transparent instead, for exporters drawing no opacity
+        Shape::Style {
+            fill,
+            stroke,
+            stroke_alignment,
+            filter,
+            opacity,
+            blend_mode,
+            shape,
+        } if !E::CAN_EXPORT_OPACITY && *opacity != 1. => {
+            let faded = Shape::Style {
+                fill: fill.clone(),
+                stroke: *stroke,
+                stroke_alignment: *stroke_alignment,
+                filter: *filter,
+                opacity: 1.,
+                blend_mode: *blend_mode,
+                shape: shape.clone(),
+            }
+            .faded(*opacity);
+
+            write_shape(&faded, exporter, parent_transform, offset)
+        }
+        // Shadows drawn as an image of the blurred silhouette, for exporters drawing no filter
+        Shape::Style {
+            fill,
+            stroke,
+            stroke_alignment,
+            filter:
+                Some(Filter::DropShadow {
+                    dx,
+                    dy,
+                    std_deviation,
+                    color,
+                }),
+            opacity,
+            blend_mode,
+            shape,
+        } if !E::CAN_EXPORT_FILTER => {
+            let styled = Shape::Style {
+                fill: fill.clone(),
+                stroke: *stroke,
+                stroke_alignment: *stroke_alignment,
+                filter: None,
+                opacity: 1.,
+                blend_mode: BlendMode::Normal,
+                shape: shape.clone(),
+            };
+            let shadow = shadow::shadow_image(
+                &styled,
+                Shadow {
+                    offset: Vector2::new(*dx, *dy),
+                    blur: *std_deviation,
+                    color: *color,
+                },
+            );
+            let lowered = Shape::Group(Group {
+                local_transform: Transform2::identity(),
+                shapes: shadow.into_iter().chain([styled]).collect(),
+                metadata: vec![],
+            });
+            let lowered = if *opacity != 1. || *blend_mode != BlendMode::Normal {
+                Shape::Style {
+                    fill: None,
+                    stroke: None,
+                    stroke_alignment: StrokeAlignment::Center,
+                    filter: None,
+                    opacity: *opacity,
+                    blend_mode: *blend_mode,
+                    shape: Box::new(lowered),
+                }
+            } else {
+                lowered
+            };
+
+            write_shape(&lowered, exporter, parent_transform, offset)
+        }
+        // Images are always clipped by the filled shape, and patterns by exporters drawing no pattern
+        Shape::Style {
+            fill: Some(fill @ (Fill::Pattern { .. } | Fill::Image { .. })),
+            stroke,
+            stroke_alignment,
+            filter,
+            opacity,
+            blend_mode,
+            shape,
+        } if !E::CAN_EXPORT_PATTERN || matches!(fill, Fill::Image { .. }) => {
+            let area = shape.local_bounding_box().straigthen();
+            let content = match fill {
+                Fill::Image { image, fit } => fitted_image(image, *fit, &area),
+                fill => pattern_tiles(fill, &area),
+            };
+            let fallback = Shape::Group(Group {
+                local_transform: Transform2::identity(),
+                shapes: vec![
+                    Clip::default()
+                        .with_shape(content)
+                        .with_clipped_by(shape.as_ref().clone())
+                        .into(),
+                    Shape::Style {
+                        fill: None,
+                        stroke: *stroke,
+                        stroke_alignment: *stroke_alignment,
+                        filter: *filter,
+                        opacity: 1.,
+                        blend_mode: BlendMode::Normal,
+                        shape: shape.clone(),
+                    },
+                ],
+                metadata: vec![],
+            });
+            let fallback = if *opacity != 1. || *blend_mode != BlendMode::Normal {
+                Shape::Style {
+                    fill: None,
+                    stroke: None,
+                    stroke_alignment: StrokeAlignment::Center,
+                    filter: None,
+                    opacity: *opacity,
+                    blend_mode: *blend_mode,
+                    shape: Box::new(fallback),
                 }
+            } else {
+                fallback
+            };
+
+            write_shape(&fallback, exporter, parent_transform, offset)
+        }
+        Shape::Style {
+            fill,
+            stroke,
+            stroke_alignment,
+            filter,
+            opacity,
+            blend_mode,
+            shape,
+        } => {
+            let style = StylePosition {
+                fill: fill.clone().map(|v| *parent_transform * v),
+                stroke: stroke.map(|v| *parent_transform * v),
+                filter: filter
+                    .filter(|_| E::CAN_EXPORT_FILTER)
+                    .map(|v| *parent_transform * v),
+                opacity: *opacity,
+                blend_mode: if E::CAN_EXPORT_BLEND_MODE {
+                    *blend_mode
+                } else {
+                    BlendMode::Normal
+                },
+            };
+
+            // Fills are painted under strokes, so offsetting the whole outline keeps the fill visible up to the original outline
+            let offset = match (&style.stroke, stroke_alignment) {
+                (None, _) => offset,
+                (Some(_), StrokeAlignment::Center) => Offset::NONE,
+                (Some(stroke), StrokeAlignment::Inside | StrokeAlignment::Outside) => Offset {
+                    distance: match stroke_alignment {
+                        StrokeAlignment::Inside => -stroke.width() / 2.,
+                        _ => stroke.width() / 2.,
+                    },
+                    join: stroke.join(),
+                    miter_limit: stroke.miter_limit(),
+                },
+            };
+
+            // Markers are filled with the color of the stroke, after the stroke itself
+            let marked = stroke.and_then(|stroke| {
+                place_markers(shape, &Transform2::identity(), stroke.width())
+                    .map(|(shape, markers)| (shape, markers, stroke.color()))
+            });
+            let styled = marked.as_ref().map_or(shape.as_ref(), |(shape, ..)| shape);
+
+            exporter.start_style(style)?;
+            write_shape(styled, exporter, parent_transform, offset)?;
+            exporter.end_style()?;
+
+            match marked {
+                Some((_, markers, color)) => write_shape(
+                    &Shape::Style {
+                        fill: Some(Fill::Color(color)),
+                        stroke: None,
+                        stroke_alignment: StrokeAlignment::Center,
+                        filter: *filter,
+                        opacity: *opacity,
+                        blend_mode: *blend_mode,
+                        shape: Box::new(Shape::Group(Group {
+                            local_transform: Transform2::identity(),
+                            shapes: markers,
+                            metadata: vec![],
+                        })),
+                    },
+                    exporter,
+                    parent_transform,
+                    Offset::NONE,
+                ),
+                None => Ok(()),
             }
-            Shape::Curve(curve) => exporter.export_curve(curve.position(parent_transform)),
-            Shape::Text(text) => exporter.export_text(text.position(parent_transform)),
-            Shape::Dynamic {
-                local_transform,
-                shaper,
-            } => {
-                let shape = shaper();
-                let parent_transform = parent_transform * local_transform;
-                shape.write_into_exporter(exporter, &parent_transform)
+        }
+        Shape::Image(image) => {
+            let position = image.position(parent_transform);
+
+            match &image.lazy_path {
+                None => exporter.export_image(position),
+                Some(path) => match crate::shapes::open_upright(path) {
+                    Ok(pixels) => exporter.export_image(ImagePosition {
+                        image: &pixels,
+                        ..position
+                    }),
+                    Err(_) => write_shape(
+                        &crate::shapes::image::placeholder(&position, &path.to_string_lossy()),
+                        exporter,
+                        &Transform2::identity(),
+                        Offset::NONE,
+                    ),
+                },
+            }
+        }
+        Shape::Ellipse(ellipse) => {
+            let position = ellipse.position(parent_transform);
+            let is_circle = (position.semi_major_axis - position.semi_minor_axis).abs() < 10e-6;
+
+            if E::CAN_EXPORT_ELLIPSE && (offset.distance == 0. || is_circle) {
+                exporter.export_ellipse(EllipsePosition {
+                    semi_major_axis: (position.semi_major_axis + offset.distance).max(0.),
+                    semi_minor_axis: (position.semi_minor_axis + offset.distance).max(0.),
+                    ..position
+                })
+            } else {
+                let curve = ellipse.as_curve().position(parent_transform);
+                exporter.export_curve(offset_curve(curve, offset))
+            }
+        }
+        Shape::Curve(curve) => {
+            exporter.export_curve(offset_curve(curve.position(parent_transform), offset))
+        }
+        Shape::Text(text) => exporter.export_text(text.position(parent_transform)),
+        Shape::LevelOfDetail(lod) => write_shape(
+            lod.pick(parent_transform),
+            exporter,
+            &lod.global_transform(parent_transform),
+            offset,
+        ),
+        Shape::Dynamic {
+            local_transform,
+            shaper,
+        } => {
+            let shape = shaper();
+            let parent_transform = parent_transform * local_transform;
+            write_shape(&shape, exporter, &parent_transform, offset)
+        }
+    }
+}
+
+/// `shape` with its curves shortened under their [markers][Marker], and the markers, for a stroke of `width`.
+///
+/// `transform` goes from the coordinates of `shape` to the ones of the style defining the stroke, where markers are returned.
+/// `None` if no curve has a marker, curves under another stroke being left to it.
+fn place_markers(
+    shape: &Shape,
+    transform: &Transform2<f32>,
+    width: f32,
+) -> Option<(Shape, Vec<Shape>)> {
+    match shape {
+        Shape::Group(Group {
+            local_transform,
+            shapes,
+            metadata,
+        }) => {
+            let transform = transform * local_transform;
+            let placed = shapes
+                .iter()
+                .map(|shape| place_markers(shape, &transform, width))
+                .collect::<Vec<_>>();
+            if placed.iter().all(Option::is_none) {
+                return None;
             }
+
+            let mut markers = vec![];
+            let shapes = shapes
+                .iter()
+                .zip(placed)
+                .map(|(shape, placed)| match placed {
+                    Some((shape, placed)) => {
+                        markers.extend(placed);
+                        shape
+                    }
+                    None => shape.clone(),
+                })
+                .collect();
+
+            Some((
+                Shape::Group(Group {
+                    local_transform: *local_transform,
+                    shapes,
+                    metadata: metadata.clone(),
+                }),
+                markers,
+            ))
         }
+        Shape::Style {
+            fill,
+            stroke: None,
+            stroke_alignment,
+            filter,
+            opacity,
+            blend_mode,
+            shape,
+        } => {
+            let (shape, markers) = place_markers(shape, transform, width)?;
+            Some((
+                Shape::Style {
+                    fill: fill.clone(),
+                    stroke: None,
+                    stroke_alignment: *stroke_alignment,
+                    filter: *filter,
+                    opacity: *opacity,
+                    blend_mode: *blend_mode,
+                    shape: Box::new(shape),
+                },
+                markers,
+            ))
+        }
+        Shape::Curve(curve) if curve.start_marker.is_some() || curve.end_marker.is_some() => {
+            let inverse = transform.try_inverse()?;
+            let (placed, markers) = curve
+                .clone()
+                .with_transform(*transform)
+                .place_markers(width);
+
+            Some((
+                Shape::Curve(Curve {
+                    local_transform: inverse,
+                    ..placed
+                }),
+                vec![markers],
+            ))
+        }
+        _ => None,
+    }
+}
+
+/// Image of a [`Fill::Image`] sized by `fit` and centered on `area`
+fn fitted_image(image: &ImageRef, fit: ImageFit, area: &BoundingBox<Straight>) -> Shape {
+    let image = image.image();
+    let (width, height) = fit.size(image.aspect_ratio(), area.width(), area.height());
+
+    Image {
+        local_transform: Transform2::identity(),
+        ..image.clone()
+    }
+    .with_resize([width, height])
+    .with_translate(area.center().coords)
+    .into()
+}
+
+/// Most copies of a tile drawn by [`pattern_tiles`]
+const MAX_PATTERN_TILES: usize = 10_000;
+
+/// Copies of the tile of a [`Fill::Pattern`] covering `area`, for exporters drawing no pattern
+fn pattern_tiles(fill: &Fill, area: &BoundingBox<Straight>) -> Shape {
+    let Fill::Pattern {
+        pattern,
+        spacing,
+        transform,
+    } = fill
+    else {
+        return Shape::default();
+    };
+    let Some(inverse) = transform.try_inverse() else {
+        return Shape::default();
+    };
+    if spacing.x <= 0. || spacing.y <= 0. {
+        return Shape::default();
+    }
+
+    // Cells covering the area, plus one around for tiles overflowing their cell
+    let corners = [
+        area.top_left(),
+        area.top_right(),
+        area.bottom_right(),
+        area.bottom_left(),
+    ]
+    .map(|corner| inverse * corner);
+    let range = |coordinate: fn(&nalgebra::Point2<f32>) -> f32, spacing: f32| {
+        let (min, max) = corners
+            .iter()
+            .map(coordinate)
+            .fold((f32::MAX, f32::MIN), |(min, max), c| {
+                (min.min(c), max.max(c))
+            });
+        ((min / spacing).round() as i64 - 1)..=((max / spacing).round() as i64 + 1)
+    };
+    let (columns, rows) = (range(|p| p.x, spacing.x), range(|p| p.y, spacing.y));
+
+    let tile = pattern.tile();
+    let shapes = rows
+        .flat_map(|row| columns.clone().map(move |column| (column, row)))
+        .take(MAX_PATTERN_TILES)
+        .map(|(column, row)| {
+            tile.clone()
+                .with_translate([column as f32 * spacing.x, row as f32 * spacing.y])
+        })
+        .collect();
+
+    Shape::Group(Group {
+        local_transform: *transform,
+        shapes,
+        metadata: vec![],
+    })
+}
+
+#[inline]
+fn offset_curve(curve: CurvePosition, offset: Offset) -> CurvePosition {
+    let Offset {
+        distance,
+        join,
+        miter_limit,
+    } = offset;
+
+    if distance == 0. {
+        curve
+    } else {
+        curve.offset(
+            distance,
+            join,
+            miter_limit,
+            (distance.abs() / 25.).max(10e-4),
+        )
     }
 }
 
@@ -173,6 +700,29 @@ pub trait Exporter {
     ///
     const CAN_EXPORT_ELLIPSE: bool = true;
 
+    /// Whether [filters][crate::style::Filter] are given to [`start_style`][Exporter::start_style].
+    ///
+    /// Otherwise, drop shadows are drawn as an image of the blurred silhouette of the shape, under it, and other filters are ignored.
+    const CAN_EXPORT_FILTER: bool = false;
+
+    /// Whether [pattern fills][crate::style::Fill::Pattern] are given to [`start_style`][Exporter::start_style].
+    ///
+    /// Otherwise, copies of the tile are exported in a [`Clip`] of the filled shape.
+    const CAN_EXPORT_PATTERN: bool = false;
+
+    /// Whether the [opacity of styles][crate::style::Style::opacity] is given to [`start_style`][Exporter::start_style].
+    ///
+    /// Otherwise, the styled shapes are [faded][Shape::faded] instead.
+    const CAN_EXPORT_OPACITY: bool = false;
+
+    /// Whether the [blend mode of styles][crate::style::Style::blend_mode] is given to [`start_style`][Exporter::start_style].
+    ///
+    /// Otherwise, the styled shapes are drawn normally.
+    const CAN_EXPORT_BLEND_MODE: bool = false;
+
+    /// Name of the backend, used to find the [hints][hint] meant for this exporter, such as `svg` or `pdf`
+    const BACKEND: Option<&'static str> = None;
+
     /// Enter a scope of style
     ///
     /// All [`Shape`][crate::shapes::Shape] between [`start_style`][Exporter::start_style] and [`end_style`][Exporter::end_style] must have this style applied to them.
@@ -189,6 +739,19 @@ pub trait Exporter {
         Ok(())
     }
 
+    /// Export `shape`, and everything in it, a way of its own, returning `false` to let it be exported as usual.
+    ///
+    /// Called before every shape, for instance to reference an identical one already exported.
+    /// `parent_transform` is the transform of the parent of `shape`, its own transform being still to apply.
+    /// Never called under a stroke aligned to the inside or the outside.
+    fn export_subtree(
+        &mut self,
+        _shape: &Shape,
+        _parent_transform: &Transform2<f32>,
+    ) -> Result<bool, Self::Error> {
+        Ok(false)
+    }
+
     /// Export an [`Image`][crate::shapes::image::Image]
     fn export_image(&mut self, image: ImagePosition) -> Result<(), Self::Error>;
     /// Export an [`Ellipse`][crate::shapes::ellipse::Ellipse]
@@ -200,3 +763,746 @@ pub trait Exporter {
     /// Export a [`Text`][crate::shapes::text::Text]
     fn export_text(&mut self, text: TextPosition) -> Result<(), Self::Error>;
 }
+
+/// What exporters draw behind a dessin
+#[derive(Debug, Clone)]
+pub enum Background {
+    /// Fill the whole canvas with a color
+    Color(Color),
+    /// Draw a shape behind the dessin, as positioned in the dessin
+    Shape(Box<Shape>),
+}
+impl From<Color> for Background {
+    fn from(color: Color) -> Self {
+        Background::Color(color)
+    }
+}
+
+/// Draw a `background` behind a dessin, and a `border` on the edges of the canvas.
+///
+/// The canvas is the area of the dessin covered by the export, and the border is drawn inside it, so it is never cropped.
+///
+/// ```
+/// # use dessin::{prelude::*, export::*};
+/// let dessin = dessin2!(Circle!(fill = Color::WHITE, radius = 10.) > ());
+///
+/// let framed = with_backdrop(
+/// 	&dessin,
+/// 	&BoundingBox::centered([30., 30.]),
+/// 	Some(&Background::Color(Color::BLACK)),
+/// 	Some(Stroke::from((Color::GRAY, 1.))),
+/// );
+/// ```
+pub fn with_backdrop(
+    shape: &Shape,
+    canvas: &BoundingBox<Straight>,
+    background: Option<&Background>,
+    border: Option<Stroke>,
+) -> Shape {
+    let outline = |inset: f32| Curve {
+        local_transform: Default::default(),
+        keypoints: vec![
+            Keypoint::Point([canvas.left() + inset, canvas.bottom() + inset].into()),
+            Keypoint::Point([canvas.right() - inset, canvas.bottom() + inset].into()),
+            Keypoint::Point([canvas.right() - inset, canvas.top() - inset].into()),
+            Keypoint::Point([canvas.left() + inset, canvas.top() - inset].into()),
+        ],
+        closed: true,
+        start_marker: None,
+        end_marker: None,
+        fill_rule: FillRule::NonZero,
+    };
+
+    let mut shapes = vec![];
+    match background {
+        Some(Background::Color(color)) => {
+            shapes.push(Style::new(outline(0.)).with_fill(*color).into());
+        }
+        Some(Background::Shape(background)) => shapes.push(background.as_ref().clone()),
+        None => {}
+    }
+    shapes.push(shape.clone());
+    if let Some(border) = border {
+        shapes.push(
+            Style::new(outline(border.width() / 2.))
+                .with_stroke(border)
+                .into(),
+        );
+    }
+
+    Shape::Group(Group {
+        local_transform: Default::default(),
+        shapes,
+        metadata: vec![],
+    })
+}
+
+/// What exporters embedding fonts do with the ones which are [restricted][crate::font::EmbeddingPermission::Restricted]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RestrictedFontPolicy {
+    /// Embed them, [`Shape::validate`] warning about them
+    #[default]
+    Warn,
+    /// Fail the export
+    Deny,
+}
+
+/// Unit of the lengths of a dessin, or of the page it is printed on
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LengthUnit {
+    /// Millimeter
+    #[default]
+    Millimeter,
+    /// Centimeter
+    Centimeter,
+    /// Meter
+    Meter,
+    /// Kilometer
+    Kilometer,
+    /// Inch
+    Inch,
+    /// Foot
+    Foot,
+    /// Typographic point, 1/72 of an inch
+    Point,
+}
+impl LengthUnit {
+    /// Length of one unit, in millimeters
+    pub fn millimeters(&self) -> f32 {
+        match self {
+            LengthUnit::Millimeter => 1.,
+            LengthUnit::Centimeter => 10.,
+            LengthUnit::Meter => 1_000.,
+            LengthUnit::Kilometer => 1_000_000.,
+            LengthUnit::Inch => 25.4,
+            LengthUnit::Foot => 304.8,
+            LengthUnit::Point => 25.4 / 72.,
+        }
+    }
+
+    /// Symbol of the unit
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            LengthUnit::Millimeter => "mm",
+            LengthUnit::Centimeter => "cm",
+            LengthUnit::Meter => "m",
+            LengthUnit::Kilometer => "km",
+            LengthUnit::Inch => "in",
+            LengthUnit::Foot => "ft",
+            LengthUnit::Point => "pt",
+        }
+    }
+}
+
+/// Scale and unit of a drawing, stamped by exporters in the bottom right corner of the canvas,
+/// for drawings measured off paper.
+///
+/// The world, what is drawn, is mapped to the page by a transform, usually the one of the group holding it.
+/// [`validate`][ScaleStatement::validate] checks this mapping matches the declared scale.
+///
+/// ```
+/// # use dessin::{prelude::*, export::*};
+/// use nalgebra::{Scale2, Transform2};
+///
+/// // A 12 m long building, drawn at 1:100
+/// let building = dessin2!(Rectangle!(stroke = (Color::BLACK, 0.05), width = 12., height = 8.) > ());
+/// let world_to_page = nalgebra::convert::<_, Transform2<f32>>(Scale2::new(10., 10.));
+/// let page = dessin2!([{ building }(transform = world_to_page)]);
+///
+/// let scale = ScaleStatement::new(100., LengthUnit::Meter);
+/// assert_eq!(scale.to_string(), "1:100 (m)");
+/// assert_eq!(scale.validate(&world_to_page), None);
+/// assert!(ScaleStatement::new(50., LengthUnit::Meter).validate(&world_to_page).is_some());
+///
+/// let stamped = with_scale_statement(&page, &BoundingBox::centered([210., 148.]), &scale);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScaleStatement {
+    /// Lengths in the world are `ratio` times the lengths on the page, 50 for 1:50, 0.5 for 2:1
+    pub ratio: f32,
+    /// Unit of the lengths of the world
+    pub world_unit: LengthUnit,
+    /// Unit of the lengths of the page, millimeters by default
+    pub page_unit: LengthUnit,
+    /// Font size of the stamped statement, in units of the page
+    pub font_size: f32,
+}
+impl ScaleStatement {
+    /// Scale of 1:`ratio`, of a world measured in `world_unit`, on a page in millimeters
+    pub fn new(ratio: f32, world_unit: LengthUnit) -> Self {
+        ScaleStatement {
+            ratio,
+            world_unit,
+            page_unit: LengthUnit::Millimeter,
+            font_size: 3.5,
+        }
+    }
+
+    /// Units of the page for a unit of the world
+    pub fn world_to_page(&self) -> f32 {
+        self.world_unit.millimeters() / (self.ratio * self.page_unit.millimeters())
+    }
+
+    /// Warn if `world_to_page`, mapping the world to the page, doesn't match this scale
+    pub fn validate(&self, world_to_page: &Transform2<f32>) -> Option<ValidationWarning> {
+        let m = world_to_page.matrix();
+        let scale = (m[(0, 0)] * m[(1, 1)] - m[(0, 1)] * m[(1, 0)]).abs().sqrt();
+        let actual = self.world_unit.millimeters() / (scale * self.page_unit.millimeters());
+
+        ((actual - self.ratio).abs() > self.ratio * 10e-4).then_some(
+            ValidationWarning::ScaleMismatch {
+                declared: self.ratio,
+                actual,
+            },
+        )
+    }
+}
+impl fmt::Display for ScaleStatement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.ratio >= 1. {
+            write!(f, "1:{}", self.ratio)?;
+        } else {
+            write!(f, "{}:1", 1. / self.ratio)?;
+        }
+        write!(f, " ({})", self.world_unit.symbol())
+    }
+}
+
+/// Stamp `statement` in the bottom right corner of the canvas, inside it
+pub fn with_scale_statement(
+    shape: &Shape,
+    canvas: &BoundingBox<Straight>,
+    statement: &ScaleStatement,
+) -> Shape {
+    let margin = statement.font_size / 2.;
+    let stamp = Style::new(Text {
+        text: statement.to_string(),
+        align: TextAlign::Right,
+        vertical_align: TextVerticalAlign::Bottom,
+        font_size: statement.font_size,
+        ..Default::default()
+    })
+    .with_fill(Color::BLACK)
+    .with_translate([canvas.right() - margin, canvas.bottom() + margin]);
+
+    Shape::Group(Group {
+        local_transform: Default::default(),
+        shapes: vec![shape.clone(), stamp.into()],
+        metadata: vec![],
+    })
+}
+
+/// Shape stamped by exporters on the canvas of every page, such as a DRAFT watermark or a CONFIDENTIAL marking.
+///
+/// The stamp is placed by its bounding box, on the same point of the canvas as its [`position`][Stamp::position].
+///
+/// ```
+/// # use dessin::{prelude::*, export::*};
+/// let page = dessin2!(Rectangle!(stroke = (Color::BLACK, 0.1), width = 200., height = 280.) > ());
+///
+/// let confidential = dessin2!(Text!(fill = Color::RED, text = "CONFIDENTIAL", font_size = 5.) > ());
+/// let stamps = [
+/// 	Stamp::watermark("DRAFT"),
+/// 	Stamp::new(confidential)
+/// 		.with_position(BoxAnchor::TopRight)
+/// 		.with_margin(5.),
+/// ];
+///
+/// let stamped = with_stamps(&page, &BoundingBox::centered([210., 297.]), &stamps);
+///
+/// let bb = stamped.local_bounding_box().straigthen();
+/// assert!((bb.top() - 297. / 2.).abs() < 5. + 10e-4);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Stamp {
+    /// Shape stamped
+    pub shape: Shape,
+    /// Point of the canvas where the same point of the stamp is placed
+    pub position: BoxAnchor,
+    /// Distance to the edges of the canvas
+    pub margin: f32,
+    /// Drawn under the dessin, instead of over it
+    pub below: bool,
+}
+impl Stamp {
+    /// Stamp `shape` in the center of the canvas, over the dessin
+    pub fn new<T: Into<Shape>>(shape: T) -> Self {
+        Stamp {
+            shape: shape.into(),
+            position: BoxAnchor::Center,
+            margin: 0.,
+            below: false,
+        }
+    }
+
+    /// Large translucent `text` across the center of the canvas
+    pub fn watermark<S: Into<String>>(text: S) -> Self {
+        let text = Style::new(Text {
+            text: text.into(),
+            align: TextAlign::Center,
+            vertical_align: TextVerticalAlign::Center,
+            font_size: 40.,
+            ..Default::default()
+        })
+        .with_fill(rgba(128, 128, 128, 64))
+        .with_rotate(Angle::degrees(45.));
+
+        Stamp::new(text)
+    }
+
+    /// Point of the canvas where the same point of the stamp is placed
+    #[inline]
+    pub fn position(&mut self, position: BoxAnchor) -> &mut Self {
+        self.position = position;
+        self
+    }
+    /// Point of the canvas where the same point of the stamp is placed
+    #[inline]
+    pub fn with_position(mut self, position: BoxAnchor) -> Self {
+        self.position(position);
+        self
+    }
+
+    /// Distance to the edges of the canvas
+    #[inline]
+    pub fn margin(&mut self, margin: f32) -> &mut Self {
+        self.margin = margin;
+        self
+    }
+    /// Distance to the edges of the canvas
+    #[inline]
+    pub fn with_margin(mut self, margin: f32) -> Self {
+        self.margin(margin);
+        self
+    }
+
+    /// Draw the stamp under the dessin
+    #[inline]
+    pub fn below(&mut self, below: bool) -> &mut Self {
+        self.below = below;
+        self
+    }
+    /// Draw the stamp under the dessin
+    #[inline]
+    pub fn with_below(mut self, below: bool) -> Self {
+        self.below(below);
+        self
+    }
+
+    /// The stamp, placed on `canvas`
+    pub fn placed_on(&self, canvas: &BoundingBox<Straight>) -> Shape {
+        let direction = self.position.direction();
+        let bb = self.shape.local_bounding_box().straigthen();
+
+        let point = |bb: &BoundingBox<Straight>, inset: f32| {
+            let half = Vector2::new(bb.width() / 2. - inset, bb.height() / 2. - inset);
+            bb.center() + direction.component_mul(&half)
+        };
+
+        self.shape
+            .clone()
+            .with_translate(point(canvas, self.margin) - point(&bb, 0.))
+    }
+}
+
+/// Draw `stamps` on the canvas, under or over `shape`
+pub fn with_stamps(shape: &Shape, canvas: &BoundingBox<Straight>, stamps: &[Stamp]) -> Shape {
+    let placed = |below: bool| {
+        stamps
+            .iter()
+            .filter(move |stamp| stamp.below == below)
+            .map(|stamp| stamp.placed_on(canvas))
+    };
+
+    Shape::Group(Group {
+        local_transform: Default::default(),
+        shapes: placed(true)
+            .chain([shape.clone()])
+            .chain(placed(false))
+            .collect(),
+        metadata: vec![],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    struct Recorder {
+        styles: Vec<StylePosition>,
+        images: Vec<(u32, u32)>,
+        ellipses: Vec<EllipsePosition>,
+        curves: Vec<CurvePosition>,
+    }
+    impl Exporter for Recorder {
+        type Error = ();
+        const BACKEND: Option<&'static str> = Some("recorder");
+        fn start_style(&mut self, style: StylePosition) -> Result<(), Self::Error> {
+            self.styles.push(style);
+            Ok(())
+        }
+        fn end_style(&mut self) -> Result<(), Self::Error> {
+            Ok(())
+        }
+        fn export_image(&mut self, image: ImagePosition) -> Result<(), Self::Error> {
+            self.images
+                .push((image.image.width(), image.image.height()));
+            Ok(())
+        }
+        fn export_ellipse(&mut self, ellipse: EllipsePosition) -> Result<(), Self::Error> {
+            self.ellipses.push(ellipse);
+            Ok(())
+        }
+        fn export_curve(&mut self, curve: CurvePosition) -> Result<(), Self::Error> {
+            self.curves.push(curve);
+            Ok(())
+        }
+        fn export_text(&mut self, _text: TextPosition) -> Result<(), Self::Error> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn stroke_alignment() {
+        let mut recorder = Recorder::default();
+        let dessin = dessin2!([
+            Circle!(stroke = (Color::BLACK, 2.), radius = 10.),
+            Circle!(
+                stroke = (Color::BLACK, 2.),
+                stroke_alignment = StrokeAlignment::Inside,
+                radius = 10.
+            ),
+            Rectangle!(
+                stroke = (Color::BLACK, 2.),
+                stroke_alignment = StrokeAlignment::Outside,
+                width = 10.,
+                height = 10.
+            ),
+        ]);
+        dessin
+            .write_into_exporter(&mut recorder, &Default::default())
+            .unwrap();
+
+        assert_eq!(recorder.ellipses[0].semi_major_axis, 10.);
+        assert_eq!(recorder.ellipses[1].semi_major_axis, 9.);
+
+        let bb = recorder.curves[0]
+            .flatten(0.1)
+            .into_iter()
+            .map(BoundingBox::at)
+            .reduce(BoundingBox::join)
+            .unwrap();
+        assert!((bb.width() - 12.).abs() < 10e-5);
+        assert!((bb.height() - 12.).abs() < 10e-5);
+    }
+
+    #[test]
+    fn stroke_alignment_joins() {
+        let mut recorder = Recorder::default();
+        let square = |join| {
+            dessin2!(Rectangle!(
+                stroke = Stroke::from((Color::BLACK, 2.)).with_join(join),
+                stroke_alignment = StrokeAlignment::Outside,
+                width = 10.,
+                height = 10.
+            ))
+        };
+        let dessin = dessin2!([
+            { square(LineJoin::Miter) },
+            { square(LineJoin::Bevel) },
+            Line!(
+                stroke = (Color::BLACK, 2.),
+                stroke_alignment = StrokeAlignment::Outside,
+                from = [0., 0.],
+                to = [10., 0.]
+            ),
+        ]);
+        dessin
+            .write_into_exporter(&mut recorder, &Default::default())
+            .unwrap();
+
+        assert_eq!(recorder.curves[0].keypoints.len(), 4);
+        assert_eq!(recorder.curves[1].keypoints.len(), 8);
+
+        // Open curves have no outside, their stroke stays centered
+        assert_eq!(
+            recorder.curves[2].flatten(0.1),
+            vec![
+                nalgebra::Point2::new(0., 0.),
+                nalgebra::Point2::new(10., 0.)
+            ]
+        );
+    }
+
+    #[test]
+    fn pattern_fallback() {
+        let mut recorder = Recorder::default();
+        let dessin = dessin2!(
+            Rectangle!(
+                fill = Fill::dots(Color::BLACK, 0.5, 2.),
+                width = 10.,
+                height = 10.,
+            ) > ()
+        );
+        dessin
+            .write_into_exporter(&mut recorder, &Default::default())
+            .unwrap();
+
+        // Dots of every cell of the rectangle, clipped by it, and the rectangle for its stroke
+        assert!(recorder.ellipses.len() >= 5 * 5);
+        assert_eq!(recorder.curves.len(), 2);
+    }
+
+    #[test]
+    fn image_fill() {
+        let mut recorder = Recorder::default();
+        let photo = ::image::DynamicImage::new_rgb8(30, 40);
+        let badge = dessin2!(
+            Circle!(
+                fill = Fill::image(photo, ImageFit::Cover),
+                stroke = (Color::BLACK, 0.2),
+                radius = 10.,
+            ) > ()
+        );
+        badge
+            .write_into_exporter(&mut recorder, &Default::default())
+            .unwrap();
+
+        // The photo, clipped by the circle, and the circle for its stroke
+        assert_eq!(recorder.images, vec![(30, 40)]);
+        assert_eq!(recorder.ellipses.len(), 2);
+    }
+
+    #[test]
+    fn markers() {
+        let mut recorder = Recorder::default();
+        let arrow = dessin2!(
+            Line!(
+                stroke = (Color::BLACK, 1.),
+                from = [0., 0.],
+                to = [10., 0.],
+                start_marker = Marker::Dot,
+                end_marker = Marker::Arrow,
+                scale = [2., 2.],
+            ) > ()
+        );
+        arrow
+            .write_into_exporter(&mut recorder, &Default::default())
+            .unwrap();
+
+        // The line stops half way into the arrow, whose size follows the width of the stroke, not the scale of the line
+        let [line, head] = recorder.curves.as_slice() else {
+            unreachable!()
+        };
+        assert_eq!(
+            line.keypoints.last(),
+            Some(&KeypointPosition::Point([15., 0.].into()))
+        );
+        assert_eq!(
+            head.keypoints.first(),
+            Some(&KeypointPosition::Point([20., 0.].into()))
+        );
+        assert_eq!(recorder.ellipses.len(), 1);
+        assert_eq!(recorder.ellipses[0].semi_major_axis, 2.);
+    }
+
+    #[test]
+    fn opacity_without_exporter_support() {
+        let mut recorder = Recorder::default();
+        let layer = dessin2!([
+            Circle!(fill = Color::RED, radius = 10.),
+            Line!(stroke = (rgba(0, 0, 255, 128), 1.), to = [10., 0.]),
+        ]);
+        Shape::from(Style::new(layer).with_opacity(0.5))
+            .write_into_exporter(&mut recorder, &Default::default())
+            .unwrap();
+
+        // Colors of the shapes are faded instead
+        let fills = recorder
+            .styles
+            .iter()
+            .map(|style| {
+                (
+                    style.fill.clone(),
+                    style.stroke.map(|stroke| stroke.color()),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            fills,
+            vec![
+                (None, None),
+                (Some(Fill::Color(rgba(255, 0, 0, 128))), None),
+                (None, Some(rgba(0, 0, 255, 64))),
+            ]
+        );
+        assert!(recorder.styles.iter().all(|style| style.opacity == 1.));
+    }
+
+    #[test]
+    fn shadow_without_exporter_support() {
+        let mut recorder = Recorder::default();
+        dessin2!(
+            Rectangle!(
+                fill = Color::WHITE,
+                shadow = Shadow {
+                    offset: [2., -2.].into(),
+                    blur: 1.,
+                    color: Color::BLACK,
+                },
+                width = 40.,
+                height = 20.,
+            ) > ()
+        )
+        .write_into_exporter(&mut recorder, &Default::default())
+        .unwrap();
+
+        // Image of the shadow, under the rectangle
+        assert_eq!(recorder.images.len(), 1);
+        assert_eq!(recorder.curves.len(), 1);
+        let (width, height) = recorder.images[0];
+        assert_eq!(width, 256);
+        assert!(height > 128 && height < 256);
+
+        // Opaque under the rectangle, fading out around it
+        let shadow = super::shadow::shadow_image(
+            &dessin2!(Rectangle!(fill = Color::WHITE, width = 40., height = 20.) > ()),
+            Shadow {
+                offset: [2., -2.].into(),
+                blur: 1.,
+                color: Color::BLACK,
+            },
+        );
+        let Some(Shape::Image(Image { image, .. })) = shadow else {
+            unreachable!()
+        };
+        let image = image.to_rgba8();
+        assert_eq!(image.get_pixel(128, image.height() / 2)[3], 255);
+        assert_eq!(image.get_pixel(0, 0)[3], 0);
+    }
+
+    #[test]
+    fn blend_mode_without_exporter_support() {
+        let mut recorder = Recorder::default();
+        let ink = dessin2!(Circle!(fill = Color::CYAN, radius = 10.) > ());
+        Shape::from(Style::new(ink).with_blend_mode(BlendMode::Multiply))
+            .write_into_exporter(&mut recorder, &Default::default())
+            .unwrap();
+
+        // Drawn normally
+        assert_eq!(recorder.styles.len(), 2);
+        assert!(recorder
+            .styles
+            .iter()
+            .all(|style| style.blend_mode == BlendMode::Normal));
+    }
+
+    #[test]
+    fn lazy_image() {
+        let path = std::env::temp_dir().join("dessin_lazy_image.png");
+        ::image::RgbaImage::new(4, 2).save(&path).unwrap();
+
+        let image = Image::from_path_lazy(&path);
+        assert_eq!(image.image_size_pixel(), (4, 2));
+        assert_eq!(image.image.width(), 0);
+
+        let mut recorder = Recorder::default();
+        let dessin = dessin2!([
+            { image },
+            Image(lazy_path = "does/not/exist.png", scale = [10., 10.]),
+        ]);
+        dessin
+            .write_into_exporter(&mut recorder, &Default::default())
+            .unwrap();
+
+        assert_eq!(recorder.images, vec![(4, 2)]);
+        // Frame and both diagonals of the placeholder
+        assert_eq!(recorder.curves.len(), 3);
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn backdrop() {
+        let mut recorder = Recorder::default();
+        let dessin = dessin2!(Circle!(fill = Color::WHITE, radius = 10.) > ());
+        with_backdrop(
+            &dessin,
+            &BoundingBox::centered([30., 20.]),
+            Some(&Background::Color(Color::BLACK)),
+            Some(Stroke::from((Color::GRAY, 2.))),
+        )
+        .write_into_exporter(&mut recorder, &Default::default())
+        .unwrap();
+
+        assert_eq!(recorder.ellipses.len(), 1);
+        assert_eq!(recorder.curves.len(), 2);
+
+        let size = |curve: &CurvePosition| {
+            let bb = curve
+                .flatten(0.1)
+                .into_iter()
+                .map(BoundingBox::at)
+                .reduce(BoundingBox::join)
+                .unwrap();
+            (bb.width(), bb.height())
+        };
+        assert_eq!(size(&recorder.curves[0]), (30., 20.));
+        assert_eq!(size(&recorder.curves[1]), (28., 18.));
+    }
+
+    #[test]
+    fn stamps() {
+        let mut recorder = Recorder::default();
+        let dessin = dessin2!(Circle!(fill = Color::WHITE, radius = 10.) > ());
+        let corner = dessin2!(Rectangle!(fill = Color::RED, width = 4., height = 2.) > ());
+        with_stamps(
+            &dessin,
+            &BoundingBox::centered([30., 20.]),
+            &[
+                Stamp::new(corner.clone())
+                    .with_position(BoxAnchor::TopLeft)
+                    .with_margin(1.),
+                Stamp::new(corner).with_below(true),
+            ],
+        )
+        .write_into_exporter(&mut recorder, &Default::default())
+        .unwrap();
+
+        assert_eq!(recorder.curves.len(), 2);
+
+        let bb = |curve: &CurvePosition| {
+            curve
+                .flatten(0.1)
+                .into_iter()
+                .map(BoundingBox::at)
+                .reduce(BoundingBox::join)
+                .unwrap()
+        };
+        // Below the circle, in the center
+        assert_eq!(
+            bb(&recorder.curves[0]).center(),
+            nalgebra::Point2::new(0., 0.)
+        );
+        // Over it, in the top left corner
+        let top_left = bb(&recorder.curves[1]);
+        assert_eq!((top_left.left(), top_left.top()), (-14., 9.));
+    }
+
+    #[test]
+    fn skip_hint() {
+        let mut recorder = Recorder::default();
+        let mut skipped = dessin2!(Circle(radius = 1.) > ());
+        skipped.add_metadata(("recorder:skip", "true"));
+        let mut other = dessin2!(Circle(radius = 2.) > ());
+        other.add_metadata(("pdf:skip", "true"));
+
+        dessin2!([{ skipped }, { other }])
+            .write_into_exporter(&mut recorder, &Default::default())
+            .unwrap();
+
+        assert_eq!(recorder.ellipses.len(), 1);
+        assert_eq!(recorder.ellipses[0].semi_major_axis, 2.);
+    }
+}
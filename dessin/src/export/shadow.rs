@@ -0,0 +1,144 @@
+use crate::{
+    display_list::{DisplayList, Primitive},
+    prelude::*,
+};
+use ::image::{imageops, DynamicImage, GrayImage, Luma, Rgba, RgbaImage};
+use nalgebra::{Point2, Vector2};
+
+/// Pixels on the longest side of the image of a shadow, its blur included
+const SHADOW_RESOLUTION: f32 = 256.;
+
+/// Sides of the polygon approximating a filled ellipse
+const ELLIPSE_SIDES: usize = 64;
+
+/// Image of the blurred silhouette of `shape`, in the color of `shadow` and offset by it, for exporters drawing no filter.
+///
+/// `None` if nothing of `shape` is filled or stroked.
+pub(super) fn shadow_image(shape: &Shape, shadow: Shadow) -> Option<Shape> {
+    let margin = 3. * shadow.blur.max(0.);
+    let bb = shape.local_bounding_box().straigthen();
+    let pixel = (bb.width().max(bb.height()) + 2. * margin) / SHADOW_RESOLUTION;
+    if !pixel.is_finite() || pixel <= 0. {
+        return None;
+    }
+
+    let polygons = silhouette_polygons(shape, pixel / 2.);
+    let points = polygons.iter().flatten();
+    let left = points.clone().map(|p| p.x).reduce(f32::min)? - margin;
+    let right = points.clone().map(|p| p.x).reduce(f32::max)? + margin;
+    let bottom = points.clone().map(|p| p.y).reduce(f32::min)? - margin;
+    let top = points.map(|p| p.y).reduce(f32::max)? + margin;
+
+    // Strokes may reach a little past the bounding box
+    let max_size = 2. * SHADOW_RESOLUTION;
+    let width = ((right - left) / pixel).ceil().clamp(1., max_size) as u32;
+    let height = ((top - bottom) / pixel).ceil().clamp(1., max_size) as u32;
+
+    let coverage = rasterize(&polygons, Point2::new(left, top), pixel, width, height);
+    let coverage = if shadow.blur > 0. {
+        imageops::blur(&coverage, shadow.blur / pixel)
+    } else {
+        coverage
+    };
+
+    let (r, g, b, a) = shadow.color.rgba();
+    let pixels = RgbaImage::from_fn(width, height, |x, y| {
+        let covered = coverage.get_pixel(x, y)[0] as u16;
+        Rgba([r, g, b, ((covered * a as u16 + 127) / 255) as u8])
+    });
+
+    let (width, height) = (width as f32 * pixel, height as f32 * pixel);
+    let center = Vector2::new(left + width / 2., top - height / 2.);
+    Some(
+        Image::default()
+            .with_image(DynamicImage::ImageRgba8(pixels))
+            .with_resize([width, height])
+            .with_translate(center + shadow.offset)
+            .into(),
+    )
+}
+
+/// Polygons covering every filled area of `shape`, its strokes included, within `tolerance`.
+///
+/// Texts are left out.
+fn silhouette_polygons(shape: &Shape, tolerance: f32) -> Vec<Vec<Point2<f32>>> {
+    DisplayList::new(&shape.outline_strokes(tolerance))
+        .iter()
+        .filter(|item| item.fill.is_some())
+        .filter_map(|item| match &item.primitive {
+            Primitive::Curve(curve) => Some(curve.flatten(tolerance)),
+            Primitive::Ellipse(EllipsePosition {
+                center,
+                semi_major_axis,
+                semi_minor_axis,
+                rotation,
+            }) => Some(
+                (0..ELLIPSE_SIDES)
+                    .map(|side| {
+                        let angle = side as f32 * std::f32::consts::TAU / ELLIPSE_SIDES as f32;
+                        let (sin, cos) = rotation.sin_cos();
+                        let (x, y) = (semi_major_axis * angle.cos(), semi_minor_axis * angle.sin());
+                        center + Vector2::new(x * cos - y * sin, x * sin + y * cos)
+                    })
+                    .collect(),
+            ),
+            Primitive::Image(image) => Some(vec![
+                image.top_left,
+                image.top_right,
+                image.bottom_right,
+                image.bottom_left,
+            ]),
+            Primitive::Text(_) => None,
+        })
+        .filter(|polygon| polygon.len() > 2)
+        .collect()
+}
+
+/// Pixels covered by `polygons`, with the nonzero rule, in an image whose top left corner is at `top_left`
+fn rasterize(
+    polygons: &[Vec<Point2<f32>>],
+    top_left: Point2<f32>,
+    pixel: f32,
+    width: u32,
+    height: u32,
+) -> GrayImage {
+    // Polygons are closed, whether or not they end with their first point
+    let edges = polygons
+        .iter()
+        .flat_map(|polygon| polygon.iter().zip(polygon.iter().cycle().skip(1)))
+        .collect::<Vec<_>>();
+
+    let mut coverage = GrayImage::new(width, height);
+    for row in 0..height {
+        let y = top_left.y - (row as f32 + 0.5) * pixel;
+        let mut crossings = edges
+            .iter()
+            .filter(|(a, b)| (a.y <= y) != (b.y <= y))
+            .map(|(a, b)| {
+                let x = a.x + (y - a.y) / (b.y - a.y) * (b.x - a.x);
+                (x, if b.y > a.y { 1 } else { -1 })
+            })
+            .collect::<Vec<_>>();
+        crossings.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+
+        let mut winding = 0;
+        for pair in crossings.windows(2) {
+            winding += pair[0].1;
+            if winding == 0 {
+                continue;
+            }
+
+            // Pixels whose center is between both crossings
+            let column = |x: f32| {
+                ((x - top_left.x) / pixel - 0.5)
+                    .ceil()
+                    .clamp(0., width as f32) as u32
+            };
+            for column in column(pair[0].0)..column(pair[1].0) {
+                coverage.put_pixel(column, row, Luma([255]));
+            }
+        }
+    }
+
+    coverage
+}
@@ -0,0 +1,127 @@
+//! Reference files of golden tests, shared by the golden modules of exporters.
+//!
+//! A reference is only written when the `DESSIN_UPDATE_GOLDEN` environment variable is set.
+//! Otherwise, a missing reference fails the test, so a fresh checkout can't pass by writing its own references.
+//!
+//! ```
+//! use dessin::golden::{check_reference, MissingReference};
+//!
+//! let reference = std::env::temp_dir().join("dessin-golden-doc-missing.txt");
+//! # let _ = std::fs::remove_file(&reference);
+//! let checked = check_reference(
+//! 	&reference,
+//! 	|path| std::fs::write(path, "actual"),
+//! 	|path| {
+//! 		assert_eq!(std::fs::read_to_string(path)?, "actual");
+//! 		Ok(())
+//! 	},
+//! );
+//!
+//! let error = checked.unwrap_err();
+//! assert!(error.get_ref().unwrap().is::<MissingReference>());
+//! ```
+
+use std::{
+    fmt, io,
+    path::{Path, PathBuf},
+};
+
+/// Environment variable forcing references to be rewritten
+pub const UPDATE_GOLDEN_ENV: &str = "DESSIN_UPDATE_GOLDEN";
+
+/// Reference file which doesn't exist, and wasn't asked to be written
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MissingReference(pub PathBuf);
+impl fmt::Display for MissingReference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Reference `{}` does not exist, set {UPDATE_GOLDEN_ENV}=1 to write it",
+            self.0.display()
+        )
+    }
+}
+impl std::error::Error for MissingReference {}
+impl From<MissingReference> for io::Error {
+    fn from(value: MissingReference) -> Self {
+        io::Error::new(io::ErrorKind::NotFound, value)
+    }
+}
+
+/// Whether references are rewritten instead of compared, as asked by [`UPDATE_GOLDEN_ENV`]
+pub fn is_updating() -> bool {
+    std::env::var_os(UPDATE_GOLDEN_ENV).is_some()
+}
+
+/// Compare an export to the `reference` file with `compare`, or write it there with `write` if [`UPDATE_GOLDEN_ENV`] is set.
+///
+/// The parent directories of the reference are created before writing it.
+pub fn check_reference<E, W, C>(reference: &Path, write: W, compare: C) -> Result<(), E>
+where
+    E: From<MissingReference> + From<io::Error>,
+    W: FnOnce(&Path) -> Result<(), E>,
+    C: FnOnce(&Path) -> Result<(), E>,
+{
+    if is_updating() {
+        if let Some(parent) = reference.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        return write(reference);
+    }
+
+    if !reference.exists() {
+        return Err(MissingReference(reference.to_path_buf()).into());
+    }
+
+    compare(reference)
+}
+
+/// Panics with a readable message if checking the `reference`, of the given `kind` such as `SVG`, failed
+#[track_caller]
+pub fn assert_reference<E: fmt::Display>(kind: &str, reference: &Path, checked: Result<(), E>) {
+    if let Err(e) = checked {
+        panic!(
+            "Golden {kind} `{}` does not match: {e}\nSet {UPDATE_GOLDEN_ENV}=1 to update it.",
+            reference.display()
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_reference_fails() {
+        let reference = std::env::temp_dir().join("dessin-golden-test-missing.txt");
+        let _ = std::fs::remove_file(&reference);
+
+        let checked: io::Result<()> = check_reference(
+            &reference,
+            |_| panic!("References are only written on demand"),
+            |_| panic!("A missing reference can't be compared"),
+        );
+
+        let error = checked.unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::NotFound);
+        let missing = error.get_ref().unwrap().downcast_ref::<MissingReference>();
+        assert_eq!(missing, Some(&MissingReference(reference.clone())));
+        assert!(!reference.exists());
+    }
+
+    #[test]
+    fn existing_reference_is_compared() {
+        let reference = std::env::temp_dir().join("dessin-golden-test-existing.txt");
+        std::fs::write(&reference, "expected").unwrap();
+
+        let checked: io::Result<()> = check_reference(
+            &reference,
+            |_| panic!("References are only written on demand"),
+            |path| {
+                assert_eq!(std::fs::read_to_string(path)?, "expected");
+                Ok(())
+            },
+        );
+        assert!(checked.is_ok());
+    }
+}
@@ -0,0 +1,219 @@
+//! Split a dessin by layer.
+//!
+//! A [`Group`] is put in a layer by the metadata [`LAYER_METADATA_KEY`], along with everything in it,
+//! unless a nested group names another layer.
+//! Exporters can then write each layer on its own, for instance the cut and engrave passes of a laser,
+//! or the planes of an animation to composite.
+//!
+//! ```
+//! # use dessin::prelude::*;
+//! use dessin::layers::LAYER_METADATA_KEY;
+//!
+//! let mut cut = dessin2!(Circle!(stroke = (Color::RED, 0.1), radius = 20.) > ());
+//! cut.add_metadata((LAYER_METADATA_KEY, "Cut"));
+//!
+//! let mut engrave = dessin2!(Text!(fill = Color::BLACK, text = "Coaster") > ());
+//! engrave.add_metadata((LAYER_METADATA_KEY, "Engrave"));
+//!
+//! let dessin = dessin2!([{ cut }, { engrave }]);
+//!
+//! assert_eq!(dessin.layer_names(), vec!["Cut", "Engrave"]);
+//!
+//! let layers = dessin.split_layers();
+//! assert_eq!(layers.len(), 2);
+//! assert_eq!(layers[0].0.as_deref(), Some("Cut"));
+//! ```
+
+use crate::prelude::*;
+
+/// Metadata key putting a [`Group`] in the layer named by its value
+pub const LAYER_METADATA_KEY: &str = "layer";
+
+fn layer_of(metadata: &[(String, String)]) -> Option<&str> {
+    metadata
+        .iter()
+        .find(|(key, _)| key == LAYER_METADATA_KEY)
+        .map(|(_, name)| name.as_str())
+}
+
+impl Shape {
+    /// Names of the layers of this dessin, in order of first appearance.
+    ///
+    /// [`Dynamic`][Shape::Dynamic] shapes are evaluated to find their layers.
+    pub fn layer_names(&self) -> Vec<String> {
+        fn collect(shape: &Shape, names: &mut Vec<String>) {
+            match shape {
+                Shape::Group(Group {
+                    shapes, metadata, ..
+                }) => {
+                    if let Some(name) = layer_of(metadata) {
+                        if !names.iter().any(|n| n == name) {
+                            names.push(name.to_string());
+                        }
+                    }
+                    for shape in shapes {
+                        collect(shape, names);
+                    }
+                }
+                Shape::Style { shape, .. } => collect(shape, names),
+                Shape::LevelOfDetail(lod) => {
+                    collect(&lod.detailed, names);
+                    for (_, shape) in &lod.simplified {
+                        collect(shape, names);
+                    }
+                }
+                Shape::Dynamic { shaper, .. } => collect(&shaper(), names),
+                Shape::Ellipse(_) | Shape::Image(_) | Shape::Text(_) | Shape::Curve(_) => {}
+            }
+        }
+
+        let mut names = vec![];
+        collect(self, &mut names);
+        names
+    }
+
+    /// This dessin, with only the content of the layer `name`, or the content outside of any layer if `None`.
+    ///
+    /// Transforms, styles and metadata are kept, so the layer is drawn exactly where it is in the whole dessin.
+    /// Returns `None` if the layer is empty.
+    /// [`Dynamic`][Shape::Dynamic] shapes are evaluated once and for all.
+    pub fn layer(&self, name: Option<&str>) -> Option<Shape> {
+        fn keep(shape: &Shape, current: Option<&str>, name: Option<&str>) -> Option<Shape> {
+            match shape {
+                Shape::Group(Group {
+                    local_transform,
+                    shapes,
+                    metadata,
+                }) => {
+                    let current = layer_of(metadata).or(current);
+                    let shapes = shapes
+                        .iter()
+                        .filter_map(|shape| keep(shape, current, name))
+                        .collect::<Vec<_>>();
+                    if shapes.is_empty() {
+                        return None;
+                    }
+
+                    Some(Shape::Group(Group {
+                        local_transform: *local_transform,
+                        shapes,
+                        metadata: metadata.clone(),
+                    }))
+                }
+                Shape::Style {
+                    fill,
+                    stroke,
+                    stroke_alignment,
+                    filter,
+                    opacity,
+                    blend_mode,
+                    shape,
+                } => Some(Shape::Style {
+                    fill: fill.clone(),
+                    stroke: *stroke,
+                    stroke_alignment: *stroke_alignment,
+                    filter: *filter,
+                    opacity: *opacity,
+                    blend_mode: *blend_mode,
+                    shape: Box::new(keep(shape, current, name)?),
+                }),
+                Shape::LevelOfDetail(lod) => {
+                    let detailed = keep(&lod.detailed, current, name);
+                    let simplified = lod
+                        .simplified
+                        .iter()
+                        .map(|(size, shape)| (*size, keep(shape, current, name)))
+                        .collect::<Vec<_>>();
+                    if detailed.is_none() && simplified.iter().all(|(_, s)| s.is_none()) {
+                        return None;
+                    }
+
+                    Some(Shape::LevelOfDetail(LevelOfDetail {
+                        local_transform: lod.local_transform,
+                        detailed: Box::new(detailed.unwrap_or_default()),
+                        simplified: simplified
+                            .into_iter()
+                            .map(|(size, shape)| (size, shape.unwrap_or_default()))
+                            .collect(),
+                    }))
+                }
+                Shape::Dynamic {
+                    local_transform,
+                    shaper,
+                } => Some(Shape::Group(Group {
+                    local_transform: *local_transform,
+                    shapes: vec![keep(&shaper(), current, name)?],
+                    metadata: vec![],
+                })),
+                Shape::Ellipse(_) | Shape::Image(_) | Shape::Text(_) | Shape::Curve(_) => {
+                    (current == name).then(|| shape.clone())
+                }
+            }
+        }
+
+        keep(self, None, name)
+    }
+
+    /// Every non empty layer of this dessin, each as a dessin of its own, see [`Shape::layer`].
+    ///
+    /// The content outside of any layer comes first, named `None`, then the layers in order of first appearance.
+    pub fn split_layers(&self) -> Vec<(Option<String>, Shape)> {
+        std::iter::once(None)
+            .chain(self.layer_names().into_iter().map(Some))
+            .filter_map(|name| {
+                let layer = self.layer(name.as_deref())?;
+                Some((name, layer))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn in_layer(shape: Shape, name: &str) -> Shape {
+        let mut shape = dessin2!([{ shape }]);
+        shape.add_metadata((LAYER_METADATA_KEY, name));
+        shape
+    }
+
+    #[test]
+    fn nested_layers() {
+        let inner = in_layer(dessin2!(Circle(radius = 1.) > ()), "Inner");
+        let outer = in_layer(
+            dessin2!([{ inner }, Circle(radius = 2.)] > (translate = [10., 0.])),
+            "Outer",
+        );
+        let dessin = dessin2!([{ outer }, Circle(radius = 3.)]);
+
+        assert_eq!(dessin.layer_names(), vec!["Outer", "Inner"]);
+
+        let layers = dessin.split_layers();
+        let names = layers
+            .iter()
+            .map(|(name, _)| name.as_deref())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec![None, Some("Outer"), Some("Inner")]);
+
+        // Each layer stays where it is in the whole dessin
+        let width = |idx: usize| layers[idx].1.local_bounding_box().straigthen().width();
+        assert!((width(0) - 6.).abs() < 10e-5);
+        assert!((width(1) - 4.).abs() < 10e-5);
+        assert!((width(2) - 2.).abs() < 10e-5);
+        let center = layers[2].1.local_bounding_box().center();
+        assert!((center.x - 10.).abs() < 10e-5);
+    }
+
+    #[test]
+    fn styles_are_kept() {
+        let cut = in_layer(dessin2!(Circle(radius = 1.) > ()), "Cut");
+        let dessin = dessin2!([[{ cut }, Circle(radius = 2.)] > !(stroke = (Color::RED, 0.1))]);
+
+        assert!(dessin.layer(Some("Engrave")).is_none());
+        assert!(matches!(
+            dessin.layer(Some("Cut")),
+            Some(Shape::Group(Group { shapes, .. })) if matches!(shapes[..], [Shape::Style { stroke: Some(_), .. }])
+        ));
+    }
+}
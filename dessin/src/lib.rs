@@ -161,14 +161,44 @@ pub mod macros;
 // See https://github.com/rust-lang/rust/issues/56409 for more details
 extern crate self as dessin;
 
+/// Shapes built once, and reused while their parameters don't change
+pub mod cache;
+/// Color profile of the colors of a dessin
+pub mod color_profile;
 /// Shapes made of basic [shapes][crate::shapes::Shape]
 pub mod contrib;
+/// Orientation of the Y axis a dessin is drawn in
+pub mod coordinates;
+/// Draw layout information on top of a dessin
+pub mod debug;
+/// Flat list of everything a dessin draws
+pub mod display_list;
 /// Declarations to create an export format.
 pub mod export;
+/// Reference files of golden tests
+pub mod golden;
+/// Split a dessin by layer
+pub mod layers;
+/// Convert strokes into filled geometry
+pub mod outline;
+/// Line weights, line types and arrowheads for technical drawings
+pub mod presets;
+/// Components registered by name, for plugins
+pub mod registry;
 /// Building blocks of a dessin
 pub mod shapes;
+/// Find what is drawn in an area
+pub mod spatial_index;
+/// Measure the complexity of a dessin
+pub mod stats;
 /// Styling of the building blocks
 pub mod style;
+/// Fill placeholders in texts
+pub mod template;
+/// Outline of the tree of a dessin
+pub mod tree;
+/// Lint a dessin before export
+pub mod validate;
 
 pub use ::image;
 pub use ::nalgebra;
@@ -0,0 +1,527 @@
+//! Convert strokes into filled geometry.
+//!
+//! Some backends have no notion of stroke: cutters follow every outline, and tessellators only fill areas.
+//! [`Shape::outline_strokes`] replaces each stroke by a filled outline of the area it covers,
+//! which can then be exported anywhere, or combined with other areas.
+//!
+//! ```
+//! # use dessin::prelude::*;
+//! let dessin = dessin2!([
+//! 	Circle!(fill = Color::RED, stroke = (Color::BLACK, 1.), radius = 10.),
+//! 	Line!(stroke = (Color::BLUE, 0.5), from = [-10., -15.], to = [10., -15.]),
+//! ]);
+//!
+//! let outlined = dessin.outline_strokes(0.01);
+//!
+//! // Strokes cover half their width on each side of the outline
+//! let bb = outlined.local_bounding_box().straigthen();
+//! assert!((bb.width() - 21.).abs() < 10e-3);
+//! ```
+
+use crate::prelude::*;
+use nalgebra::{Point2, Transform2, Vector2};
+
+impl Shape {
+    /// This dessin, with every stroke replaced by a filled outline of the area it covers.
+    ///
+    /// Curves and ellipses are [flattened][CurvePosition::flatten] within `tolerance`.
+    /// Ends of open curves are butt, and corners are mitered unless the miter is longer than the [miter limit][Stroke::miter_limit],
+    /// where they are beveled, like the defaults of SVG, whatever the [`LineCap`] and [`LineJoin`] of the stroke.
+    /// [`StrokeAlignment`] is honored, and dashes are outlined one by one.
+    ///
+    /// Texts and images lose their stroke, curves their [markers][Marker],
+    /// and [`Dynamic`][Shape::Dynamic] shapes are evaluated once and for all.
+    pub fn outline_strokes(&self, tolerance: f32) -> Shape {
+        let context = Context {
+            fill: None,
+            stroke: None,
+            stroke_alignment: StrokeAlignment::Center,
+            from_stroke: Transform2::identity(),
+            tolerance,
+        };
+
+        context.outline(self).unwrap_or_default()
+    }
+}
+
+/// Style inherited from the parents of a shape
+#[derive(Clone)]
+struct Context {
+    fill: Option<Fill>,
+    stroke: Option<Stroke>,
+    stroke_alignment: StrokeAlignment,
+    /// From the coordinates of the style defining the stroke, where its width applies, to the current ones
+    from_stroke: Transform2<f32>,
+    tolerance: f32,
+}
+impl Context {
+    fn transformed(&self, local_transform: &Transform2<f32>) -> Context {
+        Context {
+            from_stroke: self.from_stroke * local_transform,
+            ..self.clone()
+        }
+    }
+
+    /// `None` when nothing is left visible
+    fn outline(&self, shape: &Shape) -> Option<Shape> {
+        match shape {
+            Shape::Group(Group {
+                local_transform,
+                shapes,
+                metadata,
+            }) => {
+                let context = self.transformed(local_transform);
+                Some(Shape::Group(Group {
+                    local_transform: *local_transform,
+                    shapes: shapes.iter().filter_map(|s| context.outline(s)).collect(),
+                    metadata: metadata.clone(),
+                }))
+            }
+            Shape::Style {
+                fill,
+                stroke,
+                stroke_alignment,
+                filter,
+                opacity,
+                blend_mode,
+                shape,
+            } => {
+                let context = match stroke {
+                    Some(stroke) => Context {
+                        fill: fill.clone().or_else(|| self.fill.clone()),
+                        stroke: Some(*stroke),
+                        stroke_alignment: *stroke_alignment,
+                        from_stroke: Transform2::identity(),
+                        tolerance: self.tolerance,
+                    },
+                    None => Context {
+                        fill: fill.clone().or_else(|| self.fill.clone()),
+                        ..self.clone()
+                    },
+                };
+
+                Some(Shape::Style {
+                    fill: fill.clone(),
+                    stroke: None,
+                    stroke_alignment: StrokeAlignment::Center,
+                    filter: *filter,
+                    opacity: *opacity,
+                    blend_mode: *blend_mode,
+                    shape: Box::new(context.outline(shape)?),
+                })
+            }
+            Shape::Curve(curve) => self.outline_curve(shape, curve),
+            Shape::Ellipse(ellipse) => self.outline_curve(shape, &ellipse.as_curve()),
+            Shape::Text(_) | Shape::Image(_) => Some(shape.clone()),
+            Shape::LevelOfDetail(lod) => {
+                let context = self.transformed(&lod.local_transform);
+                Some(Shape::LevelOfDetail(LevelOfDetail {
+                    local_transform: lod.local_transform,
+                    detailed: Box::new(context.outline(&lod.detailed).unwrap_or_default()),
+                    simplified: lod
+                        .simplified
+                        .iter()
+                        .map(|(size, shape)| (*size, context.outline(shape).unwrap_or_default()))
+                        .collect(),
+                }))
+            }
+            Shape::Dynamic {
+                local_transform,
+                shaper,
+            } => {
+                let shape = self.transformed(local_transform).outline(&shaper())?;
+                Some(Shape::Group(Group {
+                    local_transform: *local_transform,
+                    shapes: vec![shape],
+                    metadata: vec![],
+                }))
+            }
+        }
+    }
+
+    /// The filled shape, if any, under the outline of its stroke
+    fn outline_curve(&self, shape: &Shape, curve: &Curve) -> Option<Shape> {
+        let mut shapes = vec![];
+        if self.fill.is_some() {
+            shapes.push(shape.clone());
+        }
+
+        if let (Some(stroke), Some(to_local)) = (self.stroke, self.from_stroke.try_inverse()) {
+            let (color, areas) = stroke_areas(
+                &curve.position(&self.from_stroke),
+                stroke,
+                self.stroke_alignment,
+                self.tolerance,
+            );
+
+            let areas = areas
+                .into_iter()
+                .map(|points| {
+                    Shape::Curve(Curve {
+                        local_transform: to_local,
+                        keypoints: points.into_iter().map(Keypoint::Point).collect(),
+                        closed: true,
+                        start_marker: None,
+                        end_marker: None,
+                        fill_rule: FillRule::NonZero,
+                    })
+                })
+                .collect::<Vec<_>>();
+
+            if !areas.is_empty() {
+                shapes.push(Shape::Style {
+                    fill: Some(Fill::Color(color)),
+                    stroke: None,
+                    stroke_alignment: StrokeAlignment::Center,
+                    filter: None,
+                    opacity: 1.,
+                    blend_mode: BlendMode::Normal,
+                    shape: Box::new(Shape::Group(Group {
+                        shapes: areas,
+                        ..Default::default()
+                    })),
+                });
+            }
+        }
+
+        match shapes.len() {
+            0 => None,
+            1 => shapes.pop(),
+            _ => Some(Shape::Group(Group {
+                shapes,
+                ..Default::default()
+            })),
+        }
+    }
+}
+
+/// Color of the stroke, and the closed polygons covered by it
+fn stroke_areas(
+    curve: &CurvePosition,
+    stroke: Stroke,
+    stroke_alignment: StrokeAlignment,
+    tolerance: f32,
+) -> (Color, Vec<Vec<Point2<f32>>>) {
+    let (color, width, dashes) = (stroke.color(), stroke.width(), stroke.dashes());
+    let miter_limit = stroke.miter_limit();
+    let half = width / 2.;
+    if half <= 0. || half.is_nan() {
+        return (color, vec![]);
+    }
+
+    let centerline = match stroke_alignment {
+        StrokeAlignment::Center => curve.clone(),
+        StrokeAlignment::Inside => curve.offset(-half, LineJoin::Miter, miter_limit, tolerance),
+        StrokeAlignment::Outside => curve.offset(half, LineJoin::Miter, miter_limit, tolerance),
+    };
+    let mut points = centerline.flatten(tolerance);
+    points.dedup();
+
+    let areas = match dashes {
+        None if centerline.closed => ring(&centerline, half, miter_limit, tolerance)
+            .into_iter()
+            .collect(),
+        None => band(&points, half, miter_limit).into_iter().collect(),
+        Some((dashes, offset)) => split_dashes(&points, &dashes, offset)
+            .iter()
+            .filter_map(|dash| band(dash, half, miter_limit))
+            .collect(),
+    };
+
+    (color, areas)
+}
+
+/// Area between the outline of a closed curve offset by `half` on each side.
+///
+/// The inner outline is reversed and joined to the outer one, so the hole stays empty whatever the fill rule.
+fn ring(
+    curve: &CurvePosition,
+    half: f32,
+    miter_limit: f32,
+    tolerance: f32,
+) -> Option<Vec<Point2<f32>>> {
+    let points = |distance: f32| {
+        let mut points = curve
+            .offset(distance, LineJoin::Miter, miter_limit, tolerance)
+            .flatten(tolerance);
+        // Closed by the offset
+        points.pop();
+        points
+    };
+
+    let outer = points(half);
+    let inner = points(-half);
+    if outer.len() < 3 || inner.len() < 3 {
+        return band(&curve.flatten(tolerance), half, miter_limit);
+    }
+
+    let mut ring = outer.clone();
+    ring.push(outer[0]);
+    ring.push(inner[0]);
+    ring.extend(inner.iter().skip(1).rev());
+    ring.push(inner[0]);
+
+    Some(ring)
+}
+
+/// Area covered by a polyline of width `2 * half`, with butt ends, beveled where miters are longer than `miter_limit` widths
+fn band(points: &[Point2<f32>], half: f32, miter_limit: f32) -> Option<Vec<Point2<f32>>> {
+    if points.len() < 2 {
+        return None;
+    }
+
+    let mut band = side(points, half, miter_limit);
+    band.extend(side(points, -half, miter_limit).into_iter().rev());
+    Some(band)
+}
+
+/// Points `distance` away on the left of a polyline, mitered or beveled at each corner
+fn side(points: &[Point2<f32>], distance: f32, miter_limit: f32) -> Vec<Point2<f32>> {
+    let left = |a: &Point2<f32>, b: &Point2<f32>| {
+        let d = (b - a).normalize();
+        Vector2::new(-d.y, d.x)
+    };
+
+    let n = points.len();
+    let mut side = Vec::with_capacity(n);
+    side.push(points[0] + left(&points[0], &points[1]) * distance);
+    for w in points.windows(3) {
+        let n1 = left(&w[0], &w[1]);
+        let n2 = left(&w[1], &w[2]);
+
+        let bisector = n1 + n2;
+        let miter = (bisector.magnitude() > f32::EPSILON)
+            .then(|| bisector.normalize())
+            .map(|bisector| (bisector, 1. / bisector.dot(&n1)))
+            .filter(|(_, length)| *length <= miter_limit);
+        match miter {
+            Some((bisector, length)) => side.push(w[1] + bisector * length * distance),
+            None => {
+                side.push(w[1] + n1 * distance);
+                side.push(w[1] + n2 * distance);
+            }
+        }
+    }
+    side.push(points[n - 1] + left(&points[n - 2], &points[n - 1]) * distance);
+
+    side
+}
+
+/// Split a polyline in dashes, alternating with gaps of `pattern`, starting `offset` into it.
+///
+/// Patterns with a length that isn't positive are drawn as a single dash.
+///
+/// ```
+/// # use dessin::{prelude::*, outline::split_dashes, nalgebra::Point2};
+/// let line = [Point2::new(0., 0.), Point2::new(10., 0.)];
+///
+/// // 0-2, 3-5, 6-8, 9-10
+/// let dashes = split_dashes(&line, &DashArray::new(&[2., 1.]), 0.);
+/// assert_eq!(dashes.len(), 4);
+/// assert_eq!(dashes[1], vec![Point2::new(3., 0.), Point2::new(5., 0.)]);
+/// ```
+pub fn split_dashes(
+    points: &[Point2<f32>],
+    pattern: &DashArray,
+    offset: f32,
+) -> Vec<Vec<Point2<f32>>> {
+    let lengths = pattern.as_slice();
+    if lengths.iter().any(|l| *l <= 0.) || points.is_empty() {
+        return vec![points.to_vec()];
+    }
+
+    // Position in the pattern at the start of the polyline
+    let mut idx = 0;
+    let mut left = lengths[0];
+    let mut offset = offset.rem_euclid(pattern.period());
+    while offset >= left {
+        offset -= left;
+        idx = (idx + 1) % lengths.len();
+        left = lengths[idx];
+    }
+    left -= offset;
+
+    let mut dashes = vec![];
+    let mut drawing = idx % 2 == 0;
+    let mut dash = if drawing { vec![points[0]] } else { vec![] };
+    for w in points.windows(2) {
+        let (mut from, to) = (w[0], w[1]);
+        let direction = (to - from).normalize();
+        let mut length = (to - from).magnitude();
+
+        while length > left {
+            let split = from + direction * left;
+            if drawing {
+                dash.push(split);
+                dashes.push(std::mem::take(&mut dash));
+            } else {
+                dash = vec![split];
+            }
+
+            drawing = !drawing;
+            length -= left;
+            from = split;
+            idx = (idx + 1) % lengths.len();
+            left = lengths[idx];
+        }
+
+        left -= length;
+        if drawing {
+            dash.push(to);
+        }
+    }
+    if drawing && dash.len() > 1 {
+        dashes.push(dash);
+    }
+
+    dashes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Strokes left in the dessin, and number of filled curves
+    fn count(shape: &Shape, filled: bool) -> (usize, usize) {
+        match shape {
+            Shape::Group(Group { shapes, .. }) => shapes
+                .iter()
+                .map(|s| count(s, filled))
+                .fold((0, 0), |(a, b), (c, d)| (a + c, b + d)),
+            Shape::Style {
+                fill,
+                stroke,
+                shape,
+                ..
+            } => {
+                let (strokes, fills) = count(shape, filled || fill.is_some());
+                (strokes + stroke.is_some() as usize, fills)
+            }
+            Shape::Curve(_) | Shape::Ellipse(_) => (0, filled as usize),
+            _ => (0, 0),
+        }
+    }
+
+    fn size(shape: &Shape) -> (f32, f32) {
+        let bb = shape.local_bounding_box().straigthen();
+        (bb.width(), bb.height())
+    }
+
+    #[test]
+    fn line() {
+        let line =
+            dessin2!(Line!(stroke = (Color::BLACK, 2.), from = [0., 0.], to = [10., 0.]) > ())
+                .outline_strokes(0.01);
+
+        assert_eq!(count(&line, false), (0, 1));
+        assert_eq!(size(&line), (10., 2.));
+    }
+
+    #[test]
+    fn alignment() {
+        let square = |stroke_alignment: StrokeAlignment| {
+            dessin2!(
+                Rectangle!(
+                    fill = Color::RED,
+                    stroke = (Color::BLACK, 2.),
+                    stroke_alignment = stroke_alignment,
+                    width = 10.,
+                    height = 10.,
+                ) > ()
+            )
+            .outline_strokes(0.01)
+        };
+
+        let center = square(StrokeAlignment::Center);
+        // Filled square, and the outline of its stroke
+        assert_eq!(count(&center, false), (0, 2));
+        assert_eq!(size(&center), (12., 12.));
+
+        let (width, height) = size(&square(StrokeAlignment::Outside));
+        assert!((width - 14.).abs() < 10e-4 && (height - 14.).abs() < 10e-4);
+
+        let (width, height) = size(&square(StrokeAlignment::Inside));
+        assert!((width - 10.).abs() < 10e-4 && (height - 10.).abs() < 10e-4);
+    }
+
+    #[test]
+    fn miter_limit() {
+        let spike = |stroke: Stroke| {
+            dessin2!(
+                Curve!(
+                    stroke = stroke,
+                    then = Point2::new(-5., 0.),
+                    then = Point2::new(0., 40.),
+                    then = Point2::new(5., 0.),
+                ) > ()
+            )
+            .outline_strokes(0.01)
+            .local_bounding_box()
+            .straigthen()
+            .top()
+        };
+
+        // The miter of the apex is about 8 times the width
+        let beveled = spike(Stroke::from((Color::BLACK, 2.)));
+        assert!(beveled < 41.);
+        let mitered = spike(Stroke::from((Color::BLACK, 2.)).with_miter_limit(10.));
+        assert!(mitered > 48.);
+    }
+
+    #[test]
+    fn dashes() {
+        let line = dessin2!(
+            Line!(
+                stroke = Stroke::dashed(Color::BLACK, 1., 2., 1.),
+                from = [0., 0.],
+                to = [10., 0.],
+            ) > ()
+        )
+        .outline_strokes(0.01);
+
+        // 0-2, 3-5, 6-8, 9-10
+        assert_eq!(count(&line, false), (0, 4));
+    }
+
+    #[test]
+    fn dash_pattern() {
+        let line = dessin2!(
+            Line!(
+                stroke =
+                    Stroke::dash_pattern(Color::BLACK, 1., DashArray::new(&[3., 1., 1., 1.]), 4.),
+                from = [0., 0.],
+                to = [10., 0.],
+            ) > ()
+        )
+        .outline_strokes(0.01);
+
+        // Starting at the dot: 0-1, 2-5, 6-7, 8-10
+        assert_eq!(count(&line, false), (0, 4));
+        let bb = line.local_bounding_box().straigthen();
+        assert!((bb.left() - 0.).abs() < 10e-5 && (bb.right() - 10.).abs() < 10e-5);
+    }
+
+    #[test]
+    fn scaled_stroke() {
+        let line = || {
+            dessin2!(Line!(
+                stroke = (Color::BLACK, 1.),
+                from = [0., 0.],
+                to = [10., 0.]
+            ))
+        };
+
+        // Transforms inside the style only apply to the geometry
+        let inside = dessin2!({ line() } > (scale = [2., 2.])).outline_strokes(0.01);
+        assert_eq!(size(&inside), (20., 1.));
+
+        // While transforms around the style apply to the stroke too
+        let around = Shape::Group(Group {
+            local_transform: nalgebra::convert(nalgebra::Scale2::new(2., 2.)),
+            shapes: vec![line().into()],
+            metadata: vec![],
+        })
+        .outline_strokes(0.01);
+        assert_eq!(size(&around), (20., 2.));
+    }
+}
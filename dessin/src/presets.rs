@@ -0,0 +1,204 @@
+//! Line work for technical drawings, close to ISO 128.
+//!
+//! Lengths are in millimetres, so drawings should be made at a 1:1 scale of the printed page.
+//! Dash patterns and arrowheads are proportional to the width of the line they belong to,
+//! which keeps them consistent from one line weight to another.
+//!
+//! ```
+//! # use dessin::prelude::*;
+//! use dessin::presets::{ArrowSize, LineStyle, LineWeight};
+//!
+//! let part = dessin2!([
+//! 	Circle!(stroke = LineStyle::VISIBLE, radius = 20.),
+//! 	Circle!(stroke = LineStyle::HIDDEN, radius = 15.),
+//! 	Line!(stroke = LineStyle::CENTER, from = [-25., 0.], to = [25., 0.]),
+//! 	Line!(stroke = LineStyle::CENTER, from = [0., -25.], to = [0., 25.]),
+//! ]);
+//!
+//! let callout = dessin2!(Leader(
+//! 	target = [14., 14.],
+//! 	elbow = [30., 30.],
+//! 	text = "Bore",
+//! 	thickness = LineWeight::Thin.width(),
+//! 	arrow = ArrowSize::for_width(LineWeight::Thin.width()),
+//! ));
+//!
+//! let dessin = dessin2!([{ part }, { callout }]);
+//! ```
+
+use crate::prelude::*;
+
+/// Width of a line, in millimetres
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub enum LineWeight {
+    /// 0.25 mm, for dimensions, hatching and center lines
+    #[default]
+    Thin,
+    /// 0.35 mm, for hidden outlines
+    Medium,
+    /// 0.5 mm, for visible outlines
+    Thick,
+}
+impl LineWeight {
+    /// Width of the line, in millimetres
+    pub fn width(&self) -> f32 {
+        match self {
+            LineWeight::Thin => 0.25,
+            LineWeight::Medium => 0.35,
+            LineWeight::Thick => 0.5,
+        }
+    }
+}
+
+/// Dash pattern of a line, from ISO 128
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub enum LineType {
+    /// Continuous line, for outlines and dimensions
+    #[default]
+    Continuous,
+    /// Dashes, for hidden outlines
+    Hidden,
+    /// Long dashes and dots, for axes and centers
+    Center,
+    /// Long dashes and pairs of dots, for adjacent parts and extreme positions of moving parts
+    Phantom,
+}
+impl LineType {
+    /// Dashes and gaps for a line of `width`, `None` for a continuous line
+    pub fn dashes(&self, width: f32) -> Option<DashArray> {
+        let dashes = match self {
+            LineType::Continuous => return None,
+            LineType::Hidden => DashArray::new(&[12., 3.]),
+            LineType::Center => DashArray::new(&[24., 3., 0.5, 3.]),
+            LineType::Phantom => DashArray::new(&[24., 3., 0.5, 3., 0.5, 3.]),
+        };
+        Some(dashes * width)
+    }
+}
+
+/// Weight, type and color of a line, which converts into a [`Stroke`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LineStyle {
+    /// Width of the line
+    pub weight: LineWeight,
+    /// Dash pattern of the line
+    pub line_type: LineType,
+    /// Color of the line
+    pub color: Color,
+}
+impl LineStyle {
+    /// Thick and continuous, for visible outlines
+    pub const VISIBLE: LineStyle = LineStyle::new(LineWeight::Thick, LineType::Continuous);
+    /// Medium and dashed, for hidden outlines
+    pub const HIDDEN: LineStyle = LineStyle::new(LineWeight::Medium, LineType::Hidden);
+    /// Thin long dashes and dots, for axes and centers
+    pub const CENTER: LineStyle = LineStyle::new(LineWeight::Thin, LineType::Center);
+    /// Thin long dashes and pairs of dots, for adjacent parts
+    pub const PHANTOM: LineStyle = LineStyle::new(LineWeight::Thin, LineType::Phantom);
+    /// Thin and continuous, for dimensions, leaders and hatching
+    pub const DIMENSION: LineStyle = LineStyle::new(LineWeight::Thin, LineType::Continuous);
+
+    /// Black line of `weight` and `line_type`
+    pub const fn new(weight: LineWeight, line_type: LineType) -> Self {
+        LineStyle {
+            weight,
+            line_type,
+            color: Color::BLACK,
+        }
+    }
+
+    /// Same line, in `color`
+    #[inline]
+    pub fn with_color(mut self, color: Color) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Arrowhead sized for this line
+    #[inline]
+    pub fn arrow(&self) -> ArrowSize {
+        ArrowSize::for_width(self.weight.width())
+    }
+}
+impl Default for LineStyle {
+    fn default() -> Self {
+        LineStyle::VISIBLE
+    }
+}
+
+impl From<LineStyle> for Stroke {
+    fn from(
+        LineStyle {
+            weight,
+            line_type,
+            color,
+        }: LineStyle,
+    ) -> Self {
+        let width = weight.width();
+        match line_type.dashes(width) {
+            Some(dashes) => Stroke::dash_pattern(color, width, dashes, 0.),
+            None => Stroke::from((color, width)),
+        }
+    }
+}
+
+/// Size of an arrowhead, proportional to the width of its line
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ArrowSize {
+    /// Length of the arrowhead, along the line
+    pub length: f32,
+    /// Width of the base of the arrowhead
+    pub width: f32,
+}
+impl ArrowSize {
+    /// Length of the arrowhead, in widths of the line
+    pub const LENGTH_RATIO: f32 = 10.;
+    /// Width of the base of the arrowhead, in widths of the line, about a 15° opening on each side
+    pub const WIDTH_RATIO: f32 = 5.;
+
+    /// Arrowhead for a line of `width`
+    pub fn for_width(width: f32) -> Self {
+        ArrowSize {
+            length: width * ArrowSize::LENGTH_RATIO,
+            width: width * ArrowSize::WIDTH_RATIO,
+        }
+    }
+}
+impl From<LineStyle> for ArrowSize {
+    fn from(line_style: LineStyle) -> Self {
+        line_style.arrow()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dashes_scale_with_width() {
+        let Stroke::DashPattern { dashes, width, .. } = Stroke::from(LineStyle::CENTER) else {
+            unreachable!()
+        };
+        assert_eq!(width, 0.25);
+        assert_eq!(dashes.as_slice(), &[6., 0.75, 0.125, 0.75]);
+
+        let Stroke::DashPattern { dashes, .. } =
+            Stroke::from(LineStyle::new(LineWeight::Thick, LineType::Phantom))
+        else {
+            unreachable!()
+        };
+        assert_eq!(dashes.as_slice(), &[12., 1.5, 0.25, 1.5, 0.25, 1.5]);
+
+        let visible = Stroke::from(LineStyle::VISIBLE.with_color(Color::RED));
+        assert_eq!(visible, Stroke::from((Color::RED, 0.5)));
+    }
+
+    #[test]
+    fn arrow_follows_weight() {
+        let thin = LineStyle::DIMENSION.arrow();
+        let thick = ArrowSize::from(LineStyle::VISIBLE);
+        assert_eq!(thin.length, 2.5);
+        assert_eq!(thick.length / thin.length, 2.);
+        assert_eq!(thick.width, 2.5);
+    }
+}
@@ -0,0 +1,246 @@
+//! Components registered by name, and built at runtime from parameters.
+//!
+//! External crates register their components once, and tools knowing only their name,
+//! such as scene loaders or command lines, can build them without depending on those crates.
+//! Parameters are strings, parsed by each component.
+//!
+//! ```
+//! # use dessin::prelude::*;
+//! use dessin::registry::{self, Parameters};
+//!
+//! registry::register("ring", |parameters: &Parameters| {
+//! 	let radius = parameters.parse_or("radius", 10.)?;
+//! 	let thickness = parameters.parse_or("thickness", 1.)?;
+//!
+//! 	Ok(dessin2!(Circle!(stroke = (Color::BLACK, thickness), radius = radius) > ()))
+//! });
+//!
+//! let ring = registry::build("ring", &Parameters::from_iter([("radius", "5")])).unwrap();
+//! assert!((ring.local_bounding_box().straigthen().width() - 10.).abs() < 10e-5);
+//!
+//! assert!(registry::build("ring", &Parameters::from_iter([("radius", "five")])).is_err());
+//! ```
+
+use crate::prelude::*;
+use std::{
+    collections::HashMap,
+    fmt,
+    str::FromStr,
+    sync::{Arc, OnceLock, RwLock},
+};
+
+/// Builds a component from its parameters
+pub type Constructor = dyn Fn(&Parameters) -> Result<Shape, RegistryError> + Send + Sync;
+
+static REGISTRY: OnceLock<RwLock<HashMap<String, Arc<Constructor>>>> = OnceLock::new();
+
+fn registry() -> &'static RwLock<HashMap<String, Arc<Constructor>>> {
+    REGISTRY.get_or_init(|| RwLock::new(HashMap::new()))
+}
+
+/// Error while building a registered component
+#[derive(Debug, Clone, PartialEq)]
+pub enum RegistryError {
+    /// No component is registered under this name
+    UnknownComponent(String),
+    /// A required parameter is missing
+    MissingParameter(String),
+    /// A parameter could not be parsed
+    InvalidParameter {
+        /// Name of the parameter
+        key: String,
+        /// Its value
+        value: String,
+    },
+    /// The component refused its parameters
+    Component(String),
+}
+impl fmt::Display for RegistryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RegistryError::UnknownComponent(name) => write!(f, "No component named `{name}`"),
+            RegistryError::MissingParameter(key) => write!(f, "Missing parameter `{key}`"),
+            RegistryError::InvalidParameter { key, value } => {
+                write!(f, "Invalid value `{value}` for parameter `{key}`")
+            }
+            RegistryError::Component(message) => write!(f, "{message}"),
+        }
+    }
+}
+impl std::error::Error for RegistryError {}
+
+/// Parameters of a component, by name
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Parameters(HashMap<String, String>);
+impl Parameters {
+    /// No parameter
+    pub fn new() -> Self {
+        Parameters::default()
+    }
+
+    /// Set the parameter `key`
+    #[inline]
+    pub fn insert<K: Into<String>, V: Into<String>>(&mut self, key: K, value: V) -> &mut Self {
+        self.0.insert(key.into(), value.into());
+        self
+    }
+    /// Set the parameter `key`
+    #[inline]
+    pub fn with<K: Into<String>, V: Into<String>>(mut self, key: K, value: V) -> Self {
+        self.insert(key, value);
+        self
+    }
+
+    /// Raw value of the parameter `key`
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).map(String::as_str)
+    }
+
+    /// Value of the parameter `key`, parsed, if any
+    pub fn parse<T: FromStr>(&self, key: &str) -> Result<Option<T>, RegistryError> {
+        self.get(key)
+            .map(|value| {
+                value
+                    .trim()
+                    .parse()
+                    .map_err(|_| RegistryError::InvalidParameter {
+                        key: key.to_string(),
+                        value: value.to_string(),
+                    })
+            })
+            .transpose()
+    }
+
+    /// Value of the parameter `key`, parsed, or `default` if missing
+    pub fn parse_or<T: FromStr>(&self, key: &str, default: T) -> Result<T, RegistryError> {
+        Ok(self.parse(key)?.unwrap_or(default))
+    }
+
+    /// Value of the parameter `key`, parsed, failing with [`RegistryError::MissingParameter`] if missing
+    pub fn require<T: FromStr>(&self, key: &str) -> Result<T, RegistryError> {
+        self.parse(key)?
+            .ok_or_else(|| RegistryError::MissingParameter(key.to_string()))
+    }
+
+    /// Names of the parameters
+    pub fn keys(&self) -> impl Iterator<Item = &str> {
+        self.0.keys().map(String::as_str)
+    }
+}
+impl<K: Into<String>, V: Into<String>> FromIterator<(K, V)> for Parameters {
+    fn from_iter<T: IntoIterator<Item = (K, V)>>(iter: T) -> Self {
+        Parameters(
+            iter.into_iter()
+                .map(|(k, v)| (k.into(), v.into()))
+                .collect(),
+        )
+    }
+}
+impl From<HashMap<String, String>> for Parameters {
+    fn from(parameters: HashMap<String, String>) -> Self {
+        Parameters(parameters)
+    }
+}
+
+/// Register the component `name`, replacing any previous one of the same name.
+///
+/// Returns whether a component was replaced.
+pub fn register<S, F>(name: S, constructor: F) -> bool
+where
+    S: Into<String>,
+    F: Fn(&Parameters) -> Result<Shape, RegistryError> + Send + Sync + 'static,
+{
+    registry()
+        .write()
+        .unwrap()
+        .insert(name.into(), Arc::new(constructor))
+        .is_some()
+}
+
+/// Remove the component `name`, returning whether it was registered
+pub fn unregister(name: &str) -> bool {
+    registry().write().unwrap().remove(name).is_some()
+}
+
+/// Whether a component is registered under `name`
+pub fn is_registered(name: &str) -> bool {
+    registry().read().unwrap().contains_key(name)
+}
+
+/// Names of the registered components, sorted
+pub fn component_names() -> Vec<String> {
+    let mut names = registry()
+        .read()
+        .unwrap()
+        .keys()
+        .cloned()
+        .collect::<Vec<_>>();
+    names.sort();
+    names
+}
+
+/// Build the component `name` from `parameters`
+pub fn build(name: &str, parameters: &Parameters) -> Result<Shape, RegistryError> {
+    // Released before building, so constructors can build other components
+    let constructor = registry()
+        .read()
+        .unwrap()
+        .get(name)
+        .cloned()
+        .ok_or_else(|| RegistryError::UnknownComponent(name.to_string()))?;
+
+    constructor(parameters)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn register_and_build() {
+        assert!(!register("tests::square", |parameters: &Parameters| {
+            let side: f32 = parameters.require("side")?;
+            Ok(dessin2!(Rectangle(width = side, height = side) > ()))
+        }));
+        assert!(is_registered("tests::square"));
+        assert!(component_names().contains(&"tests::square".to_string()));
+
+        let square = build("tests::square", &Parameters::new().with("side", " 4 ")).unwrap();
+        assert!((square.local_bounding_box().straigthen().width() - 4.).abs() < 10e-5);
+
+        assert_eq!(
+            build("tests::square", &Parameters::new()).err(),
+            Some(RegistryError::MissingParameter("side".to_string()))
+        );
+
+        assert!(unregister("tests::square"));
+        assert_eq!(
+            build("tests::square", &Parameters::new()).err(),
+            Some(RegistryError::UnknownComponent("tests::square".to_string()))
+        );
+    }
+
+    #[test]
+    fn nested_components() {
+        register("tests::dot", |_: &Parameters| {
+            Ok(dessin2!(Circle(radius = 1.) > ()))
+        });
+        register("tests::dots", |parameters: &Parameters| {
+            let count: usize = parameters.parse_or("count", 2)?;
+            let dots = (0..count)
+                .map(|_| build("tests::dot", &Parameters::new()))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            Ok(Shape::Group(Group {
+                local_transform: Default::default(),
+                shapes: dots,
+                metadata: vec![],
+            }))
+        });
+
+        assert!(matches!(
+            build("tests::dots", &Parameters::new().with("count", "3")),
+            Ok(Shape::Group(Group { shapes, .. })) if shapes.len() == 3
+        ));
+    }
+}
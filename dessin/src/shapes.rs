@@ -68,16 +68,21 @@
 //!
 //! ## Details
 
+pub(crate) mod angle;
 pub(crate) mod curve;
 pub(crate) mod dynamic;
 pub(crate) mod ellipse;
 pub(crate) mod image;
+pub(crate) mod lod;
+mod normalize;
 pub(crate) mod text;
 
 pub use self::image::*;
+pub use angle::*;
 pub use curve::*;
 pub use dynamic::*;
 pub use ellipse::*;
+pub use lod::*;
 use na::{Point2, Rotation2, Scale2, Vector2};
 use nalgebra::{self as na, Transform2, Translation2};
 use std::{fmt, marker::PhantomData, sync::Arc};
@@ -514,6 +519,14 @@ pub enum Shape {
         fill: Option<crate::style::Fill>,
         /// Stroke
         stroke: Option<crate::style::Stroke>,
+        /// Position of the stroke relative to closed outlines
+        stroke_alignment: crate::style::StrokeAlignment,
+        /// Effect applied to the rendering of the shape
+        filter: Option<crate::style::Filter>,
+        /// Opacity of the whole shape, from 0 to 1
+        opacity: f32,
+        /// How the colors of the shape mix with the ones under it
+        blend_mode: crate::style::BlendMode,
         /// Styled shape. (Or Shapes if it is a [`Groupe`][Shape::Group])
         shape: Box<Shape>,
     },
@@ -525,6 +538,10 @@ pub enum Shape {
     Text(Text),
     /// Curve
     Curve(Curve),
+    /// Alternative representations, picked by the size they are rendered at
+    ///
+    /// See [`LevelOfDetail`] for more details.
+    LevelOfDetail(LevelOfDetail),
     /// Shape whose body is generated only during export.
     ///
     /// Enables chirurgical changes of the shape.
@@ -593,17 +610,26 @@ impl fmt::Debug for Shape {
             Self::Style {
                 fill,
                 stroke,
+                stroke_alignment,
+                filter,
+                opacity,
+                blend_mode,
                 shape,
             } => f
                 .debug_struct("Style")
                 .field("fill", fill)
                 .field("stroke", stroke)
+                .field("stroke_alignment", stroke_alignment)
+                .field("filter", filter)
+                .field("opacity", opacity)
+                .field("blend_mode", blend_mode)
                 .field("shape", shape)
                 .finish(),
             Self::Ellipse(arg0) => f.debug_tuple("Ellipse").field(arg0).finish(),
             Self::Image(arg0) => f.debug_tuple("Image").field(arg0).finish(),
             Self::Text(arg0) => f.debug_tuple("Text").field(arg0).finish(),
             Self::Curve(arg0) => f.debug_tuple("Curve").field(arg0).finish(),
+            Self::LevelOfDetail(arg0) => f.debug_tuple("LevelOfDetail").field(arg0).finish(),
             Self::Dynamic {
                 local_transform,
                 shaper: _,
@@ -649,6 +675,9 @@ impl ShapeOp for Shape {
             Shape::Curve(v) => {
                 v.transform(transform_matrix);
             }
+            Shape::LevelOfDetail(v) => {
+                v.transform(transform_matrix);
+            }
             Shape::Dynamic {
                 local_transform, ..
             } => {
@@ -670,6 +699,7 @@ impl ShapeOp for Shape {
             Shape::Image(v) => v.local_transform(),
             Shape::Text(v) => v.local_transform(),
             Shape::Curve(v) => v.local_transform(),
+            Shape::LevelOfDetail(v) => v.local_transform(),
             Shape::Dynamic {
                 local_transform, ..
             } => local_transform,
@@ -680,21 +710,32 @@ impl ShapeOp for Shape {
 impl ShapeBoundingBox for Shape {
     fn local_bounding_box(&self) -> BoundingBox<UnParticular> {
         match self {
-            Shape::Group(Group {
-                local_transform,
-                shapes,
-                ..
-            }) => shapes
-                .iter()
-                .map(|v| v.global_bounding_box(local_transform).straigthen())
-                .reduce(BoundingBox::join)
-                .unwrap_or_else(|| BoundingBox::zero())
-                .as_unparticular(),
+            Shape::Group(group) => {
+                let bb = |shape: &Shape| {
+                    shape
+                        .global_bounding_box(&group.local_transform)
+                        .straigthen()
+                };
+
+                // Nothing is drawn outside of the clipping shape
+                if let Some((clipped, clipped_by)) = crate::contrib::Clip::parts(group) {
+                    return bb(clipped).intersect(bb(clipped_by)).as_unparticular();
+                }
+
+                group
+                    .shapes
+                    .iter()
+                    .map(bb)
+                    .reduce(BoundingBox::join)
+                    .unwrap_or_else(|| BoundingBox::zero())
+                    .as_unparticular()
+            }
             Shape::Style { shape, .. } => shape.local_bounding_box(),
             Shape::Ellipse(e) => e.local_bounding_box(),
             Shape::Image(i) => i.local_bounding_box(),
             Shape::Text(t) => t.local_bounding_box(),
             Shape::Curve(c) => c.local_bounding_box(),
+            Shape::LevelOfDetail(l) => l.local_bounding_box(),
             Shape::Dynamic {
                 local_transform,
                 shaper,
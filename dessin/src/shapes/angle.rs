@@ -0,0 +1,203 @@
+use nalgebra::Rotation2;
+use std::{
+    f32::consts::{PI, TAU},
+    ops::{Add, AddAssign, Div, Mul, Neg, Sub, SubAssign},
+};
+
+/// An angle, built from degrees or radians so the unit is never ambiguous.
+///
+/// Like everywhere in dessin, angles are counterclockwise, starting from the positive X axis,
+/// and arcs go counterclockwise from their start angle to their end angle.
+/// A bare `f32` converts to an angle in radians, like [`Rotation2::new`].
+///
+/// ```
+/// # use dessin::prelude::*;
+/// use std::f32::consts::FRAC_PI_2;
+///
+/// assert!((Angle::degrees(90.).as_radians() - FRAC_PI_2).abs() < 10e-6);
+/// assert!((Angle::degrees(-90.).normalized().as_degrees() - 270.).abs() < 10e-4);
+/// assert!((Angle::degrees(270.).normalized_signed().as_degrees() + 90.).abs() < 10e-4);
+///
+/// // Usable wherever a rotation is expected
+/// let square = dessin2!(Rectangle(width = 1., height = 1., rotate = Angle::degrees(45.)));
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, PartialOrd)]
+pub struct Angle {
+    radians: f32,
+}
+impl Angle {
+    /// No rotation
+    pub const ZERO: Angle = Angle { radians: 0. };
+    /// Quarter of a turn
+    pub const QUARTER_TURN: Angle = Angle { radians: PI / 2. };
+    /// Half of a turn
+    pub const HALF_TURN: Angle = Angle { radians: PI };
+    /// Full turn
+    pub const FULL_TURN: Angle = Angle { radians: TAU };
+
+    /// Angle of `radians`
+    #[inline]
+    pub const fn radians(radians: f32) -> Self {
+        Angle { radians }
+    }
+
+    /// Angle of `degrees`
+    #[inline]
+    pub fn degrees(degrees: f32) -> Self {
+        Angle {
+            radians: degrees.to_radians(),
+        }
+    }
+
+    /// Angle of `turns` full turns
+    #[inline]
+    pub fn turns(turns: f32) -> Self {
+        Angle {
+            radians: turns * TAU,
+        }
+    }
+
+    /// Value in radians
+    #[inline]
+    pub const fn as_radians(self) -> f32 {
+        self.radians
+    }
+
+    /// Value in degrees
+    #[inline]
+    pub fn as_degrees(self) -> f32 {
+        self.radians.to_degrees()
+    }
+
+    /// Same direction, between 0 included and a full turn excluded
+    pub fn normalized(self) -> Self {
+        let radians = self.radians.rem_euclid(TAU);
+        // Rounding of tiny negative angles
+        Angle {
+            radians: if radians >= TAU { 0. } else { radians },
+        }
+    }
+
+    /// Same direction, between minus half a turn excluded and half a turn included
+    pub fn normalized_signed(self) -> Self {
+        let Angle { radians } = self.normalized();
+        Angle {
+            radians: if radians > PI { radians - TAU } else { radians },
+        }
+    }
+
+    /// Counterclockwise span from `self` to `end`, up to a full turn.
+    ///
+    /// Equal angles span nothing, while angles a whole number of turns apart span a full turn,
+    /// as an arc from one to the other is a full circle.
+    pub fn span_to(self, end: Angle) -> Angle {
+        if self == end {
+            return Angle::ZERO;
+        }
+
+        let span = (end - self).normalized();
+        if span.radians == 0. {
+            Angle::FULL_TURN
+        } else {
+            span
+        }
+    }
+
+    /// Sine
+    #[inline]
+    pub fn sin(self) -> f32 {
+        self.radians.sin()
+    }
+
+    /// Cosine
+    #[inline]
+    pub fn cos(self) -> f32 {
+        self.radians.cos()
+    }
+}
+
+impl From<f32> for Angle {
+    /// Radians
+    #[inline]
+    fn from(radians: f32) -> Self {
+        Angle { radians }
+    }
+}
+impl From<Angle> for Rotation2<f32> {
+    #[inline]
+    fn from(angle: Angle) -> Self {
+        Rotation2::new(angle.radians)
+    }
+}
+
+impl Add for Angle {
+    type Output = Angle;
+    #[inline]
+    fn add(self, rhs: Angle) -> Angle {
+        Angle::radians(self.radians + rhs.radians)
+    }
+}
+impl AddAssign for Angle {
+    #[inline]
+    fn add_assign(&mut self, rhs: Angle) {
+        self.radians += rhs.radians;
+    }
+}
+impl Sub for Angle {
+    type Output = Angle;
+    #[inline]
+    fn sub(self, rhs: Angle) -> Angle {
+        Angle::radians(self.radians - rhs.radians)
+    }
+}
+impl SubAssign for Angle {
+    #[inline]
+    fn sub_assign(&mut self, rhs: Angle) {
+        self.radians -= rhs.radians;
+    }
+}
+impl Neg for Angle {
+    type Output = Angle;
+    #[inline]
+    fn neg(self) -> Angle {
+        Angle::radians(-self.radians)
+    }
+}
+impl Mul<f32> for Angle {
+    type Output = Angle;
+    #[inline]
+    fn mul(self, rhs: f32) -> Angle {
+        Angle::radians(self.radians * rhs)
+    }
+}
+impl Div<f32> for Angle {
+    type Output = Angle;
+    #[inline]
+    fn div(self, rhs: f32) -> Angle {
+        Angle::radians(self.radians / rhs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nalgebra::Point2;
+
+    #[test]
+    fn rotation() {
+        let p = Rotation2::from(Angle::degrees(90.)) * Point2::new(1., 0.);
+        assert!(p.x.abs() < 10e-6 && (p.y - 1.).abs() < 10e-6);
+    }
+
+    #[test]
+    fn span() {
+        let span = Angle::degrees(350.).span_to(Angle::degrees(10.));
+        assert!((span.as_degrees() - 20.).abs() < 10e-4);
+
+        let span = Angle::degrees(10.).span_to(Angle::degrees(350.));
+        assert!((span.as_degrees() - 340.).abs() < 10e-4);
+
+        assert_eq!(Angle::ZERO.span_to(Angle::ZERO), Angle::ZERO);
+        assert_eq!(Angle::ZERO.span_to(Angle::FULL_TURN), Angle::FULL_TURN);
+    }
+}
@@ -1,14 +1,217 @@
 mod keypoint;
+mod split;
 
 use super::{BoundingBox, ShapeBoundingBox, UnParticular};
-use crate::shapes::{Shape, ShapeOp};
+use crate::{
+    shapes::{Group, Shape, ShapeOp, ShapeOpWith},
+    style::{FillRule, LineJoin, Marker},
+};
 pub use keypoint::*;
-use nalgebra::{Point2, Transform2};
+use nalgebra::{Point2, Rotation2, Scale2, Transform2, Vector2};
+use std::{
+    ops::RangeInclusive,
+    sync::atomic::{AtomicU32, Ordering},
+};
+
+/// Bits of the epsilon flattened points are rounded to, 0 when they aren't
+static GEOMETRY_PRECISION: AtomicU32 = AtomicU32::new(0);
+
+/// Round every [flattened][CurvePosition::flatten] point to a multiple of `epsilon`, or stop rounding with `None`.
+///
+/// Applies to everything built on flattening, such as outlines, offsets, plotter and G-code exports.
+/// Regenerated outputs then differ only when the geometry does, not by floating point noise.
+///
+/// ```
+/// # use dessin::prelude::*;
+/// let line = dessin2!(Line(from = [0., 0.], to = [1. / 3., 1e-7]));
+/// let curve = Curve::from(line).position(&Default::default());
+/// assert_eq!(curve.flatten(0.1)[1].y, 1e-7);
+///
+/// set_geometry_precision(Some(0.001));
+/// assert_eq!(curve.flatten(0.1)[1].y, 0.);
+/// assert!((curve.flatten(0.1)[1].x - 0.333).abs() < 1e-6);
+///
+/// set_geometry_precision(None);
+/// assert_eq!(geometry_precision(), None);
+/// ```
+pub fn set_geometry_precision(epsilon: Option<f32>) {
+    let bits = epsilon
+        .filter(|epsilon| *epsilon > 0.)
+        .map_or(0, f32::to_bits);
+    GEOMETRY_PRECISION.store(bits, Ordering::Relaxed);
+}
+
+/// Epsilon flattened points are rounded to, set by [`set_geometry_precision`]
+pub fn geometry_precision() -> Option<f32> {
+    match GEOMETRY_PRECISION.load(Ordering::Relaxed) {
+        0 => None,
+        bits => Some(f32::from_bits(bits)),
+    }
+}
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct CurvePosition {
     pub keypoints: Vec<KeypointPosition>,
     pub closed: bool,
+    /// Which areas enclosed by the curve are filled
+    pub fill_rule: FillRule,
+}
+
+impl CurvePosition {
+    /// Approximate the curve by a polyline, no further than `tolerance` from the real curve.
+    ///
+    /// Closed curves end with their first point, so the polyline can be drawn as is.
+    /// Points are rounded to the [geometry precision][set_geometry_precision], if any.
+    /// Beziers without a starting point continue from the previous point, and are skipped if there is none.
+    pub fn flatten(&self, tolerance: f32) -> Vec<Point2<f32>> {
+        let mut points: Vec<Point2<f32>> = Vec::with_capacity(self.keypoints.len());
+
+        for keypoint in &self.keypoints {
+            match keypoint {
+                KeypointPosition::Point(p) => points.push(*p),
+                KeypointPosition::Bezier(b) => {
+                    if let Some(start) = b.start {
+                        points.push(start);
+                    }
+
+                    if let Some(start) = points.last().copied() {
+                        points.extend(b.flatten(start, tolerance));
+                    }
+                }
+            }
+        }
+
+        if let Some(epsilon) = geometry_precision() {
+            // Adding 0 turns -0 into 0
+            let round = |v: f32| (v / epsilon).round() * epsilon + 0.;
+            for p in &mut points {
+                *p = Point2::new(round(p.x), round(p.y));
+            }
+        }
+
+        if self.closed {
+            if let (Some(first), Some(last)) = (points.first().copied(), points.last()) {
+                if first != *last {
+                    points.push(first);
+                }
+            }
+        }
+
+        points
+    }
+
+    /// Offset a closed curve outward by `distance`, or inward if `distance` is negative.
+    ///
+    /// The curve is [flattened][CurvePosition::flatten] within `tolerance`.
+    /// Corners opening a gap are closed by `join`, like the ones of a stroke, miters longer than `miter_limit` times `distance` being beveled.
+    /// Corners where the offset sides overlap meet where they cross, no further than `miter_limit` times `distance`.
+    ///
+    /// Open curves have no inside nor outside, and are returned as is, so an aligned stroke stays centered on them.
+    ///
+    /// ```
+    /// # use dessin::{prelude::*, nalgebra::{Point2, Transform2}};
+    /// let triangle = dessin2!(Curve!(
+    /// 	then = Point2::new(0., 0.),
+    /// 	then = Point2::new(10., 0.),
+    /// 	then = Point2::new(5., 2.),
+    /// 	closed,
+    /// ))
+    /// .position(&Transform2::identity());
+    ///
+    /// let mitered = triangle.offset(1., LineJoin::Miter, 100., 0.1);
+    /// let beveled = triangle.offset(1., LineJoin::Miter, 4., 0.1);
+    /// assert_eq!(mitered.keypoints.len(), 3);
+    /// assert_eq!(beveled.keypoints.len(), 5);
+    /// ```
+    pub fn offset(
+        &self,
+        distance: f32,
+        join: LineJoin,
+        miter_limit: f32,
+        tolerance: f32,
+    ) -> CurvePosition {
+        if !self.closed || distance == 0. {
+            return self.clone();
+        }
+
+        // Nearly identical points have no reliable direction
+        let is_same = |a: &Point2<f32>, b: &Point2<f32>| (a - b).magnitude() <= tolerance / 100.;
+        let mut points = self.flatten(tolerance);
+        points.dedup_by(|a, b| is_same(a, b));
+        if points.len() > 1 && is_same(&points[0], &points[points.len() - 1]) {
+            points.pop();
+        }
+        if points.len() < 3 {
+            return self.clone();
+        }
+
+        // Positive area means counter clockwise, where the outside is on the right of each edge
+        let area: f32 = points
+            .iter()
+            .zip(points.iter().cycle().skip(1))
+            .map(|(a, b)| a.x * b.y - b.x * a.y)
+            .sum();
+        let distance = if area > 0. { distance } else { -distance };
+
+        let outward = |a: &Point2<f32>, b: &Point2<f32>| {
+            let d = (b - a).normalize();
+            Vector2::new(d.y, -d.x)
+        };
+
+        let n = points.len();
+        let mut offset = Vec::with_capacity(n);
+        for i in 0..n {
+            let (prev, curr, next) = (&points[(i + n - 1) % n], &points[i], &points[(i + 1) % n]);
+            let n1 = outward(prev, curr);
+            let n2 = outward(curr, next);
+
+            let bisector = n1 + n2;
+            if bisector.magnitude() <= f32::EPSILON {
+                offset.push(curr + n1 * distance);
+                continue;
+            }
+            let bisector = bisector.normalize();
+            let miter = 1. / bisector.dot(&n1);
+
+            // Turning left opens a gap on the right, and the other way around
+            let turn = (curr - prev).perp(&(next - curr));
+            let opens_gap = turn * distance > 0.;
+            if !opens_gap {
+                offset.push(curr + bisector * miter.min(miter_limit) * distance);
+                continue;
+            }
+
+            match join {
+                LineJoin::Miter if miter <= miter_limit => {
+                    offset.push(curr + bisector * miter * distance);
+                }
+                LineJoin::Miter | LineJoin::Bevel => {
+                    offset.push(curr + n1 * distance);
+                    offset.push(curr + n2 * distance);
+                }
+                LineJoin::Round => {
+                    let sweep = n1.angle(&n2);
+                    let radius = distance.abs();
+                    let step = 2. * (1. - (tolerance / radius).min(1.)).acos();
+                    let steps = (sweep / step.max(f32::EPSILON)).ceil().max(1.) as usize;
+                    let rotation = Rotation2::new(sweep / steps as f32 * turn.signum());
+
+                    let mut normal = n1;
+                    offset.push(curr + normal * distance);
+                    for _ in 0..steps {
+                        normal = rotation * normal;
+                        offset.push(curr + normal * distance);
+                    }
+                }
+            }
+        }
+
+        CurvePosition {
+            keypoints: offset.into_iter().map(KeypointPosition::Point).collect(),
+            closed: true,
+            fill_rule: self.fill_rule,
+        }
+    }
 }
 
 #[derive(Default, Debug, Clone, PartialEq)]
@@ -16,6 +219,12 @@ pub struct Curve {
     pub local_transform: Transform2<f32>,
     pub keypoints: Vec<Keypoint>,
     pub closed: bool,
+    /// Head drawn at the start of an open curve, by the stroke around it
+    pub start_marker: Option<Marker>,
+    /// Head drawn at the end of an open curve, by the stroke around it
+    pub end_marker: Option<Marker>,
+    /// Which areas enclosed by the curve, crossing itself, are filled
+    pub fill_rule: FillRule,
 }
 impl Curve {
     #[inline]
@@ -46,6 +255,39 @@ impl Curve {
         self
     }
 
+    #[inline]
+    pub fn start_marker(&mut self, marker: Marker) -> &mut Self {
+        self.start_marker = Some(marker);
+        self
+    }
+    #[inline]
+    pub fn with_start_marker(mut self, marker: Marker) -> Self {
+        self.start_marker(marker);
+        self
+    }
+
+    #[inline]
+    pub fn end_marker(&mut self, marker: Marker) -> &mut Self {
+        self.end_marker = Some(marker);
+        self
+    }
+    #[inline]
+    pub fn with_end_marker(mut self, marker: Marker) -> Self {
+        self.end_marker(marker);
+        self
+    }
+
+    #[inline]
+    pub fn fill_rule(&mut self, fill_rule: FillRule) -> &mut Self {
+        self.fill_rule = fill_rule;
+        self
+    }
+    #[inline]
+    pub fn with_fill_rule(mut self, fill_rule: FillRule) -> Self {
+        self.fill_rule(fill_rule);
+        self
+    }
+
     #[inline]
     pub fn closed(&mut self) -> &mut Self {
         self.close(true)
@@ -78,6 +320,97 @@ impl Curve {
         c
     }
 
+    /// Plot of `f`, sampled at `samples` evenly spaced `x` of `x_range`, and smoothed between the samples.
+    ///
+    /// See [`Curve::from_parametric_fn`] for how the curve is built.
+    ///
+    /// ```
+    /// # use dessin::prelude::*;
+    /// let sine = Curve::from_fn(|x| (x / 10.).sin() * 10., 0.0..=62.8, 20);
+    ///
+    /// assert_eq!(sine.keypoints.len(), 20);
+    /// let bb = sine.local_bounding_box().straigthen();
+    /// assert!((bb.width() - 62.8).abs() < 10e-4);
+    /// ```
+    pub fn from_fn<F: Fn(f32) -> f32>(f: F, x_range: RangeInclusive<f32>, samples: usize) -> Self {
+        Curve::from_parametric_fn(|x| Point2::new(x, f(x)), x_range, samples)
+    }
+
+    /// Plot of the points `f(t)`, sampled at `samples` evenly spaced `t` of `t_range`, and smoothed between the samples.
+    ///
+    /// Consecutive samples are joined by Béziers following the direction of the curve at each sample,
+    /// estimated from its neighbours, so the curve is smooth with few samples.
+    /// Samples which are not finite, such as asymptotes, are left out.
+    ///
+    /// ```
+    /// # use dessin::prelude::*;
+    /// use std::f32::consts::TAU;
+    ///
+    /// let spiral = Curve::from_parametric_fn(
+    /// 	|t| [t * t.cos(), t * t.sin()].into(),
+    /// 	0.0..=2. * TAU,
+    /// 	50,
+    /// );
+    ///
+    /// assert_eq!(spiral.start_point(), Some([0., 0.].into()));
+    /// assert_eq!(spiral.keypoints.len(), 50);
+    /// ```
+    pub fn from_parametric_fn<F: Fn(f32) -> Point2<f32>>(
+        f: F,
+        t_range: RangeInclusive<f32>,
+        samples: usize,
+    ) -> Self {
+        let (start, end) = t_range.into_inner();
+        let samples = samples.max(2);
+        let step = (end - start) / (samples - 1) as f32;
+        let points = (0..samples)
+            .map(|idx| {
+                let t = if idx == samples - 1 {
+                    end
+                } else {
+                    start + idx as f32 * step
+                };
+                (t, f(t))
+            })
+            .filter(|(_, p)| p.x.is_finite() && p.y.is_finite())
+            .collect::<Vec<_>>();
+
+        // Derivative of the curve at each sample, from the samples around it
+        let chord = |from: usize, to: usize| {
+            let ((t0, p0), (t1, p1)) = (points[from], points[to]);
+            if t1 > t0 {
+                (p1 - p0) / (t1 - t0)
+            } else {
+                Vector2::zeros()
+            }
+        };
+        let mut derivatives = (0..points.len())
+            .map(|idx| chord(idx.saturating_sub(1), (idx + 1).min(points.len() - 1)))
+            .collect::<Vec<_>>();
+        // Ends follow the parabola through the first, or last, three samples
+        let last = points.len().saturating_sub(1);
+        if last > 1 {
+            derivatives[0] = 2. * chord(0, 1) - derivatives[1];
+            derivatives[last] = 2. * chord(last - 1, last) - derivatives[last - 1];
+        }
+
+        let mut curve = Curve::default();
+        if let Some((_, first)) = points.first() {
+            curve.then(*first);
+        }
+        for idx in 1..points.len() {
+            let ((t0, p0), (t1, p1)) = (points[idx - 1], points[idx]);
+            let third = (t1 - t0) / 3.;
+            curve.then(Bezier::new(
+                p0 + derivatives[idx - 1] * third,
+                p1 - derivatives[idx] * third,
+                p1,
+            ));
+        }
+
+        curve
+    }
+
     fn _reversed<'a>(&'a self) -> (Self, Option<&'a Bezier>) {
         let mut keypoints = Vec::with_capacity(self.keypoints.len());
 
@@ -158,6 +491,9 @@ impl Curve {
                 local_transform: self.local_transform,
                 closed: self.closed,
                 keypoints,
+                start_marker: self.end_marker.clone(),
+                end_marker: self.start_marker.clone(),
+                fill_rule: self.fill_rule,
             },
             tmp,
         )
@@ -188,8 +524,83 @@ impl Curve {
         CurvePosition {
             keypoints: flatten_curve(self, parent_transform),
             closed: self.closed,
+            fill_rule: self.fill_rule,
         }
     }
+
+    /// This curve, shortened under its [markers][Marker], and the markers, for a stroke of `width`.
+    ///
+    /// Both are in the coordinates of the parent of the curve, where `width` applies,
+    /// and the returned curve has no marker. Closed curves have none either.
+    ///
+    /// ```
+    /// # use dessin::prelude::*;
+    /// let line = Curve::from(dessin2!(Line(from = [0., 0.], to = [20., 0.], end_marker = Marker::Arrow)));
+    /// let (line, arrow) = line.place_markers(0.5);
+    ///
+    /// // The tip of the arrow is at the end of the line, which stops under the arrow
+    /// let bb = arrow.local_bounding_box().straigthen();
+    /// assert!((bb.right() - 20.).abs() < 10e-4);
+    /// assert!(line.length() < 20.);
+    /// ```
+    pub fn place_markers(&self, width: f32) -> (Curve, Shape) {
+        let curve = Curve {
+            local_transform: Transform2::identity(),
+            keypoints: self
+                .position(&Transform2::identity())
+                .keypoints
+                .into_iter()
+                .map(|keypoint| match keypoint {
+                    KeypointPosition::Point(p) => Keypoint::Point(p),
+                    KeypointPosition::Bezier(b) => Keypoint::Bezier(b),
+                })
+                .collect(),
+            closed: self.closed,
+            start_marker: None,
+            end_marker: None,
+            fill_rule: self.fill_rule,
+        };
+
+        let markers = [self.start_marker.clone(), self.end_marker.clone()];
+        let ends = match curve.ends() {
+            Some(ends) if !self.closed && markers.iter().any(Option::is_some) => ends,
+            _ => return (curve, Shape::default()),
+        };
+
+        let length = curve.length();
+        let [start_inset, end_inset] = markers
+            .each_ref()
+            .map(|marker| marker.as_ref().map_or(0., |marker| marker.inset() * width));
+        let curve = if length > 0. && start_inset + end_inset > 0. {
+            let start = (start_inset / length).min(1.);
+            curve.trim(start, (1. - end_inset / length).max(start))
+        } else {
+            curve
+        };
+
+        let markers = markers
+            .into_iter()
+            .zip(ends)
+            .filter_map(|(marker, (end, direction))| {
+                Some(
+                    marker?
+                        .shape()
+                        .with_resize(Scale2::new(width, width))
+                        .with_rotate(Rotation2::new(direction.y.atan2(direction.x)))
+                        .with_translate(end.coords),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        (
+            curve,
+            Shape::Group(Group {
+                local_transform: Transform2::identity(),
+                shapes: markers,
+                metadata: vec![],
+            }),
+        )
+    }
 }
 
 impl ShapeOp for Curve {
@@ -244,3 +655,180 @@ where
         self.clone().into()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flatten_closed_polygon() {
+        let curve = CurvePosition {
+            keypoints: vec![
+                KeypointPosition::Point(Point2::new(0., 0.)),
+                KeypointPosition::Point(Point2::new(1., 0.)),
+                KeypointPosition::Point(Point2::new(1., 1.)),
+            ],
+            closed: true,
+            fill_rule: FillRule::NonZero,
+        };
+
+        assert_eq!(
+            curve.flatten(0.1),
+            vec![
+                Point2::new(0., 0.),
+                Point2::new(1., 0.),
+                Point2::new(1., 1.),
+                Point2::new(0., 0.),
+            ]
+        );
+    }
+
+    #[test]
+    fn flatten_bezier_within_tolerance() {
+        let bezier = Bezier::new_with_start(
+            Point2::new(0., 0.),
+            Point2::new(0., 10.),
+            Point2::new(10., 10.),
+            Point2::new(10., 0.),
+        );
+        let curve = CurvePosition {
+            keypoints: vec![KeypointPosition::Bezier(bezier.clone())],
+            closed: false,
+            fill_rule: FillRule::NonZero,
+        };
+
+        let points = curve.flatten(0.01);
+        assert!(points.len() > 10);
+        assert_eq!(points.first(), Some(&Point2::new(0., 0.)));
+        assert_eq!(points.last(), Some(&Point2::new(10., 0.)));
+
+        // Middle of each segment stays close to the curve
+        let segments = points.len() - 1;
+        for idx in 0..segments {
+            let t = (idx as f32 + 0.5) / segments as f32;
+            let on_curve = bezier.point_at(Point2::new(0., 0.), t);
+            let on_polyline = nalgebra::center(&points[idx], &points[idx + 1]);
+            assert!((on_curve - on_polyline).magnitude() < 0.02);
+        }
+    }
+
+    #[test]
+    fn offset_square() {
+        let square = |size: f32| CurvePosition {
+            keypoints: vec![
+                KeypointPosition::Point(Point2::new(-size, -size)),
+                KeypointPosition::Point(Point2::new(size, -size)),
+                KeypointPosition::Point(Point2::new(size, size)),
+                KeypointPosition::Point(Point2::new(-size, size)),
+            ],
+            closed: true,
+            fill_rule: FillRule::NonZero,
+        };
+
+        let grown = square(1.).offset(0.5, LineJoin::Miter, 4., 0.1);
+        for (a, b) in grown
+            .flatten(0.1)
+            .iter()
+            .zip(square(1.5).flatten(0.1).iter())
+        {
+            assert!((a - b).magnitude() < 10e-6);
+        }
+
+        // Orientation doesn't matter
+        let mut clockwise = square(1.);
+        clockwise.keypoints.reverse();
+        let shrunk = clockwise.offset(-0.5, LineJoin::Miter, 4., 0.1);
+        for p in shrunk.flatten(0.1) {
+            assert!((p.x.abs() - 0.5).abs() < 10e-6);
+            assert!((p.y.abs() - 0.5).abs() < 10e-6);
+        }
+    }
+
+    #[test]
+    fn offset_joins() {
+        let square = CurvePosition {
+            keypoints: vec![
+                KeypointPosition::Point(Point2::new(-1., -1.)),
+                KeypointPosition::Point(Point2::new(1., -1.)),
+                KeypointPosition::Point(Point2::new(1., 1.)),
+                KeypointPosition::Point(Point2::new(-1., 1.)),
+            ],
+            closed: true,
+            fill_rule: FillRule::NonZero,
+        };
+
+        // Square corners are mitered within the miter limit of SVG, but not within 1.2
+        assert_eq!(
+            square.offset(0.5, LineJoin::Miter, 4., 0.1).keypoints.len(),
+            4
+        );
+        assert_eq!(
+            square
+                .offset(0.5, LineJoin::Miter, 1.2, 0.1)
+                .keypoints
+                .len(),
+            8
+        );
+        assert_eq!(
+            square.offset(0.5, LineJoin::Bevel, 4., 0.1).keypoints.len(),
+            8
+        );
+
+        // Rounded corners stay at the distance from the corner
+        let rounded = square.offset(0.5, LineJoin::Round, 4., 0.01);
+        assert!(rounded.keypoints.len() > 8);
+        for p in rounded.flatten(0.01) {
+            let corner = Point2::new(p.x.signum(), p.y.signum());
+            let distance = if p.x.abs() > 1. && p.y.abs() > 1. {
+                (p - corner).magnitude()
+            } else {
+                (p.x.abs() - 1.).max(p.y.abs() - 1.)
+            };
+            assert!((distance - 0.5).abs() < 10e-5, "{p}");
+        }
+
+        // Shrinking leaves no gap to join
+        let shrunk = square.offset(-0.5, LineJoin::Round, 4., 0.01);
+        assert_eq!(shrunk.keypoints.len(), 4);
+    }
+
+    #[test]
+    fn offset_leaves_open_curves() {
+        let line = CurvePosition {
+            keypoints: vec![
+                KeypointPosition::Point(Point2::new(0., 0.)),
+                KeypointPosition::Point(Point2::new(10., 0.)),
+                KeypointPosition::Point(Point2::new(10., 10.)),
+            ],
+            closed: false,
+            fill_rule: FillRule::NonZero,
+        };
+
+        assert_eq!(line.offset(1., LineJoin::Miter, 4., 0.1), line);
+    }
+
+    #[test]
+    fn from_fn_follows_the_function() {
+        let parabola = Curve::from_fn(|x| x * x, -2.0..=2.0, 5);
+        assert_eq!(parabola.keypoints.len(), 5);
+
+        let mut start = parabola.start_point().unwrap();
+        for keypoint in &parabola.keypoints[1..] {
+            let Keypoint::Bezier(bezier) = keypoint else {
+                unreachable!()
+            };
+            let middle = bezier.point_at(start, 0.5);
+            assert!((middle.y - middle.x * middle.x).abs() < 0.05, "{middle}");
+            start = bezier.end;
+        }
+        assert_eq!(start, Point2::new(2., 4.));
+    }
+
+    #[test]
+    fn from_fn_leaves_out_asymptotes() {
+        let hyperbola = Curve::from_fn(|x| 1. / x, -1.0..=1.0, 3);
+
+        assert_eq!(hyperbola.keypoints.len(), 2);
+        assert_eq!(hyperbola.start_point(), Some(Point2::new(-1., -1.)));
+    }
+}
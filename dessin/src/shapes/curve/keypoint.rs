@@ -109,6 +109,43 @@ impl Bezier {
         }
     }
 
+    /// Point at `t` (between 0 and 1) of the bezier, starting from `start`
+    pub fn point_at(&self, start: Point2<f32>, t: f32) -> Point2<f32> {
+        let u = 1. - t;
+        let p = start.coords * (u * u * u)
+            + self.start_control.coords * (3. * u * u * t)
+            + self.end_control.coords * (3. * u * t * t)
+            + self.end.coords * (t * t * t);
+
+        Point2::from(p)
+    }
+
+    /// Approximate the bezier by straight segments, no further than `tolerance` from the real curve.
+    ///
+    /// The returned points do not include `start`.
+    pub fn flatten(&self, start: Point2<f32>, tolerance: f32) -> Vec<Point2<f32>> {
+        // Bound of the second derivative, see "Piecewise Linear Approximation of Bézier Curves" by Roger Willcocks
+        let (p0, p1, p2, p3) = (
+            start.coords,
+            self.start_control.coords,
+            self.end_control.coords,
+            self.end.coords,
+        );
+        let dd = (p0 - 2. * p1 + p2)
+            .magnitude()
+            .max((p1 - 2. * p2 + p3).magnitude());
+
+        let segments = if tolerance > 0. {
+            ((0.75 * dd / tolerance).sqrt().ceil() as usize).clamp(1, 1000)
+        } else {
+            1000
+        };
+
+        (1..=segments)
+            .map(|idx| self.point_at(start, idx as f32 / segments as f32))
+            .collect()
+    }
+
     pub fn transform(&self, parent_transform: &Transform2<f32>) -> Self {
         Bezier {
             start: self.start.map(|v| parent_transform * v),
@@ -0,0 +1,470 @@
+use super::{Bezier, Curve, Keypoint, KeypointPosition};
+use crate::style::FillRule;
+use nalgebra::{Point2, Transform2, Vector2};
+
+/// Tolerance of the polylines approximating beziers, to measure and intersect them
+const TOLERANCE: f32 = 10e-4;
+
+/// Index of a segment, and parameter along it from 0 to 1
+type Location = (usize, f32);
+
+/// Piece of a curve between two points
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Segment {
+    Line(Point2<f32>, Point2<f32>),
+    Cubic([Point2<f32>; 4]),
+}
+impl Segment {
+    fn start(&self) -> Point2<f32> {
+        match self {
+            Segment::Line(start, _) => *start,
+            Segment::Cubic([start, ..]) => *start,
+        }
+    }
+
+    fn is_degenerate(&self) -> bool {
+        match self {
+            Segment::Line(start, end) => start == end,
+            Segment::Cubic(points) => points.iter().all(|p| *p == points[0]),
+        }
+    }
+
+    fn transform(&self, transform: &Transform2<f32>) -> Segment {
+        match self {
+            Segment::Line(start, end) => Segment::Line(transform * start, transform * end),
+            Segment::Cubic(points) => Segment::Cubic(points.map(|p| transform * p)),
+        }
+    }
+
+    /// Parts of the segment before and after `u`, by de Casteljau for beziers
+    fn split(&self, u: f32) -> (Segment, Segment) {
+        let lerp = |a: Point2<f32>, b: Point2<f32>| a + (b - a) * u;
+        match *self {
+            Segment::Line(start, end) => {
+                let middle = lerp(start, end);
+                (Segment::Line(start, middle), Segment::Line(middle, end))
+            }
+            Segment::Cubic([p0, p1, p2, p3]) => {
+                let (p01, p12, p23) = (lerp(p0, p1), lerp(p1, p2), lerp(p2, p3));
+                let (p012, p123) = (lerp(p01, p12), lerp(p12, p23));
+                let p = lerp(p012, p123);
+                (
+                    Segment::Cubic([p0, p01, p012, p]),
+                    Segment::Cubic([p, p123, p23, p3]),
+                )
+            }
+        }
+    }
+
+    /// Part of the segment between the parameters `from` and `to`
+    fn between(&self, from: f32, to: f32) -> Segment {
+        let (_, after) = self.split(from);
+        let to = if from < 1. {
+            (to - from) / (1. - from)
+        } else {
+            0.
+        };
+        after.split(to).0
+    }
+
+    /// Points along the segment, with their parameter, from its start to its end
+    fn polyline(&self) -> Vec<(f32, Point2<f32>)> {
+        match *self {
+            Segment::Line(start, end) => vec![(0., start), (1., end)],
+            Segment::Cubic([p0, p1, p2, p3]) => {
+                let points = Bezier::new(p1, p2, p3).flatten(p0, TOLERANCE);
+                let count = points.len() as f32;
+                std::iter::once((0., p0))
+                    .chain(
+                        points
+                            .into_iter()
+                            .enumerate()
+                            .map(|(idx, p)| ((idx + 1) as f32 / count, p)),
+                    )
+                    .collect()
+            }
+        }
+    }
+
+    fn length(&self) -> f32 {
+        self.polyline()
+            .windows(2)
+            .map(|w| (w[1].1 - w[0].1).magnitude())
+            .sum()
+    }
+
+    /// Parameter at `distance` along the segment
+    fn parameter_at(&self, distance: f32) -> f32 {
+        let mut walked = 0.;
+        for w in self.polyline().windows(2) {
+            let length = (w[1].1 - w[0].1).magnitude();
+            if length > 0. && walked + length >= distance {
+                return w[0].0 + (w[1].0 - w[0].0) * (distance - walked) / length;
+            }
+            walked += length;
+        }
+        1.
+    }
+}
+
+/// Segments of `curve`, in its own coordinates, nested curves being placed by their transform
+fn segments_of(curve: &Curve) -> Vec<Segment> {
+    let keypoints = curve.keypoints.iter().flat_map(|keypoint| match keypoint {
+        Keypoint::Point(p) => vec![KeypointPosition::Point(*p)],
+        Keypoint::Bezier(b) => vec![KeypointPosition::Bezier(b.clone())],
+        Keypoint::Curve(c) => c.position(&Transform2::identity()).keypoints,
+    });
+
+    let mut segments = vec![];
+    let mut first = None;
+    let mut current: Option<Point2<f32>> = None;
+    for keypoint in keypoints {
+        let (start, segment) = match keypoint {
+            KeypointPosition::Point(p) => (p, current.map(|current| Segment::Line(current, p))),
+            KeypointPosition::Bezier(b) => {
+                // Beziers with a start are joined to the previous point by a line, like when flattened
+                let start = match (b.start, current) {
+                    (Some(start), Some(current)) if start != current => {
+                        segments.push(Segment::Line(current, start));
+                        start
+                    }
+                    (Some(start), _) | (None, Some(start)) => start,
+                    (None, None) => continue,
+                };
+                (
+                    start,
+                    Some(Segment::Cubic([
+                        start,
+                        b.start_control,
+                        b.end_control,
+                        b.end,
+                    ])),
+                )
+            }
+        };
+
+        first.get_or_insert(start);
+        current = Some(match segment {
+            Some(Segment::Cubic([.., end])) => end,
+            _ => start,
+        });
+        segments.extend(segment);
+    }
+
+    if curve.closed {
+        if let (Some(first), Some(current)) = (first, current) {
+            if first != current {
+                segments.push(Segment::Line(current, first));
+            }
+        }
+    }
+
+    segments
+}
+
+/// Open curve following `segments`
+fn curve_of(segments: &[Segment], local_transform: Transform2<f32>) -> Curve {
+    let start = segments
+        .first()
+        .map(|segment| Keypoint::Point(segment.start()));
+    let keypoints = start
+        .into_iter()
+        .chain(segments.iter().map(|segment| match *segment {
+            Segment::Line(_, end) => Keypoint::Point(end),
+            Segment::Cubic([_, start_control, end_control, end]) => {
+                Keypoint::Bezier(Bezier::new(start_control, end_control, end))
+            }
+        }))
+        .collect();
+
+    Curve {
+        local_transform,
+        keypoints,
+        closed: false,
+        start_marker: None,
+        end_marker: None,
+        fill_rule: FillRule::NonZero,
+    }
+}
+
+/// Location at `t`, the fraction of the length of the segments
+fn locate(segments: &[Segment], t: f32) -> Location {
+    let lengths = segments.iter().map(Segment::length).collect::<Vec<_>>();
+    let mut distance = t.clamp(0., 1.) * lengths.iter().sum::<f32>();
+    for (idx, length) in lengths.iter().enumerate() {
+        if distance <= *length || idx + 1 == segments.len() {
+            let u = if *length > 0. {
+                segments[idx].parameter_at(distance)
+            } else {
+                0.
+            };
+            return (idx, u);
+        }
+        distance -= length;
+    }
+    (0, 0.)
+}
+
+/// Segments between `from` and `to`, without the degenerate ones
+fn between(segments: &[Segment], from: Location, to: Location) -> Vec<Segment> {
+    let pieces = if from.0 == to.0 {
+        vec![segments[from.0].between(from.1, to.1)]
+    } else {
+        std::iter::once(segments[from.0].between(from.1, 1.))
+            .chain(segments[from.0 + 1..to.0].iter().copied())
+            .chain(std::iter::once(segments[to.0].between(0., to.1)))
+            .collect()
+    };
+
+    pieces
+        .into_iter()
+        .filter(|segment| !segment.is_degenerate())
+        .collect()
+}
+
+/// Fraction along `a`-`b` where it crosses `c`-`d`
+fn crossing(a: Point2<f32>, b: Point2<f32>, c: Point2<f32>, d: Point2<f32>) -> Option<f32> {
+    let (r, s) = (b - a, d - c);
+    let denominator = r.perp(&s);
+    // Parallel segments
+    if denominator.abs() <= f32::EPSILON {
+        return None;
+    }
+
+    let ac = c - a;
+    let t = ac.perp(&s) / denominator;
+    let u = ac.perp(&r) / denominator;
+    ((0. ..=1.).contains(&t) && (0. ..=1.).contains(&u)).then_some(t)
+}
+
+/// Locations, in order, where `segments` cross `others`
+fn intersections(segments: &[Segment], others: &[Segment]) -> Vec<Location> {
+    let others = others
+        .iter()
+        .flat_map(|other| {
+            let polyline = other.polyline();
+            (1..polyline.len())
+                .map(|idx| (polyline[idx - 1].1, polyline[idx].1))
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    let mut found: Vec<(Location, Point2<f32>)> = vec![];
+    for (idx, segment) in segments.iter().enumerate() {
+        let polyline = segment.polyline();
+        for w in polyline.windows(2) {
+            let ((u0, a), (u1, b)) = (w[0], w[1]);
+            for (c, d) in &others {
+                if let Some(t) = crossing(a, b, *c, *d) {
+                    found.push(((idx, u0 + (u1 - u0) * t), a + (b - a) * t));
+                }
+            }
+        }
+    }
+
+    found.sort_by(|(a, _), (b, _)| a.0.cmp(&b.0).then(a.1.total_cmp(&b.1)));
+    // Crossings at the ends of pieces of the polylines are found twice
+    found.dedup_by(|(_, a), (_, b)| (*a - *b).magnitude() <= TOLERANCE);
+    found.into_iter().map(|(location, _)| location).collect()
+}
+
+impl Curve {
+    /// Length of the curve, in its own coordinates, closing segment included
+    ///
+    /// ```
+    /// # use dessin::prelude::*;
+    /// let line = Curve::from(dessin2!(Line(from = [0., 0.], to = [3., 4.])));
+    /// assert_eq!(line.length(), 5.);
+    /// ```
+    pub fn length(&self) -> f32 {
+        segments_of(self).iter().map(Segment::length).sum()
+    }
+
+    /// Start and end of the curve, in its own coordinates, each with the unit direction pointing out of the curve there
+    pub(crate) fn ends(&self) -> Option<[(Point2<f32>, Vector2<f32>); 2]> {
+        let points = segments_of(self)
+            .iter()
+            .flat_map(|segment| segment.polyline())
+            .map(|(_, p)| p)
+            .collect::<Vec<_>>();
+
+        fn end<'a>(
+            mut points: impl Iterator<Item = &'a Point2<f32>>,
+        ) -> Option<(Point2<f32>, Vector2<f32>)> {
+            let end = *points.next()?;
+            let inside = points.find(|p| **p != end)?;
+            Some((end, (end - inside).normalize()))
+        }
+
+        Some([end(points.iter())?, end(points.iter().rev())?])
+    }
+
+    /// Parts of the curve before and after `t`, the fraction of its length from its start, between 0 and 1.
+    ///
+    /// Parts are open curves, with the transform of the curve, and closed curves are split from their start.
+    ///
+    /// ```
+    /// # use dessin::prelude::*;
+    /// let line = Curve::from(dessin2!(Line(from = [0., 0.], to = [10., 0.])));
+    /// let (before, after) = line.split_at(0.25);
+    ///
+    /// assert_eq!(before.keypoints.last(), Some(&Keypoint::Point([2.5, 0.].into())));
+    /// assert_eq!(after.start_point(), Some([2.5, 0.].into()));
+    ///
+    /// // Only the first 10 % of the outline of a circle, to draw it progressively
+    /// let circle = Curve::from(dessin2!(Circle(radius = 10.)));
+    /// let drawn = circle.trim(0., 0.1);
+    /// assert!(drawn.keypoints.len() > 1);
+    /// ```
+    pub fn split_at(&self, t: f32) -> (Curve, Curve) {
+        let segments = segments_of(self);
+        if segments.is_empty() {
+            return (self.clone(), self.clone());
+        }
+
+        let location = locate(&segments, t);
+        let end = (segments.len() - 1, 1.);
+        (
+            curve_of(&between(&segments, (0, 0.), location), self.local_transform),
+            curve_of(&between(&segments, location, end), self.local_transform),
+        )
+    }
+
+    /// Part of the curve between `t0` and `t1`, fractions of its length from its start, between 0 and 1
+    pub fn trim(&self, t0: f32, t1: f32) -> Curve {
+        let segments = segments_of(self);
+        if segments.is_empty() {
+            return self.clone();
+        }
+
+        let (t0, t1) = (t0.min(t1), t0.max(t1));
+        let (from, to) = (locate(&segments, t0), locate(&segments, t1));
+        curve_of(&between(&segments, from, to), self.local_transform)
+    }
+
+    /// Parts of the curve between the points where it crosses `other`, in order, both being in the same coordinates.
+    ///
+    /// A closed curve is split in as many parts as crossings, the part over its start being the last one.
+    /// A curve crossing nothing is returned whole.
+    ///
+    /// ```
+    /// # use dessin::prelude::*;
+    /// let line = Curve::from(dessin2!(Line(from = [-20., 0.], to = [20., 0.])));
+    /// let circle = Curve::from(dessin2!(Circle(radius = 10.)));
+    ///
+    /// let parts = line.split_at_intersections(&circle);
+    /// assert_eq!(parts.len(), 3);
+    ///
+    /// // The circle is cut in its upper and lower halves
+    /// let halves = circle.split_at_intersections(&line);
+    /// assert_eq!(halves.len(), 2);
+    /// ```
+    pub fn split_at_intersections(&self, other: &Curve) -> Vec<Curve> {
+        let segments = segments_of(self);
+        // Other in the coordinates of this curve
+        let transform = self
+            .local_transform
+            .try_inverse()
+            .unwrap_or_else(Transform2::identity)
+            * other.local_transform;
+        let others = segments_of(other)
+            .iter()
+            .map(|segment| segment.transform(&transform))
+            .collect::<Vec<_>>();
+
+        let locations = intersections(&segments, &others);
+        if locations.is_empty() {
+            return vec![self.clone()];
+        }
+
+        let bounds = std::iter::once((0, 0.))
+            .chain(locations)
+            .chain(std::iter::once((segments.len() - 1, 1.)))
+            .collect::<Vec<_>>();
+        let mut parts = bounds
+            .windows(2)
+            .map(|w| between(&segments, w[0], w[1]))
+            .collect::<Vec<_>>();
+
+        // The part before the first crossing continues the last one around the start
+        if self.closed {
+            let first = parts.remove(0);
+            parts.last_mut().unwrap().extend(first);
+        }
+
+        // Crossings at the start of a closed curve are also found at its end
+        parts
+            .into_iter()
+            .filter(|part| part.iter().map(Segment::length).sum::<f32>() > TOLERANCE)
+            .map(|part| curve_of(&part, self.local_transform))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    const EPS: f32 = 10e-4;
+
+    #[test]
+    fn split_bezier_keeps_its_shape() {
+        let bezier = Bezier::new_with_start(
+            Point2::new(0., 0.),
+            Point2::new(0., 10.),
+            Point2::new(10., 10.),
+            Point2::new(10., 0.),
+        );
+        let curve = Curve::default().with_then(bezier.clone());
+
+        let (before, after) = curve.split_at(0.5);
+        let joined = before
+            .position(&Transform2::identity())
+            .flatten(0.01)
+            .into_iter()
+            .chain(after.position(&Transform2::identity()).flatten(0.01))
+            .collect::<Vec<_>>();
+
+        // Symmetric bezier, its middle is at half its length
+        assert!((joined[joined.len() / 2] - Point2::new(5., 7.5)).magnitude() < 0.1);
+        for p in joined {
+            let closest = (0..=1000)
+                .map(|idx| bezier.point_at(Point2::new(0., 0.), idx as f32 / 1000.))
+                .map(|q| (q - p).magnitude())
+                .fold(f32::MAX, f32::min);
+            assert!(closest < 0.02);
+        }
+    }
+
+    #[test]
+    fn trim_polyline() {
+        let curve = Curve::default()
+            .with_then(Point2::new(0., 0.))
+            .with_then(Point2::new(10., 0.))
+            .with_then(Point2::new(10., 10.));
+
+        let trimmed = curve.trim(0.75, 0.25);
+        let points = trimmed.position(&Transform2::identity()).flatten(0.01);
+        assert_eq!(points.len(), 3);
+        assert!((points[0] - Point2::new(5., 0.)).magnitude() < EPS);
+        assert!((points[1] - Point2::new(10., 0.)).magnitude() < EPS);
+        assert!((points[2] - Point2::new(10., 5.)).magnitude() < EPS);
+    }
+
+    #[test]
+    fn closed_curve_split_around_its_start() {
+        let square = Curve::from(dessin2!(Rectangle(width = 2., height = 2.)));
+        let line = Curve::default()
+            .with_then(Point2::new(0., -5.))
+            .with_then(Point2::new(0., 5.));
+
+        let halves = square.split_at_intersections(&line);
+        assert_eq!(halves.len(), 2);
+        for half in halves {
+            let points = half.position(&Transform2::identity()).flatten(0.01);
+            assert!((points[0].x).abs() < EPS);
+            assert!((points[points.len() - 1].x).abs() < EPS);
+        }
+    }
+}
@@ -1,7 +1,15 @@
+mod exif;
+
+pub use exif::*;
+
 use super::{BoundingBox, ShapeBoundingBox, UnParticular};
-use crate::shapes::{Shape, ShapeOp};
-use image::DynamicImage;
-use nalgebra::{Point2, Scale2, Transform2, Vector2};
+use crate::{
+    prelude::*,
+    shapes::{Shape, ShapeOp},
+};
+use ::image::{DynamicImage, ImageResult};
+use nalgebra::{Point2, Rotation2, Scale2, Transform2, Vector2};
+use std::path::{Path, PathBuf};
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct ImagePosition<'a> {
@@ -23,11 +31,66 @@ pub struct ImagePosition<'a> {
 pub struct Image {
     pub image: DynamicImage,
     pub local_transform: Transform2<f32>,
+    /// File decoded at export time instead of [`image`][Image::image], see [`from_path_lazy`][Image::from_path_lazy]
+    pub lazy_path: Option<PathBuf>,
 }
 impl Image {
+    /// Image decoded from the file at `path`, turned upright according to its [`ExifOrientation`]
+    ///
+    /// ```no_run
+    /// # use dessin::prelude::*;
+    /// let photo = Image::from_path("photos/0001.jpg").unwrap();
+    /// let photo = dessin2!({ photo }(keep_aspect_ratio, scale = [50., 50.]));
+    /// ```
+    pub fn from_path<P: AsRef<Path>>(path: P) -> ImageResult<Self> {
+        Ok(Image::default().with_image(open_upright(path)?))
+    }
+
+    /// Image whose pixels are only decoded when exported, and dropped right after.
+    ///
+    /// Useful for huge catalogs, where keeping every image in memory is too costly.
+    /// Pixels are turned upright according to the [`ExifOrientation`] of the file.
+    /// If the file is missing or can't be decoded, a placeholder box with the path as caption is exported instead.
+    ///
+    /// ```
+    /// # use dessin::prelude::*;
+    /// let image = dessin2!(Image(
+    /// 	lazy_path = "photos/0001.jpg",
+    /// 	keep_aspect_ratio,
+    /// 	scale = [50., 50.],
+    /// ));
+    /// ```
+    #[inline]
+    pub fn from_path_lazy<P: Into<PathBuf>>(path: P) -> Self {
+        Image::default().with_lazy_path(path)
+    }
+
+    #[inline]
+    pub fn lazy_path<P: Into<PathBuf>>(&mut self, path: P) -> &mut Self {
+        self.lazy_path = Some(path.into());
+        self
+    }
+    #[inline]
+    pub fn with_lazy_path<P: Into<PathBuf>>(mut self, path: P) -> Self {
+        self.lazy_path(path);
+        self
+    }
+
+    /// Size of the image, in pixels.
+    ///
+    /// Only the header of a [lazy][Image::from_path_lazy] image is read, a missing one being 1x1.
     #[inline]
     pub fn image_size_pixel(&self) -> (u32, u32) {
-        (self.image.width(), self.image.height())
+        match &self.lazy_path {
+            Some(path) => match ::image::image_dimensions(path) {
+                Ok((width, height)) if ExifOrientation::of_file(path).swaps_dimensions() => {
+                    (height, width)
+                }
+                Ok(size) => size,
+                Err(_) => (1, 1),
+            },
+            None => (self.image.width(), self.image.height()),
+        }
     }
 
     #[inline]
@@ -38,6 +101,7 @@ impl Image {
 
     pub fn image(&mut self, image: DynamicImage) -> &mut Self {
         self.image = image;
+        self.lazy_path = None;
         self
     }
     #[inline]
@@ -82,6 +146,49 @@ impl Image {
     }
 }
 
+/// Box crossed by its diagonals, with a caption, in place of an image that can't be loaded
+pub(crate) fn placeholder(position: &ImagePosition, caption: &str) -> Shape {
+    let ImagePosition {
+        top_left,
+        top_right,
+        bottom_right,
+        bottom_left,
+        center,
+        width,
+        height,
+        rotation,
+        ..
+    } = *position;
+
+    let stroke_width = width.min(height) / 100.;
+    let font_size = (height / 10.).min(width / (caption.chars().count().max(1) as f32 * 0.6));
+
+    let frame = Style::new(dessin2!([
+        Curve(
+            then = top_left,
+            then = top_right,
+            then = bottom_right,
+            then = bottom_left,
+            closed,
+        ),
+        Line(from = top_left, to = bottom_right),
+        Line(from = top_right, to = bottom_left),
+    ]))
+    .with_stroke((Color::GRAY, stroke_width));
+
+    let caption = dessin2!(Text!(
+        fill = Color::GRAY,
+        text = caption,
+        align = TextAlign::Center,
+        vertical_align = TextVerticalAlign::Center,
+        font_size = font_size,
+        rotate = Rotation2::new(rotation),
+        translate = center - Point2::origin(),
+    ));
+
+    dessin2!([{ frame }, { caption }])
+}
+
 impl From<Image> for Shape {
     #[inline]
     fn from(v: Image) -> Self {
@@ -0,0 +1,267 @@
+use ::image::{DynamicImage, ImageResult};
+use std::{fs::File, io::Read, path::Path};
+
+/// Bytes read at the start of a file to find its EXIF metadata, which JPEG limits to 64 KiB
+const EXIF_SEARCH_LENGTH: u64 = 128 * 1024;
+
+/// EXIF tag of the orientation
+const ORIENTATION_TAG: u16 = 0x0112;
+
+/// How the pixels of a photo are stored, relative to the way it should be seen, from its EXIF metadata.
+///
+/// Cameras store pixels the way the sensor reads them, and tell in which direction the camera was held.
+///
+/// ```
+/// # use dessin::prelude::*;
+/// let sideways = ::image::DynamicImage::new_rgb8(40, 30);
+///
+/// let upright = ExifOrientation::Rotate90.apply(sideways);
+/// assert_eq!((upright.width(), upright.height()), (30, 40));
+/// ```
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ExifOrientation {
+    /// Stored upright
+    #[default]
+    Normal,
+    /// Mirrored left to right
+    FlipHorizontal,
+    /// Upside down
+    Rotate180,
+    /// Mirrored top to bottom
+    FlipVertical,
+    /// Mirrored along the diagonal from the top left corner
+    Transpose,
+    /// Upright after a quarter turn clockwise
+    Rotate90,
+    /// Mirrored along the diagonal from the top right corner
+    Transverse,
+    /// Upright after a quarter turn counterclockwise
+    Rotate270,
+}
+impl ExifOrientation {
+    /// Orientation from the value of the EXIF tag, from 1 to 8, `Normal` for any other
+    pub fn from_exif(value: u16) -> Self {
+        match value {
+            2 => ExifOrientation::FlipHorizontal,
+            3 => ExifOrientation::Rotate180,
+            4 => ExifOrientation::FlipVertical,
+            5 => ExifOrientation::Transpose,
+            6 => ExifOrientation::Rotate90,
+            7 => ExifOrientation::Transverse,
+            8 => ExifOrientation::Rotate270,
+            _ => ExifOrientation::Normal,
+        }
+    }
+
+    /// Orientation of a JPEG or PNG file, `Normal` if it has none or can't be read
+    pub fn of_file<P: AsRef<Path>>(path: P) -> Self {
+        let mut bytes = vec![];
+        match File::open(path)
+            .and_then(|file| file.take(EXIF_SEARCH_LENGTH).read_to_end(&mut bytes))
+        {
+            Ok(_) => ExifOrientation::of_bytes(&bytes),
+            Err(_) => ExifOrientation::Normal,
+        }
+    }
+
+    /// Orientation of an encoded JPEG or PNG image, `Normal` if it has none
+    pub fn of_bytes(bytes: &[u8]) -> Self {
+        let tiff = if bytes.starts_with(&[0xFF, 0xD8]) {
+            jpeg_exif(bytes)
+        } else if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+            png_exif(bytes)
+        } else {
+            None
+        };
+
+        tiff.and_then(tiff_orientation)
+            .map(ExifOrientation::from_exif)
+            .unwrap_or_default()
+    }
+
+    /// Whether the width and height of the image are swapped when turned upright
+    pub fn swaps_dimensions(&self) -> bool {
+        matches!(
+            self,
+            ExifOrientation::Transpose
+                | ExifOrientation::Rotate90
+                | ExifOrientation::Transverse
+                | ExifOrientation::Rotate270
+        )
+    }
+
+    /// `image`, as stored, turned upright
+    pub fn apply(&self, image: DynamicImage) -> DynamicImage {
+        match self {
+            ExifOrientation::Normal => image,
+            ExifOrientation::FlipHorizontal => image.fliph(),
+            ExifOrientation::Rotate180 => image.rotate180(),
+            ExifOrientation::FlipVertical => image.flipv(),
+            ExifOrientation::Transpose => image.rotate90().fliph(),
+            ExifOrientation::Rotate90 => image.rotate90(),
+            ExifOrientation::Transverse => image.rotate270().fliph(),
+            ExifOrientation::Rotate270 => image.rotate270(),
+        }
+    }
+}
+
+/// Decode the image at `path`, turned upright according to its [`ExifOrientation`]
+///
+/// Use [`image::open`][::image::open] to keep the pixels as stored.
+pub fn open_upright<P: AsRef<Path>>(path: P) -> ImageResult<DynamicImage> {
+    let path = path.as_ref();
+    let image = ::image::open(path)?;
+    Ok(ExifOrientation::of_file(path).apply(image))
+}
+
+/// TIFF structure of the EXIF segment of a JPEG
+fn jpeg_exif(bytes: &[u8]) -> Option<&[u8]> {
+    let mut idx = 2;
+    while idx + 4 <= bytes.len() && bytes[idx] == 0xFF {
+        let marker = bytes[idx + 1];
+        // Start of the compressed data, there is no metadata after it
+        if marker == 0xDA || marker == 0xD9 {
+            return None;
+        }
+
+        let length = u16::from_be_bytes([bytes[idx + 2], bytes[idx + 3]]) as usize;
+        let segment = bytes.get(idx + 4..idx + 2 + length)?;
+        if marker == 0xE1 {
+            if let Some(tiff) = segment.strip_prefix(b"Exif\0\0") {
+                return Some(tiff);
+            }
+        }
+
+        idx += 2 + length;
+    }
+
+    None
+}
+
+/// TIFF structure of the `eXIf` chunk of a PNG
+fn png_exif(bytes: &[u8]) -> Option<&[u8]> {
+    let mut idx = 8;
+    while idx + 8 <= bytes.len() {
+        let length = u32::from_be_bytes(bytes[idx..idx + 4].try_into().ok()?) as usize;
+        let kind = &bytes[idx + 4..idx + 8];
+        match kind {
+            b"eXIf" => return bytes.get(idx + 8..idx + 8 + length),
+            // Metadata comes before the pixels
+            b"IDAT" | b"IEND" => return None,
+            _ => idx += 12 + length,
+        }
+    }
+
+    None
+}
+
+/// Value of the orientation tag in the first directory of a TIFF structure
+fn tiff_orientation(tiff: &[u8]) -> Option<u16> {
+    let little_endian = match tiff.get(..2)? {
+        b"II" => true,
+        b"MM" => false,
+        _ => return None,
+    };
+    let u16_at = |idx: usize| -> Option<u16> {
+        let bytes = tiff.get(idx..idx + 2)?.try_into().ok()?;
+        Some(if little_endian {
+            u16::from_le_bytes(bytes)
+        } else {
+            u16::from_be_bytes(bytes)
+        })
+    };
+    let u32_at = |idx: usize| -> Option<u32> {
+        let bytes = tiff.get(idx..idx + 4)?.try_into().ok()?;
+        Some(if little_endian {
+            u32::from_le_bytes(bytes)
+        } else {
+            u32::from_be_bytes(bytes)
+        })
+    };
+
+    let directory = u32_at(4)? as usize;
+    let count = u16_at(directory)? as usize;
+    (0..count)
+        .map(|entry| directory + 2 + entry * 12)
+        .find(|entry| u16_at(*entry) == Some(ORIENTATION_TAG))
+        .and_then(|entry| u16_at(entry + 8))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// JPEG header with an EXIF segment holding only an orientation
+    fn jpeg_with_orientation(orientation: u16, little_endian: bool) -> Vec<u8> {
+        let u16_bytes = |v: u16| {
+            if little_endian {
+                v.to_le_bytes()
+            } else {
+                v.to_be_bytes()
+            }
+        };
+        let u32_bytes = |v: u32| {
+            if little_endian {
+                v.to_le_bytes()
+            } else {
+                v.to_be_bytes()
+            }
+        };
+
+        let mut tiff = if little_endian {
+            b"II".to_vec()
+        } else {
+            b"MM".to_vec()
+        };
+        tiff.extend(u16_bytes(42));
+        tiff.extend(u32_bytes(8));
+        tiff.extend(u16_bytes(1));
+        tiff.extend(u16_bytes(ORIENTATION_TAG));
+        tiff.extend(u16_bytes(3));
+        tiff.extend(u32_bytes(1));
+        tiff.extend(u16_bytes(orientation));
+        tiff.extend([0, 0]);
+        tiff.extend(u32_bytes(0));
+
+        let mut jpeg = vec![0xFF, 0xD8, 0xFF, 0xE1];
+        jpeg.extend(((tiff.len() + 8) as u16).to_be_bytes());
+        jpeg.extend(b"Exif\0\0");
+        jpeg.extend(tiff);
+        jpeg.extend([0xFF, 0xDA]);
+        jpeg
+    }
+
+    #[test]
+    fn read_jpeg_orientation() {
+        assert_eq!(
+            ExifOrientation::of_bytes(&jpeg_with_orientation(6, true)),
+            ExifOrientation::Rotate90
+        );
+        assert_eq!(
+            ExifOrientation::of_bytes(&jpeg_with_orientation(8, false)),
+            ExifOrientation::Rotate270
+        );
+        assert_eq!(
+            ExifOrientation::of_bytes(&[0xFF, 0xD8, 0xFF, 0xDA]),
+            ExifOrientation::Normal
+        );
+    }
+
+    #[test]
+    fn turn_upright() {
+        // The top left pixel of a wide image ends at the top right, which is also the top left of a tall one
+        let mut image = ::image::RgbImage::new(2, 1);
+        image.put_pixel(0, 0, ::image::Rgb([255, 0, 0]));
+        let upright = ExifOrientation::Rotate90
+            .apply(DynamicImage::ImageRgb8(image))
+            .to_rgb8();
+
+        assert_eq!(upright.dimensions(), (1, 2));
+        assert_eq!(upright.get_pixel(0, 0), &::image::Rgb([255, 0, 0]));
+
+        let transposed = ExifOrientation::Transpose
+            .apply(DynamicImage::ImageRgb8(::image::RgbImage::new(2, 1)))
+            .to_rgb8();
+        assert_eq!(transposed.dimensions(), (1, 2));
+    }
+}
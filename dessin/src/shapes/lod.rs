@@ -0,0 +1,130 @@
+use crate::prelude::*;
+use nalgebra::Transform2;
+
+/// Alternative representations of a shape, picked at export by the size it is rendered at.
+///
+/// The rendered size is the largest side of the bounding box of the `detailed` representation,
+/// once transformed in the coordinates given to the [`Exporter`][crate::export::Exporter].
+/// Below the size of a simplified representation, the smallest matching one is used instead.
+///
+/// ```
+/// # use dessin::prelude::*;
+/// let tick = dessin2!(LevelOfDetail(
+/// 	detailed = dessin2!(Circle!(fill = Color::BLACK, radius = 1.)),
+/// 	// A square is enough when it covers a few pixels
+/// 	below = (4., dessin2!(Rectangle!(fill = Color::BLACK, width = 2., height = 2.))),
+/// 	// And nothing at all under a pixel
+/// 	below = (1., Shape::default()),
+/// ));
+/// ```
+#[derive(Default, Debug, Clone, Shape)]
+pub struct LevelOfDetail {
+    /// [`ShapeOp`]
+    #[local_transform]
+    pub local_transform: Transform2<f32>,
+
+    /// Full detail representation, used when no simplified one applies
+    #[shape(skip)]
+    pub detailed: Box<Shape>,
+
+    /// Simplified representations, with the rendered size under which each is used
+    #[shape(skip)]
+    pub simplified: Vec<(f32, Shape)>,
+}
+
+impl LevelOfDetail {
+    /// Full detail representation
+    #[inline]
+    pub fn detailed<S: Into<Shape>>(&mut self, shape: S) -> &mut Self {
+        *self.detailed = shape.into();
+        self
+    }
+    #[inline]
+    pub fn with_detailed<S: Into<Shape>>(mut self, shape: S) -> Self {
+        self.detailed(shape);
+        self
+    }
+
+    /// Use `shape` when rendered smaller than `size`
+    #[inline]
+    pub fn below<S: Into<Shape>>(&mut self, (size, shape): (f32, S)) -> &mut Self {
+        self.simplified.push((size, shape.into()));
+        self
+    }
+    #[inline]
+    pub fn with_below<S: Into<Shape>>(mut self, below: (f32, S)) -> Self {
+        self.below(below);
+        self
+    }
+
+    /// Largest side of the `detailed` representation, once rendered
+    pub fn rendered_size(&self, parent_transform: &Transform2<f32>) -> f32 {
+        let bb = self
+            .detailed
+            .global_bounding_box(&self.global_transform(parent_transform))
+            .straigthen();
+
+        bb.width().max(bb.height())
+    }
+
+    /// Representation to render, given the transform of the parent
+    pub fn pick(&self, parent_transform: &Transform2<f32>) -> &Shape {
+        let size = self.rendered_size(parent_transform);
+
+        self.simplified
+            .iter()
+            .filter(|(below, _)| size < *below)
+            .min_by(|(a, _), (b, _)| a.total_cmp(b))
+            .map(|(_, shape)| shape)
+            .unwrap_or(&self.detailed)
+    }
+}
+
+impl From<LevelOfDetail> for Shape {
+    #[inline]
+    fn from(v: LevelOfDetail) -> Self {
+        Shape::LevelOfDetail(v)
+    }
+}
+
+impl ShapeBoundingBox for LevelOfDetail {
+    fn local_bounding_box(&self) -> BoundingBox<UnParticular> {
+        self.detailed.global_bounding_box(&self.local_transform)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use nalgebra::{Scale2, Transform2};
+
+    fn tick() -> LevelOfDetail {
+        dessin2!(LevelOfDetail(
+            detailed = dessin2!(Circle(radius = 1.)),
+            below = (4., dessin2!(Rectangle(width = 2., height = 2.))),
+            below = (1., Shape::default()),
+        ))
+    }
+
+    #[test]
+    fn pick_by_rendered_size() {
+        let tick = tick();
+
+        let at = |scale: f32| tick.pick(&nalgebra::convert(Scale2::new(scale, scale)));
+
+        assert!(matches!(at(10.), Shape::Ellipse(_)));
+        assert!(matches!(at(1.), Shape::Curve(_)));
+        assert!(matches!(at(0.1), Shape::Group(Group { shapes, .. }) if shapes.is_empty()));
+    }
+
+    #[test]
+    fn local_transform_counts() {
+        let tick = tick().with_resize(Scale2::new(0.1, 0.1));
+
+        assert!((tick.rendered_size(&Transform2::identity()) - 0.2).abs() < 10e-6);
+        assert!(matches!(
+            dessin2!({ tick } > (scale = [10., 10.])).normalize(),
+            Shape::Curve(_)
+        ));
+    }
+}
@@ -0,0 +1,339 @@
+use super::{Group, KeypointPosition, Shape, ShapeOp};
+use crate::style::Stroke;
+use nalgebra::{Point2, Transform2};
+
+impl Shape {
+    /// Canonical representation of a dessin.
+    ///
+    /// Two dessins that render the same way usually have the same normalized representation:
+    /// - transforms are pushed down to the basic shapes, so every [`Group`] has an identity transform,
+    /// - groups without metadata are merged in their parent,
+    /// - [`Dynamic`][Shape::Dynamic] shapes are evaluated,
+    /// - [`LevelOfDetail`][Shape::LevelOfDetail] representations are picked for their rendered size,
+    /// - strokes and filters are scaled by the transform they would be exported with,
+    /// - metadata are sorted.
+    ///
+    /// ```
+    /// # use dessin::prelude::*;
+    /// let a = dessin2!([Circle(radius = 1.)] > (translate = [1., 0.]));
+    /// let b = dessin2!([[Circle(radius = 1., translate = [1., 0.])]]);
+    ///
+    /// assert!(a.semantic_eq(&b, 10e-6));
+    /// ```
+    pub fn normalize(&self) -> Shape {
+        let mut shapes = vec![];
+        self.normalize_into(&Transform2::identity(), &mut shapes);
+
+        match shapes.len() {
+            1 => shapes.pop().unwrap(),
+            _ => Shape::Group(Group {
+                local_transform: Transform2::identity(),
+                shapes,
+                metadata: vec![],
+            }),
+        }
+    }
+
+    fn normalize_into(&self, parent_transform: &Transform2<f32>, acc: &mut Vec<Shape>) {
+        match self {
+            Shape::Group(Group {
+                local_transform,
+                shapes,
+                metadata,
+            }) => {
+                let transform = parent_transform * local_transform;
+
+                if metadata.is_empty() {
+                    for shape in shapes {
+                        shape.normalize_into(&transform, acc);
+                    }
+                } else {
+                    let mut children = vec![];
+                    for shape in shapes {
+                        shape.normalize_into(&transform, &mut children);
+                    }
+
+                    let mut metadata = metadata.clone();
+                    metadata.sort();
+
+                    acc.push(Shape::Group(Group {
+                        local_transform: Transform2::identity(),
+                        shapes: children,
+                        metadata,
+                    }));
+                }
+            }
+            Shape::Style {
+                fill,
+                stroke,
+                stroke_alignment,
+                filter,
+                opacity,
+                blend_mode,
+                shape,
+            } => {
+                let mut children = vec![];
+                shape.normalize_into(parent_transform, &mut children);
+
+                let shape = match children.len() {
+                    1 => children.pop().unwrap(),
+                    _ => Shape::Group(Group {
+                        local_transform: Transform2::identity(),
+                        shapes: children,
+                        metadata: vec![],
+                    }),
+                };
+
+                acc.push(Shape::Style {
+                    fill: fill.clone().map(|v| *parent_transform * v),
+                    stroke: stroke.map(|v| *parent_transform * v),
+                    stroke_alignment: *stroke_alignment,
+                    filter: filter.map(|v| *parent_transform * v),
+                    opacity: *opacity,
+                    blend_mode: *blend_mode,
+                    shape: Box::new(shape),
+                });
+            }
+            Shape::Dynamic {
+                local_transform,
+                shaper,
+            } => {
+                shaper().normalize_into(&(parent_transform * local_transform), acc);
+            }
+            Shape::LevelOfDetail(lod) => {
+                lod.pick(parent_transform)
+                    .normalize_into(&lod.global_transform(parent_transform), acc);
+            }
+            leaf => {
+                let mut leaf = leaf.clone();
+                leaf.transform(*parent_transform);
+                acc.push(leaf);
+            }
+        }
+    }
+
+    /// Whether two dessins render the same way, comparing their [normalized][Shape::normalize] representation.
+    ///
+    /// Positions and sizes are compared within `epsilon`.
+    pub fn semantic_eq(&self, other: &Shape, epsilon: f32) -> bool {
+        self.normalize()._semantic_eq(&other.normalize(), epsilon)
+    }
+
+    fn _semantic_eq(&self, other: &Shape, epsilon: f32) -> bool {
+        let near = |a: f32, b: f32| (a - b).abs() <= epsilon;
+        let near_transform = |a: &Transform2<f32>, b: &Transform2<f32>| {
+            a.matrix()
+                .iter()
+                .zip(b.matrix().iter())
+                .all(|(a, b)| near(*a, *b))
+        };
+        let ends = |stroke: &Option<Stroke>| stroke.map(|stroke| (stroke.cap(), stroke.join()));
+        let near_stroke = |a: &Option<Stroke>, b: &Option<Stroke>| match (a, b) {
+            _ if ends(a) != ends(b) => false,
+            (None, None) => true,
+            (
+                Some(Stroke::Full { color, width, .. }),
+                Some(Stroke::Full {
+                    color: other_color,
+                    width: other_width,
+                    ..
+                }),
+            ) => color == other_color && near(*width, *other_width),
+            (
+                Some(Stroke::Dashed {
+                    color,
+                    width,
+                    on,
+                    off,
+                    ..
+                }),
+                Some(Stroke::Dashed {
+                    color: other_color,
+                    width: other_width,
+                    on: other_on,
+                    off: other_off,
+                    ..
+                }),
+            ) => {
+                color == other_color
+                    && near(*width, *other_width)
+                    && near(*on, *other_on)
+                    && near(*off, *other_off)
+            }
+            (
+                Some(Stroke::DashPattern {
+                    color,
+                    width,
+                    dashes,
+                    offset,
+                    ..
+                }),
+                Some(Stroke::DashPattern {
+                    color: other_color,
+                    width: other_width,
+                    dashes: other_dashes,
+                    offset: other_offset,
+                    ..
+                }),
+            ) => {
+                color == other_color
+                    && near(*width, *other_width)
+                    && dashes.as_slice().len() == other_dashes.as_slice().len()
+                    && dashes
+                        .as_slice()
+                        .iter()
+                        .zip(other_dashes.as_slice())
+                        .all(|(a, b)| near(*a, *b))
+                    && near(*offset, *other_offset)
+            }
+            _ => false,
+        };
+
+        match (self, other) {
+            (Shape::Group(a), Shape::Group(b)) => {
+                near_transform(&a.local_transform, &b.local_transform)
+                    && a.metadata == b.metadata
+                    && a.shapes.len() == b.shapes.len()
+                    && a.shapes
+                        .iter()
+                        .zip(&b.shapes)
+                        .all(|(a, b)| a._semantic_eq(b, epsilon))
+            }
+            (
+                Shape::Style {
+                    fill,
+                    stroke,
+                    stroke_alignment,
+                    filter,
+                    opacity,
+                    blend_mode,
+                    shape,
+                },
+                Shape::Style {
+                    fill: other_fill,
+                    stroke: other_stroke,
+                    stroke_alignment: other_stroke_alignment,
+                    filter: other_filter,
+                    opacity: other_opacity,
+                    blend_mode: other_blend_mode,
+                    shape: other_shape,
+                },
+            ) => {
+                fill == other_fill
+                    && near_stroke(stroke, other_stroke)
+                    && stroke_alignment == other_stroke_alignment
+                    && filter == other_filter
+                    && near(*opacity, *other_opacity)
+                    && blend_mode == other_blend_mode
+                    && shape._semantic_eq(other_shape, epsilon)
+            }
+            (Shape::Ellipse(a), Shape::Ellipse(b)) => {
+                let identity = Transform2::identity();
+                let (a, b) = (a.position(&identity), b.position(&identity));
+                let is_circle = near(a.semi_major_axis, a.semi_minor_axis);
+
+                near(a.center.x, b.center.x)
+                    && near(a.center.y, b.center.y)
+                    && near(a.semi_major_axis, b.semi_major_axis)
+                    && near(a.semi_minor_axis, b.semi_minor_axis)
+                    && (is_circle || near(a.rotation, b.rotation))
+            }
+            (Shape::Image(a), Shape::Image(b)) => {
+                near_transform(&a.local_transform, &b.local_transform)
+                    && a.image == b.image
+                    && a.lazy_path == b.lazy_path
+            }
+            (Shape::Text(a), Shape::Text(b)) => {
+                near_transform(&a.local_transform, &b.local_transform)
+                    && a.text == b.text
+                    && a.align == b.align
+                    && a.vertical_align == b.vertical_align
+                    && a.font_weight == b.font_weight
+                    && a.font == b.font
+                    && near(a.font_size, b.font_size)
+                    && a.on_curve.is_some() == b.on_curve.is_some()
+            }
+            (Shape::Curve(a), Shape::Curve(b)) => {
+                let identity = Transform2::identity();
+                let near_point =
+                    |a: &Point2<f32>, b: &Point2<f32>| near(a.x, b.x) && near(a.y, b.y);
+                let (a, b) = (a.position(&identity), b.position(&identity));
+
+                a.closed == b.closed
+                    && a.keypoints.len() == b.keypoints.len()
+                    && a.keypoints.iter().zip(&b.keypoints).all(|k| match k {
+                        (KeypointPosition::Point(a), KeypointPosition::Point(b)) => {
+                            near_point(a, b)
+                        }
+                        (KeypointPosition::Bezier(a), KeypointPosition::Bezier(b)) => {
+                            let same_start = match (&a.start, &b.start) {
+                                (None, None) => true,
+                                (Some(a), Some(b)) => near_point(a, b),
+                                _ => false,
+                            };
+
+                            same_start
+                                && near_point(&a.start_control, &b.start_control)
+                                && near_point(&a.end_control, &b.end_control)
+                                && near_point(&a.end, &b.end)
+                        }
+                        _ => false,
+                    })
+            }
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    #[test]
+    fn transforms_are_pushed_down() {
+        let dessin = dessin2!(
+            [
+                Circle!(stroke = (Color::BLACK, 1.), radius = 1.),
+                Line(from = [0., 0.], to = [1., 0.]),
+            ] > (scale = [2., 2.])
+        );
+
+        let normalized = dessin.normalize();
+        let Shape::Group(group) = &normalized else {
+            panic!("Expected a group, got {normalized:?}");
+        };
+
+        assert_eq!(group.local_transform, Default::default());
+        assert_eq!(group.shapes.len(), 2);
+
+        let Shape::Style { stroke, .. } = &group.shapes[0] else {
+            panic!("Expected a style, got {:?}", group.shapes[0]);
+        };
+        let Some(Stroke::Full { width, .. }) = stroke else {
+            panic!("Expected a full stroke, got {stroke:?}");
+        };
+        assert!((width - 2.).abs() < 10e-6);
+    }
+
+    #[test]
+    fn metadata_are_sorted() {
+        let mut a = dessin2!([Circle(radius = 1.)]);
+        a.add_metadata(("b", "2"));
+        a.add_metadata(("a", "1"));
+
+        let mut b = dessin2!([Circle(radius = 1.)]);
+        b.add_metadata(("a", "1"));
+        b.add_metadata(("b", "2"));
+
+        assert!(a.semantic_eq(&b, 10e-6));
+    }
+
+    #[test]
+    fn different_dessins() {
+        let a = dessin2!(Circle(radius = 1.) > ());
+        let b = dessin2!(Circle(radius = 1.1) > ());
+
+        assert!(!a.semantic_eq(&b, 10e-6));
+        assert!(a.semantic_eq(&b, 0.2));
+    }
+}
@@ -82,6 +82,64 @@ impl Font {
             Font::OTF(b) | Font::TTF(b) => b.as_slice(),
         }
     }
+
+    /// What the license of the font allows when embedding it, from the `fsType` of its OS/2 table.
+    ///
+    /// `None` for fonts without an OS/2 table, or which can't be read.
+    ///
+    /// ```
+    /// # use dessin::font::*;
+    /// let font = FontGroup::hyperlegible().regular;
+    /// assert_eq!(font.embedding_permission(), Some(EmbeddingPermission::Installable));
+    /// ```
+    pub fn embedding_permission(&self) -> Option<EmbeddingPermission> {
+        let bytes = self.as_bytes();
+        let u16_at = |offset: usize| -> Option<u16> {
+            Some(u16::from_be_bytes(
+                bytes.get(offset..offset + 2)?.try_into().ok()?,
+            ))
+        };
+        let u32_at = |offset: usize| -> Option<u32> {
+            Some(u32::from_be_bytes(
+                bytes.get(offset..offset + 4)?.try_into().ok()?,
+            ))
+        };
+
+        // Table records follow the 12 bytes of the header, 16 bytes each
+        let table_count = u16_at(4)? as usize;
+        let os2 = (0..table_count)
+            .map(|idx| 12 + idx * 16)
+            .find(|record| bytes.get(*record..*record + 4) == Some(b"OS/2".as_slice()))?;
+        let fs_type = u16_at(u32_at(os2 + 8)? as usize + 8)?;
+
+        // Only bitmaps can be embedded, but fonts are embedded as outlines
+        if fs_type & 0x0200 != 0 {
+            return Some(EmbeddingPermission::Restricted);
+        }
+        // The least restrictive permission applies
+        Some(if fs_type & 0x000F == 0 {
+            EmbeddingPermission::Installable
+        } else if fs_type & 0x0008 != 0 {
+            EmbeddingPermission::Editable
+        } else if fs_type & 0x0004 != 0 {
+            EmbeddingPermission::PreviewAndPrint
+        } else {
+            EmbeddingPermission::Restricted
+        })
+    }
+}
+
+/// What the license of a [`Font`] allows when embedding it in a document
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmbeddingPermission {
+    /// Embedded freely, and even installed from the document
+    Installable,
+    /// Embedded in documents which may be edited
+    Editable,
+    /// Embedded in documents which are only viewed and printed
+    PreviewAndPrint,
+    /// Not embedded without the permission of the owner of the font
+    Restricted,
 }
 
 #[derive(Clone)]
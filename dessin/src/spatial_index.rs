@@ -0,0 +1,202 @@
+//! Find what is drawn in an area, without going through everything.
+//!
+//! A [`SpatialIndex`] is an R-tree of bounding boxes, built once, for hit-testing, cropping or
+//! finding the labels colliding with a new one.
+//! Queries return indices, in the order the boxes were given, which is the painting order for a [`DisplayList`].
+//!
+//! ```
+//! # use dessin::prelude::*;
+//! use dessin::{display_list::DisplayList, spatial_index::SpatialIndex};
+//!
+//! let dessin = dessin2!(for x in 0..100 {
+//! 	dessin2!(Circle!(fill = Color::RED, radius = 1., translate = [x as f32 * 3., 0.]))
+//! });
+//!
+//! let list = DisplayList::new(&dessin);
+//! let index = SpatialIndex::new(&list);
+//!
+//! // Hit-testing, the topmost item is the last one
+//! assert_eq!(index.query_point([30.5, 0.]), vec![10]);
+//! assert_eq!(index.query_point([31.5, 0.]), vec![]);
+//!
+//! assert_eq!(index.query_rect(&BoundingBox::mins_maxs(4.5, -1., 10., 1.)), vec![2, 3]);
+//! ```
+
+use crate::{display_list::DisplayList, prelude::*};
+use nalgebra::Point2;
+
+/// Most children of a node
+const NODE_CAPACITY: usize = 8;
+
+#[derive(Debug, Clone, PartialEq)]
+struct Node {
+    bounding_box: BoundingBox<Straight>,
+    /// Indices of the child nodes, or of the boxes for leaves
+    children: Vec<usize>,
+    leaf: bool,
+}
+
+/// R-tree of bounding boxes, bulk loaded by sort-tile-recursive
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SpatialIndex {
+    boxes: Vec<BoundingBox<Straight>>,
+    nodes: Vec<Node>,
+    root: Option<usize>,
+}
+impl SpatialIndex {
+    /// Index of the [bounding boxes][crate::display_list::DisplayItem::bounding_box] of the items of `list`
+    pub fn new(list: &DisplayList) -> Self {
+        SpatialIndex::from_boxes(list.iter().map(|item| item.bounding_box()))
+    }
+
+    /// Index of `boxes`, queries returning their position in it
+    pub fn from_boxes<I: IntoIterator<Item = BoundingBox<Straight>>>(boxes: I) -> Self {
+        let boxes = boxes.into_iter().collect::<Vec<_>>();
+        let mut nodes = vec![];
+
+        let mut level = pack(
+            (0..boxes.len()).map(|idx| (idx, boxes[idx])).collect(),
+            true,
+            &mut nodes,
+        );
+        while level.len() > 1 {
+            let entries = level
+                .into_iter()
+                .map(|idx| (idx, nodes[idx].bounding_box))
+                .collect();
+            level = pack(entries, false, &mut nodes);
+        }
+
+        SpatialIndex {
+            boxes,
+            nodes,
+            root: level.first().copied(),
+        }
+    }
+
+    /// Number of boxes
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.boxes.len()
+    }
+
+    /// Whether there is no box
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.boxes.is_empty()
+    }
+
+    /// Box at `idx`
+    #[inline]
+    pub fn bounding_box(&self, idx: usize) -> Option<&BoundingBox<Straight>> {
+        self.boxes.get(idx)
+    }
+
+    /// Boxes touching `rect`, in order
+    pub fn query_rect(&self, rect: &BoundingBox<Straight>) -> Vec<usize> {
+        let mut found = vec![];
+        let mut stack = self.root.into_iter().collect::<Vec<_>>();
+        while let Some(node) = stack.pop() {
+            let node = &self.nodes[node];
+            if !overlaps(&node.bounding_box, rect) {
+                continue;
+            }
+
+            if node.leaf {
+                found.extend(
+                    node.children
+                        .iter()
+                        .filter(|idx| overlaps(&self.boxes[**idx], rect)),
+                );
+            } else {
+                stack.extend(&node.children);
+            }
+        }
+
+        found.sort_unstable();
+        found
+    }
+
+    /// Boxes containing `point`, in order
+    #[inline]
+    pub fn query_point<P: Into<Point2<f32>>>(&self, point: P) -> Vec<usize> {
+        self.query_rect(&BoundingBox::at(point))
+    }
+}
+
+/// Whether `a` and `b` touch
+fn overlaps(a: &BoundingBox<Straight>, b: &BoundingBox<Straight>) -> bool {
+    a.left() <= b.right() && b.left() <= a.right() && a.bottom() <= b.top() && b.bottom() <= a.top()
+}
+
+/// Group `entries` into nodes of up to [`NODE_CAPACITY`] close ones, returning the new nodes
+fn pack(
+    mut entries: Vec<(usize, BoundingBox<Straight>)>,
+    leaf: bool,
+    nodes: &mut Vec<Node>,
+) -> Vec<usize> {
+    // Vertical slices of about the same number of nodes, each cut in nodes from bottom to top
+    let node_count = entries.len().div_ceil(NODE_CAPACITY);
+    let slice_count = (node_count as f32).sqrt().ceil() as usize;
+    let slice_len = slice_count.max(1) * NODE_CAPACITY;
+
+    entries.sort_by(|(_, a), (_, b)| a.center().x.total_cmp(&b.center().x));
+    let mut packed = vec![];
+    for slice in entries.chunks_mut(slice_len) {
+        slice.sort_by(|(_, a), (_, b)| a.center().y.total_cmp(&b.center().y));
+        for chunk in slice.chunks(NODE_CAPACITY) {
+            let bounding_box = chunk
+                .iter()
+                .map(|(_, bb)| *bb)
+                .reduce(BoundingBox::join)
+                .unwrap_or_else(BoundingBox::zero);
+
+            packed.push(nodes.len());
+            nodes.push(Node {
+                bounding_box,
+                children: chunk.iter().map(|(idx, _)| *idx).collect(),
+                leaf,
+            });
+        }
+    }
+
+    packed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_as_linear_scan() {
+        // Boxes of various sizes, overlapping each other
+        let boxes = (0..500)
+            .map(|idx| {
+                let (x, y) = ((idx * 37 % 101) as f32, (idx * 53 % 97) as f32);
+                let size = (idx % 7) as f32;
+                BoundingBox::mins_maxs(x, y, x + size, y + size / 2.)
+            })
+            .collect::<Vec<_>>();
+        let index = SpatialIndex::from_boxes(boxes.clone());
+        assert_eq!(index.len(), 500);
+
+        for rect in [
+            BoundingBox::mins_maxs(10., 10., 30., 20.),
+            BoundingBox::mins_maxs(-5., -5., 0., 0.),
+            BoundingBox::mins_maxs(0., 0., 200., 200.),
+            BoundingBox::at([50., 50.]),
+        ] {
+            let expected = (0..boxes.len())
+                .filter(|idx| overlaps(&boxes[*idx], &rect))
+                .collect::<Vec<_>>();
+            assert_eq!(index.query_rect(&rect), expected);
+        }
+    }
+
+    #[test]
+    fn empty() {
+        let index = SpatialIndex::from_boxes([]);
+        assert!(index.is_empty());
+        assert_eq!(index.query_point([0., 0.]), vec![]);
+    }
+}
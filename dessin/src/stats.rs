@@ -0,0 +1,313 @@
+//! Measure the complexity of a dessin.
+//!
+//! [`Shape::stats`] counts what a dessin is made of, and roughly estimates the size of its export.
+//! [`Shape::check_budget`] rejects dessins exceeding a [`Budget`],
+//! which is useful when dessins are generated from user inputs.
+//!
+//! ```
+//! # use dessin::prelude::*;
+//! use dessin::stats::{Budget, BudgetLimit};
+//!
+//! let dessin = dessin2!([
+//! 	Circle!(fill = Color::RED, radius = 10.),
+//! 	Rectangle!(stroke = (Color::BLACK, 0.5), width = 10., height = 5.),
+//! ]);
+//!
+//! let stats = dessin.stats();
+//! assert_eq!(stats.ellipses, 1);
+//! assert_eq!(stats.curves, 1);
+//! assert_eq!(stats.keypoints, 4);
+//!
+//! let budget = Budget {
+//! 	max_keypoints: Some(3),
+//! 	..Default::default()
+//! };
+//! let exceeded = dessin.check_budget(&budget).unwrap_err();
+//! assert_eq!(exceeded.limit, BudgetLimit::Keypoints);
+//! ```
+
+use crate::prelude::*;
+use std::fmt;
+
+/// What a dessin is made of
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ShapeStats {
+    /// Number of [`Group`][Shape::Group]
+    pub groups: usize,
+    /// Number of [`Style`][Shape::Style]
+    pub styles: usize,
+    /// Number of [`Ellipse`][Shape::Ellipse]
+    pub ellipses: usize,
+    /// Number of [`Image`][Shape::Image]
+    pub images: usize,
+    /// Number of [`Text`][Shape::Text]
+    pub texts: usize,
+    /// Number of [`Curve`][Shape::Curve], including curves nested in keypoints and texts on curve
+    pub curves: usize,
+    /// Number of [`LevelOfDetail`][Shape::LevelOfDetail]
+    pub levels_of_detail: usize,
+    /// Number of [`Dynamic`][Shape::Dynamic]
+    pub dynamics: usize,
+    /// Points and beziers of every curve
+    pub keypoints: usize,
+    /// Characters of every text
+    pub characters: usize,
+    /// Pixels of every image, lazy images excepted as they are not decoded yet
+    pub image_pixels: usize,
+    /// Largest number of nested shapes, a lonely basic shape having a depth of 1
+    pub depth: usize,
+}
+
+impl ShapeStats {
+    /// Number of shapes, of any kind
+    pub fn shapes(&self) -> usize {
+        self.groups
+            + self.styles
+            + self.ellipses
+            + self.images
+            + self.texts
+            + self.curves
+            + self.levels_of_detail
+            + self.dynamics
+    }
+
+    /// Rough estimation of the size of the SVG export, in bytes.
+    ///
+    /// Images are counted as base64 encoded raw pixels, which is an upper bound.
+    pub fn estimated_svg_size(&self) -> usize {
+        200 + self.groups * 20
+            + self.styles * 60
+            + self.ellipses * 80
+            + self.texts * 150
+            + self.characters
+            + self.curves * 40
+            + self.keypoints * 30
+            + self.images * 100
+            + self.image_pixels * 4 * 4 / 3
+    }
+
+    /// Rough estimation of the size of the PDF export, in bytes.
+    ///
+    /// Embedded fonts are not counted, and images are counted as raw pixels.
+    pub fn estimated_pdf_size(&self) -> usize {
+        1000 + self.styles * 60
+            + self.ellipses * 150
+            + self.texts * 80
+            + self.characters
+            + self.curves * 20
+            + self.keypoints * 30
+            + self.images * 300
+            + self.image_pixels * 3
+    }
+
+    fn visit(&mut self, shape: &Shape, depth: usize) {
+        self.depth = self.depth.max(depth);
+
+        match shape {
+            Shape::Group(Group { shapes, .. }) => {
+                self.groups += 1;
+                for shape in shapes {
+                    self.visit(shape, depth + 1);
+                }
+            }
+            Shape::Style { shape, .. } => {
+                self.styles += 1;
+                self.visit(shape, depth + 1);
+            }
+            Shape::Ellipse(_) => self.ellipses += 1,
+            Shape::Image(Image {
+                image, lazy_path, ..
+            }) => {
+                self.images += 1;
+                if lazy_path.is_none() {
+                    self.image_pixels += image.width() as usize * image.height() as usize;
+                }
+            }
+            Shape::Text(Text { text, on_curve, .. }) => {
+                self.texts += 1;
+                self.characters += text.chars().count();
+                if let Some(curve) = on_curve {
+                    self.visit_curve(curve);
+                }
+            }
+            Shape::Curve(curve) => self.visit_curve(curve),
+            // Only the detailed representation, the worst case
+            Shape::LevelOfDetail(lod) => {
+                self.levels_of_detail += 1;
+                self.visit(&lod.detailed, depth + 1);
+            }
+            Shape::Dynamic { shaper, .. } => {
+                self.dynamics += 1;
+                self.visit(&shaper(), depth + 1);
+            }
+        }
+    }
+
+    fn visit_curve(&mut self, curve: &Curve) {
+        self.curves += 1;
+        for keypoint in &curve.keypoints {
+            match keypoint {
+                Keypoint::Point(_) | Keypoint::Bezier(_) => self.keypoints += 1,
+                Keypoint::Curve(curve) => self.visit_curve(curve),
+            }
+        }
+    }
+}
+
+/// Limits of a dessin. `None` means unlimited.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Budget {
+    /// Maximum number of shapes, see [`ShapeStats::shapes`]
+    pub max_shapes: Option<usize>,
+    /// Maximum number of keypoints
+    pub max_keypoints: Option<usize>,
+    /// Maximum number of characters
+    pub max_characters: Option<usize>,
+    /// Maximum number of pixels of images
+    pub max_image_pixels: Option<usize>,
+    /// Maximum depth
+    pub max_depth: Option<usize>,
+    /// Maximum estimated size of the SVG export, in bytes
+    pub max_svg_size: Option<usize>,
+    /// Maximum estimated size of the PDF export, in bytes
+    pub max_pdf_size: Option<usize>,
+}
+
+/// Limit of a [`Budget`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BudgetLimit {
+    /// [`Budget::max_shapes`]
+    Shapes,
+    /// [`Budget::max_keypoints`]
+    Keypoints,
+    /// [`Budget::max_characters`]
+    Characters,
+    /// [`Budget::max_image_pixels`]
+    ImagePixels,
+    /// [`Budget::max_depth`]
+    Depth,
+    /// [`Budget::max_svg_size`]
+    SvgSize,
+    /// [`Budget::max_pdf_size`]
+    PdfSize,
+}
+
+/// A dessin is over its [`Budget`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BudgetExceeded {
+    /// First limit exceeded
+    pub limit: BudgetLimit,
+    /// Value of the dessin
+    pub value: usize,
+    /// Maximum allowed by the budget
+    pub max: usize,
+}
+impl fmt::Display for BudgetExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:?} of the dessin is {}, above the budget of {}",
+            self.limit, self.value, self.max
+        )
+    }
+}
+impl std::error::Error for BudgetExceeded {}
+
+impl Budget {
+    /// Check `stats` against this budget
+    pub fn check(&self, stats: &ShapeStats) -> Result<(), BudgetExceeded> {
+        [
+            (BudgetLimit::Shapes, self.max_shapes, stats.shapes()),
+            (BudgetLimit::Keypoints, self.max_keypoints, stats.keypoints),
+            (
+                BudgetLimit::Characters,
+                self.max_characters,
+                stats.characters,
+            ),
+            (
+                BudgetLimit::ImagePixels,
+                self.max_image_pixels,
+                stats.image_pixels,
+            ),
+            (BudgetLimit::Depth, self.max_depth, stats.depth),
+            (
+                BudgetLimit::SvgSize,
+                self.max_svg_size,
+                stats.estimated_svg_size(),
+            ),
+            (
+                BudgetLimit::PdfSize,
+                self.max_pdf_size,
+                stats.estimated_pdf_size(),
+            ),
+        ]
+        .into_iter()
+        .find_map(|(limit, max, value)| match max {
+            Some(max) if value > max => Some(BudgetExceeded { limit, value, max }),
+            _ => None,
+        })
+        .map_or(Ok(()), Err)
+    }
+}
+
+impl Shape {
+    /// Count what this dessin is made of.
+    ///
+    /// [`Dynamic`][Shape::Dynamic] shapes are evaluated, and only the detailed representation
+    /// of [`LevelOfDetail`] is counted.
+    pub fn stats(&self) -> ShapeStats {
+        let mut stats = ShapeStats::default();
+        stats.visit(self, 1);
+        stats
+    }
+
+    /// [`stats`][Shape::stats] of this dessin, if they fit in `budget`
+    pub fn check_budget(&self, budget: &Budget) -> Result<ShapeStats, BudgetExceeded> {
+        let stats = self.stats();
+        budget.check(&stats)?;
+        Ok(stats)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn count_and_depth() {
+        let dessin = dessin2!([
+            Circle!(fill = Color::RED, radius = 10.),
+            [Text(text = "Hello"), Line(from = [0., 0.], to = [1., 1.])],
+        ]);
+
+        let stats = dessin.stats();
+        assert_eq!(stats.groups, 2);
+        assert_eq!(stats.styles, 1);
+        assert_eq!(stats.ellipses, 1);
+        assert_eq!(stats.texts, 1);
+        assert_eq!(stats.curves, 1);
+        assert_eq!(stats.keypoints, 2);
+        assert_eq!(stats.characters, 5);
+        assert_eq!(stats.shapes(), 6);
+        // Group > Style > Ellipse
+        assert_eq!(stats.depth, 3);
+    }
+
+    #[test]
+    fn budget() {
+        let dessin = dessin2!([Circle(radius = 1.), Circle(radius = 2.)]);
+
+        assert!(dessin.check_budget(&Budget::default()).is_ok());
+        assert_eq!(
+            dessin.check_budget(&Budget {
+                max_shapes: Some(2),
+                ..Default::default()
+            }),
+            Err(BudgetExceeded {
+                limit: BudgetLimit::Shapes,
+                value: 3,
+                max: 2,
+            })
+        );
+    }
+}
@@ -1,9 +1,12 @@
 use crate::prelude::*;
-use nalgebra::{Rotation2, Scale2, Transform2, Translation2, Vector2};
+use ::image::DynamicImage;
+use nalgebra::{Point2, Rotation2, Scale2, Transform2, Translation2, Vector2};
 use std::{
     f32::consts::FRAC_1_SQRT_2,
     fmt,
+    hash::{Hash, Hasher},
     ops::{Deref, DerefMut, Mul},
+    sync::Arc,
 };
 
 /// Create a color from red, green and blue
@@ -142,6 +145,48 @@ impl Color {
         )
     }
 
+    /// Color `t` of the way from `self` to `other`, `t` being clamped between 0 and 1.
+    ///
+    /// ```
+    /// # use dessin::prelude::*;
+    /// assert_eq!(Color::BLACK.mix(Color::WHITE, 0.5), rgba(128, 128, 128, 255));
+    /// ```
+    pub fn mix(self, other: Color, t: f32) -> Color {
+        let t = if t.is_nan() { 0. } else { t.clamp(0., 1.) };
+        let (r1, g1, b1, a1) = self.rgba();
+        let (r2, g2, b2, a2) = other.rgba();
+        let channel = |c1: u8, c2: u8| (c1 as f32 + (c2 as f32 - c1 as f32) * t).round() as u8;
+
+        Color::RGBA {
+            r: channel(r1, r2),
+            g: channel(g1, g2),
+            b: channel(b1, b2),
+            a: channel(a1, a2),
+        }
+    }
+
+    /// This color with its alpha multiplied by `opacity`, clamped between 0 and 1.
+    ///
+    /// ```
+    /// # use dessin::prelude::*;
+    /// assert_eq!(Color::RED.faded(0.5), rgba(255, 0, 0, 128));
+    /// ```
+    pub fn faded(self, opacity: f32) -> Color {
+        let opacity = if opacity.is_nan() {
+            1.
+        } else {
+            opacity.clamp(0., 1.)
+        };
+        let (r, g, b, a) = self.rgba();
+
+        Color::RGBA {
+            r,
+            g,
+            b,
+            a: (a as f32 * opacity).round() as u8,
+        }
+    }
+
     /// Cast a color to (red, green, blue), as f64
     pub fn as_rgb_f64(&self) -> (f64, f64, f64) {
         let (r, g, b) = self.as_rgb();
@@ -172,16 +217,632 @@ impl fmt::Display for Color {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 
 pub struct StylePosition {
     pub stroke: Option<Stroke>,
     pub fill: Option<Fill>,
+    /// Only given to exporters which [can export filters][crate::export::Exporter::CAN_EXPORT_FILTER]
+    pub filter: Option<Filter>,
+    /// Opacity of the whole styled shape, from 0 to 1.
+    ///
+    /// Always 1 for exporters which [can't export opacity][crate::export::Exporter::CAN_EXPORT_OPACITY].
+    pub opacity: f32,
+    /// Always [`BlendMode::Normal`] for exporters which [can't export blend modes][crate::export::Exporter::CAN_EXPORT_BLEND_MODE]
+    pub blend_mode: BlendMode,
 }
 
+/// Effect applied to the rendering of a styled shape.
+///
+/// Only some exporters [can export filters][crate::export::Exporter::CAN_EXPORT_FILTER],
+/// others draw drop shadows as an image under the shape, and ignore the other filters.
+/// Lengths are in the coordinates of the style, like the width of strokes.
+///
+/// ```
+/// # use dessin::prelude::*;
+/// let card = dessin2!(Rectangle!(
+/// 	fill = Color::WHITE,
+/// 	filter = Filter::DropShadow {
+/// 		dx: 1.,
+/// 		dy: -1.,
+/// 		std_deviation: 0.5,
+/// 		color: rgba(0, 0, 0, 128),
+/// 	},
+/// 	width = 50.,
+/// 	height = 30.,
+/// ));
+/// ```
 #[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Filter {
+    /// Blur
+    GaussianBlur {
+        /// Standard deviation of the blur
+        std_deviation: f32,
+    },
+    /// Blurred copy of the shape, in a single color, offset under it
+    DropShadow {
+        /// Horizontal offset of the shadow
+        dx: f32,
+        /// Vertical offset of the shadow
+        dy: f32,
+        /// Standard deviation of the blur of the shadow
+        std_deviation: f32,
+        /// Color of the shadow
+        color: Color,
+    },
+    /// Colors turned to shades of gray
+    Grayscale,
+}
+
+impl Filter {
+    /// This filter with the alpha of its color multiplied by `opacity`
+    pub fn faded(self, opacity: f32) -> Filter {
+        match self {
+            Filter::DropShadow {
+                dx,
+                dy,
+                std_deviation,
+                color,
+            } => Filter::DropShadow {
+                dx,
+                dy,
+                std_deviation,
+                color: color.faded(opacity),
+            },
+            filter => filter,
+        }
+    }
+}
+
+/// Soft shadow of a styled shape, drawn under it.
+///
+/// A [`Filter::DropShadow`] with named fields: [SVG exports][crate::export::Exporter::CAN_EXPORT_FILTER] write it as a filter,
+/// others draw an image of the blurred silhouette of the shape under it, images staying transparent around the silhouette.
+/// Texts cast no shadow in that image.
+///
+/// ```
+/// # use dessin::prelude::*;
+/// let card = dessin2!(Rectangle!(
+/// 	fill = Color::WHITE,
+/// 	shadow = Shadow {
+/// 		offset: [1., -1.].into(),
+/// 		..Default::default()
+/// 	},
+/// 	width = 50.,
+/// 	height = 30.,
+/// ) > ());
+///
+/// assert!(matches!(card, Shape::Style { filter: Some(Filter::DropShadow { dx: 1., .. }), .. }));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Shadow {
+    /// Offset of the shadow from the shape
+    pub offset: Vector2<f32>,
+    /// Standard deviation of the blur of the shadow, sharp if 0
+    pub blur: f32,
+    /// Color of the shadow
+    pub color: Color,
+}
+impl Default for Shadow {
+    fn default() -> Self {
+        Shadow {
+            offset: Vector2::new(0.5, -0.5),
+            blur: 0.5,
+            color: rgba(0, 0, 0, 128),
+        }
+    }
+}
+impl From<Shadow> for Filter {
+    fn from(
+        Shadow {
+            offset,
+            blur,
+            color,
+        }: Shadow,
+    ) -> Self {
+        Filter::DropShadow {
+            dx: offset.x,
+            dy: offset.y,
+            std_deviation: blur,
+            color,
+        }
+    }
+}
+
+/// How the colors of a styled shape are mixed with the ones drawn under it.
+///
+/// The separable modes mix each color channel on its own, the others mix the hue, saturation and luminosity of the colors.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BlendMode {
+    /// Drawn over what is under it
+    #[default]
+    Normal,
+    /// Darkens, as inks printed on top of each other
+    Multiply,
+    /// Lightens, as lights projected on the same spot
+    Screen,
+    /// Multiplies the dark colors under it and screens the light ones
+    Overlay,
+    /// Darkest of both colors
+    Darken,
+    /// Lightest of both colors
+    Lighten,
+    /// Brightens what is under it
+    ColorDodge,
+    /// Darkens what is under it
+    ColorBurn,
+    /// Multiplies or screens, depending on the color of the shape
+    HardLight,
+    /// Darkens or lightens, depending on the color of the shape
+    SoftLight,
+    /// Difference between both colors
+    Difference,
+    /// As [`Difference`][BlendMode::Difference], with a lower contrast
+    Exclusion,
+    /// Hue of the shape, with the saturation and luminosity of what is under it
+    Hue,
+    /// Saturation of the shape, with the hue and luminosity of what is under it
+    Saturation,
+    /// Hue and saturation of the shape, with the luminosity of what is under it
+    Color,
+    /// Luminosity of the shape, with the hue and saturation of what is under it
+    Luminosity,
+}
+impl BlendMode {
+    /// Name of the mode in CSS, as `mix-blend-mode`
+    pub fn css_name(&self) -> &'static str {
+        match self {
+            BlendMode::Normal => "normal",
+            BlendMode::Multiply => "multiply",
+            BlendMode::Screen => "screen",
+            BlendMode::Overlay => "overlay",
+            BlendMode::Darken => "darken",
+            BlendMode::Lighten => "lighten",
+            BlendMode::ColorDodge => "color-dodge",
+            BlendMode::ColorBurn => "color-burn",
+            BlendMode::HardLight => "hard-light",
+            BlendMode::SoftLight => "soft-light",
+            BlendMode::Difference => "difference",
+            BlendMode::Exclusion => "exclusion",
+            BlendMode::Hue => "hue",
+            BlendMode::Saturation => "saturation",
+            BlendMode::Color => "color",
+            BlendMode::Luminosity => "luminosity",
+        }
+    }
+
+    /// Name of the mode in PDF, as the `BM` of a graphics state
+    pub fn pdf_name(&self) -> &'static str {
+        match self {
+            BlendMode::Normal => "Normal",
+            BlendMode::Multiply => "Multiply",
+            BlendMode::Screen => "Screen",
+            BlendMode::Overlay => "Overlay",
+            BlendMode::Darken => "Darken",
+            BlendMode::Lighten => "Lighten",
+            BlendMode::ColorDodge => "ColorDodge",
+            BlendMode::ColorBurn => "ColorBurn",
+            BlendMode::HardLight => "HardLight",
+            BlendMode::SoftLight => "SoftLight",
+            BlendMode::Difference => "Difference",
+            BlendMode::Exclusion => "Exclusion",
+            BlendMode::Hue => "Hue",
+            BlendMode::Saturation => "Saturation",
+            BlendMode::Color => "Color",
+            BlendMode::Luminosity => "Luminosity",
+        }
+    }
+}
+
+impl Mul<Filter> for Transform2<f32> {
+    type Output = Filter;
+    fn mul(self, rhs: Filter) -> Self::Output {
+        let factor = (self * Vector2::new(FRAC_1_SQRT_2, FRAC_1_SQRT_2)).magnitude();
+
+        match rhs {
+            Filter::GaussianBlur { std_deviation } => Filter::GaussianBlur {
+                std_deviation: factor * std_deviation,
+            },
+            Filter::DropShadow {
+                dx,
+                dy,
+                std_deviation,
+                color,
+            } => {
+                let offset = self * Vector2::new(dx, dy);
+                Filter::DropShadow {
+                    dx: offset.x,
+                    dy: offset.y,
+                    std_deviation: factor * std_deviation,
+                    color,
+                }
+            }
+            Filter::Grayscale => Filter::Grayscale,
+        }
+    }
+}
+
+/// Colors of a gradient, at offsets from 0 to 1, sorted by offset.
+///
+/// Shared by the copies of the fill.
+///
+/// ```
+/// # use dessin::prelude::*;
+/// let stops = GradientStops::new(&[(1., Color::WHITE), (0., Color::BLACK)]);
+/// assert_eq!(stops.as_slice()[0], (0., Color::BLACK));
+/// assert_eq!(stops.color_at(0.5), Some(rgba(128, 128, 128, 255)));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct GradientStops(Arc<[(f32, Color)]>);
+impl GradientStops {
+    /// Gradient of `stops`, offset and color, in any order
+    pub fn new(stops: &[(f32, Color)]) -> Self {
+        let mut stops = stops.to_vec();
+        stops.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+
+        GradientStops(stops.into())
+    }
+
+    /// Offset and color of each stop, sorted by offset
+    #[inline]
+    pub fn as_slice(&self) -> &[(f32, Color)] {
+        &self.0
+    }
+
+    /// These stops with the alpha of their colors multiplied by `opacity`
+    pub fn faded(&self, opacity: f32) -> Self {
+        GradientStops(
+            self.0
+                .iter()
+                .map(|(offset, color)| (*offset, color.faded(opacity)))
+                .collect(),
+        )
+    }
+
+    /// Color at `offset`, the one of the closest stop outside of them, `None` without stops
+    pub fn color_at(&self, offset: f32) -> Option<Color> {
+        let stops = self.as_slice();
+        let (first, last) = (stops.first()?, stops.last()?);
+        if offset <= first.0 {
+            return Some(first.1);
+        }
+
+        Some(
+            stops
+                .windows(2)
+                .find(|stops| offset <= stops[1].0)
+                .map(|stops| {
+                    let ((start, from), (end, to)) = (stops[0], stops[1]);
+                    let span = end - start;
+                    if span <= 0. {
+                        to
+                    } else {
+                        from.mix(to, (offset - start) / span)
+                    }
+                })
+                .unwrap_or(last.1),
+        )
+    }
+}
+impl<const N: usize> From<[(f32, Color); N]> for GradientStops {
+    fn from(stops: [(f32, Color); N]) -> Self {
+        GradientStops::new(&stops)
+    }
+}
+
+/// Shape repeated by a [`Fill::Pattern`], shared by the copies of the fill.
+///
+/// The tile is freed with the last fill repeating it.
+///
+/// ```
+/// # use dessin::prelude::*;
+/// let star = PatternRef::new(dessin2!(Circle!(fill = Color::RED, radius = 1.)));
+/// assert!(star.tile().local_bounding_box().width() > 0.);
+/// ```
+#[derive(Clone)]
+pub struct PatternRef(Arc<Shape>);
+impl PatternRef {
+    /// Pattern repeating `tile`
+    pub fn new<T: Into<Shape>>(tile: T) -> Self {
+        PatternRef(Arc::new(tile.into()))
+    }
+
+    /// Shape repeated by the pattern
+    pub fn tile(&self) -> &Shape {
+        &self.0
+    }
+
+    /// Pattern of the [faded][Shape::faded] tile
+    pub fn faded(&self, opacity: f32) -> PatternRef {
+        PatternRef::new(self.tile().faded(opacity))
+    }
+}
+impl fmt::Debug for PatternRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("PatternRef")
+            .field(&Arc::as_ptr(&self.0))
+            .finish()
+    }
+}
+/// References are equal when they share the same tile
+impl PartialEq for PatternRef {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+impl Eq for PatternRef {}
+impl Hash for PatternRef {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        Arc::as_ptr(&self.0).hash(state);
+    }
+}
+
+/// Image painted by a [`Fill::Image`], shared by the copies of the fill.
+///
+/// The image is freed with the last fill painting it, and can be sent to other threads.
+///
+/// ```
+/// # use dessin::prelude::*;
+/// let photo = ImageRef::new(Image::default().with_image(dessin::image::DynamicImage::new_rgb8(3, 4)));
+///
+/// std::thread::spawn(move || assert_eq!(photo.image().image.width(), 3))
+/// 	.join()
+/// 	.unwrap();
+/// ```
+#[derive(Clone)]
+pub struct ImageRef(Arc<Image>);
+impl ImageRef {
+    /// Reference to `image`, whose transform is ignored
+    pub fn new(image: Image) -> Self {
+        ImageRef(Arc::new(image))
+    }
+
+    /// The image
+    pub fn image(&self) -> &Image {
+        &self.0
+    }
+
+    /// Reference to the image with the alpha of its pixels multiplied by `opacity`
+    pub fn faded(&self, opacity: f32) -> ImageRef {
+        ImageRef::new(faded_image(self.image().clone(), opacity))
+    }
+}
+impl fmt::Debug for ImageRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("ImageRef")
+            .field(&Arc::as_ptr(&self.0))
+            .finish()
+    }
+}
+/// References are equal when they share the same image
+impl PartialEq for ImageRef {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+impl Eq for ImageRef {}
+impl Hash for ImageRef {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        Arc::as_ptr(&self.0).hash(state);
+    }
+}
+
+/// How a [`Fill::Image`] is sized to the bounding box of the filled shape
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ImageFit {
+    /// Smallest size covering the whole box, keeping the aspect ratio
+    #[default]
+    Cover,
+    /// Largest size inside the box, keeping the aspect ratio
+    Contain,
+    /// Size of the box
+    Stretch,
+}
+impl ImageFit {
+    /// Width and height of an image of `aspect_ratio` fitted in `width` and `height`
+    ///
+    /// ```
+    /// # use dessin::prelude::*;
+    /// // Portrait photo in a square
+    /// assert_eq!(ImageFit::Cover.size(0.5, 10., 10.), (10., 20.));
+    /// assert_eq!(ImageFit::Contain.size(0.5, 10., 10.), (5., 10.));
+    /// assert_eq!(ImageFit::Stretch.size(0.5, 10., 10.), (10., 10.));
+    /// ```
+    pub fn size(&self, aspect_ratio: f32, width: f32, height: f32) -> (f32, f32) {
+        let fitted_width = match self {
+            ImageFit::Cover => width.max(height * aspect_ratio),
+            ImageFit::Contain => width.min(height * aspect_ratio),
+            ImageFit::Stretch => return (width, height),
+        };
+
+        (fitted_width, fitted_width / aspect_ratio)
+    }
+}
+
+/// Paint of the inside of a shape.
+///
+/// Gradients are positioned in the coordinates of their style, so transforms of the styled shape don't move them,
+/// unlike the transforms of a group around the style.
+///
+/// ```
+/// # use dessin::prelude::*;
+/// let sunset = dessin2!(Rectangle!(
+/// 	fill = Fill::LinearGradient {
+/// 		start: [0., -10.].into(),
+/// 		end: [0., 10.].into(),
+/// 		stops: [(0., Color::ORANGE), (1., Color::BLUE)].into(),
+/// 	},
+/// 	width = 30.,
+/// 	height = 20.,
+/// ));
+///
+/// let badge = dessin2!(Circle!(
+/// 	fill = Fill::image(dessin::image::DynamicImage::new_rgb8(300, 400), ImageFit::Cover),
+/// 	radius = 10.,
+/// ));
+///
+/// let hatched = dessin2!(Circle!(
+/// 	fill = Fill::hatching(Color::BLACK, 0.2, 2., Angle::degrees(45.)),
+/// 	stroke = (Color::BLACK, 0.2),
+/// 	radius = 10.,
+/// ));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
 pub enum Fill {
+    /// Single color
     Color(Color),
+    /// Colors changing along the line from `start`, at offset 0, to `end`, at offset 1, and constant across it
+    LinearGradient {
+        /// Position of the offset 0
+        start: Point2<f32>,
+        /// Position of the offset 1
+        end: Point2<f32>,
+        /// Colors of the gradient
+        stops: GradientStops,
+    },
+    /// Colors changing from `center`, at offset 0, to the circle of `radius` around it, at offset 1
+    RadialGradient {
+        /// Position of the offset 0
+        center: Point2<f32>,
+        /// Distance to the center of the offset 1
+        radius: f32,
+        /// Colors of the gradient
+        stops: GradientStops,
+    },
+    /// Copies of a shape, centered on the cells of a grid, cut to the filled shape.
+    ///
+    /// Exporters with native patterns also cut each copy to its cell.
+    Pattern {
+        /// Shape repeated
+        pattern: PatternRef,
+        /// Size of a cell of the grid
+        spacing: Vector2<f32>,
+        /// Position, rotation and scale of the grid, whose cell around the origin is centered on it
+        transform: Transform2<f32>,
+    },
+    /// Image centered on the bounding box of the filled shape, cut to the shape
+    Image {
+        /// Image painted
+        image: ImageRef,
+        /// Size of the image
+        fit: ImageFit,
+    },
+}
+impl Fill {
+    /// Single color closest to the fill, for exporters drawing no gradient: the middle of gradients, transparent for patterns and images
+    pub fn color(&self) -> Color {
+        match self {
+            Fill::Color(color) => *color,
+            Fill::LinearGradient { stops, .. } | Fill::RadialGradient { stops, .. } => {
+                stops.color_at(0.5).unwrap_or(Color::TRANSPARENT)
+            }
+            Fill::Pattern { .. } | Fill::Image { .. } => Color::TRANSPARENT,
+        }
+    }
+
+    /// This fill with the alpha of its colors, tile or image multiplied by `opacity`
+    pub fn faded(self, opacity: f32) -> Fill {
+        match self {
+            Fill::Color(color) => Fill::Color(color.faded(opacity)),
+            Fill::LinearGradient { start, end, stops } => Fill::LinearGradient {
+                start,
+                end,
+                stops: stops.faded(opacity),
+            },
+            Fill::RadialGradient {
+                center,
+                radius,
+                stops,
+            } => Fill::RadialGradient {
+                center,
+                radius,
+                stops: stops.faded(opacity),
+            },
+            Fill::Pattern {
+                pattern,
+                spacing,
+                transform,
+            } => Fill::Pattern {
+                pattern: pattern.faded(opacity),
+                spacing,
+                transform,
+            },
+            Fill::Image { image, fit } => Fill::Image {
+                image: image.faded(opacity),
+                fit,
+            },
+        }
+    }
+
+    /// `image` sized by `fit` to the filled shape
+    pub fn image(image: DynamicImage, fit: ImageFit) -> Self {
+        Fill::Image {
+            image: ImageRef::new(Image::default().with_image(image)),
+            fit,
+        }
+    }
+
+    /// Copies of `tile` every `spacing`, on a grid rotated by `rotation`
+    pub fn pattern<T: Into<Shape>, V: Into<Vector2<f32>>>(
+        tile: T,
+        spacing: V,
+        rotation: Angle,
+    ) -> Self {
+        Fill::Pattern {
+            pattern: PatternRef::new(tile),
+            spacing: spacing.into(),
+            transform: nalgebra::convert(Rotation2::new(rotation.as_radians())),
+        }
+    }
+
+    /// Parallel lines of `color` and `width`, `spacing` apart, at `angle` from the X axis
+    pub fn hatching(color: Color, width: f32, spacing: f32, angle: Angle) -> Self {
+        let line = dessin2!(Line!(
+            stroke = (color, width),
+            from = [-spacing / 2., 0.],
+            to = [spacing / 2., 0.],
+        ));
+        Fill::pattern(line, [spacing, spacing], angle)
+    }
+
+    /// Dots of `color` and `radius`, `spacing` apart
+    pub fn dots(color: Color, radius: f32, spacing: f32) -> Self {
+        let dot = dessin2!(Circle!(fill = color, radius = radius));
+        Fill::pattern(dot, [spacing, spacing], Angle::ZERO)
+    }
+}
+
+impl Mul<Fill> for Transform2<f32> {
+    type Output = Fill;
+    fn mul(self, rhs: Fill) -> Self::Output {
+        match rhs {
+            Fill::Color(color) => Fill::Color(color),
+            Fill::LinearGradient { start, end, stops } => Fill::LinearGradient {
+                start: self * start,
+                end: self * end,
+                stops,
+            },
+            Fill::RadialGradient {
+                center,
+                radius,
+                stops,
+            } => Fill::RadialGradient {
+                center: self * center,
+                radius: (self * Vector2::new(FRAC_1_SQRT_2, FRAC_1_SQRT_2)).magnitude() * radius,
+                stops,
+            },
+            Fill::Pattern {
+                pattern,
+                spacing,
+                transform,
+            } => Fill::Pattern {
+                pattern,
+                spacing,
+                transform: self * transform,
+            },
+            Fill::Image { image, fit } => Fill::Image { image, fit },
+        }
+    }
 }
 
 impl From<Color> for Fill {
@@ -190,58 +851,582 @@ impl From<Color> for Fill {
     }
 }
 
+/// Lengths of the dashes and gaps of a [`Stroke::DashPattern`], alternating and starting with a dash.
+///
+/// Holds up to [`DashArray::CAPACITY`] lengths, the most PDF supports.
+///
+/// ```
+/// # use dessin::prelude::*;
+/// // Dash-dot
+/// let dashes = DashArray::new(&[3., 1., 0.5, 1.]);
+/// assert_eq!(dashes.as_slice(), &[3., 1., 0.5, 1.]);
+///
+/// // An odd number of lengths is repeated, like in SVG
+/// assert_eq!(DashArray::new(&[2., 1., 1.]).as_slice(), &[2., 1., 1., 2., 1., 1.]);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DashArray {
+    lengths: [f32; DashArray::CAPACITY],
+    len: usize,
+}
+impl DashArray {
+    /// Most lengths a dash array holds
+    pub const CAPACITY: usize = 6;
+
+    /// Dashes and gaps of `lengths`.
+    ///
+    /// An odd number of lengths is repeated to alternate dashes and gaps, like in SVG.
+    /// Lengths past the capacity are dropped, along with the last one if they are odd.
+    pub fn new(lengths: &[f32]) -> Self {
+        let mut len = lengths.len().min(DashArray::CAPACITY);
+        let mut array = [0.; DashArray::CAPACITY];
+        array[..len].copy_from_slice(&lengths[..len]);
+
+        if len % 2 == 1 {
+            if 2 * len <= DashArray::CAPACITY {
+                array.copy_within(..len, len);
+                len *= 2;
+            } else {
+                len -= 1;
+            }
+        }
+
+        DashArray {
+            lengths: array,
+            len,
+        }
+    }
+
+    /// Lengths of the dashes and gaps
+    #[inline]
+    pub fn as_slice(&self) -> &[f32] {
+        &self.lengths[..self.len]
+    }
+
+    /// Length of the whole pattern, before it repeats
+    pub fn period(&self) -> f32 {
+        self.as_slice().iter().sum()
+    }
+}
+impl Mul<f32> for DashArray {
+    type Output = DashArray;
+    fn mul(mut self, rhs: f32) -> Self::Output {
+        self.lengths.iter_mut().for_each(|length| *length *= rhs);
+        self
+    }
+}
+
+/// Which areas enclosed by a curve crossing itself are filled
+///
+/// ```
+/// # use dessin::prelude::*;
+/// use nalgebra::Point2;
+///
+/// // Outer square, then inner square, joined by a line back and forth
+/// let frame = dessin2!(Curve!(
+/// 	then = Point2::new(-10., -10.),
+/// 	then = Point2::new(10., -10.),
+/// 	then = Point2::new(10., 10.),
+/// 	then = Point2::new(-10., 10.),
+/// 	then = Point2::new(-10., -10.),
+/// 	then = Point2::new(-5., -5.),
+/// 	then = Point2::new(-5., 5.),
+/// 	then = Point2::new(5., 5.),
+/// 	then = Point2::new(5., -5.),
+/// 	then = Point2::new(-5., -5.),
+/// 	closed,
+/// 	fill_rule = FillRule::EvenOdd,
+/// 	fill = Color::BLACK,
+/// ));
+/// ```
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FillRule {
+    /// Areas the curve winds around, in total, at least once, like SVG and PDF by default
+    #[default]
+    NonZero,
+    /// Areas the curve crosses an odd number of times to get out of, so inner loops are holes whatever their direction
+    EvenOdd,
+}
+impl FillRule {
+    /// Whether an area the curve winds around `winding` times is filled
+    pub fn is_inside(self, winding: i32) -> bool {
+        match self {
+            FillRule::NonZero => winding != 0,
+            FillRule::EvenOdd => winding % 2 != 0,
+        }
+    }
+}
+
+/// Shape of the ends of open curves, and of dashes
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LineCap {
+    /// Squared off at the end
+    #[default]
+    Butt,
+    /// Half circle past the end
+    Round,
+    /// Half square past the end
+    Square,
+}
+
+/// Shape of the corners of curves
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LineJoin {
+    /// Outer edges extended until they meet
+    #[default]
+    Miter,
+    /// Circle around the corner
+    Round,
+    /// Outer edges joined by a straight line
+    Bevel,
+}
+
+/// Line drawn along the outline of a shape.
+///
+/// Caps and joins default to [`LineCap::Butt`] and [`LineJoin::Miter`], like SVG and PDF.
+/// Miters longer than [`Stroke::DEFAULT_MITER_LIMIT`] times the width, at sharp corners, are beveled like in SVG.
+///
+/// Strokes are made with `Stroke::from((color, width))`, [`Stroke::dashed`] or [`Stroke::dash_pattern`],
+/// then styled by [`Stroke::with_cap`], [`Stroke::with_join`] and [`Stroke::with_miter_limit`],
+/// as their variants may get new fields.
+///
+/// The variants are `#[non_exhaustive]` since they got caps, joins and miter limits:
+/// other crates can't build them with a struct expression anymore, and their patterns end with `..`.
+/// See the changelog for how to migrate.
+///
+/// ```
+/// # use dessin::prelude::*;
+/// let stroke = Stroke::from((Color::BLACK, 1.));
+///
+/// let Stroke::Full { color, width, .. } = stroke else {
+/// 	unreachable!()
+/// };
+/// assert_eq!((color, width), (stroke.color(), stroke.width()));
+/// ```
+///
+/// ```
+/// # use dessin::{prelude::*, nalgebra::Point2};
+/// let route = dessin2!(Curve!(
+/// 	stroke = Stroke::from((Color::BLUE, 2.))
+/// 		.with_cap(LineCap::Round)
+/// 		.with_join(LineJoin::Round),
+/// 	then = Point2::new(0., 0.),
+/// 	then = Point2::new(10., 5.),
+/// 	then = Point2::new(20., 0.),
+/// ));
+/// ```
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Stroke {
+    #[non_exhaustive]
     Full {
         color: Color,
         width: f32,
+        /// Shape of the ends
+        cap: LineCap,
+        /// Shape of the corners
+        join: LineJoin,
+        /// Longest miter, in widths of the stroke, before a mitered corner is beveled
+        miter_limit: f32,
     },
+    #[non_exhaustive]
     Dashed {
         color: Color,
         width: f32,
         on: f32,
         off: f32,
+        /// Shape of the ends of the dashes
+        cap: LineCap,
+        /// Shape of the corners
+        join: LineJoin,
+        /// Longest miter, in widths of the stroke, before a mitered corner is beveled
+        miter_limit: f32,
+    },
+    /// Dashes and gaps of any lengths
+    #[non_exhaustive]
+    DashPattern {
+        /// Color
+        color: Color,
+        /// Width
+        width: f32,
+        /// Lengths of the dashes and gaps
+        dashes: DashArray,
+        /// Distance into the dashes at which the stroke starts
+        offset: f32,
+        /// Shape of the ends of the dashes
+        cap: LineCap,
+        /// Shape of the corners
+        join: LineJoin,
+        /// Longest miter, in widths of the stroke, before a mitered corner is beveled
+        miter_limit: f32,
     },
 }
+impl Stroke {
+    /// Default miter limit, the one of SVG
+    pub const DEFAULT_MITER_LIMIT: f32 = 4.;
+
+    /// Dashes `on` long, every `on + off`
+    pub fn dashed(color: Color, width: f32, on: f32, off: f32) -> Self {
+        Stroke::Dashed {
+            color,
+            width,
+            on,
+            off,
+            cap: LineCap::Butt,
+            join: LineJoin::Miter,
+            miter_limit: Stroke::DEFAULT_MITER_LIMIT,
+        }
+    }
+
+    /// Dashes and gaps of `dashes`, starting `offset` into them
+    ///
+    /// ```
+    /// # use dessin::prelude::*;
+    /// // Dash, dot, dash...
+    /// let stroke = Stroke::dash_pattern(Color::BLACK, 1., DashArray::new(&[6., 2., 1., 2.]), 0.)
+    /// 	.with_cap(LineCap::Round);
+    ///
+    /// assert_eq!(stroke.dashes().unwrap().0.as_slice(), &[6., 2., 1., 2.]);
+    /// ```
+    pub fn dash_pattern(color: Color, width: f32, dashes: DashArray, offset: f32) -> Self {
+        Stroke::DashPattern {
+            color,
+            width,
+            dashes,
+            offset,
+            cap: LineCap::Butt,
+            join: LineJoin::Miter,
+            miter_limit: Stroke::DEFAULT_MITER_LIMIT,
+        }
+    }
+
+    /// Color
+    pub fn color(&self) -> Color {
+        match self {
+            Stroke::Full { color, .. }
+            | Stroke::Dashed { color, .. }
+            | Stroke::DashPattern { color, .. } => *color,
+        }
+    }
+
+    /// Width
+    pub fn width(&self) -> f32 {
+        match self {
+            Stroke::Full { width, .. }
+            | Stroke::Dashed { width, .. }
+            | Stroke::DashPattern { width, .. } => *width,
+        }
+    }
+
+    /// This stroke with the alpha of its color multiplied by `opacity`
+    pub fn faded(mut self, opacity: f32) -> Stroke {
+        match &mut self {
+            Stroke::Full { color, .. }
+            | Stroke::Dashed { color, .. }
+            | Stroke::DashPattern { color, .. } => *color = color.faded(opacity),
+        }
+        self
+    }
+
+    /// Dashes and gaps, with the distance into them at which the stroke starts, `None` if the stroke is full
+    pub fn dashes(&self) -> Option<(DashArray, f32)> {
+        match self {
+            Stroke::Full { .. } => None,
+            Stroke::Dashed { on, off, .. } => Some((DashArray::new(&[*on, *off]), 0.)),
+            Stroke::DashPattern { dashes, offset, .. } => Some((*dashes, *offset)),
+        }
+    }
+
+    /// Shape of the ends
+    pub fn cap(&self) -> LineCap {
+        match self {
+            Stroke::Full { cap, .. }
+            | Stroke::Dashed { cap, .. }
+            | Stroke::DashPattern { cap, .. } => *cap,
+        }
+    }
+    /// Same stroke, with ends shaped by `cap`
+    pub fn with_cap(mut self, cap: LineCap) -> Self {
+        match &mut self {
+            Stroke::Full { cap: c, .. }
+            | Stroke::Dashed { cap: c, .. }
+            | Stroke::DashPattern { cap: c, .. } => *c = cap,
+        }
+        self
+    }
+
+    /// Shape of the corners
+    pub fn join(&self) -> LineJoin {
+        match self {
+            Stroke::Full { join, .. }
+            | Stroke::Dashed { join, .. }
+            | Stroke::DashPattern { join, .. } => *join,
+        }
+    }
+    /// Same stroke, with corners shaped by `join`
+    pub fn with_join(mut self, join: LineJoin) -> Self {
+        match &mut self {
+            Stroke::Full { join: j, .. }
+            | Stroke::Dashed { join: j, .. }
+            | Stroke::DashPattern { join: j, .. } => *j = join,
+        }
+        self
+    }
+
+    /// Longest miter, in widths of the stroke, before a mitered corner is beveled
+    pub fn miter_limit(&self) -> f32 {
+        match self {
+            Stroke::Full { miter_limit, .. }
+            | Stroke::Dashed { miter_limit, .. }
+            | Stroke::DashPattern { miter_limit, .. } => *miter_limit,
+        }
+    }
+    /// Same stroke, with mitered corners beveled when their miter is longer than `miter_limit` widths of the stroke.
+    ///
+    /// Limits below 1, which would bevel every corner, are raised to 1 like in SVG.
+    ///
+    /// ```
+    /// # use dessin::prelude::*;
+    /// let stroke = Stroke::from((Color::BLACK, 1.)).with_miter_limit(0.5);
+    ///
+    /// assert_eq!(stroke.miter_limit(), 1.);
+    /// ```
+    pub fn with_miter_limit(mut self, miter_limit: f32) -> Self {
+        match &mut self {
+            Stroke::Full { miter_limit: m, .. }
+            | Stroke::Dashed { miter_limit: m, .. }
+            | Stroke::DashPattern { miter_limit: m, .. } => *m = miter_limit.max(1.),
+        }
+        self
+    }
+}
 
 impl From<(Color, f32)> for Stroke {
     fn from((color, width): (Color, f32)) -> Self {
-        Stroke::Full { color, width }
+        Stroke::Full {
+            color,
+            width,
+            cap: LineCap::Butt,
+            join: LineJoin::Miter,
+            miter_limit: Stroke::DEFAULT_MITER_LIMIT,
+        }
     }
 }
 
 impl Mul<Stroke> for Transform2<f32> {
     type Output = Stroke;
     fn mul(self, rhs: Stroke) -> Self::Output {
+        let factor = (self * Vector2::new(FRAC_1_SQRT_2, FRAC_1_SQRT_2)).magnitude();
+
         match rhs {
-            Stroke::Full { color, width } => Stroke::Full {
+            Stroke::Full {
+                color,
+                width,
+                cap,
+                join,
+                miter_limit,
+            } => Stroke::Full {
                 color,
-                width: (self * Vector2::new(FRAC_1_SQRT_2, FRAC_1_SQRT_2)).magnitude() * width,
+                width: factor * width,
+                cap,
+                join,
+                miter_limit,
             },
             Stroke::Dashed {
                 color,
                 width,
                 on,
                 off,
-            } => {
-                let factor = (self * Vector2::new(FRAC_1_SQRT_2, FRAC_1_SQRT_2)).magnitude();
+                cap,
+                join,
+                miter_limit,
+            } => Stroke::Dashed {
+                color,
+                width: width * factor,
+                on: on * factor,
+                off: off * factor,
+                cap,
+                join,
+                miter_limit,
+            },
+            Stroke::DashPattern {
+                color,
+                width,
+                dashes,
+                offset,
+                cap,
+                join,
+                miter_limit,
+            } => Stroke::DashPattern {
+                color,
+                width: width * factor,
+                dashes: dashes * factor,
+                offset: offset * factor,
+                cap,
+                join,
+                miter_limit,
+            },
+        }
+    }
+}
 
-                Stroke::Dashed {
-                    color,
-                    width: width * factor,
-                    on: on * factor,
-                    off: off * factor,
-                }
-            }
+/// Position of a stroke relative to the outline of a closed shape.
+///
+/// Outlines are offset by half the width of the stroke, their corners joined like the ones of the stroke.
+/// Open curves have no inside nor outside, so their stroke stays centered.
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub enum StrokeAlignment {
+    /// Centered on the outline
+    #[default]
+    Center,
+    /// Entirely inside the outline
+    Inside,
+    /// Entirely outside the outline
+    Outside,
+}
+
+/// Head drawn at an end of an open [`Curve`], such as an arrow.
+///
+/// Markers are filled with the color of the stroke, and sized in widths of the stroke,
+/// so a thicker line gets bigger heads.
+/// Curves are shortened under arrows, so their ends don't stick out of the tip.
+///
+/// ```
+/// # use dessin::prelude::*;
+/// let flow = dessin2!(Line!(
+/// 	stroke = (Color::BLACK, 0.5),
+/// 	from = [0., 0.],
+/// 	to = [20., 0.],
+/// 	start_marker = Marker::Dot,
+/// 	end_marker = Marker::Arrow,
+/// ));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Marker {
+    /// Triangle whose tip is at the end of the curve
+    Arrow,
+    /// Disk centered on the end of the curve
+    Dot,
+    /// Segment across the end of the curve
+    Bar,
+    /// Shape of a [`MarkerRef`]
+    Custom(MarkerRef),
+}
+impl Marker {
+    /// Length of an arrow, in widths of the stroke
+    pub const ARROW_LENGTH: f32 = crate::presets::ArrowSize::LENGTH_RATIO;
+    /// Width of the base of an arrow, in widths of the stroke
+    pub const ARROW_WIDTH: f32 = crate::presets::ArrowSize::WIDTH_RATIO;
+    /// Diameter of a dot, in widths of the stroke
+    pub const DOT_DIAMETER: f32 = 4.;
+    /// Length of a bar, in widths of the stroke
+    pub const BAR_LENGTH: f32 = crate::presets::ArrowSize::WIDTH_RATIO;
+
+    /// Shape of the marker for a stroke of width 1, at an end at the origin, the curve coming from negative x
+    pub fn shape(&self) -> Shape {
+        match self {
+            Marker::Arrow => Curve::default()
+                .with_then(Point2::origin())
+                .with_then(Point2::new(-Marker::ARROW_LENGTH, Marker::ARROW_WIDTH / 2.))
+                .with_then(Point2::new(
+                    -Marker::ARROW_LENGTH,
+                    -Marker::ARROW_WIDTH / 2.,
+                ))
+                .with_close(true)
+                .into(),
+            Marker::Dot => Circle::default()
+                .with_radius(Marker::DOT_DIAMETER / 2.)
+                .into(),
+            Marker::Bar => Rectangle::default()
+                .with_width(1.)
+                .with_height(Marker::BAR_LENGTH)
+                .into(),
+            Marker::Custom(marker) => marker.shape().clone(),
+        }
+    }
+
+    /// Length by which the curve is shortened under the marker, in widths of the stroke
+    pub fn inset(&self) -> f32 {
+        match self {
+            // Half way, where the arrow is wider than the stroke
+            Marker::Arrow => Marker::ARROW_LENGTH / 2.,
+            Marker::Dot | Marker::Bar | Marker::Custom(_) => 0.,
         }
     }
 }
 
-#[derive(Default, Debug, Clone, PartialEq)]
+/// Shape of a [`Marker::Custom`], shared by the copies of the marker.
+///
+/// The shape is drawn for a stroke of width 1, at an end at the origin, the curve coming from negative x.
+/// It is freed with the last curve ending with it.
+///
+/// ```
+/// # use dessin::prelude::*;
+/// // Open arrowhead, drawn with the stroke
+/// let chevron = MarkerRef::new(dessin2!(Curve!(
+/// 	stroke = (Color::BLACK, 1.),
+/// 	then = nalgebra::Point2::new(-4., 3.),
+/// 	then = nalgebra::Point2::new(0., 0.),
+/// 	then = nalgebra::Point2::new(-4., -3.),
+/// )));
+///
+/// let line = dessin2!(Line!(
+/// 	stroke = (Color::BLACK, 0.5),
+/// 	to = [20., 0.],
+/// 	end_marker = Marker::Custom(chevron),
+/// ));
+/// ```
+#[derive(Clone)]
+pub struct MarkerRef(Arc<Shape>);
+impl MarkerRef {
+    /// Marker drawing `shape`
+    pub fn new<T: Into<Shape>>(shape: T) -> Self {
+        MarkerRef(Arc::new(shape.into()))
+    }
+
+    /// Shape of the marker
+    pub fn shape(&self) -> &Shape {
+        &self.0
+    }
+
+    /// Marker of the [faded][Shape::faded] shape
+    pub fn faded(&self, opacity: f32) -> MarkerRef {
+        MarkerRef::new(self.shape().faded(opacity))
+    }
+}
+impl fmt::Debug for MarkerRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("MarkerRef")
+            .field(&Arc::as_ptr(&self.0))
+            .finish()
+    }
+}
+/// References are equal when they share the same shape
+impl PartialEq for MarkerRef {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+impl Eq for MarkerRef {}
+impl Hash for MarkerRef {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        Arc::as_ptr(&self.0).hash(state);
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct Style<T> {
     pub shape: T,
     pub fill: Option<Fill>,
     pub stroke: Option<Stroke>,
+    pub stroke_alignment: StrokeAlignment,
+    pub filter: Option<Filter>,
+    pub opacity: f32,
+    pub blend_mode: BlendMode,
+}
+impl<T: Default> Default for Style<T> {
+    fn default() -> Self {
+        Style::new(T::default())
+    }
 }
 impl<T> Style<T> {
     #[inline]
@@ -250,6 +1435,10 @@ impl<T> Style<T> {
             shape,
             fill: None,
             stroke: None,
+            stroke_alignment: StrokeAlignment::Center,
+            filter: None,
+            opacity: 1.,
+            blend_mode: BlendMode::Normal,
         }
     }
 
@@ -264,6 +1453,20 @@ impl<T> Style<T> {
         self
     }
 
+    /// Sit the stroke inside or outside of closed outlines.
+    ///
+    /// The outlines are offset when exported, so every format supports it.
+    #[inline]
+    pub fn stroke_alignment(&mut self, stroke_alignment: StrokeAlignment) -> &mut Self {
+        self.stroke_alignment = stroke_alignment;
+        self
+    }
+    #[inline]
+    pub fn with_stroke_alignment(mut self, stroke_alignment: StrokeAlignment) -> Self {
+        self.stroke_alignment(stroke_alignment);
+        self
+    }
+
     #[inline]
     pub fn fill<F: Into<Fill>>(&mut self, fill: F) -> &mut Self {
         self.fill = Some(fill.into());
@@ -274,6 +1477,76 @@ impl<T> Style<T> {
         self.fill(fill);
         self
     }
+
+    /// Effect applied to the rendering of the shape, by exporters supporting it
+    #[inline]
+    pub fn filter(&mut self, filter: Filter) -> &mut Self {
+        self.filter = Some(filter);
+        self
+    }
+    #[inline]
+    pub fn with_filter(mut self, filter: Filter) -> Self {
+        self.filter(filter);
+        self
+    }
+
+    /// Soft shadow under the shape, replacing its [filter][Style::filter]
+    #[inline]
+    pub fn shadow(&mut self, shadow: Shadow) -> &mut Self {
+        self.filter(shadow.into())
+    }
+    #[inline]
+    pub fn with_shadow(mut self, shadow: Shadow) -> Self {
+        self.shadow(shadow);
+        self
+    }
+
+    /// Opacity of the whole shape, from 0 to 1, on top of the alpha of its colors.
+    ///
+    /// Exporters which [can't export opacity][crate::export::Exporter::CAN_EXPORT_OPACITY]
+    /// make every color and image of the shape transparent instead, so its overlapping parts show through each other.
+    ///
+    /// ```
+    /// # use dessin::prelude::*;
+    /// let layer = dessin2!([
+    /// 	Circle!(fill = Color::RED, radius = 10.),
+    /// 	Rectangle!(fill = Color::BLUE, width = 10., height = 10.),
+    /// ]);
+    ///
+    /// let faded: Shape = Style::new(layer).with_opacity(0.5).into();
+    /// ```
+    #[inline]
+    pub fn opacity(&mut self, opacity: f32) -> &mut Self {
+        self.opacity = opacity;
+        self
+    }
+    #[inline]
+    pub fn with_opacity(mut self, opacity: f32) -> Self {
+        self.opacity(opacity);
+        self
+    }
+
+    /// How the colors of the shape mix with the ones under it.
+    ///
+    /// Exporters which [can't export blend modes][crate::export::Exporter::CAN_EXPORT_BLEND_MODE] draw the shape normally.
+    ///
+    /// ```
+    /// # use dessin::prelude::*;
+    /// let ink = dessin2!(Circle!(fill = Color::CYAN, radius = 10.) > ());
+    ///
+    /// let printed: Shape = Style::new(ink).with_blend_mode(BlendMode::Multiply).into();
+    /// assert!(matches!(printed, Shape::Style { blend_mode: BlendMode::Multiply, .. }));
+    /// ```
+    #[inline]
+    pub fn blend_mode(&mut self, blend_mode: BlendMode) -> &mut Self {
+        self.blend_mode = blend_mode;
+        self
+    }
+    #[inline]
+    pub fn with_blend_mode(mut self, blend_mode: BlendMode) -> Self {
+        self.blend_mode(blend_mode);
+        self
+    }
 }
 
 impl<T> Deref for Style<T> {
@@ -299,20 +1572,135 @@ impl<T: Into<Shape>> From<Style<T>> for Shape {
             shape,
             fill,
             stroke,
+            stroke_alignment,
+            filter,
+            opacity,
+            blend_mode,
         }: Style<T>,
     ) -> Self {
-        if fill.is_none() && stroke.is_none() {
+        if fill.is_none()
+            && stroke.is_none()
+            && filter.is_none()
+            && opacity == 1.
+            && blend_mode == BlendMode::Normal
+        {
             shape.into()
         } else {
             Shape::Style {
                 fill,
                 stroke,
+                stroke_alignment,
+                filter,
+                opacity,
+                blend_mode,
                 shape: Box::new(shape.into()),
             }
         }
     }
 }
 
+impl Shape {
+    /// This shape with the alpha of every color, tile and image multiplied by `opacity`.
+    ///
+    /// Unlike the [opacity of a style][Style::opacity], overlapping parts show through each other.
+    /// [`Dynamic`][Shape::Dynamic] shapes are evaluated once and for all.
+    ///
+    /// ```
+    /// # use dessin::prelude::*;
+    /// let faded = dessin2!(Circle!(fill = Color::RED, radius = 10.) > ()).faded(0.5);
+    ///
+    /// let Shape::Style { fill, .. } = faded else {
+    /// 	unreachable!()
+    /// };
+    /// assert_eq!(fill, Some(Fill::Color(rgba(255, 0, 0, 128))));
+    /// ```
+    pub fn faded(&self, opacity: f32) -> Shape {
+        match self {
+            Shape::Group(Group {
+                local_transform,
+                shapes,
+                metadata,
+            }) => Shape::Group(Group {
+                local_transform: *local_transform,
+                shapes: shapes.iter().map(|shape| shape.faded(opacity)).collect(),
+                metadata: metadata.clone(),
+            }),
+            Shape::Style {
+                fill,
+                stroke,
+                stroke_alignment,
+                filter,
+                opacity: style_opacity,
+                blend_mode,
+                shape,
+            } => Shape::Style {
+                fill: fill.clone().map(|fill| fill.faded(opacity)),
+                stroke: stroke.map(|stroke| stroke.faded(opacity)),
+                stroke_alignment: *stroke_alignment,
+                filter: filter.map(|filter| filter.faded(opacity)),
+                opacity: *style_opacity,
+                blend_mode: *blend_mode,
+                shape: Box::new(shape.faded(opacity)),
+            },
+            Shape::Image(image) => Shape::Image(faded_image(image.clone(), opacity)),
+            Shape::LevelOfDetail(lod) => Shape::LevelOfDetail(LevelOfDetail {
+                local_transform: lod.local_transform,
+                detailed: Box::new(lod.detailed.faded(opacity)),
+                simplified: lod
+                    .simplified
+                    .iter()
+                    .map(|(size, shape)| (*size, shape.faded(opacity)))
+                    .collect(),
+            }),
+            Shape::Dynamic {
+                local_transform,
+                shaper,
+            } => Shape::Group(Group {
+                local_transform: *local_transform,
+                shapes: vec![shaper().faded(opacity)],
+                metadata: vec![],
+            }),
+            // Other markers are filled with the stroke, faded with the style
+            Shape::Curve(curve) => {
+                let faded_marker = |marker: Option<Marker>| match marker {
+                    Some(Marker::Custom(marker)) => Some(Marker::Custom(marker.faded(opacity))),
+                    marker => marker,
+                };
+                Shape::Curve(Curve {
+                    start_marker: faded_marker(curve.start_marker.clone()),
+                    end_marker: faded_marker(curve.end_marker.clone()),
+                    ..curve.clone()
+                })
+            }
+            Shape::Ellipse(_) | Shape::Text(_) => self.clone(),
+        }
+    }
+}
+
+/// `image` with the alpha of its pixels multiplied by `opacity`, lazy images being decoded
+fn faded_image(mut image: Image, opacity: f32) -> Image {
+    if let Some(path) = &image.lazy_path {
+        match open_upright(path) {
+            Ok(pixels) => image.image(pixels),
+            // Left to the placeholder of the exporter
+            Err(_) => return image,
+        };
+    }
+
+    let opacity = if opacity.is_nan() {
+        1.
+    } else {
+        opacity.clamp(0., 1.)
+    };
+    let mut pixels = image.image.to_rgba8();
+    for pixel in pixels.pixels_mut() {
+        pixel[3] = (pixel[3] as f32 * opacity).round() as u8;
+    }
+    image.image = DynamicImage::ImageRgba8(pixels);
+
+    image
+}
+
 impl<T: ShapeOp> ShapeOp for Style<T> {
     #[inline]
     fn transform(&mut self, transform_matrix: Transform2<f32>) -> &mut Self {
@@ -352,3 +1740,92 @@ impl<T: ShapeBoundingBox> ShapeBoundingBox for Style<T> {
         self.shape.local_bounding_box()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn markers_are_freed_with_their_curve() {
+        let chevron = MarkerRef::new(dessin2!(Curve!(
+            stroke = (Color::BLACK, 1.),
+            then = Point2::new(-4., 3.),
+            then = Point2::new(0., 0.),
+            then = Point2::new(-4., -3.),
+        )));
+        let marker = Arc::downgrade(&chevron.0);
+        let line = dessin2!(
+            Line!(
+                stroke = (Color::BLACK, 1.),
+                to = [10., 0.],
+                end_marker = Marker::Custom(chevron),
+            ) > ()
+        );
+
+        // Fading the line fades its marker
+        let Shape::Style { shape, .. } = line.faded(0.5) else {
+            unreachable!()
+        };
+        let Shape::Curve(faded) = *shape else {
+            unreachable!()
+        };
+        let Some(Marker::Custom(faded)) = faded.end_marker else {
+            unreachable!()
+        };
+        let Shape::Style { stroke, .. } = faded.shape() else {
+            unreachable!()
+        };
+        assert_eq!(stroke.unwrap().color(), rgba(0, 0, 0, 128));
+
+        drop(line);
+        assert!(marker.upgrade().is_none());
+    }
+
+    #[test]
+    fn gradients_keep_every_stop() {
+        let stops = (0..12)
+            .rev()
+            .map(|idx| (idx as f32 / 11., rgb(idx * 20, 0, 0)))
+            .collect::<Vec<_>>();
+        let gradient = GradientStops::new(&stops);
+
+        assert_eq!(gradient.as_slice().len(), 12);
+        assert_eq!(gradient.as_slice()[0], (0., rgb(0, 0, 0)));
+        assert_eq!(gradient.color_at(1.), Some(rgba(220, 0, 0, 255)));
+        assert_eq!(gradient.color_at(10.5 / 11.), Some(rgba(210, 0, 0, 255)));
+    }
+
+    #[test]
+    fn patterns_are_freed_with_their_fill() {
+        let fill = Fill::dots(Color::RED, 1., 4.);
+        let Fill::Pattern { pattern, .. } = &fill else {
+            unreachable!()
+        };
+        let tile = Arc::downgrade(&pattern.0);
+
+        // Copies share the tile, faded ones have their own
+        let copy = fill.clone();
+        let faded = fill.clone().faded(0.5);
+        assert_eq!(copy, fill);
+        assert_ne!(faded, fill);
+
+        drop((fill, copy, faded));
+        assert!(tile.upgrade().is_none());
+    }
+
+    #[test]
+    fn images_are_freed_with_their_fill() {
+        let fill = Fill::image(DynamicImage::new_rgb8(2, 2), ImageFit::Cover);
+        let Fill::Image { image, .. } = &fill else {
+            unreachable!()
+        };
+        let image = Arc::downgrade(&image.0);
+
+        let faded = fill.clone().faded(0.5);
+        assert!(image.upgrade().is_some());
+
+        drop(fill);
+        drop(faded);
+        assert!(image.upgrade().is_none());
+    }
+}
@@ -0,0 +1,414 @@
+//! Fill `{placeholder}` tokens in texts, so one dessin can be reused for many documents.
+//!
+//! Placeholders are replaced by [`Shape::fill_template`], from anything implementing [`TemplateValues`].
+//! Braces are escaped by doubling them: `{{` and `}}`.
+//!
+//! ```
+//! # use dessin::prelude::*;
+//! use std::collections::HashMap;
+//!
+//! let certificate = dessin2!([
+//! 	Text!(text = "Certificate of completion"),
+//! 	Text!(text = "Awarded to {name}, on {date}", translate = [0., -10.]),
+//! ]);
+//!
+//! let values = HashMap::from([("name", "Ada Lovelace"), ("date", "1843-10-10")]);
+//! let ada = certificate.fill_template(&values).unwrap();
+//! ```
+//!
+//! For bulk documents, [`generate_batch`] fills the template once per record, and exports each result as it goes:
+//!
+//! ```
+//! # use dessin::prelude::*;
+//! use dessin::template::generate_batch;
+//! use std::collections::HashMap;
+//!
+//! let label = dessin2!(Text!(text = "{name}\n{street}\n{city}") > ());
+//! let records = vec![
+//! 	HashMap::from([("name", "Ada"), ("street", "12 Analytical Way"), ("city", "London")]),
+//! 	HashMap::from([("name", "Grace"), ("street", "1 Compiler Road"), ("city", "Arlington")]),
+//! ];
+//!
+//! for label in generate_batch(&label, records, |shape| Ok::<_, ()>(shape.placeholders().len())) {
+//! 	assert_eq!(label.unwrap(), 0);
+//! }
+//! ```
+
+use crate::prelude::*;
+use std::{
+    borrow::{Borrow, Cow},
+    collections::{BTreeMap, HashMap},
+    fmt,
+    hash::Hash,
+};
+
+/// Source of the values of placeholders
+pub trait TemplateValues {
+    /// Value of the placeholder `key`, if any
+    fn value(&self, key: &str) -> Option<Cow<'_, str>>;
+}
+impl<K: Borrow<str> + Hash + Eq, V: AsRef<str>> TemplateValues for HashMap<K, V> {
+    fn value(&self, key: &str) -> Option<Cow<'_, str>> {
+        self.get(key).map(|v| Cow::Borrowed(v.as_ref()))
+    }
+}
+impl<K: Borrow<str> + Ord, V: AsRef<str>> TemplateValues for BTreeMap<K, V> {
+    fn value(&self, key: &str) -> Option<Cow<'_, str>> {
+        self.get(key).map(|v| Cow::Borrowed(v.as_ref()))
+    }
+}
+impl<T: TemplateValues + ?Sized> TemplateValues for &T {
+    fn value(&self, key: &str) -> Option<Cow<'_, str>> {
+        (**self).value(key)
+    }
+}
+
+/// What to do with a placeholder without value
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub enum MissingKey {
+    /// Fail with [`TemplateError::MissingKey`]
+    #[default]
+    Error,
+    /// Leave the placeholder as is, braces included
+    Keep,
+    /// Remove the placeholder
+    Empty,
+}
+
+/// Error while filling a template
+#[derive(Debug, Clone, PartialEq)]
+pub enum TemplateError {
+    /// A placeholder has no value, with [`MissingKey::Error`]
+    MissingKey(String),
+}
+impl fmt::Display for TemplateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TemplateError::MissingKey(key) => write!(f, "No value for placeholder `{key}`"),
+        }
+    }
+}
+impl std::error::Error for TemplateError {}
+
+/// Error of a record in [`generate_batch`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum BatchError<E> {
+    /// The template could not be filled with the record
+    Template {
+        /// Index of the record
+        record: usize,
+        /// Cause
+        error: TemplateError,
+    },
+    /// The filled template could not be exported
+    Export {
+        /// Index of the record
+        record: usize,
+        /// Cause
+        error: E,
+    },
+}
+impl<E: fmt::Debug> fmt::Display for BatchError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BatchError::Template { record, error } => write!(f, "Record {record}: {error}"),
+            BatchError::Export { record, error } => write!(f, "Record {record}: {error:?}"),
+        }
+    }
+}
+impl<E: fmt::Debug> std::error::Error for BatchError<E> {}
+
+/// Piece of a templated text
+enum Token<'a> {
+    Literal(&'a str),
+    Placeholder(&'a str),
+}
+
+/// Split a text in literals and placeholders.
+///
+/// An unclosed brace is kept as a literal.
+fn tokenize(text: &str) -> Vec<Token<'_>> {
+    let mut tokens = vec![];
+    let mut rest = text;
+
+    while let Some(idx) = rest.find(['{', '}']) {
+        tokens.push(Token::Literal(&rest[..idx]));
+
+        let after = &rest[idx + 1..];
+        if after.starts_with(&rest[idx..=idx]) {
+            // Escaped brace
+            tokens.push(Token::Literal(&rest[idx..=idx]));
+            rest = &after[1..];
+        } else if rest[idx..].starts_with('{') {
+            match after.find(['{', '}']) {
+                Some(end) if after[end..].starts_with('}') => {
+                    tokens.push(Token::Placeholder(after[..end].trim()));
+                    rest = &after[end + 1..];
+                }
+                _ => {
+                    tokens.push(Token::Literal("{"));
+                    rest = after;
+                }
+            }
+        } else {
+            tokens.push(Token::Literal("}"));
+            rest = after;
+        }
+    }
+    tokens.push(Token::Literal(rest));
+
+    tokens
+}
+
+/// Replace the placeholders of a text
+pub fn fill_text<V: TemplateValues>(
+    text: &str,
+    values: &V,
+    missing_key: MissingKey,
+) -> Result<String, TemplateError> {
+    let mut filled = String::with_capacity(text.len());
+
+    for token in tokenize(text) {
+        match token {
+            Token::Literal(literal) => filled.push_str(literal),
+            Token::Placeholder(key) => match (values.value(key), missing_key) {
+                (Some(value), _) => filled.push_str(&value),
+                (None, MissingKey::Error) => {
+                    return Err(TemplateError::MissingKey(key.to_string()))
+                }
+                (None, MissingKey::Keep) => {
+                    filled.push('{');
+                    filled.push_str(key);
+                    filled.push('}');
+                }
+                (None, MissingKey::Empty) => {}
+            },
+        }
+    }
+
+    Ok(filled)
+}
+
+impl Shape {
+    /// Replace the placeholders of every text, failing on placeholders without value
+    #[inline]
+    pub fn fill_template<V: TemplateValues>(&self, values: &V) -> Result<Shape, TemplateError> {
+        self.fill_template_with_options(values, MissingKey::Error)
+    }
+
+    /// Replace the placeholders of every text.
+    ///
+    /// [`Dynamic`][Shape::Dynamic] shapes are evaluated, as their texts are only known at export.
+    pub fn fill_template_with_options<V: TemplateValues>(
+        &self,
+        values: &V,
+        missing_key: MissingKey,
+    ) -> Result<Shape, TemplateError> {
+        let shape = match self {
+            Shape::Group(Group {
+                local_transform,
+                shapes,
+                metadata,
+            }) => Shape::Group(Group {
+                local_transform: *local_transform,
+                shapes: shapes
+                    .iter()
+                    .map(|shape| shape.fill_template_with_options(values, missing_key))
+                    .collect::<Result<_, _>>()?,
+                metadata: metadata.clone(),
+            }),
+            Shape::Style {
+                fill,
+                stroke,
+                stroke_alignment,
+                filter,
+                opacity,
+                blend_mode,
+                shape,
+            } => Shape::Style {
+                fill: fill.clone(),
+                stroke: *stroke,
+                stroke_alignment: *stroke_alignment,
+                filter: *filter,
+                opacity: *opacity,
+                blend_mode: *blend_mode,
+                shape: Box::new(shape.fill_template_with_options(values, missing_key)?),
+            },
+            Shape::Text(text) => Shape::Text(Text {
+                text: fill_text(&text.text, values, missing_key)?,
+                ..text.clone()
+            }),
+            Shape::LevelOfDetail(lod) => Shape::LevelOfDetail(LevelOfDetail {
+                local_transform: lod.local_transform,
+                detailed: Box::new(
+                    lod.detailed
+                        .fill_template_with_options(values, missing_key)?,
+                ),
+                simplified: lod
+                    .simplified
+                    .iter()
+                    .map(|(size, shape)| {
+                        Ok((
+                            *size,
+                            shape.fill_template_with_options(values, missing_key)?,
+                        ))
+                    })
+                    .collect::<Result<_, _>>()?,
+            }),
+            Shape::Dynamic {
+                local_transform,
+                shaper,
+            } => shaper()
+                .fill_template_with_options(values, missing_key)?
+                .with_transform(*local_transform),
+            Shape::Ellipse(_) | Shape::Image(_) | Shape::Curve(_) => self.clone(),
+        };
+
+        Ok(shape)
+    }
+
+    /// Name of every placeholder in the texts of this dessin, in order of appearance and without duplicates
+    pub fn placeholders(&self) -> Vec<String> {
+        fn collect(shape: &Shape, acc: &mut Vec<String>) {
+            match shape {
+                Shape::Group(Group { shapes, .. }) => {
+                    shapes.iter().for_each(|shape| collect(shape, acc));
+                }
+                Shape::Style { shape, .. } => collect(shape, acc),
+                Shape::Text(text) => {
+                    for token in tokenize(&text.text) {
+                        if let Token::Placeholder(key) = token {
+                            if !acc.iter().any(|k| k == key) {
+                                acc.push(key.to_string());
+                            }
+                        }
+                    }
+                }
+                Shape::LevelOfDetail(lod) => {
+                    collect(&lod.detailed, acc);
+                    lod.simplified
+                        .iter()
+                        .for_each(|(_, shape)| collect(shape, acc));
+                }
+                Shape::Dynamic { shaper, .. } => collect(&shaper(), acc),
+                Shape::Ellipse(_) | Shape::Image(_) | Shape::Curve(_) => {}
+            }
+        }
+
+        let mut placeholders = vec![];
+        collect(self, &mut placeholders);
+        placeholders
+    }
+}
+
+/// Fill `template` with each record, and export it with `export`.
+///
+/// Records are processed lazily, one at a time, so a batch can be written out as it is generated.
+/// A failing record doesn't stop the batch.
+pub fn generate_batch<'a, V, I, F, T, E>(
+    template: &'a Shape,
+    records: I,
+    mut export: F,
+) -> impl Iterator<Item = Result<T, BatchError<E>>> + 'a
+where
+    V: TemplateValues,
+    I: IntoIterator<Item = V>,
+    I::IntoIter: 'a,
+    F: FnMut(&Shape) -> Result<T, E> + 'a,
+{
+    records
+        .into_iter()
+        .enumerate()
+        .map(move |(record, values)| {
+            let shape = template
+                .fill_template(&values)
+                .map_err(|error| BatchError::Template { record, error })?;
+
+            export(&shape).map_err(|error| BatchError::Export { record, error })
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fill_placeholders() {
+        let values = HashMap::from([("name", "Ada"), ("n", "3")]);
+
+        assert_eq!(
+            fill_text("Hello {name}, {{n}} is { n }", &values, MissingKey::Error).unwrap(),
+            "Hello Ada, {n} is 3"
+        );
+        assert_eq!(
+            fill_text("Unclosed {name", &values, MissingKey::Error).unwrap(),
+            "Unclosed {name"
+        );
+    }
+
+    #[test]
+    fn missing_key_policy() {
+        let values = BTreeMap::from([("name", "Ada")]);
+
+        assert_eq!(
+            fill_text("{name} {title}", &values, MissingKey::Error),
+            Err(TemplateError::MissingKey("title".to_string()))
+        );
+        assert_eq!(
+            fill_text("{name} {title}", &values, MissingKey::Keep).unwrap(),
+            "Ada {title}"
+        );
+        assert_eq!(
+            fill_text("{name} {title}", &values, MissingKey::Empty).unwrap(),
+            "Ada "
+        );
+    }
+
+    #[test]
+    fn fill_dessin() {
+        let template = dessin2!([
+            Text!(fill = Color::BLACK, text = "{name}"),
+            [Text(text = "{date} by {name}")],
+        ]);
+        assert_eq!(template.placeholders(), vec!["name", "date"]);
+
+        let filled = template
+            .fill_template(&HashMap::from([("name", "Ada"), ("date", "today")]))
+            .unwrap();
+        assert!(filled.placeholders().is_empty());
+
+        let expected = dessin2!([
+            Text!(fill = Color::BLACK, text = "Ada"),
+            [Text(text = "today by Ada")],
+        ]);
+        assert!(filled.semantic_eq(&expected, 10e-6));
+    }
+
+    #[test]
+    fn batch_keeps_going() {
+        let template = dessin2!(Text(text = "{name}") > ());
+        let records = vec![
+            HashMap::from([("name", "Ada")]),
+            HashMap::new(),
+            HashMap::from([("name", "Grace")]),
+        ];
+
+        let outputs = generate_batch(&template, records, |shape| match shape {
+            Shape::Text(text) => Ok(text.text.clone()),
+            _ => Err(()),
+        })
+        .collect::<Vec<_>>();
+
+        assert_eq!(
+            outputs,
+            vec![
+                Ok("Ada".to_string()),
+                Err(BatchError::Template {
+                    record: 1,
+                    error: TemplateError::MissingKey("name".to_string())
+                }),
+                Ok("Grace".to_string()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,467 @@
+//! Outline of the tree of a dessin.
+//!
+//! Macros build groups, styles and transforms implicitly, which makes it hard to tell what a dessin is made of.
+//! [`Shape::tree`] describes every node of the tree: its kind, transform, style, metadata and bounding box.
+//! It prints as indented text, and [`TreeNode::to_json`] writes it as JSON for other tools.
+//!
+//! ```
+//! # use dessin::prelude::*;
+//! let dessin = dessin2!([
+//! 	Circle!(fill = Color::RED, radius = 10.),
+//! 	Text!(text = "Hello", translate = [0., 15.]),
+//! ]);
+//!
+//! let tree = dessin.tree();
+//! assert_eq!(tree.kind, "group");
+//! assert_eq!(tree.children[0].style, vec![("fill", "#FF0000".to_string())]);
+//!
+//! // group [-11.29, -10; 11.29, 20]
+//! //   style fill=#FF0000 [-10, -10; 10, 10]
+//! //     ellipse scale=(20, 20) [-10, -10; 10, 10]
+//! //   text "Hello" translate=(0, 15) [-11.29, 10; 11.29, 20]
+//! println!("{tree}");
+//!
+//! assert!(tree.to_json().starts_with(r#"{"kind":"group","#));
+//! ```
+
+use crate::prelude::*;
+use nalgebra::{Transform2, Vector2};
+use std::fmt::{self, Write};
+
+/// Position, rotation and scale of a transform
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TransformSummary {
+    /// Translation
+    pub translation: Vector2<f32>,
+    /// Rotation, applied after the scale
+    pub rotation: Angle,
+    /// Scale along each axis, negative on Y for mirrored transforms
+    pub scale: Vector2<f32>,
+}
+impl TransformSummary {
+    /// Decompose `transform`, shears are lost
+    pub fn new(transform: &Transform2<f32>) -> Self {
+        let m = transform.matrix();
+        let x_axis = Vector2::new(m[(0, 0)], m[(1, 0)]);
+        let y_axis = Vector2::new(m[(0, 1)], m[(1, 1)]);
+        let determinant = x_axis.x * y_axis.y - x_axis.y * y_axis.x;
+
+        TransformSummary {
+            translation: Vector2::new(m[(0, 2)], m[(1, 2)]),
+            rotation: Angle::radians(x_axis.y.atan2(x_axis.x)),
+            scale: Vector2::new(x_axis.magnitude(), y_axis.magnitude().copysign(determinant)),
+        }
+    }
+
+    /// Whether the transform does nothing
+    pub fn is_identity(&self) -> bool {
+        self.translation == Vector2::zeros()
+            && self.rotation.as_radians() == 0.
+            && self.scale == Vector2::new(1., 1.)
+    }
+}
+
+/// Node of the outline of a dessin, see the [module documentation][self]
+#[derive(Debug, Clone, PartialEq)]
+pub struct TreeNode {
+    /// Kind of shape: `group`, `style`, `ellipse`, `image`, `text`, `curve`, `level_of_detail` or `dynamic`
+    pub kind: &'static str,
+    /// What the shape draws: text, size of an image, number of keypoints of a curve...
+    pub details: Option<String>,
+    /// Local transform of the shape, `None` for styles and identity transforms
+    pub transform: Option<TransformSummary>,
+    /// Fill, stroke and filter of styles, in short
+    pub style: Vec<(&'static str, String)>,
+    /// Metadata of groups
+    pub metadata: Vec<(String, String)>,
+    /// Bounding box, in the coordinates of the root
+    pub bounding_box: BoundingBox<Straight>,
+    /// Nested shapes, including the representations of levels of detail and the shape generated by dynamics
+    pub children: Vec<TreeNode>,
+}
+impl TreeNode {
+    fn new(shape: &Shape, parent_transform: &Transform2<f32>) -> Self {
+        let mut node = TreeNode {
+            kind: "",
+            details: None,
+            transform: None,
+            style: vec![],
+            metadata: vec![],
+            bounding_box: shape.global_bounding_box(parent_transform).straigthen(),
+            children: vec![],
+        };
+
+        let local_transform = match shape {
+            Shape::Style { .. } => None,
+            shape => Some(shape.local_transform()),
+        };
+        node.transform = local_transform
+            .map(TransformSummary::new)
+            .filter(|summary| !summary.is_identity());
+        let transform = parent_transform * local_transform.copied().unwrap_or_default();
+
+        match shape {
+            Shape::Group(Group {
+                shapes, metadata, ..
+            }) => {
+                node.kind = "group";
+                node.metadata = metadata.clone();
+                node.children = shapes
+                    .iter()
+                    .map(|shape| TreeNode::new(shape, &transform))
+                    .collect();
+            }
+            Shape::Style {
+                fill,
+                stroke,
+                stroke_alignment,
+                filter,
+                opacity,
+                blend_mode,
+                shape,
+            } => {
+                node.kind = "style";
+                if let Some(fill) = fill {
+                    node.style.push(("fill", describe_fill(fill)));
+                }
+                if let Some(stroke) = stroke {
+                    node.style.push(("stroke", describe_stroke(stroke)));
+                    if *stroke_alignment != StrokeAlignment::Center {
+                        node.style
+                            .push(("stroke_alignment", format!("{stroke_alignment:?}")));
+                    }
+                }
+                if let Some(filter) = filter {
+                    node.style.push(("filter", format!("{filter:?}")));
+                }
+                if *opacity != 1. {
+                    node.style.push(("opacity", opacity.to_string()));
+                }
+                if *blend_mode != BlendMode::Normal {
+                    node.style.push(("blend_mode", format!("{blend_mode:?}")));
+                }
+                node.children = vec![TreeNode::new(shape, parent_transform)];
+            }
+            Shape::Ellipse(_) => node.kind = "ellipse",
+            Shape::Image(Image {
+                image, lazy_path, ..
+            }) => {
+                node.kind = "image";
+                node.details = Some(match lazy_path {
+                    Some(path) => format!("{:?}", path.display().to_string()),
+                    None => format!("{}x{} px", image.width(), image.height()),
+                });
+            }
+            Shape::Text(Text { text, .. }) => {
+                node.kind = "text";
+                node.details = Some(format!("{text:?}"));
+            }
+            Shape::Curve(Curve {
+                keypoints, closed, ..
+            }) => {
+                node.kind = "curve";
+                node.details = Some(format!(
+                    "{} keypoints{}",
+                    keypoints.len(),
+                    if *closed { ", closed" } else { "" }
+                ));
+            }
+            Shape::LevelOfDetail(lod) => {
+                node.kind = "level_of_detail";
+                node.details = Some(format!("{} simplified", lod.simplified.len()));
+                node.children = std::iter::once(lod.detailed.as_ref())
+                    .chain(lod.simplified.iter().map(|(_, shape)| shape))
+                    .map(|shape| TreeNode::new(shape, &transform))
+                    .collect();
+            }
+            Shape::Dynamic { shaper, .. } => {
+                node.kind = "dynamic";
+                node.children = vec![TreeNode::new(&shaper(), &transform)];
+            }
+        }
+
+        node
+    }
+
+    /// The tree as JSON, on a single line.
+    ///
+    /// Each node is an object with `kind`, `details`, `transform`, `style`, `metadata`, `bounding_box` and `children`.
+    /// Bounding boxes are `[left, bottom, right, top]`, and metadata a list of `[key, value]`.
+    pub fn to_json(&self) -> String {
+        let mut json = String::new();
+        self.write_json(&mut json);
+        json
+    }
+
+    fn write_json(&self, json: &mut String) {
+        json.push_str("{\"kind\":");
+        json_string(json, self.kind);
+
+        json.push_str(",\"details\":");
+        match &self.details {
+            Some(details) => json_string(json, details),
+            None => json.push_str("null"),
+        }
+
+        json.push_str(",\"transform\":");
+        match &self.transform {
+            Some(TransformSummary {
+                translation,
+                rotation,
+                scale,
+            }) => {
+                json.push_str("{\"translation\":");
+                json_numbers(json, &[translation.x, translation.y]);
+                json.push_str(",\"rotation\":");
+                json_numbers(json, &[rotation.as_degrees()]);
+                json.push_str(",\"scale\":");
+                json_numbers(json, &[scale.x, scale.y]);
+                json.push('}');
+            }
+            None => json.push_str("null"),
+        }
+
+        json.push_str(",\"style\":{");
+        for (idx, (key, value)) in self.style.iter().enumerate() {
+            if idx > 0 {
+                json.push(',');
+            }
+            json_string(json, key);
+            json.push(':');
+            json_string(json, value);
+        }
+
+        json.push_str("},\"metadata\":[");
+        for (idx, (key, value)) in self.metadata.iter().enumerate() {
+            if idx > 0 {
+                json.push(',');
+            }
+            json.push('[');
+            json_string(json, key);
+            json.push(',');
+            json_string(json, value);
+            json.push(']');
+        }
+
+        let bb = &self.bounding_box;
+        json.push_str("],\"bounding_box\":");
+        json_numbers(json, &[bb.left(), bb.bottom(), bb.right(), bb.top()]);
+
+        json.push_str(",\"children\":[");
+        for (idx, child) in self.children.iter().enumerate() {
+            if idx > 0 {
+                json.push(',');
+            }
+            child.write_json(json);
+        }
+        json.push_str("]}");
+    }
+
+    fn write_text(&self, f: &mut fmt::Formatter<'_>, depth: usize) -> fmt::Result {
+        write!(f, "{:indent$}{}", "", self.kind, indent = depth * 2)?;
+
+        if let Some(details) = &self.details {
+            write!(f, " {details}")?;
+        }
+
+        if let Some(TransformSummary {
+            translation,
+            rotation,
+            scale,
+        }) = &self.transform
+        {
+            if *translation != Vector2::zeros() {
+                write!(
+                    f,
+                    " translate=({}, {})",
+                    Number(translation.x),
+                    Number(translation.y)
+                )?;
+            }
+            if rotation.as_radians() != 0. {
+                write!(f, " rotate={}°", Number(rotation.as_degrees()))?;
+            }
+            if *scale != Vector2::new(1., 1.) {
+                write!(f, " scale=({}, {})", Number(scale.x), Number(scale.y))?;
+            }
+        }
+
+        for (key, value) in &self.style {
+            write!(f, " {key}={value}")?;
+        }
+        for (key, value) in &self.metadata {
+            write!(f, " {key}={value:?}")?;
+        }
+
+        let bb = &self.bounding_box;
+        writeln!(
+            f,
+            " [{}, {}; {}, {}]",
+            Number(bb.left()),
+            Number(bb.bottom()),
+            Number(bb.right()),
+            Number(bb.top())
+        )?;
+
+        for child in &self.children {
+            child.write_text(f, depth + 1)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// One line per node, indented by depth, with the bounding box as `[left, bottom; right, top]`
+impl fmt::Display for TreeNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write_text(f, 0)
+    }
+}
+
+impl Shape {
+    /// Outline of the tree of this dessin, see the [module documentation][crate::tree]
+    pub fn tree(&self) -> TreeNode {
+        TreeNode::new(self, &Transform2::identity())
+    }
+}
+
+fn describe_fill(fill: &Fill) -> String {
+    match fill {
+        Fill::Color(color) => color.to_string(),
+        Fill::LinearGradient { stops, .. } => {
+            format!("linear gradient of {} stops", stops.as_slice().len())
+        }
+        Fill::RadialGradient { stops, .. } => {
+            format!("radial gradient of {} stops", stops.as_slice().len())
+        }
+        Fill::Pattern { .. } => "pattern".to_string(),
+        Fill::Image { fit, .. } => format!("image ({fit:?})"),
+    }
+}
+
+fn describe_stroke(stroke: &Stroke) -> String {
+    let dashed = match stroke {
+        Stroke::Full { .. } => "",
+        Stroke::Dashed { .. } | Stroke::DashPattern { .. } => " dashed",
+    };
+    let cap = match stroke.cap() {
+        LineCap::Butt => "",
+        LineCap::Round => " round cap",
+        LineCap::Square => " square cap",
+    };
+    let join = match stroke.join() {
+        LineJoin::Miter => "",
+        LineJoin::Round => " round join",
+        LineJoin::Bevel => " bevel join",
+    };
+    format!(
+        "{} {}{dashed}{cap}{join}",
+        stroke.color(),
+        Number(stroke.width())
+    )
+}
+
+/// Number with at most 2 decimals, without trailing zeros
+struct Number(f32);
+impl fmt::Display for Number {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rounded = format!("{:.2}", self.0);
+        let trimmed = rounded.trim_end_matches('0').trim_end_matches('.');
+        match trimmed {
+            "-0" => write!(f, "0"),
+            trimmed => write!(f, "{trimmed}"),
+        }
+    }
+}
+
+fn json_string(json: &mut String, value: &str) {
+    json.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(json, "\\u{:04x}", c as u32);
+            }
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+}
+
+/// Numbers of a JSON array, or a lone number, `null` if not finite
+fn json_numbers(json: &mut String, numbers: &[f32]) {
+    let write = |json: &mut String, n: f32| {
+        if n.is_finite() {
+            let _ = write!(json, "{n}");
+        } else {
+            json.push_str("null");
+        }
+    };
+
+    match numbers {
+        [n] => write(json, *n),
+        numbers => {
+            json.push('[');
+            for (idx, n) in numbers.iter().enumerate() {
+                if idx > 0 {
+                    json.push(',');
+                }
+                write(json, *n);
+            }
+            json.push(']');
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn kinds_and_transforms() {
+        let dessin = dessin2!([
+            Rectangle!(
+                stroke = (Color::BLACK, 0.5),
+                width = 4.,
+                height = 2.,
+                translate = [10., 0.],
+            ),
+            Text(text = "Say \"hi\"", rotate = Angle::degrees(90.)),
+        ]);
+
+        let tree = dessin.tree();
+        assert_eq!(tree.children.len(), 2);
+
+        let style = &tree.children[0];
+        assert_eq!(style.kind, "style");
+        assert_eq!(style.transform, None);
+        assert_eq!(style.style, vec![("stroke", "#000000 0.5".to_string())]);
+
+        let curve = &style.children[0];
+        assert_eq!(curve.kind, "curve");
+        assert_eq!(curve.details.as_deref(), Some("4 keypoints, closed"));
+        assert_eq!(curve.transform.unwrap().translation, Vector2::new(10., 0.));
+
+        let text = &tree.children[1];
+        let rotation = text.transform.unwrap().rotation.as_degrees();
+        assert!((rotation - 90.).abs() < 10e-4);
+
+        let json = tree.to_json();
+        assert!(json.contains(r#""details":"\"Say \\\"hi\\\"\"""#));
+        assert!(json.contains(r##""style":{"stroke":"#000000 0.5"}"##));
+    }
+
+    #[test]
+    fn text_outline() {
+        let dessin = dessin2!(Circle!(fill = Color::RED, radius = 10.) > ());
+
+        assert_eq!(
+            dessin.tree().to_string(),
+            "style fill=#FF0000 [-10, -10; 10, 10]\n  ellipse scale=(20, 20) [-10, -10; 10, 10]\n"
+        );
+    }
+}
@@ -0,0 +1,353 @@
+//! Lint a dessin before export.
+//!
+//! Most mistakes in a dessin don't fail, they silently render nothing, or render garbage.
+//! [`Shape::validate`] walks the dessin like an export would, and reports what looks wrong.
+//!
+//! ```
+//! # use dessin::prelude::*;
+//! use dessin::validate::ValidationWarning;
+//!
+//! let dessin = dessin2!([
+//! 	Circle!(stroke = (Color::BLACK, 0.001), radius = 10.),
+//! 	Text!(text = "Hello", font = "NotRegistered"),
+//! ]);
+//!
+//! let warnings = dessin.validate();
+//! assert!(matches!(warnings[0], ValidationWarning::ThinStroke { .. }));
+//! assert!(matches!(warnings[1], ValidationWarning::UnregisteredFont { .. }));
+//! ```
+
+use crate::{
+    export::{Export, Exporter},
+    font::{self, FontRef},
+    prelude::*,
+};
+use nalgebra::Point2;
+use std::{collections::HashMap, convert::Infallible, fmt};
+
+/// Something that looks wrong in a dessin
+#[derive(Debug, Clone, PartialEq)]
+pub enum ValidationWarning {
+    /// A position is NaN or infinite, most likely because of a transform
+    NonFinitePosition,
+    /// A curve has less than two points, or two consecutive identical points
+    DegenerateCurve {
+        /// Curve, in global coordinates
+        curve: CurvePosition,
+    },
+    /// A text uses a font that was not added with [`add_font`][crate::font::add_font]
+    UnregisteredFont {
+        /// Font used by the text
+        font: FontRef,
+    },
+    /// A fill is applied on a shape with no area, nothing will be visible
+    ZeroAreaFill,
+    /// A stroke, once transformed, is thinner than [`ValidationOptions::min_stroke_width`]
+    ThinStroke {
+        /// Width of the stroke, in global coordinates
+        width: f32,
+    },
+    /// A shape is not contained in a [`SafeArea`][crate::contrib::SafeArea] of an [`Artboard`][crate::contrib::Artboard]
+    CrossesSafeArea {
+        /// Name of the safe area
+        area: String,
+        /// Bounding box of the shape, in the coordinates of the artboard
+        bounding_box: BoundingBox<Straight>,
+    },
+    /// A text uses a font whose license doesn't allow embedding it
+    RestrictedFont {
+        /// Font used by the text
+        font: FontRef,
+        /// Weight of the font
+        font_weight: FontWeight,
+    },
+    /// The world isn't drawn at the scale of its [`ScaleStatement`][crate::export::ScaleStatement]
+    ScaleMismatch {
+        /// Ratio of the statement
+        declared: f32,
+        /// Ratio the world is drawn at
+        actual: f32,
+    },
+}
+impl fmt::Display for ValidationWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValidationWarning::NonFinitePosition => write!(f, "Position is NaN or infinite"),
+            ValidationWarning::DegenerateCurve { .. } => {
+                write!(f, "Curve has zero-length segments")
+            }
+            ValidationWarning::UnregisteredFont { font } => {
+                write!(f, "Font `{}` is not registered", font.font_family())
+            }
+            ValidationWarning::ZeroAreaFill => write!(f, "Filled shape has no area"),
+            ValidationWarning::ThinStroke { width } => write!(f, "Stroke of {width} is too thin"),
+            ValidationWarning::CrossesSafeArea { area, .. } => {
+                write!(f, "Shape crosses the safe area `{area}`")
+            }
+            ValidationWarning::RestrictedFont { font, font_weight } => write!(
+                f,
+                "Font `{}` doesn't allow embedding",
+                font.name(*font_weight)
+            ),
+            ValidationWarning::ScaleMismatch { declared, actual } => {
+                write!(f, "Drawn at 1:{actual} instead of 1:{declared}")
+            }
+        }
+    }
+}
+
+/// Thresholds of the validation
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ValidationOptions {
+    /// Strokes thinner than this width are reported.
+    ///
+    /// Default to 0.1, a hairline in millimeters.
+    pub min_stroke_width: f32,
+    /// Lengths and areas under this value are considered null
+    pub epsilon: f32,
+}
+impl Default for ValidationOptions {
+    fn default() -> Self {
+        ValidationOptions {
+            min_stroke_width: 0.1,
+            epsilon: 10e-6,
+        }
+    }
+}
+
+impl Shape {
+    /// Report everything that looks wrong in this dessin, with the default [`ValidationOptions`]
+    #[inline]
+    pub fn validate(&self) -> Vec<ValidationWarning> {
+        self.validate_with_options(ValidationOptions::default())
+    }
+
+    /// Report everything that looks wrong in this dessin
+    pub fn validate_with_options(&self, options: ValidationOptions) -> Vec<ValidationWarning> {
+        let mut validator = Validator {
+            options,
+            fills: vec![],
+            fonts: font::font_names(),
+            restricted_fonts: HashMap::new(),
+            warnings: vec![],
+        };
+
+        match self.write_into_exporter(&mut validator, &Default::default()) {
+            Ok(()) => {}
+            Err(infallible) => match infallible {},
+        }
+
+        validator.warnings
+    }
+}
+
+/// Walks the dessin like an [`Exporter`], but only collects warnings
+struct Validator {
+    options: ValidationOptions,
+    fills: Vec<bool>,
+    fonts: Vec<String>,
+    /// Whether each font used is restricted
+    restricted_fonts: HashMap<(FontRef, FontWeight), bool>,
+    warnings: Vec<ValidationWarning>,
+}
+impl Validator {
+    fn is_filled(&self) -> bool {
+        self.fills.last().copied().unwrap_or(false)
+    }
+
+    fn check_finite<'a, I: IntoIterator<Item = &'a Point2<f32>>>(&mut self, points: I) -> bool {
+        let is_finite = points
+            .into_iter()
+            .all(|p| p.x.is_finite() && p.y.is_finite());
+
+        if !is_finite {
+            self.warnings.push(ValidationWarning::NonFinitePosition);
+        }
+
+        is_finite
+    }
+}
+
+impl Exporter for Validator {
+    type Error = Infallible;
+
+    fn start_style(&mut self, style: StylePosition) -> Result<(), Self::Error> {
+        if let Some(
+            Stroke::Full { width, .. }
+            | Stroke::Dashed { width, .. }
+            | Stroke::DashPattern { width, .. },
+        ) = style.stroke
+        {
+            if width < self.options.min_stroke_width {
+                self.warnings.push(ValidationWarning::ThinStroke { width });
+            }
+        }
+
+        self.fills.push(style.fill.is_some());
+
+        Ok(())
+    }
+
+    fn end_style(&mut self) -> Result<(), Self::Error> {
+        self.fills.pop();
+        Ok(())
+    }
+
+    fn export_image(&mut self, image: ImagePosition) -> Result<(), Self::Error> {
+        self.check_finite([&image.center]);
+        Ok(())
+    }
+
+    fn export_ellipse(&mut self, ellipse: EllipsePosition) -> Result<(), Self::Error> {
+        if self.check_finite([&ellipse.center])
+            && self.is_filled()
+            && ellipse.semi_major_axis * ellipse.semi_minor_axis <= self.options.epsilon
+        {
+            self.warnings.push(ValidationWarning::ZeroAreaFill);
+        }
+
+        Ok(())
+    }
+
+    fn export_curve(&mut self, curve: CurvePosition) -> Result<(), Self::Error> {
+        let points = curve.flatten(self.options.epsilon.max(0.01));
+        if !self.check_finite(&points) {
+            return Ok(());
+        }
+
+        let epsilon = self.options.epsilon;
+        let degenerate = points.len() < 2
+            || points
+                .windows(2)
+                .any(|w| (w[1] - w[0]).magnitude() <= epsilon);
+
+        if self.is_filled() {
+            // Shoelace formula, fills implicitly close the curve
+            let area = points
+                .iter()
+                .zip(points.iter().cycle().skip(1))
+                .map(|(a, b)| a.x * b.y - b.x * a.y)
+                .sum::<f32>();
+
+            if area.abs() / 2. <= epsilon {
+                self.warnings.push(ValidationWarning::ZeroAreaFill);
+            }
+        }
+
+        if degenerate {
+            self.warnings
+                .push(ValidationWarning::DegenerateCurve { curve });
+        }
+
+        Ok(())
+    }
+
+    fn export_text(&mut self, text: TextPosition) -> Result<(), Self::Error> {
+        self.check_finite([&text.reference_start]);
+
+        if let Some(font) = text.font {
+            if !self.fonts.iter().any(|name| name == font.font_family()) {
+                self.warnings
+                    .push(ValidationWarning::UnregisteredFont { font: font.clone() });
+                return Ok(());
+            }
+        }
+
+        let key = (text.font.clone().unwrap_or_default(), text.font_weight);
+        if !self.restricted_fonts.contains_key(&key) {
+            let permission = font::get(key.0.clone()).get(key.1).embedding_permission();
+            let restricted = permission == Some(font::EmbeddingPermission::Restricted);
+            if restricted {
+                self.warnings.push(ValidationWarning::RestrictedFont {
+                    font: key.0.clone(),
+                    font_weight: key.1,
+                });
+            }
+            self.restricted_fonts.insert(key, restricted);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nalgebra::{Scale2, Transform2};
+
+    #[test]
+    fn valid_dessin() {
+        let dessin = dessin2!([
+            Circle!(fill = Color::RED, radius = 10.),
+            Rectangle!(stroke = (Color::BLACK, 0.5), width = 10., height = 5.),
+        ]);
+
+        assert_eq!(dessin.validate(), vec![]);
+    }
+
+    #[test]
+    fn degenerate_curve() {
+        let dessin = dessin2!(Line(from = [1., 1.], to = [1., 1.]) > ());
+
+        assert!(matches!(
+            dessin.validate()[..],
+            [ValidationWarning::DegenerateCurve { .. }]
+        ));
+    }
+
+    #[test]
+    fn zero_area_fill() {
+        let dessin = dessin2!(Rectangle!(fill = Color::RED, width = 10., height = 0.) > ());
+
+        assert!(dessin.validate().contains(&ValidationWarning::ZeroAreaFill));
+    }
+
+    #[test]
+    fn nan_transform() {
+        let nan: Transform2<f32> = nalgebra::convert(Scale2::new(f32::NAN, 1.));
+        let dessin = dessin2!(Circle(radius = 1.) > (transform = nan));
+
+        assert!(dessin
+            .validate()
+            .contains(&ValidationWarning::NonFinitePosition));
+    }
+
+    #[test]
+    fn restricted_font() {
+        // Table directory with only an OS/2 table, whose fsType restricts embedding
+        let mut bytes = vec![0, 1, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0];
+        bytes.extend(b"OS/2");
+        bytes.extend([0; 4]);
+        bytes.extend(28_u32.to_be_bytes());
+        bytes.extend(10_u32.to_be_bytes());
+        bytes.extend([0, 4, 0, 0, 0, 0, 0, 0, 0, 2]);
+
+        let font = font::Font::TTF(bytes);
+        assert_eq!(
+            font.embedding_permission(),
+            Some(font::EmbeddingPermission::Restricted)
+        );
+        let font = font::add_font(
+            "Restricted",
+            font::FontGroup {
+                regular: font,
+                bold: None,
+                italic: None,
+                bold_italic: None,
+            },
+        );
+
+        let dessin = dessin2!([
+            Text!(text = "a", font = font.clone()),
+            Text!(text = "b", font = font.clone()),
+            Text!(text = "c"),
+        ]);
+        assert_eq!(
+            dessin.validate(),
+            vec![ValidationWarning::RestrictedFont {
+                font,
+                font_weight: FontWeight::Regular,
+            }]
+        );
+    }
+}
@@ -17,26 +17,14 @@ impl From<InnerBubbleRing> for Shape {
     fn from(_: InnerBubbleRing) -> Self {
         let ring_strip = dessin2!(
             [
+                Circle!(stroke = Stroke::from((c(200), 0.1)), radius = 1.,),
                 Circle!(
-                    stroke = Stroke::Full {
-                        color: c(200),
-                        width: 0.1
-                    },
-                    radius = 1.,
-                ),
-                Circle!(
-                    stroke = Stroke::Full {
-                        color: c(150),
-                        width: 0.1
-                    },
+                    stroke = Stroke::from((c(150), 0.1)),
                     radius = 0.5,
                     translate = Translation2::new(2., 0.),
                 ),
                 Circle!(
-                    stroke = Stroke::Full {
-                        color: c(100),
-                        width: 0.1
-                    },
+                    stroke = Stroke::from((c(100), 0.1)),
                     radius = 0.25,
                     translate = Translation2::new(3.2, 0.),
                 ),
@@ -105,10 +93,7 @@ impl From<TimerRing> for Shape {
                         rotate = Rotation2::new(x as f32 * PI / 160.)
                     ))
                 },
-            ] > !(stroke = Stroke::Full {
-                color: C,
-                width: 0.2
-            })
+            ] > !(stroke = Stroke::from((C, 0.2)))
         )
         .into()
     }
@@ -120,26 +105,14 @@ impl From<ThreeColoredRing> for Shape {
     fn from(_: ThreeColoredRing) -> Self {
         dessin2!([
             Circle!(
-                stroke = Stroke::Full {
-                    color: rgb(0x96, 0x96, 0x96),
-                    width: 0.2
-                },
+                stroke = Stroke::from((rgb(0x96, 0x96, 0x96), 0.2)),
                 radius = 40.,
             ),
             Circle!(
-                stroke = Stroke::Full {
-                    color: rgb(0x2e, 0x2e, 0x2e),
-                    width: 0.2
-                },
+                stroke = Stroke::from((rgb(0x2e, 0x2e, 0x2e), 0.2)),
                 radius = 42.,
             ),
-            Circle!(
-                stroke = Stroke::Full {
-                    color: C,
-                    width: 0.2
-                },
-                radius = 44.,
-            ),
+            Circle!(stroke = Stroke::from((C, 0.2)), radius = 44.,),
         ])
     }
 }
@@ -150,55 +123,33 @@ impl From<Squares> for Shape {
     fn from(_: Squares) -> Self {
         let square_line = dessin2!(
             [
+                Rectangle!(stroke = Stroke::from((C, 0.1)), width = 2.5, height = 2.5,),
                 Rectangle!(
-                    stroke = Stroke::Full {
-                        color: C,
-                        width: 0.1
-                    },
-                    width = 2.5,
-                    height = 2.5,
-                ),
-                Rectangle!(
-                    stroke = Stroke::Full {
-                        color: c(200),
-                        width: 0.1
-                    },
+                    stroke = Stroke::from((c(200), 0.1)),
                     width = 1.8,
                     height = 1.8,
                     translate = Translation2::new(2.8, 0.),
                 ),
                 Rectangle!(
-                    stroke = Stroke::Full {
-                        color: c(150),
-                        width: 0.1
-                    },
+                    stroke = Stroke::from((c(150), 0.1)),
                     width = 1.2,
                     height = 1.2,
                     translate = Translation2::new(4.8, 0.),
                 ),
                 Rectangle!(
-                    stroke = Stroke::Full {
-                        color: c(100),
-                        width: 0.1
-                    },
+                    stroke = Stroke::from((c(100), 0.1)),
                     width = 0.8,
                     height = 0.8,
                     translate = Translation2::new(6.2, 0.),
                 ),
                 Rectangle!(
-                    stroke = Stroke::Full {
-                        color: c(50),
-                        width: 0.1
-                    },
+                    stroke = Stroke::from((c(50), 0.1)),
                     width = 0.4,
                     height = 0.4,
                     translate = Translation2::new(7.2, 0.),
                 ),
                 Rectangle!(
-                    stroke = Stroke::Full {
-                        color: c(25),
-                        width: 0.1
-                    },
+                    stroke = Stroke::from((c(25), 0.1)),
                     width = 0.2,
                     height = 0.2,
                     translate = Translation2::new(7.8, 0.),
@@ -285,10 +236,7 @@ impl From<Logo432> for Shape {
             Squares(),
             BinaryRing(radius = 30.),
             Circle!(
-                stroke = Stroke::Full {
-                    color: rgb(0x96, 0x96, 0x96),
-                    width: 0.2
-                },
+                stroke = Stroke::from((rgb(0x96, 0x96, 0x96), 0.2)),
                 radius = 70.,
             ),
             Symbol432() > (scale = [4., 4.], translate = [-20., -20.],),
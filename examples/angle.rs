@@ -0,0 +1,41 @@
+use std::fs;
+
+use dessin::prelude::*;
+use project_root::get_project_root;
+
+fn main() {
+    let clock: Shape = dessin2!([
+        // dial
+        Circle!(stroke = (Color::BLACK, 0.2), radius = 10.),
+        // a tick every twelfth of a turn
+        for hour in 0..12 {
+            dessin2!(Line!(
+                stroke = (Color::BLACK, 0.2),
+                from = [8.5, 0.],
+                to = [9.5, 0.],
+                rotate = Angle::turns(hour as f32 / 12.)
+            ))
+        },
+        // ten minutes elapsed, counterclockwise from the hand to the top
+        Arc!(
+            stroke = (rgb(0, 50, 75), 0.5),
+            radius = 7.,
+            start_angle = Angle::degrees(30.),
+            end_angle = Angle::QUARTER_TURN
+        ),
+        // hand pointing to ten past
+        Line!(
+            stroke = (Color::BLACK, 0.4),
+            from = [0., 0.],
+            to = [6., 0.],
+            rotate = Angle::degrees(30.)
+        ),
+    ]);
+
+    // prints in svg version
+    fs::write(
+        get_project_root().unwrap().join("examples/out/angle.svg"),
+        dessin_svg::to_string(&clock).unwrap(),
+    )
+    .unwrap();
+}
@@ -8,10 +8,7 @@ fn main() {
         start_angle = 0.,
         end_angle = PI / 4.,
         // creates a black pointing margin with a width of 0.1
-        stroke = Stroke::Full {
-            color: rgb(0, 50, 75),
-            width: 0.1
-        },
+        stroke = Stroke::from((rgb(0, 50, 75), 0.1)),
         // chooses a rotation of -10 radians in the trigonometric direction
         rotate = Rotation2::new(-10_f32.to_radians())
     ),]);
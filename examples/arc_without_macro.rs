@@ -14,10 +14,10 @@ fn main() {
     arc.end_angle(PI / 4.);
 
     // creates a black margin of 0.1
-    arc.stroke(Stroke::Full {
-        color: rgb(0, 50, 75),
-        width: 0.1, //do not worry if it'big. 0.1 is like a proportion, but here, it's the biggest
-    });
+    arc.stroke(Stroke::from((
+        rgb(0, 50, 75),
+        0.1, //do not worry if it'big. 0.1 is like a proportion, but here, it's the biggest
+    )));
 
     // chooses a rotation of -10 radians in the trigonometric direction
     arc.rotate(Rotation2::new(-10_f32.to_radians()));
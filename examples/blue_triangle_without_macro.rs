@@ -18,10 +18,7 @@ fn main() {
     triangle.fill(Fill::Color(rgb(0, 0, 255)));
 
     // creates a black margin of 0.2 (0.05 outside and 0.05 inside the triangle)
-    triangle.stroke(Stroke::Full {
-        color: rgb(0, 0, 0),
-        width: 0.1,
-    });
+    triangle.stroke(Stroke::from((rgb(0, 0, 0), 0.1)));
 
     //chooses a rotation of 0 radians in the trigonometric direction
     triangle.rotate(Rotation2::new(0_f32.to_radians()));
@@ -17,10 +17,7 @@ fn main() {
             // paints the inside of the diamond in diamond color
             fill = rgb(185, 242, 255),
             // creates a black margin with a width of 0.1 (0.05 outside and the same inside the diamond)
-            stroke = Stroke::Full {
-                color: rgb(0, 0, 0),
-                width: 0.1
-            },
+            stroke = Stroke::from((rgb(0, 0, 0), 0.1)),
             // chooses a rotation of -10 radians in the trigonometric direction
             rotate = Rotation2::new(-10_f32.to_radians()),
             // moves of 15 following the x axis and 5 following the y axis
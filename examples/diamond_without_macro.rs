@@ -27,10 +27,7 @@ fn main() {
     diamond.fill(Fill::Color(rgb(185, 242, 255)));
 
     // creates a black margin of 0.1 (0.05 outside and 0.05 inside the diamond)
-    diamond.stroke(Stroke::Full {
-        color: rgb(0, 0, 0),
-        width: 0.1,
-    });
+    diamond.stroke(Stroke::from((rgb(0, 0, 0), 0.1)));
 
     // chooses a rotation of -10 radians in the trigonometric direction
     diamond.rotate(Rotation2::new(-10_f32.to_radians()));
@@ -12,10 +12,7 @@ fn main() {
         // paints the inside of the rectangle in green
         fill = rgb(0, 255, 0),
         // creates a grey margin of 0.2 (0.05 outside and the same inside the rectangle)
-        stroke = Stroke::Full {
-            color: rgb(150, 150, 150),
-            width: 0.1
-        },
+        stroke = Stroke::from((rgb(150, 150, 150), 0.1)),
         //chooses a rotation of 6 radians in the trigonometric direction
         rotate = Rotation2::new(6_f32.to_radians())
     ),]);
@@ -13,10 +13,7 @@ fn main() {
     rectangle.fill(Fill::Color(rgb(0, 255, 0)));
 
     // creates a grey margin of 0.2 (0.05 outside and 0.05 inside the rectangle)
-    rectangle.stroke(Stroke::Full {
-        color: rgb(0x96, 0x96, 0x96),
-        width: 0.1,
-    });
+    rectangle.stroke(Stroke::from((rgb(0x96, 0x96, 0x96), 0.1)));
 
     //chooses a rotation of 6 radians in the trigonometric direction
     rectangle.rotate(Rotation2::new(6_f32.to_radians()));
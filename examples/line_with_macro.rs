@@ -14,10 +14,7 @@ fn main() {
             to = Point2::new(12., 5.2),
             // not needed here
             fill = rgb(255, 100, 100),
-            stroke = Stroke::Full {
-                color: rgb(255, 100, 100),
-                width: 0.05
-            },
+            stroke = Stroke::from((rgb(255, 100, 100), 0.05)),
             translate = [5., 1.]
         )
     ]);
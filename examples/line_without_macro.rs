@@ -14,10 +14,7 @@ fn main() {
 
     line.fill(rgb(255, 100, 100));
 
-    line.stroke(Stroke::Full {
-        color: rgb(255, 100, 100),
-        width: 0.05,
-    });
+    line.stroke(Stroke::from((rgb(255, 100, 100), 0.05)));
 
     line.translate([5., 1.]);
 
@@ -27,10 +27,7 @@ fn main() {
         // Rectangle!(
         //     width = 15.,
         //     height = 15.,
-        //     stroke = Stroke::Full {
-        //         color: rgb(0, 0, 0),
-        //         width: 1.
-        //     }
+        //     stroke = Stroke::from((rgb(0, 0, 0), 1.))
         // ),
     ]);
 
@@ -61,6 +58,7 @@ fn main() {
                     width: 14.,
                     height: 14.,
                 },
+                ..Default::default()
             },
         )
         .unwrap(),
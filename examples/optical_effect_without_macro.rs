@@ -61,14 +61,8 @@ fn main() {
     rectangle2.height(15.);
     rectangle2.height(15.);
 
-    rectangle2.stroke(Stroke::Full {
-        color: rgb(0, 0, 0),
-        width: 1.,
-    });
-    rectangle2.stroke(Stroke::Full {
-        color: rgb(0, 0, 0),
-        width: 1.,
-    });
+    rectangle2.stroke(Stroke::from((rgb(0, 0, 0), 1.)));
+    rectangle2.stroke(Stroke::from((rgb(0, 0, 0), 1.)));
 
     // creates a white circle in the middle
     let circle = Circle::default().with_radius(1.);
@@ -97,6 +91,7 @@ fn main() {
                     width: 14.,
                     height: 14.,
                 },
+                ..Default::default()
             },
         )
         .unwrap(),
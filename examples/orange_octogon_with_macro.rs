@@ -10,7 +10,7 @@ fn main() {
             // paints the inside of the octogon in bright orange
             fill = rgb(255, 191, 0),
             // We decide to not use stroke but it is possible
-            // stroke={Stroke::Dashed { color: rgb(0, 0, 0), width: 0.1, on: 0.2, off: 0.1}}
+            // stroke={Stroke::dashed(rgb(0, 0, 0), 0.1, 0.2, 0.1)}
 
             // chooses a rotation of -2 radians in the trigonometric direction
             rotate = Rotation2::new(-2_f32.to_radians())
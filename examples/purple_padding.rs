@@ -23,10 +23,7 @@ fn main() {
     let rectangle_2 = dessin2!(Rectangle!(
         width = 5.5,
         height = 3.8,
-        stroke = Stroke::Full {
-            color: rgb(0, 150, 0),
-            width: 0.1
-        },
+        stroke = Stroke::from((rgb(0, 150, 0), 0.1)),
         translate = [0.75, -0.1]
     ));
 
@@ -10,10 +10,7 @@ fn main() {
         // paints the inside of the circle in red
         fill = rgb(255, 0, 0),
         // creates a grey margin of 0.2 (0.1 outside and 0.1 inside the circle)
-        stroke = Stroke::Full {
-            color: rgb(0x96, 0x96, 0x96),
-            width: 0.2
-        },
+        stroke = Stroke::from((rgb(0x96, 0x96, 0x96), 0.2)),
         rotate = Rotation2::new(0_f32.to_radians()) //not visible yet but it's possible to see it in some conditions
     ),]);
 
@@ -16,12 +16,7 @@ fn main() {
             fill = rgb(0, 0, 100),
             // creates a black pointing margin with a width of 0.1 (0.05 outside and the same inside the triangle), a length of 0.2 and
             // a space of 0.1 between each of them
-            stroke = Stroke::Dashed {
-                color: rgb(0, 0, 0),
-                width: 0.1,
-                on: 0.2,
-                off: 0.1
-            },
+            stroke = Stroke::dashed(rgb(0, 0, 0), 0.1, 0.2, 0.1),
             // chooses a rotation of 0 radians in the trigonometric direction
             rotate = Rotation2::new(0_f32.to_radians())
         ),
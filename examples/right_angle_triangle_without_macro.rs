@@ -21,12 +21,7 @@ fn main() {
     triangle.fill(Fill::Color(rgb(0, 0, 100)));
 
     // creates a black margin of 0.1 (0.05 outside and 0.05 inside the triangle)
-    triangle.stroke(Stroke::Dashed {
-        color: rgb(0, 0, 0),
-        width: 0.1,
-        on: 0.2,
-        off: 0.1,
-    });
+    triangle.stroke(Stroke::dashed(rgb(0, 0, 0), 0.1, 0.2, 0.1));
 
     // chooses a rotation of 0 radians in the trigonometric direction
     triangle.rotate(Rotation2::new(0_f32.to_radians()));
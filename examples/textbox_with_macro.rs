@@ -19,10 +19,7 @@ fn main() {
             // paints the inside of the text in bright orange
             fill = rgb(255, 191, 0),
             // We decide to not use stroke but it is possible
-            stroke = Stroke::Full {
-                color: rgb(150, 10, 10),
-                width: 0.1
-            },
+            stroke = Stroke::from((rgb(150, 10, 10), 0.1)),
             // chooses a rotation of 6 radians in the trigonometric direction
             rotate = Rotation2::new(6_f32.to_radians())
         ),
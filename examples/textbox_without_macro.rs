@@ -29,10 +29,7 @@ fn main() {
     // paints the inside of the text in bright orange
     text.fill(Fill::Color(rgb(255, 191, 0)));
 
-    text.stroke(Stroke::Full {
-        color: rgb(150, 10, 10),
-        width: 0.1,
-    });
+    text.stroke(Stroke::from((rgb(150, 10, 10), 0.1)));
 
     // chooses a rotation of -6 radians in the trigonometric direction
     text.rotate(Rotation2::new(6_f32.to_radians()));
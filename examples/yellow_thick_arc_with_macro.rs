@@ -14,10 +14,7 @@ fn main() {
         // paints the inside of the thick arc in yellow
         fill = rgb(255, 255, 0),
         // creates a black margin of 0.2 (0.05 outside and the same inside the thick arc)
-        stroke = Stroke::Full {
-            color: rgb(0, 0, 0),
-            width: 0.1
-        },
+        stroke = Stroke::from((rgb(0, 0, 0), 0.1)),
         // chooses a rotation of Pi/3 in radians in the trigonometric direction
         rotate = Rotation2::new(PI / 3_f32.to_radians())
     ),]);
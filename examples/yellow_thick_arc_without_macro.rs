@@ -22,10 +22,7 @@ fn main() {
     thick_arc.fill(Fill::Color(rgb(255, 255, 0)));
 
     // creates a black margin of 0.1 (0.05 outside and 0.05 inside the thick_arc)
-    thick_arc.stroke(Stroke::Full {
-        color: rgb(0, 0, 0),
-        width: 0.1,
-    });
+    thick_arc.stroke(Stroke::from((rgb(0, 0, 0), 0.1)));
 
     // chooses a rotation of PI/3 radians in the trigonometric direction
     thick_arc.rotate(Rotation2::new(PI / 3_f32.to_radians()));